@@ -0,0 +1,67 @@
+//! Compares planning over string-keyed states with planning over a compiled domain.
+//!
+//! ```text
+//! cargo run --release --example compiled_bench
+//! ```
+
+use goap::prelude::*;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Runs `f` `iterations` times and returns the average time per run.
+fn time<F: FnMut()>(iterations: u32, mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    start.elapsed() / iterations
+}
+
+fn main() {
+    // A crafting domain with enough branching to explore a few thousand states
+    let mut actions = Vec::new();
+    for i in 0..8 {
+        actions.push(
+            Action::new(&format!("gather_{i}"))
+                .adds(&format!("resource_{i}"), 1)
+                .build(),
+        );
+    }
+    for i in 0..4 {
+        actions.push(
+            Action::new(&format!("craft_{i}"))
+                .requires(&format!("resource_{}", 2 * i), 2)
+                .requires(&format!("resource_{}", 2 * i + 1), 1)
+                .subtracts(&format!("resource_{}", 2 * i), 2)
+                .subtracts(&format!("resource_{}", 2 * i + 1), 1)
+                .sets(&format!("item_{i}"), true)
+                .build(),
+        );
+    }
+    let goal = Goal::new("crafted")
+        .requires("item_0", true)
+        .requires("item_1", true)
+        .requires("item_2", true)
+        .build();
+    let mut initial_state = State::empty();
+    for i in 0..8 {
+        initial_state.set(&format!("resource_{i}"), 0);
+    }
+
+    let planner = Planner::new();
+    let domain = planner.compile(&actions).unwrap();
+
+    let regular = time(5, || {
+        black_box(planner.plan(&initial_state, &goal, &actions).unwrap());
+    });
+    println!("plan:          {regular:?}");
+
+    let compiled = time(5, || {
+        black_box(
+            planner
+                .plan_compiled(&domain, &initial_state, &goal)
+                .unwrap(),
+        );
+    });
+    println!("plan_compiled: {compiled:?}");
+}
@@ -2,15 +2,16 @@ use goap::prelude::*;
 
 fn main() {
     // Create initial state
-    let initial_state = State::new()
-        .set("has_wood", false)
-        .set("has_planks", false)
-        .set("has_axe", false)
-        .set("has_money", true)
-        .set("at_store", false)
-        .set("at_tree", false)
-        .set("at_sawmill", false)
-        .build();
+    let initial_state = state! {
+        has_wood: false,
+        has_planks: false,
+        has_axe: false,
+        has_money: true,
+        at_home: true,
+        at_store: false,
+        at_tree: false,
+        at_sawmill: false,
+    };
 
     // Create goal state
     let goal = Goal::new("craft_planks")
@@ -31,9 +32,9 @@ fn main() {
         .sets("has_money", false)
         .build();
 
+    // The locations form a mutex group, so moving only sets the destination
     let goto_tree = Action::new("goto_tree")
         .cost(1.0)
-        .sets("at_store", false)
         .sets("at_tree", true)
         .build();
 
@@ -47,7 +48,6 @@ fn main() {
     let goto_sawmill = Action::new("goto_sawmill")
         .cost(1.0)
         .requires("has_wood", true)
-        .sets("at_tree", false)
         .sets("at_sawmill", true)
         .build();
 
@@ -68,11 +68,16 @@ fn main() {
         saw_planks,
     ];
 
-    // Create planner
-    let planner = Planner::new();
+    // Create a planner that knows the agent is in exactly one place
+    let planner = Planner::with_config(PlannerConfig::new().mutex_group(&[
+        "at_home",
+        "at_store",
+        "at_tree",
+        "at_sawmill",
+    ]));
 
     // Find plan
-    let plan_result = planner.plan(initial_state.clone(), &goal, &actions);
+    let plan_result = planner.plan(&initial_state, &goal, &actions);
     assert!(plan_result.is_ok(), "Expected to find a valid plan");
 
     let plan = plan_result.unwrap();
@@ -28,7 +28,7 @@ fn main() {
     let planner = Planner::new();
 
     // Find plan
-    let plan_result = planner.plan(initial_state.clone(), &goal, &actions);
+    let plan_result = planner.plan(&initial_state, &goal, &actions);
     assert!(plan_result.is_ok(), "Expected to find a valid plan");
 
     let plan = plan_result.unwrap();
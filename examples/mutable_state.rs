@@ -81,12 +81,12 @@ fn main() {
     }
 
     // Simulate spending gold
-    if let Some(current_gold) = character_state.get::<i32>("gold") {
-        if current_gold >= 50 {
-            character_state.set("gold", current_gold - 50);
-            character_state.set("has_magic_sword", true);
-            println!("Bought magic sword! New state: {character_state}");
-        }
+    if let Some(current_gold) = character_state.get::<i32>("gold")
+        && current_gold >= 50
+    {
+        character_state.set("gold", current_gold - 50);
+        character_state.set("has_magic_sword", true);
+        println!("Bought magic sword! New state: {character_state}");
     }
 
     let current_stamina = character_state.get::<f64>("stamina").unwrap_or(100.0);
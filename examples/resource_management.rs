@@ -7,6 +7,7 @@ fn main() {
         .set("planks", 0)
         .set("has_saw", false)
         .set("coins", 10)
+        .set("at_home", true)
         .set("at_store", false)
         .set("at_forest", false)
         .build();
@@ -14,11 +15,10 @@ fn main() {
     // Goal: Have at least 5 planks
     let goal = Goal::new("craft_planks").requires("planks", 5).build();
 
-    // Action: Go to store
+    // Action: Go to store (leaving the other locations is implied by the mutex group)
     let goto_store = Action::new("goto_store")
         .cost(1.0)
         .sets("at_store", true)
-        .sets("at_forest", false)
         .build();
 
     // Action: Buy saw
@@ -34,7 +34,6 @@ fn main() {
     let goto_forest = Action::new("goto_forest")
         .cost(1.0)
         .sets("at_forest", true)
-        .sets("at_store", false)
         .build();
 
     // Action: Gather wood
@@ -56,11 +55,15 @@ fn main() {
     // Collect all actions
     let actions = vec![goto_store, buy_saw, goto_forest, gather_wood, craft_planks];
 
-    // Create planner
-    let planner = Planner::new();
+    // Create a planner that knows the agent is in exactly one place
+    let planner = Planner::with_config(PlannerConfig::new().mutex_group(&[
+        "at_home",
+        "at_store",
+        "at_forest",
+    ]));
 
     // Find plan
-    let plan_result = planner.plan(initial_state.clone(), &goal, &actions);
+    let plan_result = planner.plan(&initial_state, &goal, &actions);
 
     // Assert that a plan was found
     assert!(plan_result.is_ok(), "Expected to find a valid plan");
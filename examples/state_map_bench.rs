@@ -0,0 +1,78 @@
+//! Compares the default `HashMap`-backed `State` with the `BTreeMap`-backed one.
+//!
+//! Run it once per map type and compare the timings:
+//!
+//! ```text
+//! cargo run --release --example state_map_bench
+//! cargo run --release --example state_map_bench --features ordered-state
+//! ```
+
+use goap::prelude::*;
+use std::collections::HashSet;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Runs `f` `iterations` times and returns the average time per run.
+fn time<F: FnMut()>(iterations: u32, mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    start.elapsed() / iterations
+}
+
+fn main() {
+    let map = if cfg!(feature = "small-state") {
+        "inline sorted array"
+    } else if cfg!(feature = "ordered-state") {
+        "BTreeMap"
+    } else {
+        "HashMap"
+    };
+    println!("State backed by {map}");
+
+    // A state with as many variables as a typical agent
+    let mut state = State::empty();
+    for i in 0..32 {
+        state.set(&format!("var_{i:02}"), i);
+    }
+
+    let lookups = time(10_000, || {
+        for i in 0..32 {
+            black_box(state.get::<i64>(&format!("var_{i:02}")));
+        }
+    });
+    println!("  32 lookups:        {lookups:?}");
+
+    let hashing = time(10_000, || {
+        let mut set = HashSet::new();
+        set.insert(black_box(state.clone()));
+    });
+    println!("  clone and hash:    {hashing:?}");
+
+    let display = time(1_000, || {
+        black_box(state.to_string());
+    });
+    println!("  display:           {display:?}");
+
+    // A planning problem exploring a few thousand states
+    let mut actions = Vec::new();
+    for i in 0..8 {
+        actions.push(
+            Action::new(&format!("raise_{i}"))
+                .adds(&format!("var_{i:02}"), 1)
+                .build(),
+        );
+    }
+    let goal = Goal::new("raised")
+        .requires("var_00", 3)
+        .requires("var_01", 4)
+        .requires("var_02", 5)
+        .requires("var_03", 6)
+        .build();
+    let planner = Planner::new();
+    let planning = time(20, || {
+        black_box(planner.plan(&state, &goal, &actions).unwrap());
+    });
+    println!("  plan:              {planning:?}");
+}
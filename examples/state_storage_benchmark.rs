@@ -0,0 +1,74 @@
+//! Times cloning, hashing and condition checks on small states.
+//!
+//! Run it once per storage and compare the timings:
+//!
+//! ```text
+//! cargo run --release --example state_storage_benchmark
+//! cargo run --release --example state_storage_benchmark --features small-state
+//! ```
+
+use goap::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 200_000;
+
+/// Runs `f` `ITERATIONS` times and returns the average time per run.
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn hash_of<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn main() {
+    let storage = if cfg!(feature = "small-state") {
+        "inline sorted array"
+    } else if cfg!(feature = "ordered-state") {
+        "BTreeMap"
+    } else {
+        "HashMap"
+    };
+    println!("State backed by {storage}");
+    println!("Run with --release for meaningful numbers.\n");
+
+    for var_count in [4, 8, 16] {
+        let mut state = State::empty();
+        for i in 0..var_count {
+            match i % 3 {
+                0 => state.set(&format!("flag_{i}"), i % 2 == 0),
+                1 => state.set(&format!("count_{i}"), i as i64 * 10),
+                _ => state.set(&format!("place_{i}"), "town"),
+            }
+        }
+        let conditions: State = state
+            .iter()
+            .take(var_count / 2)
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+
+        println!("--- {var_count} variables ---");
+        let clone = time(|| {
+            black_box(black_box(&state).clone());
+        });
+        println!("  clone:      {clone:?}");
+        let hash = time(|| {
+            black_box(hash_of(black_box(&state)));
+        });
+        println!("  hash:       {hash:?}");
+        let satisfies = time(|| {
+            black_box(black_box(&state).satisfies(&conditions));
+        });
+        println!("  satisfies:  {satisfies:?}");
+        println!();
+    }
+}
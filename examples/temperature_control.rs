@@ -61,7 +61,7 @@ fn main() {
     println!("Initial state: {initial_state:?}");
     println!("Goal state: {goal:?}");
 
-    match planner.plan(initial_state.clone(), &goal, &actions) {
+    match planner.plan(&initial_state, &goal, &actions) {
         Ok(plan) => {
             let action_count = plan.actions.len();
             let cost = plan.cost;
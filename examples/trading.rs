@@ -192,7 +192,7 @@ fn main() {
     // Create planner
     let planner = Planner::new();
 
-    match planner.plan(initial_state.clone(), &goal, &actions) {
+    match planner.plan(&initial_state, &goal, &actions) {
         Ok(plan) => {
             let action_count = plan.actions.len();
             let cost = plan.cost;
@@ -218,20 +218,20 @@ fn main() {
                     println!("Trade Routes: {routes}");
                 }
 
-                if let Some(guild) = current_state.get::<bool>("has_guild_membership") {
-                    if guild {
-                        println!("Guild Member: Yes");
-                    }
+                if let Some(guild) = current_state.get::<bool>("has_guild_membership")
+                    && guild
+                {
+                    println!("Guild Member: Yes");
                 }
-                if let Some(caravan) = current_state.get::<bool>("has_caravan") {
-                    if caravan {
-                        println!("Has Caravan: Yes");
-                    }
+                if let Some(caravan) = current_state.get::<bool>("has_caravan")
+                    && caravan
+                {
+                    println!("Has Caravan: Yes");
                 }
-                if let Some(warehouse) = current_state.get::<bool>("has_warehouse") {
-                    if warehouse {
-                        println!("Has Warehouse: Yes");
-                    }
+                if let Some(warehouse) = current_state.get::<bool>("has_warehouse")
+                    && warehouse
+                {
+                    println!("Has Warehouse: Yes");
                 }
             }
         }
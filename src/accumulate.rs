@@ -0,0 +1,277 @@
+//! Planning to produce a quantity of something.
+//!
+//! Goals like "have 70 more gold" make a plain search expand one state per
+//! repetition of the producing action, and return plans listing the same action
+//! dozens of times. [`Planner::plan_to_accumulate`] handles repetition directly:
+//! for every action adding to the variable, it plans the setup that enables the
+//! action, checks that repeating it changes nothing but the variable, and
+//! computes the number of repetitions in closed form. The result is an
+//! [`AccumulationPlan`] of [`Repetition`]s such as `work x7`.
+//!
+//! Only plans repeating a single producing action are computed this way; when no
+//! producer can be repeated on its own, e.g. because each repetition consumes an
+//! input, the planner searches for the whole plan and groups its consecutive
+//! repetitions.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let actions = vec![
+//!     Action::new("get_job").sets("has_job", true).cost(3.0).build(),
+//!     Action::new("work").requires("has_job", true).adds("gold", 10).build(),
+//!     Action::new("beg").adds("gold", 1).build(),
+//! ];
+//! let state = State::new().set("gold", 5).set("has_job", false).build();
+//!
+//! let plan = Planner::new()
+//!     .plan_to_accumulate(state, "gold", 70, &actions)
+//!     .unwrap();
+//! assert_eq!(plan.to_string(), "get_job, work x7");
+//! assert_eq!(plan.cost, 10.0);
+//! assert_eq!(plan.to_plan().actions.len(), 8);
+//! ```
+
+use crate::actions::Action;
+use crate::clusters::read_keys;
+use crate::goals::Goal;
+use crate::planner::{Plan, Planner, PlannerError};
+use crate::state::{IntoStateVar, State, StateOperation, StateVar};
+use std::borrow::Cow;
+use std::fmt;
+
+/// An action repeated a number of times in a row.
+#[derive(Clone, Debug)]
+pub struct Repetition {
+    /// The repeated action
+    pub action: Action,
+    /// How many times the action is executed
+    pub count: usize,
+}
+
+impl fmt::Display for Repetition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.action.name)?;
+        if self.count != 1 {
+            write!(f, " x{}", self.count)?;
+        }
+        Ok(())
+    }
+}
+
+/// A plan made of repeated actions.
+#[derive(Clone, Debug, Default)]
+pub struct AccumulationPlan {
+    /// The steps of the plan, in order
+    pub steps: Vec<Repetition>,
+    /// The total cost of executing every repetition
+    pub cost: f64,
+}
+
+impl AccumulationPlan {
+    /// Returns the number of actions executed, counting every repetition.
+    pub fn len(&self) -> usize {
+        self.steps.iter().map(|step| step.count).sum()
+    }
+
+    /// Returns true if the plan executes no action.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Expands the repetitions into a flat plan.
+    pub fn to_plan(&self) -> Plan {
+        Plan {
+            actions: self
+                .steps
+                .iter()
+                .flat_map(|step| std::iter::repeat_n(step.action.clone(), step.count))
+                .collect(),
+            cost: self.cost,
+            ..Plan::default()
+        }
+    }
+}
+
+impl fmt::Display for AccumulationPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, step) in self.steps.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{step}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the raw value of a number: the integer, or the fixed-point units of a float.
+fn raw_number(value: &StateVar) -> Option<i64> {
+    match value {
+        StateVar::I64(value) | StateVar::F64(value) => Some(*value),
+        _ => None,
+    }
+}
+
+/// Groups consecutive executions of the same action.
+fn group_repetitions(actions: Vec<Action>) -> Vec<Repetition> {
+    let mut steps: Vec<Repetition> = Vec::new();
+    for action in actions {
+        match steps.last_mut() {
+            Some(step) if step.action.name == action.name => step.count += 1,
+            _ => steps.push(Repetition { action, count: 1 }),
+        }
+    }
+    steps
+}
+
+impl Planner {
+    /// Plans to raise the numeric variable `key` by `amount` over its value in
+    /// `state`, returning the plan as repetitions (see the
+    /// [`accumulate`](crate::accumulate) module). A missing variable counts as zero.
+    ///
+    /// Returns [`PlannerError::IncompatibleStateTypes`] if `amount` is not a number
+    /// or the variable holds another type, and [`PlannerError::NoPlanFound`] if the
+    /// amount cannot be produced.
+    pub fn plan_to_accumulate<'s, T: IntoStateVar>(
+        &self,
+        state: impl Into<Cow<'s, State>>,
+        key: &str,
+        amount: T,
+        actions: &[Action],
+    ) -> Result<AccumulationPlan, PlannerError> {
+        let state = state.into();
+        let amount = amount.into_state_var();
+        let Some(delta) = raw_number(&amount) else {
+            return Err(PlannerError::IncompatibleStateTypes(format!(
+                "cannot accumulate {} amounts of '{key}'",
+                amount.type_name()
+            )));
+        };
+        let current = match state.vars.get(key) {
+            Some(value) if std::mem::discriminant(value) == std::mem::discriminant(&amount) => {
+                raw_number(value).unwrap_or(0)
+            }
+            Some(value) => {
+                return Err(PlannerError::IncompatibleStateTypes(format!(
+                    "'{key}' holds {} but the amount is {}",
+                    value.type_name(),
+                    amount.type_name()
+                )));
+            }
+            None => 0,
+        };
+        if delta <= 0 {
+            return Ok(AccumulationPlan::default());
+        }
+        let target = current.saturating_add(delta);
+        let target_value = match amount {
+            StateVar::F64(_) => StateVar::F64(target),
+            _ => StateVar::I64(target),
+        };
+
+        let actions = self.prepare_actions(actions);
+        let mut best: Option<AccumulationPlan> = None;
+        for producer in actions.iter() {
+            let Some(StateOperation::Add(per_step)) = producer.effects.get(key) else {
+                continue;
+            };
+            if *per_step <= 0 {
+                continue;
+            }
+            // Only minimum thresholds on the variable keep holding as it grows
+            let reads_key = {
+                let mut reads = read_keys(producer);
+                let thresholds = producer.preconditions.vars.contains_key(key);
+                reads.retain(|read| *read == key);
+                reads.len() > usize::from(thresholds)
+            };
+            if reads_key || !producer.dynamic_effects.is_empty() {
+                continue;
+            }
+            if let Some(candidate) =
+                self.repeat_producer(&state, key, target, *per_step, producer, &actions)?
+                && best.as_ref().is_none_or(|best| candidate.cost < best.cost)
+            {
+                best = Some(candidate);
+            }
+        }
+        if let Some(best) = best {
+            return Ok(best);
+        }
+
+        let goal = Goal::new(&format!("accumulate_{key}"))
+            .requires(key, target_value)
+            .build();
+        let plan = self.plan(state, &goal, &actions)?;
+        Ok(AccumulationPlan {
+            steps: group_repetitions(plan.actions),
+            cost: plan.cost,
+        })
+    }
+
+    /// Plans the setup enabling `producer` and the number of times it must then be
+    /// repeated to raise `key` to `target`, or `None` if the producer cannot be
+    /// enabled or repeating it changes other variables. `actions` must already be
+    /// prepared, so that setup plans and repetitions follow the configuration.
+    fn repeat_producer(
+        &self,
+        state: &State,
+        key: &str,
+        target: i64,
+        per_step: i64,
+        producer: &Action,
+        actions: &[Action],
+    ) -> Result<Option<AccumulationPlan>, PlannerError> {
+        let mut setup = Vec::new();
+        let mut ready = state.clone();
+        if !producer.can_execute(state) {
+            let mut goal = Goal::new(&format!("enable_{}", producer.name)).build();
+            goal.desired_state = producer.preconditions.clone();
+            match self.plan(state, &goal, actions) {
+                Ok(plan) => setup = plan.actions,
+                Err(PlannerError::NoPlanFound) => return Ok(None),
+                Err(error) => return Err(error),
+            }
+            for action in &setup {
+                ready = action.apply_effect(&ready);
+            }
+        }
+
+        let reached = ready.vars.get(key).and_then(raw_number).unwrap_or(0);
+        let remaining = target.saturating_sub(reached).max(0);
+        let count = (remaining as u64).div_ceil(per_step as u64) as usize;
+        if count > 0 {
+            let producer = std::slice::from_ref(producer);
+            let Some(first) = self.expand(&ready, producer).next() else {
+                return Ok(None);
+            };
+            if count > 1 {
+                let Some(second) = self.expand(&first.state, producer).next() else {
+                    return Ok(None);
+                };
+                // The second repetition must only raise the variable
+                let others_unchanged = second.state.vars.len() == first.state.vars.len()
+                    && second.state.vars.iter().all(|(name, value)| {
+                        name == key || first.state.vars.get(name) == Some(value)
+                    });
+                if !others_unchanged {
+                    return Ok(None);
+                }
+            }
+        }
+
+        let cost =
+            setup.iter().map(|action| action.cost).sum::<f64>() + count as f64 * producer.cost;
+        let mut steps = group_repetitions(setup);
+        if count > 0 {
+            match steps.last_mut() {
+                Some(step) if step.action.name == producer.name => step.count += count,
+                _ => steps.push(Repetition {
+                    action: producer.clone(),
+                    count,
+                }),
+            }
+        }
+        Ok(Some(AccumulationPlan { steps, cost }))
+    }
+}
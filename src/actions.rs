@@ -1,6 +1,67 @@
-use crate::state::{IntoStateVar, State, StateOperation};
+use crate::definition::{DefinitionError, check_range, is_saturated};
+use crate::executor::failed_keys;
+use crate::expr::{Assignment, CompareOp, Comparison, Expr, ExprError};
+use crate::state::{
+    CompactDisplay, Condition, ConversionError, EnumKey, EnumStateVar, IntoStateVar, State,
+    StateOperation, StateVar, TryFromStateVar, sorted_entries, value_satisfies,
+};
+use crate::time::TIME_KEY;
+use crate::units::{Quantity, QuantityKey};
 use std::collections::HashMap;
+use std::error::Error;
 use std::fmt;
+use std::sync::Arc;
+
+/// Signature of a function that computes an effect from the state an action is applied to.
+pub type EffectFn = dyn Fn(&State) -> StateOperation + Send + Sync;
+
+/// An effect whose operation is computed from the current state when the action is applied.
+/// This allows a single action to model effects like "harvest adds `farm_level * 10` grain".
+#[derive(Clone)]
+pub struct DynamicEffect {
+    /// The function computing the operation
+    func: Arc<EffectFn>,
+    /// Optional human-readable description used when displaying the action
+    description: Option<String>,
+}
+
+impl DynamicEffect {
+    /// Creates a dynamic effect from a function of the current state.
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(&State) -> StateOperation + Send + Sync + 'static,
+    {
+        DynamicEffect {
+            func: Arc::new(f),
+            description: None,
+        }
+    }
+
+    /// Attaches a human-readable description to this effect.
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Returns the description of this effect, if one was provided.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Computes the operation this effect performs on the given state.
+    pub fn evaluate(&self, state: &State) -> StateOperation {
+        (self.func)(state)
+    }
+}
+
+impl fmt::Debug for DynamicEffect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.description {
+            Some(description) => write!(f, "DynamicEffect({description})"),
+            None => write!(f, "DynamicEffect(<fn>)"),
+        }
+    }
+}
 
 /// Represents an action that can be performed to change the world state.
 /// Actions have preconditions that must be satisfied before they can be executed,
@@ -13,24 +74,78 @@ pub struct Action {
     pub cost: f64,
     /// The state conditions that must be met before this action can be executed
     pub preconditions: State,
+    /// Arithmetic comparisons that must hold before this action can be executed
+    pub expr_preconditions: Vec<Comparison>,
+    /// Groups of conditions of which at least one per group must hold before this
+    /// action can be executed
+    pub any_preconditions: Vec<Vec<(String, StateVar)>>,
+    /// Variables that must be missing from the state before this action can be executed
+    pub absent_preconditions: Vec<String>,
+    /// Comparisons variables must meet before this action can be executed, such as
+    /// `guard_alert < 2`
+    pub conditions: Vec<(String, Condition)>,
     /// The state changes that occur when this action is executed
     pub effects: HashMap<String, StateOperation>,
+    /// State changes computed from the state the action is applied to
+    pub dynamic_effects: HashMap<String, DynamicEffect>,
+    /// Labels used to group actions, e.g. for plan post-processing
+    pub tags: Vec<String>,
+    /// The state changes predicted if the action fails, applied instead of `effects`.
+    /// Empty for actions that are not expected to fail.
+    pub failure_effects: HashMap<String, StateOperation>,
+    /// Arithmetic comparisons that must hold in the state after this action.
+    /// The planner never produces a state breaking them.
+    pub postconditions: Vec<Comparison>,
+    /// The name of the action that undoes this one, used by
+    /// [`Planner::plan_undo`](crate::planner::Planner::plan_undo)
+    pub inverse: Option<String>,
+    /// Labels of resources the action holds while it runs; a
+    /// [`ParallelExecutor`](crate::parallel::ParallelExecutor) never runs two
+    /// steps sharing one at the same time
+    pub exclusive: Vec<String>,
 }
 
+/// Lists the preconditions and effects on separate lines, or on a single line
+/// such as `chop (cost: 1.0) {has_axe: true} -> {has_wood = true}` with the
+/// alternate flag (`{:#}`).
 impl fmt::Display for Action {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return self.fmt_compact(f);
+        }
         write!(f, "Action '{}' (cost: {:.1})", self.name, self.cost)?;
 
-        if !self.preconditions.vars.is_empty() {
+        if !self.preconditions.vars.is_empty()
+            || !self.expr_preconditions.is_empty()
+            || !self.any_preconditions.is_empty()
+            || !self.absent_preconditions.is_empty()
+            || !self.conditions.is_empty()
+        {
             write!(f, "\n  Preconditions:")?;
-            for (key, value) in &self.preconditions.vars {
+            for (key, value) in sorted_entries(&self.preconditions.vars) {
                 write!(f, "\n    - {key}: {value}")?;
             }
+            for comparison in &self.expr_preconditions {
+                write!(f, "\n    - {comparison}")?;
+            }
+            for group in &self.any_preconditions {
+                let alternatives: Vec<String> = group
+                    .iter()
+                    .map(|(key, value)| format!("{key}: {value}"))
+                    .collect();
+                write!(f, "\n    - any of {}", alternatives.join(" | "))?;
+            }
+            for key in &self.absent_preconditions {
+                write!(f, "\n    - {key}: absent")?;
+            }
+            for (key, condition) in &self.conditions {
+                write!(f, "\n    - {key} {condition}")?;
+            }
         }
 
-        if !self.effects.is_empty() {
+        if !self.effects.is_empty() || !self.dynamic_effects.is_empty() {
             write!(f, "\n  Effects:")?;
-            for (key, operation) in &self.effects {
+            for (key, operation) in sorted_entries(&self.effects) {
                 match operation {
                     StateOperation::Set(value) => write!(f, "\n    - Set {key} to {value}")?,
                     StateOperation::Add(value) => write!(f, "\n    - Add {value} to {key}")?,
@@ -39,12 +154,141 @@ impl fmt::Display for Action {
                     }
                 }
             }
+            for (key, effect) in sorted_entries(&self.dynamic_effects) {
+                match effect.description() {
+                    Some(description) => write!(f, "\n    - {description}")?,
+                    None => write!(f, "\n    - Compute {key} from state")?,
+                }
+            }
         }
 
+        if !self.failure_effects.is_empty() {
+            write!(f, "\n  On failure:")?;
+            for (key, operation) in sorted_entries(&self.failure_effects) {
+                match operation {
+                    StateOperation::Set(value) => write!(f, "\n    - Set {key} to {value}")?,
+                    StateOperation::Add(value) => write!(f, "\n    - Add {value} to {key}")?,
+                    StateOperation::Subtract(value) => {
+                        write!(f, "\n    - Subtract {value} from {key}")?;
+                    }
+                }
+            }
+        }
+
+        if !self.postconditions.is_empty() {
+            write!(f, "\n  Postconditions:")?;
+            for comparison in &self.postconditions {
+                write!(f, "\n    - {comparison}")?;
+            }
+        }
+
+        if let Some(inverse) = &self.inverse {
+            write!(f, "\n  Undone by: {inverse}")?;
+        }
+
+        if !self.exclusive.is_empty() {
+            write!(f, "\n  Exclusive: {}", self.exclusive.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A precondition that does not hold in a state.
+#[derive(Clone, Debug, PartialEq)]
+pub enum UnmetPrecondition {
+    /// A variable does not have the required value
+    Value {
+        /// The variable
+        key: String,
+        /// The required value; numbers are minimums
+        required: StateVar,
+        /// The value in the state, or None if the variable is missing
+        actual: Option<StateVar>,
+    },
+    /// An arithmetic comparison does not hold or cannot be evaluated
+    Comparison(Comparison),
+    /// None of a group of alternative conditions holds
+    AnyOf(Vec<(String, StateVar)>),
+    /// A variable required to be missing has a value
+    Present {
+        /// The variable
+        key: String,
+        /// The value in the state
+        actual: StateVar,
+    },
+    /// A variable does not meet a [`Condition`]
+    Condition {
+        /// The variable
+        key: String,
+        /// The condition
+        condition: Condition,
+        /// The value in the state, or None if the variable is missing
+        actual: Option<StateVar>,
+    },
+}
+
+impl fmt::Display for UnmetPrecondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnmetPrecondition::Value {
+                key,
+                required,
+                actual: Some(actual),
+            } => write!(f, "{key}: requires {required}, found {actual}"),
+            UnmetPrecondition::Value {
+                key,
+                required,
+                actual: None,
+            } => write!(f, "{key}: requires {required}, found nothing"),
+            UnmetPrecondition::Comparison(comparison) => write!(f, "{comparison} does not hold"),
+            UnmetPrecondition::AnyOf(alternatives) => {
+                let alternatives: Vec<String> = alternatives
+                    .iter()
+                    .map(|(key, value)| format!("{key}: {value}"))
+                    .collect();
+                write!(f, "none of {} holds", alternatives.join(" | "))
+            }
+            UnmetPrecondition::Present { key, actual } => {
+                write!(f, "{key}: requires absent, found {actual}")
+            }
+            UnmetPrecondition::Condition {
+                key,
+                condition,
+                actual: Some(actual),
+            } => write!(f, "{key}: requires {condition}, found {actual}"),
+            UnmetPrecondition::Condition {
+                key,
+                condition,
+                actual: None,
+            } => write!(f, "{key}: requires {condition}, found nothing"),
+        }
+    }
+}
+
+/// The preconditions that prevented an action from executing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreconditionFailure {
+    /// The name of the action
+    pub action: String,
+    /// Every unmet precondition: plain ones sorted by key, then comparisons, then
+    /// groups of alternatives, then variables required absent, then conditions,
+    /// each in the order they were added
+    pub unmet: Vec<UnmetPrecondition>,
+}
+
+impl fmt::Display for PreconditionFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Cannot execute '{}':", self.action)?;
+        for unmet in &self.unmet {
+            write!(f, "\n  - {unmet}")?;
+        }
         Ok(())
     }
 }
 
+impl Error for PreconditionFailure {}
+
 impl Action {
     /// Creates a new ActionBuilder for constructing an action with the given name.
     #[allow(clippy::new_ret_no_self)]
@@ -52,6 +296,65 @@ impl Action {
         ActionBuilder::new(name)
     }
 
+    /// Returns a view of the action formatting it on a single line, e.g.
+    /// `work (cost: 1.0) {has_job: true} -> {gold + 10}`; the same as formatting
+    /// with `{:#}`.
+    pub fn display_compact(&self) -> CompactDisplay<'_, Self> {
+        CompactDisplay::new(self)
+    }
+
+    /// Writes the single-line form of the action for its `Display` implementation.
+    fn fmt_compact(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut preconditions: Vec<String> = sorted_entries(&self.preconditions.vars)
+            .into_iter()
+            .map(|(key, value)| format!("{key}: {value}"))
+            .collect();
+        preconditions.extend(self.expr_preconditions.iter().map(ToString::to_string));
+        preconditions.extend(self.any_preconditions.iter().map(|group| {
+            let alternatives: Vec<String> = group
+                .iter()
+                .map(|(key, value)| format!("{key}: {value}"))
+                .collect();
+            format!("any of {}", alternatives.join(" | "))
+        }));
+        preconditions.extend(
+            self.absent_preconditions
+                .iter()
+                .map(|key| format!("{key}: absent")),
+        );
+        preconditions.extend(
+            self.conditions
+                .iter()
+                .map(|(key, condition)| format!("{key} {condition}")),
+        );
+
+        let mut effects: Vec<String> = sorted_entries(&self.effects)
+            .into_iter()
+            .map(|(key, operation)| match operation {
+                StateOperation::Set(value) => format!("{key} = {value}"),
+                StateOperation::Add(value) => format!("{key} + {value}"),
+                StateOperation::Subtract(value) => format!("{key} - {value}"),
+            })
+            .collect();
+        effects.extend(
+            sorted_entries(&self.dynamic_effects)
+                .into_iter()
+                .map(|(key, effect)| match effect.description() {
+                    Some(description) => description.to_string(),
+                    None => format!("{key} = <computed>"),
+                }),
+        );
+
+        write!(
+            f,
+            "{} (cost: {:.1}) {{{}}} -> {{{}}}",
+            self.name,
+            self.cost,
+            preconditions.join(", "),
+            effects.join(", ")
+        )
+    }
+
     /// Creates an action directly from its component parts.
     /// This is useful when you have the preconditions and effects already constructed.
     pub fn from_parts(
@@ -64,21 +367,321 @@ impl Action {
             name: name.to_string(),
             cost,
             preconditions,
+            expr_preconditions: Vec::new(),
+            any_preconditions: Vec::new(),
+            absent_preconditions: Vec::new(),
+            conditions: Vec::new(),
             effects,
+            dynamic_effects: HashMap::new(),
+            tags: Vec::new(),
+            failure_effects: HashMap::new(),
+            postconditions: Vec::new(),
+            inverse: None,
+            exclusive: Vec::new(),
         }
     }
 
+    /// Creates an action with the default cost of 1.0 and no preconditions or
+    /// effects, to be filled in with the mutation methods below.
+    ///
+    /// Actions can be edited in place, e.g. to adjust them to equipment and buffs
+    /// without rebuilding them:
+    ///
+    /// ```
+    /// use goap::prelude::*;
+    ///
+    /// let mut attack = Action::empty("attack");
+    /// attack.add_precondition("stamina", 10);
+    /// attack.add_effect_subtract("stamina", 10);
+    /// attack.add_effect_set("enemy_hit", true);
+    ///
+    /// // A stamina ring halves the stamina needed
+    /// let required = attack.get_precondition::<i64>("stamina").unwrap_or(0);
+    /// attack.add_precondition("stamina", required / 2);
+    /// attack.add_effect_subtract("stamina", required / 2);
+    /// attack.set_cost(attack.cost * 0.8);
+    ///
+    /// let state = State::new().set("stamina", 6).build();
+    /// assert!(attack.can_execute(&state));
+    /// assert_eq!(attack.apply_effect(&state).get::<i64>("stamina"), Some(1));
+    /// ```
+    pub fn empty(name: &str) -> Self {
+        ActionBuilder::new(name).build()
+    }
+
+    /// Sets the cost of this action.
+    pub fn set_cost(&mut self, cost: f64) {
+        self.cost = cost;
+    }
+
+    /// Sets the value a variable must have before this action can be executed,
+    /// replacing any plain or absence precondition on it. Numbers are minimums,
+    /// as with [`ActionBuilder::requires`].
+    pub fn add_precondition<T: IntoStateVar>(&mut self, key: &str, value: T) {
+        self.absent_preconditions.retain(|absent| absent != key);
+        self.preconditions.set(key, value);
+    }
+
+    /// Removes the plain precondition on a variable, returning its value if there
+    /// was one. Other kinds of preconditions on the variable are kept.
+    pub fn remove_precondition(&mut self, key: &str) -> Option<StateVar> {
+        self.preconditions.vars.remove(key)
+    }
+
+    /// Returns the value of the plain precondition on a variable, or None if there
+    /// is none or it holds another type.
+    pub fn get_precondition<T: TryFromStateVar>(&self, key: &str) -> Option<T> {
+        self.preconditions.get(key)
+    }
+
+    /// Adds an effect setting a variable, replacing any effect on it.
+    pub fn add_effect_set<T: IntoStateVar>(&mut self, key: &str, value: T) {
+        self.set_effect(key, StateOperation::Set(value.into_state_var()));
+    }
+
+    /// Adds an effect adding to a numeric variable, replacing any effect on it.
+    pub fn add_effect_add<T: NumericValue + IntoStateVar>(&mut self, key: &str, amount: T) {
+        self.set_effect(key, StateOperation::Add(raw_amount(amount)));
+    }
+
+    /// Adds an effect subtracting from a numeric variable, replacing any effect on it.
+    pub fn add_effect_subtract<T: NumericValue + IntoStateVar>(&mut self, key: &str, amount: T) {
+        self.set_effect(key, StateOperation::Subtract(raw_amount(amount)));
+    }
+
+    /// Removes the effects on a variable, returning the static effect if there
+    /// was one. A dynamic effect on the variable is removed too.
+    pub fn remove_effect(&mut self, key: &str) -> Option<StateOperation> {
+        self.dynamic_effects.remove(key);
+        self.effects.remove(key)
+    }
+
+    /// Returns the static effect on a variable, if any.
+    pub fn get_effect(&self, key: &str) -> Option<&StateOperation> {
+        self.effects.get(key)
+    }
+
+    /// Returns the sorted variables involved in preconditions that do not hold in
+    /// the state; empty if the action can be executed. See
+    /// [`Action::unmet_preconditions`] for the details of each.
+    pub fn get_missing_preconditions(&self, state: &State) -> Vec<String> {
+        failed_keys(self, state, |_| true)
+    }
+
+    /// Records a static effect, replacing any static or dynamic effect on the same key.
+    fn set_effect(&mut self, key: &str, operation: StateOperation) {
+        self.dynamic_effects.remove(key);
+        self.effects.insert(key.to_string(), operation);
+    }
+
+    /// Returns true if this action has predicted failure effects, making it a risky
+    /// step that contingency plans attach fallback branches to.
+    pub fn may_fail(&self) -> bool {
+        !self.failure_effects.is_empty()
+    }
+
+    /// Applies this action's failure effects to the given state, returning the state
+    /// predicted after the action fails.
+    pub fn apply_failure(&self, state: &State) -> State {
+        let mut new_state = state.clone();
+        new_state.apply(&self.failure_effects);
+        new_state
+    }
+
+    /// Returns true if this action carries the given tag.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|existing| existing == tag)
+    }
+
     /// Checks if this action can be executed given the current state.
     /// Returns true if all preconditions are satisfied.
     pub fn can_execute(&self, state: &State) -> bool {
         state.satisfies(&self.preconditions)
+            && self
+                .expr_preconditions
+                .iter()
+                .all(|comparison| comparison.holds(state))
+            && self.any_preconditions.iter().all(|group| {
+                group.iter().any(|(key, required)| {
+                    state
+                        .vars
+                        .get(key)
+                        .is_some_and(|current| value_satisfies(current, required))
+                })
+            })
+            && self
+                .absent_preconditions
+                .iter()
+                .all(|key| !state.vars.contains_key(key))
+            && state.satisfies_conditions(&self.conditions)
+    }
+
+    /// Returns the postconditions that do not hold in the state the action led to.
+    ///
+    /// ```
+    /// use goap::prelude::*;
+    ///
+    /// let store = Action::new("store_crate")
+    ///     .adds("used_storage", 10)
+    ///     .ensures_expr("used_storage <= storage_space")?
+    ///     .build();
+    /// let state = State::new().set("used_storage", 95).set("storage_space", 100).build();
+    /// assert_eq!(store.violated_postconditions(&store.apply_effect(&state)).len(), 1);
+    /// # Ok::<(), goap::expr::ExprError>(())
+    /// ```
+    pub fn violated_postconditions(&self, state: &State) -> Vec<&Comparison> {
+        self.postconditions
+            .iter()
+            .filter(|comparison| !comparison.holds(state))
+            .collect()
+    }
+
+    /// Checks the preconditions and applies the effects in one step, returning the
+    /// resulting state, or every precondition that does not hold.
+    ///
+    /// ```
+    /// use goap::prelude::*;
+    ///
+    /// let buy = Action::new("buy").requires("gold", 10).subtracts("gold", 10).build();
+    /// let failure = buy.dry_run(&State::new().set("gold", 4).build()).unwrap_err();
+    /// assert_eq!(
+    ///     failure.unmet,
+    ///     vec![UnmetPrecondition::Value {
+    ///         key: "gold".to_string(),
+    ///         required: StateVar::I64(10),
+    ///         actual: Some(StateVar::I64(4)),
+    ///     }]
+    /// );
+    /// ```
+    pub fn dry_run(&self, state: &State) -> Result<State, PreconditionFailure> {
+        let unmet = self.unmet_preconditions(state);
+        if unmet.is_empty() {
+            Ok(self.apply_effect(state))
+        } else {
+            Err(PreconditionFailure {
+                action: self.name.clone(),
+                unmet,
+            })
+        }
+    }
+
+    /// Returns every precondition of this action that does not hold in the state.
+    pub fn unmet_preconditions(&self, state: &State) -> Vec<UnmetPrecondition> {
+        let mut unmet: Vec<UnmetPrecondition> = sorted_entries(&self.preconditions.vars)
+            .into_iter()
+            .filter_map(|(key, required)| {
+                let actual = state.vars.get(key);
+                if actual.is_some_and(|current| value_satisfies(current, required)) {
+                    return None;
+                }
+                Some(UnmetPrecondition::Value {
+                    key: key.clone(),
+                    required: required.clone(),
+                    actual: actual.cloned(),
+                })
+            })
+            .collect();
+        unmet.extend(
+            self.expr_preconditions
+                .iter()
+                .filter(|comparison| !comparison.holds(state))
+                .map(|comparison| UnmetPrecondition::Comparison(comparison.clone())),
+        );
+        unmet.extend(
+            self.any_preconditions
+                .iter()
+                .filter(|group| {
+                    !group.iter().any(|(key, required)| {
+                        state
+                            .vars
+                            .get(key)
+                            .is_some_and(|current| value_satisfies(current, required))
+                    })
+                })
+                .map(|group| UnmetPrecondition::AnyOf(group.clone())),
+        );
+        unmet.extend(self.absent_preconditions.iter().filter_map(|key| {
+            state
+                .vars
+                .get(key)
+                .map(|actual| UnmetPrecondition::Present {
+                    key: key.clone(),
+                    actual: actual.clone(),
+                })
+        }));
+        unmet.extend(
+            self.conditions
+                .iter()
+                .filter(|(key, condition)| !condition.holds(state.vars.get(key)))
+                .map(|(key, condition)| UnmetPrecondition::Condition {
+                    key: key.clone(),
+                    condition: condition.clone(),
+                    actual: state.vars.get(key).cloned(),
+                }),
+        );
+        unmet
+    }
+
+    /// Splits an action with [`requires_any`](ActionBuilder::requires_any) groups into
+    /// one variant per combination of alternatives, each requiring its alternatives as
+    /// plain preconditions. Variants keep the name, cost and effects of the action.
+    ///
+    /// Combinations contradicting the other preconditions are left out, so the result
+    /// may be empty. An action without groups is returned unchanged.
+    pub fn variants(&self) -> Vec<Action> {
+        let mut variants = vec![Action {
+            any_preconditions: Vec::new(),
+            ..self.clone()
+        }];
+        for group in &self.any_preconditions {
+            let mut next: Vec<Action> = Vec::new();
+            for variant in &variants {
+                for (key, value) in group {
+                    if variant.absent_preconditions.contains(key) {
+                        continue;
+                    }
+                    let mut split = variant.clone();
+                    match split.preconditions.vars.get(key) {
+                        // The existing precondition already implies the alternative
+                        Some(existing) if value_satisfies(existing, value) => {}
+                        Some(existing) if !value_satisfies(value, existing) => continue,
+                        _ => {
+                            split.preconditions.vars.insert(key.clone(), value.clone());
+                        }
+                    }
+                    if !next
+                        .iter()
+                        .any(|other| other.preconditions == split.preconditions)
+                    {
+                        next.push(split);
+                    }
+                }
+            }
+            variants = next;
+        }
+        variants
+    }
+
+    /// Returns the operations this action performs when applied to the given state.
+    /// Dynamic effects are evaluated against `state` and combined with the static effects.
+    pub fn effects_for(&self, state: &State) -> HashMap<String, StateOperation> {
+        let mut effects = self.effects.clone();
+        for (key, effect) in &self.dynamic_effects {
+            effects.insert(key.clone(), effect.evaluate(state));
+        }
+        effects
     }
 
     /// Applies this action's effects to the given state, returning a new state.
     /// This does not modify the original state.
+    /// Dynamic effects are computed from the state before any effect is applied.
     pub fn apply_effect(&self, state: &State) -> State {
         let mut new_state = state.clone();
-        new_state.apply(&self.effects);
+        if self.dynamic_effects.is_empty() {
+            new_state.apply(&self.effects);
+        } else {
+            new_state.apply(&self.effects_for(state));
+        }
         new_state
     }
 }
@@ -92,8 +695,34 @@ pub struct ActionBuilder {
     cost: f64,
     /// The preconditions that must be met
     preconditions: State,
+    /// The arithmetic comparisons that must hold
+    expr_preconditions: Vec<Comparison>,
+    /// The groups of alternative conditions
+    any_preconditions: Vec<Vec<(String, StateVar)>>,
+    /// The variables that must be missing
+    absent_preconditions: Vec<String>,
+    /// The comparisons variables must meet
+    conditions: Vec<(String, Condition)>,
     /// The effects that will be applied
     effects: HashMap<String, StateOperation>,
+    /// The effects computed from the current state
+    dynamic_effects: HashMap<String, DynamicEffect>,
+    /// The tags attached to the action
+    tags: Vec<String>,
+    /// The effects predicted if the action fails
+    failure_effects: HashMap<String, StateOperation>,
+    /// The comparisons that must hold after the action
+    postconditions: Vec<Comparison>,
+    /// The name of the action undoing this one
+    inverse: Option<String>,
+    /// The resources the action holds while it runs
+    exclusive: Vec<String>,
+    /// Keys that were required with two different values
+    conflicts: Vec<String>,
+    /// Whether `try_build` reports values that could not be converted exactly
+    strict: bool,
+    /// Values that could not be converted exactly, with the key they were given for
+    invalid_values: Vec<(String, ConversionError)>,
 }
 
 impl ActionBuilder {
@@ -103,7 +732,20 @@ impl ActionBuilder {
             name: name.to_string(),
             cost: 1.0, // Default cost
             preconditions: State::empty(),
+            expr_preconditions: Vec::new(),
+            any_preconditions: Vec::new(),
+            absent_preconditions: Vec::new(),
+            conditions: Vec::new(),
             effects: HashMap::new(),
+            dynamic_effects: HashMap::new(),
+            tags: Vec::new(),
+            failure_effects: HashMap::new(),
+            postconditions: Vec::new(),
+            inverse: None,
+            exclusive: Vec::new(),
+            conflicts: Vec::new(),
+            strict: false,
+            invalid_values: Vec::new(),
         }
     }
 
@@ -113,45 +755,86 @@ impl ActionBuilder {
         self
     }
 
+    /// Makes [`ActionBuilder::try_build`] report every floating-point value that is
+    /// NaN, infinite or out of the fixed-point range, instead of only saturated ones.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Attaches a tag to this action. Tags have no effect on planning.
+    pub fn tag(mut self, tag: &str) -> Self {
+        if !self.tags.iter().any(|existing| existing == tag) {
+            self.tags.push(tag.to_string());
+        }
+        self
+    }
+
     /// Adds a precondition that must be satisfied before this action can be executed.
     fn precondition<T: IntoStateVar>(mut self, key: &str, value: T) -> Self {
-        self.preconditions.set(key, value.into_state_var());
+        let value = self.convert(key, value);
+        if self
+            .preconditions
+            .vars
+            .get(key)
+            .is_some_and(|existing| *existing != value)
+            || self.absent_preconditions.iter().any(|absent| absent == key)
+        {
+            self.conflicts.push(key.to_string());
+        }
+        self.preconditions.set(key, value);
+        self
+    }
+
+    /// Records a static effect, replacing any existing effect on the same key.
+    fn effect(mut self, key: &str, operation: StateOperation) -> Self {
+        self.dynamic_effects.remove(key);
+        self.effects.insert(key.to_string(), operation);
         self
     }
 
+    /// Converts a value, remembering any error a strict conversion would report.
+    fn convert<T: IntoStateVar>(&mut self, key: &str, value: T) -> StateVar {
+        let (value, error) = value.into_state_var_checked();
+        if let Some(error) = error {
+            self.invalid_values.push((key.to_string(), error));
+        }
+        value
+    }
+
+    /// Remembers the error a strict conversion of a floating-point amount would report.
+    fn check_amount(&mut self, key: &str, value: f64) {
+        if let Err(error) = StateVar::try_from_f64(value) {
+            self.invalid_values.push((key.to_string(), error));
+        }
+    }
+
     /// Adds an effect that sets a state variable to a specific value.
     fn effect_set_to<T: IntoStateVar>(mut self, key: &str, value: T) -> Self {
-        self.effects
-            .insert(key.to_string(), StateOperation::Set(value.into_state_var()));
-        self
+        let value = self.convert(key, value);
+        self.effect(key, StateOperation::Set(value))
     }
 
     /// Adds an effect that adds an integer value to a state variable.
-    fn effect_add_int(mut self, key: &str, value: i64) -> Self {
-        self.effects
-            .insert(key.to_string(), StateOperation::add_i64(value));
-        self
+    fn effect_add_int(self, key: &str, value: i64) -> Self {
+        self.effect(key, StateOperation::add_i64(value))
     }
 
     /// Adds an effect that subtracts an integer value from a state variable.
-    fn effect_subtract_int(mut self, key: &str, value: i64) -> Self {
-        self.effects
-            .insert(key.to_string(), StateOperation::subtract_i64(value));
-        self
+    fn effect_subtract_int(self, key: &str, value: i64) -> Self {
+        self.effect(key, StateOperation::subtract_i64(value))
     }
 
     /// Adds an effect that adds a floating-point value to a state variable.
     fn effect_add_float(mut self, key: &str, value: f64) -> Self {
-        self.effects
-            .insert(key.to_string(), StateOperation::add_f64(value));
-        self
+        self.check_amount(key, value);
+        self.effect(key, StateOperation::add_f64(value))
     }
 
     /// Adds an effect that subtracts a floating-point value from a state variable.
     fn effect_subtract_float(mut self, key: &str, value: f64) -> Self {
-        self.effects
-            .insert(key.to_string(), StateOperation::subtract_f64(value));
-        self
+        self.check_amount(key, value);
+        self.effect(key, StateOperation::subtract_f64(value))
     }
 
     /// Adds a precondition that must be satisfied before this action can be executed.
@@ -160,12 +843,120 @@ impl ActionBuilder {
         self.precondition(key, value)
     }
 
+    /// Adds a precondition on an enum variable declared with an [`EnumKey`].
+    /// Values of another type than the key's fail to compile.
+    pub fn requires_enum<T: EnumStateVar>(self, key: EnumKey<T>, value: T) -> Self {
+        self.precondition(key.name(), value)
+    }
+
+    /// Adds a precondition on a quantity variable declared with a [`QuantityKey`]:
+    /// the variable must reach `value`. Values of another quantity fail to compile.
+    pub fn requires_quantity<Q: Quantity>(self, key: QuantityKey<Q>, value: Q) -> Self {
+        self.precondition(key.name(), key.store(value))
+    }
+
+    /// Adds a precondition that a variable must be missing from the state, e.g. an
+    /// enemy position that has been forgotten (see [`BeliefState`](crate::belief::BeliefState)).
+    /// Requiring a variable both present and absent is reported as a conflict.
+    pub fn requires_absent(mut self, key: &str) -> Self {
+        if self.preconditions.vars.contains_key(key) {
+            self.conflicts.push(key.to_string());
+        }
+        if !self.absent_preconditions.iter().any(|absent| absent == key) {
+            self.absent_preconditions.push(key.to_string());
+        }
+        self
+    }
+
+    /// Adds a precondition that a variable must meet a [`Condition`]. A missing
+    /// variable never meets it.
+    pub fn requires_condition(mut self, key: &str, condition: Condition) -> Self {
+        self.conditions.push((key.to_string(), condition));
+        self
+    }
+
+    /// Adds a precondition that a numeric variable must be below a bound, e.g.
+    /// `requires_lt("guard_alert", 2)`.
+    pub fn requires_lt<T: IntoStateVar>(mut self, key: &str, bound: T) -> Self {
+        let bound = self.convert(key, bound);
+        self.requires_condition(key, Condition::LessThan(bound))
+    }
+
+    /// Adds a precondition that a numeric variable must be above a bound. Unlike
+    /// [`requires`](ActionBuilder::requires), reaching the bound is not enough.
+    pub fn requires_gt<T: IntoStateVar>(mut self, key: &str, bound: T) -> Self {
+        let bound = self.convert(key, bound);
+        self.requires_condition(key, Condition::GreaterThan(bound))
+    }
+
+    /// Adds a precondition that a variable must be set to anything but the given value.
+    pub fn requires_ne<T: IntoStateVar>(mut self, key: &str, value: T) -> Self {
+        let value = self.convert(key, value);
+        self.requires_condition(key, Condition::NotEqual(value))
+    }
+
+    /// Adds a precondition that a numeric variable must lie between `min` and
+    /// `max`, both included.
+    pub fn requires_between<T: IntoStateVar>(mut self, key: &str, min: T, max: T) -> Self {
+        let min = self.convert(key, min);
+        let max = self.convert(key, max);
+        self.requires_condition(key, Condition::InRange(min, max))
+    }
+
+    /// Adds a group of alternative preconditions of which at least one must hold,
+    /// e.g. `requires_any([("has_sword", true), ("has_bow", true)])`.
+    /// Each alternative is compared like a precondition added with `requires`.
+    /// The planner splits the action into one variant per alternative (see
+    /// [`Action::variants`]). An empty group is ignored.
+    pub fn requires_any<K, T, I>(mut self, alternatives: I) -> Self
+    where
+        K: AsRef<str>,
+        T: IntoStateVar,
+        I: IntoIterator<Item = (K, T)>,
+    {
+        let group: Vec<(String, StateVar)> = alternatives
+            .into_iter()
+            .map(|(key, value)| {
+                let key = key.as_ref();
+                (key.to_string(), self.convert(key, value))
+            })
+            .collect();
+        if !group.is_empty() {
+            self.any_preconditions.push(group);
+        }
+        self
+    }
+
     /// Adds an effect that sets a state variable to a specific value.
     /// This is an alias for the internal `effect_set_to` method.
     pub fn sets<T: IntoStateVar>(self, key: &str, value: T) -> Self {
         self.effect_set_to(key, value)
     }
 
+    /// Adds an effect setting an enum variable declared with an [`EnumKey`].
+    /// Values of another type than the key's fail to compile.
+    pub fn sets_enum<T: EnumStateVar>(self, key: EnumKey<T>, value: T) -> Self {
+        self.effect_set_to(key.name(), value)
+    }
+
+    /// Adds an effect setting a quantity variable declared with a [`QuantityKey`].
+    /// Values of another quantity fail to compile.
+    pub fn sets_quantity<Q: Quantity>(self, key: QuantityKey<Q>, value: Q) -> Self {
+        self.effect_set_to(key.name(), key.store(value))
+    }
+
+    /// Adds an effect adding to a quantity variable declared with a [`QuantityKey`].
+    /// Values of another quantity fail to compile.
+    pub fn adds_quantity<Q: Quantity>(self, key: QuantityKey<Q>, value: Q) -> Self {
+        self.effect(key.name(), StateOperation::Add(key.raw(value)))
+    }
+
+    /// Adds an effect subtracting from a quantity variable declared with a
+    /// [`QuantityKey`]. Values of another quantity fail to compile.
+    pub fn subtracts_quantity<Q: Quantity>(self, key: QuantityKey<Q>, value: Q) -> Self {
+        self.effect(key.name(), StateOperation::Subtract(key.raw(value)))
+    }
+
     /// Adds an effect that adds a numeric value to a state variable.
     /// Works with both integers and floats.
     pub fn adds<T: NumericValue>(self, key: &str, value: T) -> Self {
@@ -178,17 +969,238 @@ impl ActionBuilder {
         value.subtract_from_action_builder(self, key)
     }
 
+    /// Marks the action as one that may fail, predicting that a failure sets a state
+    /// variable to a specific value. On failure none of the regular effects apply.
+    /// Contingency plans (see [`Planner::plan_contingent`](crate::planner::Planner::plan_contingent))
+    /// plan a fallback from the predicted failure state.
+    pub fn fails_with<T: IntoStateVar>(mut self, key: &str, value: T) -> Self {
+        let value = self.convert(key, value);
+        self.failure_effects
+            .insert(key.to_string(), StateOperation::Set(value));
+        self
+    }
+
+    /// Adds an effect whose operation is computed from the state the action is applied to.
+    /// The function receives the state before any of this action's effects are applied.
+    ///
+    /// ```
+    /// use goap::prelude::*;
+    ///
+    /// let harvest = Action::new("harvest")
+    ///     .effect_fn("grain", |state| {
+    ///         StateOperation::add_i64(state.get::<i64>("farm_level").unwrap_or(0) * 10)
+    ///     })
+    ///     .build();
+    ///
+    /// let state = State::new().set("farm_level", 3).set("grain", 0).build();
+    /// assert_eq!(harvest.apply_effect(&state).get::<i64>("grain"), Some(30));
+    /// ```
+    pub fn effect_fn<F>(mut self, key: &str, f: F) -> Self
+    where
+        F: Fn(&State) -> StateOperation + Send + Sync + 'static,
+    {
+        self.effects.remove(key);
+        self.dynamic_effects
+            .insert(key.to_string(), DynamicEffect::new(f));
+        self
+    }
+
+    /// Requires that the built-in time variable has reached `tick`.
+    /// See the [`time`](crate::time) module for how time is managed.
+    pub fn requires_time(self, tick: i64) -> Self {
+        if tick <= 0 {
+            // Time starts at tick 0 and never runs backwards
+            return self;
+        }
+        self.precondition(TIME_KEY, tick)
+    }
+
+    /// Advances the built-in time variable by `ticks` when the action is performed.
+    /// A state without the time variable is treated as being at tick 0.
+    pub fn takes_time(mut self, ticks: i64) -> Self {
+        let ticks = ticks.max(0);
+        let effect = DynamicEffect::new(move |state| {
+            StateOperation::Set(StateVar::I64(state.time().saturating_add(ticks)))
+        })
+        .with_description(&format!("{TIME_KEY} += {ticks}"));
+        self.effects.remove(TIME_KEY);
+        self.dynamic_effects.insert(TIME_KEY.to_string(), effect);
+        self
+    }
+
+    /// Stores the tick at which a timer runs out, `ticks` from the time the action
+    /// is performed, in the variable `key`. Use
+    /// [`requires_timer`](ActionBuilder::requires_timer) to wait for it.
+    pub fn starts_timer(mut self, key: &str, ticks: i64) -> Self {
+        let effect = DynamicEffect::new(move |state| {
+            StateOperation::Set(StateVar::I64(state.time().saturating_add(ticks)))
+        })
+        .with_description(&format!("{key} = {TIME_KEY} + {ticks}"));
+        self.effects.remove(key);
+        self.dynamic_effects.insert(key.to_string(), effect);
+        if !self.effects.contains_key(TIME_KEY) && !self.dynamic_effects.contains_key(TIME_KEY) {
+            // Make the time variable explicit so `requires_timer` can compare with it
+            self = self.takes_time(0);
+        }
+        self
+    }
+
+    /// Requires that the timer stored in `key` by
+    /// [`starts_timer`](ActionBuilder::starts_timer) has run out.
+    /// The precondition does not hold while the timer has not been started.
+    pub fn requires_timer(mut self, key: &str) -> Self {
+        self.expr_preconditions.push(Comparison {
+            lhs: Expr::Var(TIME_KEY.to_string()),
+            op: CompareOp::Ge,
+            rhs: Expr::Var(key.to_string()),
+        });
+        self
+    }
+
+    /// Adds a precondition written as an arithmetic comparison over state variables.
+    /// Returns an error if the expression cannot be parsed.
+    ///
+    /// ```
+    /// use goap::prelude::*;
+    ///
+    /// let buy = Action::new("buy")
+    ///     .requires_expr("gold >= price * quantity")?
+    ///     .effect_expr("gold -= price * quantity")?
+    ///     .build();
+    ///
+    /// let state = State::new().set("gold", 100).set("price", 15).set("quantity", 4).build();
+    /// assert!(buy.can_execute(&state));
+    /// assert_eq!(buy.apply_effect(&state).get::<i64>("gold"), Some(40));
+    /// # Ok::<(), goap::expr::ExprError>(())
+    /// ```
+    pub fn requires_expr(mut self, expr: &str) -> Result<Self, ExprError> {
+        self.expr_preconditions.push(Comparison::parse(expr)?);
+        Ok(self)
+    }
+
+    /// Adds a postcondition written as an arithmetic comparison over state
+    /// variables, which must hold in the state after the action, e.g.
+    /// `used_storage <= storage_space`. The planner skips the action wherever its
+    /// effects would break the comparison, and
+    /// [`PlanExecutor::completed`](crate::executor::PlanExecutor::completed) reports
+    /// executions that break it. Returns an error if the expression cannot be parsed.
+    pub fn ensures_expr(mut self, expr: &str) -> Result<Self, ExprError> {
+        self.postconditions.push(Comparison::parse(expr)?);
+        Ok(self)
+    }
+
+    /// Declares the action that undoes this one, e.g. `unequip` for `equip`.
+    /// [`Planner::plan_undo`](crate::planner::Planner::plan_undo) uses it instead
+    /// of deriving an inverse from the effects.
+    pub fn inverse(mut self, action: &str) -> Self {
+        self.inverse = Some(action.to_string());
+        self
+    }
+
+    /// Declares a resource the action holds while it runs, e.g. `hands` or
+    /// `workbench`. A [`ParallelExecutor`](crate::parallel::ParallelExecutor)
+    /// never runs two steps holding the same resource at the same time. Has no
+    /// effect on planning.
+    pub fn exclusive(mut self, resource: &str) -> Self {
+        if !self.exclusive.iter().any(|existing| existing == resource) {
+            self.exclusive.push(resource.to_string());
+        }
+        self
+    }
+
+    /// Adds an effect written as an assignment (`=`, `+=` or `-=`) of an arithmetic expression.
+    /// The expression is evaluated against the state the action is applied to.
+    /// If evaluation fails at plan time (e.g. a referenced variable is missing), the effect
+    /// leaves the variable unchanged.
+    /// Returns an error if the expression cannot be parsed.
+    pub fn effect_expr(mut self, expr: &str) -> Result<Self, ExprError> {
+        let assignment = Assignment::parse(expr)?;
+        let key = assignment.key.clone();
+        let description = assignment.to_string();
+        let effect = DynamicEffect::new(move |state| {
+            assignment
+                .to_operation(state)
+                .unwrap_or(StateOperation::Add(0))
+        })
+        .with_description(&description);
+
+        self.effects.remove(&key);
+        self.dynamic_effects.insert(key, effect);
+        Ok(self)
+    }
+
+    /// Builds the final Action after checking the definition for authoring mistakes.
+    ///
+    /// Returns an error if the name is empty, the cost is NaN, infinite or negative,
+    /// a precondition was given two different values, or a floating-point value is
+    /// out of the fixed-point range. In [`strict`](ActionBuilder::strict) mode, NaN,
+    /// infinite and out-of-range amounts are reported as `DefinitionError::InvalidValue`.
+    /// [`ActionBuilder::build`] skips these checks.
+    pub fn try_build(self) -> Result<Action, DefinitionError> {
+        if self.name.is_empty() {
+            return Err(DefinitionError::EmptyName);
+        }
+        if !self.cost.is_finite() || self.cost < 0.0 {
+            return Err(DefinitionError::InvalidCost(self.cost));
+        }
+        if let Some(key) = self.conflicts.first() {
+            return Err(DefinitionError::ConflictingRequirement { key: key.clone() });
+        }
+        if self.strict
+            && let Some((key, error)) = self.invalid_values.first()
+        {
+            return Err(DefinitionError::InvalidValue {
+                key: key.clone(),
+                error: error.clone(),
+            });
+        }
+        check_range(&self.preconditions)?;
+        let mut saturated: Vec<&String> = self
+            .effects
+            .iter()
+            .filter(|(_, operation)| {
+                matches!(operation, StateOperation::Set(value) if is_saturated(value))
+            })
+            .map(|(key, _)| key)
+            .collect();
+        saturated.sort();
+        if let Some(key) = saturated.first() {
+            return Err(DefinitionError::OutOfRange {
+                key: (*key).clone(),
+            });
+        }
+        Ok(self.build())
+    }
+
     /// Builds the final Action from the configured builder.
     pub fn build(self) -> Action {
         Action {
             name: self.name,
             cost: self.cost,
             preconditions: self.preconditions,
+            expr_preconditions: self.expr_preconditions,
+            any_preconditions: self.any_preconditions,
+            absent_preconditions: self.absent_preconditions,
+            conditions: self.conditions,
             effects: self.effects,
+            dynamic_effects: self.dynamic_effects,
+            tags: self.tags,
+            failure_effects: self.failure_effects,
+            postconditions: self.postconditions,
+            inverse: self.inverse,
+            exclusive: self.exclusive,
         }
     }
 }
 
+/// Returns the raw amount of a number: the integer, or the fixed-point units of a float.
+fn raw_amount<T: IntoStateVar>(amount: T) -> i64 {
+    match amount.into_state_var() {
+        StateVar::I64(raw) | StateVar::F64(raw) => raw,
+        _ => 0,
+    }
+}
+
 /// Trait for numeric values that can be added or subtracted in action effects.
 /// This trait is implemented for i32, i64, f32, and f64.
 pub trait NumericValue {
@@ -0,0 +1,556 @@
+//! Static analysis of action sets.
+//!
+//! [`analyze_deadlocks`] finds actions that can never run from a given initial
+//! state, however the other actions are combined, and reports which of their
+//! preconditions is out of reach. It is a development tool for catching missing
+//! producer actions before they show up as a bare `PlannerError::NoPlanFound`.
+//! [`analyze_goal_reachability`] does the same for a goal's requirements.
+//!
+//! [`analyze_writers`] lists, for every variable, the actions that change it and
+//! how, and flags pairs of actions writing it with different types, such as one
+//! setting `true` and another adding a number. Such pairs are almost always
+//! authoring mistakes.
+
+use crate::actions::Action;
+use crate::goals::Goal;
+use crate::schema::StateSchema;
+use crate::state::{State, StateOperation, StateVar, sorted_entries, value_satisfies};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+/// A precondition that no reachable state can satisfy.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockedCondition {
+    /// The variable the precondition is about
+    pub key: String,
+    /// The value the precondition requires
+    pub required: StateVar,
+    /// True if some action changes this variable, just never to a satisfying value
+    pub has_producer: bool,
+}
+
+/// An action that can never be executed from the analysed initial state.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Deadlock {
+    /// The name of the action
+    pub action: String,
+    /// The preconditions that can never be met
+    pub blocked: Vec<BlockedCondition>,
+}
+
+impl fmt::Display for Deadlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Action '{}' can never run:", self.action)?;
+        for condition in &self.blocked {
+            let reason = if condition.has_producer {
+                "no action produces a satisfying value"
+            } else {
+                "no action produces this variable"
+            };
+            write!(
+                f,
+                "\n  - requires {}: {} ({reason})",
+                condition.key, condition.required
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// An over-approximation of the values each variable can take.
+#[derive(Default)]
+struct Reachable {
+    /// Every value each variable can be given
+    values: HashMap<String, HashSet<StateVar>>,
+    /// Numeric variables that can grow without limit
+    unbounded: HashSet<String>,
+    /// Variables changed by dynamic effects, which may take any value
+    any: HashSet<String>,
+}
+
+impl Reachable {
+    fn can_satisfy(&self, key: &str, required: &StateVar) -> bool {
+        if self.any.contains(key) {
+            return true;
+        }
+        let Some(values) = self.values.get(key) else {
+            return false;
+        };
+        values.iter().any(|value| {
+            value_satisfies(value, required)
+                || (self.unbounded.contains(key)
+                    && std::mem::discriminant(value) == std::mem::discriminant(required))
+        })
+    }
+
+    fn can_execute(&self, action: &Action) -> bool {
+        action
+            .preconditions
+            .vars
+            .iter()
+            .all(|(key, required)| self.can_satisfy(key, required))
+    }
+
+    fn apply(&mut self, action: &Action) {
+        for (key, operation) in &action.effects {
+            match operation {
+                StateOperation::Set(value) => {
+                    self.values
+                        .entry(key.clone())
+                        .or_default()
+                        .insert(value.clone());
+                }
+                StateOperation::Add(amount) if *amount > 0 => {
+                    self.unbounded.insert(key.clone());
+                }
+                StateOperation::Subtract(amount) if *amount < 0 => {
+                    self.unbounded.insert(key.clone());
+                }
+                _ => {}
+            }
+        }
+        self.any.extend(action.dynamic_effects.keys().cloned());
+    }
+}
+
+/// Lists the actions that can never be executed starting from `initial_state`.
+///
+/// Reachability is computed on a relaxed model where every action that becomes
+/// executable can be repeated and effects only ever add possible values, so an
+/// action reported here is guaranteed never to run. Expression preconditions are
+/// assumed to be satisfiable. Each report names the preconditions that are out of
+/// reach and whether any action produces the variable at all.
+pub fn analyze_deadlocks(actions: &[Action], initial_state: &State) -> Vec<Deadlock> {
+    let (reachable, fired) = relax(actions, initial_state);
+    let produced = produced_keys(actions);
+    actions
+        .iter()
+        .zip(fired)
+        .filter(|(_, fired)| !fired)
+        .map(|(action, _)| Deadlock {
+            action: action.name.clone(),
+            blocked: action
+                .preconditions
+                .vars
+                .iter()
+                .filter(|(key, required)| !reachable.can_satisfy(key, required))
+                .map(|(key, required)| BlockedCondition {
+                    key: key.clone(),
+                    required: required.clone(),
+                    has_producer: produced.contains(key.as_str()),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Lists the requirements of `goal` that no state reachable from `initial_state`
+/// can meet, sorted by variable.
+///
+/// Uses the same relaxed model as [`analyze_deadlocks`], so a requirement reported
+/// here is guaranteed to be out of reach. Only required values are checked;
+/// excluded values and conditions are assumed to be reachable.
+///
+/// ```
+/// use goap::prelude::*;
+///
+/// let actions = vec![Action::new("mine").requires("has_pickaxe", true).adds("ore", 1).build()];
+/// let goal = Goal::new("smith").requires("ore", 3).requires("has_hammer", true).build();
+/// let state = State::new().set("has_pickaxe", false).set("ore", 0).build();
+///
+/// let blocked = analyze_goal_reachability(&actions, &state, &goal);
+/// let keys: Vec<&str> = blocked.iter().map(|condition| condition.key.as_str()).collect();
+/// assert_eq!(keys, ["has_hammer", "ore"]);
+/// ```
+pub fn analyze_goal_reachability(
+    actions: &[Action],
+    initial_state: &State,
+    goal: &Goal,
+) -> Vec<BlockedCondition> {
+    let (reachable, _) = relax(actions, initial_state);
+    let produced = produced_keys(actions);
+    sorted_entries(&goal.desired_state.vars)
+        .into_iter()
+        .filter(|(key, required)| !reachable.can_satisfy(key, required))
+        .map(|(key, required)| BlockedCondition {
+            key: key.clone(),
+            required: required.clone(),
+            has_producer: produced.contains(key.as_str()),
+        })
+        .collect()
+}
+
+/// Computes the values reachable from `initial_state` in the relaxed model, and
+/// which actions can ever run.
+fn relax(actions: &[Action], initial_state: &State) -> (Reachable, Vec<bool>) {
+    let mut reachable = Reachable::default();
+    for (key, value) in &initial_state.vars {
+        reachable
+            .values
+            .entry(key.clone())
+            .or_default()
+            .insert(value.clone());
+    }
+
+    let mut fired = vec![false; actions.len()];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (index, action) in actions.iter().enumerate() {
+            if !fired[index] && reachable.can_execute(action) {
+                reachable.apply(action);
+                fired[index] = true;
+                changed = true;
+            }
+        }
+    }
+    (reachable, fired)
+}
+
+/// Returns the variables some action changes.
+fn produced_keys(actions: &[Action]) -> HashSet<&str> {
+    actions
+        .iter()
+        .flat_map(|action| action.effects.keys().chain(action.dynamic_effects.keys()))
+        .map(String::as_str)
+        .collect()
+}
+
+/// A goal requirement that, once unmet, no action can meet again.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeadEndRule {
+    /// The variable the requirement is about
+    pub key: String,
+    /// The required value, or the excluded value for exclusions
+    pub value: StateVar,
+    /// True if the goal excludes the value rather than requiring it
+    pub excluded: bool,
+}
+
+impl DeadEndRule {
+    /// Returns true if the state breaks the requirement, making it a dead end.
+    pub fn blocks(&self, state: &State) -> bool {
+        let current = state.vars.get(&self.key);
+        if self.excluded {
+            current == Some(&self.value)
+        } else {
+            !current.is_some_and(|current| value_satisfies(current, &self.value))
+        }
+    }
+}
+
+impl fmt::Display for DeadEndRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.value, self.excluded) {
+            (value, true) => write!(f, "{} = {value} can never change", self.key),
+            (StateVar::I64(_) | StateVar::F64(_), false) => {
+                write!(f, "{} below {} can never be raised", self.key, self.value)
+            }
+            (value, false) => write!(f, "{} can never become {value}", self.key),
+        }
+    }
+}
+
+/// The goal requirements that make states dead ends, found by [`analyze_dead_ends`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DeadEndAnalysis {
+    /// The requirements that no action can meet again once they are unmet
+    pub rules: Vec<DeadEndRule>,
+}
+
+impl DeadEndAnalysis {
+    /// Returns the first rule showing that the goal is unreachable from the state,
+    /// or None if the analysis cannot rule the goal out.
+    pub fn dead_end(&self, state: &State) -> Option<&DeadEndRule> {
+        self.rules.iter().find(|rule| rule.blocks(state))
+    }
+}
+
+impl fmt::Display for DeadEndAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Dead-end rules: {}", self.rules.len())?;
+        for rule in &self.rules {
+            write!(f, "\n  - {rule}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns true if some effect of the actions may help meet a requirement on
+/// `key` once it is unmet. A requirement for a monotonic variable to be false
+/// can never be met again once the variable is true.
+fn can_restore(
+    actions: &[Action],
+    key: &str,
+    value: &StateVar,
+    excluded: bool,
+    schema: Option<&StateSchema>,
+) -> bool {
+    let requires_false = matches!(
+        (value, excluded),
+        (StateVar::Bool(false), false) | (StateVar::Bool(true), true)
+    );
+    if requires_false && schema.is_some_and(|schema| schema.is_monotonic(key)) {
+        return false;
+    }
+    actions.iter().any(|action| {
+        if action.dynamic_effects.contains_key(key) {
+            return true;
+        }
+        match action.effects.get(key) {
+            None => false,
+            Some(StateOperation::Set(set)) if excluded => set != value,
+            Some(StateOperation::Set(set)) => value_satisfies(set, value),
+            Some(StateOperation::Add(amount)) if excluded => *amount != 0,
+            Some(StateOperation::Subtract(amount)) if excluded => *amount != 0,
+            Some(StateOperation::Add(amount)) => *amount > 0,
+            Some(StateOperation::Subtract(amount)) => *amount < 0,
+        }
+    })
+}
+
+/// Finds the requirements of `goal` that no action can help meet once they are
+/// unmet: numbers that no action increases or sets high enough, booleans and
+/// strings that no action sets to the required value, and excluded values that
+/// no action changes. Every state breaking one of them is a dead end.
+///
+/// The analysis looks at effects only, so it never reports a requirement that
+/// some action could restore. Relative requirements are ignored; resolve the goal
+/// with [`Goal::resolve`] first to include them.
+///
+/// ```
+/// use goap::prelude::*;
+///
+/// let actions = vec![Action::new("fire").requires("ammo", 1).subtracts("ammo", 1).build()];
+/// let goal = Goal::new("keep_reserve").requires("ammo", 5).build();
+///
+/// let analysis = analyze_dead_ends(&actions, &goal);
+/// let low = State::new().set("ammo", 3).build();
+/// assert_eq!(analysis.dead_end(&low).unwrap().key, "ammo");
+/// assert!(analysis.dead_end(&State::new().set("ammo", 8).build()).is_none());
+/// ```
+pub fn analyze_dead_ends(actions: &[Action], goal: &Goal) -> DeadEndAnalysis {
+    dead_end_rules(actions, goal, None)
+}
+
+/// Like [`analyze_dead_ends`], also using the [monotonic](crate::schema::KeyInfo::monotonic)
+/// declarations of a schema: a requirement for a monotonic boolean to be false
+/// is a dead end once the boolean is true, whatever the actions' effects.
+pub fn analyze_dead_ends_with_schema(
+    actions: &[Action],
+    goal: &Goal,
+    schema: &StateSchema,
+) -> DeadEndAnalysis {
+    dead_end_rules(actions, goal, Some(schema))
+}
+
+/// Finds the dead-end rules of a goal, using the schema's declarations if given.
+fn dead_end_rules(
+    actions: &[Action],
+    goal: &Goal,
+    schema: Option<&StateSchema>,
+) -> DeadEndAnalysis {
+    let required = goal
+        .desired_state
+        .vars
+        .iter()
+        .map(|(key, value)| (key, value, false));
+    let excluded = goal
+        .excluded
+        .vars
+        .iter()
+        .map(|(key, value)| (key, value, true));
+    let mut rules: Vec<DeadEndRule> = required
+        .chain(excluded)
+        .filter(|(key, value, excluded)| !can_restore(actions, key, value, *excluded, schema))
+        .map(|(key, value, excluded)| DeadEndRule {
+            key: key.clone(),
+            value: value.clone(),
+            excluded,
+        })
+        .collect();
+    rules.sort_by(|a, b| a.key.cmp(&b.key).then(a.excluded.cmp(&b.excluded)));
+    DeadEndAnalysis { rules }
+}
+
+/// An action changing a variable, listed by [`analyze_writers`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyWriter {
+    /// The name of the action
+    pub action: String,
+    /// The change, or None for a dynamic effect
+    pub operation: Option<StateOperation>,
+    /// True if the change is one of the action's failure effects
+    pub on_failure: bool,
+}
+
+impl KeyWriter {
+    /// Returns the type the write gives the variable: the type name of a set
+    /// value, `"numeric"` for additions and subtractions, or None for dynamic
+    /// effects, whose type is unknown.
+    pub fn written_type(&self) -> Option<&'static str> {
+        match &self.operation {
+            Some(StateOperation::Set(value)) => Some(value.type_name()),
+            Some(StateOperation::Add(_) | StateOperation::Subtract(_)) => Some("numeric"),
+            None => None,
+        }
+    }
+}
+
+impl fmt::Display for KeyWriter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.operation {
+            Some(StateOperation::Set(value)) => write!(f, "{}: set {value}", self.action)?,
+            Some(StateOperation::Add(amount)) => write!(f, "{}: add {amount}", self.action)?,
+            Some(StateOperation::Subtract(amount)) => {
+                write!(f, "{}: subtract {amount}", self.action)?;
+            }
+            None => write!(f, "{}: computed", self.action)?,
+        }
+        if self.on_failure {
+            write!(f, " (on failure)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Two actions writing a variable with incompatible types.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WriteConflict {
+    /// The variable
+    pub key: String,
+    /// The first action writing the variable with one type
+    pub first: KeyWriter,
+    /// The first action writing the variable with another type
+    pub second: KeyWriter,
+}
+
+impl fmt::Display for WriteConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: '{}' writes {} but '{}' writes {}",
+            self.key,
+            self.first.action,
+            self.first.written_type().unwrap_or("unknown"),
+            self.second.action,
+            self.second.written_type().unwrap_or("unknown")
+        )
+    }
+}
+
+/// The actions writing each variable, found by [`analyze_writers`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WriterReport {
+    /// The actions changing each variable, in the order of the action slice
+    pub writers: BTreeMap<String, Vec<KeyWriter>>,
+    /// The pairs of actions writing a variable with incompatible types, sorted by variable
+    pub conflicts: Vec<WriteConflict>,
+}
+
+impl WriterReport {
+    /// Returns the actions changing the variable, in the order of the action slice.
+    pub fn writers_of(&self, key: &str) -> &[KeyWriter] {
+        self.writers.get(key).map_or(&[], Vec::as_slice)
+    }
+}
+
+impl fmt::Display for WriterReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Writers of {} variables", self.writers.len())?;
+        for (key, writers) in &self.writers {
+            write!(f, "\n  {key}:")?;
+            for writer in writers {
+                write!(f, "\n    - {writer}")?;
+            }
+        }
+        if !self.conflicts.is_empty() {
+            write!(f, "\nSuspicious writes:")?;
+            for conflict in &self.conflicts {
+                write!(f, "\n  - {conflict}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns true if writes of the two types can target the same variable.
+fn compatible_writes(first: &str, second: &str) -> bool {
+    first == second
+        || matches!(
+            (first, second),
+            ("numeric", "I64" | "F64") | ("I64" | "F64", "numeric")
+        )
+}
+
+/// Lists the actions changing each variable through their effects, dynamic
+/// effects and failure effects, and reports pairs writing a variable with
+/// incompatible types: values of different types, or arithmetic on a variable
+/// another action sets to a boolean or string. Dynamic effects are listed but
+/// never conflict, since their type is unknown.
+///
+/// For each variable, one conflict is reported per pair of types, naming the
+/// first action writing each type.
+///
+/// ```
+/// use goap::prelude::*;
+///
+/// let actions = vec![
+///     Action::new("mine").adds("gold", 5).build(),
+///     Action::new("find_chest").sets("gold", true).build(),
+/// ];
+/// let report = analyze_writers(&actions);
+/// assert_eq!(report.writers_of("gold").len(), 2);
+/// assert_eq!(
+///     report.conflicts[0].to_string(),
+///     "gold: 'mine' writes numeric but 'find_chest' writes Bool"
+/// );
+/// ```
+pub fn analyze_writers(actions: &[Action]) -> WriterReport {
+    let mut writers: BTreeMap<String, Vec<KeyWriter>> = BTreeMap::new();
+    for action in actions {
+        let mut add = |key: &String, operation: Option<StateOperation>, on_failure: bool| {
+            writers.entry(key.clone()).or_default().push(KeyWriter {
+                action: action.name.clone(),
+                operation,
+                on_failure,
+            });
+        };
+        for (key, operation) in sorted_entries(&action.effects) {
+            add(key, Some(operation.clone()), false);
+        }
+        for (key, _) in sorted_entries(&action.dynamic_effects) {
+            add(key, None, false);
+        }
+        for (key, operation) in sorted_entries(&action.failure_effects) {
+            add(key, Some(operation.clone()), true);
+        }
+    }
+
+    let mut conflicts = Vec::new();
+    for (key, key_writers) in &writers {
+        let mut first_of_type: Vec<&KeyWriter> = Vec::new();
+        for writer in key_writers {
+            let Some(written) = writer.written_type() else {
+                continue;
+            };
+            if first_of_type
+                .iter()
+                .any(|first| first.written_type() == Some(written))
+            {
+                continue;
+            }
+            for first in &first_of_type {
+                if !compatible_writes(first.written_type().unwrap_or(written), written) {
+                    conflicts.push(WriteConflict {
+                        key: key.clone(),
+                        first: (*first).clone(),
+                        second: writer.clone(),
+                    });
+                }
+            }
+            first_of_type.push(writer);
+        }
+    }
+    WriterReport { writers, conflicts }
+}
@@ -0,0 +1,273 @@
+//! Goal lifecycles for agents: one-shot and recurring goals.
+//!
+//! Agents mix quest-like goals, which matter until achieved once, with
+//! maintenance goals such as staying fed, which matter again whenever they stop
+//! holding. A [`GoalArbiter`] tracks both kinds: each tick,
+//! [`GoalArbiter::update`] checks the goals against the current state, retires
+//! achieved [`Recurrence::Once`] goals, and re-arms [`Recurrence::Recurring`]
+//! goals whose requirements no longer hold. Callbacks registered with
+//! [`GoalArbiter::on_achieved`] and [`GoalArbiter::on_abandoned`] run as goals
+//! are achieved or given up, and [`GoalArbiter::active_goals`] lists the goals
+//! still worth planning for.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let mut arbiter = GoalArbiter::new()
+//!     .once(Goal::new("find_sword").requires("has_sword", true).build())
+//!     .recurring(Goal::new("eat").requires("fed", true).build());
+//!
+//! let state = State::new().set("has_sword", true).set("fed", true).build();
+//! let events = arbiter.update(&state);
+//! assert_eq!(events.len(), 2);
+//! assert!(arbiter.active_goals().is_empty());
+//!
+//! // Getting hungry re-arms the recurring goal; the quest stays retired
+//! let hungry = State::new().set("has_sword", false).set("fed", false).build();
+//! arbiter.update(&hungry);
+//! assert_eq!(arbiter.status("eat"), Some(GoalStatus::Active));
+//! assert_eq!(arbiter.status("find_sword"), Some(GoalStatus::Retired));
+//! ```
+
+use crate::goals::Goal;
+use crate::state::State;
+use std::fmt;
+
+/// What happens to a goal managed by a [`GoalArbiter`] once it is achieved.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Recurrence {
+    /// The goal waits while its requirements hold and becomes active again when
+    /// they stop holding
+    #[default]
+    Recurring,
+    /// The goal is retired for good
+    Once,
+}
+
+/// Where a goal managed by a [`GoalArbiter`] is in its lifecycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GoalStatus {
+    /// The goal is not achieved and is worth planning for
+    Active,
+    /// A recurring goal is achieved and waits for its requirements to stop holding
+    Satisfied,
+    /// A one-shot goal was achieved
+    Retired,
+    /// The goal was given up with [`GoalArbiter::abandon`]
+    Abandoned,
+}
+
+/// A change in a goal's lifecycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GoalEventKind {
+    /// The goal's requirements were met
+    Achieved,
+    /// A satisfied recurring goal became active again
+    Rearmed,
+    /// The goal was given up
+    Abandoned,
+}
+
+/// A change in the lifecycle of a named goal, reported by a [`GoalArbiter`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GoalEvent {
+    /// The name of the goal
+    pub goal: String,
+    /// What happened to the goal
+    pub kind: GoalEventKind,
+}
+
+impl fmt::Display for GoalEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            GoalEventKind::Achieved => "achieved",
+            GoalEventKind::Rearmed => "re-armed",
+            GoalEventKind::Abandoned => "abandoned",
+        };
+        write!(f, "{} {kind}", self.goal)
+    }
+}
+
+/// A callback run with a goal and the state in which its lifecycle changed.
+pub type GoalCallback = dyn FnMut(&Goal, &State) + Send;
+
+/// A goal managed by a [`GoalArbiter`].
+struct ManagedGoal {
+    /// The goal
+    goal: Goal,
+    /// What happens to the goal once achieved
+    recurrence: Recurrence,
+    /// Where the goal is in its lifecycle
+    status: GoalStatus,
+}
+
+/// Tracks the lifecycle of an agent's one-shot and recurring goals.
+#[derive(Default)]
+pub struct GoalArbiter {
+    /// The managed goals, in the order they were added
+    goals: Vec<ManagedGoal>,
+    /// Callbacks run when a goal is achieved
+    achieved: Vec<Box<GoalCallback>>,
+    /// Callbacks run when a goal is abandoned
+    abandoned: Vec<Box<GoalCallback>>,
+}
+
+impl GoalArbiter {
+    /// Creates an arbiter without goals.
+    pub fn new() -> Self {
+        GoalArbiter::default()
+    }
+
+    /// Adds an active goal, replacing any goal with the same name.
+    pub fn goal(mut self, goal: Goal, recurrence: Recurrence) -> Self {
+        self.add(goal, recurrence);
+        self
+    }
+
+    /// Adds an active goal that is retired once achieved.
+    pub fn once(self, goal: Goal) -> Self {
+        self.goal(goal, Recurrence::Once)
+    }
+
+    /// Adds an active goal that is re-armed whenever its requirements stop holding.
+    pub fn recurring(self, goal: Goal) -> Self {
+        self.goal(goal, Recurrence::Recurring)
+    }
+
+    /// Runs `callback` whenever a goal is achieved.
+    pub fn on_achieved(mut self, callback: impl FnMut(&Goal, &State) + Send + 'static) -> Self {
+        self.achieved.push(Box::new(callback));
+        self
+    }
+
+    /// Runs `callback` whenever a goal is abandoned.
+    pub fn on_abandoned(mut self, callback: impl FnMut(&Goal, &State) + Send + 'static) -> Self {
+        self.abandoned.push(Box::new(callback));
+        self
+    }
+
+    /// Adds an active goal, replacing any goal with the same name.
+    pub fn add(&mut self, goal: Goal, recurrence: Recurrence) {
+        let managed = ManagedGoal {
+            goal,
+            recurrence,
+            status: GoalStatus::Active,
+        };
+        match self
+            .goals
+            .iter_mut()
+            .find(|existing| existing.goal.name == managed.goal.name)
+        {
+            Some(existing) => *existing = managed,
+            None => self.goals.push(managed),
+        }
+    }
+
+    /// Removes the named goal without running callbacks. Returns the goal, if it
+    /// was managed.
+    pub fn remove(&mut self, name: &str) -> Option<Goal> {
+        let index = self
+            .goals
+            .iter()
+            .position(|managed| managed.goal.name == name)?;
+        Some(self.goals.remove(index).goal)
+    }
+
+    /// Returns where the named goal is in its lifecycle, if it is managed.
+    pub fn status(&self, name: &str) -> Option<GoalStatus> {
+        self.goals
+            .iter()
+            .find(|managed| managed.goal.name == name)
+            .map(|managed| managed.status)
+    }
+
+    /// Returns the active goals, in the order they were added, for handing to the
+    /// planner.
+    pub fn active_goals(&self) -> Vec<Goal> {
+        self.goals
+            .iter()
+            .filter(|managed| managed.status == GoalStatus::Active)
+            .map(|managed| managed.goal.clone())
+            .collect()
+    }
+
+    /// Returns the active goal with the highest priority, the first added among
+    /// equal priorities.
+    pub fn select(&self) -> Option<&Goal> {
+        self.goals
+            .iter()
+            .filter(|managed| managed.status == GoalStatus::Active)
+            .map(|managed| &managed.goal)
+            .rev()
+            .max_by_key(|goal| goal.priority)
+    }
+
+    /// Checks every goal against `state`: active goals whose requirements hold are
+    /// achieved, running the achievement callbacks, and satisfied recurring goals
+    /// whose requirements stopped holding are re-armed. Returns the changes, in
+    /// the order the goals were added.
+    pub fn update(&mut self, state: &State) -> Vec<GoalEvent> {
+        let mut events = Vec::new();
+        for managed in &mut self.goals {
+            let satisfied = managed.goal.is_satisfied(state);
+            let kind = match managed.status {
+                GoalStatus::Active if satisfied => {
+                    managed.status = match managed.recurrence {
+                        Recurrence::Recurring => GoalStatus::Satisfied,
+                        Recurrence::Once => GoalStatus::Retired,
+                    };
+                    for callback in &mut self.achieved {
+                        callback(&managed.goal, state);
+                    }
+                    GoalEventKind::Achieved
+                }
+                GoalStatus::Satisfied if !satisfied => {
+                    managed.status = GoalStatus::Active;
+                    GoalEventKind::Rearmed
+                }
+                _ => continue,
+            };
+            events.push(GoalEvent {
+                goal: managed.goal.name.clone(),
+                kind,
+            });
+        }
+        events
+    }
+
+    /// Gives up the named goal, running the abandonment callbacks with `state`.
+    /// Returns the event, or `None` if the goal is not managed or was already
+    /// retired or abandoned.
+    ///
+    /// Abandoned goals are not re-armed; [`GoalArbiter::rearm`] activates them again.
+    pub fn abandon(&mut self, name: &str, state: &State) -> Option<GoalEvent> {
+        let managed = self.goals.iter_mut().find(|managed| {
+            managed.goal.name == name
+                && matches!(managed.status, GoalStatus::Active | GoalStatus::Satisfied)
+        })?;
+        managed.status = GoalStatus::Abandoned;
+        for callback in &mut self.abandoned {
+            callback(&managed.goal, state);
+        }
+        Some(GoalEvent {
+            goal: managed.goal.name.clone(),
+            kind: GoalEventKind::Abandoned,
+        })
+    }
+
+    /// Makes the named goal active again, whatever its status. Returns false if
+    /// the goal is not managed.
+    pub fn rearm(&mut self, name: &str) -> bool {
+        match self
+            .goals
+            .iter_mut()
+            .find(|managed| managed.goal.name == name)
+        {
+            Some(managed) => {
+                managed.status = GoalStatus::Active;
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,163 @@
+//! Beam search planning.
+//!
+//! Beam search explores the state space one depth at a time and keeps only the
+//! best `width` states of each layer, ranked by path cost plus heuristic. It trades
+//! optimality and completeness for speed in domains with very high branching.
+//! Select it with [`SearchStrategy::Beam`](crate::planner::SearchStrategy).
+
+use crate::actions::Action;
+use crate::goals::Goal;
+use crate::observer::PlannerObserver;
+use crate::planner::{Plan, PlanStats, Planner, PlannerError, SearchBudget, plan_assumptions};
+use crate::state::State;
+use std::collections::HashMap;
+use std::mem::size_of;
+
+/// A state kept in the beam, with the path information needed to rebuild a plan.
+struct BeamNode {
+    /// The world state at this node
+    state: State,
+    /// Index of the node this one was reached from
+    parent: Option<usize>,
+    /// Index into the action slice of the action that produced this node
+    action: Option<usize>,
+    /// Cost of the path from the start to this node
+    g: f64,
+    /// Heuristic distance from this node to the goal
+    h: f64,
+}
+
+impl Planner {
+    /// Runs beam search and returns the cheapest plan found in the shallowest layer
+    /// that reaches the goal.
+    pub(crate) fn plan_beam(
+        &self,
+        initial_state: State,
+        goal: &Goal,
+        actions: &[Action],
+        width: usize,
+        observer: &mut dyn PlannerObserver,
+    ) -> Result<Plan, PlannerError> {
+        let width = width.max(1);
+        let mut stats = PlanStats::default();
+        let h = self.goal_heuristic(&initial_state, goal)?;
+        observer.on_heuristic(&initial_state, h);
+        let mut best_g: HashMap<State, f64> = HashMap::new();
+        best_g.insert(initial_state.clone(), 0.0);
+        stats.memory_bytes += beam_node_memory_bytes(&initial_state);
+        let mut nodes = vec![BeamNode {
+            state: initial_state,
+            parent: None,
+            action: None,
+            g: 0.0,
+            h,
+        }];
+        let mut layer = vec![0];
+
+        while !layer.is_empty() {
+            let mut reached = None;
+            for &index in &layer {
+                let node = &nodes[index];
+                let satisfied = goal.is_satisfied(&node.state);
+                observer.on_goal_test(&node.state, satisfied);
+                if satisfied && reached.is_none_or(|best: usize| node.g < nodes[best].g) {
+                    reached = Some(index);
+                }
+            }
+            if let Some(target) = reached {
+                return Ok(reconstruct(self, &nodes, goal, actions, target, stats));
+            }
+
+            let mut next = Vec::new();
+            for &index in &layer {
+                let state = nodes[index].state.clone();
+                let g = nodes[index].g;
+                stats.nodes_expanded += 1;
+                observer.on_expand_begin(&state);
+                let mut successors = 0;
+                for transition in self.expand(&state, actions) {
+                    observer.on_transition(&state, transition.action, &transition.state);
+                    stats.nodes_generated += 1;
+                    successors += 1;
+                    let child_g =
+                        g + transition.cost + goal.ordering_penalty(&state, &transition.state);
+                    if best_g
+                        .get(&transition.state)
+                        .is_some_and(|&known| child_g >= known)
+                    {
+                        continue;
+                    }
+                    let h = self.goal_heuristic(&transition.state, goal)?;
+                    observer.on_heuristic(&transition.state, h);
+                    stats.memory_bytes += beam_node_memory_bytes(&transition.state);
+                    best_g.insert(transition.state.clone(), child_g);
+                    next.push(nodes.len());
+                    nodes.push(BeamNode {
+                        state: transition.state,
+                        parent: Some(index),
+                        action: Some(transition.action_index),
+                        g: child_g,
+                        h,
+                    });
+                }
+                observer.on_expand_end(&state, successors);
+            }
+
+            // Keep only the most promising states of the new layer
+            next.sort_by(|a, b| {
+                let fa = nodes[*a].g + nodes[*a].h;
+                let fb = nodes[*b].g + nodes[*b].h;
+                fa.total_cmp(&fb)
+            });
+            next.truncate(width);
+            layer = next;
+
+            if let Some(limit_bytes) = self.config().max_memory_bytes
+                && stats.memory_bytes > limit_bytes
+            {
+                let best = (0..nodes.len())
+                    .min_by(|a, b| nodes[*a].h.total_cmp(&nodes[*b].h))
+                    .unwrap_or(0);
+                return Err(PlannerError::BudgetExceeded {
+                    budget: SearchBudget::Memory(limit_bytes),
+                    best_partial: Box::new(reconstruct(self, &nodes, goal, actions, best, stats)),
+                });
+            }
+        }
+
+        Err(PlannerError::NoPlanFound)
+    }
+}
+
+/// Approximate number of bytes kept for one discovered state.
+/// Each state is stored twice: in the node arena and as a key of the cost table.
+fn beam_node_memory_bytes(state: &State) -> usize {
+    2 * state.approx_memory_bytes() + size_of::<BeamNode>() + size_of::<f64>()
+}
+
+/// Rebuilds the plan leading to a node by following parent links.
+fn reconstruct(
+    planner: &Planner,
+    nodes: &[BeamNode],
+    goal: &Goal,
+    actions: &[Action],
+    target: usize,
+    stats: PlanStats,
+) -> Plan {
+    let mut path = Vec::new();
+    let mut current = Some(target);
+    while let Some(index) = current {
+        if let Some(action) = nodes[index].action {
+            path.push(actions[action].clone());
+        }
+        current = nodes[index].parent;
+    }
+    path.reverse();
+    Plan {
+        cost: nodes[target].g,
+        assumptions: plan_assumptions(&nodes[0].state, goal, &path),
+        actions: path,
+        stats,
+        currencies: planner.currency_changes(&nodes[0].state, &nodes[target].state),
+    }
+}
@@ -0,0 +1,292 @@
+//! Beliefs that are forgotten when sensors stop refreshing them.
+//!
+//! A [`BeliefState`] holds what an agent believes about the world, together with
+//! the tick each variable was last observed. Variables given a time to live are
+//! forgotten once that many ticks pass without a new observation, so plans stop
+//! relying on stale information such as an enemy position seen minutes ago.
+//!
+//! A forgotten variable is removed from the believed state. Actions can react to
+//! that with [`ActionBuilder::requires_absent`](crate::actions::ActionBuilder::requires_absent),
+//! e.g. a scouting action only worth doing while the enemy position is unknown.
+//! Variables forgotten with [`Forget::MarkUnknown`] are also listed by
+//! [`BeliefState::unknown`], which can be passed to
+//! [`Planner::plan_conformant`](crate::planner::Planner::plan_conformant) for booleans.
+//!
+//! Belief ticks are counted by the belief state itself with
+//! [`BeliefState::advance`]; they are independent of the
+//! [`time`](crate::time) variable.
+//!
+//! Noisy sensors report readings with a confidence through
+//! [`BeliefState::observe_noisy`], and a [`Fusion`] rule per variable merges
+//! them: booleans can accumulate evidence until their probability crosses a
+//! threshold, and other variables can keep the most confident recent reading
+//! while its confidence decays.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let mut belief = BeliefState::new(State::empty()).ttl("enemy_x", 3);
+//! belief.observe("enemy_x", 40);
+//!
+//! let scout = Action::new("scout")
+//!     .requires_absent("enemy_x")
+//!     .sets("enemy_x", 0)
+//!     .build();
+//! assert!(!scout.can_execute(belief.state()));
+//!
+//! // Without a fresh sighting the position is forgotten
+//! assert_eq!(belief.advance(3), vec!["enemy_x".to_string()]);
+//! assert!(scout.can_execute(belief.state()));
+//! ```
+
+use crate::state::{IntoStateVar, State, StateVar};
+use std::collections::{BTreeSet, HashMap};
+
+/// How noisy readings of a variable are merged into the belief; see
+/// [`BeliefState::observe_noisy`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fusion {
+    /// Keeps the latest reading unless the current one is more confident; the
+    /// confidence of the current reading halves every `half_life` ticks, and the
+    /// variable is forgotten once it falls below `floor`
+    Latest {
+        /// The number of ticks after which a reading's confidence has halved
+        half_life: f64,
+        /// The confidence below which the variable is forgotten
+        floor: f64,
+    },
+    /// Fuses boolean readings into the probability that the variable is true,
+    /// starting from even odds. The variable is believed true once the
+    /// probability reaches `threshold`, false once it falls to `1 - threshold`,
+    /// and is not believed in between
+    Probability {
+        /// The probability needed to believe a value, above 0.5
+        threshold: f64,
+    },
+}
+
+/// What happens to a variable when its time to live runs out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Forget {
+    /// The variable is removed from the believed state
+    #[default]
+    Remove,
+    /// The variable is removed and listed by [`BeliefState::unknown`] until observed again
+    MarkUnknown,
+}
+
+/// An agent's beliefs, with variables that expire when not observed for a while.
+#[derive(Clone, Debug, Default)]
+pub struct BeliefState {
+    /// The currently believed values
+    state: State,
+    /// Time to live in ticks and expiry behaviour per variable
+    ttls: HashMap<String, (i64, Forget)>,
+    /// The tick each variable was last observed
+    observed: HashMap<String, i64>,
+    /// Variables forgotten with [`Forget::MarkUnknown`] and not observed since
+    unknown: BTreeSet<String>,
+    /// The rule merging noisy readings per variable
+    fusion: HashMap<String, Fusion>,
+    /// The confidence of the believed reading, or the probability of true for
+    /// [`Fusion::Probability`] variables
+    confidence: HashMap<String, f64>,
+    /// The current tick
+    now: i64,
+}
+
+impl BeliefState {
+    /// Creates a belief state at tick 0, treating every variable of `state` as
+    /// observed at that tick.
+    pub fn new(state: State) -> Self {
+        let observed = state.vars.keys().map(|key| (key.clone(), 0)).collect();
+        BeliefState {
+            state,
+            ttls: HashMap::new(),
+            observed,
+            unknown: BTreeSet::new(),
+            fusion: HashMap::new(),
+            confidence: HashMap::new(),
+            now: 0,
+        }
+    }
+
+    /// Removes a variable once `ticks` ticks pass without it being observed.
+    pub fn ttl(self, key: &str, ticks: i64) -> Self {
+        self.ttl_with(key, ticks, Forget::Remove)
+    }
+
+    /// Forgets a variable in the given way once `ticks` ticks pass without it
+    /// being observed. Variables without a time to live are never forgotten.
+    pub fn ttl_with(mut self, key: &str, ticks: i64, forget: Forget) -> Self {
+        self.ttls.insert(key.to_string(), (ticks.max(0), forget));
+        self
+    }
+
+    /// Merges noisy readings of the variable with the given rule.
+    pub fn fuse(mut self, key: &str, fusion: Fusion) -> Self {
+        self.fusion.insert(key.to_string(), fusion);
+        self
+    }
+
+    /// Records a sensor reading, setting the variable and restarting its time to live.
+    pub fn observe<T: IntoStateVar>(&mut self, key: &str, value: T) {
+        self.state.set(key, value);
+        self.observed.insert(key.to_string(), self.now);
+        self.unknown.remove(key);
+        self.confidence.remove(key);
+    }
+
+    /// Records a reading that is correct with the given confidence, from 0.0 to
+    /// 1.0, merging it with earlier readings by the variable's [`Fusion`] rule.
+    /// Variables without a rule take every reading, like [`BeliefState::observe`].
+    ///
+    /// Confidences are clamped to between 0.01 and 0.99 for probability-fused
+    /// variables, so a single reading can never make a value certain. Non-boolean
+    /// readings of such variables are ignored. Every reading restarts the
+    /// variable's time to live.
+    ///
+    /// ```
+    /// use goap::prelude::*;
+    ///
+    /// let mut belief = BeliefState::new(State::empty())
+    ///     .fuse("door_open", Fusion::Probability { threshold: 0.9 });
+    ///
+    /// // One 80% reading is not enough to believe the door is open
+    /// belief.observe_noisy("door_open", true, 0.8);
+    /// assert_eq!(belief.state().get::<bool>("door_open"), None);
+    ///
+    /// belief.observe_noisy("door_open", true, 0.8);
+    /// assert_eq!(belief.state().get::<bool>("door_open"), Some(true));
+    /// assert!(belief.confidence("door_open").unwrap() > 0.9);
+    /// ```
+    pub fn observe_noisy<T: IntoStateVar>(&mut self, key: &str, value: T, confidence: f64) {
+        let value = value.into_state_var();
+        let confidence = if confidence.is_nan() {
+            0.0
+        } else {
+            confidence.clamp(0.0, 1.0)
+        };
+        match self.fusion.get(key).copied() {
+            None => {
+                self.observe(key, value);
+                self.confidence.insert(key.to_string(), confidence);
+            }
+            Some(Fusion::Latest { .. }) => {
+                let current = self.confidence.get(key).copied().unwrap_or(0.0);
+                if !self.state.vars.contains_key(key) || confidence >= current {
+                    self.observe(key, value);
+                    self.confidence.insert(key.to_string(), confidence);
+                } else {
+                    self.observed.insert(key.to_string(), self.now);
+                }
+            }
+            Some(Fusion::Probability { threshold }) => {
+                let StateVar::Bool(reading) = value else {
+                    return;
+                };
+                let confidence = confidence.clamp(0.01, 0.99);
+                let prior = self.confidence.get(key).copied().unwrap_or(0.5);
+                let likelihood = if reading {
+                    confidence
+                } else {
+                    1.0 - confidence
+                };
+                let probability =
+                    prior * likelihood / (prior * likelihood + (1.0 - prior) * (1.0 - likelihood));
+                self.observed.insert(key.to_string(), self.now);
+                self.unknown.remove(key);
+                self.confidence.insert(key.to_string(), probability);
+                if probability >= threshold {
+                    self.state.set(key, true);
+                } else if probability <= 1.0 - threshold {
+                    self.state.set(key, false);
+                } else {
+                    self.state.vars.remove(key);
+                }
+            }
+        }
+    }
+
+    /// Returns the confidence in the variable's believed value, decayed for
+    /// [`Fusion::Latest`] variables, or the probability that the variable is true
+    /// for [`Fusion::Probability`] variables. None if the variable has no noisy
+    /// reading since it was last observed exactly or forgotten.
+    pub fn confidence(&self, key: &str) -> Option<f64> {
+        self.confidence.get(key).copied()
+    }
+
+    /// Advances the current tick, decays the confidence of [`Fusion::Latest`]
+    /// readings, and forgets every variable whose time to live has run out or
+    /// whose confidence fell below its floor. Negative values are ignored.
+    /// Returns the forgotten variables, sorted.
+    pub fn advance(&mut self, ticks: i64) -> Vec<String> {
+        let ticks = ticks.max(0);
+        self.now = self.now.saturating_add(ticks);
+        let mut faded = Vec::new();
+        for (key, confidence) in &mut self.confidence {
+            if let Some(Fusion::Latest { half_life, floor }) = self.fusion.get(key) {
+                if *half_life > 0.0 {
+                    *confidence *= 0.5f64.powf(ticks as f64 / half_life);
+                }
+                if *confidence < *floor && self.state.vars.contains_key(key) {
+                    faded.push(key.clone());
+                }
+            }
+        }
+        let mut forgotten: Vec<String> = self
+            .observed
+            .iter()
+            .filter(|(key, observed)| {
+                self.ttls
+                    .get(*key)
+                    .is_some_and(|(ttl, _)| self.now - **observed >= *ttl)
+            })
+            .map(|(key, _)| key.clone())
+            .chain(faded)
+            .collect();
+        forgotten.sort();
+        forgotten.dedup();
+        for key in &forgotten {
+            self.state.vars.remove(key);
+            self.observed.remove(key);
+            self.confidence.remove(key);
+            if self
+                .ttls
+                .get(key)
+                .is_some_and(|(_, forget)| *forget == Forget::MarkUnknown)
+            {
+                self.unknown.insert(key.clone());
+            }
+        }
+        forgotten
+    }
+
+    /// Returns the current tick.
+    pub fn now(&self) -> i64 {
+        self.now
+    }
+
+    /// Returns the believed state, holding only variables that have not been forgotten.
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    /// Returns the number of ticks since the variable was last observed, or None
+    /// if it is not believed.
+    pub fn age(&self, key: &str) -> Option<i64> {
+        self.observed.get(key).map(|observed| self.now - observed)
+    }
+
+    /// Returns true if the variable was forgotten with [`Forget::MarkUnknown`] and
+    /// has not been observed since.
+    pub fn is_unknown(&self, key: &str) -> bool {
+        self.unknown.contains(key)
+    }
+
+    /// Returns the variables forgotten with [`Forget::MarkUnknown`] and not
+    /// observed since, sorted.
+    pub fn unknown(&self) -> Vec<&str> {
+        self.unknown.iter().map(String::as_str).collect()
+    }
+}
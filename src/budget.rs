@@ -0,0 +1,251 @@
+//! Search budget reports and per-agent budgets tuned from them.
+//!
+//! A fixed [`PlannerConfig::max_nodes_expanded`] is rarely right for every agent:
+//! some agents face hard problems and keep running out of budget, while others
+//! find their plans after a handful of expansions. After a planning call,
+//! [`Planner::budget_report`] tells whether a budget stopped the search and
+//! estimates how many more expansions it needed. An [`AutoBudget`] records these
+//! reports per agent and adapts each agent's node budget: agents that run out get
+//! more, agents that finish early get less.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let actions = vec![Action::new("work").adds("gold", 5).build()];
+//! let rich = Goal::new("rich").requires("gold", 40).build();
+//! let start = state! { gold: 0 };
+//! let planner = Planner::with_config(PlannerConfig::new().max_nodes_expanded(2));
+//!
+//! let result = planner.plan(&start, &rich, &actions);
+//! let report = planner.budget_report(&start, &rich, &result);
+//! assert!(report.is_binding());
+//! assert!(report.additional_nodes.unwrap() > 0);
+//!
+//! // The agent's budget grows until its plan fits
+//! let mut budgets = AutoBudget::new(2);
+//! let plan = (0..5).find_map(|_| budgets.plan("miner", &planner, &start, &rich, &actions).ok());
+//! assert_eq!(plan.unwrap().actions.len(), 8);
+//! assert!(budgets.budget(&"miner") > 2);
+//! ```
+
+use crate::actions::Action;
+use crate::goals::Goal;
+use crate::planner::{Plan, Planner, PlannerConfig, PlannerError, SearchBudget};
+use crate::state::State;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// How a planning call used its search budget, from [`Planner::budget_report`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BudgetReport {
+    /// The limit that stopped the search, if one did
+    pub binding: Option<SearchBudget>,
+    /// The number of nodes the search expanded
+    pub nodes_expanded: usize,
+    /// The configured [`PlannerConfig::max_nodes_expanded`]
+    pub node_budget: Option<usize>,
+    /// The estimated number of further expansions needed to find a plan: zero if a
+    /// plan was found, `None` if the search failed without hitting a limit
+    pub additional_nodes: Option<usize>,
+}
+
+impl BudgetReport {
+    /// Returns true if a limit stopped the search.
+    pub fn is_binding(&self) -> bool {
+        self.binding.is_some()
+    }
+
+    /// Returns the share of the node budget the search used, from 0.0 to 1.0, or
+    /// `None` without a node budget.
+    pub fn utilization(&self) -> Option<f64> {
+        self.node_budget
+            .map(|budget| (self.nodes_expanded as f64 / budget.max(1) as f64).min(1.0))
+    }
+}
+
+impl Planner {
+    /// Reports how the planning call that produced `result`, searching for `goal`
+    /// from `initial_state`, used its budget.
+    ///
+    /// When a limit stopped the search, the further expansions needed are
+    /// extrapolated from the progress of the best partial plan: the heuristic
+    /// distance to the goal it covered with the nodes expanded so far. A search
+    /// that made no progress is assumed to need as many nodes again.
+    pub fn budget_report(
+        &self,
+        initial_state: &State,
+        goal: &Goal,
+        result: &Result<Plan, PlannerError>,
+    ) -> BudgetReport {
+        let node_budget = self.config().max_nodes_expanded;
+        let (binding, partial) = match result {
+            Ok(plan) => {
+                return BudgetReport {
+                    binding: None,
+                    nodes_expanded: plan.stats.nodes_expanded,
+                    node_budget,
+                    additional_nodes: Some(0),
+                };
+            }
+            Err(PlannerError::BudgetExceeded {
+                budget,
+                best_partial,
+            }) => (*budget, best_partial),
+            Err(_) => {
+                return BudgetReport {
+                    node_budget,
+                    ..BudgetReport::default()
+                };
+            }
+        };
+
+        let goal = goal.resolve(initial_state);
+        let reached = partial
+            .actions
+            .iter()
+            .fold(initial_state.clone(), |state, action| {
+                action.apply_effect(&state)
+            });
+        let nodes = partial.stats.nodes_expanded.max(1);
+        let additional = match (
+            self.estimate(initial_state, &goal),
+            self.estimate(&reached, &goal),
+        ) {
+            (Ok(start), Ok(remaining)) if start > remaining => {
+                (nodes as f64 * remaining / (start - remaining)).ceil() as usize
+            }
+            _ => nodes,
+        };
+        BudgetReport {
+            binding: Some(binding),
+            nodes_expanded: partial.stats.nodes_expanded,
+            node_budget,
+            additional_nodes: Some(additional.max(1)),
+        }
+    }
+}
+
+/// Per-agent node budgets adapted from [`BudgetReport`]s.
+///
+/// Every agent starts with the initial budget. When a node budget stops an
+/// agent's search, its budget grows by the growth factor, or to the nodes used
+/// plus the estimated further nodes if that is more. When the agent finds a plan,
+/// its budget moves halfway towards the nodes used times the headroom factor.
+/// Budgets stay within the bounds.
+#[derive(Clone, Debug)]
+pub struct AutoBudget<K> {
+    /// The budget of agents without recorded reports
+    initial: usize,
+    /// The smallest budget
+    min: usize,
+    /// The largest budget
+    max: usize,
+    /// The factor budgets grow by when they stop a search
+    growth: f64,
+    /// The multiple of the nodes used that budgets shrink towards
+    headroom: f64,
+    /// The budget of each agent with recorded reports
+    budgets: HashMap<K, usize>,
+}
+
+impl<K: Eq + Hash> AutoBudget<K> {
+    /// Creates budgets starting at `initial` node expansions per agent, bounded
+    /// by 1 and 16 times `initial`, growing by a factor of 2 and shrinking towards
+    /// twice the nodes used.
+    pub fn new(initial: usize) -> Self {
+        let initial = initial.max(1);
+        AutoBudget {
+            initial,
+            min: 1,
+            max: initial.saturating_mul(16),
+            growth: 2.0,
+            headroom: 2.0,
+            budgets: HashMap::new(),
+        }
+    }
+
+    /// Sets the smallest and largest budget.
+    pub fn bounds(mut self, min: usize, max: usize) -> Self {
+        self.min = min.max(1);
+        self.max = max.max(self.min);
+        self
+    }
+
+    /// Sets the factor budgets grow by when they stop a search (at least 1.0).
+    pub fn growth(mut self, factor: f64) -> Self {
+        self.growth = factor.max(1.0);
+        self
+    }
+
+    /// Sets the multiple of the nodes used that budgets of successful agents shrink
+    /// towards (at least 1.0).
+    pub fn headroom(mut self, factor: f64) -> Self {
+        self.headroom = factor.max(1.0);
+        self
+    }
+
+    /// Returns the agent's current node budget.
+    pub fn budget(&self, agent: &K) -> usize {
+        self.budgets
+            .get(agent)
+            .copied()
+            .unwrap_or(self.initial)
+            .clamp(self.min, self.max)
+    }
+
+    /// Returns a copy of `base` limited to the agent's node budget.
+    pub fn config(&self, agent: &K, base: &PlannerConfig) -> PlannerConfig {
+        base.clone().max_nodes_expanded(self.budget(agent))
+    }
+
+    /// Adapts the agent's budget to a report of its latest search and returns the
+    /// new budget. Reports of searches stopped by other limits, or failing without
+    /// hitting one, leave the budget unchanged.
+    pub fn record(&mut self, agent: K, report: &BudgetReport) -> usize {
+        let current = self.budget(&agent);
+        let next = match (report.binding, report.additional_nodes) {
+            (Some(SearchBudget::NodesExpanded(_)), additional) => {
+                let needed = report
+                    .nodes_expanded
+                    .saturating_add(additional.unwrap_or(0));
+                ((current as f64 * self.growth).ceil() as usize).max(needed)
+            }
+            (None, Some(0)) => {
+                let target = (report.nodes_expanded as f64 * self.headroom).ceil() as usize;
+                if target < current {
+                    current - (current - target) / 2
+                } else {
+                    current
+                }
+            }
+            _ => current,
+        };
+        let next = next.clamp(self.min, self.max);
+        self.budgets.insert(agent, next);
+        next
+    }
+
+    /// Plans for the agent with its node budget in place of the planner's, and
+    /// records how the search used it.
+    pub fn plan<'s>(
+        &mut self,
+        agent: K,
+        planner: &Planner,
+        initial_state: impl Into<Cow<'s, State>>,
+        goal: &Goal,
+        actions: &[Action],
+    ) -> Result<Plan, PlannerError> {
+        let initial_state = initial_state.into();
+        let budgeted = Planner::with_config(self.config(&agent, planner.config()));
+        let result = budgeted.plan(initial_state.as_ref(), goal, actions);
+        let report = budgeted.budget_report(&initial_state, goal, &result);
+        self.record(agent, &report);
+        result
+    }
+
+    /// Forgets the agent's budget, so it starts over from the initial budget.
+    pub fn forget(&mut self, agent: &K) {
+        self.budgets.remove(agent);
+    }
+}
@@ -0,0 +1,209 @@
+//! Calibrating action costs from observed executions.
+//!
+//! Hand-tuned costs drift from reality as a game changes. After executing plans,
+//! record how long each action actually took and whether it succeeded in a
+//! [`PlanReport`], and feed the reports to a [`CostCalibrator`]. The calibrator
+//! keeps an exponentially weighted average of each action's duration and success
+//! rate, and suggests as cost the expected time to complete the action: the
+//! average duration divided by the success rate, since failed attempts are
+//! retried. Suggestions can be reviewed with [`CostCalibrator::suggestions`] or
+//! written into the actions with [`CostCalibrator::apply`].
+//!
+//! Durations are in cost units; scale them before recording if costs use another
+//! unit than the one durations are measured in.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let mut actions = vec![Action::new("chop_wood").cost(1.0).build()];
+//! let mut calibrator = CostCalibrator::new(0.5);
+//!
+//! let report = PlanReport::new().step("chop_wood", 3.0, true).step("chop_wood", 5.0, true);
+//! calibrator.ingest(&report);
+//! assert_eq!(calibrator.suggested_cost("chop_wood"), Some(4.0));
+//!
+//! assert_eq!(calibrator.apply(&mut actions), 1);
+//! assert_eq!(actions[0].cost, 4.0);
+//! ```
+
+use crate::actions::Action;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The observed execution of one plan step.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StepReport {
+    /// The name of the action
+    pub action: String,
+    /// How long the action took, in cost units
+    pub duration: f64,
+    /// Whether the action achieved its effects
+    pub succeeded: bool,
+}
+
+/// The observed executions of a plan's steps, in order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlanReport {
+    /// The executed steps
+    pub steps: Vec<StepReport>,
+}
+
+impl PlanReport {
+    /// Creates an empty report.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an executed step to the report.
+    pub fn step(mut self, action: &str, duration: f64, succeeded: bool) -> Self {
+        self.steps.push(StepReport {
+            action: action.to_string(),
+            duration,
+            succeeded,
+        });
+        self
+    }
+}
+
+/// The running averages observed for one action.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CostEstimate {
+    /// The weighted average duration of an attempt
+    pub duration: f64,
+    /// The weighted average share of attempts that succeeded
+    pub success_rate: f64,
+    /// The number of attempts observed
+    pub samples: usize,
+}
+
+/// A suggested cost change for an action.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CostSuggestion {
+    /// The name of the action
+    pub action: String,
+    /// The action's current cost
+    pub current: f64,
+    /// The suggested cost
+    pub suggested: f64,
+}
+
+impl fmt::Display for CostSuggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {:.2} -> {:.2}",
+            self.action, self.current, self.suggested
+        )
+    }
+}
+
+/// Learns action costs from observed executions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CostCalibrator {
+    /// Weight of each new observation, between 0 and 1
+    smoothing: f64,
+    /// Attempts needed before a cost is suggested
+    min_samples: usize,
+    /// Averages per action name
+    estimates: BTreeMap<String, CostEstimate>,
+}
+
+impl Default for CostCalibrator {
+    fn default() -> Self {
+        Self::new(0.2)
+    }
+}
+
+impl CostCalibrator {
+    /// Creates a calibrator giving each new observation the weight `smoothing`,
+    /// clamped between 0 and 1: higher values follow recent executions closely,
+    /// lower values average over a longer history. The first observation of an
+    /// action sets its averages directly.
+    pub fn new(smoothing: f64) -> Self {
+        CostCalibrator {
+            smoothing: smoothing.clamp(0.0, 1.0),
+            min_samples: 1,
+            estimates: BTreeMap::new(),
+        }
+    }
+
+    /// Only suggests a cost for actions observed at least `samples` times.
+    pub fn min_samples(mut self, samples: usize) -> Self {
+        self.min_samples = samples.max(1);
+        self
+    }
+
+    /// Records one attempt of an action. Durations that are negative or not
+    /// finite are ignored.
+    pub fn record(&mut self, action: &str, duration: f64, succeeded: bool) {
+        if !duration.is_finite() || duration < 0.0 {
+            return;
+        }
+        let success = if succeeded { 1.0 } else { 0.0 };
+        let estimate = self.estimates.entry(action.to_string()).or_default();
+        if estimate.samples == 0 {
+            estimate.duration = duration;
+            estimate.success_rate = success;
+        } else {
+            let weight = self.smoothing;
+            estimate.duration += weight * (duration - estimate.duration);
+            estimate.success_rate += weight * (success - estimate.success_rate);
+        }
+        estimate.samples += 1;
+    }
+
+    /// Records every step of a report.
+    pub fn ingest(&mut self, report: &PlanReport) {
+        for step in &report.steps {
+            self.record(&step.action, step.duration, step.succeeded);
+        }
+    }
+
+    /// Returns the averages observed for an action, if it was recorded.
+    pub fn estimate(&self, action: &str) -> Option<&CostEstimate> {
+        self.estimates.get(action)
+    }
+
+    /// Returns the suggested cost of an action: its average duration divided by
+    /// its success rate. Returns None until the action has been observed
+    /// [`min_samples`](CostCalibrator::min_samples) times, or while no attempt
+    /// has succeeded recently enough to estimate a success rate.
+    pub fn suggested_cost(&self, action: &str) -> Option<f64> {
+        let estimate = self.estimates.get(action)?;
+        if estimate.samples < self.min_samples || estimate.success_rate <= 0.0 {
+            return None;
+        }
+        Some(estimate.duration / estimate.success_rate)
+    }
+
+    /// Returns the suggested changes for the given actions, in order, leaving out
+    /// actions without a suggestion or whose cost already matches it.
+    pub fn suggestions(&self, actions: &[Action]) -> Vec<CostSuggestion> {
+        actions
+            .iter()
+            .filter_map(|action| {
+                let suggested = self.suggested_cost(&action.name)?;
+                (suggested != action.cost).then(|| CostSuggestion {
+                    action: action.name.clone(),
+                    current: action.cost,
+                    suggested,
+                })
+            })
+            .collect()
+    }
+
+    /// Sets the cost of every action with a suggestion to the suggested cost.
+    /// Returns the number of actions whose cost changed.
+    pub fn apply(&self, actions: &mut [Action]) -> usize {
+        let mut changed = 0;
+        for action in actions {
+            if let Some(suggested) = self.suggested_cost(&action.name)
+                && suggested != action.cost
+            {
+                action.cost = suggested;
+                changed += 1;
+            }
+        }
+        changed
+    }
+}
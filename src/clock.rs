@@ -0,0 +1,67 @@
+//! Time sources for planning time limits.
+//!
+//! [`PlannerConfig::max_duration`](crate::planner::PlannerConfig::max_duration)
+//! stops a search once it has run for too long. The time is read from a
+//! [`Clock`], which defaults to [`StdClock`] backed by `std::time::Instant`.
+//! Targets without `Instant`, such as WASM or embedded devices, can inject
+//! their own clock driven by a tick counter with
+//! [`PlannerConfig::clock`](crate::planner::PlannerConfig::clock).
+//!
+//! ```
+//! use goap::prelude::*;
+//! use std::sync::Arc;
+//! use std::sync::atomic::{AtomicU64, Ordering};
+//! use std::time::Duration;
+//!
+//! /// A clock advanced by the game loop, one tick per millisecond.
+//! #[derive(Debug, Default)]
+//! struct TickClock(AtomicU64);
+//!
+//! impl Clock for TickClock {
+//!     fn now(&self) -> Duration {
+//!         Duration::from_millis(self.0.load(Ordering::Relaxed))
+//!     }
+//! }
+//!
+//! let config = PlannerConfig::new()
+//!     .max_duration(Duration::from_millis(5))
+//!     .clock(Arc::new(TickClock::default()));
+//! ```
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// A monotonic source of time.
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Returns the time elapsed since an arbitrary fixed starting point.
+    /// Successive calls must never return a smaller value.
+    fn now(&self) -> Duration;
+}
+
+/// A clock backed by `std::time::Instant`.
+#[derive(Clone, Copy, Debug)]
+pub struct StdClock {
+    /// The instant the clock was created, which `now` measures from
+    start: Instant,
+}
+
+impl StdClock {
+    /// Creates a clock measuring from the current instant.
+    pub fn new() -> Self {
+        StdClock {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for StdClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for StdClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
@@ -0,0 +1,314 @@
+//! Grouping goals by similar requirements to warm-start planning.
+//!
+//! Most agents pursue a handful of goals that care about the same few variables.
+//! [`Planner::cluster_goals`] groups goals whose requirements mention similar
+//! variables and, once per cluster, works out which actions can matter for them
+//! and the cheapest action changing each variable. Given to the planner with
+//! [`PlannerConfig::goal_clusters`](crate::planner::PlannerConfig::goal_clusters),
+//! the clusters let [`Planner::plan`] search only the relevant actions and
+//! reject goals needing a variable no action changes without searching at all.
+//!
+//! Clusters are plain data that can be built once at load time, inspected
+//! through [`GoalClusters::clusters`] or its `Display` output, and rebuilt with
+//! [`GoalClusters::new`] from stored parts. They are looked up by goal name, so
+//! rebuild them whenever the goals or actions change.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let goals = [
+//!     Goal::new("fed").requires("fed", true).build(),
+//!     Goal::new("rested").requires("energy", 10).build(),
+//! ];
+//! let actions = [
+//!     Action::new("eat").sets("fed", true).build(),
+//!     Action::new("sleep").adds("energy", 10).build(),
+//!     Action::new("dance").sets("happy", true).build(),
+//! ];
+//!
+//! let clusters = Planner::new().cluster_goals(&goals, &actions, 0.5);
+//! assert_eq!(clusters.len(), 2);
+//! let fed = clusters.cluster_for("fed").unwrap();
+//! assert!(fed.actions.contains("eat") && !fed.actions.contains("dance"));
+//!
+//! let planner = Planner::with_config(PlannerConfig::new().goal_clusters(clusters));
+//! let state = State::new().set("fed", false).set("energy", 0).build();
+//! assert_eq!(planner.plan(state, &goals[0], &actions).unwrap().actions.len(), 1);
+//! ```
+
+use crate::actions::Action;
+use crate::goals::Goal;
+use crate::planner::{Planner, PlannerError};
+use crate::state::{State, value_satisfies};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// A group of goals with similar requirements and what planning for them needs.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GoalCluster {
+    /// The names of the goals in the cluster
+    pub goals: Vec<String>,
+    /// The variables the goals' requirements mention
+    pub keys: BTreeSet<String>,
+    /// The names of the actions that can contribute to reaching the goals
+    pub actions: BTreeSet<String>,
+    /// The cost of the cheapest relevant action changing each variable
+    pub cheapest: BTreeMap<String, f64>,
+}
+
+impl GoalCluster {
+    /// Returns true if the goal requires a value the state does not satisfy on a
+    /// variable no relevant action changes, so no plan can reach it.
+    fn is_unreachable(&self, state: &State, goal: &Goal) -> bool {
+        let required = goal.desired_state.vars.iter().any(|(key, required)| {
+            !self.cheapest.contains_key(key)
+                && !state
+                    .vars
+                    .get(key)
+                    .is_some_and(|current| value_satisfies(current, required))
+        });
+        let excluded = goal.excluded.vars.iter().any(|(key, excluded)| {
+            !self.cheapest.contains_key(key) && state.vars.get(key) == Some(excluded)
+        });
+        let conditions = goal.conditions.iter().any(|(key, condition)| {
+            !self.cheapest.contains_key(key) && !condition.holds(state.vars.get(key))
+        });
+        required || excluded || conditions
+    }
+}
+
+/// Goal clusters built by [`Planner::cluster_goals`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GoalClusters {
+    /// The clusters, in the order their first goal was given
+    clusters: Vec<GoalCluster>,
+}
+
+impl GoalClusters {
+    /// Creates clusters from parts, e.g. clusters stored by an asset pipeline.
+    pub fn new(clusters: Vec<GoalCluster>) -> Self {
+        GoalClusters { clusters }
+    }
+
+    /// Returns the clusters.
+    pub fn clusters(&self) -> &[GoalCluster] {
+        &self.clusters
+    }
+
+    /// Returns the cluster containing the named goal, if any.
+    pub fn cluster_for(&self, goal: &str) -> Option<&GoalCluster> {
+        self.clusters
+            .iter()
+            .find(|cluster| cluster.goals.iter().any(|name| name == goal))
+    }
+
+    /// Returns the number of clusters.
+    pub fn len(&self) -> usize {
+        self.clusters.len()
+    }
+
+    /// Returns true if there are no clusters.
+    pub fn is_empty(&self) -> bool {
+        self.clusters.is_empty()
+    }
+}
+
+impl fmt::Display for GoalClusters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, cluster) in self.clusters.iter().enumerate() {
+            writeln!(f, "Cluster {}: {}", index + 1, cluster.goals.join(", "))?;
+            let keys: Vec<&str> = cluster.keys.iter().map(String::as_str).collect();
+            writeln!(f, "  Keys: {}", keys.join(", "))?;
+            let actions: Vec<&str> = cluster.actions.iter().map(String::as_str).collect();
+            writeln!(f, "  Actions: {}", actions.join(", "))?;
+            for (key, cost) in &cluster.cheapest {
+                writeln!(f, "  Cheapest {key}: {cost:.1}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the variables a goal's requirements mention.
+pub(crate) fn goal_keys(goal: &Goal) -> BTreeSet<String> {
+    goal.desired_state
+        .vars
+        .keys()
+        .chain(goal.excluded.vars.keys())
+        .chain(goal.relative.vars.keys())
+        .chain(goal.conditions.iter().map(|(key, _)| key))
+        .cloned()
+        .collect()
+}
+
+/// Returns the Jaccard similarity of two sets of variables: the share of
+/// variables mentioned by both. Two empty sets are identical.
+fn similarity(a: &BTreeSet<String>, b: &BTreeSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// Returns the similarity of two goals' requirements, from 0.0 when they share
+/// no variable to 1.0 when they mention the same variables.
+pub fn goal_similarity(a: &Goal, b: &Goal) -> f64 {
+    similarity(&goal_keys(a), &goal_keys(b))
+}
+
+/// Returns the variables an action may change.
+pub(crate) fn changed_keys(action: &Action) -> impl Iterator<Item = &String> {
+    action
+        .effects
+        .keys()
+        .chain(action.dynamic_effects.keys())
+        .chain(action.failure_effects.keys())
+}
+
+/// Returns the variables an action's preconditions and postconditions read.
+pub(crate) fn read_keys(action: &Action) -> Vec<&str> {
+    let mut keys: Vec<&str> = action
+        .preconditions
+        .vars
+        .keys()
+        .map(String::as_str)
+        .collect();
+    for comparison in action
+        .expr_preconditions
+        .iter()
+        .chain(&action.postconditions)
+    {
+        keys.extend(comparison.lhs.variables());
+        keys.extend(comparison.rhs.variables());
+    }
+    for group in &action.any_preconditions {
+        keys.extend(group.iter().map(|(key, _)| key.as_str()));
+    }
+    keys.extend(action.absent_preconditions.iter().map(String::as_str));
+    keys.extend(action.conditions.iter().map(|(key, _)| key.as_str()));
+    keys
+}
+
+/// Computes the relevant actions and cheapest costs for a cluster's variables.
+///
+/// An action is relevant if it changes a variable the goals mention or one that
+/// a relevant action's preconditions or postconditions read. The inputs of
+/// dynamic effects are not known, so a relevant action with dynamic effects makes
+/// every action relevant.
+fn relevance(
+    keys: &BTreeSet<String>,
+    actions: &[Action],
+) -> (BTreeSet<String>, BTreeMap<String, f64>) {
+    let mut relevant_keys: BTreeSet<&str> = keys.iter().map(String::as_str).collect();
+    let mut relevant = vec![false; actions.len()];
+    let mut opaque = false;
+    loop {
+        let mut grew = false;
+        for (index, action) in actions.iter().enumerate() {
+            if relevant[index]
+                || !changed_keys(action).any(|key| relevant_keys.contains(key.as_str()))
+            {
+                continue;
+            }
+            relevant[index] = true;
+            grew = true;
+            opaque |= !action.dynamic_effects.is_empty();
+            relevant_keys.extend(read_keys(action));
+        }
+        if !grew {
+            break;
+        }
+    }
+    if opaque {
+        relevant.fill(true);
+    }
+
+    let mut names = BTreeSet::new();
+    let mut cheapest: BTreeMap<String, f64> = BTreeMap::new();
+    for (action, _) in actions
+        .iter()
+        .zip(&relevant)
+        .filter(|(_, relevant)| **relevant)
+    {
+        names.insert(action.name.clone());
+        for key in changed_keys(action) {
+            let cost = cheapest.entry(key.clone()).or_insert(action.cost);
+            *cost = cost.min(action.cost);
+        }
+    }
+    (names, cheapest)
+}
+
+impl Planner {
+    /// Groups goals whose requirements mention similar variables and precomputes
+    /// the relevant actions and cheapest costs for each group.
+    ///
+    /// Goals are taken in order and join the first cluster whose variables are at
+    /// least `min_similarity` similar to theirs (see [`goal_similarity`]), or
+    /// start a new cluster. A `min_similarity` of 0.0 puts every goal
+    /// in one cluster; 1.0 only groups goals mentioning the same variables.
+    /// Actions are prepared with this planner's configuration, e.g. leaving out
+    /// excluded tags, so use the same configuration when planning.
+    pub fn cluster_goals(
+        &self,
+        goals: &[Goal],
+        actions: &[Action],
+        min_similarity: f64,
+    ) -> GoalClusters {
+        let mut clusters: Vec<GoalCluster> = Vec::new();
+        for goal in goals {
+            let keys = goal_keys(goal);
+            match clusters
+                .iter_mut()
+                .find(|cluster| similarity(&cluster.keys, &keys) >= min_similarity)
+            {
+                Some(cluster) => {
+                    cluster.goals.push(goal.name.clone());
+                    cluster.keys.extend(keys);
+                }
+                None => clusters.push(GoalCluster {
+                    goals: vec![goal.name.clone()],
+                    keys,
+                    ..GoalCluster::default()
+                }),
+            }
+        }
+
+        let actions = self.prepare_actions(actions);
+        for cluster in &mut clusters {
+            (cluster.actions, cluster.cheapest) = relevance(&cluster.keys, &actions);
+        }
+        GoalClusters { clusters }
+    }
+
+    /// Narrows prepared actions to the relevant ones of the goal's cluster, if the
+    /// configuration has clusters containing the goal. Returns `NoPlanFound` when
+    /// the cluster shows that the goal cannot be reached from the state.
+    pub(crate) fn warm_start<'b>(
+        &self,
+        initial_state: &State,
+        goal: &Goal,
+        actions: Cow<'b, [Action]>,
+    ) -> Result<Cow<'b, [Action]>, PlannerError> {
+        let Some(cluster) = self
+            .config()
+            .goal_clusters
+            .as_ref()
+            .and_then(|clusters| clusters.cluster_for(&goal.name))
+        else {
+            return Ok(actions);
+        };
+        if cluster.is_unreachable(initial_state, goal) {
+            return Err(PlannerError::NoPlanFound);
+        }
+        Ok(Cow::Owned(
+            actions
+                .iter()
+                .filter(|action| cluster.actions.contains(&action.name))
+                .cloned()
+                .collect(),
+        ))
+    }
+}
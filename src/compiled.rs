@@ -0,0 +1,643 @@
+//! Domains compiled to a dense numeric form for fast repeated planning.
+//!
+//! [`State`] stores variables in a map keyed by strings, so every precondition
+//! check, effect and closed-set lookup hashes and compares strings. A
+//! [`CompiledDomain`] resolves every variable of a set of actions to a dense
+//! index and every string value to a number once, turning states into small
+//! arrays of copyable values. [`Planner::plan_compiled`] then runs A* over those
+//! arrays and returns an ordinary [`Plan`] of the original actions.
+//!
+//! Compile a domain once, e.g. at load time, and reuse it for every plan:
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let actions = vec![
+//!     Action::new("chop_wood").sets("has_wood", true).build(),
+//!     Action::new("build_fire")
+//!         .requires("has_wood", true)
+//!         .sets("warm", true)
+//!         .build(),
+//! ];
+//! let planner = Planner::new();
+//! let domain = planner.compile(&actions).unwrap();
+//!
+//! let goal = Goal::new("warm").requires("warm", true).build();
+//! let plan = planner
+//!     .plan_compiled(&domain, State::new().set("warm", false).build(), &goal)
+//!     .unwrap();
+//! assert_eq!(plan.actions.len(), 2);
+//! ```
+//!
+//! Compiled planning always runs a plain A* search. It honours the actions
+//! prepared by the planner's configuration (excluded tags, alternative
+//! preconditions, mutex groups and string comparisons), the up-front checks and
+//! the symbol distances of the heuristic, but not the search strategy,
+//! annealing, goal clusters or memory and time budgets. Actions with expression
+//! preconditions or dynamic effects cannot be compiled.
+
+use crate::actions::Action;
+use crate::goals::Goal;
+use crate::planner::{NodeWrapper, Plan, PlanStats, Planner, PlannerError};
+use crate::state::{State, StateOperation, StateVar};
+use std::borrow::Cow;
+use std::collections::{BinaryHeap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::mem::size_of;
+
+/// Errors reported when compiling a domain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CompileError {
+    /// The action uses a feature the compiled form cannot represent
+    Unsupported {
+        /// The name of the action
+        action: String,
+        /// The unsupported feature
+        feature: &'static str,
+    },
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompileError::Unsupported { action, feature } => {
+                write!(f, "Action '{action}' cannot be compiled: it uses {feature}")
+            }
+        }
+    }
+}
+
+impl Error for CompileError {}
+
+/// A state variable's value in compiled form; strings are interned as numbers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Value {
+    /// The variable is not set
+    Missing,
+    /// Boolean value
+    Bool(bool),
+    /// Integer value
+    I64(i64),
+    /// Fixed-point floating-point value
+    F64(i64),
+    /// Interned string value
+    Symbol(u32),
+}
+
+impl Value {
+    /// Mirrors `value_satisfies`: numbers are minimums, other values must match,
+    /// and missing values or mismatched types never satisfy.
+    fn satisfies(self, required: Value) -> bool {
+        match (self, required) {
+            (Value::Bool(current), Value::Bool(required)) => current == required,
+            (Value::I64(current), Value::I64(required)) => current >= required,
+            (Value::F64(current), Value::F64(required)) => current >= required,
+            (Value::Symbol(current), Value::Symbol(required)) => current == required,
+            _ => false,
+        }
+    }
+}
+
+/// A state operation in compiled form.
+#[derive(Clone, Copy, Debug)]
+enum Operation {
+    /// Set the variable to a value
+    Set(Value),
+    /// Add to a numeric variable
+    Add(i64),
+    /// Subtract from a numeric variable
+    Subtract(i64),
+}
+
+impl Operation {
+    /// Mirrors `apply_operation`: arithmetic on missing or non-numeric values
+    /// leaves them unchanged.
+    fn apply(self, current: Value) -> Value {
+        match (self, current) {
+            (Operation::Set(value), _) => value,
+            (Operation::Add(amount), Value::I64(value)) => Value::I64(value + amount),
+            (Operation::Add(amount), Value::F64(value)) => Value::F64(value + amount),
+            (Operation::Subtract(amount), Value::I64(value)) => Value::I64(value - amount),
+            (Operation::Subtract(amount), Value::F64(value)) => Value::F64(value - amount),
+            (_, current) => current,
+        }
+    }
+}
+
+/// An action in compiled form.
+#[derive(Clone, Debug)]
+struct CompiledAction {
+    /// Required values by variable index
+    preconditions: Box<[(usize, Value)]>,
+    /// Variables that must be missing
+    absent: Box<[usize]>,
+    /// Operations by variable index
+    effects: Box<[(usize, Operation)]>,
+    /// The cost of the action
+    cost: f64,
+}
+
+impl CompiledAction {
+    /// Returns true if the action can execute in the state.
+    fn can_execute(&self, state: &[Value]) -> bool {
+        self.preconditions
+            .iter()
+            .all(|&(key, required)| state[key].satisfies(required))
+            && self.absent.iter().all(|&key| state[key] == Value::Missing)
+    }
+
+    /// Returns the state after applying the action's effects.
+    fn apply(&self, state: &[Value]) -> Box<[Value]> {
+        let mut next: Box<[Value]> = state.into();
+        for &(key, operation) in self.effects.iter() {
+            next[key] = operation.apply(next[key]);
+        }
+        next
+    }
+}
+
+/// Actions compiled for a planner, reusable across many plan calls.
+///
+/// Build one with [`Planner::compile`] and plan with the same planner, since the
+/// actions are prepared with its configuration.
+#[derive(Clone, Debug)]
+pub struct CompiledDomain {
+    /// The variable names, by index
+    keys: Vec<String>,
+    /// Lookup from variable name to index
+    key_index: HashMap<String, usize>,
+    /// The interned string values, by number
+    symbols: Vec<String>,
+    /// Lookup from string value to number
+    symbol_index: HashMap<String, u32>,
+    /// The prepared actions, returned in plans
+    actions: Vec<Action>,
+    /// The compiled form of each prepared action
+    compiled: Vec<CompiledAction>,
+    /// The variable indices of each configured mutex group
+    mutex_groups: Vec<Vec<usize>>,
+}
+
+impl CompiledDomain {
+    /// Returns the number of variables the actions use.
+    pub fn key_count(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns the variable names in index order.
+    pub fn keys(&self) -> &[String] {
+        &self.keys
+    }
+
+    /// Returns the prepared actions plans are made of.
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    /// Returns the index of a variable name, adding it if needed.
+    fn intern_key(&mut self, key: &str) -> usize {
+        if let Some(&index) = self.key_index.get(key) {
+            return index;
+        }
+        self.keys.push(key.to_string());
+        self.key_index.insert(key.to_string(), self.keys.len() - 1);
+        self.keys.len() - 1
+    }
+
+    /// Returns the compiled form of a value, interning strings.
+    fn intern_value(&mut self, value: &StateVar) -> Value {
+        match value {
+            StateVar::Bool(value) => Value::Bool(*value),
+            StateVar::I64(value) => Value::I64(*value),
+            StateVar::F64(value) => Value::F64(*value),
+            StateVar::String(text) => {
+                if let Some(&symbol) = self.symbol_index.get(&**text) {
+                    return Value::Symbol(symbol);
+                }
+                let symbol = self.symbols.len() as u32;
+                self.symbols.push(text.to_string());
+                self.symbol_index.insert(text.to_string(), symbol);
+                Value::Symbol(symbol)
+            }
+        }
+    }
+}
+
+impl Planner {
+    /// Compiles actions into a dense numeric form for [`Planner::plan_compiled`].
+    ///
+    /// The actions are prepared with this planner's configuration first. Returns
+    /// an error if an action has expression preconditions, dynamic effects or
+    /// postconditions.
+    pub fn compile(&self, actions: &[Action]) -> Result<CompiledDomain, CompileError> {
+        let actions = self.prepare_actions(actions).into_owned();
+        let mut domain = CompiledDomain {
+            keys: Vec::new(),
+            key_index: HashMap::new(),
+            symbols: Vec::new(),
+            symbol_index: HashMap::new(),
+            actions: Vec::new(),
+            compiled: Vec::new(),
+            mutex_groups: Vec::new(),
+        };
+
+        let mut compiled = Vec::with_capacity(actions.len());
+        for action in &actions {
+            let unsupported = |feature| CompileError::Unsupported {
+                action: action.name.clone(),
+                feature,
+            };
+            if !action.expr_preconditions.is_empty() {
+                return Err(unsupported("expression preconditions"));
+            }
+            if !action.conditions.is_empty() {
+                return Err(unsupported("condition preconditions"));
+            }
+            if !action.dynamic_effects.is_empty() {
+                return Err(unsupported("dynamic effects"));
+            }
+            if !action.postconditions.is_empty() {
+                return Err(unsupported("postconditions"));
+            }
+            let preconditions = action
+                .preconditions
+                .vars
+                .iter()
+                .map(|(key, value)| (domain.intern_key(key), domain.intern_value(value)))
+                .collect();
+            let absent = action
+                .absent_preconditions
+                .iter()
+                .map(|key| domain.intern_key(key))
+                .collect();
+            let effects = action
+                .effects
+                .iter()
+                .map(|(key, operation)| {
+                    let operation = match operation {
+                        StateOperation::Set(value) => Operation::Set(domain.intern_value(value)),
+                        StateOperation::Add(amount) => Operation::Add(*amount),
+                        StateOperation::Subtract(amount) => Operation::Subtract(*amount),
+                    };
+                    (domain.intern_key(key), operation)
+                })
+                .collect();
+            compiled.push(CompiledAction {
+                preconditions,
+                absent,
+                effects,
+                cost: action.cost,
+            });
+        }
+
+        domain.mutex_groups = self
+            .config()
+            .mutex_groups
+            .iter()
+            .map(|group| {
+                group
+                    .keys()
+                    .iter()
+                    .map(|key| domain.intern_key(key))
+                    .collect()
+            })
+            .collect();
+        domain.actions = actions;
+        domain.compiled = compiled;
+        Ok(domain)
+    }
+
+    /// Finds the cheapest plan from the initial state to the goal using a compiled
+    /// domain, like [`Planner::plan`] with the A* strategy.
+    ///
+    /// Variables and string values that only appear in the initial state or the
+    /// goal are resolved per call, so the domain never changes and can be shared.
+    /// Goals with [conditions](crate::goals::Goal::conditions) are planned with the
+    /// uncompiled A* search over the domain's actions.
+    pub fn plan_compiled<'s>(
+        &self,
+        domain: &CompiledDomain,
+        initial_state: impl Into<Cow<'s, State>>,
+        goal: &Goal,
+    ) -> Result<Plan, PlannerError> {
+        let initial_state = initial_state.into();
+        if !goal.conditions.is_empty() {
+            return self.plan_weighted(initial_state, goal, &domain.actions, 1.0, None);
+        }
+        let (initial_state, goal) = self.prepare_start(initial_state.into_owned(), goal);
+        self.check_problem(&initial_state, &goal, &domain.actions)?;
+
+        let mut encoder = Encoder {
+            domain,
+            extra_keys: Vec::new(),
+            extra_symbols: Vec::new(),
+        };
+        let required: Vec<(usize, Value)> = goal
+            .desired_state
+            .vars
+            .iter()
+            .map(|(key, value)| (encoder.key(key), encoder.value(value)))
+            .collect();
+        let excluded: Vec<(usize, Value)> = goal
+            .excluded
+            .vars
+            .iter()
+            .map(|(key, value)| (encoder.key(key), encoder.value(value)))
+            .collect();
+        let width = domain.keys.len() + encoder.extra_keys.len();
+        let mut start = vec![Value::Missing; width].into_boxed_slice();
+        for (key, value) in &initial_state.vars {
+            if let Some(index) = encoder.find_key(key) {
+                start[index] = encoder.value(value);
+            }
+        }
+
+        let mut search = CompiledSearch {
+            planner: self,
+            encoder: &encoder,
+            required: &required,
+            excluded: &excluded,
+            nodes: Vec::new(),
+            index: HashMap::new(),
+            open: BinaryHeap::new(),
+            stats: PlanStats::default(),
+        };
+        search.discover(start, None, None, 0.0)?;
+        let mut plan = search.run()?;
+        plan.currencies = self.simulated_currency_changes(&initial_state, &plan.actions);
+        Ok(plan)
+    }
+}
+
+/// Resolves the variables and string values of one planning call, extending the
+/// domain's tables without changing them.
+struct Encoder<'d> {
+    /// The compiled domain
+    domain: &'d CompiledDomain,
+    /// Variables missing from the domain, indexed after its own
+    extra_keys: Vec<String>,
+    /// String values missing from the domain, numbered after its own
+    extra_symbols: Vec<String>,
+}
+
+impl Encoder<'_> {
+    /// Returns the index of a known variable.
+    fn find_key(&self, key: &str) -> Option<usize> {
+        self.domain.key_index.get(key).copied().or_else(|| {
+            self.extra_keys
+                .iter()
+                .position(|extra| extra == key)
+                .map(|position| self.domain.keys.len() + position)
+        })
+    }
+
+    /// Returns the index of a variable, adding it if needed.
+    fn key(&mut self, key: &str) -> usize {
+        self.find_key(key).unwrap_or_else(|| {
+            self.extra_keys.push(key.to_string());
+            self.domain.keys.len() + self.extra_keys.len() - 1
+        })
+    }
+
+    /// Returns the compiled form of a value, numbering unknown strings.
+    fn value(&mut self, value: &StateVar) -> Value {
+        match value {
+            StateVar::Bool(value) => Value::Bool(*value),
+            StateVar::I64(value) => Value::I64(*value),
+            StateVar::F64(value) => Value::F64(*value),
+            StateVar::String(text) => {
+                if let Some(&symbol) = self.domain.symbol_index.get(&**text) {
+                    return Value::Symbol(symbol);
+                }
+                let position = match self.extra_symbols.iter().position(|extra| extra == &**text) {
+                    Some(position) => position,
+                    None => {
+                        self.extra_symbols.push(text.to_string());
+                        self.extra_symbols.len() - 1
+                    }
+                };
+                Value::Symbol((self.domain.symbols.len() + position) as u32)
+            }
+        }
+    }
+
+    /// Returns the name of a variable index.
+    fn key_name(&self, index: usize) -> &str {
+        match self.domain.keys.get(index) {
+            Some(key) => key,
+            None => &self.extra_keys[index - self.domain.keys.len()],
+        }
+    }
+
+    /// Returns the text of a string value's number.
+    fn symbol_name(&self, symbol: u32) -> &str {
+        let symbol = symbol as usize;
+        match self.domain.symbols.get(symbol) {
+            Some(text) => text,
+            None => &self.extra_symbols[symbol - self.domain.symbols.len()],
+        }
+    }
+}
+
+/// A node of a compiled search.
+struct CompiledNode {
+    /// The state this node represents
+    state: Box<[Value]>,
+    /// Index of the node this one was reached from
+    parent: Option<usize>,
+    /// Index of the action that produced this node
+    action: Option<usize>,
+    /// Cost of the cheapest known path to this node
+    g: f64,
+    /// Heuristic estimate of the remaining cost
+    h: f64,
+    /// The g-score this node was last expanded with, used to skip stale queue entries
+    expanded_g: Option<f64>,
+}
+
+/// The working data of an A* search over a compiled domain.
+struct CompiledSearch<'a> {
+    /// The planner whose configuration drives the heuristic
+    planner: &'a Planner,
+    /// Resolves names for symbol distances and error messages
+    encoder: &'a Encoder<'a>,
+    /// The goal's required values
+    required: &'a [(usize, Value)],
+    /// The goal's excluded values
+    excluded: &'a [(usize, Value)],
+    /// All discovered nodes, indexed by insertion order
+    nodes: Vec<CompiledNode>,
+    /// Lookup from state to node index
+    index: HashMap<Box<[Value]>, usize>,
+    /// Nodes waiting to be expanded, ordered by f-score
+    open: BinaryHeap<NodeWrapper<usize>>,
+    /// Counters reported on the resulting plan
+    stats: PlanStats,
+}
+
+impl CompiledSearch<'_> {
+    /// Mirrors `Goal::is_satisfied`.
+    fn is_goal(&self, state: &[Value]) -> bool {
+        self.required
+            .iter()
+            .all(|&(key, required)| state[key].satisfies(required))
+            && self
+                .excluded
+                .iter()
+                .all(|&(key, excluded)| state[key] != excluded)
+    }
+
+    /// Mirrors `Planner::goal_heuristic`.
+    fn heuristic(&self, state: &[Value]) -> Result<f64, PlannerError> {
+        let mut total: u64 = 0;
+        for &(key, required) in self.required {
+            total += match (state[key], required) {
+                (Value::Missing, _) => 1,
+                (Value::Bool(current), Value::Bool(required)) => u64::from(current != required),
+                (Value::I64(current), Value::I64(required))
+                | (Value::F64(current), Value::F64(required)) => {
+                    (current - required).unsigned_abs()
+                }
+                (Value::Symbol(current), Value::Symbol(required)) => self
+                    .planner
+                    .config()
+                    .symbols
+                    .as_ref()
+                    .and_then(|symbols| {
+                        symbols.distance(
+                            self.encoder.key_name(key),
+                            self.encoder.symbol_name(current),
+                            self.encoder.symbol_name(required),
+                        )
+                    })
+                    .unwrap_or(u64::from(current != required)),
+                _ => {
+                    return Err(PlannerError::IncompatibleStateTypes(format!(
+                        "Cannot calculate distance for variable '{}' due to type mismatch",
+                        self.encoder.key_name(key)
+                    )));
+                }
+            };
+        }
+        let excluded = self
+            .excluded
+            .iter()
+            .filter(|&&(key, excluded)| state[key] == excluded)
+            .count();
+        Ok(total as f64 + excluded as f64)
+    }
+
+    /// Mirrors `MutexGroup::is_consistent` for every configured group.
+    fn respects_mutex_groups(&self, state: &[Value]) -> bool {
+        self.encoder.domain.mutex_groups.iter().all(|group| {
+            let constrained = group.iter().any(|&key| state[key] != Value::Missing);
+            let active = group
+                .iter()
+                .filter(|&&key| state[key] == Value::Bool(true))
+                .count();
+            !constrained || active == 1
+        })
+    }
+
+    /// Records a path to `state`, adding it to the open set if it is new or cheaper.
+    fn discover(
+        &mut self,
+        state: Box<[Value]>,
+        parent: Option<usize>,
+        action: Option<usize>,
+        g: f64,
+    ) -> Result<(), PlannerError> {
+        let node = match self.index.get(&state) {
+            Some(&existing) => {
+                if g >= self.nodes[existing].g {
+                    return Ok(());
+                }
+                let node = &mut self.nodes[existing];
+                node.parent = parent;
+                node.action = action;
+                node.g = g;
+                existing
+            }
+            None => {
+                let h = self.heuristic(&state)?;
+                let node = self.nodes.len();
+                self.stats.memory_bytes += 2 * size_of::<Value>() * state.len()
+                    + size_of::<CompiledNode>()
+                    + size_of::<usize>();
+                self.index.insert(state.clone(), node);
+                self.nodes.push(CompiledNode {
+                    state,
+                    parent,
+                    action,
+                    g,
+                    h,
+                    expanded_g: None,
+                });
+                node
+            }
+        };
+
+        let node_data = &self.nodes[node];
+        self.open.push(NodeWrapper {
+            node,
+            f_score: node_data.g + node_data.h,
+            tie_break: 0.0,
+        });
+        self.stats.memory_bytes += size_of::<NodeWrapper<usize>>();
+        Ok(())
+    }
+
+    /// Runs A* until the goal is reached or the open set is exhausted.
+    fn run(&mut self) -> Result<Plan, PlannerError> {
+        while let Some(NodeWrapper { node: current, .. }) = self.open.pop() {
+            let node = &self.nodes[current];
+            if node.expanded_g == Some(node.g) {
+                continue; // Stale entry; this node was already expanded at this cost
+            }
+            if self.is_goal(&node.state) {
+                return Ok(self.reconstruct(current));
+            }
+
+            let node = &mut self.nodes[current];
+            node.expanded_g = Some(node.g);
+            let (g, state) = (node.g, node.state.clone());
+            self.stats.nodes_expanded += 1;
+            let domain = self.encoder.domain;
+            for (index, action) in domain.compiled.iter().enumerate() {
+                if !action.can_execute(&state) {
+                    continue;
+                }
+                let next = action.apply(&state);
+                if !self.respects_mutex_groups(&next) {
+                    continue;
+                }
+                self.stats.nodes_generated += 1;
+                self.discover(next, Some(current), Some(index), g + action.cost)?;
+            }
+        }
+        Err(PlannerError::NoPlanFound)
+    }
+
+    /// Reconstructs the plan leading to the given node by following parent links.
+    fn reconstruct(&self, target: usize) -> Plan {
+        let mut actions = Vec::new();
+        let mut current = Some(target);
+        while let Some(index) = current {
+            let node = &self.nodes[index];
+            if let Some(action) = node.action {
+                actions.push(self.encoder.domain.actions[action].clone());
+            }
+            current = node.parent;
+        }
+        actions.reverse();
+
+        let cost = actions.iter().map(|action| action.cost).sum();
+        Plan {
+            actions,
+            cost,
+            stats: self.stats.clone(),
+            ..Plan::default()
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! Conformant planning over initial states with unknown boolean variables.
+//!
+//! When an agent does not know the value of a few boolean variables, every
+//! combination of those values is a possible world. [`Planner::plan_conformant`]
+//! searches over the set of possible worlds (the belief state) for a single
+//! plan whose actions are executable in every world and which reaches the goal
+//! in all of them.
+
+use crate::actions::Action;
+use crate::goals::Goal;
+use crate::planner::{NodeWrapper, Plan, Planner, PlannerError};
+use crate::state::State;
+use std::borrow::Cow;
+use std::collections::{BinaryHeap, HashMap};
+
+/// Maximum number of unknown variables accepted by conformant planning.
+/// The belief state holds 2^n worlds, so the bound keeps searches tractable.
+pub const MAX_UNKNOWN_VARS: usize = 10;
+
+/// A belief node: the possible worlds, the cost to reach them and how they were reached.
+struct BeliefNode {
+    /// One state per possible world, in a fixed order so equal beliefs hash equally
+    worlds: Vec<State>,
+    /// Index of the node this one was reached from
+    parent: Option<usize>,
+    /// Index into the action slice of the action that produced this node
+    action: Option<usize>,
+    /// Cost of the cheapest known path to this node
+    g: f64,
+}
+
+impl Planner {
+    /// Finds a single plan that achieves the goal whatever the values of the unknown variables.
+    ///
+    /// Each key in `unknown` is treated as a boolean that may be either true or false
+    /// in `initial_state`, overriding any value it already has. The returned plan only
+    /// uses actions whose preconditions hold in every possible world, and reaches the
+    /// goal in all of them.
+    ///
+    /// Returns `PlannerError::NoConformantPlan` if no such plan exists or if more than
+    /// [`MAX_UNKNOWN_VARS`] unknown variables are given.
+    pub fn plan_conformant<'s>(
+        &self,
+        initial_state: impl Into<Cow<'s, State>>,
+        unknown: &[&str],
+        goal: &Goal,
+        actions: &[Action],
+    ) -> Result<Plan, PlannerError> {
+        if unknown.len() > MAX_UNKNOWN_VARS {
+            return Err(PlannerError::NoConformantPlan);
+        }
+        let (initial_state, goal) = self.prepare_start(initial_state.into().into_owned(), goal);
+        let goal = &*goal;
+        self.check_problem(&initial_state, goal, actions)?;
+        let actions = &*self.prepare_actions(actions);
+
+        let worlds: Vec<State> = (0..1usize << unknown.len())
+            .map(|combination| {
+                let mut world = initial_state.clone();
+                for (bit, key) in unknown.iter().enumerate() {
+                    world.set(key, combination & (1 << bit) != 0);
+                }
+                world
+            })
+            .collect();
+
+        let mut nodes = vec![BeliefNode {
+            worlds: worlds.clone(),
+            parent: None,
+            action: None,
+            g: 0.0,
+        }];
+        let mut index: HashMap<Vec<State>, usize> = HashMap::new();
+        index.insert(worlds, 0);
+        let mut open = BinaryHeap::new();
+        open.push(NodeWrapper {
+            node: 0,
+            f_score: self.belief_heuristic(&nodes[0].worlds, goal)?,
+            tie_break: 0.0,
+        });
+        let mut closed = vec![false];
+
+        while let Some(NodeWrapper { node: current, .. }) = open.pop() {
+            if closed[current] {
+                continue;
+            }
+            closed[current] = true;
+
+            if nodes[current]
+                .worlds
+                .iter()
+                .all(|world| goal.is_satisfied(world))
+            {
+                return Ok(reconstruct(&nodes, current, actions));
+            }
+
+            for (action_index, action) in actions.iter().enumerate() {
+                let node = &nodes[current];
+                if !node.worlds.iter().all(|world| action.can_execute(world)) {
+                    continue;
+                }
+                let next: Vec<State> = node
+                    .worlds
+                    .iter()
+                    .map(|world| action.apply_effect(world))
+                    .collect();
+                if next
+                    .iter()
+                    .any(|world| !action.violated_postconditions(world).is_empty())
+                {
+                    continue;
+                }
+                let g = node.g + action.cost;
+
+                let next_index = match index.get(&next) {
+                    Some(&existing) if nodes[existing].g <= g => continue,
+                    Some(&existing) => {
+                        let node = &mut nodes[existing];
+                        node.parent = Some(current);
+                        node.action = Some(action_index);
+                        node.g = g;
+                        closed[existing] = false;
+                        existing
+                    }
+                    None => {
+                        let new_index = nodes.len();
+                        index.insert(next.clone(), new_index);
+                        nodes.push(BeliefNode {
+                            worlds: next,
+                            parent: Some(current),
+                            action: Some(action_index),
+                            g,
+                        });
+                        closed.push(false);
+                        new_index
+                    }
+                };
+
+                let h = self.belief_heuristic(&nodes[next_index].worlds, goal)?;
+                open.push(NodeWrapper {
+                    node: next_index,
+                    f_score: g + h,
+                    tie_break: 0.0,
+                });
+            }
+        }
+
+        Err(PlannerError::NoConformantPlan)
+    }
+
+    /// Estimates the remaining cost for a belief as the largest estimate over its worlds.
+    fn belief_heuristic(&self, worlds: &[State], goal: &Goal) -> Result<f64, PlannerError> {
+        worlds.iter().try_fold(0.0, |max: f64, world| {
+            Ok(max.max(self.goal_heuristic(world, goal)?))
+        })
+    }
+}
+
+/// Rebuilds the plan leading to a belief node by following parent links.
+fn reconstruct(nodes: &[BeliefNode], target: usize, actions: &[Action]) -> Plan {
+    let mut plan_actions = Vec::new();
+    let mut current = Some(target);
+    while let Some(index) = current {
+        if let Some(action) = nodes[index].action {
+            plan_actions.push(actions[action].clone());
+        }
+        current = nodes[index].parent;
+    }
+    plan_actions.reverse();
+
+    let cost = plan_actions.iter().map(|action| action.cost).sum();
+    Plan {
+        actions: plan_actions,
+        cost,
+        ..Plan::default()
+    }
+}
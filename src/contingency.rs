@@ -0,0 +1,188 @@
+//! Contingency plans with fallback branches at risky steps.
+//!
+//! Actions built with [`ActionBuilder::fails_with`](crate::actions::ActionBuilder::fails_with)
+//! predict the state they leave behind when they fail. [`Planner::plan_contingent`]
+//! finds a primary plan and, for every risky step in it, plans a fallback from the
+//! predicted failure state ahead of time. A [`ContingencyExecutor`] walks the plan
+//! and switches to the prepared fallback when a step fails, so the agent does not
+//! pause to replan.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let initial_state = State::new().set("door_open", false).set("lockpick", true).build();
+//! let goal = Goal::new("enter").requires("inside", true).build();
+//! let actions = vec![
+//!     Action::new("pick_lock")
+//!         .requires("lockpick", true)
+//!         .sets("door_open", true)
+//!         .fails_with("lockpick", false)
+//!         .build(),
+//!     Action::new("kick_door").sets("door_open", true).cost(5.0).build(),
+//!     Action::new("walk_in").requires("door_open", true).sets("inside", true).build(),
+//! ];
+//!
+//! let plan = Planner::new().plan_contingent(initial_state, &goal, &actions, 1).unwrap();
+//! let mut executor = plan.executor();
+//! assert_eq!(executor.current().unwrap().name, "pick_lock");
+//!
+//! // The lock pick breaks: switch to the fallback without replanning
+//! assert!(executor.failed());
+//! assert_eq!(executor.current().unwrap().name, "kick_door");
+//! ```
+
+use crate::actions::Action;
+use crate::goals::Goal;
+use crate::planner::{Plan, Planner, PlannerError};
+use crate::state::State;
+use std::borrow::Cow;
+
+/// A fallback attached to a risky step of a contingency plan.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Branch {
+    /// The index of the risky step in the plan's actions
+    pub step: usize,
+    /// The name of the action at the risky step
+    pub action: String,
+    /// The state predicted after the action fails
+    pub failure_state: State,
+    /// The plan to follow from the failure state, or `None` if the goal cannot be
+    /// reached from it or the branch depth was exhausted
+    pub fallback: Option<ContingencyPlan>,
+}
+
+/// A primary plan with fallback branches at its risky steps.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContingencyPlan {
+    /// The plan to follow while every step succeeds
+    pub plan: Plan,
+    /// One branch per risky step, in step order
+    pub branches: Vec<Branch>,
+}
+
+impl ContingencyPlan {
+    /// Returns the branch attached to a step, if the step is risky.
+    pub fn branch_at(&self, step: usize) -> Option<&Branch> {
+        self.branches.iter().find(|branch| branch.step == step)
+    }
+
+    /// Returns an executor positioned at the first step of the primary plan.
+    pub fn executor(&self) -> ContingencyExecutor<'_> {
+        ContingencyExecutor {
+            current: self,
+            step: 0,
+        }
+    }
+}
+
+/// Walks a contingency plan, switching to fallback branches when steps fail.
+#[derive(Clone, Debug)]
+pub struct ContingencyExecutor<'a> {
+    /// The plan or fallback currently being followed
+    current: &'a ContingencyPlan,
+    /// The index of the next action in the current plan
+    step: usize,
+}
+
+impl<'a> ContingencyExecutor<'a> {
+    /// Returns the action to perform next, or `None` when the plan is finished.
+    pub fn current(&self) -> Option<&'a Action> {
+        self.current.plan.actions.get(self.step)
+    }
+
+    /// Returns the actions left in the plan being followed, starting with the current one.
+    pub fn remaining(&self) -> &'a [Action] {
+        self.current
+            .plan
+            .actions
+            .get(self.step..)
+            .unwrap_or_default()
+    }
+
+    /// Returns true when every action of the plan being followed has succeeded.
+    pub fn is_finished(&self) -> bool {
+        self.step >= self.current.plan.actions.len()
+    }
+
+    /// Records that the current action succeeded and moves to the next one.
+    pub fn succeeded(&mut self) {
+        if !self.is_finished() {
+            self.step += 1;
+        }
+    }
+
+    /// Records that the current action failed and switches to its fallback branch.
+    ///
+    /// Returns false if the step has no prepared fallback, in which case the
+    /// executor is left unchanged and the caller has to replan.
+    pub fn failed(&mut self) -> bool {
+        match self
+            .current
+            .branch_at(self.step)
+            .and_then(|branch| branch.fallback.as_ref())
+        {
+            Some(fallback) => {
+                self.current = fallback;
+                self.step = 0;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Planner {
+    /// Finds a plan with fallback branches at every step whose action may fail.
+    ///
+    /// Each fallback is planned from the state predicted by the action's failure
+    /// effects. `depth` limits how many failures in a row are covered: with a depth
+    /// of 1 only the primary plan gets branches, with 2 the fallbacks get branches
+    /// of their own, and so on. Relative goal requirements stay resolved against
+    /// `initial_state` in every branch. A branch whose goal is unreachable gets no
+    /// fallback; any other planning error is returned.
+    pub fn plan_contingent<'s>(
+        &self,
+        initial_state: impl Into<Cow<'s, State>>,
+        goal: &Goal,
+        actions: &[Action],
+        depth: usize,
+    ) -> Result<ContingencyPlan, PlannerError> {
+        let initial_state = initial_state.into().into_owned();
+        let goal = goal.resolve(&initial_state);
+        self.contingent(initial_state, &goal, actions, depth)
+    }
+
+    /// Plans from a state and recursively attaches fallback branches.
+    fn contingent(
+        &self,
+        initial_state: State,
+        goal: &Goal,
+        actions: &[Action],
+        depth: usize,
+    ) -> Result<ContingencyPlan, PlannerError> {
+        let plan = self.plan(&initial_state, goal, actions)?;
+        let mut branches = Vec::new();
+        if depth > 0 {
+            let mut state = initial_state;
+            for (step, action) in plan.actions.iter().enumerate() {
+                if action.may_fail() {
+                    let failure_state = action.apply_failure(&state);
+                    let fallback =
+                        match self.contingent(failure_state.clone(), goal, actions, depth - 1) {
+                            Ok(fallback) => Some(fallback),
+                            Err(PlannerError::NoPlanFound) => None,
+                            Err(error) => return Err(error),
+                        };
+                    branches.push(Branch {
+                        step,
+                        action: action.name.clone(),
+                        failure_state,
+                        fallback,
+                    });
+                }
+                state = action.apply_effect(&state);
+            }
+        }
+        Ok(ContingencyPlan { plan, branches })
+    }
+}
@@ -0,0 +1,152 @@
+//! Sampling reachable states and measuring which actions fire.
+//!
+//! Authored domains accumulate actions that can never run, or only in corners of
+//! the state space nobody tested. [`sample_reachable_states`] random-walks the
+//! domain from an initial state and returns the distinct states it visited,
+//! together with [`ActionCoverage`] counting how often each action fired. The
+//! states make a test corpus for custom heuristics, and actions that never fired
+//! point at content worth reviewing.
+//!
+//! Walks use the planner's successor function ([`Planner::expand`]) with a
+//! default configuration, so actions breaking their postconditions never fire.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let actions = vec![
+//!     Action::new("chop").sets("has_wood", true).build(),
+//!     Action::new("build").requires("has_wood", true).sets("has_hut", true).build(),
+//!     Action::new("fly").requires("has_wings", true).sets("flying", true).build(),
+//! ];
+//! let sample = sample_reachable_states(&State::empty(), &actions, 10, 4);
+//! assert!(sample.states.len() <= 10);
+//! assert_eq!(sample.coverage.never_fired(), vec!["fly"]);
+//! ```
+
+use crate::actions::Action;
+use crate::planner::Planner;
+use crate::rng::Rng;
+use crate::state::State;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+/// How often each action fired during a random walk.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ActionCoverage {
+    /// The number of times each action fired, by name, including actions that never did
+    pub fired: BTreeMap<String, usize>,
+}
+
+impl ActionCoverage {
+    /// Returns the names of the actions that never fired, sorted.
+    pub fn never_fired(&self) -> Vec<&str> {
+        self.fired
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// Returns the share of actions that fired at least once, from 0.0 to 1.0.
+    /// An empty set of actions is fully covered.
+    pub fn ratio(&self) -> f64 {
+        if self.fired.is_empty() {
+            return 1.0;
+        }
+        let covered = self.fired.values().filter(|count| **count > 0).count();
+        covered as f64 / self.fired.len() as f64
+    }
+}
+
+impl fmt::Display for ActionCoverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let never = self.never_fired();
+        write!(
+            f,
+            "{}/{} actions fired",
+            self.fired.len() - never.len(),
+            self.fired.len()
+        )?;
+        if !never.is_empty() {
+            write!(f, "; never fired: {}", never.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// States sampled by [`sample_reachable_states`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ReachableStates {
+    /// The distinct states visited, in the order they were first reached,
+    /// starting with the initial state
+    pub states: Vec<State>,
+    /// How often each action fired during the walks
+    pub coverage: ActionCoverage,
+}
+
+/// Random-walks the domain from `initial` and returns up to `count` distinct
+/// states reached within `max_depth` actions, with the actions' coverage.
+///
+/// Runs up to `count` walks, each taking up to `max_depth` random steps and
+/// stopping early where no action applies. Sampling is deterministic; use
+/// [`sample_reachable_states_seeded`] for other samples.
+pub fn sample_reachable_states(
+    initial: &State,
+    actions: &[Action],
+    count: usize,
+    max_depth: usize,
+) -> ReachableStates {
+    sample_reachable_states_seeded(initial, actions, count, max_depth, 0)
+}
+
+/// Samples reachable states like [`sample_reachable_states`], with random walks
+/// seeded by `seed`. Equal seeds give equal samples.
+pub fn sample_reachable_states_seeded(
+    initial: &State,
+    actions: &[Action],
+    count: usize,
+    max_depth: usize,
+    seed: u64,
+) -> ReachableStates {
+    let planner = Planner::new();
+    let mut rng = Rng::new(seed);
+    let mut fired: BTreeMap<String, usize> = actions
+        .iter()
+        .map(|action| (action.name.clone(), 0))
+        .collect();
+    let mut seen = HashSet::new();
+    let mut states = Vec::new();
+    if count > 0 {
+        seen.insert(initial.clone());
+        states.push(initial.clone());
+    }
+
+    for _ in 0..count {
+        if states.len() >= count {
+            break;
+        }
+        let mut state = initial.clone();
+        for _ in 0..max_depth {
+            let mut transitions: Vec<_> = planner.expand(&state, actions).collect();
+            if transitions.is_empty() {
+                break;
+            }
+            let transition = transitions.swap_remove(rng.below(transitions.len()));
+            if let Some(fired) = fired.get_mut(&transition.action.name) {
+                *fired += 1;
+            }
+            state = transition.state;
+            if seen.insert(state.clone()) {
+                states.push(state.clone());
+                if states.len() >= count {
+                    break;
+                }
+            }
+        }
+    }
+
+    ReachableStates {
+        states,
+        coverage: ActionCoverage { fired },
+    }
+}
@@ -0,0 +1,325 @@
+//! Structured logs of agent decisions for analytics.
+//!
+//! Telemetry wants to answer questions such as "how often do guards fail to plan
+//! their patrols" across many play sessions. A [`DecisionEvent`] records one
+//! decision of one agent: selecting a goal, finding or failing to find a plan,
+//! replanning, or the outcome of an action. Events are passed to a
+//! [`DecisionSink`], which forwards them to the game's telemetry.
+//!
+//! [`Planner::plan_logged`] records the planning events itself; the agent loop
+//! records the others with [`DecisionSink::record`]. Two sinks are provided: a
+//! `Vec<DecisionEvent>` keeps every event, and [`DecisionStats`] keeps counts per
+//! agent and goal or action that can be merged across sessions.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let patrol = Goal::new("patrol").requires("at_post", true).build();
+//! let mut stats = DecisionStats::new();
+//!
+//! let planner = Planner::new();
+//! let result = planner.plan_logged("guard", State::empty(), &patrol, &[], &mut stats);
+//! assert!(result.is_err());
+//!
+//! let decisions = stats.goal("guard", "patrol");
+//! assert_eq!(decisions.plans_failed, 1);
+//! assert_eq!(decisions.failure_rate(), Some(1.0));
+//! ```
+
+use crate::actions::Action;
+use crate::goals::Goal;
+use crate::planner::{Plan, Planner, PlannerError};
+use crate::state::State;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// One decision made by an agent.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecisionEvent {
+    /// The agent chose a goal to pursue
+    GoalSelected {
+        /// The agent
+        agent: String,
+        /// The name of the goal
+        goal: String,
+    },
+    /// A plan was found for the agent's goal
+    PlanFound {
+        /// The agent
+        agent: String,
+        /// The name of the goal
+        goal: String,
+        /// The number of actions in the plan
+        steps: usize,
+        /// The total cost of the plan
+        cost: f64,
+        /// The number of states the search expanded
+        nodes_expanded: usize,
+    },
+    /// No plan was found for the agent's goal
+    PlanFailed {
+        /// The agent
+        agent: String,
+        /// The name of the goal
+        goal: String,
+        /// Why planning failed
+        error: String,
+    },
+    /// The agent abandoned its plan and planned again
+    Replanned {
+        /// The agent
+        agent: String,
+        /// The name of the goal
+        goal: String,
+        /// Why the plan was abandoned
+        reason: String,
+    },
+    /// An action of the agent's plan succeeded
+    ActionSucceeded {
+        /// The agent
+        agent: String,
+        /// The name of the action
+        action: String,
+    },
+    /// An action of the agent's plan failed
+    ActionFailed {
+        /// The agent
+        agent: String,
+        /// The name of the action
+        action: String,
+        /// Why the action failed
+        reason: String,
+    },
+}
+
+impl DecisionEvent {
+    /// Returns the agent that made the decision.
+    pub fn agent(&self) -> &str {
+        match self {
+            DecisionEvent::GoalSelected { agent, .. }
+            | DecisionEvent::PlanFound { agent, .. }
+            | DecisionEvent::PlanFailed { agent, .. }
+            | DecisionEvent::Replanned { agent, .. }
+            | DecisionEvent::ActionSucceeded { agent, .. }
+            | DecisionEvent::ActionFailed { agent, .. } => agent,
+        }
+    }
+
+    /// Returns a stable snake_case name for the kind of decision, e.g. `plan_failed`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DecisionEvent::GoalSelected { .. } => "goal_selected",
+            DecisionEvent::PlanFound { .. } => "plan_found",
+            DecisionEvent::PlanFailed { .. } => "plan_failed",
+            DecisionEvent::Replanned { .. } => "replanned",
+            DecisionEvent::ActionSucceeded { .. } => "action_succeeded",
+            DecisionEvent::ActionFailed { .. } => "action_failed",
+        }
+    }
+}
+
+/// Writes one line of `key=value` fields after the kind, with text values quoted,
+/// e.g. `plan_failed agent="guard" goal="patrol" error="No plan found"`.
+impl fmt::Display for DecisionEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} agent={:?}", self.kind(), self.agent())?;
+        match self {
+            DecisionEvent::GoalSelected { goal, .. } => write!(f, " goal={goal:?}"),
+            DecisionEvent::PlanFound {
+                goal,
+                steps,
+                cost,
+                nodes_expanded,
+                ..
+            } => write!(
+                f,
+                " goal={goal:?} steps={steps} cost={cost} nodes_expanded={nodes_expanded}"
+            ),
+            DecisionEvent::PlanFailed { goal, error, .. } => {
+                write!(f, " goal={goal:?} error={error:?}")
+            }
+            DecisionEvent::Replanned { goal, reason, .. } => {
+                write!(f, " goal={goal:?} reason={reason:?}")
+            }
+            DecisionEvent::ActionSucceeded { action, .. } => write!(f, " action={action:?}"),
+            DecisionEvent::ActionFailed { action, reason, .. } => {
+                write!(f, " action={action:?} reason={reason:?}")
+            }
+        }
+    }
+}
+
+/// Receives agent decisions, e.g. to forward them to a telemetry service.
+pub trait DecisionSink {
+    /// Called for every decision.
+    fn record(&mut self, event: &DecisionEvent);
+}
+
+/// Keeps every event, in order.
+impl DecisionSink for Vec<DecisionEvent> {
+    fn record(&mut self, event: &DecisionEvent) {
+        self.push(event.clone());
+    }
+}
+
+/// Counts of the decisions about one goal of one agent.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GoalDecisions {
+    /// Times the goal was selected
+    pub selected: usize,
+    /// Plans found for the goal
+    pub plans_found: usize,
+    /// Failed attempts to plan for the goal
+    pub plans_failed: usize,
+    /// Plans for the goal that were abandoned
+    pub replans: usize,
+}
+
+impl GoalDecisions {
+    /// Returns the share of planning attempts that failed, or None if planning
+    /// was never attempted.
+    pub fn failure_rate(&self) -> Option<f64> {
+        let attempts = self.plans_found + self.plans_failed;
+        (attempts > 0).then(|| self.plans_failed as f64 / attempts as f64)
+    }
+}
+
+/// Counts of the outcomes of one action of one agent.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ActionDecisions {
+    /// Times the action succeeded
+    pub succeeded: usize,
+    /// Times the action failed
+    pub failed: usize,
+}
+
+/// A sink counting decisions per agent and goal or action.
+///
+/// Stats from many sessions can be combined with [`DecisionStats::merge`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DecisionStats {
+    /// Counts per agent and goal name
+    goals: BTreeMap<(String, String), GoalDecisions>,
+    /// Counts per agent and action name
+    actions: BTreeMap<(String, String), ActionDecisions>,
+}
+
+impl DecisionStats {
+    /// Creates empty stats.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the counts for a goal of an agent; zero if never recorded.
+    pub fn goal(&self, agent: &str, goal: &str) -> GoalDecisions {
+        self.goals
+            .get(&(agent.to_string(), goal.to_string()))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the counts for an action of an agent; zero if never recorded.
+    pub fn action(&self, agent: &str, action: &str) -> ActionDecisions {
+        self.actions
+            .get(&(agent.to_string(), action.to_string()))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the counts of every recorded goal, by agent and goal name.
+    pub fn goals(&self) -> impl Iterator<Item = (&str, &str, GoalDecisions)> {
+        self.goals
+            .iter()
+            .map(|((agent, goal), counts)| (agent.as_str(), goal.as_str(), *counts))
+    }
+
+    /// Returns the counts of every recorded action, by agent and action name.
+    pub fn actions(&self) -> impl Iterator<Item = (&str, &str, ActionDecisions)> {
+        self.actions
+            .iter()
+            .map(|((agent, action), counts)| (agent.as_str(), action.as_str(), *counts))
+    }
+
+    /// Adds the counts of other stats, e.g. from another play session.
+    pub fn merge(&mut self, other: &DecisionStats) {
+        for (key, counts) in &other.goals {
+            let total = self.goals.entry(key.clone()).or_default();
+            total.selected += counts.selected;
+            total.plans_found += counts.plans_found;
+            total.plans_failed += counts.plans_failed;
+            total.replans += counts.replans;
+        }
+        for (key, counts) in &other.actions {
+            let total = self.actions.entry(key.clone()).or_default();
+            total.succeeded += counts.succeeded;
+            total.failed += counts.failed;
+        }
+    }
+
+    /// Returns the counts for a goal of an agent, creating them if needed.
+    fn goal_mut(&mut self, agent: &str, goal: &str) -> &mut GoalDecisions {
+        self.goals
+            .entry((agent.to_string(), goal.to_string()))
+            .or_default()
+    }
+
+    /// Returns the counts for an action of an agent, creating them if needed.
+    fn action_mut(&mut self, agent: &str, action: &str) -> &mut ActionDecisions {
+        self.actions
+            .entry((agent.to_string(), action.to_string()))
+            .or_default()
+    }
+}
+
+impl DecisionSink for DecisionStats {
+    fn record(&mut self, event: &DecisionEvent) {
+        match event {
+            DecisionEvent::GoalSelected { agent, goal } => self.goal_mut(agent, goal).selected += 1,
+            DecisionEvent::PlanFound { agent, goal, .. } => {
+                self.goal_mut(agent, goal).plans_found += 1;
+            }
+            DecisionEvent::PlanFailed { agent, goal, .. } => {
+                self.goal_mut(agent, goal).plans_failed += 1;
+            }
+            DecisionEvent::Replanned { agent, goal, .. } => self.goal_mut(agent, goal).replans += 1,
+            DecisionEvent::ActionSucceeded { agent, action } => {
+                self.action_mut(agent, action).succeeded += 1;
+            }
+            DecisionEvent::ActionFailed { agent, action, .. } => {
+                self.action_mut(agent, action).failed += 1;
+            }
+        }
+    }
+}
+
+impl Planner {
+    /// Finds a plan like [`Planner::plan`] and records the outcome for `agent` as
+    /// a [`DecisionEvent::PlanFound`] or [`DecisionEvent::PlanFailed`].
+    pub fn plan_logged<'s>(
+        &self,
+        agent: &str,
+        initial_state: impl Into<Cow<'s, State>>,
+        goal: &Goal,
+        actions: &[Action],
+        sink: &mut dyn DecisionSink,
+    ) -> Result<Plan, PlannerError> {
+        let result = self.plan(initial_state, goal, actions);
+        let event = match &result {
+            Ok(plan) => DecisionEvent::PlanFound {
+                agent: agent.to_string(),
+                goal: goal.name.clone(),
+                steps: plan.actions.len(),
+                cost: plan.cost,
+                nodes_expanded: plan.stats.nodes_expanded,
+            },
+            Err(error) => DecisionEvent::PlanFailed {
+                agent: agent.to_string(),
+                goal: goal.name.clone(),
+                error: error.to_string(),
+            },
+        };
+        sink.record(&event);
+        result
+    }
+}
@@ -0,0 +1,83 @@
+//! Validation of action and goal definitions.
+//!
+//! `ActionBuilder::try_build` and `GoalBuilder::try_build` reject definitions
+//! that are certainly authoring mistakes, so they surface when content is loaded
+//! rather than as plans that silently never work.
+
+use crate::state::{ConversionError, State, StateVar};
+use std::error::Error;
+use std::fmt;
+
+/// Errors found when validating an action or goal definition.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DefinitionError {
+    /// The action has an empty name
+    EmptyName,
+    /// The action cost is NaN, infinite or negative
+    InvalidCost(f64),
+    /// A floating-point value does not fit the fixed-point representation
+    OutOfRange {
+        /// The variable holding the value
+        key: String,
+    },
+    /// The same variable was given contradicting requirements
+    ConflictingRequirement {
+        /// The variable with contradicting requirements
+        key: String,
+    },
+    /// A strict goal has no requirements and is satisfied by every state
+    NoRequirements,
+    /// A strict builder was given a value that cannot be converted exactly
+    InvalidValue {
+        /// The variable the value was given for
+        key: String,
+        /// Why the conversion failed
+        error: ConversionError,
+    },
+}
+
+impl fmt::Display for DefinitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DefinitionError::EmptyName => write!(f, "Action name is empty"),
+            DefinitionError::InvalidCost(cost) => {
+                write!(f, "Invalid cost {cost}: must be finite and not negative")
+            }
+            DefinitionError::OutOfRange { key } => {
+                write!(f, "Value of '{key}' is out of the fixed-point range")
+            }
+            DefinitionError::ConflictingRequirement { key } => {
+                write!(f, "Conflicting requirements for '{key}'")
+            }
+            DefinitionError::NoRequirements => write!(f, "Goal has no requirements"),
+            DefinitionError::InvalidValue { key, error } => {
+                write!(f, "Invalid value for '{key}': {error}")
+            }
+        }
+    }
+}
+
+impl Error for DefinitionError {}
+
+/// Returns true if the value is an F64 that saturated the fixed-point range
+/// when it was converted.
+pub(crate) fn is_saturated(value: &StateVar) -> bool {
+    matches!(value, StateVar::F64(fixed) if *fixed == i64::MAX || *fixed == i64::MIN)
+}
+
+/// Checks that every F64 value in a state fits the fixed-point representation.
+pub(crate) fn check_range(state: &State) -> Result<(), DefinitionError> {
+    let mut saturated: Vec<&String> = state
+        .vars
+        .iter()
+        .filter(|(_, value)| is_saturated(value))
+        .map(|(key, _)| key)
+        .collect();
+    saturated.sort();
+    match saturated.first() {
+        Some(key) => Err(DefinitionError::OutOfRange {
+            key: (*key).clone(),
+        }),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,50 @@
+//! Dominance between states, used to prune resource-heavy searches.
+//!
+//! In domains about gathering resources, the search reaches many states that
+//! differ only in how much of something the agent holds. A state with the same
+//! booleans and strings as another and at least as much of every number, reached
+//! at no greater cost, can do everything the other can: with
+//! [`PlannerConfig::dominance_pruning`](crate::planner::PlannerConfig::dominance_pruning)
+//! enabled, the A* search drops such dominated states instead of exploring them.
+//!
+//! Pruning assumes that more of a number never hurts, which holds for the
+//! built-in preconditions and goal requirements (numbers are minimums). Domains
+//! with expression preconditions or postconditions capping a number, or goals
+//! excluding numeric values, should leave it disabled, since it may then discard
+//! the only way to the goal.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let rich = State::new().set("gold", 30).set("has_pick", true).build();
+//! let poor = State::new().set("gold", 10).set("has_pick", true).build();
+//! assert!(dominates(&rich, &poor));
+//! assert!(!dominates(&poor, &rich));
+//! ```
+
+use crate::state::{State, StateVar};
+
+/// Returns true if state `a` dominates state `b`: both have the same variables,
+/// with equal booleans and strings, and every number in `a` is at least the one
+/// in `b`. Every state dominates itself.
+pub fn dominates(a: &State, b: &State) -> bool {
+    a.vars.len() == b.vars.len() && a.satisfies(b)
+}
+
+/// Returns the part of a state that must be equal for one state to dominate
+/// another: its variables with every number replaced by zero.
+pub(crate) fn dominance_signature(state: &State) -> State {
+    State {
+        vars: state
+            .vars
+            .iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    StateVar::I64(_) | StateVar::F64(_) => StateVar::I64(0),
+                    other => other.clone(),
+                };
+                (key.clone(), value)
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,116 @@
+//! Exhaustive enumeration of plans for small domains.
+//!
+//! [`Planner::enumerate_plans`] lists every way of reaching a goal within a
+//! depth limit. The number of plans grows exponentially with the depth, so this
+//! is meant for unit-testing domains and for design tools, not for agents.
+
+use crate::actions::Action;
+use crate::goals::Goal;
+use crate::planner::{Plan, PlanStats, Planner, PlannerError, plan_assumptions};
+use crate::state::State;
+use std::borrow::Cow;
+
+impl Planner {
+    /// Returns every plan of at most `max_depth` actions that achieves the goal.
+    ///
+    /// Plans stop at the first step that satisfies the goal, and never visit the
+    /// same state twice, so loops and redundant trailing actions are not listed.
+    /// The plans are sorted by cost, then by length; plans with equal cost and
+    /// length keep the order in which the actions are given. An empty plan is
+    /// returned if the initial state already satisfies the goal.
+    pub fn enumerate_plans<'s>(
+        &self,
+        initial_state: impl Into<Cow<'s, State>>,
+        goal: &Goal,
+        actions: &[Action],
+        max_depth: usize,
+    ) -> Result<Vec<Plan>, PlannerError> {
+        let (initial_state, goal) = self.prepare_start(initial_state.into().into_owned(), goal);
+        self.check_problem(&initial_state, &goal, actions)?;
+        let actions = self.prepare_actions(actions);
+
+        let mut enumeration = Enumeration {
+            planner: self,
+            goal: &goal,
+            actions: &actions,
+            max_depth,
+            path: Vec::new(),
+            visited: vec![initial_state.clone()],
+            stats: PlanStats::default(),
+            plans: Vec::new(),
+        };
+        enumeration.visit(&initial_state);
+
+        let Enumeration {
+            mut plans, stats, ..
+        } = enumeration;
+        for plan in &mut plans {
+            plan.stats = stats.clone();
+        }
+        plans.sort_by(|a, b| {
+            a.cost
+                .total_cmp(&b.cost)
+                .then(a.actions.len().cmp(&b.actions.len()))
+        });
+        Ok(plans)
+    }
+}
+
+/// The working data of a depth-first enumeration.
+struct Enumeration<'a> {
+    /// The planner whose successor function is used
+    planner: &'a Planner,
+    /// The goal being enumerated
+    goal: &'a Goal,
+    /// The actions available to plans
+    actions: &'a [Action],
+    /// Maximum number of actions in a plan
+    max_depth: usize,
+    /// Indices of the actions leading to the current state
+    path: Vec<usize>,
+    /// States on the current path, including the initial state
+    visited: Vec<State>,
+    /// Counters reported on every plan
+    stats: PlanStats,
+    /// Plans found so far
+    plans: Vec<Plan>,
+}
+
+impl Enumeration<'_> {
+    /// Records a plan if `state` satisfies the goal, and otherwise extends the path.
+    fn visit(&mut self, state: &State) {
+        if self.goal.is_satisfied(state) {
+            let actions: Vec<Action> = self
+                .path
+                .iter()
+                .map(|&index| self.actions[index].clone())
+                .collect();
+            let cost = actions.iter().map(|action| action.cost).sum();
+            self.plans.push(Plan {
+                assumptions: plan_assumptions(&self.visited[0], self.goal, &actions),
+                actions,
+                cost,
+                stats: PlanStats::default(),
+                currencies: self.planner.currency_changes(&self.visited[0], state),
+            });
+            return;
+        }
+        if self.path.len() >= self.max_depth {
+            return;
+        }
+
+        self.stats.nodes_expanded += 1;
+        let (planner, actions) = (self.planner, self.actions);
+        for transition in planner.expand(state, actions) {
+            self.stats.nodes_generated += 1;
+            if self.visited.contains(&transition.state) {
+                continue;
+            }
+            self.path.push(transition.action_index);
+            self.visited.push(transition.state.clone());
+            self.visit(&transition.state);
+            self.visited.pop();
+            self.path.pop();
+        }
+    }
+}
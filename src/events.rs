@@ -0,0 +1,70 @@
+//! Change events for simulated and executed plans.
+//!
+//! Game systems such as UI, audio or achievements often want to react when a
+//! variable changes. [`Plan::simulate_with_events`] reports every change a plan
+//! is predicted to make, and [`key_changes`] reports the changes between two
+//! actual states, so callers do not have to diff states themselves.
+
+use crate::planner::Plan;
+use crate::state::{State, StateVar};
+
+/// An event emitted while simulating or executing a plan.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlanEvent {
+    /// A variable changed value, was added or was removed
+    KeyChanged {
+        /// The variable that changed
+        key: String,
+        /// The value before the step, or None if the variable was not set
+        old: Option<StateVar>,
+        /// The value after the step, or None if the variable was removed
+        new: Option<StateVar>,
+        /// Index of the plan step that caused the change
+        step: usize,
+    },
+}
+
+/// Returns a `KeyChanged` event for every variable that differs between `before`
+/// and `after`, sorted by key.
+///
+/// Use this when executing a plan to report the actual changes made by `step`.
+pub fn key_changes(before: &State, after: &State, step: usize) -> Vec<PlanEvent> {
+    let mut keys: Vec<&String> = before
+        .vars
+        .keys()
+        .chain(after.vars.keys())
+        .filter(|key| before.vars.get(*key) != after.vars.get(*key))
+        .collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .map(|key| PlanEvent::KeyChanged {
+            key: key.clone(),
+            old: before.vars.get(key).cloned(),
+            new: after.vars.get(key).cloned(),
+            step,
+        })
+        .collect()
+}
+
+impl Plan {
+    /// Applies each step of the plan to `initial_state` and passes every predicted
+    /// change to `on_event`, in step order. Returns the final state.
+    ///
+    /// Preconditions are not checked; the effects of every step are applied. To
+    /// forward events to another thread, send them through a channel from the callback.
+    pub fn simulate_with_events<F>(&self, initial_state: &State, mut on_event: F) -> State
+    where
+        F: FnMut(PlanEvent),
+    {
+        let mut state = initial_state.clone();
+        for (step, action) in self.actions.iter().enumerate() {
+            let next = action.apply_effect(&state);
+            for event in key_changes(&state, &next, step) {
+                on_event(event);
+            }
+            state = next;
+        }
+        state
+    }
+}
@@ -0,0 +1,533 @@
+//! Executing plans with a precondition watchdog.
+//!
+//! The world can change between planning and acting, so a [`PlanExecutor`]
+//! re-checks an action's preconditions against the live state before handing it
+//! out. How much it checks is chosen per executor with [`PreconditionCheck`] and
+//! can be overridden per action:
+//!
+//! - [`Strict`](PreconditionCheck::Strict) checks every precondition.
+//! - [`Optimistic`](PreconditionCheck::Optimistic) only checks preconditions on
+//!   variables reported as changed with [`PlanExecutor::notify_changed`]. All
+//!   other variables are assumed to hold the values predicted when planning, so
+//!   the check costs nothing while the world behaves as predicted.
+//! - [`Skip`](PreconditionCheck::Skip) performs no checks.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let goal = Goal::new("rich").requires("gold", 10).build();
+//! let actions = vec![
+//!     Action::new("work").requires("has_job", true).adds("gold", 10).build(),
+//! ];
+//! let mut state = State::new().set("has_job", true).set("gold", 0).build();
+//! let plan = Planner::new().plan(&state, &goal, &actions).unwrap();
+//!
+//! let mut executor = PlanExecutor::new(plan).mode(PreconditionCheck::Optimistic);
+//! state.set("has_job", false);
+//! executor.notify_changed("has_job");
+//! assert!(executor.next_action(&state).is_err());
+//! ```
+//!
+//! An executor's progress can be saved with the game: [`PlanExecutor::snapshot`]
+//! returns an [`ExecutorSnapshot`] naming the plan's actions, with a line-based
+//! text form, and [`PlanExecutor::restore`] resumes it mid-plan on load. Timers
+//! started with [`starts_timer`](crate::actions::ActionBuilder::starts_timer) live
+//! in the state and are saved with it.
+
+use crate::actions::Action;
+use crate::ownership::KeyOwnership;
+use crate::planner::Plan;
+use crate::state::{State, StateOperation, value_satisfies};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+
+/// How thoroughly preconditions are re-checked before an action is executed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PreconditionCheck {
+    /// Check every precondition against the current state (the default)
+    #[default]
+    Strict,
+    /// Only check preconditions on variables reported as changed since planning
+    Optimistic,
+    /// Perform no checks
+    Skip,
+}
+
+/// Errors reported by a [`PlanExecutor`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExecutionError {
+    /// The next action's preconditions do not hold in the current state
+    PreconditionFailed {
+        /// The index of the action in the plan
+        step: usize,
+        /// The name of the action
+        action: String,
+        /// The variables of the failed preconditions, sorted
+        keys: Vec<String>,
+    },
+    /// The next action modifies variables the executing agent neither owns nor shares
+    OwnershipViolated {
+        /// The index of the action in the plan
+        step: usize,
+        /// The name of the action
+        action: String,
+        /// The executing agent
+        agent: String,
+        /// The variables the agent may not modify, sorted
+        keys: Vec<String>,
+    },
+    /// An executed action left a state breaking its postconditions
+    PostconditionViolated {
+        /// The index of the action in the plan
+        step: usize,
+        /// The name of the action
+        action: String,
+        /// The postconditions that do not hold, as written
+        conditions: Vec<String>,
+    },
+}
+
+impl fmt::Display for ExecutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecutionError::PreconditionFailed { step, action, keys } => write!(
+                f,
+                "Preconditions of '{action}' at step {step} no longer hold: {}",
+                keys.join(", ")
+            ),
+            ExecutionError::OwnershipViolated {
+                step,
+                action,
+                agent,
+                keys,
+            } => write!(
+                f,
+                "'{action}' at step {step} modifies variables not owned by '{agent}': {}",
+                keys.join(", ")
+            ),
+            ExecutionError::PostconditionViolated {
+                step,
+                action,
+                conditions,
+            } => write!(
+                f,
+                "Postconditions of '{action}' at step {step} do not hold: {}",
+                conditions.join(", ")
+            ),
+        }
+    }
+}
+
+impl Error for ExecutionError {}
+
+/// Errors reported when restoring a [`PlanExecutor`] from a snapshot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RestoreError {
+    /// A line of a snapshot's text form could not be read
+    Malformed {
+        /// The 1-based line number
+        line: usize,
+    },
+    /// The snapshot names an action that is not among the given actions
+    UnknownAction(String),
+    /// The snapshot's step is beyond the end of its plan
+    StepOutOfRange {
+        /// The saved step
+        step: usize,
+        /// The number of actions in the saved plan
+        len: usize,
+    },
+}
+
+impl fmt::Display for RestoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RestoreError::Malformed { line } => write!(f, "Malformed snapshot line {line}"),
+            RestoreError::UnknownAction(name) => write!(f, "Unknown action '{name}'"),
+            RestoreError::StepOutOfRange { step, len } => {
+                write!(
+                    f,
+                    "Step {step} is beyond the end of a plan of {len} actions"
+                )
+            }
+        }
+    }
+}
+
+impl Error for RestoreError {}
+
+/// The progress of a [`PlanExecutor`] as plain data, for game saves.
+///
+/// Actions are referred to by name, since actions with dynamic effects hold code.
+/// The executor's checks, overrides and ownership are configuration rather than
+/// progress: apply them again after [`PlanExecutor::restore`]. Plan statistics
+/// and currency totals are not saved.
+///
+/// The text form written by `Display` and read by [`ExecutorSnapshot::parse`]
+/// has one `key value` pair per line:
+///
+/// ```text
+/// step 1
+/// cost 2
+/// action open_shop
+/// action buy_bread
+/// changed shop_open
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExecutorSnapshot {
+    /// The names of the plan's actions, in order
+    pub actions: Vec<String>,
+    /// The total cost of the plan
+    pub cost: f64,
+    /// The index of the next action
+    pub step: usize,
+    /// Variables reported as changed since planning, sorted
+    pub changed: Vec<String>,
+}
+
+impl ExecutorSnapshot {
+    /// Reads a snapshot from the text form written by `Display`. Blank lines are
+    /// ignored.
+    pub fn parse(text: &str) -> Result<Self, RestoreError> {
+        let mut snapshot = ExecutorSnapshot::default();
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let malformed = RestoreError::Malformed { line: index + 1 };
+            let (key, value) = line.split_once(' ').ok_or(malformed.clone())?;
+            match key {
+                "step" => snapshot.step = value.parse().map_err(|_| malformed)?,
+                "cost" => snapshot.cost = value.parse().map_err(|_| malformed)?,
+                "action" => snapshot.actions.push(value.to_string()),
+                "changed" => snapshot.changed.push(value.to_string()),
+                _ => return Err(malformed),
+            }
+        }
+        Ok(snapshot)
+    }
+}
+
+impl fmt::Display for ExecutorSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "step {}", self.step)?;
+        writeln!(f, "cost {}", self.cost)?;
+        for action in &self.actions {
+            writeln!(f, "action {action}")?;
+        }
+        for key in &self.changed {
+            writeln!(f, "changed {key}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Walks a plan, checking each action's preconditions before it is executed.
+#[derive(Clone, Debug)]
+pub struct PlanExecutor {
+    /// The plan being executed
+    plan: Plan,
+    /// The index of the next action
+    step: usize,
+    /// The check used for actions without an override
+    mode: PreconditionCheck,
+    /// Checks used for specific actions, by name
+    overrides: HashMap<String, PreconditionCheck>,
+    /// Variables whose value may differ from the one predicted when planning
+    changed: HashSet<String>,
+    /// The executing agent and the variables it may modify, if checked
+    ownership: Option<(String, KeyOwnership)>,
+}
+
+impl PlanExecutor {
+    /// Creates an executor positioned at the first action of the plan, using
+    /// [`PreconditionCheck::Strict`].
+    pub fn new(plan: Plan) -> Self {
+        PlanExecutor {
+            plan,
+            step: 0,
+            mode: PreconditionCheck::default(),
+            overrides: HashMap::new(),
+            changed: HashSet::new(),
+            ownership: None,
+        }
+    }
+
+    /// Sets the check used for actions without an override.
+    pub fn mode(mut self, mode: PreconditionCheck) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets the check used for every action with the given name.
+    pub fn override_mode(mut self, action: &str, mode: PreconditionCheck) -> Self {
+        self.overrides.insert(action.to_string(), mode);
+        self
+    }
+
+    /// Executes the plan on behalf of `agent`, rejecting actions that modify
+    /// variables the agent neither owns nor shares according to `ownership`.
+    /// The check runs whatever the precondition check mode.
+    pub fn ownership(mut self, agent: &str, ownership: KeyOwnership) -> Self {
+        self.ownership = Some((agent.to_string(), ownership));
+        self
+    }
+
+    /// Returns the check used for the given action.
+    pub fn mode_for(&self, action: &Action) -> PreconditionCheck {
+        self.overrides
+            .get(&action.name)
+            .copied()
+            .unwrap_or(self.mode)
+    }
+
+    /// Returns the plan being executed.
+    pub fn plan(&self) -> &Plan {
+        &self.plan
+    }
+
+    /// Returns the index of the next action in the plan.
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Returns true when every action of the plan has succeeded.
+    pub fn is_finished(&self) -> bool {
+        self.step >= self.plan.actions.len()
+    }
+
+    /// Reports that a variable may no longer hold the value predicted when planning,
+    /// e.g. because another agent or a world event changed it. Optimistic checks
+    /// only look at reported variables.
+    pub fn notify_changed(&mut self, key: &str) {
+        self.changed.insert(key.to_string());
+    }
+
+    /// Reports every variable whose value differs between two states.
+    pub fn notify_state_changes(&mut self, before: &State, after: &State) {
+        for (key, value) in &after.vars {
+            if before.vars.get(key) != Some(value) {
+                self.changed.insert(key.clone());
+            }
+        }
+        for key in before.vars.keys() {
+            if !after.vars.contains_key(key) {
+                self.changed.insert(key.clone());
+            }
+        }
+    }
+
+    /// Returns the variables of the plan's assumptions that `state` breaks at the
+    /// current step, sorted (see [`Plan::broken_assumptions`]). Checking only
+    /// these variables each tick is enough to tell whether the plan still works
+    /// as found.
+    pub fn broken_assumptions(&self, state: &State) -> Vec<&str> {
+        self.plan.broken_assumptions(self.step, state)
+    }
+
+    /// Returns the next action if its preconditions pass the configured check in
+    /// the current state and it only modifies variables the agent may modify, or
+    /// `None` when the plan is finished.
+    pub fn next_action(&self, state: &State) -> Result<Option<&Action>, ExecutionError> {
+        let Some(action) = self.plan.actions.get(self.step) else {
+            return Ok(None);
+        };
+        if let Some((agent, ownership)) = &self.ownership {
+            let keys = ownership.violations(agent, action);
+            if !keys.is_empty() {
+                return Err(ExecutionError::OwnershipViolated {
+                    step: self.step,
+                    action: action.name.clone(),
+                    agent: agent.clone(),
+                    keys,
+                });
+            }
+        }
+        let keys = match self.mode_for(action) {
+            PreconditionCheck::Strict => failed_keys(action, state, |_| true),
+            PreconditionCheck::Optimistic if self.changed.is_empty() => Vec::new(),
+            PreconditionCheck::Optimistic => {
+                failed_keys(action, state, |key| self.changed.contains(key))
+            }
+            PreconditionCheck::Skip => Vec::new(),
+        };
+        if keys.is_empty() {
+            Ok(Some(action))
+        } else {
+            Err(ExecutionError::PreconditionFailed {
+                step: self.step,
+                action: action.name.clone(),
+                keys,
+            })
+        }
+    }
+
+    /// Records that the next action was executed and moves past it.
+    ///
+    /// Variables the action sets are back to their predicted values. Variables it
+    /// computes from the state are treated as changed while any other variable is.
+    pub fn succeeded(&mut self) {
+        let Some(action) = self.plan.actions.get(self.step) else {
+            return;
+        };
+        for (key, operation) in &action.effects {
+            if matches!(operation, StateOperation::Set(_)) {
+                self.changed.remove(key);
+            }
+        }
+        if !self.changed.is_empty() {
+            self.changed.extend(action.dynamic_effects.keys().cloned());
+        }
+        self.step += 1;
+    }
+
+    /// Returns the executor's progress for saving; see [`ExecutorSnapshot`].
+    pub fn snapshot(&self) -> ExecutorSnapshot {
+        let mut changed: Vec<String> = self.changed.iter().cloned().collect();
+        changed.sort();
+        ExecutorSnapshot {
+            actions: self
+                .plan
+                .actions
+                .iter()
+                .map(|action| action.name.clone())
+                .collect(),
+            cost: self.plan.cost,
+            step: self.step,
+            changed,
+        }
+    }
+
+    /// Resumes a saved executor, looking up its actions by name in `actions`
+    /// (the first action with a name is used). The executor uses
+    /// [`PreconditionCheck::Strict`] until configured otherwise.
+    ///
+    /// ```
+    /// use goap::prelude::*;
+    ///
+    /// let actions = vec![
+    ///     Action::new("chop").sets("has_wood", true).build(),
+    ///     Action::new("build").requires("has_wood", true).sets("has_hut", true).build(),
+    /// ];
+    /// let goal = Goal::new("shelter").requires("has_hut", true).build();
+    /// let plan = Planner::new().plan(State::empty(), &goal, &actions).unwrap();
+    ///
+    /// let mut executor = PlanExecutor::new(plan);
+    /// executor.succeeded();
+    /// let saved = executor.snapshot().to_string();
+    ///
+    /// let snapshot = ExecutorSnapshot::parse(&saved).unwrap();
+    /// let restored = PlanExecutor::restore(&snapshot, &actions).unwrap();
+    /// assert_eq!(restored.step(), 1);
+    /// assert_eq!(restored.plan().actions[1].name, "build");
+    /// ```
+    pub fn restore(snapshot: &ExecutorSnapshot, actions: &[Action]) -> Result<Self, RestoreError> {
+        let plan_actions = snapshot
+            .actions
+            .iter()
+            .map(|name| {
+                actions
+                    .iter()
+                    .find(|action| action.name == *name)
+                    .cloned()
+                    .ok_or_else(|| RestoreError::UnknownAction(name.clone()))
+            })
+            .collect::<Result<Vec<Action>, RestoreError>>()?;
+        if snapshot.step > plan_actions.len() {
+            return Err(RestoreError::StepOutOfRange {
+                step: snapshot.step,
+                len: plan_actions.len(),
+            });
+        }
+        let mut executor = PlanExecutor::new(Plan {
+            actions: plan_actions,
+            cost: snapshot.cost,
+            ..Plan::default()
+        });
+        executor.step = snapshot.step;
+        executor.changed = snapshot.changed.iter().cloned().collect();
+        Ok(executor)
+    }
+
+    /// Records that the next action was executed, leading to `state`, and moves
+    /// past it like [`PlanExecutor::succeeded`].
+    ///
+    /// Returns an error if the action's postconditions do not hold in `state`,
+    /// which points at an action whose effects do not match the world. The
+    /// executor moves past the action either way, so the caller decides whether
+    /// to continue or replan.
+    pub fn completed(&mut self, state: &State) -> Result<(), ExecutionError> {
+        let Some(action) = self.plan.actions.get(self.step) else {
+            return Ok(());
+        };
+        let conditions: Vec<String> = action
+            .violated_postconditions(state)
+            .iter()
+            .map(|comparison| comparison.to_string())
+            .collect();
+        let (step, name) = (self.step, action.name.clone());
+        self.succeeded();
+        if conditions.is_empty() {
+            Ok(())
+        } else {
+            Err(ExecutionError::PostconditionViolated {
+                step,
+                action: name,
+                conditions,
+            })
+        }
+    }
+}
+
+/// Returns the sorted variables of the action's failed preconditions, only checking
+/// preconditions that involve a variable accepted by `check`.
+pub(crate) fn failed_keys(
+    action: &Action,
+    state: &State,
+    check: impl Fn(&str) -> bool,
+) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::new();
+    for (key, required) in &action.preconditions.vars {
+        if check(key)
+            && !state
+                .vars
+                .get(key)
+                .is_some_and(|current| value_satisfies(current, required))
+        {
+            keys.push(key.clone());
+        }
+    }
+    for comparison in &action.expr_preconditions {
+        let mut variables = comparison.lhs.variables();
+        variables.extend(comparison.rhs.variables());
+        if variables.iter().any(|key| check(key)) && !comparison.holds(state) {
+            keys.extend(variables.iter().map(|key| key.to_string()));
+        }
+    }
+    for group in &action.any_preconditions {
+        if group.iter().any(|(key, _)| check(key))
+            && !group.iter().any(|(key, required)| {
+                state
+                    .vars
+                    .get(key)
+                    .is_some_and(|current| value_satisfies(current, required))
+            })
+        {
+            keys.extend(group.iter().map(|(key, _)| key.clone()));
+        }
+    }
+    for key in &action.absent_preconditions {
+        if check(key) && state.vars.contains_key(key) {
+            keys.push(key.clone());
+        }
+    }
+    for (key, condition) in &action.conditions {
+        if check(key) && !condition.holds(state.vars.get(key)) {
+            keys.push(key.clone());
+        }
+    }
+    keys.sort();
+    keys.dedup();
+    keys
+}
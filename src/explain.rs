@@ -0,0 +1,143 @@
+//! Explaining why no plan was found.
+//!
+//! A bare [`PlannerError::NoPlanFound`] says nothing about what went wrong.
+//! [`Planner::plan_diagnosed`] plans like [`Planner::plan`], but on failure
+//! returns a [`PlanFailure`] combining the static analysis of the domain with what
+//! the search saw: the goal requirements no action sequence can meet (see
+//! [`analyze_goal_reachability`]), the actions that can never run (see
+//! [`analyze_deadlocks`]), and the explored state closest to the goal with the
+//! requirements it still misses.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let actions = vec![
+//!     Action::new("mine").requires("has_pickaxe", true).adds("ore", 1).build(),
+//!     Action::new("smelt").requires("ore", 1).subtracts("ore", 1).adds("iron", 1).build(),
+//! ];
+//! let goal = Goal::new("smith").requires("iron", 2).build();
+//! let state = State::new().set("has_pickaxe", false).set("ore", 1).set("iron", 0).build();
+//!
+//! let failure = Planner::new().plan_diagnosed(&state, &goal, &actions).unwrap_err();
+//! assert_eq!(failure.deadlocks[0].action, "mine");
+//! assert_eq!(failure.closest_state.get::<i64>("iron"), Some(1));
+//! println!("{failure}");
+//! ```
+
+use crate::actions::{Action, UnmetPrecondition};
+use crate::analysis::{BlockedCondition, Deadlock, analyze_deadlocks, analyze_goal_reachability};
+use crate::goals::Goal;
+use crate::observer::NoopObserver;
+use crate::planner::{Plan, Planner, PlannerError, Search};
+use crate::state::State;
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+
+/// Why a search found no plan, from [`Planner::plan_diagnosed`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlanFailure {
+    /// The error the planner failed with
+    pub error: PlannerError,
+    /// The goal requirements no state reachable from the initial state can meet
+    pub unreachable: Vec<BlockedCondition>,
+    /// The actions that can never run from the initial state
+    pub deadlocks: Vec<Deadlock>,
+    /// The plan to the explored state with the lowest heuristic distance to the
+    /// goal; empty if the search did not start
+    pub closest: Plan,
+    /// The explored state with the lowest heuristic distance to the goal
+    pub closest_state: State,
+    /// The goal requirements the closest state does not meet
+    pub unmet: Vec<UnmetPrecondition>,
+}
+
+impl fmt::Display for PlanFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+        if !self.unreachable.is_empty() {
+            write!(f, "\nUnreachable goal requirements:")?;
+            for condition in &self.unreachable {
+                let reason = if condition.has_producer {
+                    "no action produces a satisfying value"
+                } else {
+                    "no action produces this variable"
+                };
+                write!(
+                    f,
+                    "\n  - {}: requires {} ({reason})",
+                    condition.key, condition.required
+                )?;
+            }
+        }
+        for deadlock in &self.deadlocks {
+            write!(f, "\n{deadlock}")?;
+        }
+        if !self.unmet.is_empty() {
+            write!(
+                f,
+                "\nClosest state reached after {} steps misses:",
+                self.closest.actions.len()
+            )?;
+            for unmet in &self.unmet {
+                write!(f, "\n  - {unmet}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Error for PlanFailure {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl Planner {
+    /// Finds a plan like [`Planner::plan`] with an A* search, explaining any failure
+    /// with a [`PlanFailure`] (see the [`explain`](crate::explain) module).
+    ///
+    /// The analysis runs only on failure, over every action not excluded by the
+    /// configuration, so it is meant for development rather than every tick.
+    pub fn plan_diagnosed<'s>(
+        &self,
+        initial_state: impl Into<Cow<'s, State>>,
+        goal: &Goal,
+        actions: &[Action],
+    ) -> Result<Plan, Box<PlanFailure>> {
+        let (initial_state, goal) = self.prepare_start(initial_state.into().into_owned(), goal);
+        let actions = self.prepare_actions(actions);
+
+        let (error, closest) = match self.check_problem(&initial_state, &goal, &actions) {
+            Ok(()) => {
+                let dead_ends = self.dead_end_analysis(&goal, &actions);
+                let mut observer = NoopObserver;
+                let mut search =
+                    Search::new(self, &goal, &actions, dead_ends.as_ref(), &mut observer);
+                let error = match search
+                    .push_start(initial_state.clone())
+                    .and_then(|()| search.run())
+                {
+                    Ok(plan) => return Ok(plan),
+                    Err(error) => error,
+                };
+                let closest = search
+                    .closest()
+                    .map(|node| (search.reconstruct(node), search.node_state(node).clone()));
+                (error, closest)
+            }
+            Err(error) => (error, None),
+        };
+
+        let (closest, closest_state) =
+            closest.unwrap_or_else(|| (Plan::default(), initial_state.clone()));
+        Err(Box::new(PlanFailure {
+            error,
+            unreachable: analyze_goal_reachability(&actions, &initial_state, &goal),
+            deadlocks: analyze_deadlocks(&actions, &initial_state),
+            unmet: goal.unmet_requirements(&closest_state),
+            closest,
+            closest_state,
+        }))
+    }
+}
@@ -0,0 +1,609 @@
+use crate::state::{ConversionError, State, StateOperation, StateVar, round_scaled};
+use std::error::Error;
+use std::fmt;
+
+/// The maximum nesting depth of an expression: each parenthesis, sign and
+/// operator counts as one level. Deeper input is rejected before it can
+/// exhaust the stack.
+pub const MAX_NESTING: usize = 128;
+
+/// Errors that can occur when parsing or evaluating expressions.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ExprError {
+    /// The input contains a character that is not part of the expression language
+    UnexpectedChar {
+        /// Byte offset of the character in the input
+        position: usize,
+        /// The character found
+        found: char,
+    },
+    /// A token appeared where it is not allowed
+    UnexpectedToken {
+        /// Byte offset of the token in the input
+        position: usize,
+        /// The text found
+        found: String,
+    },
+    /// The input ended before the expression was complete
+    UnexpectedEnd,
+    /// The expression refers to a state variable that does not exist
+    UnknownVariable(String),
+    /// The expression refers to a state variable that is not numeric
+    NonNumericVariable(String),
+    /// The expression divides by zero
+    DivisionByZero,
+    /// The expression is nested more than [`MAX_NESTING`] levels deep
+    TooDeeplyNested {
+        /// Byte offset of the token exceeding the limit
+        position: usize,
+    },
+    /// The value of an assignment cannot be stored in its variable, e.g. because
+    /// it is NaN or too large
+    InvalidValue {
+        /// The variable being assigned
+        key: String,
+        /// Why the value cannot be stored
+        error: ConversionError,
+    },
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExprError::UnexpectedChar { position, found } => {
+                write!(f, "Unexpected character '{found}' at position {position}")
+            }
+            ExprError::UnexpectedToken { position, found } => {
+                write!(f, "Unexpected '{found}' at position {position}")
+            }
+            ExprError::UnexpectedEnd => write!(f, "Unexpected end of expression"),
+            ExprError::UnknownVariable(var) => write!(f, "State variable '{var}' not found"),
+            ExprError::NonNumericVariable(var) => {
+                write!(f, "State variable '{var}' is not numeric")
+            }
+            ExprError::DivisionByZero => write!(f, "Division by zero"),
+            ExprError::TooDeeplyNested { position } => write!(
+                f,
+                "Expression nested more than {MAX_NESTING} levels deep at position {position}"
+            ),
+            ExprError::InvalidValue { key, error } => {
+                write!(f, "Cannot assign to '{key}': {error}")
+            }
+        }
+    }
+}
+
+impl Error for ExprError {}
+
+/// Arithmetic operators supported in expressions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryOp {
+    /// Addition (`+`)
+    Add,
+    /// Subtraction (`-`)
+    Sub,
+    /// Multiplication (`*`)
+    Mul,
+    /// Division (`/`)
+    Div,
+}
+
+impl BinaryOp {
+    fn precedence(self) -> u8 {
+        match self {
+            BinaryOp::Add | BinaryOp::Sub => 1,
+            BinaryOp::Mul | BinaryOp::Div => 2,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+        }
+    }
+}
+
+/// An arithmetic expression over numeric state variables.
+///
+/// Integer and fixed-point float variables are both read as `f64`, so
+/// `gold * 1.5` works regardless of how `gold` is stored.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    /// A numeric literal
+    Number(f64),
+    /// A reference to a numeric state variable
+    Var(String),
+    /// Negation of a sub-expression
+    Neg(Box<Expr>),
+    /// A binary arithmetic operation
+    Binary {
+        /// The operator
+        op: BinaryOp,
+        /// The left operand
+        lhs: Box<Expr>,
+        /// The right operand
+        rhs: Box<Expr>,
+    },
+}
+
+impl Expr {
+    /// Parses an arithmetic expression such as `price * quantity + 5`.
+    pub fn parse(input: &str) -> Result<Self, ExprError> {
+        let mut parser = Parser::new(input)?;
+        let expr = parser.expr()?;
+        parser.finish()?;
+        Ok(expr)
+    }
+
+    /// Evaluates the expression against the given state.
+    pub fn evaluate(&self, state: &State) -> Result<f64, ExprError> {
+        match self {
+            Expr::Number(value) => Ok(*value),
+            Expr::Var(key) => match state.vars.get(key) {
+                Some(StateVar::I64(value)) => Ok(*value as f64),
+                Some(var @ StateVar::F64(_)) => Ok(var.as_f64().unwrap_or_default()),
+                Some(_) => Err(ExprError::NonNumericVariable(key.clone())),
+                None => Err(ExprError::UnknownVariable(key.clone())),
+            },
+            Expr::Neg(inner) => Ok(-inner.evaluate(state)?),
+            Expr::Binary { op, lhs, rhs } => {
+                let lhs = lhs.evaluate(state)?;
+                let rhs = rhs.evaluate(state)?;
+                match op {
+                    BinaryOp::Add => Ok(lhs + rhs),
+                    BinaryOp::Sub => Ok(lhs - rhs),
+                    BinaryOp::Mul => Ok(lhs * rhs),
+                    BinaryOp::Div if rhs == 0.0 => Err(ExprError::DivisionByZero),
+                    BinaryOp::Div => Ok(lhs / rhs),
+                }
+            }
+        }
+    }
+
+    /// Returns the names of all state variables referenced by this expression.
+    pub fn variables(&self) -> Vec<&str> {
+        let mut vars = Vec::new();
+        self.collect_variables(&mut vars);
+        vars
+    }
+
+    fn collect_variables<'a>(&'a self, vars: &mut Vec<&'a str>) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Var(key) => {
+                if !vars.contains(&key.as_str()) {
+                    vars.push(key);
+                }
+            }
+            Expr::Neg(inner) => inner.collect_variables(vars),
+            Expr::Binary { lhs, rhs, .. } => {
+                lhs.collect_variables(vars);
+                rhs.collect_variables(vars);
+            }
+        }
+    }
+
+    fn fmt_with_precedence(&self, f: &mut fmt::Formatter<'_>, parent: u8) -> fmt::Result {
+        match self {
+            Expr::Number(value) => write!(f, "{value}"),
+            Expr::Var(key) => write!(f, "{key}"),
+            Expr::Neg(inner) => {
+                write!(f, "-")?;
+                inner.fmt_with_precedence(f, 3)
+            }
+            Expr::Binary { op, lhs, rhs } => {
+                let precedence = op.precedence();
+                if precedence < parent {
+                    write!(f, "(")?;
+                }
+                lhs.fmt_with_precedence(f, precedence)?;
+                write!(f, " {} ", op.symbol())?;
+                // Right operands bind tighter so `a - (b - c)` keeps its parentheses
+                rhs.fmt_with_precedence(f, precedence + 1)?;
+                if precedence < parent {
+                    write!(f, ")")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_with_precedence(f, 0)
+    }
+}
+
+/// Comparison operators usable in expression preconditions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompareOp {
+    /// Less than (`<`)
+    Lt,
+    /// Less than or equal (`<=`)
+    Le,
+    /// Greater than (`>`)
+    Gt,
+    /// Greater than or equal (`>=`)
+    Ge,
+    /// Equal (`==`)
+    Eq,
+    /// Not equal (`!=`)
+    Ne,
+}
+
+impl CompareOp {
+    fn symbol(self) -> &'static str {
+        match self {
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+            CompareOp::Eq => "==",
+            CompareOp::Ne => "!=",
+        }
+    }
+}
+
+/// A comparison between two expressions, e.g. `gold >= price * quantity`.
+/// Used as a precondition that is checked against the current state.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comparison {
+    /// The left-hand side of the comparison
+    pub lhs: Expr,
+    /// The comparison operator
+    pub op: CompareOp,
+    /// The right-hand side of the comparison
+    pub rhs: Expr,
+}
+
+impl Comparison {
+    /// Parses a comparison such as `gold >= price * quantity`.
+    pub fn parse(input: &str) -> Result<Self, ExprError> {
+        let mut parser = Parser::new(input)?;
+        let lhs = parser.expr()?;
+        let op = match parser.next() {
+            Some((_, Token::Compare(op))) => op,
+            Some((position, token)) => {
+                return Err(ExprError::UnexpectedToken {
+                    position,
+                    found: token.to_string(),
+                });
+            }
+            None => return Err(ExprError::UnexpectedEnd),
+        };
+        let rhs = parser.expr()?;
+        parser.finish()?;
+        Ok(Comparison { lhs, op, rhs })
+    }
+
+    /// Evaluates the comparison against the given state.
+    pub fn evaluate(&self, state: &State) -> Result<bool, ExprError> {
+        let lhs = self.lhs.evaluate(state)?;
+        let rhs = self.rhs.evaluate(state)?;
+        Ok(match self.op {
+            CompareOp::Lt => lhs < rhs,
+            CompareOp::Le => lhs <= rhs,
+            CompareOp::Gt => lhs > rhs,
+            CompareOp::Ge => lhs >= rhs,
+            CompareOp::Eq => lhs == rhs,
+            CompareOp::Ne => lhs != rhs,
+        })
+    }
+
+    /// Returns true if the comparison holds for the given state.
+    /// Comparisons that cannot be evaluated (e.g. missing variables) do not hold.
+    pub fn holds(&self, state: &State) -> bool {
+        self.evaluate(state).unwrap_or(false)
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.lhs, self.op.symbol(), self.rhs)
+    }
+}
+
+/// Assignment operators usable in expression effects.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AssignOp {
+    /// Set the variable (`=`)
+    Set,
+    /// Add to the variable (`+=`)
+    Add,
+    /// Subtract from the variable (`-=`)
+    Subtract,
+}
+
+/// An effect written as an assignment, e.g. `gold -= price * quantity`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Assignment {
+    /// The state variable being modified
+    pub key: String,
+    /// How the value is combined with the variable
+    pub op: AssignOp,
+    /// The value computed from the current state
+    pub value: Expr,
+}
+
+impl Assignment {
+    /// Parses an assignment such as `gold -= price * quantity`.
+    pub fn parse(input: &str) -> Result<Self, ExprError> {
+        let mut parser = Parser::new(input)?;
+        let key = match parser.next() {
+            Some((_, Token::Ident(key))) => key,
+            Some((position, token)) => {
+                return Err(ExprError::UnexpectedToken {
+                    position,
+                    found: token.to_string(),
+                });
+            }
+            None => return Err(ExprError::UnexpectedEnd),
+        };
+        let op = match parser.next() {
+            Some((_, Token::Assign(op))) => op,
+            Some((position, token)) => {
+                return Err(ExprError::UnexpectedToken {
+                    position,
+                    found: token.to_string(),
+                });
+            }
+            None => return Err(ExprError::UnexpectedEnd),
+        };
+        let value = parser.expr()?;
+        parser.finish()?;
+        Ok(Assignment { key, op, value })
+    }
+
+    /// Computes the state operation this assignment performs on the given state.
+    /// The result is converted to the type of the target variable: fixed-point
+    /// for F64 variables, rounded integers otherwise. Returns
+    /// [`ExprError::InvalidValue`] if the result is NaN, infinite or out of range
+    /// for the variable.
+    pub fn to_operation(&self, state: &State) -> Result<StateOperation, ExprError> {
+        let value = self.value.evaluate(state)?;
+        let is_float = match state.vars.get(&self.key) {
+            Some(StateVar::F64(_)) => true,
+            Some(StateVar::I64(_)) => false,
+            Some(_) => return Err(ExprError::NonNumericVariable(self.key.clone())),
+            None => value.fract() != 0.0,
+        };
+        let (scale, target) = if is_float {
+            (1000.0, "F64")
+        } else {
+            (1.0, "I64")
+        };
+        let raw = round_scaled(value, scale, target).map_err(|error| ExprError::InvalidValue {
+            key: self.key.clone(),
+            error,
+        })?;
+        Ok(match (self.op, is_float) {
+            (AssignOp::Set, true) => StateOperation::Set(StateVar::F64(raw)),
+            (AssignOp::Set, false) => StateOperation::set_i64(raw),
+            (AssignOp::Add, _) => StateOperation::Add(raw),
+            (AssignOp::Subtract, _) => StateOperation::Subtract(raw),
+        })
+    }
+}
+
+impl fmt::Display for Assignment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self.op {
+            AssignOp::Set => "=",
+            AssignOp::Add => "+=",
+            AssignOp::Subtract => "-=",
+        };
+        write!(f, "{} {symbol} {}", self.key, self.value)
+    }
+}
+
+/// Tokens produced by the expression lexer.
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(BinaryOp),
+    Compare(CompareOp),
+    Assign(AssignOp),
+    LParen,
+    RParen,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(value) => write!(f, "{value}"),
+            Token::Ident(name) => write!(f, "{name}"),
+            Token::Op(op) => write!(f, "{}", op.symbol()),
+            Token::Compare(op) => write!(f, "{}", op.symbol()),
+            Token::Assign(AssignOp::Set) => write!(f, "="),
+            Token::Assign(AssignOp::Add) => write!(f, "+="),
+            Token::Assign(AssignOp::Subtract) => write!(f, "-="),
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+        }
+    }
+}
+
+/// Splits the input into positioned tokens.
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, ExprError> {
+    let chars: Vec<(usize, char)> = input.char_indices().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (position, c) = chars[i];
+        let next = chars.get(i + 1).map(|(_, c)| *c);
+        let (token, width) = match (c, next) {
+            (c, _) if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            (c, _) if c.is_ascii_digit() || c == '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].1.is_ascii_digit() || chars[i].1 == '.') {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().map(|(_, c)| c).collect();
+                let value = text.parse().map_err(|_| ExprError::UnexpectedToken {
+                    position,
+                    found: text.clone(),
+                })?;
+                tokens.push((position, Token::Number(value)));
+                continue;
+            }
+            (c, _) if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].1.is_alphanumeric() || chars[i].1 == '_' || chars[i].1 == '.')
+                {
+                    i += 1;
+                }
+                let name: String = chars[start..i].iter().map(|(_, c)| c).collect();
+                tokens.push((position, Token::Ident(name)));
+                continue;
+            }
+            ('<', Some('=')) => (Token::Compare(CompareOp::Le), 2),
+            ('>', Some('=')) => (Token::Compare(CompareOp::Ge), 2),
+            ('=', Some('=')) => (Token::Compare(CompareOp::Eq), 2),
+            ('!', Some('=')) => (Token::Compare(CompareOp::Ne), 2),
+            ('+', Some('=')) => (Token::Assign(AssignOp::Add), 2),
+            ('-', Some('=')) => (Token::Assign(AssignOp::Subtract), 2),
+            ('<', _) => (Token::Compare(CompareOp::Lt), 1),
+            ('>', _) => (Token::Compare(CompareOp::Gt), 1),
+            ('=', _) => (Token::Assign(AssignOp::Set), 1),
+            ('+', _) => (Token::Op(BinaryOp::Add), 1),
+            ('-', _) => (Token::Op(BinaryOp::Sub), 1),
+            ('*', _) => (Token::Op(BinaryOp::Mul), 1),
+            ('/', _) => (Token::Op(BinaryOp::Div), 1),
+            ('(', _) => (Token::LParen, 1),
+            (')', _) => (Token::RParen, 1),
+            (found, _) => return Err(ExprError::UnexpectedChar { position, found }),
+        };
+        tokens.push((position, token));
+        i += width;
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive descent parser over a token stream.
+struct Parser {
+    /// Remaining tokens, reversed so the next token can be popped
+    tokens: Vec<(usize, Token)>,
+    /// The nesting depth of the expression parsed so far
+    depth: usize,
+}
+
+impl Parser {
+    fn new(input: &str) -> Result<Self, ExprError> {
+        let mut tokens = tokenize(input)?;
+        tokens.reverse();
+        Ok(Parser { tokens, depth: 0 })
+    }
+
+    /// Enters one level of nesting for the token at `position`, failing past
+    /// [`MAX_NESTING`].
+    fn enter(&mut self, position: usize) -> Result<(), ExprError> {
+        if self.depth >= MAX_NESTING {
+            return Err(ExprError::TooDeeplyNested { position });
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn next(&mut self) -> Option<(usize, Token)> {
+        self.tokens.pop()
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.last().map(|(_, token)| token)
+    }
+
+    fn finish(&mut self) -> Result<(), ExprError> {
+        match self.next() {
+            None => Ok(()),
+            Some((position, token)) => Err(ExprError::UnexpectedToken {
+                position,
+                found: token.to_string(),
+            }),
+        }
+    }
+
+    /// expr := term (('+' | '-') term)*
+    fn expr(&mut self) -> Result<Expr, ExprError> {
+        let depth = self.depth;
+        let mut lhs = self.term()?;
+        while let Some(Token::Op(op @ (BinaryOp::Add | BinaryOp::Sub))) = self.peek() {
+            let op = *op;
+            if let Some((position, _)) = self.next() {
+                // Each operator nests the operations before it one level deeper
+                self.enter(position)?;
+            }
+            let rhs = self.term()?;
+            lhs = Expr::Binary {
+                op,
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+            };
+        }
+        self.depth = depth;
+        Ok(lhs)
+    }
+
+    /// term := factor (('*' | '/') factor)*
+    fn term(&mut self) -> Result<Expr, ExprError> {
+        let depth = self.depth;
+        let mut lhs = self.factor()?;
+        while let Some(Token::Op(op @ (BinaryOp::Mul | BinaryOp::Div))) = self.peek() {
+            let op = *op;
+            if let Some((position, _)) = self.next() {
+                // Each operator nests the operations before it one level deeper
+                self.enter(position)?;
+            }
+            let rhs = self.factor()?;
+            lhs = Expr::Binary {
+                op,
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+            };
+        }
+        self.depth = depth;
+        Ok(lhs)
+    }
+
+    /// factor := number | identifier | '-' factor | '(' expr ')'
+    fn factor(&mut self) -> Result<Expr, ExprError> {
+        match self.next() {
+            Some((_, Token::Number(value))) => Ok(Expr::Number(value)),
+            Some((_, Token::Ident(name))) => Ok(Expr::Var(name)),
+            Some((position, Token::Op(BinaryOp::Sub))) => {
+                self.enter(position)?;
+                let inner = self.factor()?;
+                self.depth -= 1;
+                Ok(Expr::Neg(Box::new(inner)))
+            }
+            Some((position, Token::LParen)) => {
+                self.enter(position)?;
+                let inner = self.expr()?;
+                self.depth -= 1;
+                match self.next() {
+                    Some((_, Token::RParen)) => Ok(inner),
+                    Some((position, token)) => Err(ExprError::UnexpectedToken {
+                        position,
+                        found: token.to_string(),
+                    }),
+                    None => Err(ExprError::UnexpectedEnd),
+                }
+            }
+            Some((position, token)) => Err(ExprError::UnexpectedToken {
+                position,
+                found: token.to_string(),
+            }),
+            None => Err(ExprError::UnexpectedEnd),
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! Fallback behaviours for agents whose goals are all unplannable.
+//!
+//! An agent whose every goal fails to plan would otherwise get an error every
+//! tick and stand frozen. A [`Fallback`] holds low-priority goals with actions
+//! that always achieve them, such as idling or wandering, and
+//! [`Planner::plan_or_fallback`] plans for the first achievable real goal by
+//! priority, falling back to these behaviours when none is.
+//!
+//! The built-in behaviours mark their completion in the variables [`IDLE_KEY`]
+//! and [`WANDER_KEY`], which the game can read to play the matching animation.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let goals = vec![Goal::new("eat").requires("fed", true).priority(3).build()];
+//! let actions = vec![Action::new("eat_bread").requires("has_bread", true).sets("fed", true).build()];
+//! let state = State::new().set("has_bread", false).build();
+//!
+//! let choice = Planner::new()
+//!     .plan_or_fallback(state, &goals, &actions, &Fallback::idle(0.5))
+//!     .unwrap();
+//! assert!(choice.fallback);
+//! assert_eq!(choice.goal, "idle");
+//! assert_eq!(choice.plan.actions[0].name, "idle");
+//! ```
+
+use crate::actions::Action;
+use crate::goals::Goal;
+use crate::planner::{Plan, Planner, PlannerError};
+use crate::state::State;
+use std::borrow::Cow;
+
+/// The variable set by the built-in idle action.
+pub const IDLE_KEY: &str = "fallback_idled";
+
+/// The variable set by the built-in wander action.
+pub const WANDER_KEY: &str = "fallback_wandered";
+
+/// Low-priority goals, and the actions achieving them, that agents fall back to.
+#[derive(Clone, Debug, Default)]
+pub struct Fallback {
+    /// The fallback goals, tried in order
+    goals: Vec<Goal>,
+    /// The actions added to the agent's actions when planning fallback goals
+    actions: Vec<Action>,
+}
+
+impl Fallback {
+    /// Creates an empty set of fallback behaviours.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates the built-in idle behaviour: an `idle` goal of priority 0 and an
+    /// `idle` action with the given cost and no preconditions.
+    pub fn idle(cost: f64) -> Self {
+        Self::new().behavior(
+            Goal::new("idle")
+                .requires(IDLE_KEY, true)
+                .priority(0)
+                .build(),
+            Action::new("idle").sets(IDLE_KEY, true).cost(cost).build(),
+        )
+    }
+
+    /// Adds the built-in wander behaviour: a `wander` goal of priority 0 and a
+    /// `wander` action with the given cost and no preconditions. It is tried
+    /// after the behaviours already added.
+    pub fn wander(self, cost: f64) -> Self {
+        self.behavior(
+            Goal::new("wander")
+                .requires(WANDER_KEY, true)
+                .priority(0)
+                .build(),
+            Action::new("wander")
+                .sets(WANDER_KEY, true)
+                .cost(cost)
+                .build(),
+        )
+    }
+
+    /// Adds a custom behaviour: a goal and an action helping to achieve it.
+    pub fn behavior(mut self, goal: Goal, action: Action) -> Self {
+        self.goals.push(goal);
+        self.actions.push(action);
+        self
+    }
+
+    /// Returns the fallback goals, in the order they are tried.
+    pub fn goals(&self) -> &[Goal] {
+        &self.goals
+    }
+
+    /// Returns the actions of the fallback behaviours.
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+}
+
+/// The goal an agent should pursue and the plan achieving it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GoalChoice {
+    /// The name of the chosen goal
+    pub goal: String,
+    /// The plan achieving the goal
+    pub plan: Plan,
+    /// True if no real goal could be planned and a fallback goal was chosen
+    pub fallback: bool,
+}
+
+impl Planner {
+    /// Plans for the highest-priority goal that can be achieved, trying goals of
+    /// equal priority in the given order, and falls back to the fallback goals
+    /// when none can.
+    ///
+    /// Goals the state already satisfies are skipped. Fallback goals are planned
+    /// with the fallback actions added to `actions`, from the state with the
+    /// goal's variables removed, so a behaviour such as idling is chosen again on
+    /// every call even after its effects were applied to the world.
+    ///
+    /// Returns the error of the first real goal that failed if every fallback
+    /// goal fails too, or [`PlannerError::NoPlanFound`] if there was none.
+    pub fn plan_or_fallback<'s>(
+        &self,
+        state: impl Into<Cow<'s, State>>,
+        goals: &[Goal],
+        actions: &[Action],
+        fallback: &Fallback,
+    ) -> Result<GoalChoice, PlannerError> {
+        let state = state.into();
+        let mut ordered: Vec<&Goal> = goals.iter().collect();
+        ordered.sort_by_key(|goal| std::cmp::Reverse(goal.priority));
+
+        let mut first_error = None;
+        for goal in ordered {
+            if goal.is_satisfied(&state) {
+                continue;
+            }
+            match self.plan(&*state, goal, actions) {
+                Ok(plan) => {
+                    return Ok(GoalChoice {
+                        goal: goal.name.clone(),
+                        plan,
+                        fallback: false,
+                    });
+                }
+                Err(error) => {
+                    first_error.get_or_insert(error);
+                }
+            }
+        }
+
+        let mut all_actions = actions.to_vec();
+        all_actions.extend_from_slice(&fallback.actions);
+        for goal in &fallback.goals {
+            let mut fresh = state.clone().into_owned();
+            for key in goal.desired_state.vars.keys() {
+                fresh.vars.remove(key);
+            }
+            if let Ok(plan) = self.plan(fresh, goal, &all_actions) {
+                return Ok(GoalChoice {
+                    goal: goal.name.clone(),
+                    plan,
+                    fallback: true,
+                });
+            }
+        }
+        Err(first_error.unwrap_or(PlannerError::NoPlanFound))
+    }
+}
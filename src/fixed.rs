@@ -0,0 +1,511 @@
+//! Allocation-free planning for domains with a fixed number of variables.
+//!
+//! Some targets, such as robot control loops, must not allocate while running.
+//! When a domain is known at compile time to have at most `N` variables, its
+//! state fits in a [`FixedState<N>`]: an array of `N` integer slots, with
+//! booleans stored as 0 and 1 and fixed-point floats in their raw form (see
+//! [`StateVar::F64`](crate::state::StateVar::F64)). Actions and goals refer to
+//! slots by index and are declared with `'static` data like the
+//! [`static_domain`](crate::static_domain) types.
+//!
+//! A [`FixedSearch<N, CAP>`] holds every buffer an A* search needs for up to
+//! `CAP` states. Create it once, e.g. at start-up or in a `static`, and reuse it
+//! for every plan: planning never touches the heap.
+//!
+//! ```
+//! use goap::fixed::{FixedAction, FixedCondition, FixedOp, FixedSearch, FixedState};
+//!
+//! const HAS_PART: usize = 0;
+//! const ASSEMBLED: usize = 1;
+//!
+//! static ACTIONS: [FixedAction; 2] = [
+//!     FixedAction::new("fetch_part").effects(&[FixedOp::Set(HAS_PART, 1)]),
+//!     FixedAction::new("assemble")
+//!         .requires(&[FixedCondition::Equals(HAS_PART, 1)])
+//!         .effects(&[FixedOp::Set(ASSEMBLED, 1), FixedOp::Set(HAS_PART, 0)]),
+//! ];
+//!
+//! let mut search = FixedSearch::<2, 64>::new();
+//! let goal = [FixedCondition::Equals(ASSEMBLED, 1)];
+//! let plan = search.plan(FixedState::new([0, 0]), &goal, &ACTIONS).unwrap();
+//! assert_eq!(plan.steps, [0, 1]);
+//! assert_eq!(plan.cost, 2.0);
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+/// Marks a missing node or action index.
+const NONE: usize = usize::MAX;
+
+/// A condition on one slot of a [`FixedState`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FixedCondition {
+    /// The slot holds exactly the value; use this for booleans
+    Equals(usize, i64),
+    /// The slot holds at least the value
+    AtLeast(usize, i64),
+}
+
+impl FixedCondition {
+    /// Returns the slot the condition is on.
+    pub const fn slot(self) -> usize {
+        match self {
+            FixedCondition::Equals(slot, _) | FixedCondition::AtLeast(slot, _) => slot,
+        }
+    }
+
+    /// Returns true if the condition holds for the values.
+    fn holds(self, values: &[i64]) -> bool {
+        match self {
+            FixedCondition::Equals(slot, value) => values[slot] == value,
+            FixedCondition::AtLeast(slot, value) => values[slot] >= value,
+        }
+    }
+
+    /// Returns how far the values are from meeting the condition: one for an
+    /// unequal value, the shortfall for a minimum.
+    fn distance(self, values: &[i64]) -> u64 {
+        match self {
+            FixedCondition::Equals(slot, value) => u64::from(values[slot] != value),
+            FixedCondition::AtLeast(slot, value) => {
+                value.saturating_sub(values[slot]).max(0) as u64
+            }
+        }
+    }
+}
+
+/// An effect on one slot of a [`FixedState`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FixedOp {
+    /// Set the slot to a value
+    Set(usize, i64),
+    /// Add to the slot; subtract with a negative amount
+    Add(usize, i64),
+}
+
+impl FixedOp {
+    /// Returns the slot the effect changes.
+    pub const fn slot(self) -> usize {
+        match self {
+            FixedOp::Set(slot, _) | FixedOp::Add(slot, _) => slot,
+        }
+    }
+}
+
+/// An action over a [`FixedState`], backed entirely by static data.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FixedAction {
+    /// The name of the action
+    pub name: &'static str,
+    /// The cost of executing this action
+    pub cost: f64,
+    /// The conditions that must hold before this action can be executed
+    pub preconditions: &'static [FixedCondition],
+    /// The state changes that occur when this action is executed
+    pub effects: &'static [FixedOp],
+}
+
+impl FixedAction {
+    /// Creates an action with the given name, a cost of 1.0 and no preconditions or effects.
+    pub const fn new(name: &'static str) -> Self {
+        FixedAction {
+            name,
+            cost: 1.0,
+            preconditions: &[],
+            effects: &[],
+        }
+    }
+
+    /// Sets the cost of this action.
+    pub const fn cost(mut self, cost: f64) -> Self {
+        self.cost = cost;
+        self
+    }
+
+    /// Sets the preconditions of this action.
+    pub const fn requires(mut self, preconditions: &'static [FixedCondition]) -> Self {
+        self.preconditions = preconditions;
+        self
+    }
+
+    /// Sets the effects of this action.
+    pub const fn effects(mut self, effects: &'static [FixedOp]) -> Self {
+        self.effects = effects;
+        self
+    }
+}
+
+/// A state of exactly `N` integer slots, stored inline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FixedState<const N: usize> {
+    /// The value of each slot
+    values: [i64; N],
+}
+
+impl<const N: usize> FixedState<N> {
+    /// Creates a state from the value of each slot.
+    pub const fn new(values: [i64; N]) -> Self {
+        FixedState { values }
+    }
+
+    /// Returns the value of a slot. Panics if the slot is out of range.
+    pub const fn get(&self, slot: usize) -> i64 {
+        self.values[slot]
+    }
+
+    /// Sets the value of a slot. Panics if the slot is out of range.
+    pub fn set(&mut self, slot: usize, value: i64) {
+        self.values[slot] = value;
+    }
+
+    /// Returns the value of every slot.
+    pub const fn values(&self) -> &[i64; N] {
+        &self.values
+    }
+
+    /// Returns true if every condition holds.
+    pub fn satisfies(&self, conditions: &[FixedCondition]) -> bool {
+        conditions
+            .iter()
+            .all(|condition| condition.holds(&self.values))
+    }
+
+    /// Returns the state after applying the effects.
+    pub fn apply(&self, effects: &[FixedOp]) -> Self {
+        let mut next = *self;
+        for effect in effects {
+            match *effect {
+                FixedOp::Set(slot, value) => next.values[slot] = value,
+                FixedOp::Add(slot, amount) => {
+                    next.values[slot] = next.values[slot].saturating_add(amount);
+                }
+            }
+        }
+        next
+    }
+
+    /// Hashes the slots without allocating or depending on a hasher's state.
+    fn hash_slots(&self) -> u64 {
+        // FNV-1a over whole slots
+        self.values
+            .iter()
+            .fold(0xcbf2_9ce4_8422_2325, |hash, value| {
+                (hash ^ *value as u64).wrapping_mul(0x0000_0100_0000_01b3)
+            })
+    }
+}
+
+/// Errors reported by a [`FixedSearch`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FixedPlanError {
+    /// No sequence of actions reaches the goal
+    NoPlanFound,
+    /// The search needed more than the workspace's capacity of states
+    CapacityExceeded,
+    /// A goal condition, precondition or effect refers to a slot beyond the state size
+    InvalidSlot {
+        /// The out-of-range slot
+        slot: usize,
+    },
+}
+
+impl fmt::Display for FixedPlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FixedPlanError::NoPlanFound => write!(f, "No plan found"),
+            FixedPlanError::CapacityExceeded => {
+                write!(f, "The search needed more states than the workspace holds")
+            }
+            FixedPlanError::InvalidSlot { slot } => {
+                write!(f, "Slot {slot} is beyond the size of the state")
+            }
+        }
+    }
+}
+
+impl Error for FixedPlanError {}
+
+/// A plan found by a [`FixedSearch`], borrowing the workspace.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FixedPlan<'a> {
+    /// The indices of the actions to perform, in order
+    pub steps: &'a [usize],
+    /// The total cost of the actions
+    pub cost: f64,
+    /// Number of states expanded by the search
+    pub nodes_expanded: usize,
+}
+
+/// A node of a fixed search.
+#[derive(Clone, Copy, Debug)]
+struct FixedNode<const N: usize> {
+    /// The state this node represents
+    state: FixedState<N>,
+    /// Index of the node this one was reached from
+    parent: usize,
+    /// Index of the action that produced this node
+    action: usize,
+    /// Cost of the cheapest known path to this node
+    g: f64,
+    /// Heuristic estimate of the remaining cost
+    h: f64,
+    /// Position in the open heap, or NONE if not queued
+    heap_index: usize,
+}
+
+impl<const N: usize> FixedNode<N> {
+    /// An unused node slot.
+    const EMPTY: Self = FixedNode {
+        state: FixedState { values: [0; N] },
+        parent: NONE,
+        action: NONE,
+        g: 0.0,
+        h: 0.0,
+        heap_index: NONE,
+    };
+
+    /// The node's f-score.
+    fn f(&self) -> f64 {
+        self.g + self.h
+    }
+}
+
+/// A reusable A* workspace for states of `N` slots, holding up to `CAP` states.
+///
+/// All buffers are arrays inside the workspace, so it takes roughly
+/// `CAP * (8 * N + 72)` bytes wherever it is placed; planning does not allocate.
+pub struct FixedSearch<const N: usize, const CAP: usize> {
+    /// All discovered nodes; the first `len` are in use
+    nodes: [FixedNode<N>; CAP],
+    /// Number of nodes in use
+    len: usize,
+    /// Binary min-heap of node indices ordered by f-score
+    heap: [usize; CAP],
+    /// Number of queued nodes
+    heap_len: usize,
+    /// Open-addressing table of node indices, two slots per bucket
+    table: [[usize; 2]; CAP],
+    /// The action indices of the last plan found
+    path: [usize; CAP],
+    /// Number of nodes expanded by the last search
+    expanded: usize,
+}
+
+impl<const N: usize, const CAP: usize> Default for FixedSearch<N, CAP> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize, const CAP: usize> FixedSearch<N, CAP> {
+    /// Creates an empty workspace.
+    pub const fn new() -> Self {
+        FixedSearch {
+            nodes: [FixedNode::EMPTY; CAP],
+            len: 0,
+            heap: [NONE; CAP],
+            heap_len: 0,
+            table: [[NONE; 2]; CAP],
+            path: [NONE; CAP],
+            expanded: 0,
+        }
+    }
+
+    /// Finds the cheapest sequence of actions from `start` to a state meeting
+    /// every goal condition.
+    ///
+    /// The heuristic counts one per unequal slot and the shortfall of each
+    /// minimum, like the regular planner's. Returns
+    /// [`FixedPlanError::CapacityExceeded`] when the search needs more than `CAP`
+    /// states.
+    pub fn plan(
+        &mut self,
+        start: FixedState<N>,
+        goal: &[FixedCondition],
+        actions: &[FixedAction],
+    ) -> Result<FixedPlan<'_>, FixedPlanError> {
+        let slots = goal
+            .iter()
+            .map(|condition| condition.slot())
+            .chain(actions.iter().flat_map(|action| {
+                let preconditions = action.preconditions.iter().map(|c| c.slot());
+                preconditions.chain(action.effects.iter().map(|effect| effect.slot()))
+            }));
+        for slot in slots {
+            if slot >= N {
+                return Err(FixedPlanError::InvalidSlot { slot });
+            }
+        }
+
+        if CAP == 0 {
+            return Err(FixedPlanError::CapacityExceeded);
+        }
+        self.len = 0;
+        self.heap_len = 0;
+        self.expanded = 0;
+        self.table = [[NONE; 2]; CAP];
+        self.discover(start, NONE, NONE, 0.0, goal)?;
+
+        while let Some(current) = self.pop() {
+            let node = self.nodes[current];
+            if node.state.satisfies(goal) {
+                return Ok(self.reconstruct(current));
+            }
+            self.expanded += 1;
+            for (index, action) in actions.iter().enumerate() {
+                if !node.state.satisfies(action.preconditions) {
+                    continue;
+                }
+                let next = node.state.apply(action.effects);
+                self.discover(next, current, index, node.g + action.cost, goal)?;
+            }
+        }
+        Err(FixedPlanError::NoPlanFound)
+    }
+
+    /// Records a path to `state`, queueing it if it is new or cheaper.
+    fn discover(
+        &mut self,
+        state: FixedState<N>,
+        parent: usize,
+        action: usize,
+        g: f64,
+        goal: &[FixedCondition],
+    ) -> Result<(), FixedPlanError> {
+        let node = match self.lookup(&state) {
+            Ok(existing) => {
+                if g >= self.nodes[existing].g {
+                    return Ok(());
+                }
+                let node = &mut self.nodes[existing];
+                node.parent = parent;
+                node.action = action;
+                node.g = g;
+                existing
+            }
+            Err(_) if self.len == CAP => return Err(FixedPlanError::CapacityExceeded),
+            Err((bucket, slot)) => {
+                let h: u64 = goal
+                    .iter()
+                    .map(|condition| condition.distance(&state.values))
+                    .sum();
+                let node = self.len;
+                self.nodes[node] = FixedNode {
+                    state,
+                    parent,
+                    action,
+                    g,
+                    h: h as f64,
+                    heap_index: NONE,
+                };
+                self.len += 1;
+                self.table[bucket][slot] = node;
+                node
+            }
+        };
+
+        match self.nodes[node].heap_index {
+            NONE => {
+                let position = self.heap_len;
+                self.heap[position] = node;
+                self.nodes[node].heap_index = position;
+                self.heap_len += 1;
+                self.sift_up(position);
+            }
+            position => self.sift_up(position),
+        }
+        Ok(())
+    }
+
+    /// Finds the node holding `state`, or the free table slot to insert it at.
+    fn lookup(&self, state: &FixedState<N>) -> Result<usize, (usize, usize)> {
+        let start = (state.hash_slots() % CAP as u64) as usize;
+        for probe in 0..CAP {
+            let bucket = (start + probe) % CAP;
+            for slot in 0..2 {
+                match self.table[bucket][slot] {
+                    NONE => return Err((bucket, slot)),
+                    node if self.nodes[node].state == *state => return Ok(node),
+                    _ => {}
+                }
+            }
+        }
+        // The table has twice as many slots as there are nodes, so it never fills
+        unreachable!("fixed search table is full")
+    }
+
+    /// Returns true if the node at heap position `a` should be expanded before `b`.
+    fn before(&self, a: usize, b: usize) -> bool {
+        let (a, b) = (&self.nodes[self.heap[a]], &self.nodes[self.heap[b]]);
+        a.f() < b.f() || (a.f() == b.f() && a.h < b.h)
+    }
+
+    /// Swaps two heap positions, keeping the nodes' positions up to date.
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.nodes[self.heap[a]].heap_index = a;
+        self.nodes[self.heap[b]].heap_index = b;
+    }
+
+    /// Moves a heap entry up until its parent comes before it.
+    fn sift_up(&mut self, mut position: usize) {
+        while position > 0 {
+            let parent = (position - 1) / 2;
+            if !self.before(position, parent) {
+                break;
+            }
+            self.swap(position, parent);
+            position = parent;
+        }
+    }
+
+    /// Removes and returns the node with the lowest f-score.
+    fn pop(&mut self) -> Option<usize> {
+        if self.heap_len == 0 {
+            return None;
+        }
+        let top = self.heap[0];
+        self.heap_len -= 1;
+        self.swap(0, self.heap_len);
+        self.nodes[top].heap_index = NONE;
+
+        let mut position = 0;
+        loop {
+            let (left, right) = (2 * position + 1, 2 * position + 2);
+            let mut first = position;
+            if left < self.heap_len && self.before(left, first) {
+                first = left;
+            }
+            if right < self.heap_len && self.before(right, first) {
+                first = right;
+            }
+            if first == position {
+                break;
+            }
+            self.swap(position, first);
+            position = first;
+        }
+        Some(top)
+    }
+
+    /// Writes the actions leading to the node into the path buffer.
+    fn reconstruct(&mut self, target: usize) -> FixedPlan<'_> {
+        let mut steps = 0;
+        let mut current = target;
+        while self.nodes[current].parent != NONE {
+            steps += 1;
+            current = self.nodes[current].parent;
+        }
+        let mut current = target;
+        for step in (0..steps).rev() {
+            self.path[step] = self.nodes[current].action;
+            current = self.nodes[current].parent;
+        }
+        FixedPlan {
+            steps: &self.path[..steps],
+            cost: self.nodes[target].g,
+            nodes_expanded: self.expanded,
+        }
+    }
+}
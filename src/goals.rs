@@ -1,4 +1,10 @@
-use crate::state::{IntoStateVar, State};
+use crate::actions::UnmetPrecondition;
+use crate::definition::{DefinitionError, check_range};
+use crate::state::{
+    Condition, ConversionError, EnumKey, EnumStateVar, IntoStateVar, State, StateVar,
+    sorted_entries, value_satisfies,
+};
+use crate::units::{Quantity, QuantityKey};
 use std::fmt;
 
 /// A goal is a desired state of the world that an agent wants to achieve.
@@ -8,8 +14,36 @@ pub struct Goal {
     pub name: String,
     /// The desired state of the world that this goal represents.
     pub desired_state: State,
+    /// Values that variables must not hold for the goal to be achieved.
+    pub excluded: State,
+    /// Numeric requirements relative to the initial state, resolved when planning.
+    pub relative: State,
+    /// Comparisons variables must meet, such as `noise_level < 3`.
+    pub conditions: Vec<(String, Condition)>,
     /// The priority of this goal. Higher values indicate higher priority.
     pub priority: u16,
+    /// The name of the planner profile to search with, if any.
+    pub profile: Option<String>,
+    /// Preferred orders in which to achieve the requirements.
+    pub ordering: Vec<OrderingHint>,
+}
+
+/// A preference to achieve one requirement of a goal before another, such as
+/// "be hidden before moving into position".
+///
+/// The preference is soft: the planner adds `penalty` to the cost of every step
+/// that achieves the `then` requirement while the `first` requirement is unmet,
+/// so it picks an ordering-respecting plan unless violating the order saves more
+/// than the penalty. Plan costs only count action costs, without penalties.
+/// The A* and beam searches honour hints; the other strategies ignore them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrderingHint {
+    /// The requirement to achieve first
+    pub first: String,
+    /// The requirement to achieve afterwards
+    pub then: String,
+    /// The cost added to a step achieving `then` while `first` is unmet
+    pub penalty: f64,
 }
 
 impl fmt::Display for Goal {
@@ -17,10 +51,26 @@ impl fmt::Display for Goal {
         write!(f, "Goal '{}' (priority: {})", self.name, self.priority)?;
         if !self.desired_state.vars.is_empty() {
             write!(f, " desired state:")?;
-            for (key, value) in &self.desired_state.vars {
+            for (key, value) in sorted_entries(&self.desired_state.vars) {
                 write!(f, "\n  - {key}: {value}")?;
             }
         }
+        for (key, value) in sorted_entries(&self.excluded.vars) {
+            write!(f, "\n  - {key}: not {value}")?;
+        }
+        for (key, value) in sorted_entries(&self.relative.vars) {
+            write!(f, "\n  - {key}: {value} more than initially")?;
+        }
+        for (key, condition) in &self.conditions {
+            write!(f, "\n  - {key} {condition}")?;
+        }
+        for hint in &self.ordering {
+            write!(
+                f,
+                "\n  - prefer {} before {} (penalty {:.1})",
+                hint.first, hint.then, hint.penalty
+            )?;
+        }
         Ok(())
     }
 }
@@ -38,15 +88,181 @@ impl Goal {
         Goal {
             name: name.to_string(),
             desired_state,
+            excluded: State::empty(),
+            relative: State::empty(),
+            conditions: Vec::new(),
             priority,
+            profile: None,
+            ordering: Vec::new(),
         }
     }
 
+    /// Returns a copy of this goal with its relative requirements turned into absolute
+    /// ones, using the values in `initial_state` as the baseline.
+    /// A variable missing from `initial_state` is treated as starting at zero.
+    pub fn resolve(&self, initial_state: &State) -> Goal {
+        let mut resolved = self.clone();
+        for (key, delta) in std::mem::take(&mut resolved.relative.vars) {
+            let target = match (initial_state.vars.get(&key), &delta) {
+                (Some(StateVar::I64(current)), StateVar::I64(delta)) => {
+                    StateVar::I64(current + delta)
+                }
+                (Some(StateVar::F64(current)), StateVar::F64(delta)) => {
+                    StateVar::F64(current + delta)
+                }
+                _ => delta,
+            };
+            resolved.desired_state.vars.insert(key, target);
+        }
+        resolved
+    }
+
     /// Checks if this goal is satisfied by the given state.
-    /// Returns true if the state meets all requirements of the desired state.
+    /// Returns true if the state meets all requirements of the desired state,
+    /// holds none of the excluded values and meets every condition.
+    /// Relative requirements are only checked once the goal is resolved.
     pub fn is_satisfied(&self, state: &State) -> bool {
         state.satisfies(&self.desired_state)
+            && state.excludes(&self.excluded)
+            && state.satisfies_conditions(&self.conditions)
     }
+
+    /// Returns the number of conditions the state does not meet.
+    pub(crate) fn unmet_conditions(&self, state: &State) -> usize {
+        self.conditions
+            .iter()
+            .filter(|(key, condition)| !condition.holds(state.vars.get(key)))
+            .count()
+    }
+
+    /// Estimates how far the state is from satisfying this goal, without planning.
+    /// Use it to rank goals or drive progress bars.
+    ///
+    /// Each numeric requirement the state falls short of adds the missing amount,
+    /// in the variable's own units, counting a missing variable as zero. Every other
+    /// unmet requirement or condition, and every excluded value the state holds, adds one. The
+    /// distance is zero exactly when the goal is satisfied. Unlike the planner's
+    /// heuristic, values beyond a numeric minimum do not count as distance.
+    /// Relative requirements are only counted once the goal is resolved.
+    ///
+    /// ```
+    /// use goap::prelude::*;
+    ///
+    /// let goal = Goal::new("rich").requires("gold", 100).requires("has_house", true).build();
+    /// let state = State::new().set("gold", 40).set("has_house", false).build();
+    /// assert_eq!(goal.distance(&state), 61.0);
+    /// ```
+    pub fn distance(&self, state: &State) -> f64 {
+        let unmet: f64 = self
+            .desired_state
+            .vars
+            .iter()
+            .map(|(key, required)| requirement_distance(state.vars.get(key), required))
+            .sum();
+        let excluded = self
+            .excluded
+            .vars
+            .iter()
+            .filter(|(key, value)| state.vars.get(*key) == Some(*value))
+            .count();
+        unmet + (excluded + self.unmet_conditions(state)) as f64
+    }
+
+    /// Returns true if the requirement, exclusion and conditions on `key` hold in
+    /// the state. Keys the goal says nothing about always hold.
+    pub fn requirement_met(&self, key: &str, state: &State) -> bool {
+        let required = self.desired_state.vars.get(key).is_none_or(|required| {
+            state
+                .vars
+                .get(key)
+                .is_some_and(|current| value_satisfies(current, required))
+        });
+        let excluded = self
+            .excluded
+            .vars
+            .get(key)
+            .is_some_and(|value| state.vars.get(key) == Some(value));
+        let conditions = self
+            .conditions
+            .iter()
+            .filter(|(name, _)| name == key)
+            .all(|(_, condition)| condition.holds(state.vars.get(key)));
+        required && !excluded && conditions
+    }
+
+    /// Returns the requirements the state does not meet: required values sorted by
+    /// variable, then excluded values the state holds, as
+    /// [`Condition::NotEqual`], then unmet conditions in the order they were added.
+    /// Relative requirements are only checked once the goal is resolved.
+    pub fn unmet_requirements(&self, state: &State) -> Vec<UnmetPrecondition> {
+        let mut unmet = Vec::new();
+        for (key, required) in sorted_entries(&self.desired_state.vars) {
+            let actual = state.vars.get(key);
+            if !actual.is_some_and(|actual| value_satisfies(actual, required)) {
+                unmet.push(UnmetPrecondition::Value {
+                    key: key.clone(),
+                    required: required.clone(),
+                    actual: actual.cloned(),
+                });
+            }
+        }
+        for (key, value) in sorted_entries(&self.excluded.vars) {
+            if state.vars.get(key) == Some(value) {
+                unmet.push(UnmetPrecondition::Condition {
+                    key: key.clone(),
+                    condition: Condition::NotEqual(value.clone()),
+                    actual: Some(value.clone()),
+                });
+            }
+        }
+        for (key, condition) in &self.conditions {
+            let actual = state.vars.get(key);
+            if !condition.holds(actual) {
+                unmet.push(UnmetPrecondition::Condition {
+                    key: key.clone(),
+                    condition: condition.clone(),
+                    actual: actual.cloned(),
+                });
+            }
+        }
+        unmet
+    }
+
+    /// Returns the ordering penalty of a step from `before` to `after`: the sum of
+    /// the penalties of the [ordering hints](OrderingHint) whose `then`
+    /// requirement the step achieves while their `first` requirement is unmet.
+    pub fn ordering_penalty(&self, before: &State, after: &State) -> f64 {
+        self.ordering
+            .iter()
+            .filter(|hint| {
+                !self.requirement_met(&hint.then, before)
+                    && self.requirement_met(&hint.then, after)
+                    && !self.requirement_met(&hint.first, after)
+            })
+            .map(|hint| hint.penalty)
+            .sum()
+    }
+}
+
+/// Returns how far a value is from meeting a requirement: the shortfall for
+/// numbers and one for anything else that does not match.
+fn requirement_distance(current: Option<&StateVar>, required: &StateVar) -> f64 {
+    if current.is_some_and(|current| value_satisfies(current, required)) {
+        return 0.0;
+    }
+    let shortfall = match (current, required) {
+        (Some(StateVar::I64(current)), StateVar::I64(required)) => {
+            required.saturating_sub(*current) as f64
+        }
+        (Some(StateVar::F64(current)), StateVar::F64(required)) => {
+            required.saturating_sub(*current) as f64 / 1000.0
+        }
+        (None, StateVar::I64(required)) => *required as f64,
+        (None, StateVar::F64(required)) => *required as f64 / 1000.0,
+        _ => 1.0,
+    };
+    // A missing variable is unmet even when the requirement is zero or negative
+    if shortfall > 0.0 { shortfall } else { 1.0 }
 }
 
 /// Builder for constructing goals with a fluent interface.
@@ -56,8 +272,24 @@ pub struct GoalBuilder {
     name: String,
     /// The desired state that must be achieved
     desired_state: State,
+    /// The values that must be avoided
+    excluded: State,
+    /// The requirements relative to the initial state
+    relative: State,
+    /// The comparisons variables must meet
+    conditions: Vec<(String, Condition)>,
     /// The priority of the goal (defaults to 1)
     priority: u16,
+    /// The planner profile to search with
+    profile: Option<String>,
+    /// The preferred orders of the requirements
+    ordering: Vec<OrderingHint>,
+    /// Whether `try_build` rejects goals without requirements and inexact values
+    strict: bool,
+    /// Keys that were given contradicting requirements
+    conflicts: Vec<String>,
+    /// Values that could not be converted exactly, with the key they were given for
+    invalid_values: Vec<(String, ConversionError)>,
 }
 
 impl GoalBuilder {
@@ -66,7 +298,15 @@ impl GoalBuilder {
         GoalBuilder {
             name: name.to_string(),
             desired_state: State::empty(),
+            excluded: State::empty(),
+            relative: State::empty(),
+            conditions: Vec::new(),
             priority: 1,
+            profile: None,
+            ordering: Vec::new(),
+            strict: false,
+            conflicts: Vec::new(),
+            invalid_values: Vec::new(),
         }
     }
 
@@ -76,19 +316,191 @@ impl GoalBuilder {
         self
     }
 
+    /// Selects the planner profile to search with, e.g. `"urgent"` for reactive
+    /// goals (see [`PlannerProfile`](crate::profiles::PlannerProfile)).
+    pub fn profile(mut self, name: &str) -> Self {
+        self.profile = Some(name.to_string());
+        self
+    }
+
+    /// Prefers achieving the requirement on `first` before the one on `then`,
+    /// e.g. `prefers_order("hidden", "in_position", 5.0)`. Plans achieving `then`
+    /// while `first` is unmet are penalized by `penalty` (see [`OrderingHint`]).
+    /// Negative penalties are treated as zero.
+    pub fn prefers_order(mut self, first: &str, then: &str, penalty: f64) -> Self {
+        self.ordering.push(OrderingHint {
+            first: first.to_string(),
+            then: then.to_string(),
+            penalty: penalty.max(0.0),
+        });
+        self
+    }
+
     /// Adds a requirement that must be satisfied for this goal to be considered achieved.
     /// This method accepts any type that can be converted to a StateVar.
     pub fn requires<T: IntoStateVar>(mut self, key: &str, value: T) -> Self {
-        self.desired_state.set(key, value.into_state_var());
+        let value = self.convert(key, value);
+        let conflicting = self
+            .desired_state
+            .vars
+            .get(key)
+            .is_some_and(|existing| *existing != value)
+            || self.excluded.vars.get(key) == Some(&value)
+            || self.relative.vars.contains_key(key);
+        self.note_conflict(key, conflicting);
+        self.desired_state.set(key, value);
         self
     }
 
+    /// Adds a requirement on an enum variable declared with an [`EnumKey`]. Values
+    /// of another type than the key's fail to compile.
+    pub fn requires_enum<T: EnumStateVar>(self, key: EnumKey<T>, value: T) -> Self {
+        self.requires(key.name(), value)
+    }
+
+    /// Adds a requirement on a quantity variable declared with a [`QuantityKey`]:
+    /// the variable must reach `value`. Values of another quantity fail to compile.
+    pub fn requires_quantity<Q: Quantity>(self, key: QuantityKey<Q>, value: Q) -> Self {
+        self.requires(key.name(), key.store(value))
+    }
+
+    /// Adds a requirement that a variable must not hold the given value,
+    /// e.g. `requires_not("weather", "storm")`. A missing variable satisfies it.
+    /// Useful for goals about avoiding a state rather than reaching one.
+    pub fn requires_not<T: IntoStateVar>(mut self, key: &str, value: T) -> Self {
+        let value = self.convert(key, value);
+        let conflicting = self.desired_state.vars.get(key) == Some(&value);
+        self.note_conflict(key, conflicting);
+        self.excluded.set(key, value);
+        self
+    }
+
+    /// Adds a numeric requirement relative to the state planning starts from,
+    /// e.g. `requires_delta("gold", 100)` for "100 more gold than now".
+    /// The planner resolves it against the initial state (see [`Goal::resolve`]).
+    pub fn requires_delta<T: IntoStateVar>(mut self, key: &str, delta: T) -> Self {
+        let delta = self.convert(key, delta);
+        let conflicting = self.desired_state.vars.contains_key(key)
+            || self
+                .relative
+                .vars
+                .get(key)
+                .is_some_and(|existing| *existing != delta);
+        self.note_conflict(key, conflicting);
+        self.relative.set(key, delta);
+        self
+    }
+
+    /// Adds a requirement that a variable must meet a [`Condition`]. A missing
+    /// variable never meets it.
+    pub fn requires_condition(mut self, key: &str, condition: Condition) -> Self {
+        self.conditions.push((key.to_string(), condition));
+        self
+    }
+
+    /// Adds a requirement that a numeric variable must stay below a bound, e.g.
+    /// `requires_less_than("noise_level", 3)`.
+    pub fn requires_less_than<T: IntoStateVar>(mut self, key: &str, bound: T) -> Self {
+        let bound = self.convert(key, bound);
+        self.requires_condition(key, Condition::LessThan(bound))
+    }
+
+    /// Adds a requirement that a numeric variable must not exceed a bound.
+    pub fn requires_at_most<T: IntoStateVar>(mut self, key: &str, bound: T) -> Self {
+        let bound = self.convert(key, bound);
+        self.requires_condition(key, Condition::AtMost(bound))
+    }
+
+    /// Adds a requirement that a numeric variable must be above a bound. Unlike
+    /// [`requires`](GoalBuilder::requires), reaching the bound is not enough.
+    pub fn requires_greater_than<T: IntoStateVar>(mut self, key: &str, bound: T) -> Self {
+        let bound = self.convert(key, bound);
+        self.requires_condition(key, Condition::GreaterThan(bound))
+    }
+
+    /// Adds a requirement that a variable must be set to anything but the given
+    /// value. Unlike [`requires_not`](GoalBuilder::requires_not), a missing
+    /// variable does not satisfy it.
+    pub fn requires_not_equal<T: IntoStateVar>(mut self, key: &str, value: T) -> Self {
+        let value = self.convert(key, value);
+        self.requires_condition(key, Condition::NotEqual(value))
+    }
+
+    /// Adds a requirement that a numeric variable must lie between `min` and
+    /// `max`, both included, e.g. `requires_in_range("temperature", 18, 24)`.
+    pub fn requires_in_range<T: IntoStateVar>(mut self, key: &str, min: T, max: T) -> Self {
+        let min = self.convert(key, min);
+        let max = self.convert(key, max);
+        self.requires_condition(key, Condition::InRange(min, max))
+    }
+
+    /// Makes [`GoalBuilder::try_build`] reject goals without any requirements, which
+    /// every state satisfies, and floating-point values that are NaN, infinite or
+    /// out of the fixed-point range.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Converts a value, remembering any error a strict conversion would report.
+    fn convert<T: IntoStateVar>(&mut self, key: &str, value: T) -> StateVar {
+        let (value, error) = value.into_state_var_checked();
+        if let Some(error) = error {
+            self.invalid_values.push((key.to_string(), error));
+        }
+        value
+    }
+
+    /// Records a contradicting requirement for `try_build` to report.
+    fn note_conflict(&mut self, key: &str, conflicting: bool) {
+        if conflicting {
+            self.conflicts.push(key.to_string());
+        }
+    }
+
+    /// Builds the final Goal after checking the definition for authoring mistakes.
+    ///
+    /// Returns an error if a variable was given contradicting requirements, a
+    /// floating-point value is out of the fixed-point range, or the builder is
+    /// [`strict`](GoalBuilder::strict) and the goal has no requirements or was given
+    /// a value that cannot be converted exactly. [`GoalBuilder::build`] skips these checks.
+    pub fn try_build(self) -> Result<Goal, DefinitionError> {
+        if let Some(key) = self.conflicts.first() {
+            return Err(DefinitionError::ConflictingRequirement { key: key.clone() });
+        }
+        if self.strict
+            && let Some((key, error)) = self.invalid_values.first()
+        {
+            return Err(DefinitionError::InvalidValue {
+                key: key.clone(),
+                error: error.clone(),
+            });
+        }
+        check_range(&self.desired_state)?;
+        check_range(&self.excluded)?;
+        check_range(&self.relative)?;
+        if self.strict
+            && self.desired_state.vars.is_empty()
+            && self.excluded.vars.is_empty()
+            && self.relative.vars.is_empty()
+            && self.conditions.is_empty()
+        {
+            return Err(DefinitionError::NoRequirements);
+        }
+        Ok(self.build())
+    }
+
     /// Builds the final Goal from the configured builder.
     pub fn build(self) -> Goal {
         Goal {
             name: self.name,
             desired_state: self.desired_state,
+            excluded: self.excluded,
+            relative: self.relative,
+            conditions: self.conditions,
             priority: self.priority,
+            profile: self.profile,
+            ordering: self.ordering,
         }
     }
 }
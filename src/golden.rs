@@ -0,0 +1,194 @@
+//! Golden files for locking in expected plans.
+//!
+//! [`golden_text`] renders a planning scenario and its plan in a canonical text
+//! format: keys are sorted and numbers printed with fixed precision, so the same
+//! plan always renders identically. [`check_golden`] compares that text with a
+//! file checked into the repository and reports a line diff on mismatch. Set the
+//! `GOAP_UPDATE_GOLDEN` environment variable to write the current output instead,
+//! after reviewing an intended change.
+//!
+//! ```no_run
+//! use goap::golden::{check_golden, golden_text};
+//! use goap::prelude::*;
+//!
+//! let initial_state = State::new().set("gold", 0).build();
+//! let goal = Goal::new("rich").requires("gold", 10).build();
+//! let actions = vec![Action::new("work").adds("gold", 5).build()];
+//! let plan = Planner::new().plan(&initial_state, &goal, &actions).unwrap();
+//!
+//! let text = golden_text(&initial_state, &goal, &plan);
+//! if let Err(error) = check_golden("tests/golden/rich.txt", &text) {
+//!     panic!("{error}");
+//! }
+//! ```
+
+use crate::goals::Goal;
+use crate::planner::Plan;
+use crate::state::{State, StateVar};
+use std::error::Error;
+use std::fmt::{self, Write};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Environment variable that makes [`check_golden`] rewrite golden files.
+pub const UPDATE_ENV_VAR: &str = "GOAP_UPDATE_GOLDEN";
+
+/// Errors reported when comparing against a golden file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GoldenError {
+    /// The golden file does not exist yet
+    Missing(PathBuf),
+    /// The output differs from the golden file
+    Mismatch {
+        /// The golden file
+        path: PathBuf,
+        /// Line diff from the golden file to the output, `-` for expected and `+` for actual
+        diff: String,
+    },
+    /// The golden file could not be read or written
+    Io {
+        /// The golden file
+        path: PathBuf,
+        /// The underlying error message
+        message: String,
+    },
+}
+
+impl fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoldenError::Missing(path) => write!(
+                f,
+                "Golden file {} does not exist; set {UPDATE_ENV_VAR}=1 to create it",
+                path.display()
+            ),
+            GoldenError::Mismatch { path, diff } => write!(
+                f,
+                "Output differs from golden file {} (set {UPDATE_ENV_VAR}=1 to accept):\n{diff}",
+                path.display()
+            ),
+            GoldenError::Io { path, message } => {
+                write!(f, "Cannot access golden file {}: {message}", path.display())
+            }
+        }
+    }
+}
+
+impl Error for GoldenError {}
+
+/// Renders the initial state, goal and plan in a canonical text format.
+pub fn golden_text(initial_state: &State, goal: &Goal, plan: &Plan) -> String {
+    let mut text = String::new();
+    // Writing to a String cannot fail
+    let _ = render(&mut text, initial_state, goal, plan);
+    text
+}
+
+/// Writes the canonical text of a scenario.
+fn render(text: &mut String, initial_state: &State, goal: &Goal, plan: &Plan) -> fmt::Result {
+    writeln!(text, "initial state:")?;
+    render_vars(text, initial_state, "")?;
+    writeln!(text, "goal: {} (priority {})", goal.name, goal.priority)?;
+    render_vars(text, &goal.desired_state, "require ")?;
+    render_vars(text, &goal.excluded, "exclude ")?;
+    render_vars(text, &goal.relative, "increase ")?;
+    for (key, condition) in &goal.conditions {
+        writeln!(text, "  require {key} {condition}")?;
+    }
+    writeln!(text, "plan: cost {:.3}", plan.cost)?;
+    for (step, action) in plan.actions.iter().enumerate() {
+        writeln!(
+            text,
+            "  {}. {} (cost {:.3})",
+            step + 1,
+            action.name,
+            action.cost
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes one line per variable, sorted by key.
+fn render_vars(text: &mut String, state: &State, prefix: &str) -> fmt::Result {
+    let mut vars: Vec<(&String, &StateVar)> = state.vars.iter().collect();
+    vars.sort_by(|a, b| a.0.cmp(b.0));
+    for (key, value) in vars {
+        match value {
+            StateVar::String(value) => writeln!(text, "  {prefix}{key} = {value:?}")?,
+            value => writeln!(text, "  {prefix}{key} = {value}")?,
+        }
+    }
+    Ok(())
+}
+
+/// Compares `actual` with the contents of the golden file at `path`.
+///
+/// Line endings are normalised before comparing. If the `GOAP_UPDATE_GOLDEN`
+/// environment variable is set, the file is written with `actual` instead.
+pub fn check_golden(path: impl AsRef<Path>, actual: &str) -> Result<(), GoldenError> {
+    let path = path.as_ref();
+    let io_error = |error: std::io::Error| GoldenError::Io {
+        path: path.to_path_buf(),
+        message: error.to_string(),
+    };
+    if std::env::var_os(UPDATE_ENV_VAR).is_some() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        return fs::write(path, actual).map_err(io_error);
+    }
+
+    let expected = match fs::read_to_string(path) {
+        Ok(expected) => expected,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            return Err(GoldenError::Missing(path.to_path_buf()));
+        }
+        Err(error) => return Err(io_error(error)),
+    };
+    let expected = expected.replace("\r\n", "\n");
+    let actual = actual.replace("\r\n", "\n");
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(GoldenError::Mismatch {
+            path: path.to_path_buf(),
+            diff: line_diff(&expected, &actual),
+        })
+    }
+}
+
+/// Returns a line diff between two texts based on their longest common subsequence.
+/// Unchanged lines are prefixed with two spaces, removed ones with `- ` and added ones with `+ `.
+pub fn line_diff(expected: &str, actual: &str) -> String {
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+
+    // common[i][j] is the length of the longest common subsequence of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            diff.push_str(&format!("  {}\n", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            diff.push_str(&format!("- {}\n", old[i]));
+            i += 1;
+        } else {
+            diff.push_str(&format!("+ {}\n", new[j]));
+            j += 1;
+        }
+    }
+    diff
+}
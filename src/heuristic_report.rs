@@ -0,0 +1,448 @@
+//! Per-variable breakdown of the planner's heuristic.
+//!
+//! A search that expands far too many states is often misled by one term of the
+//! heuristic, such as a gold requirement counting one per coin and swamping every
+//! other requirement. [`Planner::heuristic_report`] lists the contribution of each
+//! goal requirement to the heuristic value of a state, largest first, and flags
+//! terms that are likely to overestimate the remaining cost (see [`Overestimate`]).
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let goal = Goal::new("rich")
+//!     .requires("gold", 2000)
+//!     .requires("has_shop", true)
+//!     .build();
+//! let state = State::new().set("gold", 20).set("has_shop", false).build();
+//!
+//! let report = Planner::new().heuristic_report(&state, &goal).unwrap();
+//! assert_eq!(report.total, 1981.0);
+//! assert_eq!(report.terms[0].key, "gold");
+//! assert_eq!(report.terms[0].suspect, Some(Overestimate::PerUnit));
+//! println!("{report}");
+//! ```
+//!
+//! To look at the whole search landscape instead of one state,
+//! [`Planner::heuristic_table`] tabulates the heuristic over many states, such as
+//! a grid built with [`state_grid`] or states sampled with
+//! [`sample_reachable_states`](crate::coverage::sample_reachable_states), and
+//! [`Planner::heuristic_table_along`] over the states a plan passes through. The
+//! [`HeuristicTable`] exports as CSV or JSON for plotting, and
+//! [`HeuristicTable::plateaus`] finds runs of plan steps the heuristic cannot tell
+//! apart, where A* degenerates into a breadth-first search.
+
+use crate::goals::Goal;
+use crate::planner::{Plan, Planner, PlannerError, resolve_goal};
+use crate::state::{State, StateVar, value_satisfies};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fmt::Write;
+use std::ops::Range;
+
+/// Why a heuristic term is suspected of overestimating the remaining cost.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overestimate {
+    /// The numeric requirement is already met, but the heuristic counts how far
+    /// the value is beyond the minimum
+    BeyondMinimum,
+    /// The integer distance counts one per unit, which overestimates whenever an
+    /// action changes the variable by more than one unit per unit of cost
+    PerUnit,
+    /// The float distance counts one per thousandth, the fixed-point resolution
+    FixedPoint,
+}
+
+impl fmt::Display for Overestimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Overestimate::BeyondMinimum => write!(f, "already met, excess counted"),
+            Overestimate::PerUnit => write!(f, "counted per unit"),
+            Overestimate::FixedPoint => write!(f, "counted per thousandth"),
+        }
+    }
+}
+
+/// The contribution of one goal requirement to the heuristic.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeuristicTerm {
+    /// The variable the requirement is about
+    pub key: String,
+    /// The value in the state, or None if the variable is missing
+    pub current: Option<StateVar>,
+    /// The required value, or the excluded value for exclusions
+    pub required: StateVar,
+    /// True if the goal excludes the value rather than requiring it
+    pub excluded: bool,
+    /// The term's share of the heuristic value
+    pub distance: f64,
+    /// Why the term may overestimate the remaining cost, if it may
+    pub suspect: Option<Overestimate>,
+}
+
+/// The heuristic value of a state, broken down per goal requirement.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HeuristicReport {
+    /// The heuristic value, the sum of the terms' distances
+    pub total: f64,
+    /// One term per requirement and exclusion, largest distance first, then by key
+    pub terms: Vec<HeuristicTerm>,
+}
+
+impl HeuristicReport {
+    /// Returns the terms suspected of overestimating the remaining cost.
+    pub fn suspects(&self) -> impl Iterator<Item = &HeuristicTerm> {
+        self.terms.iter().filter(|term| term.suspect.is_some())
+    }
+}
+
+impl fmt::Display for HeuristicReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Heuristic: {:.1}", self.total)?;
+        for term in &self.terms {
+            let current = term
+                .current
+                .as_ref()
+                .map_or("missing".to_string(), ToString::to_string);
+            let relation = if term.excluded { "must not be" } else { "->" };
+            write!(
+                f,
+                "\n  {}: {:.1} ({current} {relation} {})",
+                term.key, term.distance, term.required
+            )?;
+            if let Some(suspect) = term.suspect {
+                write!(f, " [suspect: {suspect}]")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns why a numeric requirement's distance may overestimate, if it may.
+fn suspect(current: Option<&StateVar>, required: &StateVar, distance: u64) -> Option<Overestimate> {
+    let current = current?;
+    match (current, required) {
+        (StateVar::I64(_) | StateVar::F64(_), _)
+            if distance > 0 && value_satisfies(current, required) =>
+        {
+            Some(Overestimate::BeyondMinimum)
+        }
+        (StateVar::I64(_), StateVar::I64(_)) if distance > 1 => Some(Overestimate::PerUnit),
+        (StateVar::F64(_), StateVar::F64(_)) if distance > 0 => Some(Overestimate::FixedPoint),
+        _ => None,
+    }
+}
+
+impl Planner {
+    /// Breaks the heuristic value of a state down per goal requirement, flagging
+    /// terms suspected of overestimating the remaining cost.
+    ///
+    /// The total equals [`Planner::estimate`], except that relative requirements
+    /// are resolved against `state` and so count their full amount. Returns an
+    /// error if state variables have incompatible types.
+    pub fn heuristic_report(
+        &self,
+        state: &State,
+        goal: &Goal,
+    ) -> Result<HeuristicReport, PlannerError> {
+        let goal = resolve_goal(goal, state);
+        let mut terms = Vec::new();
+        for (key, required) in &goal.desired_state.vars {
+            let current = state.vars.get(key);
+            let distance = self.requirement_distance(key, current, required)?;
+            terms.push(HeuristicTerm {
+                key: key.clone(),
+                current: current.cloned(),
+                required: required.clone(),
+                excluded: false,
+                distance: distance as f64,
+                suspect: suspect(current, required, distance),
+            });
+        }
+        for (key, excluded) in &goal.excluded.vars {
+            let current = state.vars.get(key);
+            terms.push(HeuristicTerm {
+                key: key.clone(),
+                current: current.cloned(),
+                required: excluded.clone(),
+                excluded: true,
+                distance: if current == Some(excluded) { 1.0 } else { 0.0 },
+                suspect: None,
+            });
+        }
+        terms.sort_by(|a, b| {
+            b.distance
+                .total_cmp(&a.distance)
+                .then_with(|| a.key.cmp(&b.key))
+                .then_with(|| a.excluded.cmp(&b.excluded))
+        });
+        Ok(HeuristicReport {
+            total: terms.iter().map(|term| term.distance).sum(),
+            terms,
+        })
+    }
+}
+
+/// The heuristic value of one state in a [`HeuristicTable`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeuristicRow {
+    /// The number of plan steps leading to the state, for tables along a plan
+    pub step: Option<usize>,
+    /// The cost of those steps, for tables along a plan
+    pub cost: Option<f64>,
+    /// The heuristic value of the state
+    pub heuristic: f64,
+    /// The value of each column's variable, `None` where it is missing
+    pub values: Vec<Option<StateVar>>,
+}
+
+/// Heuristic values of many states, for offline analysis of the search landscape.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HeuristicTable {
+    /// The variables with a column: every variable of any state, sorted
+    pub keys: Vec<String>,
+    /// One row per state, in the order the states were given
+    pub rows: Vec<HeuristicRow>,
+}
+
+impl HeuristicTable {
+    /// Builds a table from states and their rows, filling in the rows' values.
+    fn new(states: &[&State], mut rows: Vec<HeuristicRow>) -> Self {
+        let keys: Vec<String> = states
+            .iter()
+            .flat_map(|state| state.vars.keys())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .cloned()
+            .collect();
+        for (state, row) in states.iter().zip(&mut rows) {
+            row.values = keys
+                .iter()
+                .map(|key| state.vars.get(key).cloned())
+                .collect();
+        }
+        HeuristicTable { keys, rows }
+    }
+
+    /// Returns the runs of two or more consecutive rows with the same heuristic
+    /// value, as row ranges. Along a plan, these are stretches where the heuristic
+    /// gives the search no guidance.
+    pub fn plateaus(&self) -> Vec<Range<usize>> {
+        let mut plateaus = Vec::new();
+        let mut start = 0;
+        for end in 1..=self.rows.len() {
+            if end == self.rows.len() || self.rows[end].heuristic != self.rows[start].heuristic {
+                if end - start > 1 {
+                    plateaus.push(start..end);
+                }
+                start = end;
+            }
+        }
+        plateaus
+    }
+
+    /// Formats the table as CSV with a header line: `step`, `cost` and `heuristic`
+    /// columns, then one column per variable. Missing values are left empty.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("step,cost,heuristic");
+        for key in &self.keys {
+            csv.push(',');
+            csv.push_str(&csv_field(key));
+        }
+        for row in &self.rows {
+            csv.push('\n');
+            if let Some(step) = row.step {
+                let _ = write!(csv, "{step}");
+            }
+            csv.push(',');
+            if let Some(cost) = row.cost {
+                let _ = write!(csv, "{cost}");
+            }
+            let _ = write!(csv, ",{}", row.heuristic);
+            for value in &row.values {
+                csv.push(',');
+                if let Some(value) = value {
+                    csv.push_str(&csv_field(&value.to_string()));
+                }
+            }
+        }
+        csv.push('\n');
+        csv
+    }
+
+    /// Formats the table as a JSON array with one object per row, holding
+    /// `step` and `cost` (`null` for states not on a plan), `heuristic`, and the
+    /// row's variables in a `state` object.
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("[");
+        for (index, row) in self.rows.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            let step = row.step.map_or("null".to_string(), |step| step.to_string());
+            let cost = row.cost.map_or("null".to_string(), |cost| cost.to_string());
+            let _ = write!(
+                json,
+                "{{\"step\":{step},\"cost\":{cost},\"heuristic\":{},\"state\":{{",
+                row.heuristic
+            );
+            let mut first = true;
+            for (key, value) in self.keys.iter().zip(&row.values) {
+                let Some(value) = value else {
+                    continue;
+                };
+                if !first {
+                    json.push(',');
+                }
+                first = false;
+                let _ = write!(json, "{}:{}", json_string(key), json_value(value));
+            }
+            json.push_str("}}");
+        }
+        json.push(']');
+        json
+    }
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Formats a string as a JSON string literal.
+fn json_string(text: &str) -> String {
+    let mut json = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Formats a state value as a JSON value.
+fn json_value(value: &StateVar) -> String {
+    match value {
+        StateVar::Bool(value) => value.to_string(),
+        StateVar::I64(value) => value.to_string(),
+        StateVar::F64(_) => value.as_f64().unwrap_or_default().to_string(),
+        StateVar::String(text) => json_string(text),
+    }
+}
+
+/// Returns every combination of the axis values applied to `base`, varying the
+/// last axis fastest. Without axes, the grid is `base` alone.
+///
+/// ```
+/// use goap::prelude::*;
+///
+/// let base = State::new().set("has_axe", false).build();
+/// let grid = state_grid(&base, &[
+///     ("gold", vec![StateVar::I64(0), StateVar::I64(50), StateVar::I64(100)]),
+///     ("has_axe", vec![StateVar::Bool(false), StateVar::Bool(true)]),
+/// ]);
+/// assert_eq!(grid.len(), 6);
+/// assert_eq!(grid[5].get::<i64>("gold"), Some(100));
+/// ```
+pub fn state_grid(base: &State, axes: &[(&str, Vec<StateVar>)]) -> Vec<State> {
+    let mut grid = vec![base.clone()];
+    for (key, values) in axes {
+        grid = grid
+            .iter()
+            .flat_map(|state| {
+                values.iter().map(|value| {
+                    let mut state = state.clone();
+                    state.vars.insert(key.to_string(), value.clone());
+                    state
+                })
+            })
+            .collect();
+    }
+    grid
+}
+
+impl Planner {
+    /// Tabulates the planner's heuristic for `goal` over the given states, e.g. a
+    /// [`state_grid`] or sampled reachable states.
+    ///
+    /// Relative requirements are resolved against the first state. Returns an
+    /// error if state variables have incompatible types.
+    ///
+    /// ```
+    /// use goap::prelude::*;
+    ///
+    /// let goal = Goal::new("rich").requires("gold", 100).build();
+    /// let grid = state_grid(&State::empty(), &[
+    ///     ("gold", vec![StateVar::I64(0), StateVar::I64(60)]),
+    /// ]);
+    /// let table = Planner::new().heuristic_table(&grid, &goal).unwrap();
+    /// assert_eq!(table.to_csv(), "step,cost,heuristic,gold\n,,100,0\n,,40,60\n");
+    /// ```
+    pub fn heuristic_table<'a>(
+        &self,
+        states: impl IntoIterator<Item = &'a State>,
+        goal: &Goal,
+    ) -> Result<HeuristicTable, PlannerError> {
+        let states: Vec<&State> = states.into_iter().collect();
+        let Some(first) = states.first() else {
+            return Ok(HeuristicTable::default());
+        };
+        let goal = resolve_goal(goal, first);
+        let rows = states
+            .iter()
+            .map(|state| {
+                Ok(HeuristicRow {
+                    step: None,
+                    cost: None,
+                    heuristic: self.goal_heuristic(state, &goal)?,
+                    values: Vec::new(),
+                })
+            })
+            .collect::<Result<_, PlannerError>>()?;
+        Ok(HeuristicTable::new(&states, rows))
+    }
+
+    /// Tabulates the planner's heuristic for `goal` over the states `plan` passes
+    /// through from `initial_state`, starting with the initial state, with the
+    /// step and the cost spent so far on each row.
+    ///
+    /// Relative requirements are resolved against `initial_state`. Returns an
+    /// error if state variables have incompatible types.
+    pub fn heuristic_table_along(
+        &self,
+        plan: &Plan,
+        initial_state: &State,
+        goal: &Goal,
+    ) -> Result<HeuristicTable, PlannerError> {
+        let goal = resolve_goal(goal, initial_state);
+        let mut states = vec![initial_state.clone()];
+        for action in &plan.actions {
+            let next = action.apply_effect(&states[states.len() - 1]);
+            states.push(next);
+        }
+        let mut cost = 0.0;
+        let mut rows = Vec::with_capacity(states.len());
+        for (step, state) in states.iter().enumerate() {
+            if step > 0 {
+                cost += plan.actions[step - 1].cost;
+            }
+            rows.push(HeuristicRow {
+                step: Some(step),
+                cost: Some(cost),
+                heuristic: self.goal_heuristic(state, &goal)?,
+                values: Vec::new(),
+            });
+        }
+        let states: Vec<&State> = states.iter().collect();
+        Ok(HeuristicTable::new(&states, rows))
+    }
+}
@@ -10,14 +10,136 @@
 //! - **Goals**: Define desired world states that the agent wants to achieve
 //! - **Actions**: Operations that can change the world state, with preconditions and effects
 //! - **Planner**: Uses A* search to find optimal sequences of actions to achieve goals
+//!
+//! ## Concurrency
+//!
+//! The core types ([`State`](state::State), [`Goal`](goals::Goal),
+//! [`Action`](actions::Action), [`Planner`](planner::Planner),
+//! [`Plan`](planner::Plan) and the types they contain) are `Send + Sync`, which
+//! is checked at compile time. Planning only reads the planner, goal and actions,
+//! so one action set can be shared by planner threads without copying:
+//!
+//! ```
+//! use goap::prelude::*;
+//! use std::sync::Arc;
+//! use std::thread;
+//!
+//! let actions: Arc<[Action]> = Arc::from(vec![Action::new("eat").sets("fed", true).build()]);
+//! let planner = Arc::new(Planner::new());
+//! let goal = Arc::new(Goal::new("fed").requires("fed", true).build());
+//!
+//! let workers: Vec<_> = (0..4)
+//!     .map(|_| {
+//!         let (actions, planner, goal) = (actions.clone(), planner.clone(), goal.clone());
+//!         thread::spawn(move || planner.plan(State::empty(), &goal, &actions).unwrap())
+//!     })
+//!     .collect();
+//! for worker in workers {
+//!     assert_eq!(worker.join().unwrap().actions.len(), 1);
+//! }
+//! ```
+//!
+//! Shared state that agents update while others plan belongs behind a lock; see
+//! [`Planner::plan_shared`](planner::Planner::plan_shared). Caches and other
+//! interior mutability added to these types must keep them `Send + Sync`.
 
+pub mod accumulate;
 /// Actions module - defines actions that can be performed to change state
 pub mod actions;
+pub mod analysis;
+pub mod arbiter;
+/// Beam search planning that keeps only the best states per depth
+mod beam;
+pub mod belief;
+pub mod budget;
+pub mod calibration;
+pub mod clock;
+pub mod clusters;
+pub mod compiled;
+pub mod conformant;
+pub mod contingency;
+pub mod coverage;
+pub mod decisions;
+pub mod definition;
+pub mod dominance;
+/// Exhaustive enumeration of plans up to a depth limit
+mod enumerate;
+pub mod events;
+pub mod executor;
+pub mod explain;
+/// Expression module - arithmetic expressions for data-driven preconditions and effects
+pub mod expr;
+pub mod fallback;
+pub mod fixed;
 /// Goals module - defines goals that agents want to achieve
 pub mod goals;
+pub mod golden;
+pub mod heuristic_report;
+#[cfg(any(feature = "json", feature = "ron", feature = "toml"))]
+pub mod loader;
+pub mod mcts;
+pub mod mutex;
+pub mod navigation;
+/// Observer module - profiling and telemetry hooks for the planner
+pub mod observer;
+pub mod ownership;
+pub mod parallel;
+pub mod path_cost;
+pub mod pipeline;
 /// Planner module - implements A* search for finding action sequences
 pub mod planner;
 /// Prelude module - convenient imports for common use cases
 pub mod prelude;
+pub mod priorities;
+pub mod profiles;
+pub mod regression;
+pub mod remap;
+pub mod replan;
+/// Random number generation for randomised search strategies
+mod rng;
+pub mod robustness;
+pub mod sampling;
+pub mod scheduler;
+pub mod schema;
+pub mod scoring;
+pub mod sequential;
+pub mod session;
+#[cfg(feature = "signature")]
+pub mod signature;
+/// Inline sorted storage for states, used with the `small-state` feature
+#[cfg(feature = "small-state")]
+mod small_state;
+pub mod smoothing;
+pub mod snapshot;
+pub mod solver;
+pub mod source;
 /// State module - represents world state using typed variables
 pub mod state;
+pub mod static_domain;
+pub mod strings;
+pub mod symbols;
+pub mod time;
+pub mod typed;
+pub mod undo;
+pub mod units;
+
+// Core types are shared between planner threads; fail the build if one stops
+// being `Send + Sync`
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<state::State>();
+    assert_send_sync::<state::StateVar>();
+    assert_send_sync::<goals::Goal>();
+    assert_send_sync::<actions::Action>();
+    assert_send_sync::<actions::ActionBuilder>();
+    assert_send_sync::<planner::Planner>();
+    assert_send_sync::<planner::PlannerConfig>();
+    assert_send_sync::<planner::Plan>();
+    assert_send_sync::<planner::PlannerError>();
+    assert_send_sync::<executor::PlanExecutor>();
+    assert_send_sync::<parallel::ParallelExecutor>();
+    assert_send_sync::<compiled::CompiledDomain>();
+    assert_send_sync::<clusters::GoalClusters>();
+    assert_send_sync::<session::PlanningSession>();
+    assert_send_sync::<scheduler::PlanningScheduler>();
+};
@@ -0,0 +1,419 @@
+//! Loading actions, goals and initial states from data files.
+//!
+//! Designers author domains as data and iterate on them without recompiling. A
+//! [`Domain`] file holds an initial state, goals and actions; it is parsed from
+//! JSON, RON or TOML with the `json`, `ron` and `toml` features. Every entry is
+//! built with the strict builders ([`ActionBuilder::strict`] and
+//! [`GoalBuilder::strict`]), and a [`LoadError`] names the offending entry, such
+//! as `actions[2] 'mine': Invalid cost -1: must be finite and not negative`.
+//!
+//! Actions accept `name`, `cost` (1.0 by default), `requires`, `requires_absent`,
+//! `requires_expr`, `sets`, `adds`, `subtracts`, `effect_expr`, `fails_with` and
+//! `tags`; goals accept `name`, `priority` (1 by default), `requires` and
+//! `requires_not`. Unknown fields are rejected, so misspelled ones do not go
+//! unnoticed.
+//!
+//! ```
+//! # #[cfg(feature = "json")]
+//! # {
+//! use goap::prelude::*;
+//!
+//! let domain = Domain::from_json(r#"{
+//!     "initial_state": { "gold": 0, "has_pickaxe": false },
+//!     "goals": [{ "name": "rich", "requires": { "gold": 20 } }],
+//!     "actions": [
+//!         { "name": "buy_pickaxe", "cost": 2.0, "sets": { "has_pickaxe": true } },
+//!         { "name": "mine", "requires": { "has_pickaxe": true }, "adds": { "gold": 10 } }
+//!     ]
+//! }"#).unwrap();
+//!
+//! let goal = domain.goal("rich").unwrap();
+//! let plan = Planner::new().plan(&domain.initial_state, goal, &domain.actions).unwrap();
+//! assert_eq!(plan.actions.len(), 3);
+//! # }
+//! ```
+
+use crate::actions::{Action, ActionBuilder};
+use crate::definition::DefinitionError;
+use crate::expr::ExprError;
+use crate::goals::{Goal, GoalBuilder};
+use crate::state::{State, StateVar};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+
+/// Actions, goals and an initial state loaded from a data file.
+#[derive(Clone, Debug, Default)]
+pub struct Domain {
+    /// The initial state
+    pub initial_state: State,
+    /// The goals, in file order
+    pub goals: Vec<Goal>,
+    /// The actions, in file order
+    pub actions: Vec<Action>,
+}
+
+impl Domain {
+    /// Returns the goal with the given name, if the domain has one.
+    pub fn goal(&self, name: &str) -> Option<&Goal> {
+        self.goals.iter().find(|goal| goal.name == name)
+    }
+
+    /// Returns the action with the given name, if the domain has one.
+    pub fn action(&self, name: &str) -> Option<&Action> {
+        self.actions.iter().find(|action| action.name == name)
+    }
+
+    /// Parses a domain from JSON.
+    #[cfg(feature = "json")]
+    pub fn from_json(text: &str) -> Result<Domain, LoadError> {
+        let definition: DomainDef =
+            serde_json::from_str(text).map_err(|error| LoadError::Syntax {
+                format: "JSON",
+                message: error.to_string(),
+            })?;
+        definition.build()
+    }
+
+    /// Parses a domain from RON.
+    #[cfg(feature = "ron")]
+    pub fn from_ron(text: &str) -> Result<Domain, LoadError> {
+        let definition: DomainDef = ron::from_str(text).map_err(|error| LoadError::Syntax {
+            format: "RON",
+            message: error.to_string(),
+        })?;
+        definition.build()
+    }
+
+    /// Parses a domain from TOML.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(text: &str) -> Result<Domain, LoadError> {
+        let definition: DomainDef = toml::from_str(text).map_err(|error| LoadError::Syntax {
+            format: "TOML",
+            message: error.to_string(),
+        })?;
+        definition.build()
+    }
+
+    /// Reads a domain from a file, choosing the format by its extension (`json`,
+    /// `ron` or `toml`).
+    ///
+    /// Returns [`LoadError::UnsupportedFormat`] for other extensions and for
+    /// formats whose feature is disabled.
+    pub fn load(path: impl AsRef<Path>) -> Result<Domain, LoadError> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default();
+        let parse: fn(&str) -> Result<Domain, LoadError> = match extension {
+            #[cfg(feature = "json")]
+            "json" => Domain::from_json,
+            #[cfg(feature = "ron")]
+            "ron" => Domain::from_ron,
+            #[cfg(feature = "toml")]
+            "toml" => Domain::from_toml,
+            _ => {
+                return Err(LoadError::UnsupportedFormat {
+                    path: path.display().to_string(),
+                });
+            }
+        };
+        let text = std::fs::read_to_string(path).map_err(|error| LoadError::Io {
+            path: path.display().to_string(),
+            message: error.to_string(),
+        })?;
+        parse(&text)
+    }
+}
+
+/// Why an action or goal entry could not be built.
+#[derive(Debug, PartialEq)]
+pub enum EntryError {
+    /// The definition failed validation
+    Definition(DefinitionError),
+    /// An expression could not be parsed
+    Expression(ExprError),
+    /// An `adds` or `subtracts` amount is not a number
+    NotNumeric {
+        /// The variable the amount was given for
+        key: String,
+    },
+}
+
+impl fmt::Display for EntryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntryError::Definition(error) => write!(f, "{error}"),
+            EntryError::Expression(error) => write!(f, "{error}"),
+            EntryError::NotNumeric { key } => write!(f, "Amount for '{key}' is not a number"),
+        }
+    }
+}
+
+/// Errors that can occur when loading a [`Domain`].
+#[derive(Debug, PartialEq)]
+pub enum LoadError {
+    /// The file could not be read
+    Io {
+        /// The path of the file
+        path: String,
+        /// The error reported by the file system
+        message: String,
+    },
+    /// The file extension is not a supported format, or its feature is disabled
+    UnsupportedFormat {
+        /// The path of the file
+        path: String,
+    },
+    /// The text is not valid in its format or does not have the domain's shape
+    Syntax {
+        /// The name of the format
+        format: &'static str,
+        /// The parser's message, with the position of the error
+        message: String,
+    },
+    /// An action entry could not be built
+    Action {
+        /// The position of the entry in the actions list
+        index: usize,
+        /// The name of the action
+        name: String,
+        /// Why the entry could not be built
+        error: EntryError,
+    },
+    /// A goal entry could not be built
+    Goal {
+        /// The position of the entry in the goals list
+        index: usize,
+        /// The name of the goal
+        name: String,
+        /// Why the entry could not be built
+        error: EntryError,
+    },
+    /// Two actions or two goals have the same name
+    DuplicateName {
+        /// The list holding the entries, `actions` or `goals`
+        list: &'static str,
+        /// The position of the second entry
+        index: usize,
+        /// The repeated name
+        name: String,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io { path, message } => write!(f, "Cannot read '{path}': {message}"),
+            LoadError::UnsupportedFormat { path } => {
+                write!(f, "Unsupported domain format for '{path}'")
+            }
+            LoadError::Syntax { format, message } => write!(f, "Invalid {format}: {message}"),
+            LoadError::Action { index, name, error } => {
+                write!(f, "actions[{index}] '{name}': {error}")
+            }
+            LoadError::Goal { index, name, error } => write!(f, "goals[{index}] '{name}': {error}"),
+            LoadError::DuplicateName { list, index, name } => {
+                write!(f, "{list}[{index}] '{name}': Name is already used")
+            }
+        }
+    }
+}
+
+impl Error for LoadError {}
+
+/// A variable value as written in a data file.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ValueDef {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl From<ValueDef> for StateVar {
+    fn from(value: ValueDef) -> Self {
+        match value {
+            ValueDef::Bool(value) => StateVar::Bool(value),
+            ValueDef::Int(value) => StateVar::I64(value),
+            ValueDef::Float(value) => StateVar::from_f64(value),
+            ValueDef::Text(value) => StateVar::from(value.as_str()),
+        }
+    }
+}
+
+/// Variables as written in a data file.
+type VarsDef = BTreeMap<String, ValueDef>;
+
+fn default_cost() -> f64 {
+    1.0
+}
+
+fn default_priority() -> u16 {
+    1
+}
+
+/// An action entry.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ActionDef {
+    name: String,
+    #[serde(default = "default_cost")]
+    cost: f64,
+    #[serde(default)]
+    requires: VarsDef,
+    #[serde(default)]
+    requires_absent: Vec<String>,
+    #[serde(default)]
+    requires_expr: Vec<String>,
+    #[serde(default)]
+    sets: VarsDef,
+    #[serde(default)]
+    adds: VarsDef,
+    #[serde(default)]
+    subtracts: VarsDef,
+    #[serde(default)]
+    effect_expr: Vec<String>,
+    #[serde(default)]
+    fails_with: VarsDef,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+impl ActionDef {
+    fn build(self) -> Result<Action, EntryError> {
+        let mut builder = ActionBuilder::new(&self.name).strict(true).cost(self.cost);
+        for (key, value) in self.requires {
+            builder = builder.requires(&key, StateVar::from(value));
+        }
+        for key in &self.requires_absent {
+            builder = builder.requires_absent(key);
+        }
+        for expr in &self.requires_expr {
+            builder = builder
+                .requires_expr(expr)
+                .map_err(EntryError::Expression)?;
+        }
+        for (key, value) in self.sets {
+            builder = builder.sets(&key, StateVar::from(value));
+        }
+        for (key, value) in self.adds {
+            builder = match value {
+                ValueDef::Int(amount) => builder.adds(&key, amount),
+                ValueDef::Float(amount) => builder.adds(&key, amount),
+                _ => return Err(EntryError::NotNumeric { key }),
+            };
+        }
+        for (key, value) in self.subtracts {
+            builder = match value {
+                ValueDef::Int(amount) => builder.subtracts(&key, amount),
+                ValueDef::Float(amount) => builder.subtracts(&key, amount),
+                _ => return Err(EntryError::NotNumeric { key }),
+            };
+        }
+        for expr in &self.effect_expr {
+            builder = builder.effect_expr(expr).map_err(EntryError::Expression)?;
+        }
+        for (key, value) in self.fails_with {
+            builder = builder.fails_with(&key, StateVar::from(value));
+        }
+        for tag in &self.tags {
+            builder = builder.tag(tag);
+        }
+        builder.try_build().map_err(EntryError::Definition)
+    }
+}
+
+/// A goal entry.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GoalDef {
+    name: String,
+    #[serde(default = "default_priority")]
+    priority: u16,
+    #[serde(default)]
+    requires: VarsDef,
+    #[serde(default)]
+    requires_not: VarsDef,
+}
+
+impl GoalDef {
+    fn build(self) -> Result<Goal, EntryError> {
+        let mut builder = GoalBuilder::new(&self.name)
+            .strict(true)
+            .priority(self.priority);
+        for (key, value) in self.requires {
+            builder = builder.requires(&key, StateVar::from(value));
+        }
+        for (key, value) in self.requires_not {
+            builder = builder.requires_not(&key, StateVar::from(value));
+        }
+        builder.try_build().map_err(EntryError::Definition)
+    }
+}
+
+/// A whole domain file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DomainDef {
+    #[serde(default)]
+    initial_state: VarsDef,
+    #[serde(default)]
+    goals: Vec<GoalDef>,
+    #[serde(default)]
+    actions: Vec<ActionDef>,
+}
+
+impl DomainDef {
+    fn build(self) -> Result<Domain, LoadError> {
+        let mut initial_state = State::empty();
+        for (key, value) in self.initial_state {
+            initial_state.set(&key, StateVar::from(value));
+        }
+
+        let mut names = HashSet::new();
+        let mut goals = Vec::with_capacity(self.goals.len());
+        for (index, goal) in self.goals.into_iter().enumerate() {
+            let name = goal.name.clone();
+            if !names.insert(name.clone()) {
+                return Err(LoadError::DuplicateName {
+                    list: "goals",
+                    index,
+                    name,
+                });
+            }
+            goals.push(
+                goal.build()
+                    .map_err(|error| LoadError::Goal { index, name, error })?,
+            );
+        }
+
+        names.clear();
+        let mut actions = Vec::with_capacity(self.actions.len());
+        for (index, action) in self.actions.into_iter().enumerate() {
+            let name = action.name.clone();
+            if !names.insert(name.clone()) {
+                return Err(LoadError::DuplicateName {
+                    list: "actions",
+                    index,
+                    name,
+                });
+            }
+            actions.push(action.build().map_err(|error| LoadError::Action {
+                index,
+                name,
+                error,
+            })?);
+        }
+
+        Ok(Domain {
+            initial_state,
+            goals,
+            actions,
+        })
+    }
+}
@@ -0,0 +1,281 @@
+//! Monte Carlo Tree Search planning.
+//!
+//! For domains whose branching makes A* impractical, Monte Carlo Tree Search
+//! grows a search tree guided by random simulations ("rollouts") and returns
+//! the cheapest goal-reaching action sequence seen within its iteration budget.
+//! Select it with [`SearchStrategy::MonteCarlo`](crate::planner::SearchStrategy)
+//! or call [`Planner::plan_with_rollout`] to supply a custom [`RolloutPolicy`].
+//! Plans are not guaranteed to be optimal.
+
+use crate::actions::Action;
+use crate::goals::Goal;
+use crate::observer::PlannerObserver;
+use crate::planner::{
+    Plan, PlanStats, Planner, PlannerError, SearchStrategy, Transition, plan_assumptions,
+};
+use crate::rng::Rng;
+use crate::state::State;
+use std::borrow::Cow;
+
+/// Settings for Monte Carlo Tree Search.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MonteCarloConfig {
+    /// Number of select-expand-simulate iterations
+    pub iterations: usize,
+    /// Maximum number of actions in a plan, including the rollout
+    pub max_depth: usize,
+    /// UCT exploration constant; higher values explore more
+    pub exploration: f64,
+    /// Seed for the random numbers; equal seeds give equal plans
+    pub seed: u64,
+}
+
+impl Default for MonteCarloConfig {
+    fn default() -> Self {
+        MonteCarloConfig {
+            iterations: 1000,
+            max_depth: 20,
+            exploration: std::f64::consts::SQRT_2,
+            seed: 0,
+        }
+    }
+}
+
+/// Chooses which transition to follow during a rollout.
+pub trait RolloutPolicy {
+    /// Returns the index into `transitions` of the transition to take.
+    /// `transitions` is never empty; `random` is a fresh random number from the
+    /// planner's seeded generator, so seeded policies stay reproducible.
+    fn choose(&mut self, state: &State, transitions: &[Transition<'_>], random: u64) -> usize;
+}
+
+/// A rollout policy that picks transitions uniformly at random.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RandomRollout;
+
+impl RolloutPolicy for RandomRollout {
+    fn choose(&mut self, _state: &State, transitions: &[Transition<'_>], random: u64) -> usize {
+        (random % transitions.len() as u64) as usize
+    }
+}
+
+/// A node of the Monte Carlo search tree.
+struct TreeNode {
+    /// The state at this node
+    state: State,
+    /// The parent node
+    parent: Option<usize>,
+    /// The action that led here from the parent
+    action: Option<usize>,
+    /// Cost of the path from the root
+    g: f64,
+    /// Number of actions from the root
+    depth: usize,
+    /// Expanded children
+    children: Vec<usize>,
+    /// Actions not yet expanded into children; None until first visited
+    untried: Option<Vec<usize>>,
+    /// Number of simulations through this node
+    visits: f64,
+    /// Sum of simulation rewards through this node
+    reward: f64,
+}
+
+impl Planner {
+    /// Finds a plan with Monte Carlo Tree Search using a custom rollout policy.
+    ///
+    /// Uses the settings of [`SearchStrategy::MonteCarlo`] if the planner is configured
+    /// with it, and the default [`MonteCarloConfig`] otherwise.
+    pub fn plan_with_rollout<'s>(
+        &self,
+        initial_state: impl Into<Cow<'s, State>>,
+        goal: &Goal,
+        actions: &[Action],
+        policy: &mut dyn RolloutPolicy,
+    ) -> Result<Plan, PlannerError> {
+        let (initial_state, goal) = self.prepare_start(initial_state.into().into_owned(), goal);
+        self.check_problem(&initial_state, &goal, actions)?;
+        let actions = self.prepare_actions(actions);
+        let settings = match self.config().strategy {
+            SearchStrategy::MonteCarlo(settings) => settings,
+            _ => MonteCarloConfig::default(),
+        };
+        self.plan_monte_carlo(
+            initial_state,
+            &goal,
+            &actions,
+            settings,
+            policy,
+            &mut crate::observer::NoopObserver,
+        )
+    }
+
+    /// Runs Monte Carlo Tree Search and returns the cheapest plan reaching the goal.
+    pub(crate) fn plan_monte_carlo(
+        &self,
+        initial_state: State,
+        goal: &Goal,
+        actions: &[Action],
+        settings: MonteCarloConfig,
+        policy: &mut dyn RolloutPolicy,
+        observer: &mut dyn PlannerObserver,
+    ) -> Result<Plan, PlannerError> {
+        let mut rng = Rng::new(settings.seed);
+        let mut stats = PlanStats::default();
+        let mut best: Option<(f64, Vec<usize>)> = None;
+        let mut nodes = vec![TreeNode {
+            state: initial_state,
+            parent: None,
+            action: None,
+            g: 0.0,
+            depth: 0,
+            children: Vec::new(),
+            untried: None,
+            visits: 0.0,
+            reward: 0.0,
+        }];
+
+        for _ in 0..settings.iterations {
+            // Selection: descend through fully expanded nodes by UCT
+            let mut current = 0;
+            loop {
+                let node = &nodes[current];
+                let terminal = goal.is_satisfied(&node.state) || node.depth >= settings.max_depth;
+                let fully_expanded = node.untried.as_ref().is_some_and(Vec::is_empty);
+                if terminal || !fully_expanded || node.children.is_empty() {
+                    break;
+                }
+                let parent_visits = node.visits.max(1.0);
+                current = *node
+                    .children
+                    .iter()
+                    .max_by(|a, b| {
+                        let ua = uct(&nodes[**a], parent_visits, settings.exploration);
+                        let ub = uct(&nodes[**b], parent_visits, settings.exploration);
+                        ua.total_cmp(&ub)
+                    })
+                    .unwrap_or(&current);
+            }
+
+            // Expansion: add one untried child
+            let satisfied = goal.is_satisfied(&nodes[current].state);
+            observer.on_goal_test(&nodes[current].state, satisfied);
+            if !satisfied && nodes[current].depth < settings.max_depth {
+                if nodes[current].untried.is_none() {
+                    let state = &nodes[current].state;
+                    let untried = self
+                        .expand(state, actions)
+                        .map(|transition| transition.action_index)
+                        .collect();
+                    nodes[current].untried = Some(untried);
+                }
+                let untried = nodes[current].untried.as_ref().map_or(0, Vec::len);
+                if untried > 0 {
+                    let pick = rng.below(untried);
+                    let action_index = nodes[current]
+                        .untried
+                        .as_mut()
+                        .map_or(0, |untried| untried.swap_remove(pick));
+                    let parent = &nodes[current];
+                    observer.on_expand_begin(&parent.state);
+                    let action = &actions[action_index];
+                    let state = action.apply_effect(&parent.state);
+                    let child = TreeNode {
+                        state,
+                        parent: Some(current),
+                        action: Some(action_index),
+                        g: parent.g + action.cost,
+                        depth: parent.depth + 1,
+                        children: Vec::new(),
+                        untried: None,
+                        visits: 0.0,
+                        reward: 0.0,
+                    };
+                    observer.on_transition(&parent.state, action, &child.state);
+                    observer.on_expand_end(&parent.state, 1);
+                    stats.nodes_expanded += 1;
+                    stats.nodes_generated += 1;
+                    let child_index = nodes.len();
+                    nodes[current].children.push(child_index);
+                    nodes.push(child);
+                    current = child_index;
+                }
+            }
+
+            // Simulation: follow the rollout policy from the selected node
+            let mut path = tree_path(&nodes, current);
+            let mut state = nodes[current].state.clone();
+            let mut cost = nodes[current].g;
+            let mut depth = nodes[current].depth;
+            while !goal.is_satisfied(&state) && depth < settings.max_depth {
+                let transitions: Vec<Transition> = self.expand(&state, actions).collect();
+                if transitions.is_empty() {
+                    break;
+                }
+                let choice = policy
+                    .choose(&state, &transitions, rng.next_u64())
+                    .min(transitions.len() - 1);
+                let transition = &transitions[choice];
+                cost += transition.cost;
+                path.push(transition.action_index);
+                stats.nodes_generated += 1;
+                let next = transition.state.clone();
+                state = next;
+                depth += 1;
+            }
+
+            let reward = if goal.is_satisfied(&state) {
+                if best.as_ref().is_none_or(|(best_cost, _)| cost < *best_cost) {
+                    best = Some((cost, path));
+                }
+                0.5 + 0.5 / (1.0 + cost)
+            } else {
+                0.5 / (1.0 + self.goal_heuristic(&state, goal)?)
+            };
+
+            // Backpropagation
+            let mut node = Some(current);
+            while let Some(index) = node {
+                nodes[index].visits += 1.0;
+                nodes[index].reward += reward;
+                node = nodes[index].parent;
+            }
+        }
+
+        stats.memory_bytes = nodes
+            .iter()
+            .map(|node| node.state.approx_memory_bytes() + size_of::<TreeNode>())
+            .sum();
+        let (cost, path) = best.ok_or(PlannerError::NoPlanFound)?;
+        let path: Vec<Action> = path
+            .into_iter()
+            .map(|index| actions[index].clone())
+            .collect();
+        Ok(Plan {
+            currencies: self.simulated_currency_changes(&nodes[0].state, &path),
+            assumptions: plan_assumptions(&nodes[0].state, goal, &path),
+            actions: path,
+            cost,
+            stats,
+        })
+    }
+}
+
+/// Upper confidence bound of a child, balancing average reward and exploration.
+fn uct(node: &TreeNode, parent_visits: f64, exploration: f64) -> f64 {
+    if node.visits == 0.0 {
+        return f64::INFINITY;
+    }
+    node.reward / node.visits + exploration * (parent_visits.ln() / node.visits).sqrt()
+}
+
+/// Returns the action indices on the path from the root to a node.
+fn tree_path(nodes: &[TreeNode], mut node: usize) -> Vec<usize> {
+    let mut path = Vec::new();
+    while let Some(action) = nodes[node].action {
+        path.push(action);
+        node = nodes[node].parent.unwrap_or(0);
+    }
+    path.reverse();
+    path
+}
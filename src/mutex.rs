@@ -0,0 +1,307 @@
+//! Mutually exclusive boolean variables.
+//!
+//! Domains that model location or mode with one flag per value
+//! (`at_base`, `at_point_a`, ...) need every action to clear all the other
+//! flags, and the planner can otherwise reach states where several are true.
+//! A [`MutexGroup`] declares once that exactly one flag of the group is true.
+//! Registered with [`PlannerConfig::mutex_group`](crate::planner::PlannerConfig::mutex_group),
+//! actions that set a member to true automatically clear the other members,
+//! and states violating the group are pruned from the search.
+//! [`PlannerConfig::complete_actions`](crate::planner::PlannerConfig::complete_actions)
+//! returns the completed actions for executing or simulating plans elsewhere.
+//!
+//! [`FlagsToEnum`] goes one step further and migrates such a domain to a single
+//! string variable (`location = "base"`), rewriting states, goals and actions.
+
+use crate::actions::Action;
+use crate::goals::Goal;
+use crate::state::{State, StateOperation, StateVar};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+/// A group of boolean variables of which exactly one is true.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MutexGroup {
+    /// The member variables
+    keys: Vec<String>,
+}
+
+impl MutexGroup {
+    /// Creates a group from the given member variables.
+    pub fn new(keys: &[&str]) -> Self {
+        MutexGroup {
+            keys: keys.iter().map(|key| key.to_string()).collect(),
+        }
+    }
+
+    /// Returns the member variables.
+    pub fn keys(&self) -> &[String] {
+        &self.keys
+    }
+
+    /// Returns the member that is true in the given state, if exactly one is.
+    pub fn active<'a>(&'a self, state: &State) -> Option<&'a str> {
+        let mut active = self.keys.iter().filter(|key| is_true(state, key));
+        match (active.next(), active.next()) {
+            (Some(key), None) => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the state respects the group.
+    /// States that contain none of the members are not constrained by it.
+    pub fn is_consistent(&self, state: &State) -> bool {
+        let constrained = self.keys.iter().any(|key| state.vars.contains_key(key));
+        !constrained || self.active(state).is_some()
+    }
+
+    /// Returns a copy of the action where setting a member to true also sets every
+    /// other member to false, in its effects and likewise in its failure effects.
+    /// Effects the action already declares are kept.
+    pub fn complete_action(&self, action: &Action) -> Action {
+        let mut completed = action.clone();
+        self.complete_effects(&mut completed.effects);
+        self.complete_effects(&mut completed.failure_effects);
+        completed
+    }
+
+    /// Clears every other member in effects that set a member to true.
+    fn complete_effects(&self, effects: &mut HashMap<String, StateOperation>) {
+        let activates = self.keys.iter().any(|key| {
+            matches!(
+                effects.get(key),
+                Some(StateOperation::Set(StateVar::Bool(true)))
+            )
+        });
+        if activates {
+            for key in &self.keys {
+                effects
+                    .entry(key.clone())
+                    .or_insert(StateOperation::Set(StateVar::Bool(false)));
+            }
+        }
+    }
+}
+
+/// Errors that can occur when converting boolean flags into a single variable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FlagConversionError {
+    /// The state has zero or several true flags, so it has no single value
+    InconsistentState,
+    /// A flag is required to be false, which an equality on one variable cannot express
+    NegatedFlag {
+        /// The flag required to be false
+        flag: String,
+    },
+    /// Two different flags are required or set to true together
+    ConflictingFlags {
+        /// The first flag found
+        first: String,
+        /// The second flag found
+        second: String,
+    },
+    /// A flag is cleared without another flag being set, or changed arithmetically
+    UnsupportedEffect {
+        /// The flag with the unsupported effect
+        flag: String,
+    },
+}
+
+impl fmt::Display for FlagConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlagConversionError::InconsistentState => {
+                write!(f, "State does not have exactly one true flag")
+            }
+            FlagConversionError::NegatedFlag { flag } => {
+                write!(f, "Cannot convert requirement that '{flag}' is false")
+            }
+            FlagConversionError::ConflictingFlags { first, second } => {
+                write!(f, "Flags '{first}' and '{second}' cannot both be true")
+            }
+            FlagConversionError::UnsupportedEffect { flag } => {
+                write!(f, "Cannot convert effect on '{flag}'")
+            }
+        }
+    }
+}
+
+impl Error for FlagConversionError {}
+
+/// Converts a set of mutually exclusive boolean flags into one string variable.
+///
+/// ```
+/// use goap::prelude::*;
+///
+/// let conversion = FlagsToEnum::new("location")
+///     .flag("at_base", "base")
+///     .flag("at_point_a", "point_a");
+///
+/// let state = State::new().set("at_base", true).set("at_point_a", false).build();
+/// let converted = conversion.convert_state(&state).unwrap();
+/// assert_eq!(converted.get::<String>("location"), Some("base".to_string()));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FlagsToEnum {
+    /// The variable that replaces the flags
+    variable: String,
+    /// Pairs of (flag, value of the variable when that flag is true)
+    flags: Vec<(String, String)>,
+}
+
+impl FlagsToEnum {
+    /// Creates a conversion into the given variable, with no flags yet.
+    pub fn new(variable: &str) -> Self {
+        FlagsToEnum {
+            variable: variable.to_string(),
+            flags: Vec::new(),
+        }
+    }
+
+    /// Creates a conversion for every member of a mutex group,
+    /// using each flag's name as its value.
+    pub fn from_group(group: &MutexGroup, variable: &str) -> Self {
+        group
+            .keys()
+            .iter()
+            .fold(Self::new(variable), |conversion, key| {
+                conversion.flag(key, key)
+            })
+    }
+
+    /// Maps a flag to the value the variable takes when the flag is true.
+    pub fn flag(mut self, flag: &str, value: &str) -> Self {
+        self.flags.push((flag.to_string(), value.to_string()));
+        self
+    }
+
+    /// Returns the value a flag maps to, if it belongs to this conversion.
+    fn value_of(&self, flag: &str) -> Option<&str> {
+        self.flags
+            .iter()
+            .find(|(existing, _)| existing == flag)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Converts a world state, replacing the flags with the variable.
+    /// States that contain none of the flags are returned unchanged.
+    pub fn convert_state(&self, state: &State) -> Result<State, FlagConversionError> {
+        let mut converted = state.clone();
+        let mut present = false;
+        let mut active = Vec::new();
+        for (flag, value) in &self.flags {
+            if let Some(var) = converted.vars.remove(flag) {
+                present = true;
+                if var == StateVar::Bool(true) {
+                    active.push(value.as_str());
+                }
+            }
+        }
+        match active.as_slice() {
+            [] if !present => Ok(converted),
+            [value] => {
+                converted.set(&self.variable, *value);
+                Ok(converted)
+            }
+            _ => Err(FlagConversionError::InconsistentState),
+        }
+    }
+
+    /// Converts a set of requirements, such as preconditions or a goal's desired state.
+    /// Flags required to be true become an equality on the variable.
+    pub fn convert_requirements(&self, requirements: &State) -> Result<State, FlagConversionError> {
+        let mut converted = requirements.clone();
+        let mut required: Option<&str> = None;
+        for (flag, _) in &self.flags {
+            match converted.vars.remove(flag) {
+                Some(StateVar::Bool(true)) => {
+                    if let Some(first) = required {
+                        return Err(FlagConversionError::ConflictingFlags {
+                            first: first.to_string(),
+                            second: flag.clone(),
+                        });
+                    }
+                    required = Some(flag);
+                }
+                Some(_) => {
+                    return Err(FlagConversionError::NegatedFlag { flag: flag.clone() });
+                }
+                None => {}
+            }
+        }
+        if let Some(value) = required.and_then(|flag| self.value_of(flag)) {
+            converted.set(&self.variable, value);
+        }
+        Ok(converted)
+    }
+
+    /// Converts an action's preconditions and effects.
+    /// Setting a flag to true becomes setting the variable; clearing the other flags
+    /// in the same action is dropped as implied.
+    pub fn convert_action(&self, action: &Action) -> Result<Action, FlagConversionError> {
+        let mut converted = action.clone();
+        converted.preconditions = self.convert_requirements(&action.preconditions)?;
+
+        let mut effects: HashMap<String, StateOperation> = HashMap::new();
+        let mut activated: Option<&str> = None;
+        let mut cleared = Vec::new();
+        for (key, operation) in &action.effects {
+            if self.value_of(key).is_none() {
+                effects.insert(key.clone(), operation.clone());
+                continue;
+            }
+            match operation {
+                StateOperation::Set(StateVar::Bool(true)) => {
+                    if let Some(first) = activated {
+                        return Err(FlagConversionError::ConflictingFlags {
+                            first: first.to_string(),
+                            second: key.clone(),
+                        });
+                    }
+                    activated = Some(key);
+                }
+                StateOperation::Set(StateVar::Bool(false)) => cleared.push(key),
+                _ => return Err(FlagConversionError::UnsupportedEffect { flag: key.clone() }),
+            }
+        }
+        match activated.and_then(|flag| self.value_of(flag)) {
+            Some(value) => {
+                effects.insert(
+                    self.variable.clone(),
+                    StateOperation::Set(StateVar::from(value)),
+                );
+            }
+            None => {
+                if let Some(flag) = cleared.first() {
+                    return Err(FlagConversionError::UnsupportedEffect {
+                        flag: flag.to_string(),
+                    });
+                }
+            }
+        }
+        converted.effects = effects;
+        Ok(converted)
+    }
+
+    /// Converts a goal's desired state and excluded values.
+    /// Excluding a flag being true becomes excluding the corresponding value.
+    pub fn convert_goal(&self, goal: &Goal) -> Result<Goal, FlagConversionError> {
+        let mut converted = goal.clone();
+        converted.desired_state = self.convert_requirements(&goal.desired_state)?;
+        for (flag, value) in &self.flags {
+            match converted.excluded.vars.remove(flag) {
+                Some(StateVar::Bool(true)) => {
+                    converted.excluded.set(&self.variable, value.as_str())
+                }
+                Some(_) => return Err(FlagConversionError::NegatedFlag { flag: flag.clone() }),
+                None => {}
+            }
+        }
+        Ok(converted)
+    }
+}
+
+fn is_true(state: &State, key: &str) -> bool {
+    matches!(state.vars.get(key), Some(StateVar::Bool(true)))
+}
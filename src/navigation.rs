@@ -0,0 +1,149 @@
+//! Movement between locations held in a single state variable.
+//!
+//! Navigation domains otherwise need a hand-written action for every pair of
+//! locations. A [`TransitionModel`] describes which locations exist and what it
+//! costs to travel between them, and generates the movement actions from that.
+//! [`LocationGraph`] is a ready-made model built from weighted connections.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let graph = LocationGraph::new("location")
+//!     .connect("base", "point_a", 5.0)
+//!     .connect("point_a", "point_b", 3.0);
+//!
+//! let mut actions = graph.movement_actions();
+//! actions.push(
+//!     Action::new("report_at_b")
+//!         .requires("location", "point_b")
+//!         .sets("reported", true)
+//!         .build(),
+//! );
+//!
+//! let initial = State::new().set("location", "base").set("reported", false).build();
+//! let goal = Goal::new("report").requires("reported", true).build();
+//! let plan = Planner::new().plan(initial, &goal, &actions).unwrap();
+//! assert_eq!(plan.cost, 9.0);
+//! ```
+
+use crate::actions::Action;
+use crate::symbols::SymbolTable;
+
+/// Tag attached to generated movement actions, e.g. for [`crate::planner::Plan::smoothed`].
+pub const MOVEMENT_TAG: &str = "movement";
+
+/// Describes the locations a variable can hold and the cost of moving between them.
+pub trait TransitionModel {
+    /// The state variable holding the current location.
+    fn key(&self) -> &str;
+
+    /// All known locations.
+    fn locations(&self) -> Vec<String>;
+
+    /// The cost of moving directly from one location to another,
+    /// or None if there is no direct route.
+    fn travel_cost(&self, from: &str, to: &str) -> Option<f64>;
+
+    /// Generates one movement action per direct route.
+    /// Each action is named `move_{from}_to_{to}`, requires the location variable
+    /// to equal `from`, sets it to `to` and is tagged with [`MOVEMENT_TAG`].
+    fn movement_actions(&self) -> Vec<Action> {
+        let locations = self.locations();
+        let mut actions = Vec::new();
+        for from in &locations {
+            for to in &locations {
+                if from == to {
+                    continue;
+                }
+                if let Some(cost) = self.travel_cost(from, to) {
+                    actions.push(
+                        Action::new(&format!("move_{from}_to_{to}"))
+                            .cost(cost)
+                            .requires(self.key(), from.as_str())
+                            .sets(self.key(), to.as_str())
+                            .tag(MOVEMENT_TAG)
+                            .build(),
+                    );
+                }
+            }
+        }
+        actions
+    }
+}
+
+/// A transition model built from weighted connections between named locations.
+#[derive(Clone, Debug)]
+pub struct LocationGraph {
+    /// The state variable holding the current location
+    key: String,
+    /// Known locations, in the order they were added
+    locations: Vec<String>,
+    /// Direct routes as (from, to, cost)
+    routes: Vec<(String, String, f64)>,
+}
+
+impl LocationGraph {
+    /// Creates an empty graph for the given location variable.
+    pub fn new(key: &str) -> Self {
+        LocationGraph {
+            key: key.to_string(),
+            locations: Vec::new(),
+            routes: Vec::new(),
+        }
+    }
+
+    /// Adds a location without any routes.
+    pub fn location(mut self, name: &str) -> Self {
+        self.add_location(name);
+        self
+    }
+
+    /// Connects two locations in both directions with the same travel cost.
+    pub fn connect(self, a: &str, b: &str, cost: f64) -> Self {
+        self.connect_one_way(a, b, cost).connect_one_way(b, a, cost)
+    }
+
+    /// Adds a route from one location to another only.
+    /// Replaces the cost of an existing route between the same locations.
+    pub fn connect_one_way(mut self, from: &str, to: &str, cost: f64) -> Self {
+        self.add_location(from);
+        self.add_location(to);
+        self.routes.retain(|(f, t, _)| !(f == from && t == to));
+        self.routes.push((from.to_string(), to.to_string(), cost));
+        self
+    }
+
+    /// Builds a symbol table registering the locations and their connections,
+    /// so the planner can validate location values and use graph distances in its heuristic.
+    pub fn symbols(&self) -> SymbolTable {
+        let names: Vec<&str> = self.locations.iter().map(String::as_str).collect();
+        let mut symbols = SymbolTable::new().define(&self.key, &names);
+        for (from, to, _) in &self.routes {
+            symbols = symbols.connect(&self.key, from, to);
+        }
+        symbols
+    }
+
+    fn add_location(&mut self, name: &str) {
+        if !self.locations.iter().any(|location| location == name) {
+            self.locations.push(name.to_string());
+        }
+    }
+}
+
+impl TransitionModel for LocationGraph {
+    fn key(&self) -> &str {
+        &self.key
+    }
+
+    fn locations(&self) -> Vec<String> {
+        self.locations.clone()
+    }
+
+    fn travel_cost(&self, from: &str, to: &str) -> Option<f64> {
+        self.routes
+            .iter()
+            .find(|(f, t, _)| f == from && t == to)
+            .map(|(_, _, cost)| *cost)
+    }
+}
@@ -0,0 +1,63 @@
+use crate::actions::Action;
+use crate::state::State;
+
+/// Hooks invoked by the planner while it searches.
+///
+/// Implement this trait to feed planning activity into profilers or telemetry
+/// (puffin, Tracy, custom counters) without the library depending on them.
+/// Every method has an empty default implementation, so observers only need to
+/// override the events they care about.
+pub trait PlannerObserver {
+    /// Called when the planner starts expanding a state.
+    fn on_expand_begin(&mut self, _state: &State) {}
+
+    /// Called when the planner finishes expanding a state,
+    /// with the number of successor states that were generated.
+    fn on_expand_end(&mut self, _state: &State, _successors: usize) {}
+
+    /// Called after the heuristic has been evaluated for a newly discovered state.
+    fn on_heuristic(&mut self, _state: &State, _value: f64) {}
+
+    /// Called after a state has been tested against the goal.
+    fn on_goal_test(&mut self, _state: &State, _satisfied: bool) {}
+
+    /// Called when an action is applied to produce a successor state.
+    fn on_transition(&mut self, _from: &State, _action: &Action, _to: &State) {}
+}
+
+/// An observer that ignores every event. Used when no observer is supplied.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoopObserver;
+
+impl PlannerObserver for NoopObserver {}
+
+/// An observer that counts planner events, useful for quick profiling and tests.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CountingObserver {
+    /// Number of expansions started
+    pub expansions: usize,
+    /// Number of heuristic evaluations
+    pub heuristic_evaluations: usize,
+    /// Number of goal tests performed
+    pub goal_tests: usize,
+    /// Number of transitions generated
+    pub transitions: usize,
+}
+
+impl PlannerObserver for CountingObserver {
+    fn on_expand_begin(&mut self, _state: &State) {
+        self.expansions += 1;
+    }
+
+    fn on_heuristic(&mut self, _state: &State, _value: f64) {
+        self.heuristic_evaluations += 1;
+    }
+
+    fn on_goal_test(&mut self, _state: &State, _satisfied: bool) {
+        self.goal_tests += 1;
+    }
+
+    fn on_transition(&mut self, _from: &State, _action: &Action, _to: &State) {
+        self.transitions += 1;
+    }
+}
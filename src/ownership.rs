@@ -0,0 +1,95 @@
+//! Which agent may modify which state variables.
+//!
+//! When several agents plan over one shared state, an action authored for one
+//! agent must not change another agent's variables, e.g. a guard's action
+//! lowering the player's `health` by mistake. [`KeyOwnership`] records the owner
+//! of each variable and the variables every agent may modify. Give it to a
+//! [`PlanExecutor`](crate::executor::PlanExecutor) with
+//! [`ownership`](crate::executor::PlanExecutor::ownership) to reject actions that
+//! modify variables the executing agent neither owns nor shares.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let ownership = KeyOwnership::new()
+//!     .owns("guard", ["guard_health", "guard_alert"])
+//!     .owns("player", ["player_health"])
+//!     .shared("door_open");
+//!
+//! let open = Action::new("open_door").sets("door_open", true).build();
+//! let buggy = Action::new("patrol").subtracts("player_health", 5).build();
+//! assert!(ownership.violations("guard", &open).is_empty());
+//! assert_eq!(ownership.violations("guard", &buggy), ["player_health"]);
+//! ```
+
+use crate::actions::Action;
+use std::collections::{HashMap, HashSet};
+
+/// The owner of each state variable, and the variables shared by every agent.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeyOwnership {
+    /// The agent owning each variable
+    owners: HashMap<String, String>,
+    /// Variables any agent may modify
+    shared: HashSet<String>,
+}
+
+impl KeyOwnership {
+    /// Creates ownership metadata with no owned or shared variables.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes `agent` the owner of the given variables, replacing previous owners.
+    pub fn owns<I, S>(mut self, agent: &str, keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for key in keys {
+            let key = key.as_ref();
+            self.shared.remove(key);
+            self.owners.insert(key.to_string(), agent.to_string());
+        }
+        self
+    }
+
+    /// Declares a variable shared, so that every agent may modify it.
+    pub fn shared(mut self, key: &str) -> Self {
+        self.owners.remove(key);
+        self.shared.insert(key.to_string());
+        self
+    }
+
+    /// Returns the agent owning a variable, if any.
+    pub fn owner(&self, key: &str) -> Option<&str> {
+        self.owners.get(key).map(String::as_str)
+    }
+
+    /// Returns true if the variable is declared shared.
+    pub fn is_shared(&self, key: &str) -> bool {
+        self.shared.contains(key)
+    }
+
+    /// Returns true if `agent` owns the variable or it is shared. Variables that
+    /// are neither owned nor shared may not be modified by anyone.
+    pub fn may_modify(&self, agent: &str, key: &str) -> bool {
+        self.is_shared(key) || self.owner(key) == Some(agent)
+    }
+
+    /// Returns the variables the action may modify, on success or failure, that
+    /// `agent` is not allowed to modify, sorted.
+    pub fn violations(&self, agent: &str, action: &Action) -> Vec<String> {
+        let mut keys: Vec<String> = action
+            .effects
+            .keys()
+            .chain(action.dynamic_effects.keys())
+            .chain(action.failure_effects.keys())
+            .filter(|key| !self.may_modify(agent, key))
+            .cloned()
+            .collect();
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+}
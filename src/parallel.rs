@@ -0,0 +1,211 @@
+//! Running independent steps of a plan at the same time.
+//!
+//! Plans are sequences, but many of their steps do not depend on each other: a
+//! builder unit can craft while its battery charges. A [`ParallelExecutor`]
+//! derives the order the plan really needs from what each step reads and
+//! changes, and hands out up to a configured number of steps at once. A step
+//! starts only after every earlier step it depends on has finished, and steps
+//! holding the same resource, declared with
+//! [`ActionBuilder::exclusive`](crate::actions::ActionBuilder::exclusive), never
+//! run together.
+//!
+//! Step `b` depends on an earlier step `a` when `b` reads a variable `a`
+//! changes, `b` changes a variable `a` reads, or both change the same variable.
+//! The inputs of dynamic effects are not known, so steps with dynamic effects
+//! depend on every earlier step and every later step depends on them.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let actions = vec![
+//!     Action::new("charge_battery").sets("charged", true).build(),
+//!     Action::new("craft").sets("has_part", true).build(),
+//!     Action::new("build")
+//!         .requires("charged", true)
+//!         .requires("has_part", true)
+//!         .sets("built", true)
+//!         .build(),
+//! ];
+//! let goal = Goal::new("build").requires("built", true).build();
+//! let state = State::empty();
+//! let plan = Planner::new().plan(&state, &goal, &actions).unwrap();
+//!
+//! let mut executor = ParallelExecutor::new(plan).slots(2);
+//! assert_eq!(executor.start_ready(&state).unwrap().len(), 2);
+//! assert!(executor.ready().is_empty());
+//! ```
+
+use crate::actions::Action;
+use crate::clusters::{changed_keys, read_keys};
+use crate::executor::{ExecutionError, failed_keys};
+use crate::planner::Plan;
+use crate::state::State;
+use std::collections::HashSet;
+
+/// The progress of a step of a [`ParallelExecutor`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StepStatus {
+    /// The step has not started
+    Pending,
+    /// The step was started and has not finished
+    Running,
+    /// The step has finished
+    Finished,
+}
+
+/// Executes the steps of a plan, running independent steps at the same time.
+#[derive(Clone, Debug)]
+pub struct ParallelExecutor {
+    /// The plan being executed
+    plan: Plan,
+    /// The maximum number of steps running at once
+    slots: usize,
+    /// The earlier steps each step depends on
+    dependencies: Vec<Vec<usize>>,
+    /// The progress of each step
+    status: Vec<StepStatus>,
+}
+
+/// Returns true if `later` must wait for `earlier` to finish.
+fn depends(earlier: &Action, later: &Action) -> bool {
+    if !earlier.dynamic_effects.is_empty() || !later.dynamic_effects.is_empty() {
+        return true;
+    }
+    let earlier_changes: HashSet<&str> = changed_keys(earlier).map(String::as_str).collect();
+    let later_changes: HashSet<&str> = changed_keys(later).map(String::as_str).collect();
+    !earlier_changes.is_disjoint(&later_changes)
+        || read_keys(later)
+            .iter()
+            .any(|key| earlier_changes.contains(key))
+        || read_keys(earlier)
+            .iter()
+            .any(|key| later_changes.contains(key))
+}
+
+impl ParallelExecutor {
+    /// Creates an executor for the plan with a single slot, which runs the steps
+    /// one after the other.
+    pub fn new(plan: Plan) -> Self {
+        let dependencies = (0..plan.actions.len())
+            .map(|later| {
+                (0..later)
+                    .filter(|&earlier| depends(&plan.actions[earlier], &plan.actions[later]))
+                    .collect()
+            })
+            .collect();
+        let status = vec![StepStatus::Pending; plan.actions.len()];
+        ParallelExecutor {
+            plan,
+            slots: 1,
+            dependencies,
+            status,
+        }
+    }
+
+    /// Sets the maximum number of steps running at once (at least one).
+    pub fn slots(mut self, slots: usize) -> Self {
+        self.slots = slots.max(1);
+        self
+    }
+
+    /// Returns the plan being executed.
+    pub fn plan(&self) -> &Plan {
+        &self.plan
+    }
+
+    /// Returns the earlier steps the given step waits for, in plan order.
+    pub fn dependencies(&self, step: usize) -> &[usize] {
+        self.dependencies.get(step).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the progress of the given step, or `None` if it is out of range.
+    pub fn status(&self, step: usize) -> Option<StepStatus> {
+        self.status.get(step).copied()
+    }
+
+    /// Returns the running steps, in plan order.
+    pub fn running(&self) -> Vec<usize> {
+        self.steps_with(StepStatus::Running)
+    }
+
+    /// Returns true when every step has finished.
+    pub fn is_finished(&self) -> bool {
+        self.status
+            .iter()
+            .all(|status| *status == StepStatus::Finished)
+    }
+
+    /// Returns the steps that can start now, in plan order: pending steps whose
+    /// dependencies have finished and that hold no resource a running or earlier
+    /// ready step holds, up to the free slots.
+    pub fn ready(&self) -> Vec<usize> {
+        let mut held: HashSet<&str> = HashSet::new();
+        let mut ready = Vec::new();
+        let running = self.running();
+        for &step in &running {
+            held.extend(self.plan.actions[step].exclusive.iter().map(String::as_str));
+        }
+        let free = self.slots.saturating_sub(running.len());
+        for step in self.steps_with(StepStatus::Pending) {
+            if ready.len() >= free {
+                break;
+            }
+            let action = &self.plan.actions[step];
+            let joined = self.dependencies[step]
+                .iter()
+                .all(|&dependency| self.status[dependency] == StepStatus::Finished);
+            if joined
+                && action
+                    .exclusive
+                    .iter()
+                    .all(|resource| !held.contains(resource.as_str()))
+            {
+                held.extend(action.exclusive.iter().map(String::as_str));
+                ready.push(step);
+            }
+        }
+        ready
+    }
+
+    /// Starts every ready step and returns them; see [`ParallelExecutor::ready`].
+    ///
+    /// Returns an error, and starts nothing, if the preconditions of a ready step
+    /// do not hold in the current state.
+    pub fn start_ready(&mut self, state: &State) -> Result<Vec<usize>, ExecutionError> {
+        let ready = self.ready();
+        for &step in &ready {
+            let action = &self.plan.actions[step];
+            let keys = failed_keys(action, state, |_| true);
+            if !keys.is_empty() {
+                return Err(ExecutionError::PreconditionFailed {
+                    step,
+                    action: action.name.clone(),
+                    keys,
+                });
+            }
+        }
+        for &step in &ready {
+            self.status[step] = StepStatus::Running;
+        }
+        Ok(ready)
+    }
+
+    /// Records that a running step finished, freeing its slot and resources.
+    /// Returns false, changing nothing, if the step was not running.
+    pub fn finished(&mut self, step: usize) -> bool {
+        match self.status.get_mut(step) {
+            Some(status) if *status == StepStatus::Running => {
+                *status = StepStatus::Finished;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the steps with the given progress, in plan order.
+    fn steps_with(&self, wanted: StepStatus) -> Vec<usize> {
+        (0..self.status.len())
+            .filter(|&step| self.status[step] == wanted)
+            .collect()
+    }
+}
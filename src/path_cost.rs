@@ -0,0 +1,125 @@
+//! Action costs that depend on the plan so far.
+//!
+//! An action's cost is normally fixed, but some domains want to price an action
+//! by what came before it: escalating the cost of repeating the same attack, or
+//! discounting a finisher that follows its setup move. A [`PathCost`] set with
+//! [`PlannerConfig::path_cost`](crate::planner::PlannerConfig::path_cost)
+//! computes the cost of every step from the action, the state it is taken in and
+//! a [`PathContext`] listing the actions before it.
+//!
+//! The A* search uses path costs; the other strategies and sampling keep using
+//! the actions' own costs. Costs should not be negative. The search keeps one
+//! path per state, so when two paths reach the same state only the cheaper one is
+//! continued, even if the other would make later steps cheaper: the plan is the
+//! cheapest among the paths the search keeps rather than guaranteed optimal.
+//! The path is only collected when a path cost is set, so other plans pay nothing.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let actions = vec![
+//!     Action::new("slash").adds("damage", 3).cost(1.0).build(),
+//!     Action::new("fireball").adds("damage", 3).cost(1.5).build(),
+//! ];
+//! let goal = Goal::new("kill").requires("damage", 9).build();
+//!
+//! // Repeating the previous action costs one more than usual
+//! let cost = PathCost::new(|action: &Action, _: &State, path: &PathContext| {
+//!     action.cost + path.repeats(&action.name) as f64
+//! });
+//! let planner = Planner::with_config(PlannerConfig::new().path_cost(cost));
+//! let plan = planner
+//!     .plan(State::new().set("damage", 0).build(), &goal, &actions)
+//!     .unwrap();
+//! let names: Vec<&str> = plan.actions.iter().map(|a| a.name.as_str()).collect();
+//! assert_eq!(names.len(), 3);
+//! assert!(names.windows(2).all(|pair| pair[0] != pair[1]));
+//! ```
+
+use crate::actions::Action;
+use crate::state::State;
+use std::fmt;
+use std::sync::Arc;
+
+/// The actions taken before a step, oldest first.
+#[derive(Clone, Debug, Default)]
+pub struct PathContext<'a> {
+    /// The actions of the path, oldest first
+    actions: Vec<&'a Action>,
+}
+
+impl<'a> PathContext<'a> {
+    /// Creates a context from the actions taken so far, oldest first.
+    pub fn new(actions: Vec<&'a Action>) -> Self {
+        PathContext { actions }
+    }
+
+    /// Returns the actions taken so far, oldest first.
+    pub fn actions(&self) -> &[&'a Action] {
+        &self.actions
+    }
+
+    /// Returns the number of actions taken so far.
+    pub fn depth(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Returns the most recent action, if any.
+    pub fn last(&self) -> Option<&'a Action> {
+        self.actions.last().copied()
+    }
+
+    /// Returns how many times in a row the path ends with the named action.
+    pub fn repeats(&self, name: &str) -> usize {
+        self.actions
+            .iter()
+            .rev()
+            .take_while(|action| action.name == name)
+            .count()
+    }
+
+    /// Returns how many times the path contains the named action.
+    pub fn count(&self, name: &str) -> usize {
+        self.actions
+            .iter()
+            .filter(|action| action.name == name)
+            .count()
+    }
+
+    /// Appends an action to the path.
+    pub(crate) fn push(&mut self, action: &'a Action) {
+        self.actions.push(action);
+    }
+}
+
+/// Type alias for the function computing a step's cost.
+pub type PathCostFn = dyn Fn(&Action, &State, &PathContext<'_>) -> f64 + Send + Sync;
+
+/// Computes the cost of a step from its action, the state it is taken in and the
+/// actions before it.
+#[derive(Clone)]
+pub struct PathCost {
+    /// The function computing the cost
+    func: Arc<PathCostFn>,
+}
+
+impl PathCost {
+    /// Creates a path cost from a function of the action, the state and the path.
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(&Action, &State, &PathContext<'_>) -> f64 + Send + Sync + 'static,
+    {
+        PathCost { func: Arc::new(f) }
+    }
+
+    /// Computes the cost of taking `action` in `state` after the actions of `path`.
+    pub fn evaluate(&self, action: &Action, state: &State, path: &PathContext<'_>) -> f64 {
+        (self.func)(action, state, path)
+    }
+}
+
+impl fmt::Debug for PathCost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PathCost(<fn>)")
+    }
+}
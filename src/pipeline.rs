@@ -0,0 +1,269 @@
+//! Trying several planning strategies in order.
+//!
+//! Production agents rarely call the planner once: they first look for a plan
+//! they already have, then try to reuse the rest of their previous plan, then
+//! run a quick greedy search, and only then pay for a full search. A
+//! [`PlannerPipeline`] chains these [`PipelineStage`]s, each with its own budget,
+//! and reports which stage produced the plan.
+//!
+//! External backends join the pipeline as [`PipelineStage::Solver`] stages; see
+//! [`Solver`].
+//!
+//! The pipeline remembers the last plan found for each goal, by name, together
+//! with the state it was planned from; the cache and repair stages read it. The
+//! remembered plans assume the actions do not change between calls: call
+//! [`PlannerPipeline::clear`] when they do.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let actions = vec![
+//!     Action::new("chop").sets("has_wood", true).build(),
+//!     Action::new("build").requires("has_wood", true).sets("has_hut", true).build(),
+//! ];
+//! let goal = Goal::new("shelter").requires("has_hut", true).build();
+//! let mut pipeline = PlannerPipeline::new(Planner::new())
+//!     .cache()
+//!     .repair()
+//!     .greedy(5.0, 100)
+//!     .search(None);
+//!
+//! let start = State::new().set("has_wood", false).build();
+//! let first = pipeline.plan(&start, &goal, &actions).unwrap();
+//! assert_eq!(first.name, "greedy");
+//! assert_eq!(pipeline.plan(&start, &goal, &actions).unwrap().name, "cache");
+//!
+//! // After chopping, the rest of the previous plan still works
+//! let chopped = State::new().set("has_wood", true).build();
+//! let repaired = pipeline.plan(&chopped, &goal, &actions).unwrap();
+//! assert_eq!(repaired.name, "repair");
+//! assert_eq!(repaired.plan.actions[0].name, "build");
+//! ```
+
+use crate::actions::Action;
+use crate::goals::Goal;
+use crate::planner::{Plan, Planner, PlannerError};
+use crate::solver::Solver;
+use crate::state::State;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A strategy tried by a [`PlannerPipeline`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PipelineStage {
+    /// Returns the last plan found for the goal if it was planned from the same state
+    Cache,
+    /// Returns the cheapest suffix of the last plan found for the goal that can be
+    /// executed from the state and achieves the goal
+    Repair,
+    /// Runs a weighted A* search, which favours states close to the goal over cheap
+    /// paths, giving up after `node_budget` expansions
+    Greedy {
+        /// The weight of the heuristic (values below 1.0 are treated as 1.0)
+        weight: f64,
+        /// The maximum number of expansions
+        node_budget: usize,
+    },
+    /// Runs the planner's configured search; with a node budget, runs an A*
+    /// search that gives up after that many expansions instead
+    Search {
+        /// The maximum number of expansions, if limited
+        node_budget: Option<usize>,
+    },
+    /// Asks an external [`Solver`] added with [`PlannerPipeline::solver`], by its
+    /// index among the pipeline's solvers, and checks the plan it returns with
+    /// [`Solver::solve_checked`]
+    Solver {
+        /// The index of the solver
+        index: usize,
+    },
+}
+
+impl PipelineStage {
+    /// Returns the name of the stage, as reported in [`PipelinePlan::name`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            PipelineStage::Cache => "cache",
+            PipelineStage::Repair => "repair",
+            PipelineStage::Greedy { .. } => "greedy",
+            PipelineStage::Search { .. } => "search",
+            PipelineStage::Solver { .. } => "solver",
+        }
+    }
+}
+
+/// A plan found by a [`PlannerPipeline`] and the stage that found it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PipelinePlan {
+    /// The plan
+    pub plan: Plan,
+    /// The index of the stage that found the plan
+    pub stage: usize,
+    /// The name of the stage that found the plan
+    pub name: &'static str,
+}
+
+/// Tries planning strategies in order until one finds a plan.
+#[derive(Clone, Debug)]
+pub struct PlannerPipeline {
+    /// The planner used by the search stages
+    planner: Planner,
+    /// The stages, in the order they are tried
+    stages: Vec<PipelineStage>,
+    /// The external solvers asked by solver stages
+    solvers: Vec<Arc<dyn Solver>>,
+    /// The last plan found per goal name, with the state it was planned from
+    plans: HashMap<String, (State, Plan)>,
+}
+
+impl PlannerPipeline {
+    /// Creates a pipeline without stages, whose search stages use `planner`.
+    pub fn new(planner: Planner) -> Self {
+        PlannerPipeline {
+            planner,
+            stages: Vec::new(),
+            solvers: Vec::new(),
+            plans: HashMap::new(),
+        }
+    }
+
+    /// Appends a stage.
+    pub fn stage(mut self, stage: PipelineStage) -> Self {
+        self.stages.push(stage);
+        self
+    }
+
+    /// Appends a [`PipelineStage::Cache`] stage.
+    pub fn cache(self) -> Self {
+        self.stage(PipelineStage::Cache)
+    }
+
+    /// Appends a [`PipelineStage::Repair`] stage.
+    pub fn repair(self) -> Self {
+        self.stage(PipelineStage::Repair)
+    }
+
+    /// Appends a [`PipelineStage::Greedy`] stage.
+    pub fn greedy(self, weight: f64, node_budget: usize) -> Self {
+        self.stage(PipelineStage::Greedy {
+            weight,
+            node_budget,
+        })
+    }
+
+    /// Appends a [`PipelineStage::Search`] stage.
+    pub fn search(self, node_budget: Option<usize>) -> Self {
+        self.stage(PipelineStage::Search { node_budget })
+    }
+
+    /// Appends a [`PipelineStage::Solver`] stage asking `solver`.
+    pub fn solver(mut self, solver: Arc<dyn Solver>) -> Self {
+        let index = self.solvers.len();
+        self.solvers.push(solver);
+        self.stage(PipelineStage::Solver { index })
+    }
+
+    /// Returns the stages, in the order they are tried.
+    pub fn stages(&self) -> &[PipelineStage] {
+        &self.stages
+    }
+
+    /// Forgets the remembered plans.
+    pub fn clear(&mut self) {
+        self.plans.clear();
+    }
+
+    /// Forgets the remembered plan for the named goal.
+    pub fn forget(&mut self, goal: &str) {
+        self.plans.remove(goal);
+    }
+
+    /// Tries the stages in order and returns the first plan found, remembering it
+    /// for the goal.
+    ///
+    /// Returns the error of the last stage that failed with one, or
+    /// [`PlannerError::NoPlanFound`] if no stage produced a plan or an error.
+    pub fn plan(
+        &mut self,
+        state: &State,
+        goal: &Goal,
+        actions: &[Action],
+    ) -> Result<PipelinePlan, PlannerError> {
+        let mut last_error = None;
+        for (index, stage) in self.stages.iter().enumerate() {
+            let result = match *stage {
+                PipelineStage::Cache => Ok(self
+                    .plans
+                    .get(&goal.name)
+                    .filter(|(start, _)| start == state)
+                    .map(|(_, plan)| plan.clone())),
+                PipelineStage::Repair => Ok(self
+                    .plans
+                    .get(&goal.name)
+                    .and_then(|(_, plan)| repair(state, goal, plan))),
+                PipelineStage::Greedy {
+                    weight,
+                    node_budget,
+                } => self
+                    .planner
+                    .plan_weighted(state, goal, actions, weight.max(1.0), Some(node_budget))
+                    .map(Some),
+                PipelineStage::Search { node_budget: None } => {
+                    self.planner.plan(state, goal, actions).map(Some)
+                }
+                PipelineStage::Search {
+                    node_budget: Some(node_budget),
+                } => self
+                    .planner
+                    .plan_weighted(state, goal, actions, 1.0, Some(node_budget))
+                    .map(Some),
+                PipelineStage::Solver { index } => match self.solvers.get(index) {
+                    Some(solver) => solver.solve_checked(state, goal, actions).map(Some),
+                    None => Ok(None),
+                },
+            };
+            match result {
+                Ok(Some(plan)) => {
+                    self.plans
+                        .insert(goal.name.clone(), (state.clone(), plan.clone()));
+                    return Ok(PipelinePlan {
+                        plan,
+                        stage: index,
+                        name: stage.name(),
+                    });
+                }
+                Ok(None) => {}
+                Err(error) => last_error = Some(error),
+            }
+        }
+        Err(last_error.unwrap_or(PlannerError::NoPlanFound))
+    }
+}
+
+/// Returns the cheapest suffix of `plan` that can be executed from `state` and
+/// achieves the goal, if there is one.
+fn repair(state: &State, goal: &Goal, plan: &Plan) -> Option<Plan> {
+    let mut best: Option<(usize, f64)> = None;
+    for start in 0..=plan.actions.len() {
+        let suffix = &plan.actions[start..];
+        let cost: f64 = suffix.iter().map(|action| action.cost).sum();
+        if best.is_some_and(|(_, best_cost)| best_cost <= cost) {
+            continue;
+        }
+        let mut current = state.clone();
+        let executable = suffix.iter().all(|action| {
+            let ok = action.can_execute(&current);
+            current = action.apply_effect(&current);
+            ok
+        });
+        if executable && goal.is_satisfied(&current) {
+            best = Some((start, cost));
+        }
+    }
+    let (start, cost) = best?;
+    Some(Plan {
+        actions: plan.actions[start..].to_vec(),
+        cost,
+        ..Plan::default()
+    })
+}
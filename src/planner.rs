@@ -1,18 +1,127 @@
 use crate::actions::Action;
+use crate::analysis::{DeadEndAnalysis, analyze_dead_ends, analyze_dead_ends_with_schema};
+use crate::clock::{Clock, StdClock};
+use crate::clusters::{GoalClusters, goal_keys, read_keys};
+use crate::dominance::{dominance_signature, dominates};
 use crate::goals::Goal;
-use crate::state::State;
+use crate::mcts::{MonteCarloConfig, RandomRollout};
+use crate::mutex::MutexGroup;
+use crate::observer::{NoopObserver, PlannerObserver};
+use crate::path_cost::{PathContext, PathCost};
+use crate::profiles::PlannerProfile;
+use crate::regression::regress;
+use crate::sampling::SamplingConfig;
+use crate::schema::StateSchema;
+use crate::state::{CompactDisplay, State, StateOperation, StateVar, value_satisfies};
+use crate::strings::{StringComparison, StringComparisons};
+use crate::symbols::{SymbolError, SymbolTable};
+use std::borrow::Cow;
 use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap};
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet};
 use std::error::Error;
 use std::fmt;
+use std::mem::size_of;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Errors that can occur during planning.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum PlannerError {
     /// No valid sequence of actions could be found to achieve the goal
     NoPlanFound,
     /// State variables have incompatible types for comparison
     IncompatibleStateTypes(String),
+    /// No single plan achieves the goal for every combination of the unknown initial values
+    NoConformantPlan,
+    /// A string value is not registered in the configured symbol table
+    UnknownSymbol(SymbolError),
+    /// A variable is used with different types across the initial state, goal and actions.
+    /// Only reported when `PlannerConfig::strict_types` is enabled.
+    TypeConflict {
+        /// The conflicting variable
+        key: String,
+        /// The type found at the conflicting use
+        found: &'static str,
+        /// The type established by the initial state, the goal or an earlier action
+        expected: &'static str,
+        /// The action containing the conflicting use, or None if it is in the goal
+        action: Option<String>,
+    },
+    /// The goal selects a profile that is neither configured nor built in
+    UnknownProfile(String),
+    /// An action declares an inverse that is not among the available actions
+    UnknownInverse(String),
+    /// An action of a path seeded into a planning session is unknown or cannot be
+    /// applied where the path reaches it
+    InvalidSeed(String),
+    /// The search hit a limit set with [`PlannerConfig::max_nodes_expanded`],
+    /// [`PlannerConfig::max_plan_length`], [`PlannerConfig::max_duration`] or
+    /// [`PlannerConfig::max_memory_bytes`].
+    /// Carries the plan towards the explored state closest to the goal, whose
+    /// statistics describe the search so far.
+    ///
+    /// Running out of memory is reported here too, with `budget` set to
+    /// [`SearchBudget::Memory`] holding the configured cap; the memory the search
+    /// had used is in `best_partial.stats.memory_bytes`. Match on it to handle the
+    /// memory limit alone:
+    ///
+    /// ```
+    /// use goap::prelude::*;
+    ///
+    /// # let goal = Goal::new("far").requires("n", 50).build();
+    /// # let actions = vec![
+    /// #     Action::new("step").adds("n", 1).build(),
+    /// #     Action::new("skip").adds("n", 2).cost(3.0).build(),
+    /// # ];
+    /// let planner = Planner::with_config(PlannerConfig::new().max_memory_bytes(1));
+    /// match planner.plan(&state! { n: 0 }, &goal, &actions) {
+    ///     Err(PlannerError::BudgetExceeded { budget: SearchBudget::Memory(cap), best_partial }) => {
+    ///         assert_eq!(cap, 1);
+    ///         assert!(best_partial.stats.memory_bytes > cap);
+    ///     }
+    ///     other => panic!("expected the memory limit, got {other:?}"),
+    /// }
+    /// ```
+    BudgetExceeded {
+        /// The limit that was hit
+        budget: SearchBudget,
+        /// The plan to the explored state with the lowest heuristic distance to the goal
+        best_partial: Box<Plan>,
+    },
+    /// An external [`Solver`](crate::solver::Solver) failed or returned a plan that
+    /// does not work
+    SolverFailed {
+        /// The name of the solver
+        solver: String,
+        /// Why the solver failed
+        reason: String,
+    },
+}
+
+/// A search limit reported by [`PlannerError::BudgetExceeded`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchBudget {
+    /// The maximum number of node expansions
+    NodesExpanded(usize),
+    /// The maximum number of actions in a plan; reported when the search found no
+    /// plan within the length but cut off longer paths
+    PlanLength(usize),
+    /// The maximum search time
+    Duration(Duration),
+    /// The approximate memory cap of the search structures, in bytes; the memory
+    /// used is in the partial plan's statistics
+    Memory(usize),
+}
+
+impl fmt::Display for SearchBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchBudget::NodesExpanded(nodes) => write!(f, "{nodes} node expansions"),
+            SearchBudget::PlanLength(length) => write!(f, "plan length of {length}"),
+            SearchBudget::Duration(duration) => write!(f, "duration of {duration:?}"),
+            SearchBudget::Memory(bytes) => write!(f, "memory of {bytes} bytes"),
+        }
+    }
 }
 
 impl fmt::Display for PlannerError {
@@ -22,25 +131,226 @@ impl fmt::Display for PlannerError {
             PlannerError::IncompatibleStateTypes(msg) => {
                 write!(f, "Incompatible state types: {msg}")
             }
+            PlannerError::NoConformantPlan => {
+                write!(
+                    f,
+                    "No plan achieves the goal for every possible initial state"
+                )
+            }
+            PlannerError::UnknownSymbol(error) => write!(f, "Unknown symbol: {error}"),
+            PlannerError::TypeConflict {
+                key,
+                found,
+                expected,
+                action,
+            } => {
+                write!(
+                    f,
+                    "Type conflict for '{key}': found {found}, expected {expected}"
+                )?;
+                match action {
+                    Some(action) => write!(f, " (in action '{action}')"),
+                    None => write!(f, " (in goal)"),
+                }
+            }
+            PlannerError::UnknownProfile(name) => write!(f, "Unknown planner profile '{name}'"),
+            PlannerError::UnknownInverse(name) => write!(f, "Unknown inverse action '{name}'"),
+            PlannerError::InvalidSeed(name) => {
+                write!(f, "Seeded action '{name}' is unknown or cannot be applied")
+            }
+            PlannerError::BudgetExceeded {
+                budget,
+                best_partial,
+            } => write!(
+                f,
+                "Search budget of {budget} exceeded after {} node expansions; \
+                 best partial plan has {} steps",
+                best_partial.stats.nodes_expanded,
+                best_partial.actions.len()
+            ),
+            PlannerError::SolverFailed { solver, reason } => {
+                write!(f, "Solver '{solver}' failed: {reason}")
+            }
         }
     }
 }
 
 impl Error for PlannerError {}
 
+/// Statistics collected while searching for a plan.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlanStats {
+    /// Number of states taken from the open set and expanded
+    pub nodes_expanded: usize,
+    /// Number of successor states generated
+    pub nodes_generated: usize,
+    /// Number of generated states pruned because an explored state dominated them
+    /// (see [`PlannerConfig::dominance_pruning`])
+    pub nodes_dominated: usize,
+    /// Number of generated states pruned because the goal is provably unreachable
+    /// from them (see [`PlannerConfig::dead_end_pruning`])
+    pub dead_ends: usize,
+    /// Approximate peak memory used by the search structures, in bytes
+    pub memory_bytes: usize,
+}
+
 /// A plan represents a sequence of actions that will achieve a goal.
 /// It includes the actions to perform and the total cost of execution.
-#[derive(Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct Plan {
     /// The sequence of actions to perform in order
     pub actions: Vec<Action>,
     /// The total cost of executing all actions in the plan
     pub cost: f64,
+    /// Statistics about the search that produced this plan
+    pub stats: PlanStats,
+    /// The net change of each currency tracked with [`PlannerConfig::track_currency`]
+    /// from the initial state to the end of the plan; negative when spent. Left
+    /// empty by conformant plans, whose outcome depends on the unknown variables
+    pub currencies: BTreeMap<String, f64>,
+    /// The variables of the initial state the plan relies on, with their values
+    /// (see [`Assumption`]). Left empty by plans that are not searched from a
+    /// known initial state
+    pub assumptions: BTreeMap<String, Assumption>,
+}
+
+/// A value of the initial state a plan relies on.
+///
+/// A variable is an assumption when a precondition or the goal reads it before
+/// any step of the plan sets it, so its initial value decides whether the plan
+/// works. While executing, checking only these variables tells whether the plan
+/// is still valid: see [`Plan::broken_assumptions`]. The check is conservative,
+/// since any change counts even if the new value would also do, and cannot see
+/// variables read only by the closures of dynamic effects.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Assumption {
+    /// The value the plan was found with, or `None` if the variable was missing
+    pub value: Option<StateVar>,
+    /// The index of the first step that changes the variable, or the length of
+    /// the plan if none does. The value is only expected to hold until this step
+    /// is performed
+    pub until: usize,
+}
+
+/// Plans are equal when they contain the same actions (by name) in the same order
+/// and have the same total cost. Search statistics are not compared. A NaN cost
+/// equals itself, so that equality stays reflexive and `Plan` can be `Eq`.
+impl PartialEq for Plan {
+    fn eq(&self, other: &Self) -> bool {
+        (self.cost == other.cost || (self.cost.is_nan() && other.cost.is_nan()))
+            && self.actions.len() == other.actions.len()
+            && self
+                .actions
+                .iter()
+                .zip(&other.actions)
+                .all(|(a, b)| a.name == b.name)
+    }
 }
 
+impl Eq for Plan {}
+
+impl Plan {
+    /// Returns the assumptions that `state` breaks when the plan is about to perform
+    /// `step`, sorted. Assumptions about variables an earlier step has already
+    /// changed are not checked.
+    ///
+    /// ```
+    /// use goap::prelude::*;
+    ///
+    /// let goal = Goal::new("fed").requires("fed", true).build();
+    /// let actions = vec![
+    ///     Action::new("buy_food").requires("shop_open", true).sets("fed", true).build(),
+    /// ];
+    /// let mut state = State::new().set("shop_open", true).set("weather", "sunny").build();
+    /// let plan = Planner::new().plan(&state, &goal, &actions).unwrap();
+    ///
+    /// // The weather does not matter to the plan; the shop does
+    /// state.set("weather", "rain");
+    /// assert!(plan.broken_assumptions(0, &state).is_empty());
+    /// state.set("shop_open", false);
+    /// assert_eq!(plan.broken_assumptions(0, &state), ["shop_open"]);
+    /// ```
+    pub fn broken_assumptions(&self, step: usize, state: &State) -> Vec<&str> {
+        self.assumptions
+            .iter()
+            .filter(|(key, assumption)| {
+                assumption.until >= step && state.vars.get(*key) != assumption.value.as_ref()
+            })
+            .map(|(key, _)| key.as_str())
+            .collect()
+    }
+
+    /// Returns true if `state` keeps every assumption still relevant when the plan is
+    /// about to perform `step` (see [`Plan::broken_assumptions`]).
+    pub fn assumptions_hold(&self, step: usize, state: &State) -> bool {
+        self.broken_assumptions(step, state).is_empty()
+    }
+
+    /// Checks whether executing this plan from `step` onwards still achieves the goal
+    /// when starting in `state`.
+    ///
+    /// After an interruption, an executor can use this to skip steps that are already
+    /// done instead of replanning. The check regresses the goal through the remaining
+    /// steps (see [`regress`]) and tests `state` against the result, so it is
+    /// conservative for actions whose effects cannot be regressed exactly.
+    /// Excluded values and conditions of the goal are checked by simulating the
+    /// remaining steps.
+    /// Returns false if `step` is past the end of the plan.
+    pub fn can_resume_at(&self, step: usize, state: &State, goal: &Goal) -> bool {
+        let Some(remaining) = self.actions.get(step..) else {
+            return false;
+        };
+        let regressed = regress(&goal.desired_state, remaining)
+            .is_some_and(|required| state.satisfies(&required));
+        regressed
+            && ((goal.excluded.vars.is_empty() && goal.conditions.is_empty())
+                || goal.is_satisfied(&remaining.iter().fold(state.clone(), |current, action| {
+                    action.apply_effect(&current)
+                })))
+    }
+
+    /// Returns the net change of a tracked currency over the plan, e.g. -240.0 for
+    /// a plan spending 240 gold, or None if the currency is not tracked.
+    pub fn currency_change(&self, key: &str) -> Option<f64> {
+        self.currencies.get(key).copied()
+    }
+
+    /// Returns a view of the plan formatting it on a single line listing the action
+    /// names, e.g. `Plan (total cost: 3.0): chop -> build`; the same as formatting
+    /// with `{:#}`.
+    pub fn display_compact(&self) -> CompactDisplay<'_, Self> {
+        CompactDisplay::new(self)
+    }
+
+    /// Returns the steps of the plan whose action carries the given tag, in order.
+    pub fn actions_with_tag<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a Action> {
+        self.actions
+            .iter()
+            .filter(move |action| action.has_tag(tag))
+    }
+}
+
+/// Lists the steps one per line, or on a single line such as
+/// `Plan (total cost: 3.0): chop -> build` with the alternate flag (`{:#}`).
 impl fmt::Display for Plan {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "Plan (total cost: {:.1}): ", self.cost)?;
+            if self.actions.is_empty() {
+                return write!(f, "no steps");
+            }
+            let names: Vec<&str> = self.actions.iter().map(|a| a.name.as_str()).collect();
+            return write!(f, "{}", names.join(" -> "));
+        }
         writeln!(f, "Plan (total cost: {:.1}):", self.cost)?;
+        if !self.currencies.is_empty() {
+            let changes: Vec<String> = self
+                .currencies
+                .iter()
+                .map(|(key, change)| format!("{key} {change:+}"))
+                .collect();
+            writeln!(f, "Currencies: {}", changes.join(", "))?;
+        }
         for (i, action) in self.actions.iter().enumerate() {
             writeln!(f, "Step {}: {}", i + 1, action)?;
         }
@@ -48,20 +358,340 @@ impl fmt::Display for Plan {
     }
 }
 
-/// A planner that uses A* search to find optimal sequences of actions.
-/// The planner is stateless and can be reused for multiple planning requests.
-pub struct Planner {}
+/// The result of planning with a limited horizon.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HorizonPlan {
+    /// The plan found within the horizon
+    pub plan: Plan,
+    /// True if the plan reaches the goal; false if it is only a prefix
+    /// leading to the explored state closest to the goal
+    pub complete: bool,
+}
+
+/// The result of [`Planner::plan_anytime`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AnytimePlan {
+    /// The plan found before the search was interrupted
+    pub plan: Plan,
+    /// True if the plan reaches the goal; false if the search ran out of budget
+    /// and the plan only leads to the explored state closest to the goal
+    pub complete: bool,
+}
 
-impl Default for Planner {
-    fn default() -> Self {
-        Self::new()
+/// A successor of a state: the action taken and the state it leads to.
+#[derive(Clone, Debug)]
+pub struct Transition<'a> {
+    /// The action that was applied
+    pub action: &'a Action,
+    /// The position of the action in the slice passed to [`Planner::expand`]
+    pub action_index: usize,
+    /// The state after applying the action
+    pub state: State,
+    /// The cost of the action
+    pub cost: f64,
+}
+
+/// The search algorithm used by [`Planner::plan`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SearchStrategy {
+    /// A* search, optimal with respect to the heuristic (the default)
+    #[default]
+    AStar,
+    /// Monte Carlo Tree Search with random rollouts, for very large domains
+    MonteCarlo(MonteCarloConfig),
+    /// Beam search keeping only the best `width` states per depth, for domains
+    /// with very high branching. Fast but neither optimal nor complete: like A*, it
+    /// does not terminate in unbounded domains if the beam never reaches the goal.
+    Beam {
+        /// Number of states kept per depth (values below 1 are treated as 1)
+        width: usize,
+    },
+}
+
+/// A heuristic weight that starts high and anneals towards 1.0 as a node budget is used.
+///
+/// Early searches are greedy and find a plan quickly; later ones weigh the heuristic
+/// less and look for cheaper plans, each bounded by the best cost found so far.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WeightSchedule {
+    /// The heuristic weight of the first search (values below 1.0 are treated as 1.0)
+    pub initial_weight: f64,
+    /// The total number of node expansions shared by all searches
+    pub node_budget: usize,
+}
+
+impl WeightSchedule {
+    /// Returns the weight to use once `consumed` nodes of the budget have been expanded.
+    /// Decreases linearly from the initial weight to 1.0 over the budget.
+    pub fn weight_at(&self, consumed: usize) -> f64 {
+        let remaining = 1.0 - (consumed as f64 / self.node_budget.max(1) as f64).min(1.0);
+        1.0 + (self.initial_weight - 1.0).max(0.0) * remaining
     }
 }
 
+/// Configuration options for the planner.
+/// Use the fluent setters to override the defaults.
+#[derive(Clone, Debug, Default)]
+pub struct PlannerConfig {
+    /// Approximate memory cap for the search structures, in bytes.
+    /// When exceeded, planning stops with `PlannerError::BudgetExceeded`.
+    pub max_memory_bytes: Option<usize>,
+    /// When true, variable types are checked across the initial state, goal and actions
+    /// before searching, and conflicts are reported as `PlannerError::TypeConflict`
+    /// instead of silently producing unsatisfiable conditions.
+    pub strict_types: bool,
+    /// Allowed string values per variable. When set, the initial state, goal and
+    /// actions are validated before searching, and adjacency between values is used
+    /// as the heuristic distance for those variables.
+    pub symbols: Option<SymbolTable>,
+    /// Groups of boolean variables of which exactly one is true.
+    /// Actions are completed to respect the groups and violating states are pruned.
+    pub mutex_groups: Vec<MutexGroup>,
+    /// When set, planning runs a series of weighted searches within a node budget
+    /// and returns the cheapest plan found.
+    pub annealing: Option<WeightSchedule>,
+    /// The search algorithm to use
+    pub strategy: SearchStrategy,
+    /// Comparison rules for string variables. String values are rewritten into
+    /// their canonical form before searching.
+    pub string_comparisons: StringComparisons,
+    /// The clock measuring [`PlannerConfig::max_duration`]; a [`StdClock`] when not set
+    pub clock: Option<Arc<dyn Clock>>,
+    /// Actions carrying any of these tags are left out of planning
+    pub excluded_tags: Vec<String>,
+    /// Goal clusters narrowing the actions [`Planner::plan`] searches per goal
+    pub goal_clusters: Option<Arc<GoalClusters>>,
+    /// Numeric variables whose net change is reported on every plan
+    pub currencies: Vec<String>,
+    /// Named search settings that goals select, in addition to the presets
+    pub profiles: BTreeMap<String, PlannerProfile>,
+    /// When true, the A* search prunes states dominated by a state reached at no
+    /// greater cost (see the [`dominance`](crate::dominance) module)
+    pub dominance_pruning: bool,
+    /// When true, the A* search prunes states from which the goal is provably
+    /// unreachable (see [`analyze_dead_ends`])
+    pub dead_end_pruning: bool,
+    /// Metadata for the state variables; its monotonic declarations prune
+    /// transitions that would decrease a monotonic variable
+    pub schema: Option<Arc<StateSchema>>,
+    /// When set, A* planning samples varied, near-optimal plans instead of
+    /// always returning the cheapest one
+    pub sampling: Option<SamplingConfig>,
+    /// When set, the A* search prices every step with this function of the plan
+    /// so far instead of the action's own cost
+    pub path_cost: Option<PathCost>,
+    /// Maximum number of nodes an A* search may expand. When exceeded, planning
+    /// stops with `PlannerError::BudgetExceeded`.
+    pub max_nodes_expanded: Option<usize>,
+    /// Maximum number of actions in an A* plan. Longer paths are not explored, and
+    /// a search that finds no plan within the length stops with
+    /// `PlannerError::BudgetExceeded`.
+    pub max_plan_length: Option<usize>,
+    /// Maximum time an A* search may run, measured by the configured clock. When
+    /// exceeded, planning stops with `PlannerError::BudgetExceeded`.
+    pub max_duration: Option<Duration>,
+}
+
+impl PlannerConfig {
+    /// Creates a configuration with default settings (no limits).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Caps the approximate memory used by the search structures.
+    pub fn max_memory_bytes(mut self, bytes: usize) -> Self {
+        self.max_memory_bytes = Some(bytes);
+        self
+    }
+
+    /// Limits the number of nodes an A* search may expand, so searches over large
+    /// action sets cannot hang.
+    pub fn max_nodes_expanded(mut self, nodes: usize) -> Self {
+        self.max_nodes_expanded = Some(nodes);
+        self
+    }
+
+    /// Limits the number of actions in an A* plan.
+    pub fn max_plan_length(mut self, length: usize) -> Self {
+        self.max_plan_length = Some(length);
+        self
+    }
+
+    /// Limits how long an A* search may run, reporting
+    /// `PlannerError::BudgetExceeded` when the time runs out.
+    pub fn max_duration(mut self, duration: Duration) -> Self {
+        self.max_duration = Some(duration);
+        self
+    }
+
+    /// Sets the clock used to measure the maximum duration, for targets where
+    /// `std::time::Instant` is unavailable.
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Enables or disables up-front type checking of the planning problem.
+    pub fn strict_types(mut self, strict: bool) -> Self {
+        self.strict_types = strict;
+        self
+    }
+
+    /// Registers allowed string values and their adjacency for validation and heuristics.
+    pub fn symbols(mut self, symbols: SymbolTable) -> Self {
+        self.symbols = Some(symbols);
+        self
+    }
+
+    /// Plans with a heuristic weight that anneals from `initial_weight` to 1.0
+    /// over `node_budget` expansions, returning the best plan found within the budget.
+    pub fn annealed_weight(mut self, initial_weight: f64, node_budget: usize) -> Self {
+        self.annealing = Some(WeightSchedule {
+            initial_weight,
+            node_budget,
+        });
+        self
+    }
+
+    /// Selects the search algorithm.
+    pub fn strategy(mut self, strategy: SearchStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Sets how values of a string variable are compared, e.g. ignoring case.
+    pub fn string_comparison(mut self, key: &str, mode: StringComparison) -> Self {
+        self.string_comparisons.set(key, mode);
+        self
+    }
+
+    /// Leaves actions carrying any of the given tags out of planning, so one set of
+    /// actions can serve several behavioural modes, e.g. `exclude_tags(["loud"])`
+    /// while sneaking. Adds to any tags already excluded.
+    pub fn exclude_tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.excluded_tags
+            .extend(tags.into_iter().map(|tag| tag.as_ref().to_string()));
+        self
+    }
+
+    /// Reports the net change of a numeric variable, such as gold or time, on
+    /// every plan (see [`Plan::currencies`]), separately from the planner cost.
+    pub fn track_currency(mut self, key: &str) -> Self {
+        if !self.currencies.iter().any(|tracked| tracked == key) {
+            self.currencies.push(key.to_string());
+        }
+        self
+    }
+
+    /// Registers search settings that goals select by name with
+    /// [`GoalBuilder::profile`](crate::goals::GoalBuilder::profile), replacing any
+    /// profile or preset with the same name.
+    pub fn profile(mut self, name: &str, profile: PlannerProfile) -> Self {
+        self.profiles.insert(name.to_string(), profile);
+        self
+    }
+
+    /// Warm-starts planning for clustered goals with clusters built by
+    /// [`Planner::cluster_goals`].
+    pub fn goal_clusters(mut self, clusters: GoalClusters) -> Self {
+        self.goal_clusters = Some(Arc::new(clusters));
+        self
+    }
+
+    /// Prunes states that another state reached at no greater cost dominates,
+    /// having the same booleans and strings and at least as much of every number.
+    /// Only correct when more of a number never hurts; see the
+    /// [`dominance`](crate::dominance) module.
+    pub fn dominance_pruning(mut self, enabled: bool) -> Self {
+        self.dominance_pruning = enabled;
+        self
+    }
+
+    /// Prunes states breaking a goal requirement that no action can meet again,
+    /// as found by [`analyze_dead_ends`]. The analysis runs once per planning call,
+    /// and once per [`PlanningSession`](crate::session::PlanningSession).
+    pub fn dead_end_pruning(mut self, enabled: bool) -> Self {
+        self.dead_end_pruning = enabled;
+        self
+    }
+
+    /// Gives the planner the variables' metadata. Actions that would decrease a
+    /// [monotonic](crate::schema::KeyInfo::monotonic) variable are never applied,
+    /// and dead-end pruning uses the declarations too.
+    pub fn schema(mut self, schema: StateSchema) -> Self {
+        self.schema = Some(Arc::new(schema));
+        self
+    }
+
+    /// Samples varied, near-optimal plans instead of always returning the
+    /// cheapest one (see the [`sampling`](crate::sampling) module).
+    pub fn sampling(mut self, settings: SamplingConfig) -> Self {
+        self.sampling = Some(settings);
+        self
+    }
+
+    /// Prices the steps of A* plans with a function of the plan so far (see the
+    /// [`path_cost`](crate::path_cost) module).
+    pub fn path_cost(mut self, cost: PathCost) -> Self {
+        self.path_cost = Some(cost);
+        self
+    }
+
+    /// Declares that exactly one of the given boolean variables is true at any time.
+    pub fn mutex_group(mut self, keys: &[&str]) -> Self {
+        self.mutex_groups.push(MutexGroup::new(keys));
+        self
+    }
+
+    /// Returns a copy of the action completed with the effects implied by every
+    /// mutex group (see [`MutexGroup::complete_action`]).
+    pub fn complete_action(&self, action: &Action) -> Action {
+        self.mutex_groups
+            .iter()
+            .fold(action.clone(), |action, group| {
+                group.complete_action(&action)
+            })
+    }
+
+    /// Returns copies of the actions completed with the effects implied by every
+    /// mutex group. The planner completes actions itself; this is for executing or
+    /// simulating them elsewhere with the same effects.
+    pub fn complete_actions(&self, actions: &[Action]) -> Vec<Action> {
+        actions
+            .iter()
+            .map(|action| self.complete_action(action))
+            .collect()
+    }
+}
+
+/// A planner that uses A* search to find optimal sequences of actions.
+/// The planner is stateless and can be reused for multiple planning requests.
+#[derive(Clone, Debug, Default)]
+pub struct Planner {
+    /// The configuration used for every planning request
+    config: PlannerConfig,
+}
+
 impl Planner {
     /// Creates a new planner instance.
     pub fn new() -> Self {
-        Planner {}
+        Planner {
+            config: PlannerConfig::default(),
+        }
+    }
+
+    /// Creates a planner with the given configuration.
+    pub fn with_config(config: PlannerConfig) -> Self {
+        Planner { config }
+    }
+
+    /// Returns the configuration used by this planner.
+    pub fn config(&self) -> &PlannerConfig {
+        &self.config
     }
 
     /// Finds a plan to achieve the given goal starting from the initial state.
@@ -72,142 +702,1137 @@ impl Planner {
     ///
     /// # Arguments
     ///
-    /// * `initial_state` - The starting state of the world
+    /// * `initial_state` - The starting state of the world, owned or borrowed
+    ///   (a borrowed state is cloned once, so callers need not clone it)
     /// * `goal` - The goal to achieve
     /// * `actions` - The available actions that can be performed
-    pub fn plan(
+    pub fn plan<'s>(
+        &self,
+        initial_state: impl Into<Cow<'s, State>>,
+        goal: &Goal,
+        actions: &[Action],
+    ) -> Result<Plan, PlannerError> {
+        self.plan_observed(initial_state, goal, actions, &mut NoopObserver)
+    }
+
+    /// Finds a plan like [`Planner::plan`], reporting search events to the given observer.
+    /// Use this to instrument planning with profilers or telemetry.
+    ///
+    /// If the goal selects a profile, the search uses the profile's settings
+    /// (see [`PlannerProfile`]).
+    pub fn plan_observed<'s>(
+        &self,
+        initial_state: impl Into<Cow<'s, State>>,
+        goal: &Goal,
+        actions: &[Action],
+        observer: &mut dyn PlannerObserver,
+    ) -> Result<Plan, PlannerError> {
+        let initial_state = initial_state.into().into_owned();
+        match self.profiled(goal)? {
+            Some(planner) => planner.search_observed(initial_state, goal, actions, observer),
+            None => self.search_observed(initial_state, goal, actions, observer),
+        }
+    }
+
+    /// Runs the configured search for [`Planner::plan_observed`].
+    fn search_observed(
         &self,
         initial_state: State,
         goal: &Goal,
         actions: &[Action],
+        observer: &mut dyn PlannerObserver,
     ) -> Result<Plan, PlannerError> {
-        let mut open_set = BinaryHeap::new();
-        let mut came_from = HashMap::new();
-        let mut g_score = HashMap::new();
-        let mut action_taken = HashMap::new();
+        let (initial_state, goal) = self.prepare_start(initial_state, goal);
+        self.check_problem(&initial_state, &goal, actions)?;
+        let actions = self.warm_start(&initial_state, &goal, self.prepare_actions(actions))?;
+        match self.config.strategy {
+            SearchStrategy::AStar => {}
+            SearchStrategy::MonteCarlo(settings) => {
+                let mut policy = RandomRollout;
+                return self.plan_monte_carlo(
+                    initial_state,
+                    &goal,
+                    &actions,
+                    settings,
+                    &mut policy,
+                    observer,
+                );
+            }
+            SearchStrategy::Beam { width } => {
+                return self.plan_beam(initial_state, &goal, &actions, width, observer);
+            }
+        }
+        if let Some(settings) = self.config.sampling {
+            return self.plan_sampled(initial_state, &goal, &actions, observer, settings);
+        }
+        if let Some(schedule) = self.config.annealing {
+            return self.plan_annealed(initial_state, &goal, &actions, observer, schedule);
+        }
+        let dead_ends = self.dead_end_analysis(&goal, &actions);
+        let mut search = Search::new(self, &goal, &actions, dead_ends.as_ref(), observer);
+        search.push_start(initial_state)?;
+        search.run()
+    }
 
-        g_score.insert(initial_state.clone(), 0.0);
-        let initial_h = self.heuristic(&initial_state, &goal.desired_state)?;
+    /// Runs weighted searches with a decreasing weight until the node budget is spent,
+    /// a search with weight 1.0 completes, or no cheaper plan exists.
+    fn plan_annealed(
+        &self,
+        initial_state: State,
+        goal: &Goal,
+        actions: &[Action],
+        observer: &mut dyn PlannerObserver,
+        schedule: WeightSchedule,
+    ) -> Result<Plan, PlannerError> {
+        let step = (schedule.initial_weight - 1.0).max(0.0) / 8.0;
+        let mut weight = schedule.weight_at(0);
+        let mut consumed = 0;
+        let mut stats = PlanStats::default();
+        let mut best: Option<Plan> = None;
+        let dead_ends = self.dead_end_analysis(goal, actions);
 
-        open_set.push(NodeWrapper {
-            node: initial_state.clone(),
-            f_score: initial_h,
-        });
+        while consumed < schedule.node_budget {
+            let mut search = Search::new(self, goal, actions, dead_ends.as_ref(), &mut *observer);
+            search.weight = weight;
+            search.node_budget = Some(schedule.node_budget - consumed);
+            search.cost_bound = best.as_ref().map(|plan| plan.cost);
+            search.push_start(initial_state.clone())?;
+            let result = search.find_goal();
 
-        while let Some(NodeWrapper {
-            node: current,
-            f_score: _,
-        }) = open_set.pop()
-        {
-            if goal.is_satisfied(&current) {
-                let plan = self.reconstruct_path(&came_from, &action_taken, &current);
-                return Ok(plan);
+            consumed += search.stats.nodes_expanded;
+            stats.nodes_expanded += search.stats.nodes_expanded;
+            stats.nodes_generated += search.stats.nodes_generated;
+            stats.nodes_dominated += search.stats.nodes_dominated;
+            stats.dead_ends += search.stats.dead_ends;
+            stats.memory_bytes = stats.memory_bytes.max(search.stats.memory_bytes);
+
+            match result {
+                Ok(node) => best = Some(search.reconstruct(node)),
+                // The bounded search space was exhausted: no cheaper plan exists
+                Err(PlannerError::NoPlanFound) if !search.budget_exhausted => break,
+                Err(PlannerError::NoPlanFound) => {}
+                Err(error) => return Err(error),
+            }
+            if weight <= 1.0 || search.budget_exhausted {
+                break;
+            }
+            weight = schedule.weight_at(consumed).min(weight - step).max(1.0);
+        }
+
+        let mut plan = best.ok_or(PlannerError::NoPlanFound)?;
+        plan.stats = stats;
+        Ok(plan)
+    }
+
+    /// Runs a single A* search with the given heuristic weight, giving up after
+    /// `node_budget` expansions if one is set.
+    pub(crate) fn plan_weighted<'s>(
+        &self,
+        initial_state: impl Into<Cow<'s, State>>,
+        goal: &Goal,
+        actions: &[Action],
+        weight: f64,
+        node_budget: Option<usize>,
+    ) -> Result<Plan, PlannerError> {
+        let (initial_state, goal) = self.prepare_start(initial_state.into().into_owned(), goal);
+        self.check_problem(&initial_state, &goal, actions)?;
+        let actions = self.warm_start(&initial_state, &goal, self.prepare_actions(actions))?;
+        let dead_ends = self.dead_end_analysis(&goal, &actions);
+        let mut observer = NoopObserver;
+        let mut search = Search::new(self, &goal, &actions, dead_ends.as_ref(), &mut observer);
+        search.weight = weight;
+        search.node_budget = node_budget;
+        search.push_start(initial_state)?;
+        search.run()
+    }
+
+    /// Plans at most `horizon` steps towards the goal, for receding-horizon control.
+    ///
+    /// If the goal can be reached within `horizon` actions, the full plan is returned
+    /// with `complete` set. Otherwise the search stops at the horizon and returns the
+    /// prefix leading to the explored state with the lowest heuristic distance to the
+    /// goal. A partial plan is only a best guess: execute its first step or few, then
+    /// call `plan_horizon` again from the new state. An empty partial plan means no
+    /// explored state looked closer to the goal than the current one.
+    ///
+    /// Use this for domains too large to solve in one search; when a full plan is
+    /// affordable, [`Planner::plan`] gives better results.
+    pub fn plan_horizon<'s>(
+        &self,
+        horizon: usize,
+        initial_state: impl Into<Cow<'s, State>>,
+        goal: &Goal,
+        actions: &[Action],
+    ) -> Result<HorizonPlan, PlannerError> {
+        let (initial_state, goal) = self.prepare_start(initial_state.into().into_owned(), goal);
+        self.check_problem(&initial_state, &goal, actions)?;
+        let actions = self.warm_start(&initial_state, &goal, self.prepare_actions(actions))?;
+        let dead_ends = self.dead_end_analysis(&goal, &actions);
+        let mut observer = NoopObserver;
+        let mut search = Search::new(self, &goal, &actions, dead_ends.as_ref(), &mut observer);
+        search.max_depth = Some(horizon);
+        search.push_start(initial_state)?;
+        match search.find_goal() {
+            Ok(goal_node) => Ok(HorizonPlan {
+                plan: search.reconstruct(goal_node),
+                complete: true,
+            }),
+            Err(PlannerError::NoPlanFound) => {
+                let best = search.best.ok_or(PlannerError::NoPlanFound)?;
+                Ok(HorizonPlan {
+                    plan: search.reconstruct(best),
+                    complete: false,
+                })
             }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Finds a plan like [`Planner::plan`], but when the search runs out of budget
+    /// returns the plan to the explored state with the lowest heuristic distance
+    /// to the goal instead of failing, so the agent can do something reasonable now.
+    ///
+    /// Budgets are the planner's limits: [`PlannerConfig::max_nodes_expanded`],
+    /// [`PlannerConfig::max_plan_length`], [`PlannerConfig::max_duration`] and
+    /// [`PlannerConfig::max_memory_bytes`]. Without one, this searches until it
+    /// finds a plan or exhausts the search space. A search that exhausts the space
+    /// without reaching the goal still fails with [`PlannerError::NoPlanFound`].
+    ///
+    /// ```
+    /// use goap::prelude::*;
+    ///
+    /// let actions = vec![Action::new("work").adds("gold", 1).build()];
+    /// let goal = Goal::new("rich").requires("gold", 1000).build();
+    /// let planner = Planner::with_config(PlannerConfig::new().max_nodes_expanded(10));
+    ///
+    /// let result = planner
+    ///     .plan_anytime(State::new().set("gold", 0).build(), &goal, &actions)
+    ///     .unwrap();
+    /// assert!(!result.complete);
+    /// assert_eq!(result.plan.actions.len(), 10);
+    /// ```
+    pub fn plan_anytime<'s>(
+        &self,
+        initial_state: impl Into<Cow<'s, State>>,
+        goal: &Goal,
+        actions: &[Action],
+    ) -> Result<AnytimePlan, PlannerError> {
+        match self.plan(initial_state, goal, actions) {
+            Ok(plan) => Ok(AnytimePlan {
+                plan,
+                complete: true,
+            }),
+            Err(PlannerError::BudgetExceeded { best_partial, .. }) => Ok(AnytimePlan {
+                plan: *best_partial,
+                complete: false,
+            }),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Finds the cheapest plan starting from any of several candidate initial states.
+    ///
+    /// Useful when the agent is unsure of its exact situation: all candidates are
+    /// searched together and the plan from whichever one reaches the goal most
+    /// cheaply is returned, along with the index of that state in `initial_states`.
+    /// Relative goal requirements are resolved against the first candidate.
+    /// Returns `PlannerError::NoPlanFound` if `initial_states` is empty or no
+    /// candidate can reach the goal.
+    pub fn plan_from_any(
+        &self,
+        initial_states: &[State],
+        goal: &Goal,
+        actions: &[Action],
+    ) -> Result<(usize, Plan), PlannerError> {
+        let goal = match initial_states.first() {
+            Some(first) => self.prepare_start(first.clone(), goal).1,
+            None => Cow::Borrowed(goal),
+        };
+        let initial_states: Vec<State> = initial_states
+            .iter()
+            .map(|state| {
+                let mut state = state.clone();
+                self.config.string_comparisons.canonical_state(&mut state);
+                state
+            })
+            .collect();
+        for initial_state in &initial_states {
+            self.check_problem(initial_state, &goal, actions)?;
+        }
+        let actions = self.prepare_actions(actions);
+        let dead_ends = self.dead_end_analysis(&goal, &actions);
+        let mut observer = NoopObserver;
+        let mut search = Search::new(self, &goal, &actions, dead_ends.as_ref(), &mut observer);
+        for initial_state in &initial_states {
+            search.push_start(initial_state.clone())?;
+        }
+        let goal_node = search.find_goal()?;
+        let root = &search.nodes[search.root(goal_node)].state;
+        let start = initial_states
+            .iter()
+            .position(|state| state == root)
+            .ok_or(PlannerError::NoPlanFound)?;
+        Ok((start, search.reconstruct(goal_node)))
+    }
+
+    /// Analyzes the dead ends of the goal for searches to prune, if dead-end
+    /// pruning is enabled.
+    pub(crate) fn dead_end_analysis(
+        &self,
+        goal: &Goal,
+        actions: &[Action],
+    ) -> Option<DeadEndAnalysis> {
+        self.config
+            .dead_end_pruning
+            .then(|| match &self.config.schema {
+                Some(schema) => analyze_dead_ends_with_schema(actions, goal, schema),
+                None => analyze_dead_ends(actions, goal),
+            })
+    }
 
-            let current_g = *g_score.get(&current).unwrap_or(&f64::INFINITY);
-            let transitions = self.get_valid_transitions(&current, actions);
+    /// Leaves out actions with excluded tags, splits actions with alternative
+    /// preconditions into variants, completes them with the effects implied by the
+    /// configured mutex groups and rewrites their string values into canonical form.
+    pub(crate) fn prepare_actions<'b>(&self, actions: &'b [Action]) -> Cow<'b, [Action]> {
+        let excluded = &self.config.excluded_tags;
+        if excluded.is_empty()
+            && self.config.mutex_groups.is_empty()
+            && self.config.string_comparisons.is_empty()
+            && actions
+                .iter()
+                .all(|action| action.any_preconditions.is_empty())
+        {
+            return Cow::Borrowed(actions);
+        }
+        Cow::Owned(
+            actions
+                .iter()
+                .filter(|action| !excluded.iter().any(|tag| action.has_tag(tag)))
+                .flat_map(Action::variants)
+                .map(|action| {
+                    let mut action = self.config.complete_action(&action);
+                    self.config.string_comparisons.canonical_action(&mut action);
+                    action
+                })
+                .collect(),
+        )
+    }
 
-            for (next_state, cost, action) in transitions {
-                let tentative_g = current_g + cost;
-                let next_h = self.heuristic(&next_state, &goal.desired_state)?;
-                let next_f = tentative_g + next_h;
+    /// Rewrites the initial state's string values into canonical form, and resolves
+    /// the goal's relative requirements against it.
+    pub(crate) fn prepare_start<'g>(
+        &self,
+        mut initial_state: State,
+        goal: &'g Goal,
+    ) -> (State, Cow<'g, Goal>) {
+        let comparisons = &self.config.string_comparisons;
+        comparisons.canonical_state(&mut initial_state);
+        let mut goal = resolve_goal(goal, &initial_state);
+        if !comparisons.is_empty() {
+            comparisons.canonical_goal(goal.to_mut());
+        }
+        (initial_state, goal)
+    }
 
-                if tentative_g < *g_score.get(&next_state).unwrap_or(&f64::INFINITY) {
-                    came_from.insert(next_state.clone(), current.clone());
-                    action_taken.insert(next_state.clone(), action);
-                    g_score.insert(next_state.clone(), tentative_g);
+    /// Returns true if the state respects every configured mutex group.
+    fn respects_mutex_groups(&self, state: &State) -> bool {
+        self.config
+            .mutex_groups
+            .iter()
+            .all(|group| group.is_consistent(state))
+    }
 
-                    open_set.push(NodeWrapper {
-                        node: next_state,
-                        f_score: next_f,
-                    });
+    /// Generates the successors of a state: every action that can execute in it,
+    /// with the state it leads to.
+    ///
+    /// This is the successor function used by the planner's own search, including
+    /// pruning of states that violate configured mutex groups or the action's
+    /// postconditions, or that decrease a variable the schema declares monotonic. It lets custom search
+    /// algorithms (beam search, MCTS, ...) run on the crate's state and action model.
+    pub fn expand<'a>(
+        &'a self,
+        state: &'a State,
+        actions: &'a [Action],
+    ) -> impl Iterator<Item = Transition<'a>> + 'a {
+        actions
+            .iter()
+            .enumerate()
+            .filter(|(_, action)| action.can_execute(state))
+            .filter_map(move |(action_index, action)| {
+                let mut next = action.apply_effect(state);
+                if !action.dynamic_effects.is_empty() {
+                    // Computed values are not known in advance and may not be canonical
+                    self.config.string_comparisons.canonical_state(&mut next);
                 }
-            }
+                let valid = self.respects_mutex_groups(&next)
+                    && action.violated_postconditions(&next).is_empty()
+                    && self
+                        .config
+                        .schema
+                        .as_ref()
+                        .is_none_or(|schema| schema.decreased(state, &next).is_none());
+                valid.then_some(Transition {
+                    action,
+                    action_index,
+                    state: next,
+                    cost: action.cost,
+                })
+            })
+    }
+
+    /// Returns the net change of each tracked currency between two states. Missing
+    /// variables count as zero; variables that are not numeric are left out.
+    pub(crate) fn currency_changes(&self, start: &State, end: &State) -> BTreeMap<String, f64> {
+        self.config
+            .currencies
+            .iter()
+            .filter_map(|key| {
+                let amount = |state: &State| match state.vars.get(key) {
+                    None => Some(0.0),
+                    Some(StateVar::I64(value)) => Some(*value as f64),
+                    Some(value) => value.as_f64(),
+                };
+                Some((key.clone(), amount(end)? - amount(start)?))
+            })
+            .collect()
+    }
+
+    /// Returns the net change of each tracked currency over a sequence of actions
+    /// applied to a state, for searches that do not keep every visited state.
+    pub(crate) fn simulated_currency_changes(
+        &self,
+        start: &State,
+        actions: &[Action],
+    ) -> BTreeMap<String, f64> {
+        if self.config.currencies.is_empty() {
+            return BTreeMap::new();
         }
+        let end = actions
+            .iter()
+            .fold(start.clone(), |state, action| action.apply_effect(&state));
+        self.currency_changes(start, &end)
+    }
 
-        Err(PlannerError::NoPlanFound)
+    /// Estimates the remaining cost from a state to a goal with the planner's heuristic.
+    /// Returns an error if state variables have incompatible types.
+    pub fn estimate(&self, state: &State, goal: &Goal) -> Result<f64, PlannerError> {
+        self.goal_heuristic(state, goal)
     }
 
-    /// Gets all valid transitions from the current state.
-    /// Returns a vector of (next_state, cost, action) tuples for actions that can be executed.
-    fn get_valid_transitions(
+    /// Runs the up-front checks enabled in the configuration.
+    pub(crate) fn check_problem(
         &self,
-        state: &State,
+        initial_state: &State,
+        goal: &Goal,
         actions: &[Action],
-    ) -> Vec<(State, f64, Action)> {
-        let mut transitions = Vec::new();
-        for action in actions {
-            if action.can_execute(state) {
-                let new_state = action.apply_effect(state);
-                transitions.push((new_state, action.cost, action.clone()));
-            }
+    ) -> Result<(), PlannerError> {
+        if self.config.strict_types {
+            check_types(initial_state, goal, actions)?;
+        }
+        if let Some(symbols) = &self.config.symbols {
+            symbols
+                .validate_state(initial_state)
+                .and_then(|_| symbols.validate_goal(goal))
+                .and_then(|_| actions.iter().try_for_each(|a| symbols.validate_action(a)))
+                .map_err(PlannerError::UnknownSymbol)?;
         }
-        transitions
+        Ok(())
+    }
+
+    /// Calculates the heuristic distance from a state to a goal, counting one step
+    /// for every excluded value the state still holds and every unmet condition.
+    pub(crate) fn goal_heuristic(&self, current: &State, goal: &Goal) -> Result<f64, PlannerError> {
+        let excluded = goal
+            .excluded
+            .vars
+            .iter()
+            .filter(|(key, value)| current.vars.get(*key) == Some(value))
+            .count();
+        let unmet = excluded + goal.unmet_conditions(current);
+        Ok(self.heuristic(current, &goal.desired_state)? + unmet as f64)
     }
 
     /// Calculates the heuristic distance from the current state to the goal state.
     /// This is used by A* to guide the search towards the goal.
     /// Returns the estimated cost to reach the goal from the current state.
     /// Returns an error if state variables have incompatible types.
-    fn heuristic(&self, current: &State, goal: &State) -> Result<f64, PlannerError> {
+    pub(crate) fn heuristic(&self, current: &State, goal: &State) -> Result<f64, PlannerError> {
         let mut total_distance = 0;
-
-        // Calculate distance for each goal requirement
         for (key, goal_val) in &goal.vars {
-            match current.vars.get(key) {
-                Some(current_val) => {
-                    let distance = current_val.distance(goal_val).map_err(|_| {
-                        PlannerError::IncompatibleStateTypes(format!(
-                            "Cannot calculate distance for variable '{key}' due to type mismatch"
-                        ))
-                    })?;
-                    total_distance += distance;
+            total_distance += self.requirement_distance(key, current.vars.get(key), goal_val)?;
+        }
+        Ok(total_distance as f64)
+    }
+
+    /// Calculates the heuristic distance of a single goal requirement: the symbol
+    /// distance for registered string values, the value distance otherwise, and
+    /// one for a missing variable.
+    pub(crate) fn requirement_distance(
+        &self,
+        key: &str,
+        current: Option<&StateVar>,
+        required: &StateVar,
+    ) -> Result<u64, PlannerError> {
+        let Some(current_val) = current else {
+            return Ok(1); // Penalty for missing keys
+        };
+        if let (StateVar::String(from), StateVar::String(to)) = (current_val, required)
+            && let Some(distance) = self
+                .config
+                .symbols
+                .as_ref()
+                .and_then(|symbols| symbols.distance(key, from, to))
+        {
+            return Ok(distance);
+        }
+        current_val.distance(required).map_err(|_| {
+            PlannerError::IncompatibleStateTypes(format!(
+                "Cannot calculate distance for variable '{key}' due to type mismatch"
+            ))
+        })
+    }
+}
+
+/// Resolves relative goal requirements, borrowing the goal when it has none.
+pub(crate) fn resolve_goal<'g>(goal: &'g Goal, initial_state: &State) -> Cow<'g, Goal> {
+    if goal.relative.vars.is_empty() {
+        Cow::Borrowed(goal)
+    } else {
+        Cow::Owned(goal.resolve(initial_state))
+    }
+}
+
+/// Checks that every variable is used with a single type across the planning problem.
+/// The initial state establishes types first, followed by the goal and then each action.
+/// Add and Subtract effects require a numeric (I64 or F64) variable.
+fn check_types(initial_state: &State, goal: &Goal, actions: &[Action]) -> Result<(), PlannerError> {
+    let mut types: HashMap<&str, &'static str> = initial_state
+        .vars
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.type_name()))
+        .collect();
+
+    let conflict =
+        |key: &str, found, expected, action: Option<&Action>| PlannerError::TypeConflict {
+            key: key.to_string(),
+            found,
+            expected,
+            action: action.map(|action| action.name.clone()),
+        };
+
+    let mut uses: Vec<(&str, &'static str, Option<&Action>)> = goal
+        .desired_state
+        .vars
+        .iter()
+        .chain(&goal.excluded.vars)
+        .map(|(key, value)| (key.as_str(), value.type_name(), None))
+        .collect();
+    for action in actions {
+        for (key, value) in &action.preconditions.vars {
+            uses.push((key, value.type_name(), Some(action)));
+        }
+        for (key, operation) in &action.effects {
+            match operation {
+                StateOperation::Set(value) => uses.push((key, value.type_name(), Some(action))),
+                StateOperation::Add(_) | StateOperation::Subtract(_) => {
+                    if let Some(&expected) = types.get(key.as_str())
+                        && expected != "I64"
+                        && expected != "F64"
+                    {
+                        return Err(conflict(key, "numeric", expected, Some(action)));
+                    }
                 }
-                None => {
-                    total_distance += 1; // Penalty for missing keys
+            }
+        }
+    }
+
+    for (key, found, action) in uses {
+        let expected = *types.entry(key).or_insert(found);
+        if expected != found {
+            return Err(conflict(key, found, expected, action));
+        }
+    }
+    Ok(())
+}
+
+/// A state discovered during search, with the path information needed to rebuild a plan.
+pub(crate) struct SearchNode {
+    /// The world state at this node
+    pub(crate) state: State,
+    /// Index of the node this one was reached from
+    pub(crate) parent: Option<usize>,
+    /// Index into the action slice of the action that produced this node
+    pub(crate) action: Option<usize>,
+    /// Cost of the cheapest known path from the start to this node
+    pub(crate) g: f64,
+    /// Heuristic distance from this node to the goal
+    pub(crate) h: f64,
+    /// Secondary ordering key among nodes with equal f-scores; lower is expanded first
+    pub(crate) tie_break: f64,
+    /// The g-score this node was last expanded with, used to skip stale queue entries
+    pub(crate) expanded_g: Option<f64>,
+    /// Number of actions on the cheapest known path to this node
+    pub(crate) depth: usize,
+}
+
+/// The working data of a single A* search.
+pub(crate) struct Search<'a> {
+    /// The planner whose configuration drives this search
+    planner: &'a Planner,
+    /// The goal being searched for
+    goal: &'a Goal,
+    /// The actions available to the search
+    actions: &'a [Action],
+    /// Receives search events for profiling and telemetry
+    observer: &'a mut dyn PlannerObserver,
+    /// All discovered nodes, indexed by insertion order
+    nodes: Vec<SearchNode>,
+    /// Lookup from state to node index
+    index: HashMap<State, usize>,
+    /// Nodes grouped by dominance signature, when dominance pruning is enabled
+    dominance: HashMap<State, Vec<usize>>,
+    /// The goal requirements that make states dead ends, when pruning them
+    dead_ends: Option<&'a DeadEndAnalysis>,
+    /// Nodes waiting to be expanded, ordered by f-score
+    open: BinaryHeap<NodeWrapper<usize>>,
+    /// Counters reported on the resulting plan
+    pub(crate) stats: PlanStats,
+    /// The node with the lowest heuristic distance seen so far
+    best: Option<usize>,
+    /// Nodes at this depth are goal-tested but not expanded
+    max_depth: Option<usize>,
+    /// Multiplier applied to the heuristic in f-scores
+    pub(crate) weight: f64,
+    /// Maximum number of expansions before the search gives up
+    pub(crate) node_budget: Option<usize>,
+    /// Set when the search stopped because the node budget ran out
+    pub(crate) budget_exhausted: bool,
+    /// Paths costing this much or more are not recorded
+    cost_bound: Option<f64>,
+    /// The clock and the time at which the search gives up, if time is limited
+    deadline: Option<(Arc<dyn Clock>, Duration)>,
+    /// True once a node was left unexpanded because of the configured maximum plan length
+    length_cutoff: bool,
+    /// Goals whose unmet requirements, weighted by priority, break ties between nodes
+    pub(crate) tie_break_goals: &'a [Goal],
+}
+
+/// The owned data of a search, kept between calls to continue searching later.
+pub(crate) struct SearchTree {
+    /// All discovered nodes, indexed by insertion order
+    pub(crate) nodes: Vec<SearchNode>,
+    /// Lookup from state to node index
+    pub(crate) index: HashMap<State, usize>,
+    /// Nodes grouped by dominance signature, when dominance pruning is enabled
+    pub(crate) dominance: HashMap<State, Vec<usize>>,
+    /// Nodes waiting to be expanded, ordered by f-score
+    pub(crate) open: BinaryHeap<NodeWrapper<usize>>,
+    /// Counters reported on the resulting plan
+    pub(crate) stats: PlanStats,
+    /// The node with the lowest heuristic distance seen so far
+    pub(crate) best: Option<usize>,
+}
+
+impl<'a> Search<'a> {
+    /// Continues a search from a tree saved with [`Search::into_tree`].
+    pub(crate) fn from_tree(
+        planner: &'a Planner,
+        goal: &'a Goal,
+        actions: &'a [Action],
+        dead_ends: Option<&'a DeadEndAnalysis>,
+        observer: &'a mut dyn PlannerObserver,
+        tree: SearchTree,
+    ) -> Self {
+        let mut search = Search::new(planner, goal, actions, dead_ends, observer);
+        search.nodes = tree.nodes;
+        search.index = tree.index;
+        search.dominance = tree.dominance;
+        search.open = tree.open;
+        search.stats = tree.stats;
+        search.best = tree.best;
+        search
+    }
+
+    /// Releases the owned search data so it can outlive the borrowed context.
+    pub(crate) fn into_tree(self) -> SearchTree {
+        SearchTree {
+            nodes: self.nodes,
+            index: self.index,
+            dominance: self.dominance,
+            open: self.open,
+            stats: self.stats,
+            best: self.best,
+        }
+    }
+
+    /// Starts an empty search, pruning the dead ends of an analysis computed once
+    /// per planning call with [`Planner::dead_end_analysis`].
+    pub(crate) fn new(
+        planner: &'a Planner,
+        goal: &'a Goal,
+        actions: &'a [Action],
+        dead_ends: Option<&'a DeadEndAnalysis>,
+        observer: &'a mut dyn PlannerObserver,
+    ) -> Self {
+        let deadline = planner.config.max_duration.map(|duration| {
+            let clock = planner
+                .config
+                .clock
+                .clone()
+                .unwrap_or_else(|| Arc::new(StdClock::new()));
+            let deadline = clock.now() + duration;
+            (clock, deadline)
+        });
+        Search {
+            planner,
+            goal,
+            actions,
+            observer,
+            nodes: Vec::new(),
+            index: HashMap::new(),
+            dominance: HashMap::new(),
+            dead_ends,
+            open: BinaryHeap::new(),
+            stats: PlanStats::default(),
+            best: None,
+            max_depth: None,
+            weight: 1.0,
+            node_budget: None,
+            budget_exhausted: false,
+            cost_bound: None,
+            deadline,
+            length_cutoff: false,
+            tie_break_goals: &[],
+        }
+    }
+
+    /// Adds a start state with a g-score of zero.
+    pub(crate) fn push_start(&mut self, state: State) -> Result<(), PlannerError> {
+        self.discover(state, None, None, 0.0)
+    }
+
+    /// Records a path to `state`, adding it to the open set if it is new or cheaper.
+    fn discover(
+        &mut self,
+        state: State,
+        parent: Option<usize>,
+        action: Option<usize>,
+        g: f64,
+    ) -> Result<(), PlannerError> {
+        if self.cost_bound.is_some_and(|bound| g >= bound) {
+            return Ok(());
+        }
+        let depth = parent.map_or(0, |parent| self.nodes[parent].depth + 1);
+        let node = match self.index.get(&state) {
+            Some(&existing) => {
+                if g >= self.nodes[existing].g {
+                    return Ok(());
                 }
+                let node = &mut self.nodes[existing];
+                node.parent = parent;
+                node.action = action;
+                node.g = g;
+                node.depth = depth;
+                existing
+            }
+            None => {
+                if self
+                    .dead_ends
+                    .as_ref()
+                    .is_some_and(|analysis| analysis.dead_end(&state).is_some())
+                {
+                    self.stats.dead_ends += 1;
+                    return Ok(());
+                }
+                if self.planner.config.dominance_pruning && self.is_dominated(&state, g) {
+                    self.stats.nodes_dominated += 1;
+                    return Ok(());
+                }
+                let h = self.planner.goal_heuristic(&state, self.goal)?;
+                self.observer.on_heuristic(&state, h);
+                let tie_break = priority_penalty(&state, self.tie_break_goals);
+                let node = self.nodes.len();
+                self.stats.memory_bytes += node_memory_bytes(&state);
+                if self.planner.config.dominance_pruning {
+                    let signature = dominance_signature(&state);
+                    if !self.dominance.contains_key(&signature) {
+                        self.stats.memory_bytes += signature.approx_memory_bytes();
+                    }
+                    self.dominance.entry(signature).or_default().push(node);
+                    self.stats.memory_bytes += size_of::<usize>();
+                }
+                self.index.insert(state.clone(), node);
+                self.nodes.push(SearchNode {
+                    state,
+                    parent,
+                    action,
+                    g,
+                    h,
+                    tie_break,
+                    expanded_g: None,
+                    depth,
+                });
+                let is_better = self.best.is_none_or(|best| {
+                    let best = &self.nodes[best];
+                    h < best.h || (h == best.h && g < best.g)
+                });
+                if is_better {
+                    self.best = Some(node);
+                }
+                node
+            }
+        };
+
+        let node_data = &self.nodes[node];
+        let f_score = node_data.g + self.weight * node_data.h;
+        self.open.push(NodeWrapper {
+            node,
+            f_score,
+            tie_break: node_data.tie_break,
+        });
+        self.stats.memory_bytes += size_of::<NodeWrapper<usize>>();
+        Ok(())
+    }
+
+    /// Returns true if a known node with the same signature dominates `state` and
+    /// was reached at no greater cost than `g`.
+    fn is_dominated(&self, state: &State, g: f64) -> bool {
+        self.dominance
+            .get(&dominance_signature(state))
+            .is_some_and(|group| {
+                group.iter().any(|&other| {
+                    let other = &self.nodes[other];
+                    other.g <= g && dominates(&other.state, state)
+                })
+            })
+    }
+
+    /// Runs A* until the goal is reached, the open set is exhausted, or a limit is hit.
+    pub(crate) fn run(&mut self) -> Result<Plan, PlannerError> {
+        let goal_node = self.find_goal()?;
+        Ok(self.reconstruct(goal_node))
+    }
+
+    /// Searches until a goal node is taken from the open set, returning its index.
+    pub(crate) fn find_goal(&mut self) -> Result<usize, PlannerError> {
+        while let Some(NodeWrapper { node: current, .. }) = self.open.pop() {
+            let node = &self.nodes[current];
+            if node.expanded_g == Some(node.g) {
+                continue; // Stale entry; this node was already expanded at this cost
+            }
+
+            let satisfied = self.goal.is_satisfied(&node.state);
+            self.observer.on_goal_test(&node.state, satisfied);
+            if satisfied {
+                return Ok(current);
+            }
+
+            if let Some(limit_bytes) = self.planner.config.max_memory_bytes
+                && self.stats.memory_bytes > limit_bytes
+            {
+                let best = self.best.unwrap_or(current);
+                return Err(PlannerError::BudgetExceeded {
+                    budget: SearchBudget::Memory(limit_bytes),
+                    best_partial: Box::new(self.reconstruct(best)),
+                });
+            }
+
+            if let Some(duration) = self.planner.config.max_duration
+                && let Some((clock, deadline)) = &self.deadline
+                && clock.now() >= *deadline
+            {
+                let best = self.best.unwrap_or(current);
+                return Err(PlannerError::BudgetExceeded {
+                    budget: SearchBudget::Duration(duration),
+                    best_partial: Box::new(self.reconstruct(best)),
+                });
+            }
+
+            if let Some(nodes) = self.planner.config.max_nodes_expanded
+                && self.stats.nodes_expanded >= nodes
+            {
+                let best = self.best.unwrap_or(current);
+                return Err(PlannerError::BudgetExceeded {
+                    budget: SearchBudget::NodesExpanded(nodes),
+                    best_partial: Box::new(self.reconstruct(best)),
+                });
+            }
+
+            if let Some(length) = self.planner.config.max_plan_length
+                && self.nodes[current].depth >= length
+            {
+                self.length_cutoff = true;
+                continue;
+            }
+
+            if self
+                .max_depth
+                .is_some_and(|max_depth| self.nodes[current].depth >= max_depth)
+            {
+                continue;
+            }
+
+            if self
+                .node_budget
+                .is_some_and(|budget| self.stats.nodes_expanded >= budget)
+            {
+                // Keep the node queued so the search can be resumed later
+                let node = &self.nodes[current];
+                let f_score = node.g + self.weight * node.h;
+                self.open.push(NodeWrapper {
+                    node: current,
+                    f_score,
+                    tie_break: node.tie_break,
+                });
+                self.budget_exhausted = true;
+                return Err(PlannerError::NoPlanFound);
             }
+
+            self.expand(current)?;
         }
 
-        Ok(total_distance as f64)
+        match (self.length_cutoff, self.planner.config.max_plan_length) {
+            (true, Some(length)) => Err(PlannerError::BudgetExceeded {
+                budget: SearchBudget::PlanLength(length),
+                best_partial: Box::new(self.reconstruct(self.best.unwrap_or(0))),
+            }),
+            _ => Err(PlannerError::NoPlanFound),
+        }
     }
 
-    /// Reconstructs the final plan from the search data structures.
-    /// Traces back through the came_from map to build the sequence of actions.
-    fn reconstruct_path(
-        &self,
-        came_from: &HashMap<State, State>,
-        action_taken: &HashMap<State, Action>,
-        current: &State,
-    ) -> Plan {
-        let mut total_cost = 0.0;
-        let mut actions = Vec::new();
-        let mut current_state = current;
+    /// Discovers the states along a path of actions, given by name, from a node as
+    /// if the search had expanded them. Stops early at a state the search prunes.
+    pub(crate) fn seed_path(&mut self, from: usize, path: &[&str]) -> Result<(), PlannerError> {
+        let mut current = from;
+        for name in path {
+            let state = self.nodes[current].state.clone();
+            let (planner, actions) = (self.planner, self.actions);
+            let transition = planner
+                .expand(&state, actions)
+                .find(|transition| transition.action.name == *name)
+                .ok_or_else(|| PlannerError::InvalidSeed(name.to_string()))?;
+            let g = self.nodes[current].g
+                + transition.cost
+                + self.goal.ordering_penalty(&state, &transition.state);
+            let next = transition.state.clone();
+            self.discover(
+                transition.state,
+                Some(current),
+                Some(transition.action_index),
+                g,
+            )?;
+            match self.index.get(&next) {
+                Some(&node) => current = node,
+                None => return Ok(()),
+            }
+        }
+        Ok(())
+    }
 
-        while let Some(prev_state) = came_from.get(current_state) {
-            if let Some(action) = action_taken.get(current_state) {
-                actions.push(action.clone());
-                total_cost += action.cost;
+    /// Generates all successors of a node.
+    fn expand(&mut self, current: usize) -> Result<(), PlannerError> {
+        let node = &mut self.nodes[current];
+        node.expanded_g = Some(node.g);
+        let current_g = node.g;
+        let state = node.state.clone();
+        self.stats.nodes_expanded += 1;
+        self.observer.on_expand_begin(&state);
+
+        let mut successors = 0;
+        let (planner, actions) = (self.planner, self.actions);
+        let path = planner
+            .config
+            .path_cost
+            .as_ref()
+            .map(|_| self.path_context(current));
+        for transition in planner.expand(&state, actions) {
+            self.observer
+                .on_transition(&state, transition.action, &transition.state);
+            self.stats.nodes_generated += 1;
+            successors += 1;
+            let cost = match (&planner.config.path_cost, &path) {
+                (Some(path_cost), Some(path)) => {
+                    path_cost.evaluate(transition.action, &state, path)
+                }
+                _ => transition.cost,
+            };
+            let g = current_g + cost + self.goal.ordering_penalty(&state, &transition.state);
+            self.discover(
+                transition.state,
+                Some(current),
+                Some(transition.action_index),
+                g,
+            )?;
+        }
+
+        self.observer.on_expand_end(&state, successors);
+        Ok(())
+    }
+
+    /// Returns the actions on the path to a node, oldest first.
+    fn path_context(&self, node: usize) -> PathContext<'a> {
+        let mut path = Vec::new();
+        let mut current = Some(node);
+        while let Some(index) = current {
+            let node = &self.nodes[index];
+            if let Some(action) = node.action {
+                path.push(&self.actions[action]);
             }
-            current_state = prev_state;
+            current = node.parent;
         }
+        path.reverse();
+        PathContext::new(path)
+    }
+
+    /// Returns the explored node with the lowest heuristic distance to the goal.
+    pub(crate) fn closest(&self) -> Option<usize> {
+        self.best
+    }
+
+    /// Returns the state of a node.
+    pub(crate) fn node_state(&self, node: usize) -> &State {
+        &self.nodes[node].state
+    }
 
+    /// Follows parent links from a node back to the start node it was reached from.
+    fn root(&self, mut node: usize) -> usize {
+        while let Some(parent) = self.nodes[node].parent {
+            node = parent;
+        }
+        node
+    }
+
+    /// Reconstructs the plan leading to the given node by following parent links.
+    pub(crate) fn reconstruct(&self, target: usize) -> Plan {
+        let mut actions = Vec::new();
+        let mut states = Vec::new();
+        let mut current = Some(target);
+        while let Some(index) = current {
+            let node = &self.nodes[index];
+            states.push(&node.state);
+            if let Some(action) = node.action {
+                actions.push(self.actions[action].clone());
+            }
+            current = node.parent;
+        }
         actions.reverse();
+        states.reverse();
+
+        let cost = match &self.planner.config.path_cost {
+            Some(path_cost) => {
+                let mut path = PathContext::default();
+                let mut cost = 0.0;
+                for (action, state) in actions.iter().zip(&states) {
+                    cost += path_cost.evaluate(action, state, &path);
+                    path.push(action);
+                }
+                cost
+            }
+            None => actions.iter().map(|action| action.cost).sum(),
+        };
+        let start = &self.nodes[self.root(target)].state;
         Plan {
+            assumptions: plan_assumptions(start, self.goal, &actions),
             actions,
-            cost: total_cost,
+            cost,
+            stats: self.stats.clone(),
+            currencies: self
+                .planner
+                .currency_changes(start, &self.nodes[target].state),
         }
     }
 }
 
+/// Returns the variables of `start` that a plan of `actions` towards `goal` relies
+/// on; see [`Assumption`].
+pub(crate) fn plan_assumptions(
+    start: &State,
+    goal: &Goal,
+    actions: &[Action],
+) -> BTreeMap<String, Assumption> {
+    let mut relied: HashSet<&str> = HashSet::new();
+    let mut set: HashSet<&str> = HashSet::new();
+    let mut changed: HashMap<&str, usize> = HashMap::new();
+    for (step, action) in actions.iter().enumerate() {
+        relied.extend(
+            read_keys(action)
+                .into_iter()
+                .filter(|key| !set.contains(key)),
+        );
+        for (key, operation) in &action.effects {
+            changed.entry(key).or_insert(step);
+            if matches!(operation, StateOperation::Set(_)) {
+                set.insert(key);
+            }
+        }
+        for key in action.dynamic_effects.keys() {
+            changed.entry(key).or_insert(step);
+        }
+    }
+    let goal_keys = goal_keys(goal);
+    relied.extend(
+        goal_keys
+            .iter()
+            .map(String::as_str)
+            .filter(|key| !set.contains(key)),
+    );
+    relied
+        .into_iter()
+        .map(|key| {
+            let assumption = Assumption {
+                value: start.vars.get(key).cloned(),
+                until: changed.get(key).copied().unwrap_or(actions.len()),
+            };
+            (key.to_string(), assumption)
+        })
+        .collect()
+}
+
+/// Approximate number of bytes the search keeps for one discovered state.
+/// Each state is stored twice: in the node arena and as a key of the lookup table.
+pub(crate) fn node_memory_bytes(state: &State) -> usize {
+    2 * state.approx_memory_bytes() + size_of::<SearchNode>() + size_of::<usize>()
+}
+
+/// Sums, over the given goals, the goal's priority for each of its requirements that
+/// the state does not meet. Zero when there are no goals.
+pub(crate) fn priority_penalty(state: &State, goals: &[Goal]) -> f64 {
+    goals
+        .iter()
+        .map(|goal| {
+            let unmet = goal
+                .desired_state
+                .vars
+                .iter()
+                .filter(|(key, required)| {
+                    !state
+                        .vars
+                        .get(*key)
+                        .is_some_and(|current| value_satisfies(current, required))
+                })
+                .count()
+                + goal
+                    .excluded
+                    .vars
+                    .iter()
+                    .filter(|(key, value)| state.vars.get(*key) == Some(*value))
+                    .count()
+                + goal.unmet_conditions(state);
+            f64::from(goal.priority) * unmet as f64
+        })
+        .sum()
+}
+
 /// Wrapper for nodes in the A* search priority queue.
 /// Allows states to be ordered by their f-score for efficient retrieval.
 #[derive(Clone)]
-struct NodeWrapper<N> {
+pub(crate) struct NodeWrapper<N> {
     /// The state being wrapped
-    node: N,
+    pub(crate) node: N,
     /// The f-score (g + h) used for A* search ordering
-    f_score: f64,
+    pub(crate) f_score: f64,
+    /// Orders nodes with equal f-scores; lower values are retrieved first
+    pub(crate) tie_break: f64,
 }
 
 impl<N: PartialEq> PartialEq for NodeWrapper<N> {
@@ -222,7 +1847,10 @@ impl<N: Eq> Ord for NodeWrapper<N> {
     fn cmp(&self, other: &Self) -> Ordering {
         // Use total ordering: NaN values are treated as greater than any finite value
         // This means NaN f-scores will have the lowest priority in our min-heap
-        other.f_score.total_cmp(&self.f_score)
+        other
+            .f_score
+            .total_cmp(&self.f_score)
+            .then_with(|| other.tie_break.total_cmp(&self.tie_break))
     }
 }
 
@@ -244,10 +1872,12 @@ mod tests {
         let node1 = NodeWrapper {
             node: state1,
             f_score: 10.0,
+            tie_break: 0.0,
         };
         let node2 = NodeWrapper {
             node: state2,
             f_score: 5.0,
+            tie_break: 0.0,
         };
 
         // Test ordering - lower f_score should be higher priority
@@ -275,14 +1905,17 @@ mod tests {
         let normal_node = NodeWrapper {
             node: state1,
             f_score: 10.0,
+            tie_break: 0.0,
         };
         let nan_node = NodeWrapper {
             node: state2,
             f_score: f64::NAN,
+            tie_break: 0.0,
         };
         let another_nan_node = NodeWrapper {
             node: state3,
             f_score: f64::NAN,
+            tie_break: 0.0,
         };
 
         // Test that NaN nodes are ordered consistently
@@ -3,13 +3,141 @@
 //! This module re-exports the most commonly used types and traits from the library,
 //! allowing users to import everything they need with `use goap::prelude::*;`.
 
+/// Plans producing a quantity of something as repeated actions
+pub use crate::accumulate::{AccumulationPlan, Repetition};
 /// Action-related types for defining what agents can do
-pub use crate::actions::{Action, NumericValue};
+pub use crate::actions::{
+    Action, DynamicEffect, NumericValue, PreconditionFailure, UnmetPrecondition,
+};
+/// Analysis tools for debugging domains
+pub use crate::analysis::{
+    BlockedCondition, DeadEndAnalysis, DeadEndRule, Deadlock, KeyWriter, WriteConflict,
+    WriterReport, analyze_dead_ends, analyze_dead_ends_with_schema, analyze_deadlocks,
+    analyze_goal_reachability, analyze_writers,
+};
+/// Goal lifecycles for one-shot and recurring goals
+pub use crate::arbiter::{
+    GoalArbiter, GoalCallback, GoalEvent, GoalEventKind, GoalStatus, Recurrence,
+};
+/// Beliefs that expire without fresh observations
+pub use crate::belief::{BeliefState, Forget, Fusion};
+/// Search budget reports and adaptive per-agent budgets
+pub use crate::budget::{AutoBudget, BudgetReport};
+/// Action cost calibration from observed executions
+pub use crate::calibration::{
+    CostCalibrator, CostEstimate, CostSuggestion, PlanReport, StepReport,
+};
+/// Time sources for planning time budgets
+pub use crate::clock::{Clock, StdClock};
+/// Goal clusters for warm-starting planning
+pub use crate::clusters::{GoalCluster, GoalClusters, goal_similarity};
+/// Domains compiled for fast repeated planning
+pub use crate::compiled::{CompileError, CompiledDomain};
+/// Contingency plans with fallback branches
+pub use crate::contingency::{Branch, ContingencyExecutor, ContingencyPlan};
+/// Reachable state samples and action coverage
+pub use crate::coverage::{
+    ActionCoverage, ReachableStates, sample_reachable_states, sample_reachable_states_seeded,
+};
+/// Logs of agent decisions for analytics
+pub use crate::decisions::{
+    ActionDecisions, DecisionEvent, DecisionSink, DecisionStats, GoalDecisions,
+};
+/// Validation errors for action and goal definitions
+pub use crate::definition::DefinitionError;
+/// Dominance between states
+pub use crate::dominance::dominates;
+/// Change events for simulated and executed plans
+pub use crate::events::{PlanEvent, key_changes};
+/// Plan execution with precondition checks
+pub use crate::executor::{
+    ExecutionError, ExecutorSnapshot, PlanExecutor, PreconditionCheck, RestoreError,
+};
+/// Explanations of planning failures
+pub use crate::explain::PlanFailure;
+/// Expression types for arithmetic preconditions and effects
+pub use crate::expr::{Assignment, Comparison, Expr, ExprError};
+/// Fallback behaviours when no goal can be planned
+pub use crate::fallback::{Fallback, GoalChoice, IDLE_KEY, WANDER_KEY};
 /// Goal-related types for defining what agents want to achieve
-pub use crate::goals::Goal;
+pub use crate::goals::{Goal, OrderingHint};
+/// Per-variable breakdown of the planner's heuristic
+pub use crate::heuristic_report::{
+    HeuristicReport, HeuristicRow, HeuristicTable, HeuristicTerm, Overestimate, state_grid,
+};
+/// Domains loaded from data files
+#[cfg(any(feature = "json", feature = "ron", feature = "toml"))]
+pub use crate::loader::{Domain, EntryError, LoadError};
+/// Monte Carlo Tree Search types
+pub use crate::mcts::{MonteCarloConfig, RandomRollout, RolloutPolicy};
+/// Mutually exclusive variable groups
+pub use crate::mutex::{FlagConversionError, FlagsToEnum, MutexGroup};
+/// Navigation types for generating movement actions
+pub use crate::navigation::{LocationGraph, MOVEMENT_TAG, TransitionModel};
+/// Observer types for instrumenting planning
+pub use crate::observer::{CountingObserver, PlannerObserver};
+/// Ownership of state variables shared between agents
+pub use crate::ownership::KeyOwnership;
+/// Running independent plan steps at the same time
+pub use crate::parallel::{ParallelExecutor, StepStatus};
+/// Action costs that depend on the plan so far
+pub use crate::path_cost::{PathContext, PathCost, PathCostFn};
+/// Planning strategies tried in order
+pub use crate::pipeline::{PipelinePlan, PipelineStage, PlannerPipeline};
 /// Planning-related types for finding sequences of actions
-pub use crate::planner::{Plan, Planner, PlannerError};
+pub use crate::planner::{
+    AnytimePlan, Assumption, HorizonPlan, Plan, PlanStats, Planner, PlannerConfig, PlannerError,
+    SearchBudget, SearchStrategy, Transition, WeightSchedule,
+};
+/// Planning for several goals ordered by priority
+pub use crate::priorities::{GoalCompletion, PrioritizedPlan};
+/// Named search settings selected per goal
+pub use crate::profiles::PlannerProfile;
+/// Goal regression for plan repair and resumption checks
+pub use crate::regression::regress;
+/// Renaming state variables of generic action libraries
+pub use crate::remap::KeyMap;
+/// Repairing previous plans for changed states
+pub use crate::replan::{PlanRepair, RepairedPlan};
+/// Plan robustness under perturbation
+pub use crate::robustness::{Perturbation, Perturbations, RobustnessReport};
+/// Sampling varied, near-optimal plans
+pub use crate::sampling::SamplingConfig;
+/// Per-frame scheduling of plan requests from many agents
+pub use crate::scheduler::{PlanningScheduler, RequestId, SchedulingPolicy};
+/// Metadata for state variables
+pub use crate::schema::{KeyInfo, StateSchema};
+/// Plan scoring types for evaluating plans from other sources
+pub use crate::scoring::{
+    ActionCosts, CostModel, Invariant, InvariantCosts, InvariantViolation, PlanScore, score_plan,
+};
+/// Combining the plans of sequential goals
+pub use crate::sequential::{GoalMarker, MergedPlan, merge_sequential_plans};
+/// Persistent planning sessions
+pub use crate::session::{FrontierNode, PlanningSession, SessionStatus};
+/// Stable plan hashes and signatures
+#[cfg(feature = "signature")]
+pub use crate::signature::PlanDigest;
+/// Planning from snapshots of shared state
+pub use crate::snapshot::SnapshotPlan;
+/// Pluggable planning backends
+pub use crate::solver::Solver;
+/// Planning states read from external storage
+pub use crate::source::{EntitySource, KeyReader, KeyReaders, StateSource, domain_keys};
+/// Building states from key-value literals
+pub use crate::state;
 /// State-related types for representing the world state
 pub use crate::state::{
-    EnumStateVar, IntoStateVar, State, StateError, StateOperation, StateVar, TryFromStateVar,
+    CompactDisplay, Condition, ConversionError, EnumKey, EnumStateVar, IntoStateVar, State,
+    StateError, StateOperation, StateVar, TryFromStateVar,
 };
+/// Comparison rules for string variables
+pub use crate::strings::{StringComparison, StringComparisons};
+/// Symbol tables for validating string values
+pub use crate::symbols::{SymbolError, SymbolTable};
+/// The built-in time variable
+pub use crate::time::{Process, TIME_KEY, Wait, wait_action};
+/// Typed wrappers around states
+pub use crate::typed_state;
+/// Typed quantities and unit checks for numeric variables
+pub use crate::units::{Percent, Quantity, QuantityKey, QuantityScale, UnitError};
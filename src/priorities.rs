@@ -0,0 +1,182 @@
+//! Planning for several goals at once, ordered by priority.
+//!
+//! [`Planner::plan_goals`] merges the requirements of several goals into one and
+//! searches for a plan that satisfies all of them. Among plans of equal cost, the
+//! search prefers the one that meets the requirements of higher-priority goals
+//! earlier. The returned [`PrioritizedPlan`] records the step at which each goal
+//! became satisfied, so callers can see why the actions were ordered as they are.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let initial_state = State::new().set("fed", false).set("safe", false).build();
+//! let goals = [
+//!     Goal::new("eat").requires("fed", true).priority(1).build(),
+//!     Goal::new("hide").requires("safe", true).priority(5).build(),
+//! ];
+//! let actions = [
+//!     Action::new("forage").sets("fed", true).build(),
+//!     Action::new("take_cover").sets("safe", true).build(),
+//! ];
+//!
+//! let result = Planner::new().plan_goals(initial_state, &goals, &actions).unwrap();
+//! assert_eq!(result.plan.actions[0].name, "take_cover");
+//! assert_eq!(result.completions[0].goal, "hide");
+//! ```
+
+use crate::actions::Action;
+use crate::definition::DefinitionError;
+use crate::goals::Goal;
+use crate::observer::NoopObserver;
+use crate::planner::{Plan, Planner, PlannerError, Search};
+use crate::state::{State, StateVar};
+use std::borrow::Cow;
+
+/// When one of the merged goals became satisfied during a plan.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GoalCompletion {
+    /// The name of the goal
+    pub goal: String,
+    /// The priority of the goal
+    pub priority: u16,
+    /// The number of actions after which the goal holds for the rest of the plan;
+    /// zero if it already held in the initial state
+    pub step: usize,
+}
+
+/// The result of planning for several goals at once.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PrioritizedPlan {
+    /// The plan satisfying every goal
+    pub plan: Plan,
+    /// One entry per goal, ordered by the step at which it was completed and then by
+    /// descending priority
+    pub completions: Vec<GoalCompletion>,
+}
+
+impl Goal {
+    /// Merges several goals into one that is satisfied only when all of them are.
+    ///
+    /// The merged goal is named after the goals joined with `+` and has the highest
+    /// of their priorities. When two goals require a minimum of the same numeric
+    /// variable, the larger minimum is kept. Returns an error if the goals place
+    /// contradicting requirements on a variable.
+    pub fn merge(goals: &[Goal]) -> Result<Goal, DefinitionError> {
+        let mut merged = Goal::from_state(
+            &goals
+                .iter()
+                .map(|goal| goal.name.as_str())
+                .collect::<Vec<_>>()
+                .join("+"),
+            State::empty(),
+            goals.iter().map(|goal| goal.priority).max().unwrap_or(0),
+        );
+        for goal in goals {
+            merge_minimums(&mut merged.desired_state, &goal.desired_state)?;
+            merge_minimums(&mut merged.relative, &goal.relative)?;
+            merged.excluded.vars.extend(
+                goal.excluded
+                    .vars
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone())),
+            );
+            merged.conditions.extend(goal.conditions.iter().cloned());
+        }
+        if let Some(key) = merged
+            .excluded
+            .vars
+            .iter()
+            .find(|(key, value)| merged.desired_state.vars.get(*key) == Some(*value))
+            .map(|(key, _)| key)
+        {
+            return Err(DefinitionError::ConflictingRequirement { key: key.clone() });
+        }
+        Ok(merged)
+    }
+}
+
+/// Adds the requirements of `from` to `into`, keeping the larger of two numeric minimums.
+fn merge_minimums(into: &mut State, from: &State) -> Result<(), DefinitionError> {
+    for (key, value) in &from.vars {
+        let merged = match (into.vars.get(key), value) {
+            (None, _) => value.clone(),
+            (Some(StateVar::I64(current)), StateVar::I64(other)) => {
+                StateVar::I64(*current.max(other))
+            }
+            (Some(StateVar::F64(current)), StateVar::F64(other)) => {
+                StateVar::F64(*current.max(other))
+            }
+            (Some(current), other) if current == other => continue,
+            _ => {
+                return Err(DefinitionError::ConflictingRequirement { key: key.clone() });
+            }
+        };
+        into.vars.insert(key.clone(), merged);
+    }
+    Ok(())
+}
+
+impl Planner {
+    /// Finds a plan that satisfies every goal, preferring to meet the requirements of
+    /// higher-priority goals earlier when plans are otherwise equally good.
+    ///
+    /// Relative requirements are resolved against `initial_state` before the goals
+    /// are merged with [`Goal::merge`]. Priorities only break ties between search
+    /// nodes with equal f-scores, so the plan is as cheap as one for the merged goal
+    /// found by [`Planner::plan`]. The search always uses A*, ignoring the configured
+    /// strategy and annealing schedule. Returns [`PlannerError::NoPlanFound`] if the
+    /// goals contradict each other.
+    pub fn plan_goals<'s>(
+        &self,
+        initial_state: impl Into<Cow<'s, State>>,
+        goals: &[Goal],
+        actions: &[Action],
+    ) -> Result<PrioritizedPlan, PlannerError> {
+        let initial_state = initial_state.into().into_owned();
+        let goals: Vec<Goal> = goals
+            .iter()
+            .map(|goal| {
+                self.prepare_start(initial_state.clone(), goal)
+                    .1
+                    .into_owned()
+            })
+            .collect();
+        let merged = Goal::merge(&goals).map_err(|_| PlannerError::NoPlanFound)?;
+        let (initial_state, merged) = self.prepare_start(initial_state, &merged);
+        self.check_problem(&initial_state, &merged, actions)?;
+        let actions = self.prepare_actions(actions);
+
+        let dead_ends = self.dead_end_analysis(&merged, &actions);
+        let mut observer = NoopObserver;
+        let mut search = Search::new(self, &merged, &actions, dead_ends.as_ref(), &mut observer);
+        search.tie_break_goals = &goals;
+        search.push_start(initial_state.clone())?;
+        let plan = search.run()?;
+
+        let completions = completions(&initial_state, &goals, &plan);
+        Ok(PrioritizedPlan { plan, completions })
+    }
+}
+
+/// Finds, for each goal, the first step after which it stays satisfied until the
+/// end of the plan.
+fn completions(initial_state: &State, goals: &[Goal], plan: &Plan) -> Vec<GoalCompletion> {
+    let mut states = vec![initial_state.clone()];
+    for action in &plan.actions {
+        let next = action.apply_effect(states.last().unwrap_or(initial_state));
+        states.push(next);
+    }
+    let mut completions: Vec<GoalCompletion> = goals
+        .iter()
+        .map(|goal| GoalCompletion {
+            goal: goal.name.clone(),
+            priority: goal.priority,
+            step: states
+                .iter()
+                .rposition(|state| !goal.is_satisfied(state))
+                .map_or(0, |last_unmet| last_unmet + 1),
+        })
+        .collect();
+    completions.sort_by(|a, b| a.step.cmp(&b.step).then(b.priority.cmp(&a.priority)));
+    completions
+}
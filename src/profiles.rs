@@ -0,0 +1,145 @@
+//! Named search settings that goals select by name.
+//!
+//! Reactive goals such as fleeing need a plan this frame, even a slightly worse
+//! one, while strategic goals such as building a base can afford an exhaustive
+//! search. A [`PlannerProfile`] bundles the settings that trade plan quality for
+//! speed: the search strategy, heuristic weights and budgets, and the tags of
+//! actions left out. Goals refer to a profile with
+//! [`GoalBuilder::profile`](crate::goals::GoalBuilder::profile) and
+//! [`Planner::plan`] searches with that profile's settings instead of the
+//! configuration's.
+//!
+//! The profiles `"urgent"`, `"background"` and `"optimal"` are built in (see
+//! [`PlannerProfile::preset`]). Register more, or replace the presets, with
+//! [`PlannerConfig::profile`](crate::planner::PlannerConfig::profile).
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let actions = [Action::new("run").sets("safe", true).build()];
+//! let flee = Goal::new("flee").requires("safe", true).profile("urgent").build();
+//! let expand = Goal::new("expand").requires("safe", true).profile("siege").build();
+//!
+//! let planner = Planner::with_config(
+//!     PlannerConfig::new().profile("siege", PlannerProfile::new().exclude_tags(["loud"])),
+//! );
+//! assert!(planner.plan(State::empty(), &flee, &actions).is_ok());
+//! assert!(planner.plan(State::empty(), &expand, &actions).is_ok());
+//! ```
+
+use crate::goals::Goal;
+use crate::planner::{Planner, PlannerError, SearchStrategy, WeightSchedule};
+use std::time::Duration;
+
+/// Search settings that a goal can select by name.
+///
+/// Each setting replaces the configuration's when planning for the goal, except
+/// excluded tags, which are added to the configuration's. A new profile runs an
+/// unbounded A* search.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlannerProfile {
+    /// The search algorithm to use
+    pub strategy: SearchStrategy,
+    /// The annealed heuristic weight, if any
+    pub annealing: Option<WeightSchedule>,
+    /// Maximum time an A* search may run
+    pub max_duration: Option<Duration>,
+    /// Approximate memory cap for the search structures, in bytes
+    pub max_memory_bytes: Option<usize>,
+    /// Actions carrying any of these tags are left out of planning
+    pub excluded_tags: Vec<String>,
+}
+
+impl PlannerProfile {
+    /// Creates a profile running an unbounded A* search.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the built-in profile with the given name, if any:
+    ///
+    /// - `"urgent"`: a greedy search with weight 5.0 annealed over 500 expansions,
+    ///   for reactive goals that need some plan quickly
+    /// - `"background"`: weight 2.0 annealed over 20 000 expansions, for goals
+    ///   that can wait for a good plan
+    /// - `"optimal"`: an unbounded A* search finding the cheapest plan
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "urgent" => Some(Self::new().annealed_weight(5.0, 500)),
+            "background" => Some(Self::new().annealed_weight(2.0, 20_000)),
+            "optimal" => Some(Self::new()),
+            _ => None,
+        }
+    }
+
+    /// Selects the search algorithm.
+    pub fn strategy(mut self, strategy: SearchStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Plans with a heuristic weight that anneals from `initial_weight` to 1.0
+    /// over `node_budget` expansions.
+    pub fn annealed_weight(mut self, initial_weight: f64, node_budget: usize) -> Self {
+        self.annealing = Some(WeightSchedule {
+            initial_weight,
+            node_budget,
+        });
+        self
+    }
+
+    /// Limits how long an A* search may run.
+    pub fn max_duration(mut self, duration: Duration) -> Self {
+        self.max_duration = Some(duration);
+        self
+    }
+
+    /// Caps the approximate memory used by the search structures.
+    pub fn max_memory_bytes(mut self, bytes: usize) -> Self {
+        self.max_memory_bytes = Some(bytes);
+        self
+    }
+
+    /// Leaves actions carrying any of the given tags out of planning.
+    pub fn exclude_tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.excluded_tags
+            .extend(tags.into_iter().map(|tag| tag.as_ref().to_string()));
+        self
+    }
+}
+
+impl Planner {
+    /// Returns the profile a goal selects, looked up among the configured
+    /// profiles and then the presets, or None if the goal selects no profile.
+    pub fn goal_profile(&self, goal: &Goal) -> Result<Option<PlannerProfile>, PlannerError> {
+        let Some(name) = &goal.profile else {
+            return Ok(None);
+        };
+        self.config()
+            .profiles
+            .get(name)
+            .cloned()
+            .or_else(|| PlannerProfile::preset(name))
+            .map(Some)
+            .ok_or_else(|| PlannerError::UnknownProfile(name.clone()))
+    }
+
+    /// Returns a planner searching with the goal's profile, or None if the goal
+    /// selects no profile.
+    pub(crate) fn profiled(&self, goal: &Goal) -> Result<Option<Planner>, PlannerError> {
+        let Some(profile) = self.goal_profile(goal)? else {
+            return Ok(None);
+        };
+        let mut config = self.config().clone();
+        config.strategy = profile.strategy;
+        config.annealing = profile.annealing;
+        config.max_duration = profile.max_duration;
+        config.max_memory_bytes = profile.max_memory_bytes;
+        config.excluded_tags.extend(profile.excluded_tags);
+        Ok(Some(Planner::with_config(config)))
+    }
+}
@@ -0,0 +1,92 @@
+//! Goal regression: computing what must hold before a sequence of actions.
+//!
+//! Regressing a set of requirements through a plan suffix yields its weakest
+//! precondition: the least a state must satisfy for the suffix to be executable
+//! and to leave the requirements satisfied. This is the building block for plan
+//! repair and for checking whether an interrupted plan can still be resumed.
+
+use crate::actions::Action;
+use crate::state::{State, StateOperation, StateVar, value_satisfies};
+
+/// Computes the weakest precondition of executing `actions` in order so that
+/// `requirements` hold afterwards.
+///
+/// Any state that satisfies the returned requirements (using [`State::satisfies`])
+/// can execute every action in the sequence and ends up satisfying `requirements`.
+/// Returns `None` when no state can, for example because an action sets a required
+/// variable to the wrong value or two conditions demand different values.
+///
+/// The result is conservative where the action model cannot be regressed exactly:
+/// actions with expression or absence preconditions, postconditions, or with dynamic
+/// effects on a required variable, also produce `None`.
+pub fn regress(requirements: &State, actions: &[Action]) -> Option<State> {
+    let mut required = requirements.clone();
+    for action in actions.iter().rev() {
+        required = regress_action(&required, action)?;
+    }
+    Some(required)
+}
+
+/// Regresses requirements through a single action.
+fn regress_action(required: &State, action: &Action) -> Option<State> {
+    if !action.expr_preconditions.is_empty()
+        || !action.any_preconditions.is_empty()
+        || !action.absent_preconditions.is_empty()
+        || !action.conditions.is_empty()
+        || !action.postconditions.is_empty()
+    {
+        return None;
+    }
+
+    let mut before = State::empty();
+    for (key, value) in &required.vars {
+        if action.dynamic_effects.contains_key(key) {
+            return None;
+        }
+        match action.effects.get(key) {
+            Some(StateOperation::Set(set_value)) => {
+                if !value_satisfies(set_value, value) {
+                    return None;
+                }
+                // The action establishes this requirement itself
+            }
+            Some(StateOperation::Add(amount)) => {
+                before.vars.insert(key.clone(), offset(value, -amount));
+            }
+            Some(StateOperation::Subtract(amount)) => {
+                before.vars.insert(key.clone(), offset(value, *amount));
+            }
+            None => {
+                before.vars.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    for (key, precondition) in &action.preconditions.vars {
+        let combined = match before.vars.get(key) {
+            Some(existing) => combine(existing, precondition)?,
+            None => precondition.clone(),
+        };
+        before.vars.insert(key.clone(), combined);
+    }
+    Some(before)
+}
+
+/// Shifts a numeric lower bound by `amount`; other values are unaffected by arithmetic.
+fn offset(value: &StateVar, amount: i64) -> StateVar {
+    match value {
+        StateVar::I64(bound) => StateVar::I64(bound + amount),
+        StateVar::F64(bound) => StateVar::F64(bound + amount),
+        other => other.clone(),
+    }
+}
+
+/// Combines two requirements on the same variable into one that implies both.
+fn combine(a: &StateVar, b: &StateVar) -> Option<StateVar> {
+    match (a, b) {
+        (StateVar::I64(x), StateVar::I64(y)) => Some(StateVar::I64(*x.max(y))),
+        (StateVar::F64(x), StateVar::F64(y)) => Some(StateVar::F64(*x.max(y))),
+        _ if a == b => Some(a.clone()),
+        _ => None,
+    }
+}
@@ -0,0 +1,250 @@
+//! Renaming state variables when instantiating an action library.
+//!
+//! An action library is often authored once with generic keys such as
+//! `self.health` and then used by many agents whose state stores the same facts
+//! under their own keys, such as `tank_health`. A [`KeyMap`] lists the renames,
+//! exactly or by prefix, and rewrites actions, goals and states with them, so the
+//! library does not have to be cloned and edited by hand for every agent.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let library = vec![
+//!     Action::new("heal")
+//!         .requires("self.has_medkit", true)
+//!         .adds("self.health", 50)
+//!         .build(),
+//! ];
+//! let tank = KeyMap::new().prefix("self.", "tank_");
+//! let actions = tank.actions(&library);
+//! assert!(actions[0].effects.contains_key("tank_health"));
+//!
+//! let state = State::new()
+//!     .set("tank_has_medkit", true)
+//!     .set("tank_health", 20)
+//!     .build();
+//! let goal = tank.goal(&Goal::new("healthy").requires("self.health", 70).build());
+//! let plan = Planner::new().plan(state, &goal, &actions).unwrap();
+//! assert_eq!(plan.actions[0].name, "heal");
+//! ```
+
+use crate::actions::{Action, DynamicEffect};
+use crate::expr::{Comparison, Expr};
+use crate::goals::{Goal, OrderingHint};
+use crate::state::{Condition, State, StateOperation};
+use std::collections::HashMap;
+
+/// Renames state variables in actions, goals and states.
+///
+/// Exact renames take precedence over prefix renames, and the longest matching
+/// prefix wins. Keys matching no rename are kept as they are, so variables shared
+/// by every agent, such as the time variable, need no entry.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeyMap {
+    /// Renames of whole keys
+    exact: HashMap<String, String>,
+    /// Renames of key prefixes, as (from, to)
+    prefixes: Vec<(String, String)>,
+}
+
+impl KeyMap {
+    /// Creates a map that renames nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Renames the key `from` to `to`.
+    pub fn rename(mut self, from: &str, to: &str) -> Self {
+        self.exact.insert(from.to_string(), to.to_string());
+        self
+    }
+
+    /// Renames every key starting with `from` by replacing that prefix with `to`.
+    pub fn prefix(mut self, from: &str, to: &str) -> Self {
+        self.prefixes.retain(|(existing, _)| existing != from);
+        self.prefixes.push((from.to_string(), to.to_string()));
+        self.prefixes
+            .sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+        self
+    }
+
+    /// Returns true if the map renames nothing.
+    pub fn is_empty(&self) -> bool {
+        self.exact.is_empty() && self.prefixes.is_empty()
+    }
+
+    /// Returns the new name of a key.
+    pub fn key(&self, key: &str) -> String {
+        if let Some(renamed) = self.exact.get(key) {
+            return renamed.clone();
+        }
+        for (from, to) in &self.prefixes {
+            if let Some(rest) = key.strip_prefix(from.as_str()) {
+                return format!("{to}{rest}");
+            }
+        }
+        key.to_string()
+    }
+
+    /// Returns the key that is renamed to `key`, if a rename produces it.
+    fn original(&self, key: &str) -> Option<String> {
+        if let Some((from, _)) = self.exact.iter().find(|(_, to)| to.as_str() == key) {
+            return Some(from.clone());
+        }
+        self.prefixes.iter().find_map(|(from, to)| {
+            let candidate = format!("{from}{}", key.strip_prefix(to.as_str())?);
+            (self.key(&candidate) == key).then_some(candidate)
+        })
+    }
+
+    /// Returns a copy of the state with its variables renamed.
+    pub fn state(&self, state: &State) -> State {
+        State {
+            vars: state
+                .vars
+                .iter()
+                .map(|(key, value)| (self.key(key), value.clone()))
+                .collect(),
+        }
+    }
+
+    /// Returns a copy of the goal with its requirements renamed.
+    pub fn goal(&self, goal: &Goal) -> Goal {
+        Goal {
+            desired_state: self.state(&goal.desired_state),
+            excluded: self.state(&goal.excluded),
+            relative: self.state(&goal.relative),
+            conditions: self.conditions(&goal.conditions),
+            ordering: goal
+                .ordering
+                .iter()
+                .map(|hint| OrderingHint {
+                    first: self.key(&hint.first),
+                    then: self.key(&hint.then),
+                    penalty: hint.penalty,
+                })
+                .collect(),
+            ..goal.clone()
+        }
+    }
+
+    /// Returns a copy of the action with the variables of its preconditions,
+    /// effects and postconditions renamed.
+    ///
+    /// Dynamic effects keep computing their operation with the generic keys: each
+    /// evaluation sees a copy of the state with the renamed variables given their
+    /// original names, which costs one state copy per evaluation. Descriptions of
+    /// dynamic effects are kept as written.
+    pub fn action(&self, action: &Action) -> Action {
+        Action {
+            preconditions: self.state(&action.preconditions),
+            expr_preconditions: self.comparisons(&action.expr_preconditions),
+            any_preconditions: action
+                .any_preconditions
+                .iter()
+                .map(|group| {
+                    group
+                        .iter()
+                        .map(|(key, value)| (self.key(key), value.clone()))
+                        .collect()
+                })
+                .collect(),
+            absent_preconditions: action
+                .absent_preconditions
+                .iter()
+                .map(|key| self.key(key))
+                .collect(),
+            conditions: self.conditions(&action.conditions),
+            effects: self.operations(&action.effects),
+            dynamic_effects: action
+                .dynamic_effects
+                .iter()
+                .map(|(key, effect)| (self.key(key), self.dynamic_effect(effect)))
+                .collect(),
+            failure_effects: self.operations(&action.failure_effects),
+            postconditions: self.comparisons(&action.postconditions),
+            ..action.clone()
+        }
+    }
+
+    /// Returns copies of the actions with their variables renamed.
+    pub fn actions(&self, actions: &[Action]) -> Vec<Action> {
+        actions.iter().map(|action| self.action(action)).collect()
+    }
+
+    /// Returns a copy of the state with renamed variables given their original
+    /// names, the state generic code expects.
+    fn generic_state(&self, state: &State) -> State {
+        State {
+            vars: state
+                .vars
+                .iter()
+                .map(|(key, value)| {
+                    (
+                        self.original(key).unwrap_or_else(|| key.clone()),
+                        value.clone(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Wraps a dynamic effect so it reads the state through the original names.
+    fn dynamic_effect(&self, effect: &DynamicEffect) -> DynamicEffect {
+        if self.is_empty() {
+            return effect.clone();
+        }
+        let map = self.clone();
+        let inner = effect.clone();
+        let wrapped = DynamicEffect::new(move |state| inner.evaluate(&map.generic_state(state)));
+        match effect.description() {
+            Some(description) => wrapped.with_description(description),
+            None => wrapped,
+        }
+    }
+
+    /// Returns a copy of the effects with their variables renamed.
+    fn operations(
+        &self,
+        effects: &HashMap<String, StateOperation>,
+    ) -> HashMap<String, StateOperation> {
+        effects
+            .iter()
+            .map(|(key, op)| (self.key(key), op.clone()))
+            .collect()
+    }
+
+    /// Returns copies of the conditions with their variables renamed.
+    fn conditions(&self, conditions: &[(String, Condition)]) -> Vec<(String, Condition)> {
+        conditions
+            .iter()
+            .map(|(key, condition)| (self.key(key), condition.clone()))
+            .collect()
+    }
+
+    /// Returns copies of the comparisons with their variables renamed.
+    fn comparisons(&self, comparisons: &[Comparison]) -> Vec<Comparison> {
+        comparisons
+            .iter()
+            .map(|comparison| Comparison {
+                lhs: self.expr(&comparison.lhs),
+                op: comparison.op,
+                rhs: self.expr(&comparison.rhs),
+            })
+            .collect()
+    }
+
+    /// Returns a copy of the expression with its variables renamed.
+    fn expr(&self, expr: &Expr) -> Expr {
+        match expr {
+            Expr::Number(value) => Expr::Number(*value),
+            Expr::Var(key) => Expr::Var(self.key(key)),
+            Expr::Neg(inner) => Expr::Neg(Box::new(self.expr(inner))),
+            Expr::Binary { op, lhs, rhs } => Expr::Binary {
+                op: *op,
+                lhs: Box::new(self.expr(lhs)),
+                rhs: Box::new(self.expr(rhs)),
+            },
+        }
+    }
+}
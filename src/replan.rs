@@ -0,0 +1,301 @@
+//! Repairing a previous plan after the world changed slightly.
+//!
+//! Agents that replan every few seconds usually find the world only a little
+//! different from when they last planned. [`Planner::replan`] first checks
+//! whether the rest of the previous plan still works from the new state, and if
+//! not, keeps the plan and searches only for a short bridge of actions that
+//! restores what a broken step needs, falling back to a full search when no
+//! bridge is found. The returned [`RepairedPlan`] says which of these happened.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let actions = vec![
+//!     Action::new("buy_axe").sets("has_axe", true).cost(3.0).build(),
+//!     Action::new("sharpen_axe").requires("has_axe", true).sets("sharp", true).build(),
+//!     Action::new("chop").requires("sharp", true).sets("has_wood", true).build(),
+//!     Action::new("build").requires("has_wood", true).sets("has_hut", true).build(),
+//! ];
+//! let goal = Goal::new("shelter").requires("has_hut", true).build();
+//! let planner = Planner::new();
+//! let start = state! { has_axe: true, sharp: true };
+//! let plan = planner.plan(&start, &goal, &actions).unwrap();
+//!
+//! // The axe went blunt: sharpening it again repairs the plan
+//! let blunt = state! { has_axe: true, sharp: false };
+//! let repaired = planner.replan(&plan, &blunt, &goal, &actions).unwrap();
+//! assert_eq!(repaired.repair, PlanRepair::Patched { skipped: 0, at: 0, inserted: 1 });
+//! assert_eq!(repaired.plan.display_compact().to_string(),
+//!     "Plan (total cost: 3.0): sharpen_axe -> chop -> build");
+//! ```
+
+use crate::actions::Action;
+use crate::goals::Goal;
+use crate::observer::NoopObserver;
+use crate::planner::{Plan, PlanStats, Planner, PlannerError, Search, plan_assumptions};
+use crate::regression::regress;
+use crate::state::State;
+use std::borrow::Cow;
+
+/// Node expansions shared by the bridge searches of one [`Planner::replan`] call
+/// when no node limit is configured.
+const BRIDGE_NODE_BUDGET: usize = 1_000;
+
+/// How [`Planner::replan`] produced its plan.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlanRepair {
+    /// The previous plan still works from the new state once its first `skipped`
+    /// steps, already done or no longer needed, are left out
+    Reused {
+        /// The number of leading steps of the previous plan left out
+        skipped: usize,
+    },
+    /// A bridge of new actions was inserted before a step of the previous plan that
+    /// could no longer be performed, or at its end if the goal was no longer met
+    Patched {
+        /// The number of leading steps of the previous plan left out
+        skipped: usize,
+        /// The index, in the previous plan, of the step the bridge was inserted before
+        at: usize,
+        /// The number of actions in the bridge
+        inserted: usize,
+    },
+    /// No repair worked and the plan was searched from scratch
+    Replanned,
+}
+
+/// A plan produced by [`Planner::replan`] and how it was obtained.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RepairedPlan {
+    /// The plan achieving the goal from the new state
+    pub plan: Plan,
+    /// How the plan was obtained from the previous one
+    pub repair: PlanRepair,
+}
+
+/// A way to patch the previous plan, ranked by its estimated total cost.
+struct Patch {
+    /// The number of leading steps left out
+    skipped: usize,
+    /// The index of the step the bridge goes before
+    at: usize,
+    /// The state reached before the bridge
+    state: State,
+    /// The requirements the bridge must meet
+    target: State,
+    /// The cost of the kept steps plus the heuristic estimate for the bridge
+    estimate: f64,
+}
+
+impl Planner {
+    /// Updates `previous`, a plan for `goal`, to start from `new_state`, searching as
+    /// little as possible.
+    ///
+    /// 1. If the steps of `previous` from some index on can be performed from
+    ///    `new_state` and achieve the goal, the cheapest such suffix is reused
+    ///    without searching.
+    /// 2. Otherwise, for each number of leading steps left out, the remaining steps
+    ///    are simulated up to the first one that cannot be performed (or the end, if
+    ///    the goal is not met). The search then only looks for a bridge from there to
+    ///    the weakest precondition of the steps that follow (see [`regress`]).
+    ///    Candidates are tried in order of their kept cost plus the heuristic
+    ///    estimate of the bridge, until the cheapest repair found costs less than
+    ///    the next estimate; on equal costs, the repair keeping more of the previous
+    ///    plan wins.
+    /// 3. If no bridge is found, the plan is searched from scratch with
+    ///    [`Planner::plan`].
+    ///
+    /// Bridges are searched with A* and share a budget of half of
+    /// [`PlannerConfig::max_nodes_expanded`](crate::planner::PlannerConfig::max_nodes_expanded),
+    /// or 1,000 node expansions without one, so that repairing never costs much
+    /// more than the full search it falls back to.
+    ///
+    /// Reused and patched plans cost the sum of their actions' costs, carry the
+    /// statistics of the bridge search, and are checked by simulating each step
+    /// as the search would expand it, including excluded tags, mutex groups,
+    /// postconditions, the schema, and the excluded values and conditions of the
+    /// goal. Relative requirements are resolved against `new_state`, as by
+    /// [`Planner::plan`]. Errors other than [`PlannerError::NoPlanFound`] from a
+    /// bridge search are returned.
+    pub fn replan<'s>(
+        &self,
+        previous: &Plan,
+        new_state: impl Into<Cow<'s, State>>,
+        goal: &Goal,
+        actions: &[Action],
+    ) -> Result<RepairedPlan, PlannerError> {
+        let (start, resolved) = self.prepare_start(new_state.into().into_owned(), goal);
+        self.check_problem(&start, &resolved, actions)?;
+        let prepared = self.prepare_actions(actions);
+        let steps = &previous.actions;
+
+        let reused = (0..=steps.len())
+            .filter(|&skipped| self.achieves(&start, &resolved, &steps[skipped..]))
+            .min_by(|&a, &b| step_cost(&steps[a..]).total_cmp(&step_cost(&steps[b..])));
+        if let Some(skipped) = reused {
+            let plan = self.assemble(&start, &resolved, steps[skipped..].to_vec(), None);
+            return Ok(RepairedPlan {
+                plan,
+                repair: PlanRepair::Reused { skipped },
+            });
+        }
+
+        let mut patches: Vec<Patch> = (0..steps.len())
+            .filter_map(|skipped| self.patch(&start, &resolved, steps, skipped))
+            .collect();
+        patches.sort_by(|a, b| {
+            a.estimate
+                .total_cmp(&b.estimate)
+                .then(a.skipped.cmp(&b.skipped))
+        });
+        let mut best: Option<RepairedPlan> = None;
+        let mut node_budget = self
+            .config()
+            .max_nodes_expanded
+            .map_or(BRIDGE_NODE_BUDGET, |nodes| nodes / 2);
+        for patch in patches {
+            if node_budget == 0
+                || best
+                    .as_ref()
+                    .is_some_and(|best| best.plan.cost < patch.estimate)
+            {
+                break;
+            }
+            let bridge_goal = Goal::from_state(&resolved.name, patch.target, resolved.priority);
+            let (bridge, expanded) =
+                self.bridge(patch.state, &bridge_goal, &prepared, node_budget)?;
+            node_budget = node_budget.saturating_sub(expanded);
+            let Some(bridge) = bridge else {
+                continue;
+            };
+            let inserted = bridge.actions.len();
+            let mut repaired = steps[patch.skipped..patch.at].to_vec();
+            repaired.extend(bridge.actions);
+            repaired.extend_from_slice(&steps[patch.at..]);
+            if !self.achieves(&start, &resolved, &repaired) {
+                continue;
+            }
+            let cost = step_cost(&repaired);
+            let better = best.as_ref().is_none_or(|best| match best.repair {
+                PlanRepair::Patched { skipped, .. } => {
+                    cost < best.plan.cost || (cost == best.plan.cost && patch.skipped < skipped)
+                }
+                _ => true,
+            });
+            if better {
+                best = Some(RepairedPlan {
+                    plan: self.assemble(&start, &resolved, repaired, Some(bridge.stats)),
+                    repair: PlanRepair::Patched {
+                        skipped: patch.skipped,
+                        at: patch.at,
+                        inserted,
+                    },
+                });
+            }
+        }
+        if let Some(best) = best {
+            return Ok(best);
+        }
+
+        Ok(RepairedPlan {
+            plan: self.plan(start, goal, actions)?,
+            repair: PlanRepair::Replanned,
+        })
+    }
+
+    /// Simulates the steps of `steps` from `skipped` on until one cannot be
+    /// performed, and returns the patch inserting a bridge there. Returns `None` if
+    /// the following steps cannot be regressed or the heuristic fails.
+    fn patch(&self, start: &State, goal: &Goal, steps: &[Action], skipped: usize) -> Option<Patch> {
+        let mut state = start.clone();
+        let mut at = skipped;
+        while let Some(next) = steps
+            .get(at)
+            .and_then(|action| self.perform(&state, action))
+        {
+            state = next;
+            at += 1;
+        }
+        let target = regress(&goal.desired_state, &steps[at..])?;
+        let estimate = step_cost(&steps[skipped..])
+            + self
+                .estimate(&state, &Goal::from_state(&goal.name, target.clone(), 0))
+                .ok()?;
+        Some(Patch {
+            skipped,
+            at,
+            state,
+            target,
+            estimate,
+        })
+    }
+
+    /// Searches for a bridge from `state` to `goal` within `node_budget` node
+    /// expansions, returning the bridge, if one was found, and the expansions used.
+    fn bridge(
+        &self,
+        state: State,
+        goal: &Goal,
+        actions: &[Action],
+        node_budget: usize,
+    ) -> Result<(Option<Plan>, usize), PlannerError> {
+        let dead_ends = self.dead_end_analysis(goal, actions);
+        let mut observer = NoopObserver;
+        let mut search = Search::new(self, goal, actions, dead_ends.as_ref(), &mut observer);
+        search.node_budget = Some(node_budget);
+        search.push_start(state)?;
+        match search.run() {
+            Ok(plan) => {
+                let expanded = plan.stats.nodes_expanded;
+                Ok((Some(plan), expanded))
+            }
+            Err(PlannerError::NoPlanFound) => Ok((None, search.stats.nodes_expanded)),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Performs `action` from `state` as the search would expand it, returning the
+    /// state reached by its first prepared variant that can run, or `None` if the
+    /// action is excluded or cannot run.
+    fn perform(&self, state: &State, action: &Action) -> Option<State> {
+        let variants = self.prepare_actions(std::slice::from_ref(action));
+        self.expand(state, &variants)
+            .next()
+            .map(|transition| transition.state)
+    }
+
+    /// Returns true if `steps` can be performed one after another from `start` and
+    /// achieve the goal.
+    fn achieves(&self, start: &State, goal: &Goal, steps: &[Action]) -> bool {
+        let mut state = start.clone();
+        for action in steps {
+            match self.perform(&state, action) {
+                Some(next) => state = next,
+                None => return false,
+            }
+        }
+        goal.is_satisfied(&state)
+    }
+
+    /// Builds the plan performing `steps` from `start`.
+    fn assemble(
+        &self,
+        start: &State,
+        goal: &Goal,
+        steps: Vec<Action>,
+        stats: Option<PlanStats>,
+    ) -> Plan {
+        Plan {
+            cost: step_cost(&steps),
+            stats: stats.unwrap_or_default(),
+            currencies: self.simulated_currency_changes(start, &steps),
+            assumptions: plan_assumptions(start, goal, &steps),
+            actions: steps,
+        }
+    }
+}
+
+/// Returns the summed cost of the actions.
+fn step_cost(steps: &[Action]) -> f64 {
+    steps.iter().map(|action| action.cost).sum()
+}
@@ -0,0 +1,37 @@
+//! A small deterministic random number generator.
+//!
+//! Randomised strategies need reproducible runs for debugging and tests, and the
+//! crate has no dependencies, so this implements SplitMix64.
+
+/// SplitMix64 pseudo-random number generator.
+#[derive(Clone, Debug)]
+pub(crate) struct Rng {
+    /// The internal state, advanced on every draw
+    state: u64,
+}
+
+impl Rng {
+    /// Creates a generator from a seed. Equal seeds produce equal sequences.
+    pub(crate) fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    /// Returns the next 64 random bits.
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a random number in `[0, 1)`.
+    pub(crate) fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns a random index below `bound`, which must be non-zero.
+    pub(crate) fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
@@ -0,0 +1,330 @@
+//! Scoring plans by how well they survive a perturbed world.
+//!
+//! The cheapest plan is often the most fragile one: it spends the last coin or
+//! relies on an action that sometimes fails. [`Plan::robustness`] replays a plan
+//! many times from copies of the initial state randomly changed by
+//! [`Perturbations`], optionally failing actions as it goes, and reports in a
+//! [`RobustnessReport`] how many runs still executed every step and achieved the
+//! goal. Comparing the reports of two plans shows whether a sturdier alternative
+//! is worth its extra cost.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let goal = Goal::new("fed").requires("fed", true).build();
+//! let cheap = Plan {
+//!     actions: vec![Action::new("buy_food").requires("gold", 10).sets("fed", true).build()],
+//!     ..Plan::default()
+//! };
+//! let sturdy = Plan {
+//!     actions: vec![Action::new("forage").cost(3.0).sets("fed", true).build()],
+//!     ..Plan::default()
+//! };
+//! let initial = State::new().set("gold", 12).set("fed", false).build();
+//! let perturbations = Perturbations::new(100).jitter("gold", -5, 5);
+//!
+//! let cheap = cheap.robustness(&initial, &goal, &perturbations);
+//! let sturdy = sturdy.robustness(&initial, &goal, &perturbations);
+//! assert!(cheap.ratio() < 1.0);
+//! assert_eq!(sturdy.ratio(), 1.0);
+//! ```
+
+use crate::goals::Goal;
+use crate::planner::Plan;
+use crate::rng::Rng;
+use crate::state::{IntoStateVar, State, StateVar};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// A random change to the initial state or to action outcomes.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Perturbation {
+    /// Shifts a number by a random amount between `min` and `max`, inclusive,
+    /// given in the variable's type. Variables of another type, or missing ones,
+    /// are left alone
+    Jitter {
+        /// The variable to shift
+        key: String,
+        /// The smallest shift
+        min: StateVar,
+        /// The largest shift
+        max: StateVar,
+    },
+    /// Replaces a variable with one of the values, picked uniformly
+    OneOf {
+        /// The variable to replace
+        key: String,
+        /// The values to pick from
+        values: Vec<StateVar>,
+    },
+    /// Negates a boolean with the given probability
+    Flip {
+        /// The variable to negate
+        key: String,
+        /// The probability of negating it
+        probability: f64,
+    },
+    /// Removes a variable with the given probability
+    Remove {
+        /// The variable to remove
+        key: String,
+        /// The probability of removing it
+        probability: f64,
+    },
+    /// Makes every execution of the named action fail with the given probability,
+    /// applying its failure effects instead of its effects
+    ActionFails {
+        /// The name of the action
+        action: String,
+        /// The probability of each execution failing
+        probability: f64,
+    },
+}
+
+/// How to perturb the runs of [`Plan::robustness`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Perturbations {
+    /// The perturbations, applied to the initial state in order
+    pub rules: Vec<Perturbation>,
+    /// The number of perturbed runs
+    pub runs: usize,
+    /// The seed of the random draws
+    pub seed: u64,
+}
+
+impl Perturbations {
+    /// Creates a specification for `runs` runs without perturbations.
+    pub fn new(runs: usize) -> Self {
+        Perturbations {
+            rules: Vec::new(),
+            runs,
+            seed: 0,
+        }
+    }
+
+    /// Seeds the random draws. Equal seeds give equal reports.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Adds a perturbation.
+    pub fn rule(mut self, rule: Perturbation) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Shifts a number by a random amount between `min` and `max`, inclusive.
+    pub fn jitter<T: IntoStateVar>(self, key: &str, min: T, max: T) -> Self {
+        self.rule(Perturbation::Jitter {
+            key: key.to_string(),
+            min: min.into_state_var(),
+            max: max.into_state_var(),
+        })
+    }
+
+    /// Replaces a variable with one of the values, picked uniformly.
+    pub fn one_of<T: IntoStateVar>(self, key: &str, values: impl IntoIterator<Item = T>) -> Self {
+        self.rule(Perturbation::OneOf {
+            key: key.to_string(),
+            values: values
+                .into_iter()
+                .map(IntoStateVar::into_state_var)
+                .collect(),
+        })
+    }
+
+    /// Negates a boolean with the given probability.
+    pub fn flip(self, key: &str, probability: f64) -> Self {
+        self.rule(Perturbation::Flip {
+            key: key.to_string(),
+            probability,
+        })
+    }
+
+    /// Removes a variable with the given probability.
+    pub fn remove(self, key: &str, probability: f64) -> Self {
+        self.rule(Perturbation::Remove {
+            key: key.to_string(),
+            probability,
+        })
+    }
+
+    /// Makes every execution of the named action fail with the given probability.
+    pub fn action_fails(self, action: &str, probability: f64) -> Self {
+        self.rule(Perturbation::ActionFails {
+            action: action.to_string(),
+            probability,
+        })
+    }
+
+    /// Returns a perturbed copy of the state.
+    fn perturb(&self, state: &State, rng: &mut Rng) -> State {
+        let mut state = state.clone();
+        for rule in &self.rules {
+            match rule {
+                Perturbation::Jitter { key, min, max } => {
+                    let shifted = match (state.vars.get(key), min, max) {
+                        (Some(StateVar::I64(value)), StateVar::I64(min), StateVar::I64(max)) => {
+                            Some(StateVar::I64(value.saturating_add(draw(rng, *min, *max))))
+                        }
+                        (Some(StateVar::F64(value)), StateVar::F64(min), StateVar::F64(max)) => {
+                            Some(StateVar::F64(value.saturating_add(draw(rng, *min, *max))))
+                        }
+                        _ => None,
+                    };
+                    if let Some(shifted) = shifted {
+                        state.vars.insert(key.clone(), shifted);
+                    }
+                }
+                Perturbation::OneOf { key, values } => {
+                    if !values.is_empty() {
+                        let value = values[rng.below(values.len())].clone();
+                        state.vars.insert(key.clone(), value);
+                    }
+                }
+                Perturbation::Flip { key, probability } => {
+                    if let Some(StateVar::Bool(value)) = state.vars.get(key).cloned()
+                        && rng.unit() < *probability
+                    {
+                        state.vars.insert(key.clone(), StateVar::Bool(!value));
+                    }
+                }
+                Perturbation::Remove { key, probability } => {
+                    if state.vars.contains_key(key) && rng.unit() < *probability {
+                        state.vars.remove(key);
+                    }
+                }
+                Perturbation::ActionFails { .. } => {}
+            }
+        }
+        state
+    }
+
+    /// Returns the probability that an execution of the named action fails.
+    fn failure_probability(&self, action: &str) -> f64 {
+        self.rules
+            .iter()
+            .filter_map(|rule| match rule {
+                Perturbation::ActionFails {
+                    action: name,
+                    probability,
+                } if name == action => Some(*probability),
+                _ => None,
+            })
+            .fold(0.0, |combined, probability| {
+                1.0 - (1.0 - combined) * (1.0 - probability.clamp(0.0, 1.0))
+            })
+    }
+}
+
+/// Draws a number between `min` and `max`, inclusive, in either order.
+fn draw(rng: &mut Rng, min: i64, max: i64) -> i64 {
+    let (low, high) = if min <= max { (min, max) } else { (max, min) };
+    let span = high.abs_diff(low);
+    if span == u64::MAX {
+        return rng.next_u64() as i64;
+    }
+    low.wrapping_add((rng.next_u64() % (span + 1)) as i64)
+}
+
+/// How a plan fared in perturbed runs, from [`Plan::robustness`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RobustnessReport {
+    /// The number of runs
+    pub runs: usize,
+    /// The number of runs that executed every step and achieved the goal
+    pub successes: usize,
+    /// The number of runs stopped by each step whose preconditions did not hold,
+    /// by step index
+    pub blocked: BTreeMap<usize, usize>,
+    /// The number of runs that executed every step without achieving the goal
+    pub goal_unmet: usize,
+}
+
+impl RobustnessReport {
+    /// Returns the share of runs that succeeded, from 0.0 to 1.0. A report
+    /// without runs counts as fully robust.
+    pub fn ratio(&self) -> f64 {
+        if self.runs == 0 {
+            return 1.0;
+        }
+        self.successes as f64 / self.runs as f64
+    }
+
+    /// Returns the step that stopped the most runs, if any run was stopped.
+    pub fn weakest_step(&self) -> Option<usize> {
+        self.blocked
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+            .map(|(step, _)| *step)
+    }
+}
+
+impl fmt::Display for RobustnessReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} runs succeeded ({:.0}%)",
+            self.successes,
+            self.runs,
+            self.ratio() * 100.0
+        )?;
+        for (step, count) in &self.blocked {
+            write!(f, "; {count} blocked at step {step}")?;
+        }
+        if self.goal_unmet > 0 {
+            write!(f, "; {} missed the goal", self.goal_unmet)?;
+        }
+        Ok(())
+    }
+}
+
+impl Plan {
+    /// Replays the plan from perturbed copies of `initial` and reports how many
+    /// runs executed every step and achieved the goal (see the
+    /// [`robustness`](crate::robustness) module).
+    ///
+    /// Relative requirements of the goal are resolved against each perturbed
+    /// initial state. A run stops at the first step whose preconditions do not hold. Failed
+    /// actions apply their failure effects, which leave the state unchanged for
+    /// actions that declare none, and the run continues.
+    pub fn robustness(
+        &self,
+        initial: &State,
+        goal: &Goal,
+        perturbations: &Perturbations,
+    ) -> RobustnessReport {
+        let failures: Vec<f64> = self
+            .actions
+            .iter()
+            .map(|action| perturbations.failure_probability(&action.name))
+            .collect();
+        let mut rng = Rng::new(perturbations.seed);
+        let mut report = RobustnessReport {
+            runs: perturbations.runs,
+            ..RobustnessReport::default()
+        };
+        'runs: for _ in 0..perturbations.runs {
+            let mut state = perturbations.perturb(initial, &mut rng);
+            let goal = goal.resolve(&state);
+            for (step, action) in self.actions.iter().enumerate() {
+                if !action.can_execute(&state) {
+                    *report.blocked.entry(step).or_default() += 1;
+                    continue 'runs;
+                }
+                state = if failures[step] > 0.0 && rng.unit() < failures[step] {
+                    action.apply_failure(&state)
+                } else {
+                    action.apply_effect(&state)
+                };
+            }
+            if goal.is_satisfied(&state) {
+                report.successes += 1;
+            } else {
+                report.goal_unmet += 1;
+            }
+        }
+        report
+    }
+}
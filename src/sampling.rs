@@ -0,0 +1,163 @@
+//! Sampling varied, near-optimal plans.
+//!
+//! An agent that always takes the cheapest plan behaves identically every time,
+//! which looks robotic when several plans cost about the same. With
+//! [`PlannerConfig::sampling`](crate::planner::PlannerConfig::sampling) set, the
+//! planner builds the plan step by step: at each state it computes, for every
+//! successor, the cost of the cheapest plan through it, and picks one of the
+//! successors whose plans are near-optimal with a softmax over their extra cost.
+//! The `temperature` controls how readily more expensive successors are picked,
+//! and the `tolerance` bounds the total extra cost: a sampled plan never costs
+//! more than `1 + tolerance` times the optimal plan, as measured by the
+//! heuristic-guided A* search.
+//!
+//! Sampling runs an A* search per successor at every step, so it is much slower
+//! than a single search and best suited to small domains. It replaces the A*
+//! search and annealing; the other strategies ignore it.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let actions = vec![
+//!     Action::new("take_left_path").sets("at_camp", true).cost(2.0).build(),
+//!     Action::new("take_right_path").sets("at_camp", true).cost(2.0).build(),
+//! ];
+//! let goal = Goal::new("reach_camp").requires("at_camp", true).build();
+//!
+//! let mut routes = std::collections::BTreeSet::new();
+//! for seed in 0..16 {
+//!     let config = PlannerConfig::new().sampling(SamplingConfig { seed, ..Default::default() });
+//!     let plan = Planner::with_config(config)
+//!         .plan(State::empty(), &goal, &actions)
+//!         .unwrap();
+//!     assert_eq!(plan.cost, 2.0);
+//!     routes.insert(plan.actions[0].name.clone());
+//! }
+//! assert_eq!(routes.len(), 2);
+//! ```
+
+use crate::actions::Action;
+use crate::goals::Goal;
+use crate::observer::PlannerObserver;
+use crate::planner::{Plan, PlanStats, Planner, PlannerError, Search, plan_assumptions};
+use crate::rng::Rng;
+use crate::state::State;
+use std::collections::HashSet;
+
+/// Settings for sampling varied, near-optimal plans.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SamplingConfig {
+    /// How readily successors with more expensive plans are picked; at zero the
+    /// cheapest successor is always taken
+    pub temperature: f64,
+    /// The extra cost allowed over the optimal plan, as a share of its cost
+    pub tolerance: f64,
+    /// Seed for the random numbers; equal seeds give equal plans, so vary it
+    /// between requests for variety
+    pub seed: u64,
+}
+
+impl Default for SamplingConfig {
+    fn default() -> Self {
+        SamplingConfig {
+            temperature: 1.0,
+            tolerance: 0.2,
+            seed: 0,
+        }
+    }
+}
+
+/// Slack absorbing rounding errors when comparing plan costs.
+const COST_EPSILON: f64 = 1e-9;
+
+impl Planner {
+    /// Builds a plan by sampling among near-optimal successors at every step.
+    pub(crate) fn plan_sampled(
+        &self,
+        initial_state: State,
+        goal: &Goal,
+        actions: &[Action],
+        observer: &mut dyn PlannerObserver,
+        settings: SamplingConfig,
+    ) -> Result<Plan, PlannerError> {
+        let mut stats = PlanStats::default();
+        let dead_ends = self.dead_end_analysis(goal, actions);
+        let mut cheapest = |state: &State| -> Result<Option<Plan>, PlannerError> {
+            let mut search = Search::new(self, goal, actions, dead_ends.as_ref(), &mut *observer);
+            search.push_start(state.clone())?;
+            let result = search.run();
+            stats.nodes_expanded += search.stats.nodes_expanded;
+            stats.nodes_generated += search.stats.nodes_generated;
+            stats.nodes_dominated += search.stats.nodes_dominated;
+            stats.dead_ends += search.stats.dead_ends;
+            stats.memory_bytes = stats.memory_bytes.max(search.stats.memory_bytes);
+            match result {
+                Ok(plan) => Ok(Some(plan)),
+                Err(PlannerError::NoPlanFound) => Ok(None),
+                Err(error) => Err(error),
+            }
+        };
+
+        let mut rest = cheapest(&initial_state)?.ok_or(PlannerError::NoPlanFound)?;
+        let mut slack = rest.cost * settings.tolerance.max(0.0);
+        let mut rng = Rng::new(settings.seed);
+        let mut state = initial_state.clone();
+        let mut visited = HashSet::from([initial_state.clone()]);
+        let mut path: Vec<Action> = Vec::new();
+
+        while !goal.is_satisfied(&state) {
+            // The cheapest plan through each unvisited successor
+            let mut candidates = Vec::new();
+            for transition in self.expand(&state, actions) {
+                if visited.contains(&transition.state) {
+                    continue;
+                }
+                if let Some(plan) = cheapest(&transition.state)? {
+                    let cost = transition.cost + plan.cost;
+                    candidates.push((transition.action, transition.state, plan, cost));
+                }
+            }
+            let Some(best) = candidates.iter().map(|c| c.3).min_by(f64::total_cmp) else {
+                // Every continuation revisits a state: finish with the cheapest plan
+                path.append(&mut rest.actions);
+                break;
+            };
+            candidates.retain(|candidate| candidate.3 - best <= slack + COST_EPSILON);
+
+            let pick = if settings.temperature > 0.0 {
+                let weights: Vec<f64> = candidates
+                    .iter()
+                    .map(|candidate| (-(candidate.3 - best) / settings.temperature).exp())
+                    .collect();
+                let mut target = rng.unit() * weights.iter().sum::<f64>();
+                weights
+                    .iter()
+                    .position(|weight| {
+                        target -= weight;
+                        target < 0.0
+                    })
+                    .unwrap_or(weights.len() - 1)
+            } else {
+                candidates
+                    .iter()
+                    .position(|candidate| candidate.3 == best)
+                    .unwrap_or(0)
+            };
+
+            let (action, next, plan, cost) = candidates.swap_remove(pick);
+            slack = (slack - (cost - best)).max(0.0);
+            path.push(action.clone());
+            visited.insert(next.clone());
+            state = next;
+            rest = plan;
+        }
+
+        Ok(Plan {
+            cost: path.iter().map(|action| action.cost).sum(),
+            stats,
+            currencies: self.simulated_currency_changes(&initial_state, &path),
+            assumptions: plan_assumptions(&initial_state, goal, &path),
+            actions: path,
+        })
+    }
+}
@@ -0,0 +1,227 @@
+//! Sharing a per-frame planning budget between many agents.
+//!
+//! A [`PlanningScheduler`] queues plan requests from any number of agents and
+//! works on them a frame at a time. Each call to [`PlanningScheduler::run_frame`]
+//! spends at most the configured number of node expansions, and optionally at
+//! most a time budget, on the queued requests in the order chosen by the
+//! [`SchedulingPolicy`]. Requests that do not finish keep their search progress
+//! (see [`PlanningSession`]) and continue in later frames.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let actions = vec![Action::new("work").adds("gold", 5).build()];
+//! let mut scheduler = PlanningScheduler::new(Planner::new(), 4);
+//! let rich = Goal::new("rich").requires("gold", 50).priority(1).build();
+//! let fed = Goal::new("fed").requires("gold", 5).priority(9).build();
+//! let start = State::new().set("gold", 0).build();
+//! let slow = scheduler.submit(rich, actions.clone(), &start).unwrap();
+//! let urgent = scheduler.submit(fed, actions, start).unwrap();
+//!
+//! // The higher-priority request is served first
+//! let finished = scheduler.run_frame();
+//! assert_eq!(finished[0].0, urgent);
+//! assert!(scheduler.is_pending(slow));
+//! ```
+
+use crate::actions::Action;
+use crate::clock::{Clock, StdClock};
+use crate::goals::Goal;
+use crate::planner::{Plan, Planner, PlannerError};
+use crate::session::{PlanningSession, SessionStatus};
+use crate::state::State;
+use std::borrow::Cow;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Identifies a request submitted to a [`PlanningScheduler`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RequestId(pub u64);
+
+/// The order in which a [`PlanningScheduler`] serves queued requests.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SchedulingPolicy {
+    /// Higher goal priorities first; requests waiting longer first among equal priorities
+    #[default]
+    GoalPriority,
+    /// Requests waiting longer first; higher goal priorities first among equally old requests
+    Staleness,
+}
+
+/// A queued plan request.
+struct Request {
+    /// The identifier handed out on submission
+    id: RequestId,
+    /// The priority of the requested goal
+    priority: u16,
+    /// The frame the request was submitted in
+    submitted: u64,
+    /// The resumable search for the request
+    session: PlanningSession,
+}
+
+/// Arbitrates a per-frame planning budget between plan requests from many agents.
+pub struct PlanningScheduler {
+    /// The planner whose configuration drives every search
+    planner: Planner,
+    /// Node expansions available per frame, shared by all requests
+    node_budget: usize,
+    /// Node expansions a single request may use per frame
+    slice: usize,
+    /// Time available per frame, if limited
+    time_budget: Option<Duration>,
+    /// The clock measuring the time budget
+    clock: Arc<dyn Clock>,
+    /// The order requests are served in
+    policy: SchedulingPolicy,
+    /// The queued requests, in submission order
+    requests: Vec<Request>,
+    /// The number of frames run so far
+    frame: u64,
+    /// The identifier of the next request
+    next_id: u64,
+}
+
+impl PlanningScheduler {
+    /// Creates a scheduler spending at most `node_budget` node expansions per frame.
+    /// A single request may use the whole budget unless [`slice`](Self::slice) limits it.
+    pub fn new(planner: Planner, node_budget: usize) -> Self {
+        PlanningScheduler {
+            planner,
+            node_budget,
+            slice: node_budget,
+            time_budget: None,
+            clock: Arc::new(StdClock::new()),
+            policy: SchedulingPolicy::default(),
+            requests: Vec::new(),
+            frame: 0,
+            next_id: 0,
+        }
+    }
+
+    /// Limits the node expansions a single request may use per frame, so that
+    /// one expensive request cannot take the whole budget.
+    pub fn slice(mut self, slice: usize) -> Self {
+        self.slice = slice;
+        self
+    }
+
+    /// Limits the time spent per frame. The clock is checked between requests, so
+    /// a frame may overrun the budget by the time one slice takes.
+    pub fn time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self
+    }
+
+    /// Sets the clock measuring the time budget. Defaults to [`StdClock`].
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Sets the order in which requests are served.
+    pub fn policy(mut self, policy: SchedulingPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Queues a request for a plan from `initial_state` to `goal`.
+    /// Returns an error if the problem fails the planner's up-front checks.
+    pub fn submit<'s>(
+        &mut self,
+        goal: Goal,
+        actions: Vec<Action>,
+        initial_state: impl Into<Cow<'s, State>>,
+    ) -> Result<RequestId, PlannerError> {
+        let priority = goal.priority;
+        let session = PlanningSession::new(self.planner.clone(), goal, actions, initial_state)?;
+        let id = RequestId(self.next_id);
+        self.next_id += 1;
+        self.requests.push(Request {
+            id,
+            priority,
+            submitted: self.frame,
+            session,
+        });
+        Ok(id)
+    }
+
+    /// Moves a queued request to a new current state, keeping whatever search
+    /// progress still applies. Returns false if the request is not queued.
+    pub fn update_state(&mut self, id: RequestId, state: State) -> Result<bool, PlannerError> {
+        match self.requests.iter_mut().find(|request| request.id == id) {
+            Some(request) => request.session.set_state(state).map(|()| true),
+            None => Ok(false),
+        }
+    }
+
+    /// Removes a queued request. Returns false if it was not queued.
+    pub fn cancel(&mut self, id: RequestId) -> bool {
+        let queued = self.requests.len();
+        self.requests.retain(|request| request.id != id);
+        self.requests.len() < queued
+    }
+
+    /// Returns true if the request is still queued.
+    pub fn is_pending(&self, id: RequestId) -> bool {
+        self.requests.iter().any(|request| request.id == id)
+    }
+
+    /// Returns the number of queued requests.
+    pub fn pending(&self) -> usize {
+        self.requests.len()
+    }
+
+    /// Returns the number of frames run so far.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Spends one frame's budget on the queued requests, in policy order.
+    ///
+    /// Returns the requests that finished during the frame with their plan or
+    /// error, in the order they were served. Requests that ran out of budget stay
+    /// queued and continue in the next frame.
+    pub fn run_frame(&mut self) -> Vec<(RequestId, Result<Plan, PlannerError>)> {
+        match self.policy {
+            SchedulingPolicy::GoalPriority => self.requests.sort_by(|a, b| {
+                b.priority
+                    .cmp(&a.priority)
+                    .then(a.submitted.cmp(&b.submitted))
+            }),
+            SchedulingPolicy::Staleness => self.requests.sort_by(|a, b| {
+                a.submitted
+                    .cmp(&b.submitted)
+                    .then(b.priority.cmp(&a.priority))
+            }),
+        }
+
+        let started = self.clock.now();
+        let mut remaining = self.node_budget;
+        let mut finished = Vec::new();
+        let mut index = 0;
+        while index < self.requests.len() && remaining > 0 {
+            if let Some(budget) = self.time_budget
+                && self.clock.now().saturating_sub(started) >= budget
+            {
+                break;
+            }
+            let request = &mut self.requests[index];
+            let before = request.session.nodes_expanded();
+            let status = request.session.resume(remaining.min(self.slice));
+            let used = request.session.nodes_expanded().saturating_sub(before);
+            remaining = remaining.saturating_sub(used);
+            match status {
+                SessionStatus::Searching => index += 1,
+                SessionStatus::Found(plan) => {
+                    finished.push((self.requests.remove(index).id, Ok(plan)));
+                }
+                SessionStatus::Failed(error) => {
+                    finished.push((self.requests.remove(index).id, Err(error)));
+                }
+            }
+        }
+        self.frame += 1;
+        finished
+    }
+}
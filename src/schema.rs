@@ -0,0 +1,288 @@
+//! Documentation metadata for state variables.
+//!
+//! A [`StateSchema`] describes variables with a description, a unit, an upper
+//! bound and a display category. It makes debug output readable by grouping
+//! variables by category and printing values with their units, e.g.
+//! `health: 75/100 HP`.
+//!
+//! Variables can also be declared [monotonic](KeyInfo::monotonic), never
+//! decreasing, like experience or reputation. Given the schema with
+//! [`PlannerConfig::schema`](crate::planner::PlannerConfig::schema), the planner
+//! never applies an action that would decrease such a variable, and treats states
+//! in which a monotonic variable has already become true against the goal's
+//! wishes as dead ends.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let schema = StateSchema::new()
+//!     .key("health", KeyInfo::new().unit("HP").maximum(100).category("vitals"))
+//!     .key("gold", KeyInfo::new().unit("gp").category("inventory"));
+//! let state = State::new().set("health", 75).set("gold", 20).build();
+//!
+//! assert_eq!(
+//!     schema.display_state(&state).to_string(),
+//!     "inventory:\n  gold: 20 gp\nvitals:\n  health: 75/100 HP\n"
+//! );
+//! ```
+
+use crate::events::{PlanEvent, key_changes};
+use crate::planner::Plan;
+use crate::state::{EnumKey, EnumStateVar, IntoStateVar, State, StateVar};
+use crate::units::QuantityScale;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Metadata describing one state variable.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct KeyInfo {
+    /// What the variable means
+    pub description: Option<String>,
+    /// The unit values are measured in, printed after the value
+    pub unit: Option<String>,
+    /// The largest meaningful value, printed after the value as `value/maximum`
+    pub maximum: Option<StateVar>,
+    /// The group the variable is listed under in debug output
+    pub category: Option<String>,
+    /// True if the variable never decreases: numbers only grow and booleans,
+    /// once true, stay true
+    pub monotonic: bool,
+    /// The name of the enum type the variable holds, if declared with
+    /// [`StateSchema::enum_key`]
+    pub enum_type: Option<String>,
+    /// How amounts are stored, if declared with [`StateSchema::quantity_key`];
+    /// values are printed as amounts of the unit
+    pub scale: Option<QuantityScale>,
+}
+
+impl KeyInfo {
+    /// Creates metadata with no fields set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the description.
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Sets the unit.
+    pub fn unit(mut self, unit: &str) -> Self {
+        self.unit = Some(unit.to_string());
+        self
+    }
+
+    /// Sets the largest meaningful value.
+    pub fn maximum<T: IntoStateVar>(mut self, maximum: T) -> Self {
+        self.maximum = Some(maximum.into_state_var());
+        self
+    }
+
+    /// Sets the display category.
+    pub fn category(mut self, category: &str) -> Self {
+        self.category = Some(category.to_string());
+        self
+    }
+
+    /// Declares that the variable never decreases, e.g. experience points or a
+    /// `has_met_king` flag.
+    pub fn monotonic(mut self) -> Self {
+        self.monotonic = true;
+        self
+    }
+
+    /// Formats a value with this variable's maximum and unit.
+    pub fn format_value(&self, value: &StateVar) -> String {
+        let amount = |value: &StateVar| match self.scale.and_then(|scale| scale.amount(value)) {
+            Some(amount) => amount.to_string(),
+            None => value.to_string(),
+        };
+        let mut text = amount(value);
+        if let Some(maximum) = &self.maximum {
+            text = format!("{text}/{}", amount(maximum));
+        }
+        if let Some(unit) = &self.unit {
+            text = format!("{text} {unit}");
+        }
+        text
+    }
+}
+
+/// Metadata for the variables of a domain.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StateSchema {
+    /// Metadata per variable
+    keys: HashMap<String, KeyInfo>,
+}
+
+impl StateSchema {
+    /// Creates an empty schema.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the metadata of a variable, replacing any already registered.
+    pub fn key(mut self, key: &str, info: KeyInfo) -> Self {
+        self.keys.insert(key.to_string(), info);
+        self
+    }
+
+    /// Sets the metadata of an enum variable, recording the enum type of its
+    /// [`EnumKey`] declaration.
+    pub fn enum_key<T: EnumStateVar>(self, key: EnumKey<T>, info: KeyInfo) -> Self {
+        let info = KeyInfo {
+            enum_type: Some(std::any::type_name::<T>().to_string()),
+            ..info
+        };
+        self.key(key.name(), info)
+    }
+
+    /// Returns the name of the enum type a variable holds, if it was registered
+    /// with [`StateSchema::enum_key`].
+    pub fn enum_type(&self, key: &str) -> Option<&str> {
+        self.info(key)?.enum_type.as_deref()
+    }
+
+    /// Returns the metadata of a variable, if registered.
+    pub fn info(&self, key: &str) -> Option<&KeyInfo> {
+        self.keys.get(key)
+    }
+
+    /// Returns the description of a variable, if registered.
+    pub fn description(&self, key: &str) -> Option<&str> {
+        self.info(key)?.description.as_deref()
+    }
+
+    /// Returns true if the variable is declared monotonic.
+    pub fn is_monotonic(&self, key: &str) -> bool {
+        self.info(key).is_some_and(|info| info.monotonic)
+    }
+
+    /// Returns a monotonic variable that is lower in `after` than in `before`,
+    /// if there is one. Booleans are lower when false, and variables missing from
+    /// either state are not compared.
+    pub fn decreased<'a>(&'a self, before: &State, after: &State) -> Option<&'a str> {
+        self.keys
+            .iter()
+            .filter(|(_, info)| info.monotonic)
+            .find(
+                |(key, _)| match (before.vars.get(*key), after.vars.get(*key)) {
+                    (Some(StateVar::I64(old)), Some(StateVar::I64(new))) => new < old,
+                    (Some(StateVar::F64(old)), Some(StateVar::F64(new))) => new < old,
+                    (Some(StateVar::Bool(old)), Some(StateVar::Bool(new))) => *old && !new,
+                    _ => false,
+                },
+            )
+            .map(|(key, _)| key.as_str())
+    }
+
+    /// Formats a value of a variable with its maximum and unit, if registered.
+    pub fn format_value(&self, key: &str, value: &StateVar) -> String {
+        match self.info(key) {
+            Some(info) => info.format_value(value),
+            None => value.to_string(),
+        }
+    }
+
+    /// Returns the category of a variable; unregistered and uncategorised
+    /// variables have none.
+    fn category(&self, key: &str) -> Option<&str> {
+        self.info(key)?.category.as_deref()
+    }
+
+    /// Sorts keys by category, listing uncategorised keys first, then by name.
+    fn sort_keys(&self, keys: &mut [&String]) {
+        keys.sort_by(|a, b| {
+            self.category(a)
+                .cmp(&self.category(b))
+                .then_with(|| a.cmp(b))
+        });
+    }
+
+    /// Returns a displayable view of a state, grouped by category with units.
+    pub fn display_state<'a>(&'a self, state: &'a State) -> StateDisplay<'a> {
+        StateDisplay {
+            schema: self,
+            state,
+        }
+    }
+
+    /// Returns a displayable view of a plan listing the changes each step is
+    /// predicted to make from `initial_state`, ordered by category with units.
+    pub fn display_plan<'a>(&'a self, plan: &'a Plan, initial_state: &'a State) -> PlanDisplay<'a> {
+        PlanDisplay {
+            schema: self,
+            plan,
+            initial_state,
+        }
+    }
+}
+
+/// A state formatted with a [`StateSchema`].
+pub struct StateDisplay<'a> {
+    /// The schema providing units and categories
+    schema: &'a StateSchema,
+    /// The state to format
+    state: &'a State,
+}
+
+impl fmt::Display for StateDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut keys: Vec<&String> = self.state.vars.keys().collect();
+        self.schema.sort_keys(&mut keys);
+        let mut current: Option<Option<&str>> = None;
+        for key in keys {
+            let category = self.schema.category(key);
+            if current != Some(category) {
+                if let Some(category) = category {
+                    writeln!(f, "{category}:")?;
+                }
+                current = Some(category);
+            }
+            let indent = if category.is_some() { "  " } else { "" };
+            writeln!(
+                f,
+                "{indent}{key}: {}",
+                self.schema.format_value(key, &self.state.vars[key])
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A plan formatted with a [`StateSchema`].
+pub struct PlanDisplay<'a> {
+    /// The schema providing units and categories
+    schema: &'a StateSchema,
+    /// The plan to format
+    plan: &'a Plan,
+    /// The state the plan starts from
+    initial_state: &'a State,
+}
+
+impl fmt::Display for PlanDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Plan (total cost: {:.1}):", self.plan.cost)?;
+        let mut state = self.initial_state.clone();
+        for (step, action) in self.plan.actions.iter().enumerate() {
+            writeln!(f, "Step {}: {}", step + 1, action.name)?;
+            let next = action.apply_effect(&state);
+            let changes = key_changes(&state, &next, step);
+            let mut keys: Vec<&String> = changes
+                .iter()
+                .map(|PlanEvent::KeyChanged { key, .. }| key)
+                .collect();
+            self.schema.sort_keys(&mut keys);
+            for key in keys {
+                let value = match next.vars.get(key) {
+                    Some(value) => self.schema.format_value(key, value),
+                    None => "unset".to_string(),
+                };
+                writeln!(f, "  - {key}: {value}")?;
+            }
+            state = next;
+        }
+        Ok(())
+    }
+}
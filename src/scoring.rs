@@ -0,0 +1,183 @@
+//! Evaluating plans that were not produced by the planner.
+//!
+//! [`score_plan`] replays a plan from an initial state and reports whether it is
+//! executable, what it costs under a [`CostModel`], how much of the goal it
+//! achieves and which [`Invariant`]s it breaks along the way. This lets scripted
+//! sequences, designer-authored cutscenes or suggestions from other systems be
+//! compared with planner output using the same rules.
+
+use crate::actions::Action;
+use crate::goals::Goal;
+use crate::planner::Plan;
+use crate::state::{State, value_satisfies};
+
+/// A condition that should hold in every state a plan passes through.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Invariant {
+    /// The name used when reporting violations
+    pub name: String,
+    /// The conditions the state must satisfy (using [`State::satisfies`])
+    pub condition: State,
+}
+
+impl Invariant {
+    /// Creates an invariant from a name and the conditions it requires.
+    pub fn new(name: &str, condition: State) -> Self {
+        Invariant {
+            name: name.to_string(),
+            condition,
+        }
+    }
+
+    /// Returns true if the state satisfies this invariant.
+    pub fn holds(&self, state: &State) -> bool {
+        state.satisfies(&self.condition)
+    }
+}
+
+/// The rules a plan is scored against: action costs and invariants.
+pub trait CostModel {
+    /// The cost of executing an action in the given state.
+    fn action_cost(&self, action: &Action, state: &State) -> f64;
+
+    /// Conditions that every state along the plan should satisfy.
+    fn invariants(&self) -> &[Invariant] {
+        &[]
+    }
+}
+
+/// A cost model that uses each action's own cost and has no invariants.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ActionCosts;
+
+impl CostModel for ActionCosts {
+    fn action_cost(&self, action: &Action, _state: &State) -> f64 {
+        action.cost
+    }
+}
+
+/// A cost model that uses each action's own cost and checks a list of invariants.
+#[derive(Clone, Debug, Default)]
+pub struct InvariantCosts {
+    /// The invariants to check
+    pub invariants: Vec<Invariant>,
+}
+
+impl CostModel for InvariantCosts {
+    fn action_cost(&self, action: &Action, _state: &State) -> f64 {
+        action.cost
+    }
+
+    fn invariants(&self) -> &[Invariant] {
+        &self.invariants
+    }
+}
+
+/// An invariant that did not hold at some point of a plan.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvariantViolation {
+    /// The number of steps executed when the violation was seen (0 is the initial state)
+    pub step: usize,
+    /// The name of the violated invariant
+    pub invariant: String,
+}
+
+/// The evaluation of a plan.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlanScore {
+    /// True if every action could be executed in order
+    pub valid: bool,
+    /// The index of the first action whose preconditions did not hold
+    pub first_invalid_step: Option<usize>,
+    /// The total cost of the actions that were executed
+    pub total_cost: f64,
+    /// True if the final state achieves the goal
+    pub goal_satisfied: bool,
+    /// The fraction of goal requirements met by the final state, from 0.0 to 1.0
+    pub goal_coverage: f64,
+    /// Every invariant violation seen while replaying the plan
+    pub invariant_violations: Vec<InvariantViolation>,
+    /// The state reached after the last executed action
+    pub final_state: State,
+}
+
+/// Replays a plan from `initial_state` and scores it against a goal and cost model.
+///
+/// Execution stops at the first action whose preconditions do not hold; the score
+/// then describes the state reached so far. Relative goal requirements are resolved
+/// against `initial_state`. Goal coverage counts required and excluded values alike.
+pub fn score_plan(
+    plan: &Plan,
+    initial_state: &State,
+    goal: &Goal,
+    cost_model: &dyn CostModel,
+) -> PlanScore {
+    let goal = goal.resolve(initial_state);
+    let mut state = initial_state.clone();
+    let mut total_cost = 0.0;
+    let mut first_invalid_step = None;
+    let mut invariant_violations = Vec::new();
+    check_invariants(cost_model, &state, 0, &mut invariant_violations);
+
+    for (index, action) in plan.actions.iter().enumerate() {
+        if !action.can_execute(&state) {
+            first_invalid_step = Some(index);
+            break;
+        }
+        total_cost += cost_model.action_cost(action, &state);
+        state = action.apply_effect(&state);
+        check_invariants(cost_model, &state, index + 1, &mut invariant_violations);
+    }
+
+    let requirements =
+        goal.desired_state.vars.len() + goal.excluded.vars.len() + goal.conditions.len();
+    let met = goal
+        .desired_state
+        .vars
+        .iter()
+        .filter(|(key, required)| {
+            state
+                .vars
+                .get(*key)
+                .is_some_and(|current| value_satisfies(current, required))
+        })
+        .count()
+        + goal
+            .excluded
+            .vars
+            .iter()
+            .filter(|(key, value)| state.vars.get(*key) != Some(value))
+            .count()
+        + (goal.conditions.len() - goal.unmet_conditions(&state));
+    let goal_coverage = if requirements == 0 {
+        1.0
+    } else {
+        met as f64 / requirements as f64
+    };
+
+    PlanScore {
+        valid: first_invalid_step.is_none(),
+        first_invalid_step,
+        total_cost,
+        goal_satisfied: goal.is_satisfied(&state),
+        goal_coverage,
+        invariant_violations,
+        final_state: state,
+    }
+}
+
+fn check_invariants(
+    cost_model: &dyn CostModel,
+    state: &State,
+    step: usize,
+    violations: &mut Vec<InvariantViolation>,
+) {
+    for invariant in cost_model.invariants() {
+        if !invariant.holds(state) {
+            violations.push(InvariantViolation {
+                step,
+                invariant: invariant.name.clone(),
+            });
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Combining the plans of goals pursued one after another.
+//!
+//! An agent working through a queue of goals, such as the active goals of a
+//! [`GoalArbiter`](crate::arbiter::GoalArbiter), often plans each goal from the
+//! same state. Performed back to back, the plans repeat work: both may begin with
+//! `goto_town`, or both may end with `return_home`. [`merge_sequential_plans`]
+//! concatenates the plans, leaving out leading steps a plan shares with an earlier
+//! plan and trailing steps an earlier plan shares with the next one, and records
+//! the step at which each goal becomes satisfied.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let initial_state = state! { at_town: false, at_home: true };
+//! let actions = [
+//!     Action::new("goto_town").requires("at_home", true)
+//!         .sets("at_town", true).sets("at_home", false).build(),
+//!     Action::new("buy_sword").requires("at_town", true).sets("has_sword", true).build(),
+//!     Action::new("buy_shield").requires("at_town", true).sets("has_shield", true).build(),
+//! ];
+//! let sword = Goal::new("sword").requires("has_sword", true).build();
+//! let shield = Goal::new("shield").requires("has_shield", true).build();
+//!
+//! let planner = Planner::new();
+//! let sword_plan = planner.plan(&initial_state, &sword, &actions).unwrap();
+//! let shield_plan = planner.plan(&initial_state, &shield, &actions).unwrap();
+//!
+//! let merged =
+//!     merge_sequential_plans(&initial_state, &[(&sword, &sword_plan), (&shield, &shield_plan)])
+//!         .unwrap();
+//! assert_eq!(merged.plan.display_compact().to_string(),
+//!     "Plan (total cost: 3.0): goto_town -> buy_sword -> buy_shield");
+//! assert_eq!(merged.markers[1], GoalMarker { goal: "shield".to_string(), step: 3 });
+//! assert_eq!(merged.removed_steps, 1);
+//! ```
+
+use crate::actions::Action;
+use crate::goals::Goal;
+use crate::planner::Plan;
+use crate::state::State;
+
+/// When a goal of a merged plan becomes satisfied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GoalMarker {
+    /// The name of the goal
+    pub goal: String,
+    /// The number of steps of the merged plan after which the goal is first
+    /// satisfied, no earlier than the marker of the goal before it; zero if it
+    /// already held in the initial state
+    pub step: usize,
+}
+
+/// Plans for goals pursued one after another, combined into one plan.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MergedPlan {
+    /// The combined plan, costing the sum of its actions' costs. Search statistics,
+    /// currencies and assumptions are left empty
+    pub plan: Plan,
+    /// One marker per goal, in the order the goals were given
+    pub markers: Vec<GoalMarker>,
+    /// The number of steps of the given plans left out of the combined plan
+    pub removed_steps: usize,
+}
+
+/// Combines the plans for several goals, to be pursued in the given order starting
+/// in `initial_state`, into one plan without repeated work.
+///
+/// Each plan is appended in turn, leaving out as many steps as possible while the
+/// combined plan stays valid: its leading steps shared with an earlier plan, the
+/// whole plan when its goal already holds, and trailing steps of the previous plan
+/// that the plan ends with as well, which then run once at its end. The combined
+/// plan is valid when every step's preconditions hold, simulated from
+/// `initial_state`, and each goal becomes satisfied, in order.
+///
+/// Relative requirements are resolved against `initial_state`. Returns `None` if a
+/// plan cannot be performed after the plans before it, even in full.
+pub fn merge_sequential_plans(
+    initial_state: &State,
+    plans: &[(&Goal, &Plan)],
+) -> Option<MergedPlan> {
+    let goals: Vec<Goal> = plans
+        .iter()
+        .map(|(goal, _)| goal.resolve(initial_state))
+        .collect();
+    let mut steps: Vec<Action> = Vec::new();
+    let mut markers = Vec::new();
+    let mut segment_start = 0;
+    for (index, (_, plan)) in plans.iter().enumerate() {
+        let prefix = plans[..index]
+            .iter()
+            .map(|(_, earlier)| shared_prefix(&earlier.actions, &plan.actions))
+            .max()
+            .unwrap_or(0);
+        let suffix = shared_suffix(&steps[segment_start..], &plan.actions);
+
+        // Try the largest reductions first; skipping the whole plan comes first.
+        // Trimmed steps run again at the end of this plan, so the plan must still
+        // perform them
+        let len = plan.actions.len();
+        let mut candidates: Vec<(usize, usize)> = (0..=prefix)
+            .chain([len])
+            .flat_map(|skipped| {
+                (0..=suffix.min(len - skipped)).map(move |trimmed| (trimmed, skipped))
+            })
+            .collect();
+        candidates.sort_by(|a, b| (b.0 + b.1).cmp(&(a.0 + a.1)).then(b.1.cmp(&a.1)));
+        candidates.dedup();
+
+        let (kept, candidate, found) = candidates.into_iter().find_map(|(trimmed, skipped)| {
+            let kept = steps.len() - trimmed;
+            let mut candidate = steps[..kept].to_vec();
+            candidate.extend_from_slice(&plan.actions[skipped..]);
+            let found = goal_markers(initial_state, &goals[..=index], &candidate)?;
+            Some((kept, candidate, found))
+        })?;
+        segment_start = kept;
+        steps = candidate;
+        markers = found;
+    }
+
+    let given: usize = plans.iter().map(|(_, plan)| plan.actions.len()).sum();
+    let plan = Plan {
+        cost: steps.iter().map(|action| action.cost).sum(),
+        actions: steps,
+        ..Plan::default()
+    };
+    let markers = goals
+        .iter()
+        .zip(markers)
+        .map(|(goal, step)| GoalMarker {
+            goal: goal.name.clone(),
+            step,
+        })
+        .collect();
+    Some(MergedPlan {
+        removed_steps: given - plan.actions.len(),
+        plan,
+        markers,
+    })
+}
+
+/// Simulates `steps` from `initial_state` and returns, for each goal in order, the
+/// first step count no earlier than the previous goal's at which it holds. Returns
+/// `None` if a step's preconditions fail or a goal is never reached.
+fn goal_markers(initial_state: &State, goals: &[Goal], steps: &[Action]) -> Option<Vec<usize>> {
+    let mut states = vec![initial_state.clone()];
+    for action in steps {
+        let current = states.last()?;
+        if !action.can_execute(current) {
+            return None;
+        }
+        states.push(action.apply_effect(current));
+    }
+    let mut from = 0;
+    goals
+        .iter()
+        .map(|goal| {
+            from += states[from..]
+                .iter()
+                .position(|state| goal.is_satisfied(state))?;
+            Some(from)
+        })
+        .collect()
+}
+
+/// Returns the number of leading actions of `a` and `b` with the same names.
+fn shared_prefix(a: &[Action], b: &[Action]) -> usize {
+    a.iter()
+        .zip(b)
+        .take_while(|(a, b)| a.name == b.name)
+        .count()
+}
+
+/// Returns the number of trailing actions of `a` and `b` with the same names.
+fn shared_suffix(a: &[Action], b: &[Action]) -> usize {
+    a.iter()
+        .rev()
+        .zip(b.iter().rev())
+        .take_while(|(a, b)| a.name == b.name)
+        .count()
+}
@@ -0,0 +1,453 @@
+//! Planning sessions that keep their search alive between ticks.
+//!
+//! Agents that replan towards the same goal every tick usually see only small
+//! state changes. A [`PlanningSession`] owns the search structures so that work
+//! can be spread over several ticks with [`PlanningSession::resume`], and so that
+//! a state update that lands on an already explored state reuses the explored
+//! subtree (re-rooted at the new state) instead of starting from scratch.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let actions = vec![Action::new("work").adds("gold", 5).build()];
+//! let goal = Goal::new("rich").requires("gold", 20).build();
+//! let initial = State::new().set("gold", 0).build();
+//!
+//! let mut session = PlanningSession::new(Planner::new(), goal, actions, initial).unwrap();
+//! let plan = loop {
+//!     match session.resume(2) {
+//!         SessionStatus::Found(plan) => break plan,
+//!         SessionStatus::Searching => continue, // next tick
+//!         SessionStatus::Failed(error) => panic!("{error}"),
+//!     }
+//! };
+//! assert_eq!(plan.actions.len(), 4);
+//! ```
+//!
+//! Between calls to `resume`, the paused search can be inspected and steered,
+//! e.g. for human-in-the-loop or learning-guided search experiments:
+//! [`PlanningSession::frontier`] lists the best states waiting to be expanded
+//! with their scores, [`PlanningSession::seed`] adds the states along a path of
+//! actions as if the search had found them, and
+//! [`PlanningSession::set_weight`] changes the weight of the heuristic.
+
+use crate::actions::Action;
+use crate::analysis::DeadEndAnalysis;
+use crate::dominance::dominance_signature;
+use crate::goals::Goal;
+use crate::observer::NoopObserver;
+use crate::planner::{
+    NodeWrapper, Plan, PlanStats, Planner, PlannerError, Search, SearchNode, SearchTree,
+    node_memory_bytes, plan_assumptions,
+};
+use crate::state::{State, StateOperation};
+use std::borrow::Cow;
+use std::collections::{BinaryHeap, HashMap};
+use std::mem::size_of;
+
+/// The outcome of resuming a planning session.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SessionStatus {
+    /// The node budget ran out before the search finished; resume again later
+    Searching,
+    /// A plan from the session's current state was found
+    Found(Plan),
+    /// The search finished without a plan, or failed
+    Failed(PlannerError),
+}
+
+/// A state waiting in the open set of a paused search.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrontierNode {
+    /// The state
+    pub state: State,
+    /// The cost of the cheapest known path from the session's state
+    pub g: f64,
+    /// The heuristic distance to the goal
+    pub h: f64,
+    /// The score the search orders states by: `g + weight * h`
+    pub f: f64,
+    /// The number of actions on the cheapest known path
+    pub depth: usize,
+}
+
+/// A search towards a fixed goal that persists across ticks.
+pub struct PlanningSession {
+    /// The planner whose configuration drives the search
+    planner: Planner,
+    /// The goal, with relative requirements resolved against the initial state
+    goal: Goal,
+    /// The available actions, completed according to the planner configuration
+    actions: Vec<Action>,
+    /// The goal's dead ends, when the planner prunes them
+    dead_ends: Option<DeadEndAnalysis>,
+    /// The state the current search is rooted at
+    state: State,
+    /// The persisted search data
+    tree: Option<SearchTree>,
+    /// The plan found from the current state, if any
+    plan: Option<Plan>,
+    /// The states the plan passes through, starting with the current state
+    path: Vec<State>,
+    /// Incremented every time the state changes
+    generation: u64,
+    /// The multiplier applied to the heuristic in f-scores
+    weight: f64,
+}
+
+impl PlanningSession {
+    /// Starts a session towards `goal` from `initial_state`.
+    /// No search work is done until [`PlanningSession::resume`] is called.
+    pub fn new<'s>(
+        planner: Planner,
+        goal: Goal,
+        actions: Vec<Action>,
+        initial_state: impl Into<Cow<'s, State>>,
+    ) -> Result<Self, PlannerError> {
+        let (initial_state, goal) = planner.prepare_start(initial_state.into().into_owned(), &goal);
+        let goal = goal.into_owned();
+        planner.check_problem(&initial_state, &goal, &actions)?;
+        let actions = planner.prepare_actions(&actions).into_owned();
+        let dead_ends = planner.dead_end_analysis(&goal, &actions);
+        let mut session = PlanningSession {
+            planner,
+            goal,
+            actions,
+            dead_ends,
+            state: initial_state,
+            tree: None,
+            plan: None,
+            path: Vec::new(),
+            generation: 0,
+            weight: 1.0,
+        };
+        session.restart()?;
+        Ok(session)
+    }
+
+    /// Returns the state the session is currently planning from.
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    /// Returns the goal being planned for.
+    pub fn goal(&self) -> &Goal {
+        &self.goal
+    }
+
+    /// Returns how many times the state has changed since the session started.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Returns how many nodes the search has expanded since it was last started
+    /// or re-rooted.
+    pub fn nodes_expanded(&self) -> usize {
+        self.tree
+            .as_ref()
+            .map_or(0, |tree| tree.stats.nodes_expanded)
+    }
+
+    /// Continues the search for at most `node_budget` more expansions.
+    /// Once a plan is found it is returned again without further search until the
+    /// state changes.
+    pub fn resume(&mut self, node_budget: usize) -> SessionStatus {
+        if let Some(plan) = &self.plan {
+            return SessionStatus::Found(plan.clone());
+        }
+        let Some(tree) = self.tree.take() else {
+            return SessionStatus::Failed(PlannerError::NoPlanFound);
+        };
+
+        let mut observer = NoopObserver;
+        let mut search = Search::from_tree(
+            &self.planner,
+            &self.goal,
+            &self.actions,
+            self.dead_ends.as_ref(),
+            &mut observer,
+            tree,
+        );
+        search.weight = self.weight;
+        search.node_budget = Some(search.stats.nodes_expanded + node_budget);
+        let status = match search.find_goal() {
+            Ok(node) => {
+                let plan = search.reconstruct(node);
+                self.path = plan_path(&self.state, &plan);
+                self.plan = Some(plan.clone());
+                SessionStatus::Found(plan)
+            }
+            Err(PlannerError::NoPlanFound) if search.budget_exhausted => SessionStatus::Searching,
+            Err(error) => SessionStatus::Failed(error),
+        };
+        self.tree = Some(search.into_tree());
+        status
+    }
+
+    /// Returns up to `count` states waiting to be expanded, in the order the search
+    /// would expand them. Empty once the search has run out of states.
+    pub fn frontier(&self, count: usize) -> Vec<FrontierNode> {
+        let Some(tree) = &self.tree else {
+            return Vec::new();
+        };
+        let mut listed = vec![false; tree.nodes.len()];
+        let mut frontier = Vec::new();
+        for entry in tree.open.clone().into_sorted_vec().into_iter().rev() {
+            let node = &tree.nodes[entry.node];
+            if frontier.len() >= count {
+                break;
+            }
+            if listed[entry.node] || node.expanded_g == Some(node.g) {
+                continue;
+            }
+            listed[entry.node] = true;
+            frontier.push(FrontierNode {
+                state: node.state.clone(),
+                g: node.g,
+                h: node.h,
+                f: node.g + self.weight * node.h,
+                depth: node.depth,
+            });
+        }
+        frontier
+    }
+
+    /// Adds the states along a path of actions, given by name, from the session's
+    /// state to the search, as if it had found them, so the next call to
+    /// [`PlanningSession::resume`] considers them. States the search prunes end
+    /// the path early.
+    ///
+    /// Returns [`PlannerError::InvalidSeed`] if an action is unknown or cannot be
+    /// applied where the path reaches it; the states before it are kept.
+    pub fn seed(&mut self, path: &[&str]) -> Result<(), PlannerError> {
+        let Some(tree) = self.tree.take() else {
+            return Ok(());
+        };
+        let root = tree.index.get(&self.state).copied();
+        let mut observer = NoopObserver;
+        let mut search = Search::from_tree(
+            &self.planner,
+            &self.goal,
+            &self.actions,
+            self.dead_ends.as_ref(),
+            &mut observer,
+            tree,
+        );
+        search.weight = self.weight;
+        let result = match root {
+            Some(root) => search.seed_path(root, path),
+            None => Ok(()),
+        };
+        self.tree = Some(search.into_tree());
+        result
+    }
+
+    /// Returns the weight of the heuristic in the search's f-scores.
+    pub fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    /// Sets the weight of the heuristic for the rest of the search, reordering the
+    /// states waiting to be expanded. Weights above 1.0 search more greedily and
+    /// may find more expensive plans; negative weights are treated as 0.0.
+    pub fn set_weight(&mut self, weight: f64) {
+        self.weight = weight.max(0.0);
+        if let Some(tree) = &mut self.tree {
+            reopen(tree, self.weight);
+        }
+    }
+
+    /// Applies state changes, as observed in the world, to the session's state.
+    pub fn apply_changes(
+        &mut self,
+        changes: &HashMap<String, StateOperation>,
+    ) -> Result<(), PlannerError> {
+        let mut state = self.state.clone();
+        state.apply(changes);
+        self.set_state(state)
+    }
+
+    /// Moves the session to a new current state.
+    ///
+    /// If the state lies on the plan already found, the remaining steps are kept.
+    /// Otherwise, if the state was explored by the search, the search continues from
+    /// the subtree below it; if not, the search starts over.
+    pub fn set_state(&mut self, mut state: State) -> Result<(), PlannerError> {
+        let comparisons = &self.planner.config().string_comparisons;
+        comparisons.canonical_state(&mut state);
+        if state == self.state {
+            return Ok(());
+        }
+        self.generation += 1;
+
+        let on_plan = self.path.iter().position(|visited| *visited == state);
+        let known = self
+            .tree
+            .as_ref()
+            .and_then(|tree| tree.index.get(&state).copied());
+        self.state = state;
+
+        match (on_plan, self.plan.take(), known) {
+            (Some(step), Some(plan), _) => {
+                let actions = plan.actions[step..].to_vec();
+                self.path.drain(..step);
+                let currencies = match self.path.last() {
+                    Some(end) => self.planner.currency_changes(&self.path[0], end),
+                    None => plan.currencies,
+                };
+                self.plan = Some(Plan {
+                    assumptions: plan_assumptions(&self.state, &self.goal, &actions),
+                    cost: actions.iter().map(|action| action.cost).sum(),
+                    actions,
+                    stats: plan.stats,
+                    currencies,
+                });
+                if let (Some(tree), Some(root)) = (self.tree.take(), known) {
+                    self.tree = Some(reroot(tree, root, self.weight));
+                }
+                Ok(())
+            }
+            (_, _, Some(root)) => {
+                self.path.clear();
+                self.tree = self.tree.take().map(|tree| reroot(tree, root, self.weight));
+                Ok(())
+            }
+            _ => {
+                self.path.clear();
+                self.restart()
+            }
+        }
+    }
+
+    /// Discards the search data and starts a new search from the current state.
+    fn restart(&mut self) -> Result<(), PlannerError> {
+        let mut observer = NoopObserver;
+        let mut search = Search::new(
+            &self.planner,
+            &self.goal,
+            &self.actions,
+            self.dead_ends.as_ref(),
+            &mut observer,
+        );
+        search.weight = self.weight;
+        search.push_start(self.state.clone())?;
+        self.tree = Some(search.into_tree());
+        Ok(())
+    }
+}
+
+/// Returns the states a plan passes through, starting with `start`.
+fn plan_path(start: &State, plan: &Plan) -> Vec<State> {
+    let mut path = vec![start.clone()];
+    for action in &plan.actions {
+        let next = action.apply_effect(path.last().unwrap_or(start));
+        path.push(next);
+    }
+    path
+}
+
+/// Rebuilds the open set with f-scores for the given heuristic weight, dropping
+/// duplicate and stale entries.
+fn reopen(tree: &mut SearchTree, weight: f64) {
+    let mut queued = vec![false; tree.nodes.len()];
+    for entry in std::mem::take(&mut tree.open).into_vec() {
+        let node = &tree.nodes[entry.node];
+        if queued[entry.node] || node.expanded_g == Some(node.g) {
+            continue;
+        }
+        queued[entry.node] = true;
+        tree.open.push(NodeWrapper {
+            node: entry.node,
+            f_score: node.g + weight * node.h,
+            tie_break: node.tie_break,
+        });
+    }
+}
+
+/// Keeps only the subtree below `root`, with costs measured from it.
+/// Every kept node is reopened with f-scores for the given heuristic weight;
+/// cached heuristic values are reused.
+fn reroot(tree: SearchTree, root: usize, weight: f64) -> SearchTree {
+    // Dominance groups are only kept while dominance pruning is enabled
+    let group_dominance = !tree.dominance.is_empty();
+    // Determine which nodes descend from the new root
+    let mut in_subtree: Vec<Option<bool>> = vec![None; tree.nodes.len()];
+    in_subtree[root] = Some(true);
+    for start in 0..tree.nodes.len() {
+        let mut chain = Vec::new();
+        let mut current = Some(start);
+        let result = loop {
+            match current {
+                None => break false,
+                Some(node) => {
+                    if let Some(known) = in_subtree[node] {
+                        break known;
+                    }
+                    chain.push(node);
+                    current = tree.nodes[node].parent;
+                }
+            }
+        };
+        for node in chain {
+            in_subtree[node] = Some(result);
+        }
+    }
+
+    let root_g = tree.nodes[root].g;
+    let root_depth = tree.nodes[root].depth;
+    let mut remap: Vec<Option<usize>> = vec![None; tree.nodes.len()];
+    let mut nodes = Vec::new();
+    for (old, node) in tree.nodes.into_iter().enumerate() {
+        if in_subtree[old] != Some(true) {
+            continue;
+        }
+        remap[old] = Some(nodes.len());
+        let is_root = old == root;
+        nodes.push(SearchNode {
+            parent: if is_root { None } else { node.parent },
+            action: if is_root { None } else { node.action },
+            g: node.g - root_g,
+            expanded_g: None,
+            depth: node.depth - root_depth,
+            ..node
+        });
+    }
+
+    let mut stats = PlanStats::default();
+    let mut index = HashMap::new();
+    let mut dominance: HashMap<State, Vec<usize>> = HashMap::new();
+    let mut open = BinaryHeap::new();
+    let mut best: Option<usize> = None;
+    for (new, node) in nodes.iter_mut().enumerate() {
+        node.parent = node.parent.and_then(|parent| remap[parent]);
+        stats.memory_bytes += node_memory_bytes(&node.state) + size_of::<NodeWrapper<usize>>();
+        index.insert(node.state.clone(), new);
+        if group_dominance {
+            let group = dominance.entry(dominance_signature(&node.state));
+            group.or_default().push(new);
+        }
+        open.push(NodeWrapper {
+            node: new,
+            f_score: node.g + weight * node.h,
+            tie_break: node.tie_break,
+        });
+    }
+    for (new, node) in nodes.iter().enumerate() {
+        let is_better = best.is_none_or(|best| {
+            let best = &nodes[best];
+            node.h < best.h || (node.h == best.h && node.g < best.g)
+        });
+        if is_better {
+            best = Some(new);
+        }
+    }
+
+    SearchTree {
+        nodes,
+        index,
+        dominance,
+        open,
+        stats,
+        best,
+    }
+}
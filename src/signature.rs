@@ -0,0 +1,246 @@
+//! Stable plan hashes and signatures for validating plans sent over a network.
+//!
+//! Games with an authoritative server can let clients plan locally and send the
+//! result to the server. [`Plan::content_hash`] digests a plan's content into a
+//! [`PlanDigest`] that is identical on every platform and build, so the server can
+//! compare a submitted plan with one it would accept. A client that must prove a
+//! plan came from the server (or the other way round) can carry an HMAC-SHA256
+//! signature made with [`Plan::sign`] under a shared secret and checked with
+//! [`Plan::verify_signature`]. Hashing and signing use the `sha2` and `hmac`
+//! crates and need the `signature` feature.
+//!
+//! The digest covers the plan's cost and, for each step in order, the action's
+//! name, cost, preconditions, conditions, effects and postconditions. Map keys
+//! are sorted and numbers written in a fixed byte order, so the digest does not
+//! depend on hash map iteration order, the `ordered-state` feature or the target.
+//! Expressions are covered by their text and dynamic effects by their keys only,
+//! since closures cannot be digested. Search statistics, currencies, assumptions,
+//! tags and other execution hints are not covered.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let actions = vec![Action::new("chop").sets("has_wood", true).build()];
+//! let goal = Goal::new("wood").requires("has_wood", true).build();
+//! let plan = Planner::new().plan(State::empty(), &goal, &actions).unwrap();
+//!
+//! // Client side: sign the plan with the shared secret
+//! let key = b"shared secret";
+//! let signature = plan.sign(key);
+//!
+//! // Server side: the same plan verifies, a tampered one does not
+//! assert!(plan.verify_signature(key, &signature));
+//! let mut tampered = plan.clone();
+//! tampered.cost = 0.0;
+//! assert!(!tampered.verify_signature(key, &signature));
+//! assert_ne!(tampered.content_hash(), plan.content_hash());
+//! ```
+
+use crate::actions::Action;
+use crate::planner::Plan;
+use crate::state::{State, StateOperation, StateVar};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+/// A SHA-256 digest of a plan, or an HMAC-SHA256 signature of one.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PlanDigest(pub [u8; 32]);
+
+impl PlanDigest {
+    /// Returns the bytes of the digest.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Parses a digest from 64 hexadecimal digits, as formatted by `Display`.
+    /// Returns `None` for any other text.
+    pub fn from_hex(text: &str) -> Option<Self> {
+        if text.len() != 64 || !text.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        let mut bytes = [0; 32];
+        for (byte, pair) in bytes.iter_mut().zip(text.as_bytes().chunks(2)) {
+            let pair = std::str::from_utf8(pair).ok()?;
+            *byte = u8::from_str_radix(pair, 16).ok()?;
+        }
+        Some(PlanDigest(bytes))
+    }
+}
+
+/// Formats the digest as 64 lowercase hexadecimal digits.
+impl fmt::Display for PlanDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+impl fmt::Debug for PlanDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PlanDigest({self})")
+    }
+}
+
+impl Plan {
+    /// Returns the SHA-256 digest of the plan's content, the same on every
+    /// platform. See the [module documentation](crate::signature) for what it
+    /// covers.
+    pub fn content_hash(&self) -> PlanDigest {
+        PlanDigest(Sha256::digest(self.canonical_bytes()).into())
+    }
+
+    /// Returns the HMAC-SHA256 signature of the plan's content under `key`.
+    pub fn sign(&self, key: &[u8]) -> PlanDigest {
+        PlanDigest(self.mac(key).finalize().into_bytes().into())
+    }
+
+    /// Returns true if `signature` is the signature of this plan under `key`. The
+    /// comparison runs in constant time, so it reveals nothing about where the
+    /// signatures differ.
+    pub fn verify_signature(&self, key: &[u8], signature: &PlanDigest) -> bool {
+        self.mac(key).verify_slice(signature.as_bytes()).is_ok()
+    }
+
+    /// Returns the HMAC-SHA256 of the plan's content under `key`, not yet finalized.
+    fn mac(&self, key: &[u8]) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+        mac.update(&self.canonical_bytes());
+        mac
+    }
+
+    /// Encodes the covered content of the plan as bytes.
+    fn canonical_bytes(&self) -> Vec<u8> {
+        let mut out = Encoder(b"goap-plan-v1".to_vec());
+        out.f64(self.cost);
+        out.len(self.actions.len());
+        for action in &self.actions {
+            out.action(action);
+        }
+        out.0
+    }
+}
+
+/// Writes values as length-prefixed, little-endian bytes.
+struct Encoder(Vec<u8>);
+
+impl Encoder {
+    fn len(&mut self, len: usize) {
+        self.0.extend_from_slice(&(len as u64).to_le_bytes());
+    }
+
+    fn f64(&mut self, value: f64) {
+        self.0.extend_from_slice(&value.to_bits().to_le_bytes());
+    }
+
+    fn str(&mut self, text: &str) {
+        self.len(text.len());
+        self.0.extend_from_slice(text.as_bytes());
+    }
+
+    fn strs<'a>(&mut self, texts: impl IntoIterator<Item = &'a str>) {
+        let mut texts: Vec<&str> = texts.into_iter().collect();
+        texts.sort_unstable();
+        self.len(texts.len());
+        texts.into_iter().for_each(|text| self.str(text));
+    }
+
+    fn value(&mut self, value: &StateVar) {
+        match value {
+            StateVar::Bool(value) => self.0.extend_from_slice(&[0, u8::from(*value)]),
+            StateVar::I64(value) => {
+                self.0.push(1);
+                self.0.extend_from_slice(&value.to_le_bytes());
+            }
+            StateVar::F64(value) => {
+                self.0.push(2);
+                self.0.extend_from_slice(&value.to_le_bytes());
+            }
+            StateVar::String(value) => {
+                self.0.push(3);
+                self.str(value);
+            }
+        }
+    }
+
+    fn state(&mut self, state: &State) {
+        let mut vars: Vec<(&String, &StateVar)> = state.vars.iter().collect();
+        vars.sort_by(|a, b| a.0.cmp(b.0));
+        self.len(vars.len());
+        for (key, value) in vars {
+            self.str(key);
+            self.value(value);
+        }
+    }
+
+    fn action(&mut self, action: &Action) {
+        self.str(&action.name);
+        self.f64(action.cost);
+        self.state(&action.preconditions);
+        self.strs(action.absent_preconditions.iter().map(String::as_str));
+
+        let mut groups: Vec<Vec<u8>> = action
+            .any_preconditions
+            .iter()
+            .map(|group| {
+                let mut alternatives: Vec<Vec<u8>> = group
+                    .iter()
+                    .map(|(key, value)| {
+                        let mut alternative = Encoder(Vec::new());
+                        alternative.str(key);
+                        alternative.value(value);
+                        alternative.0
+                    })
+                    .collect();
+                alternatives.sort_unstable();
+                let mut encoded = Encoder(Vec::new());
+                encoded.len(alternatives.len());
+                encoded.0.extend(alternatives.concat());
+                encoded.0
+            })
+            .collect();
+        groups.sort_unstable();
+        self.len(groups.len());
+        self.0.extend(groups.concat());
+
+        let conditions: Vec<String> = action
+            .conditions
+            .iter()
+            .map(|(key, condition)| format!("{key} {condition}"))
+            .collect();
+        self.strs(conditions.iter().map(String::as_str));
+        let expressions: Vec<String> = action
+            .expr_preconditions
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        self.strs(expressions.iter().map(String::as_str));
+
+        let mut effects: Vec<(&String, &StateOperation)> = action.effects.iter().collect();
+        effects.sort_by(|a, b| a.0.cmp(b.0));
+        self.len(effects.len());
+        for (key, operation) in effects {
+            self.str(key);
+            match operation {
+                StateOperation::Set(value) => {
+                    self.0.push(0);
+                    self.value(value);
+                }
+                StateOperation::Add(amount) => {
+                    self.0.push(1);
+                    self.0.extend_from_slice(&amount.to_le_bytes());
+                }
+                StateOperation::Subtract(amount) => {
+                    self.0.push(2);
+                    self.0.extend_from_slice(&amount.to_le_bytes());
+                }
+            }
+        }
+        self.strs(action.dynamic_effects.keys().map(String::as_str));
+        let postconditions: Vec<String> = action
+            .postconditions
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        self.strs(postconditions.iter().map(String::as_str));
+    }
+}
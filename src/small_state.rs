@@ -0,0 +1,295 @@
+//! Inline sorted storage backing [`State`](crate::state::State) with the
+//! `small-state` feature.
+//!
+//! Most agents have only a handful of state variables. With the feature
+//! enabled, a state stores its variables as `(key, value)` pairs sorted by key,
+//! inline for up to [`INLINE_CAPACITY`] variables and on the heap beyond that.
+//! Keys are reference-counted and shared between copies of a state, so cloning
+//! a small state allocates nothing and copies no key text, where a `HashMap`
+//! allocates a table and every key. Sorted storage also lets hashing and
+//! equality walk the variables in order without sorting the keys first, and
+//! iteration, `Display` and hashing follow key order as with the
+//! `ordered-state` feature.
+//!
+//! In the `state_storage_benchmark` example, against the `HashMap` storage,
+//! cloning was about 1.1x as fast at 4 and 8 variables and 1.8x at 16, hashing
+//! about 2x as fast, and `satisfies` level at 4 variables and up to 1.2x as fast
+//! beyond. Lookups are binary searches, so they lose to hashing as states grow:
+//! 32 lookups in a 32-variable state took 1.2x to 1.5x as long in the
+//! `state_map_bench` example, though its planning problem still ran about twice
+//! as fast. Inserting a new variable shifts the ones after it. Compare both on
+//! your machine:
+//!
+//! ```text
+//! cargo run --release --example state_storage_benchmark
+//! cargo run --release --example state_storage_benchmark --features small-state
+//! ```
+
+use crate::state::StateVar;
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::Index;
+use std::sync::Arc;
+
+/// Number of variables a state stores without heap-allocating its entry list.
+pub(crate) const INLINE_CAPACITY: usize = 16;
+
+/// A variable with its shared key. Slots hold `Option`s so that entries can be
+/// shifted in place; the `Arc` makes the option no larger than the entry.
+type Entry = (Arc<String>, StateVar);
+
+/// Storage for the sorted entries of a [`SmallMap`].
+// The inline variant is deliberately large: avoiding the heap is its purpose.
+#[allow(clippy::large_enum_variant)]
+enum Entries {
+    /// Up to INLINE_CAPACITY entries; the first `len` slots are occupied
+    Inline {
+        len: usize,
+        slots: [Option<Entry>; INLINE_CAPACITY],
+    },
+    /// Entries moved to the heap once the inline capacity was exceeded; every
+    /// slot is occupied
+    Heap(Vec<Option<Entry>>),
+}
+
+impl Clone for Entries {
+    /// Clones only the occupied slots; copying the keys only bumps their counts.
+    fn clone(&self) -> Self {
+        match self {
+            Entries::Inline { len, slots } => {
+                let mut cloned = [const { None }; INLINE_CAPACITY];
+                cloned[..*len].clone_from_slice(&slots[..*len]);
+                Entries::Inline {
+                    len: *len,
+                    slots: cloned,
+                }
+            }
+            Entries::Heap(slots) => Entries::Heap(slots.clone()),
+        }
+    }
+}
+
+/// A map from variable names to values, sorted by name, with inline capacity
+/// for small states.
+#[derive(Clone)]
+pub(crate) struct SmallMap {
+    /// The variables, sorted by key
+    entries: Entries,
+}
+
+impl SmallMap {
+    /// Creates an empty map storing its variables inline.
+    pub(crate) fn new() -> Self {
+        SmallMap {
+            entries: Entries::Inline {
+                len: 0,
+                slots: [const { None }; INLINE_CAPACITY],
+            },
+        }
+    }
+
+    /// Returns the occupied slots, in key order.
+    fn slots(&self) -> &[Option<Entry>] {
+        match &self.entries {
+            Entries::Inline { len, slots } => &slots[..*len],
+            Entries::Heap(slots) => slots,
+        }
+    }
+
+    /// Returns the occupied slots mutably, in key order.
+    fn slots_mut(&mut self) -> &mut [Option<Entry>] {
+        match &mut self.entries {
+            Entries::Inline { len, slots } => &mut slots[..*len],
+            Entries::Heap(slots) => slots,
+        }
+    }
+
+    /// Binary search for a key, returning its index or the insertion point.
+    fn search(&self, key: &str) -> Result<usize, usize> {
+        self.slots().binary_search_by(|slot| {
+            slot.as_ref()
+                .map_or(Ordering::Greater, |(entry, _)| entry.as_str().cmp(key))
+        })
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.slots().len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of entries stored on the heap, for memory estimates.
+    pub(crate) fn heap_capacity(&self) -> usize {
+        match &self.entries {
+            Entries::Inline { .. } => 0,
+            Entries::Heap(slots) => slots.capacity(),
+        }
+    }
+
+    pub(crate) fn get<K: AsRef<str> + ?Sized>(&self, key: &K) -> Option<&StateVar> {
+        let index = self.search(key.as_ref()).ok()?;
+        self.slots()[index].as_ref().map(|(_, value)| value)
+    }
+
+    pub(crate) fn contains_key<K: AsRef<str> + ?Sized>(&self, key: &K) -> bool {
+        self.search(key.as_ref()).is_ok()
+    }
+
+    /// Sets a variable, keeping the existing shared key if it is already set.
+    /// Returns the previous value.
+    pub(crate) fn insert(&mut self, key: String, value: StateVar) -> Option<StateVar> {
+        match self.search(&key) {
+            Ok(index) => self.slots_mut()[index]
+                .as_mut()
+                .map(|(_, current)| std::mem::replace(current, value)),
+            Err(index) => {
+                let entry = Some((Arc::new(key), value));
+                match &mut self.entries {
+                    Entries::Inline { len, slots } if *len < INLINE_CAPACITY => {
+                        slots[index..=*len].rotate_right(1);
+                        slots[index] = entry;
+                        *len += 1;
+                    }
+                    Entries::Inline { slots, .. } => {
+                        let mut spilled: Vec<Option<Entry>> =
+                            slots.iter_mut().map(Option::take).collect();
+                        spilled.insert(index, entry);
+                        self.entries = Entries::Heap(spilled);
+                    }
+                    Entries::Heap(slots) => slots.insert(index, entry),
+                }
+                None
+            }
+        }
+    }
+
+    pub(crate) fn remove<K: AsRef<str> + ?Sized>(&mut self, key: &K) -> Option<StateVar> {
+        let index = self.search(key.as_ref()).ok()?;
+        let entry = match &mut self.entries {
+            Entries::Inline { len, slots } => {
+                let entry = slots[index].take();
+                slots[index..*len].rotate_left(1);
+                *len -= 1;
+                entry
+            }
+            Entries::Heap(slots) => slots.remove(index),
+        };
+        entry.map(|(_, value)| value)
+    }
+
+    pub(crate) fn iter(&self) -> Iter<'_> {
+        Iter(self.slots().iter())
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = (&String, &mut StateVar)> {
+        self.slots_mut()
+            .iter_mut()
+            .filter_map(|slot| slot.as_mut().map(|(key, value)| (&**key, value)))
+    }
+
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &String> {
+        self.iter().map(|(key, _)| key)
+    }
+}
+
+impl Default for SmallMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartialEq for SmallMap {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for SmallMap {}
+
+impl fmt::Debug for SmallMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: AsRef<str> + ?Sized> Index<&K> for SmallMap {
+    type Output = StateVar;
+
+    fn index(&self, key: &K) -> &StateVar {
+        self.get(key).expect("state variable not found")
+    }
+}
+
+impl Extend<(String, StateVar)> for SmallMap {
+    fn extend<I: IntoIterator<Item = (String, StateVar)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl FromIterator<(String, StateVar)> for SmallMap {
+    fn from_iter<I: IntoIterator<Item = (String, StateVar)>>(iter: I) -> Self {
+        let mut map = SmallMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+/// An iterator over the variables of a [`SmallMap`], in key order.
+#[derive(Clone, Debug)]
+pub(crate) struct Iter<'a>(std::slice::Iter<'a, Option<Entry>>);
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a String, &'a StateVar);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()?.as_ref().map(|(key, value)| (&**key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a> IntoIterator for &'a SmallMap {
+    type Item = (&'a String, &'a StateVar);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An owning iterator over the variables of a [`SmallMap`], in key order.
+#[derive(Debug)]
+pub(crate) struct IntoIter(std::vec::IntoIter<Option<Entry>>);
+
+impl Iterator for IntoIter {
+    type Item = (String, StateVar);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0
+            .next()?
+            .map(|(key, value)| (Arc::unwrap_or_clone(key), value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl IntoIterator for SmallMap {
+    type Item = (String, StateVar);
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let slots = match self.entries {
+            Entries::Inline { len, slots } => slots.into_iter().take(len).collect(),
+            Entries::Heap(slots) => slots,
+        };
+        IntoIter(slots.into_iter())
+    }
+}
@@ -0,0 +1,135 @@
+//! Plan post-processing that merges runs of related steps.
+//!
+//! Plans over generated movement actions often contain several hops in a row.
+//! [`Plan::smoothed`] collapses each run of consecutive actions carrying a tag
+//! into a single composite step, so an executor receives one "move to C" step
+//! instead of every intermediate hop.
+
+use crate::actions::Action;
+use crate::planner::{Assumption, Plan};
+use crate::regression::regress;
+use crate::state::{State, StateOperation, apply_operation};
+use std::collections::HashMap;
+
+impl Plan {
+    /// Returns a copy of this plan where each run of two or more consecutive actions
+    /// tagged with `tag` is replaced by one composite action (see [`merge_actions`]).
+    /// Runs that cannot be merged exactly are left unchanged.
+    pub fn smoothed(&self, tag: &str) -> Plan {
+        let mut actions = Vec::new();
+        let mut steps = Vec::with_capacity(self.actions.len());
+        let mut run: Vec<Action> = Vec::new();
+        for action in &self.actions {
+            if action.has_tag(tag) {
+                run.push(action.clone());
+            } else {
+                flush_run(&mut run, &mut actions, &mut steps);
+                steps.push(actions.len());
+                actions.push(action.clone());
+            }
+        }
+        flush_run(&mut run, &mut actions, &mut steps);
+
+        // Assumptions hold until the step that now contains their original step
+        let assumptions = self
+            .assumptions
+            .iter()
+            .map(|(key, assumption)| {
+                let until = steps.get(assumption.until).copied();
+                let assumption = Assumption {
+                    value: assumption.value.clone(),
+                    until: until.unwrap_or(actions.len()),
+                };
+                (key.clone(), assumption)
+            })
+            .collect();
+        Plan {
+            actions,
+            cost: self.cost,
+            stats: self.stats.clone(),
+            currencies: self.currencies.clone(),
+            assumptions,
+        }
+    }
+}
+
+/// Moves a run of tagged actions into the output, merged when possible, and
+/// records the output index of each of its steps.
+fn flush_run(run: &mut Vec<Action>, output: &mut Vec<Action>, steps: &mut Vec<usize>) {
+    match merge_actions(run) {
+        Some(merged) if run.len() > 1 => {
+            steps.extend(std::iter::repeat_n(output.len(), run.len()));
+            output.push(merged);
+        }
+        _ => {
+            steps.extend(output.len()..output.len() + run.len());
+            output.append(run);
+        }
+    }
+    run.clear();
+}
+
+/// Combines a sequence of actions into one action with the same overall behaviour.
+///
+/// The composite is named `{first}..{last}`, costs the sum of the parts, requires
+/// the weakest precondition of the sequence and applies the combined effects.
+/// It keeps the tags shared by every part. Returns `None` for an empty sequence or
+/// when the parts use expression, absence or condition preconditions, dynamic effects or
+/// postconditions, which cannot be combined exactly.
+pub fn merge_actions(actions: &[Action]) -> Option<Action> {
+    let (first, last) = (actions.first()?, actions.last()?);
+    if actions.iter().any(|action| {
+        !action.expr_preconditions.is_empty()
+            || !action.any_preconditions.is_empty()
+            || !action.absent_preconditions.is_empty()
+            || !action.conditions.is_empty()
+            || !action.dynamic_effects.is_empty()
+            || !action.postconditions.is_empty()
+    }) {
+        return None;
+    }
+
+    let preconditions = regress(&State::empty(), actions)?;
+    let mut effects: HashMap<String, StateOperation> = HashMap::new();
+    for action in actions {
+        for (key, operation) in &action.effects {
+            let combined = match effects.remove(key) {
+                Some(previous) => compose(previous, operation),
+                None => operation.clone(),
+            };
+            effects.insert(key.clone(), combined);
+        }
+    }
+
+    let name = if actions.len() == 1 {
+        first.name.clone()
+    } else {
+        format!("{}..{}", first.name, last.name)
+    };
+    let cost = actions.iter().map(|action| action.cost).sum();
+    let mut merged = Action::from_parts(&name, cost, preconditions, effects);
+    merged.tags = first
+        .tags
+        .iter()
+        .filter(|tag| actions.iter().all(|action| action.has_tag(tag)))
+        .cloned()
+        .collect();
+    Some(merged)
+}
+
+/// Combines two operations on the same variable applied one after the other.
+fn compose(first: StateOperation, second: &StateOperation) -> StateOperation {
+    match (first, second) {
+        (_, StateOperation::Set(value)) => StateOperation::Set(value.clone()),
+        (StateOperation::Set(value), operation) => {
+            let updated = apply_operation(Some(&value), operation).unwrap_or(value);
+            StateOperation::Set(updated)
+        }
+        (StateOperation::Add(a), StateOperation::Add(b)) => StateOperation::Add(a + b),
+        (StateOperation::Add(a), StateOperation::Subtract(b)) => StateOperation::Add(a - b),
+        (StateOperation::Subtract(a), StateOperation::Add(b)) => StateOperation::Subtract(a - b),
+        (StateOperation::Subtract(a), StateOperation::Subtract(b)) => {
+            StateOperation::Subtract(a + b)
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! Planning against a world state that other threads keep mutating.
+//!
+//! [`Planner::plan_shared`] copies a shared state under a read lock, plans on the
+//! copy without holding the lock, and then compares the copy with the shared state
+//! again so the caller knows whether the world moved on while planning.
+
+use crate::actions::Action;
+use crate::events::{PlanEvent, key_changes};
+use crate::goals::Goal;
+use crate::planner::{Plan, Planner, PlannerError};
+use crate::state::State;
+use std::sync::{PoisonError, RwLock};
+
+/// A plan made from a snapshot of a shared state.
+#[derive(Clone, Debug, Default)]
+pub struct SnapshotPlan {
+    /// The plan found from the snapshot
+    pub plan: Plan,
+    /// The state the plan was made from
+    pub snapshot: State,
+    /// Variables whose shared value differed from the snapshot when planning finished,
+    /// sorted by key
+    pub changed_keys: Vec<String>,
+}
+
+impl SnapshotPlan {
+    /// Returns true if the shared state changed while planning, in which case the
+    /// plan may no longer be valid.
+    pub fn is_stale(&self) -> bool {
+        !self.changed_keys.is_empty()
+    }
+}
+
+impl Planner {
+    /// Plans from a consistent snapshot of a state shared with other threads.
+    ///
+    /// The read lock is held only while copying the state, so writers are not blocked
+    /// by the search. When planning finishes the shared state is read again and the
+    /// variables that changed are reported in [`SnapshotPlan::changed_keys`]. A
+    /// poisoned lock is read anyway instead of panicking.
+    pub fn plan_shared(
+        &self,
+        shared: &RwLock<State>,
+        goal: &Goal,
+        actions: &[Action],
+    ) -> Result<SnapshotPlan, PlannerError> {
+        let snapshot = shared
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        let plan = self.plan(&snapshot, goal, actions)?;
+        let changed_keys = {
+            let current = shared.read().unwrap_or_else(PoisonError::into_inner);
+            key_changes(&snapshot, &current, 0)
+        }
+        .into_iter()
+        .map(|PlanEvent::KeyChanged { key, .. }| key)
+        .collect();
+        Ok(SnapshotPlan {
+            plan,
+            snapshot,
+            changed_keys,
+        })
+    }
+}
@@ -0,0 +1,117 @@
+//! Planning backends behind a common interface.
+//!
+//! Some domains are better served by other planning techniques than the built-in
+//! search: a SAT-based planner for long plans, an ILP solver for resource
+//! optimisation, or a remote planning service shared by many clients. A
+//! [`Solver`] abstracts the planning call so such backends produce the same
+//! [`Plan`] type as the built-in [`Planner`], which is the default implementation.
+//! A [`PlannerPipeline`](crate::pipeline::PlannerPipeline) can try a solver as one
+//! of its stages with [`PlannerPipeline::solver`](crate::pipeline::PlannerPipeline::solver).
+//!
+//! Plans from external backends are not trusted blindly: [`Solver::solve_checked`]
+//! replays the plan and reports [`PlannerError::SolverFailed`] if a step cannot be
+//! performed or the goal is not achieved.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! /// A backend that always proposes the same plan, standing in for a service.
+//! #[derive(Debug)]
+//! struct Scripted(Vec<Action>);
+//!
+//! impl Solver for Scripted {
+//!     fn name(&self) -> &str {
+//!         "scripted"
+//!     }
+//!
+//!     fn solve(&self, _: &State, _: &Goal, _: &[Action]) -> Result<Plan, PlannerError> {
+//!         let cost = self.0.iter().map(|action| action.cost).sum();
+//!         Ok(Plan { actions: self.0.clone(), cost, ..Plan::default() })
+//!     }
+//! }
+//!
+//! let chop = Action::new("chop").requires("has_axe", true).sets("has_wood", true).build();
+//! let goal = Goal::new("wood").requires("has_wood", true).build();
+//! let state = state! { has_axe: false };
+//!
+//! // The built-in planner finds no plan; the scripted one is rejected on replay
+//! let solvers: [&dyn Solver; 2] = [&Planner::new(), &Scripted(vec![chop.clone()])];
+//! for solver in solvers {
+//!     assert!(solver.solve_checked(&state, &goal, &[chop.clone()]).is_err());
+//! }
+//! ```
+
+use crate::actions::Action;
+use crate::goals::Goal;
+use crate::planner::{Plan, Planner, PlannerError};
+use crate::state::State;
+use std::fmt;
+
+/// A planning backend producing [`Plan`]s for goals.
+pub trait Solver: fmt::Debug + Send + Sync {
+    /// Returns a short name identifying the backend in errors and logs.
+    fn name(&self) -> &str;
+
+    /// Finds a plan achieving `goal` from `initial_state` with the given actions.
+    ///
+    /// Backends report their own failures, such as an unreachable service, with
+    /// [`PlannerError::SolverFailed`], and [`PlannerError::NoPlanFound`] when the
+    /// goal cannot be achieved.
+    fn solve(
+        &self,
+        initial_state: &State,
+        goal: &Goal,
+        actions: &[Action],
+    ) -> Result<Plan, PlannerError>;
+
+    /// Calls [`Solver::solve`] and replays the plan from `initial_state`, returning
+    /// [`PlannerError::SolverFailed`] if a step's preconditions do not hold or the
+    /// plan does not achieve the goal. Relative requirements are resolved against
+    /// `initial_state`.
+    fn solve_checked(
+        &self,
+        initial_state: &State,
+        goal: &Goal,
+        actions: &[Action],
+    ) -> Result<Plan, PlannerError> {
+        let plan = self.solve(initial_state, goal, actions)?;
+        let failed = |reason: String| PlannerError::SolverFailed {
+            solver: self.name().to_string(),
+            reason,
+        };
+        let mut state = initial_state.clone();
+        for (step, action) in plan.actions.iter().enumerate() {
+            if !action.can_execute(&state) {
+                return Err(failed(format!(
+                    "step {} '{}' cannot be performed",
+                    step + 1,
+                    action.name
+                )));
+            }
+            state = action.apply_effect(&state);
+        }
+        if !goal.resolve(initial_state).is_satisfied(&state) {
+            return Err(failed(format!(
+                "the plan does not achieve goal '{}'",
+                goal.name
+            )));
+        }
+        Ok(plan)
+    }
+}
+
+/// The built-in search, as configured.
+impl Solver for Planner {
+    fn name(&self) -> &str {
+        "planner"
+    }
+
+    fn solve(
+        &self,
+        initial_state: &State,
+        goal: &Goal,
+        actions: &[Action],
+    ) -> Result<Plan, PlannerError> {
+        self.plan(initial_state, goal, actions)
+    }
+}
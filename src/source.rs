@@ -0,0 +1,200 @@
+//! Reading planning states from external storage such as ECS worlds.
+//!
+//! Games usually keep the facts an agent plans over in their own storage, often
+//! hundreds of components in an ECS world. Copying all of them into a [`State`]
+//! for every agent every tick wastes most of the work: a domain only mentions a
+//! handful of variables. A [`StateSource`] answers lookups of single variables,
+//! and [`Planner::plan_from_source`] reads only the variables the goal and
+//! actions declare (see [`domain_keys`]) before planning.
+//!
+//! States, maps of variables and closures are sources. [`KeyReaders`] turns any
+//! world type into one by registering a reader per variable, and with the `bevy`
+//! feature `KeyReaders::component` reads Bevy components directly.
+//!
+//! ```
+//! use goap::prelude::*;
+//! use std::collections::HashMap;
+//!
+//! struct World {
+//!     gold: HashMap<u32, i64>,
+//! }
+//!
+//! let readers = KeyReaders::new().key("gold", |world: &World, entity: u32| {
+//!     world.gold.get(&entity).map(|gold| StateVar::I64(*gold))
+//! });
+//! let world = World { gold: HashMap::from([(7, 30)]) };
+//!
+//! let goal = Goal::new("rich").requires("gold", 50).build();
+//! let actions = vec![Action::new("mine").adds("gold", 10).build()];
+//! let plan = Planner::new()
+//!     .plan_from_source(&readers.source(&world, 7), &goal, &actions)
+//!     .unwrap();
+//! assert_eq!(plan.actions.len(), 2);
+//! ```
+
+use crate::actions::Action;
+use crate::clusters::{changed_keys, goal_keys, read_keys};
+use crate::goals::Goal;
+use crate::planner::{Plan, Planner, PlannerError};
+use crate::state::{State, StateVar};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::hash::BuildHasher;
+
+/// Storage that can be read as a planning state one variable at a time.
+pub trait StateSource {
+    /// Returns the value of the variable, or `None` if the source does not have it.
+    fn read(&self, key: &str) -> Option<StateVar>;
+}
+
+impl StateSource for State {
+    fn read(&self, key: &str) -> Option<StateVar> {
+        self.vars.get(key).cloned()
+    }
+}
+
+impl<S: BuildHasher> StateSource for HashMap<String, StateVar, S> {
+    fn read(&self, key: &str) -> Option<StateVar> {
+        self.get(key).cloned()
+    }
+}
+
+impl StateSource for BTreeMap<String, StateVar> {
+    fn read(&self, key: &str) -> Option<StateVar> {
+        self.get(key).cloned()
+    }
+}
+
+impl<F: Fn(&str) -> Option<StateVar>> StateSource for F {
+    fn read(&self, key: &str) -> Option<StateVar> {
+        self(key)
+    }
+}
+
+/// A reader of one variable of an entity in a world.
+pub type KeyReader<W, E> = dyn Fn(&W, E) -> Option<StateVar> + Send + Sync;
+
+/// Readers of variables of entities in a world of type `W`, whose entities are
+/// identified by `E`.
+pub struct KeyReaders<W: ?Sized, E> {
+    /// The reader of each variable
+    readers: HashMap<String, Box<KeyReader<W, E>>>,
+}
+
+impl<W: ?Sized, E> Default for KeyReaders<W, E> {
+    fn default() -> Self {
+        KeyReaders {
+            readers: HashMap::new(),
+        }
+    }
+}
+
+impl<W: ?Sized, E: Copy> KeyReaders<W, E> {
+    /// Creates a set of readers without variables.
+    pub fn new() -> Self {
+        KeyReaders::default()
+    }
+
+    /// Reads the variable with `read`, replacing any earlier reader of it.
+    pub fn key(
+        mut self,
+        key: &str,
+        read: impl Fn(&W, E) -> Option<StateVar> + Send + Sync + 'static,
+    ) -> Self {
+        self.readers.insert(key.to_string(), Box::new(read));
+        self
+    }
+
+    /// Returns the variables with a reader, sorted.
+    pub fn keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self.readers.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    /// Returns a source reading the variables of `entity` in `world`.
+    pub fn source<'a>(&'a self, world: &'a W, entity: E) -> EntitySource<'a, W, E> {
+        EntitySource {
+            readers: self,
+            world,
+            entity,
+        }
+    }
+}
+
+#[cfg(feature = "bevy")]
+impl KeyReaders<bevy_ecs::world::World, bevy_ecs::entity::Entity> {
+    /// Reads the variable from the entity's `C` component, if it has one.
+    pub fn component<C, T>(self, key: &str, read: impl Fn(&C) -> T + Send + Sync + 'static) -> Self
+    where
+        C: bevy_ecs::component::Component,
+        T: crate::state::IntoStateVar,
+    {
+        self.key(key, move |world, entity| {
+            world
+                .get::<C>(entity)
+                .map(|component| read(component).into_state_var())
+        })
+    }
+}
+
+/// The variables of one entity in a world, read through [`KeyReaders`].
+pub struct EntitySource<'a, W: ?Sized, E> {
+    /// The readers of the variables
+    readers: &'a KeyReaders<W, E>,
+    /// The world the entity lives in
+    world: &'a W,
+    /// The entity whose variables are read
+    entity: E,
+}
+
+impl<W: ?Sized, E: Copy> StateSource for EntitySource<'_, W, E> {
+    fn read(&self, key: &str) -> Option<StateVar> {
+        (self.readers.readers.get(key)?)(self.world, self.entity)
+    }
+}
+
+/// Returns the variables a goal and its actions mention: the goal's requirements
+/// and every variable an action reads or changes, sorted.
+///
+/// Variables read only by the closures of dynamic effects cannot be seen, and are
+/// not included.
+pub fn domain_keys(goal: &Goal, actions: &[Action]) -> BTreeSet<String> {
+    let mut keys = goal_keys(goal);
+    for action in actions {
+        keys.extend(read_keys(action).into_iter().map(str::to_string));
+        keys.extend(changed_keys(action).cloned());
+    }
+    keys
+}
+
+impl State {
+    /// Reads the given variables from `source` into a state, skipping the ones the
+    /// source does not have.
+    pub fn from_source<K: AsRef<str>>(
+        source: &(impl StateSource + ?Sized),
+        keys: impl IntoIterator<Item = K>,
+    ) -> State {
+        let mut state = State::empty();
+        for key in keys {
+            let key = key.as_ref();
+            if let Some(value) = source.read(key) {
+                state.vars.insert(key.to_string(), value);
+            }
+        }
+        state
+    }
+}
+
+impl Planner {
+    /// Plans from a state read out of `source`, reading only the variables in
+    /// [`domain_keys`].
+    pub fn plan_from_source(
+        &self,
+        source: &(impl StateSource + ?Sized),
+        goal: &Goal,
+        actions: &[Action],
+    ) -> Result<Plan, PlannerError> {
+        let state = State::from_source(source, domain_keys(goal, actions));
+        self.plan(state, goal, actions)
+    }
+}
@@ -1,7 +1,12 @@
+use std::borrow::Cow;
+#[cfg(all(feature = "ordered-state", not(feature = "small-state")))]
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
 use std::hash::Hash;
+use std::marker::PhantomData;
+use std::sync::Arc;
 
 /// Errors that can occur when working with state variables.
 #[derive(Debug, PartialEq, Eq)]
@@ -25,22 +30,142 @@ impl fmt::Display for StateError {
 
 impl Error for StateError {}
 
+/// Errors that can occur when converting numbers to or from state variables.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConversionError {
+    /// The floating-point value is NaN or infinite
+    NotFinite,
+    /// The value does not fit in the target representation
+    OutOfRange {
+        /// The value that was converted, formatted for display
+        value: String,
+        /// The representation it was converted to
+        target: &'static str,
+    },
+    /// The state variable holds a different type
+    WrongType {
+        /// The type that was requested
+        expected: &'static str,
+        /// The type of the state variable
+        found: &'static str,
+    },
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConversionError::NotFinite => write!(f, "Value is NaN or infinite"),
+            ConversionError::OutOfRange { value, target } => {
+                write!(f, "Value {value} does not fit in {target}")
+            }
+            ConversionError::WrongType { expected, found } => {
+                write!(f, "Expected {expected}, found {found}")
+            }
+        }
+    }
+}
+
+impl Error for ConversionError {}
+
+/// The map holding the variables of a [`State`].
+///
+/// A `HashMap` by default. With the `ordered-state` feature it is a `BTreeMap`,
+/// so iteration, `Display` and hashing follow key order without sorting. The
+/// ordered map has slower lookups but hashes states faster, since the keys need
+/// no sorting; which wins for planning depends on the domain, so measure with the
+/// `state_map_bench` example. Choose the ordered map when deterministic iteration
+/// matters, e.g. for serialization or replays. With the `small-state` feature it
+/// is a sorted array stored inline for small states.
+///
+/// The map is private to the crate, so enabling the feature changes no public
+/// type: states are read through [`State::iter`] and the other accessors.
+#[cfg(not(any(feature = "ordered-state", feature = "small-state")))]
+pub(crate) type StateMap = HashMap<String, StateVar>;
+
+/// The map holding the variables of a [`State`].
+///
+/// A `BTreeMap`, because the `ordered-state` feature is enabled, so iteration,
+/// `Display` and hashing follow key order.
+#[cfg(all(feature = "ordered-state", not(feature = "small-state")))]
+pub(crate) type StateMap = BTreeMap<String, StateVar>;
+
+/// The map holding the variables of a [`State`].
+///
+/// An inline sorted array, because the `small-state` feature is enabled; see
+/// [`crate::small_state`]. It is also ordered by key, so it takes precedence
+/// when `ordered-state` is enabled as well.
+#[cfg(feature = "small-state")]
+pub(crate) type StateMap = crate::small_state::SmallMap;
+
 /// Represents the state of the world as a collection of named variables.
 /// Each variable has a name (string key) and a typed value (StateVar).
 /// States are used to represent the current world state, goal states, and action preconditions.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct State {
     /// The variables that make up this state, indexed by name
-    pub vars: HashMap<String, StateVar>,
+    pub(crate) vars: StateMap,
 }
 
+/// An iterator over the variables of a [`State`], returned by [`State::iter`].
+///
+/// Variables come in arbitrary order, or in key order with the `ordered-state`
+/// or `small-state` feature.
+#[derive(Clone, Debug)]
+pub struct Iter<'a>(<&'a StateMap as IntoIterator>::IntoIter);
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a String, &'a StateVar);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+/// An owning iterator over the variables of a [`State`].
+///
+/// Variables come in arbitrary order, or in key order with the `ordered-state`
+/// or `small-state` feature.
+#[derive(Debug)]
+pub struct IntoIter(<StateMap as IntoIterator>::IntoIter);
+
+impl Iterator for IntoIter {
+    type Item = (String, StateVar);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl ExactSizeIterator for IntoIter {}
+
+/// Lists the variables one per line, or on a single line such as
+/// `{gold: 120, has_wood: true}` with the alternate flag (`{:#}`).
 impl fmt::Display for State {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.vars.is_empty() {
+        if f.alternate() {
+            write!(f, "{{")?;
+            for (index, (key, value)) in sorted_entries(&self.vars).into_iter().enumerate() {
+                if index > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{key}: {value}")?;
+            }
+            write!(f, "}}")?;
+        } else if self.vars.is_empty() {
             write!(f, "empty state")?;
         } else {
             writeln!(f, "State:")?;
-            for (key, value) in &self.vars {
+            for (key, value) in sorted_entries(&self.vars) {
                 writeln!(f, "  - {key}: {value}")?;
             }
         }
@@ -48,15 +173,47 @@ impl fmt::Display for State {
     }
 }
 
+/// Returns the entries of a map sorted by key, for deterministic output.
+pub(crate) fn sorted_entries<'a, V>(
+    map: impl IntoIterator<Item = (&'a String, &'a V)>,
+) -> Vec<(&'a String, &'a V)> {
+    let mut entries: Vec<(&String, &V)> = map.into_iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+/// Formats a value on a single line, using the alternate form of its `Display`.
+///
+/// Returned by `display_compact` on [`State`],
+/// [`Action`](crate::actions::Action) and [`Plan`](crate::planner::Plan), for
+/// logs where multi-line output gets in the way.
+#[derive(Clone, Copy, Debug)]
+pub struct CompactDisplay<'a, T: ?Sized>(&'a T);
+
+impl<'a, T: ?Sized> CompactDisplay<'a, T> {
+    /// Wraps a value to be formatted on a single line.
+    pub fn new(value: &'a T) -> Self {
+        CompactDisplay(value)
+    }
+}
+
+impl<T: fmt::Display + ?Sized> fmt::Display for CompactDisplay<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#}", self.0)
+    }
+}
+
 // Manual Hash implementation for WorldState
 impl Hash for State {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        // Sort keys to ensure consistent hashing
-        let mut keys: Vec<_> = self.vars.keys().collect();
-        keys.sort();
-        for key in keys {
+        // Sort keys to ensure consistent hashing; an ordered map is already sorted
+        #[cfg(not(any(feature = "ordered-state", feature = "small-state")))]
+        let entries = sorted_entries(&self.vars);
+        #[cfg(any(feature = "ordered-state", feature = "small-state"))]
+        let entries = &self.vars;
+        for (key, value) in entries {
             key.hash(state);
-            self.vars.get(key).unwrap().hash(state);
+            value.hash(state);
         }
     }
 }
@@ -67,6 +224,115 @@ impl Default for State {
     }
 }
 
+/// Builds a state from `(key, value)` pairs, e.g. mapped from ECS component queries.
+/// Later pairs overwrite earlier ones with the same key.
+///
+/// ```
+/// use goap::prelude::*;
+///
+/// let health = [("player", 80), ("enemy", 35)];
+/// let state: State = health
+///     .iter()
+///     .map(|(name, hp)| (format!("{name}_health"), *hp))
+///     .collect();
+/// assert_eq!(state.get::<i64>("enemy_health"), Some(35));
+/// ```
+impl<K: Into<String>, V: IntoStateVar> FromIterator<(K, V)> for State {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut state = State::empty();
+        state.extend(iter);
+        state
+    }
+}
+
+/// Sets every `(key, value)` pair, overwriting existing values.
+impl<K: Into<String>, V: IntoStateVar> Extend<(K, V)> for State {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self.vars.extend(
+            iter.into_iter()
+                .map(|(key, value)| (key.into(), value.into_state_var())),
+        );
+    }
+}
+
+impl IntoIterator for State {
+    type Item = (String, StateVar);
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self.vars.into_iter())
+    }
+}
+
+impl<'a> IntoIterator for &'a State {
+    type Item = (&'a String, &'a StateVar);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Builds a state from a map of its variables, whatever map backs [`State`].
+impl From<HashMap<String, StateVar>> for State {
+    fn from(vars: HashMap<String, StateVar>) -> Self {
+        vars.into_iter().collect()
+    }
+}
+
+/// Collects the variables of a state into a `HashMap`, whatever map backs it.
+impl From<State> for HashMap<String, StateVar> {
+    fn from(state: State) -> Self {
+        state.into_iter().collect()
+    }
+}
+
+/// Builds a [`State`](crate::state::State) from `key: value` literals, a
+/// shorthand for [`State::from_pairs`](crate::state::State::from_pairs).
+///
+/// Keys are identifiers or string literals, and values are anything implementing
+/// [`IntoStateVar`](crate::state::IntoStateVar). Later keys overwrite earlier
+/// ones.
+///
+/// ```
+/// use goap::prelude::*;
+///
+/// let state = state! { gold: 100, has_axe: true, "home.location": "village" };
+/// assert_eq!(state.get::<i64>("gold"), Some(100));
+/// assert_eq!(state.get::<String>("home.location").as_deref(), Some("village"));
+/// assert_eq!(state! {}, State::empty());
+/// ```
+#[macro_export]
+macro_rules! state {
+    (@key $key:ident) => {
+        stringify!($key)
+    };
+    (@key $key:literal) => {
+        $key
+    };
+    ($($key:tt : $value:expr),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut state = $crate::state::State::empty();
+        $(state.set($crate::state!(@key $key), $value);)*
+        state
+    }};
+}
+
+/// Lets planning methods take the initial state owned, without a copy.
+impl From<State> for Cow<'_, State> {
+    fn from(state: State) -> Self {
+        Cow::Owned(state)
+    }
+}
+
+/// Lets planning methods take the initial state borrowed, copying it only when
+/// the search needs a state of its own.
+impl<'a> From<&'a State> for Cow<'a, State> {
+    fn from(state: &'a State) -> Self {
+        Cow::Borrowed(state)
+    }
+}
+
 impl State {
     /// Creates a new StateBuilder for constructing a state with the fluent interface.
     #[allow(clippy::new_ret_no_self)]
@@ -74,100 +340,130 @@ impl State {
         StateBuilder::new()
     }
 
+    /// Returns a view of the state formatting it on a single line with sorted keys,
+    /// e.g. `{gold: 120, has_wood: true}`; the same as formatting with `{:#}`.
+    pub fn display_compact(&self) -> CompactDisplay<'_, Self> {
+        CompactDisplay::new(self)
+    }
+
     /// Creates an empty state with no variables.
     pub fn empty() -> Self {
         State {
-            vars: HashMap::new(),
+            vars: StateMap::new(),
         }
     }
 
+    /// Creates a state from `(key, value)` pairs of values converted into
+    /// [`StateVar`]s. Later pairs overwrite earlier ones with the same key. The
+    /// [`state!`](crate::state!) macro builds the same state from literals.
+    ///
+    /// ```
+    /// use goap::prelude::*;
+    ///
+    /// let state = State::from_pairs([("gold", 100.into()), ("has_axe", true.into())]);
+    /// assert_eq!(state, State::new().set("gold", 100).set("has_axe", true).build());
+    /// ```
+    pub fn from_pairs<K: Into<String>>(pairs: impl IntoIterator<Item = (K, StateVar)>) -> Self {
+        pairs.into_iter().collect()
+    }
+
     /// Generic set method that accepts any type implementing IntoStateVar
     pub fn set<T: IntoStateVar>(&mut self, key: &str, value: T) {
         self.vars.insert(key.to_string(), value.into_state_var());
     }
 
+    /// Sets an enum variable declared with an [`EnumKey`].
+    pub fn set_enum<T: EnumStateVar>(&mut self, key: EnumKey<T>, value: T) {
+        self.set(key.name(), value);
+    }
+
     /// Primary get method with type inference - returns None if key doesn't exist or type doesn't match
     /// Usage: let x: Option<i32> = state.get("x");
     pub fn get<T>(&self, key: &str) -> Option<T>
     where
         T: TryFromStateVar,
     {
-        self.get_raw(key)
+        self.var(key)
             .and_then(|var| T::try_from_state_var(var, key).ok())
     }
 
-    /// Internal method to get raw StateVar - not exposed to library users
-    fn get_raw(&self, key: &str) -> Option<&StateVar> {
+    /// Returns the untyped value of a variable, or None if it is not set.
+    pub fn var(&self, key: &str) -> Option<&StateVar> {
         self.vars.get(key)
     }
 
+    /// Returns true if the variable is set.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.vars.contains_key(key)
+    }
+
+    /// Removes a variable, returning its value if it was set.
+    pub fn remove(&mut self, key: &str) -> Option<StateVar> {
+        self.vars.remove(key)
+    }
+
+    /// Returns the number of variables.
+    pub fn len(&self) -> usize {
+        self.vars.len()
+    }
+
+    /// Returns true if the state has no variables.
+    pub fn is_empty(&self) -> bool {
+        self.vars.is_empty()
+    }
+
+    /// Iterates over the variables and their values, in arbitrary order, or in key
+    /// order with the `ordered-state` or `small-state` feature.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter(self.vars.iter())
+    }
+
+    /// Iterates over the names of the variables, in the order of [`State::iter`].
+    pub fn keys(&self) -> impl Iterator<Item = &String> + '_ {
+        self.vars.keys()
+    }
+
     /// Checks if this state satisfies all the conditions in the given state.
     /// For boolean and string variables, values must match exactly.
     /// For numeric variables, this state's value must be >= the required value.
     pub fn satisfies(&self, conditions: &State) -> bool {
-        for (key, value) in &conditions.vars {
-            match self.vars.get(key) {
-                Some(current_value) => {
-                    match (current_value, value) {
-                        (StateVar::Bool(cur), StateVar::Bool(req)) => {
-                            if cur != req {
-                                return false;
-                            }
-                        }
-                        (StateVar::I64(cur), StateVar::I64(req)) => {
-                            if cur < req {
-                                return false;
-                            }
-                        }
-                        (StateVar::F64(cur), StateVar::F64(req)) => {
-                            if cur < req {
-                                return false;
-                            }
-                        }
-                        (StateVar::String(cur), StateVar::String(req)) => {
-                            if cur != req {
-                                return false;
-                            }
-                        }
-                        _ => return false, // Mismatched types
-                    }
-                }
-                None => return false,
-            }
-        }
-        true
+        conditions.vars.iter().all(|(key, required)| {
+            self.vars
+                .get(key)
+                .is_some_and(|current| value_satisfies(current, required))
+        })
+    }
+
+    /// Checks that every variable named in `conditions` meets its [`Condition`].
+    ///
+    /// ```
+    /// use goap::prelude::*;
+    ///
+    /// let state = State::new().set("noise_level", 2).build();
+    /// let quiet = [("noise_level".to_string(), Condition::LessThan(StateVar::I64(3)))];
+    /// assert!(state.satisfies_conditions(&quiet));
+    /// ```
+    pub fn satisfies_conditions(&self, conditions: &[(String, Condition)]) -> bool {
+        conditions
+            .iter()
+            .all(|(key, condition)| condition.holds(self.vars.get(key)))
+    }
+
+    /// Checks that this state holds none of the values in the given state.
+    /// A variable that is missing from this state never matches an excluded value.
+    pub fn excludes(&self, excluded: &State) -> bool {
+        excluded
+            .vars
+            .iter()
+            .all(|(key, value)| self.vars.get(key) != Some(value))
     }
 
     /// Applies a set of state operations to this state, modifying it in place.
     /// Operations can set variables to new values, add to numeric variables, or subtract from them.
     pub fn apply(&mut self, changes: &HashMap<String, StateOperation>) {
         for (key, operation) in changes {
-            match operation {
-                StateOperation::Set(value) => {
-                    self.vars.insert(key.clone(), value.clone());
-                }
-                StateOperation::Add(amount) => match self.vars.get(key) {
-                    Some(StateVar::I64(current)) => {
-                        self.vars
-                            .insert(key.clone(), StateVar::I64(current + amount));
-                    }
-                    Some(StateVar::F64(current)) => {
-                        self.vars
-                            .insert(key.clone(), StateVar::F64(current + amount));
-                    }
-                    _ => {}
-                },
-                StateOperation::Subtract(amount) => match self.vars.get(key) {
-                    Some(StateVar::I64(current)) => {
-                        self.vars
-                            .insert(key.clone(), StateVar::I64(current - amount));
-                    }
-                    Some(StateVar::F64(current)) => {
-                        self.vars
-                            .insert(key.clone(), StateVar::F64(current - amount));
-                    }
-                    _ => {}
-                },
+            if let Some(value) = apply_operation(self.vars.get(key), operation) {
+                self.vars.insert(key.clone(), value);
             }
         }
     }
@@ -178,20 +474,164 @@ impl State {
             self.vars.insert(key.clone(), value.clone());
         }
     }
+
+    /// Returns an approximation of the heap and inline memory used by this state, in bytes.
+    /// Shared string values are counted in full, so the estimate errs on the high side.
+    pub fn approx_memory_bytes(&self) -> usize {
+        let entry_size = std::mem::size_of::<(String, StateVar)>() + 1;
+        #[cfg(not(any(feature = "ordered-state", feature = "small-state")))]
+        let table = self.vars.capacity() * entry_size;
+        #[cfg(all(feature = "ordered-state", not(feature = "small-state")))]
+        let table = self.vars.len() * entry_size;
+        #[cfg(feature = "small-state")]
+        let table = self.vars.heap_capacity() * entry_size;
+        let text: usize = self
+            .vars
+            .iter()
+            .map(|(key, value)| key.len() + value.as_string().map_or(0, str::len))
+            .sum();
+        std::mem::size_of::<State>() + table + text
+    }
+}
+
+/// Rounds `value * scale` to an integer, returning an error instead of saturating
+/// if `value` is not finite or the result does not fit in an `i64`. `target` names
+/// the representation in the error.
+pub(crate) fn round_scaled(
+    value: f64,
+    scale: f64,
+    target: &'static str,
+) -> Result<i64, ConversionError> {
+    if !value.is_finite() {
+        return Err(ConversionError::NotFinite);
+    }
+    let rounded = (value * scale).round();
+    // i64::MAX as f64 rounds up to 2^63, which is itself out of range
+    if rounded < i64::MIN as f64 || rounded >= i64::MAX as f64 {
+        return Err(ConversionError::OutOfRange {
+            value: value.to_string(),
+            target,
+        });
+    }
+    Ok(rounded as i64)
+}
+
+/// Checks whether a single current value satisfies a required value.
+/// For boolean and string variables, values must match exactly.
+/// For numeric variables, the current value must be >= the required value.
+/// Mismatched types never satisfy each other.
+pub(crate) fn value_satisfies(current: &StateVar, required: &StateVar) -> bool {
+    match (current, required) {
+        (StateVar::Bool(cur), StateVar::Bool(req)) => cur == req,
+        (StateVar::I64(cur), StateVar::I64(req)) => cur >= req,
+        (StateVar::F64(cur), StateVar::F64(req)) => cur >= req,
+        (StateVar::String(cur), StateVar::String(req)) => cur == req,
+        _ => false,
+    }
+}
+
+/// A comparison of a single variable with constant values, such as
+/// `noise_level < 3`, for requirements that the `>=` semantics of numeric
+/// requirements cannot express.
+///
+/// Ordering comparisons only hold between numbers of the same type (integers
+/// with integers, floats with floats); [`Condition::NotEqual`] compares values of
+/// any type. A missing variable never meets a condition.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Condition {
+    /// The value must be below the bound
+    LessThan(StateVar),
+    /// The value must not exceed the bound
+    AtMost(StateVar),
+    /// The value must be above the bound
+    GreaterThan(StateVar),
+    /// The value must reach the bound
+    AtLeast(StateVar),
+    /// The value must differ from the given one
+    NotEqual(StateVar),
+    /// The value must lie between the bounds, both included
+    InRange(StateVar, StateVar),
+}
+
+impl Condition {
+    /// Returns true if the value of a variable, `None` if it is missing, meets the condition.
+    pub fn holds(&self, value: Option<&StateVar>) -> bool {
+        let Some(value) = value else {
+            return false;
+        };
+        match self {
+            Condition::LessThan(bound) => numeric_order(value, bound).is_some_and(|o| o.is_lt()),
+            Condition::AtMost(bound) => numeric_order(value, bound).is_some_and(|o| o.is_le()),
+            Condition::GreaterThan(bound) => numeric_order(value, bound).is_some_and(|o| o.is_gt()),
+            Condition::AtLeast(bound) => numeric_order(value, bound).is_some_and(|o| o.is_ge()),
+            Condition::NotEqual(other) => value != other,
+            Condition::InRange(min, max) => {
+                numeric_order(value, min).is_some_and(|o| o.is_ge())
+                    && numeric_order(value, max).is_some_and(|o| o.is_le())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::LessThan(bound) => write!(f, "< {bound}"),
+            Condition::AtMost(bound) => write!(f, "<= {bound}"),
+            Condition::GreaterThan(bound) => write!(f, "> {bound}"),
+            Condition::AtLeast(bound) => write!(f, ">= {bound}"),
+            Condition::NotEqual(other) => write!(f, "!= {other}"),
+            Condition::InRange(min, max) => write!(f, "in {min}..={max}"),
+        }
+    }
+}
+
+/// Orders two numbers of the same type; other values cannot be ordered.
+fn numeric_order(value: &StateVar, bound: &StateVar) -> Option<std::cmp::Ordering> {
+    match (value, bound) {
+        (StateVar::I64(value), StateVar::I64(bound))
+        | (StateVar::F64(value), StateVar::F64(bound)) => Some(value.cmp(bound)),
+        _ => None,
+    }
+}
+
+/// Computes the new value of a variable after applying an operation to its current value.
+/// Returns None when the operation leaves the variable unchanged
+/// (adding to or subtracting from a missing or non-numeric variable).
+pub(crate) fn apply_operation(
+    current: Option<&StateVar>,
+    operation: &StateOperation,
+) -> Option<StateVar> {
+    match (operation, current) {
+        (StateOperation::Set(value), _) => Some(value.clone()),
+        (StateOperation::Add(amount), Some(StateVar::I64(value))) => {
+            Some(StateVar::I64(value + amount))
+        }
+        (StateOperation::Add(amount), Some(StateVar::F64(value))) => {
+            Some(StateVar::F64(value + amount))
+        }
+        (StateOperation::Subtract(amount), Some(StateVar::I64(value))) => {
+            Some(StateVar::I64(value - amount))
+        }
+        (StateOperation::Subtract(amount), Some(StateVar::F64(value))) => {
+            Some(StateVar::F64(value - amount))
+        }
+        _ => None,
+    }
 }
 
 /// Builder for constructing states with a fluent interface.
 /// Use `State::new()` to create a new builder.
 pub struct StateBuilder {
     /// The variables being built
-    vars: HashMap<String, StateVar>,
+    vars: StateMap,
 }
 
 impl StateBuilder {
     /// Creates a new empty StateBuilder.
     pub fn new() -> Self {
         StateBuilder {
-            vars: HashMap::new(),
+            vars: StateMap::new(),
         }
     }
 
@@ -201,6 +641,11 @@ impl StateBuilder {
         self
     }
 
+    /// Sets an enum variable declared with an [`EnumKey`].
+    pub fn set_enum<T: EnumStateVar>(self, key: EnumKey<T>, value: T) -> Self {
+        self.set(key.name(), value)
+    }
+
     /// Builds the final State from the configured builder.
     pub fn build(self) -> State {
         State { vars: self.vars }
@@ -236,7 +681,9 @@ pub enum StateVar {
     /// Note that arithmetic operations (Add/Subtract) can be performed using convenience methods.
     F64(i64),
     /// String/text value for names, locations, enum values, etc.
-    String(String),
+    /// The text is reference-counted, so cloning a state during search
+    /// shares string values instead of copying them.
+    String(Arc<str>),
 }
 
 impl fmt::Display for StateVar {
@@ -258,6 +705,23 @@ impl StateVar {
         StateVar::F64((value * 1000.0).round() as i64)
     }
 
+    /// Creates a new F64 StateVar from a floating point value, rounded to 3 decimal places.
+    /// Unlike [`StateVar::from_f64`], which saturates huge values and maps NaN to zero,
+    /// returns an error if the value is not finite or does not fit the fixed-point range.
+    pub fn try_from_f64(value: f64) -> Result<Self, ConversionError> {
+        round_scaled(value, 1000.0, "F64").map(StateVar::F64)
+    }
+
+    /// Returns the name of this value's type, as used in error messages.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            StateVar::Bool(_) => "Bool",
+            StateVar::I64(_) => "I64",
+            StateVar::F64(_) => "F64",
+            StateVar::String(_) => "String",
+        }
+    }
+
     /// Converts an F64 StateVar back to a floating point value.
     /// Returns None if the StateVar is not an F64.
     pub fn as_f64(&self) -> Option<f64> {
@@ -285,6 +749,34 @@ impl StateVar {
         }
     }
 
+    /// Extracts the value as an i64, reporting why the conversion failed.
+    pub fn try_into_i64(&self) -> Result<i64, ConversionError> {
+        self.as_i64().ok_or_else(|| self.wrong_type("I64"))
+    }
+
+    /// Extracts the value as an i32, reporting why the conversion failed.
+    /// Returns `ConversionError::OutOfRange` if the value does not fit in an i32.
+    pub fn try_into_i32(&self) -> Result<i32, ConversionError> {
+        let value = self.try_into_i64()?;
+        value.try_into().map_err(|_| ConversionError::OutOfRange {
+            value: value.to_string(),
+            target: "i32",
+        })
+    }
+
+    /// Extracts the value as an f64, reporting why the conversion failed.
+    pub fn try_into_f64(&self) -> Result<f64, ConversionError> {
+        self.as_f64().ok_or_else(|| self.wrong_type("F64"))
+    }
+
+    /// Creates the error for a conversion to a type this value does not hold.
+    fn wrong_type(&self, expected: &'static str) -> ConversionError {
+        ConversionError::WrongType {
+            expected,
+            found: self.type_name(),
+        }
+    }
+
     /// Extracts the value as a bool.
     /// Returns None if the StateVar is not a Bool.
     pub fn as_bool(&self) -> Option<bool> {
@@ -298,7 +790,7 @@ impl StateVar {
     /// Returns None if the StateVar is not a String.
     pub fn as_string(&self) -> Option<&str> {
         match self {
-            StateVar::String(value) => Some(value),
+            StateVar::String(value) => Some(value.as_ref()),
             _ => None,
         }
     }
@@ -343,13 +835,13 @@ impl From<f64> for StateVar {
 
 impl From<String> for StateVar {
     fn from(value: String) -> Self {
-        StateVar::String(value)
+        StateVar::String(value.into())
     }
 }
 
 impl From<&str> for StateVar {
     fn from(value: &str) -> Self {
-        StateVar::String(value.to_string())
+        StateVar::String(value.into())
     }
 }
 
@@ -378,6 +870,16 @@ impl From<i8> for StateVar {
 pub trait IntoStateVar {
     /// Converts this value into a StateVar.
     fn into_state_var(self) -> StateVar;
+
+    /// Converts this value into a StateVar, also returning the error a strict
+    /// conversion would have reported. Builders use this in strict mode.
+    /// The default implementation never reports an error.
+    fn into_state_var_checked(self) -> (StateVar, Option<ConversionError>)
+    where
+        Self: Sized,
+    {
+        (self.into_state_var(), None)
+    }
 }
 
 /// Trait for types that can be extracted from StateVar with proper error handling.
@@ -471,23 +973,31 @@ impl IntoStateVar for f64 {
     fn into_state_var(self) -> StateVar {
         StateVar::from_f64(self)
     }
+
+    fn into_state_var_checked(self) -> (StateVar, Option<ConversionError>) {
+        (StateVar::from_f64(self), StateVar::try_from_f64(self).err())
+    }
 }
 
 impl IntoStateVar for f32 {
     fn into_state_var(self) -> StateVar {
         StateVar::from_f64(self as f64)
     }
+
+    fn into_state_var_checked(self) -> (StateVar, Option<ConversionError>) {
+        (self as f64).into_state_var_checked()
+    }
 }
 
 impl IntoStateVar for String {
     fn into_state_var(self) -> StateVar {
-        StateVar::String(self)
+        StateVar::String(self.into())
     }
 }
 
 impl IntoStateVar for &str {
     fn into_state_var(self) -> StateVar {
-        StateVar::String(self.to_string())
+        StateVar::String(self.into())
     }
 }
 
@@ -508,7 +1018,106 @@ where
     T: EnumStateVar,
 {
     fn into_state_var(self) -> StateVar {
-        StateVar::String(self.to_string())
+        StateVar::String(self.to_string().into())
+    }
+}
+
+/// A state variable holding values of the enum type `T`.
+///
+/// Declaring enum variables as constants ties each one to its type: the
+/// `_enum` methods of the builders only accept values of that type, so a value
+/// of another enum fails to compile instead of silently never matching.
+///
+/// ```
+/// use goap::prelude::*;
+/// use std::fmt;
+///
+/// #[derive(Clone, Copy)]
+/// enum Location { Town, Forest }
+///
+/// impl fmt::Display for Location {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         match self {
+///             Location::Town => write!(f, "town"),
+///             Location::Forest => write!(f, "forest"),
+///         }
+///     }
+/// }
+///
+/// impl EnumStateVar for Location {}
+///
+/// const LOCATION: EnumKey<Location> = EnumKey::new("location");
+///
+/// let state = State::new().set_enum(LOCATION, Location::Forest).build();
+/// let goal = Goal::new("go_home").requires_enum(LOCATION, Location::Town).build();
+/// let walk = Action::new("walk_home").sets_enum(LOCATION, Location::Town).build();
+/// let plan = Planner::new().plan(state, &goal, &[walk]).unwrap();
+/// assert_eq!(plan.actions.len(), 1);
+/// ```
+///
+/// Using a value of another type is a compile error:
+///
+/// ```compile_fail
+/// use goap::prelude::*;
+/// use std::fmt;
+///
+/// struct Weather;
+/// impl fmt::Display for Weather {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         write!(f, "rain")
+///     }
+/// }
+/// impl EnumStateVar for Weather {}
+///
+/// struct Location;
+/// impl fmt::Display for Location {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         write!(f, "town")
+///     }
+/// }
+/// impl EnumStateVar for Location {}
+///
+/// const LOCATION: EnumKey<Location> = EnumKey::new("location");
+/// let goal = Goal::new("go_home").requires_enum(LOCATION, Weather).build();
+/// ```
+pub struct EnumKey<T> {
+    /// The name of the variable
+    name: &'static str,
+    /// The type of the variable's values
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> EnumKey<T> {
+    /// Declares the variable `name` as holding values of type `T`.
+    pub const fn new(name: &'static str) -> Self {
+        EnumKey {
+            name,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the name of the variable.
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<T> Clone for EnumKey<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for EnumKey<T> {}
+
+impl<T> fmt::Debug for EnumKey<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "EnumKey<{}>({:?})",
+            std::any::type_name::<T>(),
+            self.name
+        )
     }
 }
 
@@ -0,0 +1,224 @@
+//! Domain definitions that can be declared in `const` and `static` contexts.
+//!
+//! These types only hold `&'static` data, so a whole domain can be written at
+//! compile time without allocating. They are converted to regular [`Action`]s
+//! and [`Goal`]s when planning.
+//!
+//! ```
+//! use goap::prelude::*;
+//! use goap::static_domain::{StaticAction, StaticDomain, StaticGoal, StaticOp, StaticValue};
+//!
+//! static DOMAIN: StaticDomain = StaticDomain::new(
+//!     &[
+//!         StaticAction::new("chop_tree")
+//!             .cost(2.0)
+//!             .requires(&[("has_axe", StaticValue::Bool(true))])
+//!             .effects(&[("wood", StaticOp::AddInt(1))]),
+//!     ],
+//!     &[StaticGoal::new("gather_wood").requires(&[("wood", StaticValue::Int(1))])],
+//! );
+//!
+//! let initial = State::new().set("has_axe", true).set("wood", 0).build();
+//! let goal = DOMAIN.goal("gather_wood").unwrap();
+//! let plan = Planner::new().plan(initial, &goal, &DOMAIN.actions()).unwrap();
+//! assert_eq!(plan.actions[0].name, "chop_tree");
+//! ```
+
+use crate::actions::Action;
+use crate::goals::Goal;
+use crate::state::{State, StateOperation, StateVar};
+use std::collections::HashMap;
+
+/// A state value that can be constructed in a `const` context.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StaticValue {
+    /// Boolean value
+    Bool(bool),
+    /// Integer value
+    Int(i64),
+    /// Floating point value, converted to fixed-point when planning
+    Float(f64),
+    /// String value for names, locations, enum values, etc.
+    Str(&'static str),
+}
+
+impl StaticValue {
+    /// Converts this value into a StateVar.
+    pub fn to_state_var(self) -> StateVar {
+        match self {
+            StaticValue::Bool(value) => StateVar::Bool(value),
+            StaticValue::Int(value) => StateVar::I64(value),
+            StaticValue::Float(value) => StateVar::from_f64(value),
+            StaticValue::Str(value) => StateVar::from(value),
+        }
+    }
+}
+
+/// An effect operation that can be constructed in a `const` context.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StaticOp {
+    /// Set a variable to a specific value
+    Set(StaticValue),
+    /// Add to an integer variable
+    AddInt(i64),
+    /// Subtract from an integer variable
+    SubtractInt(i64),
+    /// Add to a float variable
+    AddFloat(f64),
+    /// Subtract from a float variable
+    SubtractFloat(f64),
+}
+
+impl StaticOp {
+    /// Converts this operation into a StateOperation.
+    pub fn to_operation(self) -> StateOperation {
+        match self {
+            StaticOp::Set(value) => StateOperation::Set(value.to_state_var()),
+            StaticOp::AddInt(value) => StateOperation::add_i64(value),
+            StaticOp::SubtractInt(value) => StateOperation::subtract_i64(value),
+            StaticOp::AddFloat(value) => StateOperation::add_f64(value),
+            StaticOp::SubtractFloat(value) => StateOperation::subtract_f64(value),
+        }
+    }
+}
+
+/// An action definition backed entirely by static data.
+#[derive(Clone, Copy, Debug)]
+pub struct StaticAction {
+    /// The name of the action
+    pub name: &'static str,
+    /// The cost of executing this action
+    pub cost: f64,
+    /// The preconditions that must be met before this action can be executed
+    pub preconditions: &'static [(&'static str, StaticValue)],
+    /// The state changes that occur when this action is executed
+    pub effects: &'static [(&'static str, StaticOp)],
+}
+
+impl StaticAction {
+    /// Creates a static action with the given name, a cost of 1.0 and no preconditions or effects.
+    pub const fn new(name: &'static str) -> Self {
+        StaticAction {
+            name,
+            cost: 1.0,
+            preconditions: &[],
+            effects: &[],
+        }
+    }
+
+    /// Sets the cost of this action.
+    pub const fn cost(mut self, cost: f64) -> Self {
+        self.cost = cost;
+        self
+    }
+
+    /// Sets the preconditions of this action.
+    pub const fn requires(mut self, preconditions: &'static [(&'static str, StaticValue)]) -> Self {
+        self.preconditions = preconditions;
+        self
+    }
+
+    /// Sets the effects of this action.
+    pub const fn effects(mut self, effects: &'static [(&'static str, StaticOp)]) -> Self {
+        self.effects = effects;
+        self
+    }
+
+    /// Converts this definition into an Action usable by the planner.
+    pub fn to_action(&self) -> Action {
+        let mut preconditions = State::empty();
+        for (key, value) in self.preconditions {
+            preconditions.set(key, value.to_state_var());
+        }
+        let effects: HashMap<String, StateOperation> = self
+            .effects
+            .iter()
+            .map(|(key, op)| (key.to_string(), op.to_operation()))
+            .collect();
+        Action::from_parts(self.name, self.cost, preconditions, effects)
+    }
+}
+
+impl From<&StaticAction> for Action {
+    fn from(action: &StaticAction) -> Self {
+        action.to_action()
+    }
+}
+
+/// A goal definition backed entirely by static data.
+#[derive(Clone, Copy, Debug)]
+pub struct StaticGoal {
+    /// The name of the goal
+    pub name: &'static str,
+    /// The priority of this goal. Higher values indicate higher priority.
+    pub priority: u16,
+    /// The requirements that must be satisfied for the goal to be achieved
+    pub requirements: &'static [(&'static str, StaticValue)],
+}
+
+impl StaticGoal {
+    /// Creates a static goal with the given name, a priority of 1 and no requirements.
+    pub const fn new(name: &'static str) -> Self {
+        StaticGoal {
+            name,
+            priority: 1,
+            requirements: &[],
+        }
+    }
+
+    /// Sets the priority of this goal.
+    pub const fn priority(mut self, priority: u16) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Sets the requirements of this goal.
+    pub const fn requires(mut self, requirements: &'static [(&'static str, StaticValue)]) -> Self {
+        self.requirements = requirements;
+        self
+    }
+
+    /// Converts this definition into a Goal usable by the planner.
+    pub fn to_goal(&self) -> Goal {
+        let mut desired_state = State::empty();
+        for (key, value) in self.requirements {
+            desired_state.set(key, value.to_state_var());
+        }
+        Goal::from_state(self.name, desired_state, self.priority)
+    }
+}
+
+impl From<&StaticGoal> for Goal {
+    fn from(goal: &StaticGoal) -> Self {
+        goal.to_goal()
+    }
+}
+
+/// A complete domain of static actions and goals.
+#[derive(Clone, Copy, Debug)]
+pub struct StaticDomain {
+    /// The actions available in this domain
+    pub actions: &'static [StaticAction],
+    /// The goals defined in this domain
+    pub goals: &'static [StaticGoal],
+}
+
+impl StaticDomain {
+    /// Creates a domain from static action and goal definitions.
+    pub const fn new(actions: &'static [StaticAction], goals: &'static [StaticGoal]) -> Self {
+        StaticDomain { actions, goals }
+    }
+
+    /// Converts all static actions into Actions usable by the planner.
+    pub fn actions(&self) -> Vec<Action> {
+        self.actions.iter().map(StaticAction::to_action).collect()
+    }
+
+    /// Looks up a goal by name and converts it into a Goal usable by the planner.
+    pub fn goal(&self, name: &str) -> Option<Goal> {
+        self.goals
+            .iter()
+            .find(|goal| goal.name == name)
+            .map(StaticGoal::to_goal)
+    }
+}
@@ -0,0 +1,140 @@
+//! Per-variable comparison rules for string values.
+//!
+//! Designer-authored content often writes the same value in different ways,
+//! such as `"Town"` and `"town"`, or `"007"` and `"7"`. Registering a
+//! [`StringComparison`] for a variable with
+//! [`PlannerConfig::string_comparison`](crate::planner::PlannerConfig::string_comparison)
+//! makes the planner treat such values as equal. The planner rewrites string
+//! values into a canonical form before searching, so precondition checks, goal
+//! tests and heuristic distances all use the same rule.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let config = PlannerConfig::new().string_comparison("location", StringComparison::CaseInsensitive);
+//! let planner = Planner::with_config(config);
+//!
+//! let goal = Goal::new("home").requires("location", "Town").build();
+//! let initial_state = State::new().set("location", "town").build();
+//! let plan = planner.plan(initial_state, &goal, &[]).unwrap();
+//! assert!(plan.actions.is_empty());
+//! ```
+
+use crate::actions::Action;
+use crate::goals::Goal;
+use crate::state::{State, StateOperation, StateVar};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// How values of a string variable are compared.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StringComparison {
+    /// Values are equal only if they are identical (the default)
+    #[default]
+    Exact,
+    /// Values are equal if they match ignoring letter case
+    CaseInsensitive,
+    /// Values are equal if they match ignoring leading and trailing whitespace
+    Trimmed,
+    /// Values holding integers are equal if the integers are, so `"007"` equals `"7"`.
+    /// Surrounding whitespace is ignored; other values are compared exactly.
+    NumericId,
+}
+
+impl StringComparison {
+    /// Returns the canonical form of a value: two values are equal under this
+    /// comparison exactly when their canonical forms are identical.
+    pub fn canonical<'a>(&self, value: &'a str) -> Cow<'a, str> {
+        match self {
+            StringComparison::Exact => Cow::Borrowed(value),
+            StringComparison::CaseInsensitive => {
+                if value.chars().any(char::is_uppercase) {
+                    Cow::Owned(value.to_lowercase())
+                } else {
+                    Cow::Borrowed(value)
+                }
+            }
+            StringComparison::Trimmed => Cow::Borrowed(value.trim()),
+            StringComparison::NumericId => match value.trim().parse::<i64>() {
+                Ok(id) => Cow::Owned(id.to_string()),
+                Err(_) => Cow::Borrowed(value),
+            },
+        }
+    }
+
+    /// Returns true if the two values are equal under this comparison.
+    pub fn equals(&self, a: &str, b: &str) -> bool {
+        self.canonical(a) == self.canonical(b)
+    }
+}
+
+/// The comparison rules registered for each string variable.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StringComparisons {
+    /// Comparison rule per variable; unlisted variables are compared exactly
+    modes: HashMap<String, StringComparison>,
+}
+
+impl StringComparisons {
+    /// Sets the comparison rule for a variable.
+    pub fn set(&mut self, key: &str, mode: StringComparison) {
+        self.modes.insert(key.to_string(), mode);
+    }
+
+    /// Returns the comparison rule for a variable.
+    pub fn get(&self, key: &str) -> StringComparison {
+        self.modes.get(key).copied().unwrap_or_default()
+    }
+
+    /// Returns true if no variable has a rule other than exact comparison.
+    pub fn is_empty(&self) -> bool {
+        self.modes
+            .values()
+            .all(|mode| *mode == StringComparison::Exact)
+    }
+
+    /// Rewrites a value into the canonical form for its variable.
+    fn canonical_value(&self, key: &str, value: &StateVar) -> Option<StateVar> {
+        let StateVar::String(text) = value else {
+            return None;
+        };
+        match self.get(key).canonical(text) {
+            Cow::Borrowed(canonical) if canonical == &**text => None,
+            canonical => Some(StateVar::String(canonical.as_ref().into())),
+        }
+    }
+
+    /// Rewrites every string value of a state into its canonical form.
+    pub fn canonical_state(&self, state: &mut State) {
+        if self.is_empty() {
+            return;
+        }
+        for (key, value) in state.vars.iter_mut() {
+            if let Some(canonical) = self.canonical_value(key, value) {
+                *value = canonical;
+            }
+        }
+    }
+
+    /// Rewrites the string values of a goal's requirements into their canonical form.
+    pub fn canonical_goal(&self, goal: &mut Goal) {
+        self.canonical_state(&mut goal.desired_state);
+        self.canonical_state(&mut goal.excluded);
+    }
+
+    /// Rewrites the string values of an action's preconditions and effects into
+    /// their canonical form.
+    pub fn canonical_action(&self, action: &mut Action) {
+        self.canonical_state(&mut action.preconditions);
+        if self.is_empty() {
+            return;
+        }
+        for (key, operation) in &mut action.effects {
+            if let StateOperation::Set(value) = operation
+                && let Some(canonical) = self.canonical_value(key, value)
+            {
+                *value = canonical;
+            }
+        }
+    }
+}
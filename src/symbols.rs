@@ -0,0 +1,189 @@
+//! Registries of allowed string values per state variable.
+//!
+//! A [`SymbolTable`] lists the values a string variable may take, such as
+//! `location ∈ {town, forest, cave}`, so typos are caught before planning.
+//! When the values are connected by an adjacency graph, the planner uses
+//! shortest-path distances between them in its heuristic instead of treating
+//! every pair of unequal strings as one step apart.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let symbols = SymbolTable::new()
+//!     .define("location", &["town", "forest", "cave"])
+//!     .connect("location", "town", "forest")
+//!     .connect("location", "forest", "cave");
+//! assert_eq!(symbols.distance("location", "town", "cave"), Some(2));
+//!
+//! let planner = Planner::with_config(PlannerConfig::new().symbols(symbols));
+//! ```
+
+use crate::actions::Action;
+use crate::goals::Goal;
+use crate::state::{State, StateOperation, StateVar};
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+
+/// Error returned when a string value is not registered for its variable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SymbolError {
+    /// The variable holding the value
+    pub key: String,
+    /// The unregistered value
+    pub value: String,
+}
+
+impl fmt::Display for SymbolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "'{}' is not an allowed value for '{}'",
+            self.value, self.key
+        )
+    }
+}
+
+impl Error for SymbolError {}
+
+/// The allowed values of one variable and the graph connecting them.
+#[derive(Clone, Debug, Default)]
+struct SymbolSet {
+    /// The allowed values, in definition order
+    values: Vec<String>,
+    /// Undirected adjacency between values
+    edges: HashMap<String, Vec<String>>,
+    /// Shortest-path distances between connected values, recomputed when edges change
+    distances: HashMap<(String, String), u64>,
+}
+
+impl SymbolSet {
+    fn add_value(&mut self, value: &str) {
+        if !self.values.iter().any(|existing| existing == value) {
+            self.values.push(value.to_string());
+        }
+    }
+
+    /// Recomputes all-pairs shortest paths with a breadth-first search from every value.
+    fn compute_distances(&mut self) {
+        self.distances.clear();
+        for start in &self.values {
+            let mut queue = VecDeque::from([(start.as_str(), 0)]);
+            let mut seen = vec![start.as_str()];
+            while let Some((current, distance)) = queue.pop_front() {
+                self.distances
+                    .insert((start.clone(), current.to_string()), distance);
+                for next in self.edges.get(current).into_iter().flatten() {
+                    if !seen.contains(&next.as_str()) {
+                        seen.push(next);
+                        queue.push_back((next, distance + 1));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A registry of allowed string values per variable, with optional adjacency between them.
+/// Variables without an entry are unrestricted.
+#[derive(Clone, Debug, Default)]
+pub struct SymbolTable {
+    /// The registered variables
+    sets: HashMap<String, SymbolSet>,
+}
+
+impl SymbolTable {
+    /// Creates an empty symbol table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the allowed values for a variable, adding to any already registered.
+    pub fn define(mut self, key: &str, values: &[&str]) -> Self {
+        let set = self.sets.entry(key.to_string()).or_default();
+        for value in values {
+            set.add_value(value);
+        }
+        set.compute_distances();
+        self
+    }
+
+    /// Connects two values of a variable in both directions, registering them if needed.
+    /// Distances between values are the number of connections on the shortest path.
+    pub fn connect(mut self, key: &str, a: &str, b: &str) -> Self {
+        let set = self.sets.entry(key.to_string()).or_default();
+        set.add_value(a);
+        set.add_value(b);
+        set.edges
+            .entry(a.to_string())
+            .or_default()
+            .push(b.to_string());
+        set.edges
+            .entry(b.to_string())
+            .or_default()
+            .push(a.to_string());
+        set.compute_distances();
+        self
+    }
+
+    /// Returns the allowed values for a variable, or None if it is unrestricted.
+    pub fn values(&self, key: &str) -> Option<&[String]> {
+        self.sets.get(key).map(|set| set.values.as_slice())
+    }
+
+    /// Returns true if the value is allowed for the variable.
+    /// Values of unregistered variables are always allowed.
+    pub fn is_allowed(&self, key: &str, value: &str) -> bool {
+        self.sets
+            .get(key)
+            .is_none_or(|set| set.values.iter().any(|allowed| allowed == value))
+    }
+
+    /// Returns the graph distance between two values of a variable.
+    /// Returns None if the variable has no connections or the values are not connected.
+    pub fn distance(&self, key: &str, from: &str, to: &str) -> Option<u64> {
+        let set = self.sets.get(key)?;
+        if set.edges.is_empty() {
+            return None;
+        }
+        set.distances
+            .get(&(from.to_string(), to.to_string()))
+            .copied()
+    }
+
+    /// Checks every string variable in a state against the registry.
+    pub fn validate_state(&self, state: &State) -> Result<(), SymbolError> {
+        state
+            .vars
+            .iter()
+            .try_for_each(|(key, value)| self.validate_var(key, value))
+    }
+
+    /// Checks the preconditions and Set effects of an action against the registry.
+    pub fn validate_action(&self, action: &Action) -> Result<(), SymbolError> {
+        self.validate_state(&action.preconditions)?;
+        action
+            .effects
+            .iter()
+            .try_for_each(|(key, operation)| match operation {
+                StateOperation::Set(value) => self.validate_var(key, value),
+                _ => Ok(()),
+            })
+    }
+
+    /// Checks the requirements and excluded values of a goal against the registry.
+    pub fn validate_goal(&self, goal: &Goal) -> Result<(), SymbolError> {
+        self.validate_state(&goal.desired_state)?;
+        self.validate_state(&goal.excluded)
+    }
+
+    fn validate_var(&self, key: &str, value: &StateVar) -> Result<(), SymbolError> {
+        match value {
+            StateVar::String(text) if !self.is_allowed(key, text) => Err(SymbolError {
+                key: key.to_string(),
+                value: text.to_string(),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
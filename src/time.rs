@@ -0,0 +1,255 @@
+//! A built-in monotonic time variable.
+//!
+//! Time is the integer state variable [`TIME_KEY`], counted in ticks of whatever
+//! length the game uses. The planner treats it like any other numeric variable,
+//! so actions can require a point in time (a shop that opens at tick 100), take
+//! time to perform, and start timers that later actions wait for:
+//!
+//! - [`ActionBuilder::requires_time`](crate::actions::ActionBuilder::requires_time)
+//!   requires that time has reached a tick.
+//! - [`ActionBuilder::takes_time`](crate::actions::ActionBuilder::takes_time)
+//!   advances time by the action's duration.
+//! - [`ActionBuilder::starts_timer`](crate::actions::ActionBuilder::starts_timer)
+//!   and [`ActionBuilder::requires_timer`](crate::actions::ActionBuilder::requires_timer)
+//!   store a deadline in a variable and wait for it.
+//! - [`wait_action`] lets the planner pass time when nothing else is useful, and
+//!   [`Wait`] builds wait actions of several lengths that also let passive
+//!   processes such as a charging battery or growing crops progress.
+//!
+//! A state without the time variable is treated as being at tick 0, so states
+//! only need it once time has passed. While executing a plan, the game advances
+//! the real state with [`State::advance_time`]. Time only ever grows, so a goal
+//! that can never be reached may keep a search waiting forever; bound such
+//! searches with a node budget or time budget.
+//!
+//! ```
+//! use goap::prelude::*;
+//! use goap::time::wait_action;
+//!
+//! let actions = vec![
+//!     Action::new("buy_bread")
+//!         .requires_time(30)
+//!         .sets("has_bread", true)
+//!         .build(),
+//!     wait_action(10),
+//! ];
+//! let goal = Goal::new("fed").requires("has_bread", true).build();
+//! let plan = Planner::new().plan(State::empty(), &goal, &actions).unwrap();
+//! assert_eq!(plan.actions.len(), 4);
+//! ```
+
+use crate::actions::{Action, DynamicEffect};
+use crate::state::{IntoStateVar, State, StateOperation, StateVar};
+
+/// The name of the built-in time variable.
+pub const TIME_KEY: &str = "time";
+
+/// The name of actions created by [`wait_action`].
+pub const WAIT_ACTION: &str = "wait";
+
+impl State {
+    /// Returns the current tick, or 0 if the state has no time variable.
+    pub fn time(&self) -> i64 {
+        match self.vars.get(TIME_KEY) {
+            Some(StateVar::I64(ticks)) => *ticks,
+            _ => 0,
+        }
+    }
+
+    /// Advances the time variable by the given number of ticks, creating it if
+    /// needed. Negative values are ignored since time never runs backwards.
+    pub fn advance_time(&mut self, ticks: i64) {
+        let now = self.time().saturating_add(ticks.max(0));
+        self.set(TIME_KEY, now);
+    }
+}
+
+/// Creates an action that does nothing but let `ticks` ticks pass, with a cost of
+/// one per tick. Give the planner such an action when plans may need to wait for
+/// a point in time or a timer.
+pub fn wait_action(ticks: i64) -> Action {
+    Wait::new().ticks(ticks).build()
+}
+
+/// A variable that changes on its own while time passes, such as a charging
+/// battery or growing crops.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Process {
+    /// The variable that changes
+    pub key: String,
+    /// The change per tick, an integer or a float
+    pub rate: StateVar,
+    /// The value the process stops at, if any: a maximum for growing variables
+    /// and a minimum for shrinking ones
+    pub limit: Option<StateVar>,
+}
+
+impl Process {
+    /// Returns the value of the variable after `ticks` ticks. A missing variable
+    /// starts at zero; a variable of another type than the rate does not change.
+    fn progress(&self, state: &State, ticks: i64) -> StateOperation {
+        let current = match state.vars.get(&self.key) {
+            Some(value) => value.clone(),
+            None => match self.rate {
+                StateVar::F64(_) => StateVar::F64(0),
+                _ => StateVar::I64(0),
+            },
+        };
+        let next = match (&current, &self.rate, &self.limit) {
+            (StateVar::I64(value), StateVar::I64(rate), None)
+            | (StateVar::F64(value), StateVar::F64(rate), None) => {
+                value.saturating_add(rate.saturating_mul(ticks))
+            }
+            (StateVar::I64(value), StateVar::I64(rate), Some(StateVar::I64(limit)))
+            | (StateVar::F64(value), StateVar::F64(rate), Some(StateVar::F64(limit))) => {
+                let next = value.saturating_add(rate.saturating_mul(ticks));
+                if *rate >= 0 {
+                    if value >= limit {
+                        *value
+                    } else {
+                        next.min(*limit)
+                    }
+                } else if value <= limit {
+                    *value
+                } else {
+                    next.max(*limit)
+                }
+            }
+            _ => return StateOperation::Add(0),
+        };
+        StateOperation::Set(match current {
+            StateVar::F64(_) => StateVar::F64(next),
+            _ => StateVar::I64(next),
+        })
+    }
+}
+
+/// Builds wait actions that let time pass and passive processes progress, so the
+/// planner can wait for a battery to charge or crops to grow instead of needing
+/// explicit no-op actions.
+///
+/// Each duration becomes one action, so the planner can combine long and short
+/// waits to wait exactly as long as needed. A single duration gives an action
+/// named [`WAIT_ACTION`]; several give actions named `wait_<ticks>`.
+///
+/// ```
+/// use goap::prelude::*;
+///
+/// let mut actions = vec![
+///     Action::new("drive")
+///         .requires_gt("battery", 79)
+///         .sets("arrived", true)
+///         .build(),
+/// ];
+/// actions.extend(
+///     Wait::new()
+///         .durations([1, 5])
+///         .process_until("battery", 10, 100)
+///         .actions(),
+/// );
+///
+/// let initial_state = State::new().set("battery", 20).build();
+/// let goal = Goal::new("arrive").requires("arrived", true).build();
+/// let plan = Planner::new().plan(initial_state, &goal, &actions).unwrap();
+/// assert_eq!(plan.cost, 7.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Wait {
+    /// The lengths of the waits, in ticks
+    pub durations: Vec<i64>,
+    /// The cost of each tick of waiting
+    pub cost_per_tick: f64,
+    /// The variables that change while waiting
+    pub processes: Vec<Process>,
+}
+
+impl Default for Wait {
+    fn default() -> Self {
+        Wait {
+            durations: vec![1],
+            cost_per_tick: 1.0,
+            processes: Vec::new(),
+        }
+    }
+}
+
+impl Wait {
+    /// Creates a wait of one tick, with a cost of one per tick and no processes.
+    pub fn new() -> Self {
+        Wait::default()
+    }
+
+    /// Waits for exactly `ticks` ticks.
+    pub fn ticks(self, ticks: i64) -> Self {
+        self.durations([ticks])
+    }
+
+    /// Offers a wait for each of the given numbers of ticks.
+    pub fn durations(mut self, durations: impl IntoIterator<Item = i64>) -> Self {
+        self.durations = durations.into_iter().map(|ticks| ticks.max(0)).collect();
+        self
+    }
+
+    /// Sets the cost of each tick of waiting.
+    pub fn cost_per_tick(mut self, cost: f64) -> Self {
+        self.cost_per_tick = cost;
+        self
+    }
+
+    /// Changes the variable by `rate` per tick while waiting.
+    pub fn process<T: IntoStateVar>(mut self, key: &str, rate: T) -> Self {
+        self.processes.push(Process {
+            key: key.to_string(),
+            rate: rate.into_state_var(),
+            limit: None,
+        });
+        self
+    }
+
+    /// Changes the variable by `rate` per tick while waiting, stopping at `limit`.
+    pub fn process_until<T: IntoStateVar>(mut self, key: &str, rate: T, limit: T) -> Self {
+        self.processes.push(Process {
+            key: key.to_string(),
+            rate: rate.into_state_var(),
+            limit: Some(limit.into_state_var()),
+        });
+        self
+    }
+
+    /// Returns the action waiting for the first duration.
+    pub fn build(&self) -> Action {
+        self.action(WAIT_ACTION, self.durations.first().copied().unwrap_or(0))
+    }
+
+    /// Returns one wait action per duration.
+    pub fn actions(&self) -> Vec<Action> {
+        if self.durations.len() == 1 {
+            return vec![self.build()];
+        }
+        self.durations
+            .iter()
+            .map(|&ticks| self.action(&format!("{WAIT_ACTION}_{ticks}"), ticks))
+            .collect()
+    }
+
+    /// Creates the action waiting for `ticks` ticks.
+    fn action(&self, name: &str, ticks: i64) -> Action {
+        let ticks = ticks.max(0);
+        let mut action = Action::new(name)
+            .takes_time(ticks)
+            .cost(self.cost_per_tick * ticks as f64)
+            .build();
+        for process in &self.processes {
+            let mut description = format!("{} += {} * {ticks}", process.key, process.rate);
+            if let Some(limit) = &process.limit {
+                description.push_str(&format!(" until {limit}"));
+            }
+            let progress = process.clone();
+            let effect = DynamicEffect::new(move |state| progress.progress(state, ticks))
+                .with_description(&description);
+            action.effects.remove(&process.key);
+            action.dynamic_effects.insert(process.key.clone(), effect);
+        }
+        action
+    }
+}
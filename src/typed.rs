@@ -0,0 +1,123 @@
+//! Typed wrappers around states.
+//!
+//! States are keyed by strings so domains can be authored as data, but game code
+//! reading them pays for it with stringly typed lookups and `Option`s everywhere.
+//! The [`typed_state!`](crate::typed_state) macro declares a struct wrapping a
+//! [`State`](crate::state::State) with one typed getter and setter per variable,
+//! and a [`StateSchema`](crate::schema::StateSchema) describing those variables.
+//! The wrapper converts to and from `State`, so it can be handed to the planner
+//! and built from whatever the planner returns.
+//!
+//! Each field is written `getter / setter: Type`, optionally followed by
+//! `=> KeyInfo` metadata for the schema. The variable's key is the getter's
+//! name. Getters return the type's default when the variable is missing or holds
+//! another type.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! typed_state! {
+//!     /// What a villager knows about the world.
+//!     #[derive(Clone, Debug, Default, PartialEq)]
+//!     pub struct Villager {
+//!         /// Gold carried
+//!         gold / set_gold: i64 => KeyInfo::new().unit("gp"),
+//!         has_wood / set_has_wood: bool,
+//!         location / set_location: String,
+//!     }
+//! }
+//!
+//! let mut villager = Villager::default();
+//! villager.set_gold(120);
+//! villager.set_location("forest".to_string());
+//! assert_eq!(villager.gold(), 120);
+//! assert!(!villager.has_wood());
+//!
+//! let goal = Goal::new("wood").requires("has_wood", true).build();
+//! let actions = vec![Action::new("chop").requires("location", "forest").sets("has_wood", true).build()];
+//! let plan = Planner::new().plan(villager.state(), &goal, &actions).unwrap();
+//!
+//! let next = Villager::from_state(plan.actions[0].apply_effect(villager.state()));
+//! assert!(next.has_wood());
+//! let schema = Villager::schema();
+//! assert_eq!(schema.info("gold").and_then(|info| info.unit.as_deref()), Some("gp"));
+//! ```
+
+/// Declares a struct wrapping a [`State`](crate::state::State) with typed
+/// accessors; see the [`typed`](crate::typed) module.
+///
+/// The struct gets `from_state`, `state`, `into_state` and `schema` methods,
+/// `From` conversions to and from `State`, and for each field a getter and a
+/// setter. Attributes on the struct are kept; attributes on a field, such as its
+/// documentation, go on its getter.
+#[macro_export]
+macro_rules! typed_state {
+    (@info) => {
+        $crate::schema::KeyInfo::new()
+    };
+    (@info $info:expr) => {
+        $info
+    };
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[$field_meta:meta])*
+                $getter:ident / $setter:ident : $ty:ty $(=> $info:expr)?
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            /// The wrapped state
+            state: $crate::state::State,
+        }
+
+        impl $name {
+            /// Wraps a state.
+            pub fn from_state(state: $crate::state::State) -> Self {
+                $name { state }
+            }
+
+            /// Returns the wrapped state.
+            pub fn state(&self) -> &$crate::state::State {
+                &self.state
+            }
+
+            /// Returns the wrapped state, consuming the wrapper.
+            pub fn into_state(self) -> $crate::state::State {
+                self.state
+            }
+
+            /// Returns a schema registering every variable of the wrapper.
+            pub fn schema() -> $crate::schema::StateSchema {
+                $crate::schema::StateSchema::new()
+                    $(.key(stringify!($getter), $crate::typed_state!(@info $($info)?)))*
+            }
+
+            $(
+                $(#[$field_meta])*
+                pub fn $getter(&self) -> $ty {
+                    self.state.get::<$ty>(stringify!($getter)).unwrap_or_default()
+                }
+
+                #[doc = concat!("Sets `", stringify!($getter), "`.")]
+                pub fn $setter(&mut self, value: $ty) {
+                    self.state.set(stringify!($getter), value);
+                }
+            )*
+        }
+
+        impl ::std::convert::From<$crate::state::State> for $name {
+            fn from(state: $crate::state::State) -> Self {
+                $name::from_state(state)
+            }
+        }
+
+        impl ::std::convert::From<$name> for $crate::state::State {
+            fn from(wrapper: $name) -> Self {
+                wrapper.into_state()
+            }
+        }
+    };
+}
@@ -0,0 +1,135 @@
+//! Plans returning the world to the state before another plan.
+//!
+//! Behaviours such as "abort the mission and restore the loadout" need to undo
+//! the steps already taken. [`Planner::plan_undo`] walks a plan backwards and
+//! pairs every step with an inverse: the action named with
+//! [`ActionBuilder::inverse`](crate::actions::ActionBuilder::inverse) when there is
+//! one, or an action derived from the step's effects otherwise.
+//!
+//! A derived inverse is named `undo_<action>`, has no preconditions and the cost
+//! of the step it undoes. It subtracts what the step added and adds what it
+//! subtracted, so the undo stays correct if the amount changed in the meantime,
+//! and sets every other changed variable back to its value before the step.
+//! Variables the step created cannot be removed again and are left as they are,
+//! which is why the result only approximately restores the original state.
+//!
+//! ```
+//! use goap::prelude::*;
+//!
+//! let actions = vec![
+//!     Action::new("equip_rifle")
+//!         .requires("rifle_equipped", false)
+//!         .sets("rifle_equipped", true)
+//!         .subtracts("rifles_in_locker", 1)
+//!         .inverse("store_rifle")
+//!         .build(),
+//!     Action::new("store_rifle")
+//!         .requires("rifle_equipped", true)
+//!         .sets("rifle_equipped", false)
+//!         .adds("rifles_in_locker", 1)
+//!         .build(),
+//!     Action::new("buy_ammo").subtracts("gold", 10).adds("ammo", 30).build(),
+//! ];
+//! let start = State::new()
+//!     .set("rifle_equipped", false)
+//!     .set("rifles_in_locker", 2)
+//!     .set("gold", 50)
+//!     .set("ammo", 0)
+//!     .build();
+//! let goal = Goal::new("armed")
+//!     .requires("rifle_equipped", true)
+//!     .requires("ammo", 30)
+//!     .build();
+//!
+//! let planner = Planner::new();
+//! let plan = planner.plan(&start, &goal, &actions).unwrap();
+//! let undo = planner.plan_undo(&start, &plan, &actions).unwrap();
+//! let names: Vec<&str> = undo.actions.iter().map(|a| a.name.as_str()).collect();
+//! assert!(names.contains(&"store_rifle") && names.contains(&"undo_buy_ammo"));
+//! ```
+
+use crate::actions::Action;
+use crate::planner::{Plan, PlanStats, Planner, PlannerError};
+use crate::state::{State, StateOperation};
+use std::collections::{BTreeMap, HashMap};
+
+/// Derives the action undoing `action`, which led from `before` to `after`.
+fn derive_inverse(action: &Action, before: &State, after: &State) -> Action {
+    let mut effects = HashMap::new();
+    for (key, value) in &after.vars {
+        let Some(previous) = before.vars.get(key) else {
+            // The step created the variable, which cannot be removed again
+            continue;
+        };
+        if previous == value {
+            continue;
+        }
+        let operation = match action.effects.get(key) {
+            Some(StateOperation::Add(amount)) => StateOperation::Subtract(*amount),
+            Some(StateOperation::Subtract(amount)) => StateOperation::Add(*amount),
+            _ => StateOperation::Set(previous.clone()),
+        };
+        effects.insert(key.clone(), operation);
+    }
+    let mut inverse = Action::from_parts(
+        &format!("undo_{}", action.name),
+        action.cost,
+        State::empty(),
+        effects,
+    );
+    inverse.tags = action.tags.clone();
+    inverse
+}
+
+impl Planner {
+    /// Builds a plan undoing `plan`, which was executed from `initial_state`, by
+    /// undoing its steps in reverse order.
+    ///
+    /// A step whose action declares an inverse is undone by the action of that
+    /// name in `actions`, as long as its preconditions hold at that point of the
+    /// undo plan; otherwise, and for steps without a declared inverse, an inverse
+    /// is derived from the step's effects (see the [module docs](crate::undo)).
+    /// Returns [`PlannerError::UnknownInverse`] if a declared inverse is not in
+    /// `actions`.
+    pub fn plan_undo(
+        &self,
+        initial_state: &State,
+        plan: &Plan,
+        actions: &[Action],
+    ) -> Result<Plan, PlannerError> {
+        let mut states = vec![initial_state.clone()];
+        for action in &plan.actions {
+            let next = action.apply_effect(&states[states.len() - 1]);
+            states.push(next);
+        }
+
+        let end = states[states.len() - 1].clone();
+        let mut state = end.clone();
+        let mut undo = Vec::with_capacity(plan.actions.len());
+        for (step, action) in plan.actions.iter().enumerate().rev() {
+            let declared = match &action.inverse {
+                Some(name) => Some(
+                    actions
+                        .iter()
+                        .find(|candidate| &candidate.name == name)
+                        .ok_or_else(|| PlannerError::UnknownInverse(name.clone()))?,
+                ),
+                None => None,
+            };
+            let inverse = match declared {
+                Some(inverse) if inverse.can_execute(&state) => inverse.clone(),
+                _ => derive_inverse(action, &states[step], &states[step + 1]),
+            };
+            state = inverse.apply_effect(&state);
+            undo.push(inverse);
+        }
+
+        Ok(Plan {
+            cost: undo.iter().map(|action| action.cost).sum(),
+            stats: PlanStats::default(),
+            currencies: self.currency_changes(&end, &state),
+            assumptions: BTreeMap::new(),
+            actions: undo,
+        })
+    }
+}
@@ -0,0 +1,449 @@
+//! Units for numeric state variables.
+//!
+//! Numeric variables are plain numbers to the planner, so nothing stops an
+//! action from adding a cooldown in seconds to a purse of gold, or one author
+//! from storing a duration in seconds while another stores it in milliseconds.
+//! A [`QuantityKey`] declares that a variable holds amounts of a [`Quantity`],
+//! such as a [`Duration`] or a [`Percent`], and how they are stored: as
+//! fixed-point floats in the quantity's unit, or as integers counting a fraction
+//! of it (see [`QuantityScale`]). Builders given a quantity key only accept values
+//! of its quantity, so mixing units fails to compile, and every value is
+//! converted the same way.
+//!
+//! Registering the keys with [`StateSchema::quantity_key`] records their units
+//! and scales, so that [`StateSchema::format_value`] prints amounts in the unit
+//! and [`StateSchema::check_units`] catches the remaining mistakes at validation
+//! time: expressions that add or compare variables of different units, and
+//! values stored with the wrong representation.
+//!
+//! ```
+//! use goap::prelude::*;
+//! use std::time::Duration;
+//!
+//! const COOLDOWN: QuantityKey<Duration> = QuantityKey::scaled("cooldown", 1000.0);
+//! const MORALE: QuantityKey<Percent> = QuantityKey::new("morale");
+//!
+//! let rally = Action::new("rally")
+//!     .requires_quantity(MORALE, Percent(20.0))
+//!     .adds_quantity(MORALE, Percent(25.0))
+//!     .sets_quantity(COOLDOWN, Duration::from_secs_f64(1.5))
+//!     .build();
+//!
+//! let state = State::new()
+//!     .set_quantity(COOLDOWN, Duration::ZERO)
+//!     .set_quantity(MORALE, Percent(50.0))
+//!     .build();
+//! let after = rally.apply_effect(&state);
+//! assert_eq!(after.get::<i64>("cooldown"), Some(1500));
+//! assert_eq!(after.get_quantity(MORALE), Some(Percent(75.0)));
+//!
+//! let schema = StateSchema::new()
+//!     .quantity_key(COOLDOWN, KeyInfo::new())
+//!     .quantity_key(MORALE, KeyInfo::new());
+//! assert_eq!(schema.format_value("cooldown", after.var("cooldown").unwrap()), "1.5 s");
+//! ```
+//!
+//! Giving a key a value of another quantity is a compile error:
+//!
+//! ```compile_fail
+//! use goap::prelude::*;
+//! use std::time::Duration;
+//!
+//! const MORALE: QuantityKey<Percent> = QuantityKey::new("morale");
+//! let action = Action::new("wait").adds_quantity(MORALE, Duration::from_secs(5)).build();
+//! ```
+
+use crate::actions::Action;
+use crate::expr::{BinaryOp, Comparison, Expr};
+use crate::goals::Goal;
+use crate::schema::{KeyInfo, StateSchema};
+use crate::state::{State, StateBuilder, StateOperation, StateVar};
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+use std::time::Duration;
+
+/// A kind of amount measured in a unit, such as a duration in seconds.
+///
+/// Implement it for newtypes of game-specific units:
+///
+/// ```
+/// use goap::prelude::*;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// struct Gold(i64);
+///
+/// impl Quantity for Gold {
+///     const UNIT: &'static str = "gp";
+///     fn amount(self) -> f64 {
+///         self.0 as f64
+///     }
+///     fn from_amount(amount: f64) -> Self {
+///         Gold(amount.round() as i64)
+///     }
+/// }
+///
+/// const PURSE: QuantityKey<Gold> = QuantityKey::scaled("purse", 1.0);
+/// let state = State::new().set_quantity(PURSE, Gold(30)).build();
+/// assert_eq!(state.get::<i64>("purse"), Some(30));
+/// ```
+pub trait Quantity: Copy {
+    /// The unit amounts are measured in, e.g. `s`
+    const UNIT: &'static str;
+
+    /// Returns the amount in [`Quantity::UNIT`].
+    fn amount(self) -> f64;
+
+    /// Creates a value from an amount in [`Quantity::UNIT`].
+    fn from_amount(amount: f64) -> Self;
+}
+
+/// Durations are measured in seconds. Negative amounts become zero.
+impl Quantity for Duration {
+    const UNIT: &'static str = "s";
+
+    fn amount(self) -> f64 {
+        self.as_secs_f64()
+    }
+
+    fn from_amount(amount: f64) -> Self {
+        Duration::try_from_secs_f64(amount.max(0.0)).unwrap_or(Duration::MAX)
+    }
+}
+
+/// A share in percent, e.g. `Percent(75.0)` for three quarters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct Percent(pub f64);
+
+impl Quantity for Percent {
+    const UNIT: &'static str = "%";
+
+    fn amount(self) -> f64 {
+        self.0
+    }
+
+    fn from_amount(amount: f64) -> Self {
+        Percent(amount)
+    }
+}
+
+/// How a quantity variable stores its amounts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QuantityScale {
+    /// As fixed-point floats in the quantity's unit
+    FixedPoint,
+    /// As integers counting `1 / scale` of the unit, e.g. a scale of 1000 stores
+    /// seconds as whole milliseconds. Amounts are rounded to the nearest count
+    Integer(f64),
+}
+
+impl QuantityScale {
+    /// Converts an amount in the unit to a stored value.
+    pub fn store(self, amount: f64) -> StateVar {
+        match self {
+            QuantityScale::FixedPoint => StateVar::from_f64(amount),
+            QuantityScale::Integer(scale) => StateVar::I64((amount * scale).round() as i64),
+        }
+    }
+
+    /// Converts a stored value back to an amount in the unit, or `None` if the
+    /// value does not have the stored representation.
+    pub fn amount(self, value: &StateVar) -> Option<f64> {
+        match (self, value) {
+            (QuantityScale::FixedPoint, StateVar::F64(_)) => value.as_f64(),
+            (QuantityScale::Integer(scale), StateVar::I64(count)) => Some(*count as f64 / scale),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the stored value type.
+    fn type_name(self) -> &'static str {
+        match self {
+            QuantityScale::FixedPoint => "F64",
+            QuantityScale::Integer(_) => "I64",
+        }
+    }
+}
+
+/// A typed handle to a numeric variable holding amounts of the quantity `Q`.
+///
+/// Builders taking a quantity key only accept values of `Q` and convert them
+/// with the key's [`QuantityScale`], so every action and goal stores the
+/// variable the same way.
+pub struct QuantityKey<Q> {
+    /// The name of the variable
+    name: &'static str,
+    /// How amounts are stored
+    scale: QuantityScale,
+    /// The quantity of the variable's values
+    marker: PhantomData<fn() -> Q>,
+}
+
+impl<Q> QuantityKey<Q> {
+    /// Declares the variable `name` as holding amounts of `Q`, stored as
+    /// fixed-point floats in the quantity's unit.
+    pub const fn new(name: &'static str) -> Self {
+        QuantityKey {
+            name,
+            scale: QuantityScale::FixedPoint,
+            marker: PhantomData,
+        }
+    }
+
+    /// Declares the variable `name` as holding amounts of `Q`, stored as integers
+    /// counting `1 / scale` of the quantity's unit.
+    pub const fn scaled(name: &'static str, scale: f64) -> Self {
+        QuantityKey {
+            name,
+            scale: QuantityScale::Integer(scale),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the name of the variable.
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns how amounts are stored.
+    pub const fn scale(&self) -> QuantityScale {
+        self.scale
+    }
+}
+
+impl<Q: Quantity> QuantityKey<Q> {
+    /// Converts a value to the variable's stored representation.
+    pub fn store(&self, value: Q) -> StateVar {
+        self.scale.store(value.amount())
+    }
+
+    /// Converts a stored value back to a quantity, or `None` if the value does
+    /// not have the stored representation.
+    pub fn load(&self, value: &StateVar) -> Option<Q> {
+        self.scale.amount(value).map(Q::from_amount)
+    }
+
+    /// Returns the stored representation of `value` as the raw integer added to
+    /// the variable by [`StateOperation::Add`].
+    pub(crate) fn raw(&self, value: Q) -> i64 {
+        match self.store(value) {
+            StateVar::F64(amount) | StateVar::I64(amount) => amount,
+            _ => 0,
+        }
+    }
+}
+
+impl<Q> Clone for QuantityKey<Q> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Q> Copy for QuantityKey<Q> {}
+
+impl<Q> fmt::Debug for QuantityKey<Q> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "QuantityKey<{}>({:?}, {:?})",
+            std::any::type_name::<Q>(),
+            self.name,
+            self.scale
+        )
+    }
+}
+
+impl State {
+    /// Sets a quantity variable declared with a [`QuantityKey`].
+    pub fn set_quantity<Q: Quantity>(&mut self, key: QuantityKey<Q>, value: Q) {
+        self.vars.insert(key.name().to_string(), key.store(value));
+    }
+
+    /// Returns the value of a quantity variable declared with a [`QuantityKey`],
+    /// or `None` if it is missing or not stored as the key declares.
+    pub fn get_quantity<Q: Quantity>(&self, key: QuantityKey<Q>) -> Option<Q> {
+        key.load(self.vars.get(key.name())?)
+    }
+}
+
+impl StateBuilder {
+    /// Sets a quantity variable declared with a [`QuantityKey`].
+    pub fn set_quantity<Q: Quantity>(self, key: QuantityKey<Q>, value: Q) -> Self {
+        self.set(key.name(), key.store(value))
+    }
+}
+
+/// A unit mistake found by [`StateSchema::check_units`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum UnitError {
+    /// An expression adds, subtracts or compares variables of different units
+    Mismatch {
+        /// The action using the expression
+        action: String,
+        /// The expression
+        expression: String,
+        /// The unit of the left-hand operand
+        left: String,
+        /// The unit of the right-hand operand
+        right: String,
+    },
+    /// A value given for a quantity variable is not stored as its scale declares
+    Representation {
+        /// The action or goal giving the value
+        source: String,
+        /// The variable
+        key: String,
+        /// The type the scale stores values as
+        expected: &'static str,
+        /// The type of the value
+        found: &'static str,
+    },
+}
+
+impl fmt::Display for UnitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnitError::Mismatch {
+                action,
+                expression,
+                left,
+                right,
+            } => write!(
+                f,
+                "Action '{action}': '{expression}' mixes units {left} and {right}"
+            ),
+            UnitError::Representation {
+                source,
+                key,
+                expected,
+                found,
+            } => write!(f, "{source}: {key} is stored as {expected}, found {found}"),
+        }
+    }
+}
+
+impl Error for UnitError {}
+
+impl StateSchema {
+    /// Sets the metadata of a quantity variable, recording the unit and scale of
+    /// its [`QuantityKey`] declaration.
+    pub fn quantity_key<Q: Quantity>(self, key: QuantityKey<Q>, info: KeyInfo) -> Self {
+        let info = KeyInfo {
+            unit: Some(Q::UNIT.to_string()),
+            scale: Some(key.scale()),
+            ..info
+        };
+        self.key(key.name(), info)
+    }
+
+    /// Checks actions and goals against the units of the registered variables.
+    ///
+    /// Reports expression preconditions and postconditions that add, subtract or
+    /// compare variables of different units, and values of quantity variables
+    /// registered with [`StateSchema::quantity_key`] whose type differs from the
+    /// one their scale stores. Number literals fit any unit, and products and
+    /// quotients are not checked. Expression effects cannot be inspected.
+    pub fn check_units(&self, actions: &[Action], goals: &[Goal]) -> Vec<UnitError> {
+        let mut errors = Vec::new();
+        for action in actions {
+            for comparison in action
+                .expr_preconditions
+                .iter()
+                .chain(&action.postconditions)
+            {
+                if let Some((left, right)) = self.comparison_mismatch(comparison) {
+                    errors.push(UnitError::Mismatch {
+                        action: action.name.clone(),
+                        expression: comparison.to_string(),
+                        left,
+                        right,
+                    });
+                }
+            }
+            let source = format!("Action '{}'", action.name);
+            let values = action.preconditions.vars.iter().chain(
+                action
+                    .effects
+                    .iter()
+                    .chain(&action.failure_effects)
+                    .filter_map(|(key, operation)| match operation {
+                        StateOperation::Set(value) => Some((key, value)),
+                        _ => None,
+                    }),
+            );
+            self.check_representations(&source, values, &mut errors);
+        }
+        for goal in goals {
+            let source = format!("Goal '{}'", goal.name);
+            let values = goal
+                .desired_state
+                .vars
+                .iter()
+                .chain(&goal.excluded.vars)
+                .chain(&goal.relative.vars);
+            self.check_representations(&source, values, &mut errors);
+        }
+        errors
+    }
+
+    /// Reports values of quantity variables not stored as their scale declares,
+    /// sorted by variable.
+    fn check_representations<'a>(
+        &self,
+        source: &str,
+        values: impl Iterator<Item = (&'a String, &'a StateVar)>,
+        errors: &mut Vec<UnitError>,
+    ) {
+        let mut values: Vec<(&String, &StateVar)> = values.collect();
+        values.sort_by(|a, b| a.0.cmp(b.0));
+        for (key, value) in values {
+            let Some(scale) = self.info(key).and_then(|info| info.scale) else {
+                continue;
+            };
+            if scale.amount(value).is_none() {
+                errors.push(UnitError::Representation {
+                    source: source.to_string(),
+                    key: key.clone(),
+                    expected: scale.type_name(),
+                    found: value.type_name(),
+                });
+            }
+        }
+    }
+
+    /// Returns the two units a comparison mixes, if it mixes any.
+    fn comparison_mismatch(&self, comparison: &Comparison) -> Option<(String, String)> {
+        let units = self
+            .unit_of(&comparison.lhs)
+            .and_then(|left| Ok((left, self.unit_of(&comparison.rhs)?)));
+        match units {
+            Err(mixed) => Some(mixed),
+            Ok((Some(left), Some(right))) if left != right => {
+                Some((left.to_string(), right.to_string()))
+            }
+            Ok(_) => None,
+        }
+    }
+
+    /// Returns the unit of an expression, `None` if it has no known unit, or the
+    /// two units of the first sum or difference mixing units.
+    fn unit_of(&self, expr: &Expr) -> Result<Option<&str>, (String, String)> {
+        match expr {
+            Expr::Number(_) => Ok(None),
+            Expr::Var(key) => Ok(self.info(key).and_then(|info| info.unit.as_deref())),
+            Expr::Neg(inner) => self.unit_of(inner),
+            Expr::Binary { op, lhs, rhs } => {
+                let (left, right) = (self.unit_of(lhs)?, self.unit_of(rhs)?);
+                match op {
+                    BinaryOp::Add | BinaryOp::Sub => match (left, right) {
+                        (Some(left), Some(right)) if left != right => {
+                            Err((left.to_string(), right.to_string()))
+                        }
+                        _ => Ok(left.or(right)),
+                    },
+                    BinaryOp::Mul | BinaryOp::Div => Ok(None),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for planning to produce a quantity of something
+
+    /// Test closed-form repetition of the cheapest producer
+    /// Validates: The setup and repetition count are computed without expanding a
+    /// state per repetition, and the flat plan reaches the target
+    /// Failure: Large quantities are searched step by step or the count is off by one
+    #[test]
+    fn test_accumulate_closed_form() {
+        let actions = vec![
+            Action::new("buy_pickaxe")
+                .requires("gold", 10)
+                .subtracts("gold", 10)
+                .sets("has_pickaxe", true)
+                .build(),
+            Action::new("mine")
+                .requires("has_pickaxe", true)
+                .adds("ore", 3)
+                .cost(2.0)
+                .build(),
+            Action::new("scavenge").adds("ore", 1).cost(4.0).build(),
+        ];
+        let state = State::new()
+            .set("gold", 10)
+            .set("has_pickaxe", false)
+            .set("ore", 1)
+            .build();
+
+        let plan = Planner::new()
+            .plan_to_accumulate(state.clone(), "ore", 1000, &actions)
+            .unwrap();
+        assert_eq!(plan.to_string(), "buy_pickaxe, mine x334");
+        assert_eq!(plan.len(), 335);
+        assert_eq!(plan.cost, 1.0 + 334.0 * 2.0);
+
+        let end = plan.to_plan().simulate_with_events(&state, |_| {});
+        assert!(end.get::<i64>("ore").unwrap() >= 1001);
+    }
+
+    /// Test falling back to a search when no producer repeats on its own
+    /// Validates: Producers consuming an input are planned with the regular search
+    /// and their consecutive repetitions are grouped
+    /// Failure: The closed form repeats an action whose preconditions stop holding
+    #[test]
+    fn test_accumulate_fallback_search() {
+        let actions = vec![
+            Action::new("chop").adds("logs", 2).build(),
+            Action::new("saw")
+                .requires("logs", 1)
+                .subtracts("logs", 1)
+                .adds("planks", 2)
+                .build(),
+        ];
+        let state = State::new().set("logs", 0).set("planks", 0).build();
+        let plan = Planner::new()
+            .plan_to_accumulate(state.clone(), "planks", 4, &actions)
+            .unwrap();
+        assert_eq!(
+            plan.to_plan()
+                .simulate_with_events(&state, |_| {})
+                .get::<i64>("planks"),
+            Some(4)
+        );
+        assert_eq!(plan.len(), 3);
+        assert!(plan.steps.iter().any(|step| step.count == 2));
+    }
+
+    /// Test amounts that need no plan or cannot be accumulated
+    /// Validates: Non-positive amounts give empty plans and type mismatches are errors
+    /// Failure: The planner searches for nothing or mixes numeric types
+    #[test]
+    fn test_accumulate_edge_cases() {
+        let actions = vec![Action::new("work").adds("gold", 5).build()];
+        let state = State::new().set("gold", 3).build();
+        let planner = Planner::new();
+
+        let plan = planner
+            .plan_to_accumulate(state.clone(), "gold", 0, &actions)
+            .unwrap();
+        assert!(plan.is_empty());
+
+        let plan = planner
+            .plan_to_accumulate(State::empty(), "gold", 12, &actions)
+            .unwrap();
+        assert_eq!(plan.to_string(), "work x3");
+
+        assert!(matches!(
+            planner.plan_to_accumulate(state.clone(), "gold", 1.5, &actions),
+            Err(PlannerError::IncompatibleStateTypes(_))
+        ));
+        assert!(matches!(
+            planner.plan_to_accumulate(state, "gold", true, &actions),
+            Err(PlannerError::IncompatibleStateTypes(_))
+        ));
+    }
+
+    /// Test that accumulation respects excluded tags
+    /// Validates: Producers are chosen from the prepared action set, as in `plan`
+    /// Failure: An excluded producer is repeated because the raw actions were used
+    #[test]
+    fn test_accumulate_skips_excluded_producer() {
+        let actions = vec![
+            Action::new("work").adds("gold", 10).tag("loud").build(),
+            Action::new("beg").adds("gold", 1).build(),
+        ];
+        let state = State::new().set("gold", 0).build();
+        let planner = Planner::with_config(PlannerConfig::new().exclude_tags(["loud"]));
+
+        let plan = planner
+            .plan_to_accumulate(&state, "gold", 20, &actions)
+            .unwrap();
+        assert_eq!(plan.to_string(), "beg x20");
+
+        let goal = Goal::new("rich").requires("gold", 20).build();
+        let searched = planner.plan(&state, &goal, &actions).unwrap();
+        assert!(searched.actions.iter().all(|action| action.name == "beg"));
+    }
+}
@@ -314,13 +314,13 @@ mod tests {
             .build();
 
         if let StateOperation::Set(value) = action.effects.get("location").unwrap() {
-            assert_eq!(value, &StateVar::String("forest".to_string()));
+            assert_eq!(value, &StateVar::String("forest".into()));
         } else {
             panic!("Expected Set operation for location");
         }
 
         if let StateOperation::Set(value) = action.effects.get("weather").unwrap() {
-            assert_eq!(value, &StateVar::String("rainy".to_string()));
+            assert_eq!(value, &StateVar::String("rainy".into()));
         } else {
             panic!("Expected Set operation for weather");
         }
@@ -426,9 +426,539 @@ mod tests {
 
         // Check string set
         if let StateOperation::Set(value) = action.effects.get("location").unwrap() {
-            assert_eq!(value, &StateVar::String("treasure_room".to_string()));
+            assert_eq!(value, &StateVar::String("treasure_room".into()));
         } else {
             panic!("Expected Set operation for location");
         }
     }
+
+    // Tests for dynamic effects
+
+    /// Test dynamic effect computed from the current state
+    /// Validates: effect_fn computes its operation from the state the action is applied to
+    /// Failure: Dynamic effects are ignored or evaluated against the wrong state
+    #[test]
+    fn test_dynamic_effect_scales_with_state() {
+        let harvest = Action::new("harvest")
+            .effect_fn("grain", |state| {
+                StateOperation::add_i64(state.get::<i64>("farm_level").unwrap_or(0) * 10)
+            })
+            .build();
+
+        let level_one = State::new().set("farm_level", 1).set("grain", 5).build();
+        let level_four = State::new().set("farm_level", 4).set("grain", 5).build();
+
+        assert_eq!(harvest.apply_effect(&level_one).get::<i64>("grain"), Some(15));
+        assert_eq!(harvest.apply_effect(&level_four).get::<i64>("grain"), Some(45));
+    }
+
+    /// Test dynamic effects read the state before other effects are applied
+    /// Validates: Static effects on other keys don't leak into dynamic effect evaluation
+    /// Failure: Dynamic effects observe partially-applied action effects
+    #[test]
+    fn test_dynamic_effect_uses_pre_action_state() {
+        let upgrade_and_harvest = Action::new("upgrade_and_harvest")
+            .adds("farm_level", 1)
+            .effect_fn("grain", |state| {
+                StateOperation::add_i64(state.get::<i64>("farm_level").unwrap_or(0) * 10)
+            })
+            .build();
+
+        let state = State::new().set("farm_level", 2).set("grain", 0).build();
+        let result = upgrade_and_harvest.apply_effect(&state);
+
+        assert_eq!(result.get::<i64>("farm_level"), Some(3));
+        assert_eq!(result.get::<i64>("grain"), Some(20));
+    }
+
+    /// Test static and dynamic effects on the same key replace each other
+    /// Validates: The last builder call for a key wins, regardless of effect kind
+    /// Failure: Both effects are kept and applied twice
+    #[test]
+    fn test_dynamic_effect_replaces_static_effect() {
+        let action = Action::new("pay")
+            .subtracts("gold", 5)
+            .effect_fn("gold", |_| StateOperation::set_i64(0))
+            .build();
+        assert!(!action.effects.contains_key("gold"));
+        assert!(action.dynamic_effects.contains_key("gold"));
+
+        let action = Action::new("pay")
+            .effect_fn("gold", |_| StateOperation::set_i64(0))
+            .subtracts("gold", 5)
+            .build();
+        assert!(action.effects.contains_key("gold"));
+        assert!(action.dynamic_effects.is_empty());
+
+        let display = format!(
+            "{}",
+            Action::new("harvest")
+                .effect_fn("grain", |_| StateOperation::add_i64(1))
+                .build()
+        );
+        assert!(display.contains("Compute grain from state"));
+    }
+
+
+    /// Test action tags
+    /// Validates: tag() attaches labels once and has_tag() finds them
+    /// Failure: Tags are lost or duplicated when building actions
+    #[test]
+    fn test_action_tags() {
+        let action = Action::new("move")
+            .tag("movement")
+            .tag("movement")
+            .tag("outdoor")
+            .build();
+        assert_eq!(action.tags, vec!["movement", "outdoor"]);
+        assert!(action.has_tag("outdoor"));
+        assert!(!action.has_tag("combat"));
+    }
+
+    // Tests for validated action building
+
+    /// Test building a well-formed action with validation
+    /// Validates: try_build accepts valid definitions and matches build
+    /// Failure: Valid content is rejected at load time
+    #[test]
+    fn test_action_try_build_valid() {
+        let action = Action::new("chop")
+            .cost(2.5)
+            .requires("has_axe", true)
+            .requires("has_axe", true)
+            .sets("speed", 1.5)
+            .try_build()
+            .unwrap();
+        assert_eq!(action.name, "chop");
+        assert_eq!(action.cost, 2.5);
+    }
+
+    /// Test rejecting broken action definitions
+    /// Validates: Empty names, bad costs, conflicting preconditions and out-of-range floats
+    /// Failure: Authoring mistakes only show up as plans that never work
+    #[test]
+    fn test_action_try_build_errors() {
+        assert_eq!(
+            Action::new("").try_build().unwrap_err(),
+            DefinitionError::EmptyName
+        );
+        assert!(matches!(
+            Action::new("a").cost(f64::NAN).try_build(),
+            Err(DefinitionError::InvalidCost(_))
+        ));
+        assert_eq!(
+            Action::new("a").cost(-1.0).try_build().unwrap_err(),
+            DefinitionError::InvalidCost(-1.0)
+        );
+        assert_eq!(
+            Action::new("a")
+                .requires("door", "open")
+                .requires("door", "closed")
+                .try_build()
+                .unwrap_err(),
+            DefinitionError::ConflictingRequirement {
+                key: "door".to_string()
+            }
+        );
+        assert_eq!(
+            Action::new("a")
+                .sets("speed", 1e300)
+                .try_build()
+                .unwrap_err(),
+            DefinitionError::OutOfRange {
+                key: "speed".to_string()
+            }
+        );
+        assert_eq!(
+            Action::new("a")
+                .requires("speed", -1e300)
+                .try_build()
+                .unwrap_err(),
+            DefinitionError::OutOfRange {
+                key: "speed".to_string()
+            }
+        );
+    }
+
+    /// Test strict validation of action values
+    /// Validates: Strict builders report NaN, infinite and out-of-range floats anywhere
+    /// Failure: NaN amounts silently become zero in shipped content
+    #[test]
+    fn test_action_try_build_strict_values() {
+        let nan_amount = || Action::new("heal").adds("health", f64::NAN);
+        assert!(nan_amount().try_build().is_ok());
+        assert_eq!(
+            nan_amount().strict(true).try_build().unwrap_err(),
+            DefinitionError::InvalidValue {
+                key: "health".to_string(),
+                error: ConversionError::NotFinite
+            }
+        );
+        assert!(matches!(
+            Action::new("a")
+                .requires("speed", 1e17)
+                .strict(true)
+                .try_build(),
+            Err(DefinitionError::InvalidValue {
+                error: ConversionError::OutOfRange { .. },
+                ..
+            })
+        ));
+        assert!(
+            Action::new("a")
+                .strict(true)
+                .requires("speed", 1.5)
+                .subtracts("stamina", 0.25)
+                .try_build()
+                .is_ok()
+        );
+    }
+
+    // Tests for alternative preconditions
+
+    /// Test checking an action with alternative preconditions
+    /// Validates: The action can execute when any alternative of every group holds
+    /// Failure: requires_any behaves like requires or ignores its alternatives
+    #[test]
+    fn test_requires_any_can_execute() {
+        let attack = Action::new("attack")
+            .requires("enemy_visible", true)
+            .requires_any([("has_sword", true), ("has_bow", true)])
+            .sets("enemy_hurt", true)
+            .build();
+
+        let armed_with = |sword: bool, bow: bool| {
+            State::new()
+                .set("enemy_visible", true)
+                .set("has_sword", sword)
+                .set("has_bow", bow)
+                .build()
+        };
+        assert!(attack.can_execute(&armed_with(true, false)));
+        assert!(attack.can_execute(&armed_with(false, true)));
+        assert!(!attack.can_execute(&armed_with(false, false)));
+        assert!(attack.to_string().contains("any of"));
+    }
+
+    /// Test splitting an action into variants
+    /// Validates: One variant per alternative, with contradicting combinations removed
+    /// Failure: The planner cannot reason about each alternative separately
+    #[test]
+    fn test_action_variants() {
+        let attack = Action::new("attack")
+            .requires("weapon", "sword")
+            .requires_any([("weapon", "sword"), ("weapon", "bow")])
+            .requires_any([("stamina", 5), ("rage", 10)])
+            .build();
+
+        let variants = attack.variants();
+        assert_eq!(variants.len(), 2);
+        for variant in &variants {
+            assert_eq!(variant.name, "attack");
+            assert!(variant.any_preconditions.is_empty());
+            assert_eq!(
+                variant.preconditions.get::<String>("weapon").as_deref(),
+                Some("sword")
+            );
+        }
+        assert_eq!(variants[0].preconditions.get::<i64>("stamina"), Some(5));
+        assert_eq!(variants[1].preconditions.get::<i64>("rage"), Some(10));
+
+        let plain = Action::new("wait").build();
+        assert_eq!(plain.variants().len(), 1);
+    }
+
+    /// Test planning with alternative preconditions
+    /// Validates: The planner picks whichever alternative is cheapest to reach
+    /// Failure: Authors still need per-weapon copies of one logical action
+    #[test]
+    fn test_requires_any_planning() {
+        let actions = vec![
+            Action::new("attack")
+                .requires_any([("has_sword", true), ("has_bow", true)])
+                .sets("enemy_hurt", true)
+                .build(),
+            Action::new("buy_sword")
+                .sets("has_sword", true)
+                .cost(5.0)
+                .build(),
+            Action::new("craft_bow")
+                .sets("has_bow", true)
+                .cost(2.0)
+                .build(),
+        ];
+        let goal = Goal::new("hurt").requires("enemy_hurt", true).build();
+        let initial_state = State::new()
+            .set("has_sword", false)
+            .set("has_bow", false)
+            .set("enemy_hurt", false)
+            .build();
+
+        let plan = Planner::new().plan(initial_state, &goal, &actions).unwrap();
+        let names: Vec<&str> = plan.actions.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["craft_bow", "attack"]);
+        assert_eq!(plan.cost, 3.0);
+    }
+
+    /// Test predicted failure effects
+    /// Validates: A failing action applies only its failure effects
+    /// Failure: Regular effects leak into the failure state or risky actions are not detected
+    #[test]
+    fn test_failure_effects() {
+        let pick_lock = Action::new("pick_lock")
+            .sets("door_open", true)
+            .fails_with("lockpick", false)
+            .build();
+        assert!(pick_lock.may_fail());
+        assert!(!Action::new("walk").build().may_fail());
+
+        let state = State::new()
+            .set("door_open", false)
+            .set("lockpick", true)
+            .build();
+        let failed = pick_lock.apply_failure(&state);
+        assert_eq!(failed.get::<bool>("lockpick"), Some(false));
+        assert_eq!(failed.get::<bool>("door_open"), Some(false));
+        assert!(pick_lock.to_string().contains("On failure"));
+    }
+
+    /// Test dry runs of executable actions
+    /// Validates: The resulting state is returned when preconditions hold
+    /// Failure: dry_run disagrees with can_execute and apply_effect
+    #[test]
+    fn test_dry_run_success() {
+        let action = Action::new("buy")
+            .requires("gold", 10)
+            .subtracts("gold", 10)
+            .sets("has_sword", true)
+            .build();
+        let state = State::new().set("gold", 15).build();
+        let result = action.dry_run(&state).unwrap();
+        assert_eq!(result, action.apply_effect(&state));
+        assert_eq!(result.get::<i64>("gold"), Some(5));
+    }
+
+    /// Test dry runs of blocked actions
+    /// Validates: Every unmet precondition is reported with required and actual values
+    /// Failure: Failures are missing, unordered or lack context
+    #[test]
+    fn test_dry_run_reports_unmet_preconditions() {
+        let action = Action::new("attack")
+            .requires("stamina", 5)
+            .requires("alive", true)
+            .requires("target", "orc")
+            .requires_any([("has_sword", true), ("has_bow", true)])
+            .requires_expr("range <= reach")
+            .unwrap()
+            .build();
+        let state = State::new()
+            .set("stamina", 2)
+            .set("alive", true)
+            .set("has_sword", false)
+            .set("range", 3)
+            .set("reach", 1)
+            .build();
+
+        let failure = action.dry_run(&state).unwrap_err();
+        assert_eq!(failure.action, "attack");
+        assert_eq!(failure.unmet.len(), 4);
+        assert_eq!(
+            failure.unmet[0],
+            UnmetPrecondition::Value {
+                key: "stamina".to_string(),
+                required: StateVar::I64(5),
+                actual: Some(StateVar::I64(2)),
+            }
+        );
+        assert_eq!(
+            failure.unmet[1],
+            UnmetPrecondition::Value {
+                key: "target".to_string(),
+                required: StateVar::from("orc"),
+                actual: None,
+            }
+        );
+        assert!(matches!(failure.unmet[2], UnmetPrecondition::Comparison(_)));
+        assert!(matches!(&failure.unmet[3], UnmetPrecondition::AnyOf(group) if group.len() == 2));
+
+        let message = failure.to_string();
+        assert!(message.contains("stamina: requires 5, found 2"));
+        assert!(message.contains("target: requires orc, found nothing"));
+        assert!(message.contains("range <= reach does not hold"));
+    }
+
+    /// Test preconditions requiring a variable to be missing
+    /// Validates: The action runs only while the variable is absent, is reported
+    /// by dry_run otherwise, and requiring the key present too is a conflict
+    /// Failure: Absence preconditions are ignored or misreported
+    #[test]
+    fn test_requires_absent() {
+        let scout = Action::new("scout")
+            .requires_absent("enemy_x")
+            .sets("enemy_x", 0)
+            .build();
+        assert!(scout.can_execute(&State::empty()));
+
+        let seen = State::new().set("enemy_x", 40).build();
+        assert!(!scout.can_execute(&seen));
+        let failure = scout.dry_run(&seen).unwrap_err();
+        assert_eq!(
+            failure.unmet,
+            vec![UnmetPrecondition::Present {
+                key: "enemy_x".to_string(),
+                actual: StateVar::I64(40),
+            }]
+        );
+        assert_eq!(
+            failure.unmet[0].to_string(),
+            "enemy_x: requires absent, found 40"
+        );
+        assert!(scout.to_string().contains("enemy_x: absent"));
+
+        let conflicting = Action::new("confused")
+            .requires("enemy_x", 1)
+            .requires_absent("enemy_x")
+            .try_build();
+        assert!(conflicting.is_err());
+    }
+
+    /// Test compact single-line action formatting
+    /// Validates: display_compact lists preconditions and effects on one line
+    /// Failure: Compact output spans lines or omits conditions
+    #[test]
+    fn test_action_display_compact() {
+        let action = Action::new("work")
+            .requires("has_job", true)
+            .adds("gold", 10)
+            .subtracts("energy", 2)
+            .sets("tired", true)
+            .cost(1.5)
+            .build();
+
+        let compact = action.display_compact().to_string();
+        assert_eq!(
+            compact,
+            "work (cost: 1.5) {has_job: true} -> {energy - 2, gold + 10, tired = true}"
+        );
+        assert_eq!(format!("{action:#}"), compact);
+
+        let idle = Action::new("idle").build();
+        assert_eq!(format!("{idle:#}"), "idle (cost: 1.0) {} -> {}");
+    }
+
+    /// Test comparison preconditions on actions
+    /// Validates: requires_lt, requires_gt, requires_ne and requires_between gate can_execute and report unmet conditions
+    /// Failure: Conditions are ignored or reported without their values
+    #[test]
+    fn test_action_condition_preconditions() {
+        let sneak = Action::new("sneak_past")
+            .requires_lt("guard_alert", 2)
+            .requires_gt("stealth", 5)
+            .requires_ne("light", "bright")
+            .requires_between("distance", 3, 10)
+            .sets("past_guard", true)
+            .build();
+
+        let unnoticed = State::new()
+            .set("guard_alert", 1)
+            .set("stealth", 6)
+            .set("light", "dim")
+            .set("distance", 10)
+            .build();
+        assert!(sneak.can_execute(&unnoticed));
+
+        let alerted = State::new()
+            .set("guard_alert", 2)
+            .set("stealth", 5)
+            .set("light", "dim")
+            .set("distance", 3)
+            .build();
+        assert!(!sneak.can_execute(&alerted));
+        let unmet = sneak.unmet_preconditions(&alerted);
+        assert_eq!(
+            unmet,
+            vec![
+                UnmetPrecondition::Condition {
+                    key: "guard_alert".to_string(),
+                    condition: Condition::LessThan(StateVar::I64(2)),
+                    actual: Some(StateVar::I64(2)),
+                },
+                UnmetPrecondition::Condition {
+                    key: "stealth".to_string(),
+                    condition: Condition::GreaterThan(StateVar::I64(5)),
+                    actual: Some(StateVar::I64(5)),
+                },
+            ]
+        );
+        assert_eq!(unmet[0].to_string(), "guard_alert: requires < 2, found 2");
+        assert!(sneak.to_string().contains("distance in 3..=10"));
+        assert!(!sneak.can_execute(&State::empty()));
+    }
+
+    /// Test editing an action's cost and preconditions in place
+    /// Validates: empty, set_cost, add/remove/get_precondition and get_missing_preconditions
+    /// Failure: In-place edits are lost or missing preconditions are misreported
+    #[test]
+    fn test_action_mutate_preconditions() {
+        let mut action = Action::empty("attack");
+        assert_eq!(action.cost, 1.0);
+        assert!(action.preconditions.is_empty());
+
+        action.set_cost(3.0);
+        action.add_precondition("stamina", 10);
+        action.add_precondition("has_weapon", true);
+        assert_eq!(action.cost, 3.0);
+        assert_eq!(action.get_precondition::<i64>("stamina"), Some(10));
+        assert_eq!(action.get_precondition::<bool>("stamina"), None);
+
+        action.add_precondition("stamina", 15);
+        assert_eq!(action.get_precondition::<i64>("stamina"), Some(15));
+
+        let state = State::new().set("stamina", 12).build();
+        assert_eq!(
+            action.get_missing_preconditions(&state),
+            vec!["has_weapon", "stamina"]
+        );
+
+        assert_eq!(
+            action.remove_precondition("has_weapon"),
+            Some(StateVar::Bool(true))
+        );
+        assert_eq!(action.remove_precondition("has_weapon"), None);
+        action.add_precondition("stamina", 12);
+        assert!(action.get_missing_preconditions(&state).is_empty());
+        assert!(action.can_execute(&state));
+    }
+
+    /// Test editing an action's effects in place
+    /// Validates: add_effect_set/add/subtract replace effects and remove_effect drops static and dynamic effects
+    /// Failure: Effects are duplicated, lost or applied with the wrong amounts
+    #[test]
+    fn test_action_mutate_effects() {
+        let mut action = Action::new("cast")
+            .effect_fn("mana", |_| StateOperation::set_i64(0))
+            .build();
+
+        action.add_effect_subtract("mana", 5);
+        action.add_effect_add("experience", 1.5);
+        action.add_effect_set("casting", true);
+        assert!(action.dynamic_effects.is_empty());
+        assert_eq!(
+            action.get_effect("mana"),
+            Some(&StateOperation::subtract_i64(5))
+        );
+
+        let state = State::new().set("mana", 20).set("experience", 1.0).build();
+        let after = action.apply_effect(&state);
+        assert_eq!(after.get::<i64>("mana"), Some(15));
+        assert_eq!(after.get::<f64>("experience"), Some(2.5));
+        assert_eq!(after.get::<bool>("casting"), Some(true));
+
+        assert_eq!(
+            action.remove_effect("casting"),
+            Some(StateOperation::Set(StateVar::Bool(true)))
+        );
+        assert_eq!(action.get_effect("casting"), None);
+        assert_eq!(action.remove_effect("casting"), None);
+    }
 }
@@ -0,0 +1,234 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for deadlock analysis
+
+    /// Test detection of an action whose precondition has no producer
+    /// Validates: The missing producer variable is pinpointed
+    /// Failure: Unreachable actions go unreported or the wrong key is blamed
+    #[test]
+    fn test_analyze_missing_producer() {
+        let initial_state = State::new()
+            .set("has_wood", false)
+            .set("has_tools", false)
+            .build();
+        let actions = vec![
+            Action::new("craft_item")
+                .requires("has_tools", true)
+                .requires("has_wood", true)
+                .sets("has_item", true)
+                .build(),
+            Action::new("get_wood").sets("has_wood", true).build(),
+        ];
+
+        let deadlocks = analyze_deadlocks(&actions, &initial_state);
+        assert_eq!(deadlocks.len(), 1);
+        assert_eq!(deadlocks[0].action, "craft_item");
+        assert_eq!(
+            deadlocks[0].blocked,
+            vec![BlockedCondition {
+                key: "has_tools".to_string(),
+                required: StateVar::Bool(true),
+                has_producer: false,
+            }]
+        );
+        assert!(deadlocks[0].to_string().contains("has_tools"));
+    }
+
+    /// Test chains of actions and numeric accumulation are considered reachable
+    /// Validates: Repeated Add effects and Set chains make preconditions reachable
+    /// Failure: Reachable actions are reported as deadlocked
+    #[test]
+    fn test_analyze_reachable_chains() {
+        let initial_state = State::new()
+            .set("gold", 0)
+            .set("location", "home")
+            .build();
+        let actions = vec![
+            Action::new("go_mine").sets("location", "mine").build(),
+            Action::new("mine")
+                .requires("location", "mine")
+                .adds("gold", 1)
+                .build(),
+            Action::new("buy_castle").requires("gold", 1000).build(),
+        ];
+        assert!(analyze_deadlocks(&actions, &initial_state).is_empty());
+    }
+
+    /// Test variables that are produced but never with the required value
+    /// Validates: has_producer distinguishes wrong values from missing producers
+    /// Failure: Reports do not help tell the two cases apart
+    #[test]
+    fn test_analyze_wrong_value_producer() {
+        let initial_state = State::new().set("location", "home").build();
+        let actions = vec![
+            Action::new("go_town").sets("location", "town").build(),
+            Action::new("explore_cave")
+                .requires("location", "cave")
+                .sets("explored", true)
+                .build(),
+            Action::new("celebrate").requires("explored", true).build(),
+        ];
+
+        let deadlocks = analyze_deadlocks(&actions, &initial_state);
+        let names: Vec<&str> = deadlocks.iter().map(|d| d.action.as_str()).collect();
+        assert_eq!(names, vec!["explore_cave", "celebrate"]);
+        assert!(deadlocks[0].blocked[0].has_producer);
+        assert!(deadlocks[1].blocked[0].has_producer);
+    }
+
+    /// Test finding goal requirements that cannot be restored
+    /// Validates: Consumed resources, unproducible values and unchangeable excluded
+    /// values are reported, while restorable requirements are not
+    /// Failure: Reachable states are reported as dead ends or dead ends are missed
+    #[test]
+    fn test_analyze_dead_ends() {
+        let actions = vec![
+            Action::new("burn_torch")
+                .subtracts("torches", 1)
+                .sets("lit", true)
+                .build(),
+            Action::new("chop_wood").adds("wood", 2).build(),
+            Action::new("forge").sets("blade", "rusty").build(),
+        ];
+        let goal = Goal::new("escape")
+            .requires("torches", 1)
+            .requires("wood", 5)
+            .requires("lit", true)
+            .requires("blade", "sharp")
+            .requires_not("cursed", true)
+            .build();
+
+        let analysis = analyze_dead_ends(&actions, &goal);
+        let keys: Vec<&str> = analysis.rules.iter().map(|r| r.key.as_str()).collect();
+        assert_eq!(keys, vec!["blade", "cursed", "torches"]);
+
+        let alive = State::new()
+            .set("torches", 1)
+            .set("blade", "sharp")
+            .set("cursed", false)
+            .build();
+        assert!(analysis.dead_end(&alive).is_none());
+        let burnt = State::new().set("torches", 0).set("blade", "sharp").build();
+        assert_eq!(analysis.dead_end(&burnt).unwrap().key, "torches");
+        assert_eq!(
+            analysis.dead_end(&burnt).unwrap().to_string(),
+            "torches below 1 can never be raised"
+        );
+        assert!(
+            analysis
+                .to_string()
+                .contains("cursed = true can never change")
+        );
+    }
+
+    /// Test pruning dead ends during search
+    /// Validates: States that consumed a non-renewable resource are pruned and the
+    /// search still finds the same plan with fewer expansions
+    /// Failure: The search explores states from which the goal is unreachable
+    #[test]
+    fn test_dead_end_pruning() {
+        let actions = vec![
+            Action::new("burn_torch")
+                .requires("torches", 1)
+                .subtracts("torches", 1)
+                .sets("lit", true)
+                .build(),
+            Action::new("light_lamp")
+                .sets("lit", true)
+                .cost(3.0)
+                .build(),
+            Action::new("search_room")
+                .requires("lit", true)
+                .sets("has_key", true)
+                .build(),
+        ];
+        let start = State::new()
+            .set("torches", 1)
+            .set("lit", false)
+            .set("has_key", false)
+            .build();
+        let goal = Goal::new("keep_torch")
+            .requires("has_key", true)
+            .requires("torches", 1)
+            .build();
+
+        let plain = Planner::new().plan(start.clone(), &goal, &actions).unwrap();
+        let pruning = Planner::with_config(PlannerConfig::new().dead_end_pruning(true))
+            .plan(start, &goal, &actions)
+            .unwrap();
+
+        assert_eq!(pruning, plain);
+        assert_eq!(plain.stats.dead_ends, 0);
+        assert!(pruning.stats.dead_ends > 0);
+        assert!(pruning.stats.nodes_expanded < plain.stats.nodes_expanded);
+    }
+
+    /// Test the writer report lists every action changing a variable
+    /// Validates: Effects, dynamic effects and failure effects are listed per variable
+    /// Failure: Writers are missing, misattributed or listed under the wrong variable
+    #[test]
+    fn test_analyze_writers_lists_writers() {
+        let actions = vec![
+            Action::new("mine")
+                .adds("gold", 5)
+                .fails_with("gold", 0)
+                .build(),
+            Action::new("buy")
+                .subtracts("gold", 10)
+                .sets("has_sword", true)
+                .build(),
+            Action::new("gamble")
+                .effect_fn("gold", |_| StateOperation::set_i64(0))
+                .build(),
+        ];
+        let report = analyze_writers(&actions);
+
+        let gold: Vec<String> = report
+            .writers_of("gold")
+            .iter()
+            .map(|w| w.to_string())
+            .collect();
+        assert_eq!(
+            gold,
+            vec![
+                "mine: add 5",
+                "mine: set 0 (on failure)",
+                "buy: subtract 10",
+                "gamble: computed"
+            ]
+        );
+        assert_eq!(report.writers_of("has_sword").len(), 1);
+        assert!(report.writers_of("missing").is_empty());
+        assert!(report.conflicts.is_empty());
+        assert!(!report.to_string().contains("Suspicious"));
+    }
+
+    /// Test the writer report flags variables written with different types
+    /// Validates: Set values of different types and arithmetic on booleans are reported once per type pair
+    /// Failure: Suspicious pairs go unreported or compatible writes are flagged
+    #[test]
+    fn test_analyze_writers_flags_type_conflicts() {
+        let actions = vec![
+            Action::new("mine").adds("gold", 5).build(),
+            Action::new("reset_gold").sets("gold", 0).build(),
+            Action::new("find_chest").sets("gold", true).build(),
+            Action::new("open_chest").sets("gold", false).build(),
+            Action::new("rename").sets("title", "knight").build(),
+            Action::new("promote").sets("title", 2).build(),
+        ];
+        let report = analyze_writers(&actions);
+
+        let conflicts: Vec<String> = report.conflicts.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            conflicts,
+            vec![
+                "gold: 'mine' writes numeric but 'find_chest' writes Bool",
+                "gold: 'reset_gold' writes I64 but 'find_chest' writes Bool",
+                "title: 'rename' writes String but 'promote' writes I64",
+            ]
+        );
+        assert!(report.to_string().contains("Suspicious writes:"));
+    }
+}
@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+    use std::sync::{Arc, Mutex};
+
+    // Tests for goal lifecycles managed by GoalArbiter
+
+    /// Test that one-shot goals retire and recurring goals re-arm
+    /// Validates: update reports achievements and re-arms only recurring goals
+    /// Failure: Would indicate quests are replanned after completion or maintenance goals are lost
+    #[test]
+    fn test_once_and_recurring_goals() {
+        let mut arbiter = GoalArbiter::new()
+            .once(Goal::new("quest").requires("has_relic", true).build())
+            .recurring(Goal::new("eat").requires("fed", true).build());
+
+        let fed = State::new()
+            .set("has_relic", false)
+            .set("fed", true)
+            .build();
+        assert_eq!(
+            arbiter.update(&fed),
+            vec![GoalEvent {
+                goal: "eat".to_string(),
+                kind: GoalEventKind::Achieved,
+            }]
+        );
+        assert_eq!(arbiter.active_goals()[0].name, "quest");
+        assert!(arbiter.update(&fed).is_empty());
+
+        let done = State::new()
+            .set("has_relic", true)
+            .set("fed", false)
+            .build();
+        let events: Vec<String> = arbiter
+            .update(&done)
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(events, ["quest achieved", "eat re-armed"]);
+        assert_eq!(arbiter.status("quest"), Some(GoalStatus::Retired));
+        assert_eq!(arbiter.select().unwrap().name, "eat");
+
+        // A retired quest stays retired even when its requirement stops holding
+        let lost = State::new()
+            .set("has_relic", false)
+            .set("fed", false)
+            .build();
+        assert!(arbiter.update(&lost).is_empty());
+        assert_eq!(arbiter.status("quest"), Some(GoalStatus::Retired));
+    }
+
+    /// Test achievement and abandonment callbacks
+    /// Validates: Callbacks run once per lifecycle change and abandoned goals stop being planned
+    /// Failure: Would indicate game code misses quest completions or receives duplicates
+    #[test]
+    fn test_callbacks() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let achieved = Arc::clone(&log);
+        let abandoned = Arc::clone(&log);
+        let mut arbiter = GoalArbiter::new()
+            .once(Goal::new("escort").requires("arrived", true).build())
+            .once(
+                Goal::new("hunt")
+                    .requires("has_meat", true)
+                    .priority(5)
+                    .build(),
+            )
+            .on_achieved(move |goal, _| achieved.lock().unwrap().push(format!("+{}", goal.name)))
+            .on_abandoned(move |goal, _| abandoned.lock().unwrap().push(format!("-{}", goal.name)));
+        assert_eq!(arbiter.select().unwrap().name, "hunt");
+
+        let state = State::new()
+            .set("arrived", false)
+            .set("has_meat", false)
+            .build();
+        assert!(arbiter.abandon("hunt", &state).is_some());
+        assert!(arbiter.abandon("hunt", &state).is_none());
+        assert_eq!(arbiter.select().unwrap().name, "escort");
+
+        let arrived = State::new()
+            .set("arrived", true)
+            .set("has_meat", true)
+            .build();
+        arbiter.update(&arrived);
+        arbiter.update(&arrived);
+        assert_eq!(*log.lock().unwrap(), ["-hunt", "+escort"]);
+        assert!(arbiter.active_goals().is_empty());
+
+        assert!(arbiter.rearm("hunt"));
+        assert_eq!(arbiter.update(&arrived)[0].to_string(), "hunt achieved");
+    }
+}
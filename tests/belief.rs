@@ -0,0 +1,157 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for beliefs that expire without fresh observations
+
+    /// Test forgetting a variable after its time to live
+    /// Validates: Variables are removed once the TTL passes without an observation,
+    /// and variables without a TTL are kept
+    /// Failure: Stale values survive or fresh ones are dropped
+    #[test]
+    fn test_ttl_expires_unobserved_values() {
+        let start = State::new().set("has_weapon", true).build();
+        let mut belief = BeliefState::new(start).ttl("enemy_x", 5);
+        belief.observe("enemy_x", 12);
+
+        assert!(belief.advance(4).is_empty());
+        assert_eq!(belief.age("enemy_x"), Some(4));
+        assert_eq!(belief.advance(1), vec!["enemy_x".to_string()]);
+        assert_eq!(belief.state().get::<i64>("enemy_x"), None);
+        assert_eq!(belief.age("enemy_x"), None);
+        assert_eq!(belief.state().get::<bool>("has_weapon"), Some(true));
+        assert_eq!(belief.now(), 5);
+    }
+
+    /// Test refreshing a variable with a new observation
+    /// Validates: Observing a variable restarts its time to live
+    /// Failure: Refreshed values are forgotten on the original schedule
+    #[test]
+    fn test_observation_refreshes_ttl() {
+        let mut belief = BeliefState::new(State::empty()).ttl("enemy_x", 3);
+        belief.observe("enemy_x", 1);
+        belief.advance(2);
+        belief.observe("enemy_x", 2);
+
+        assert!(belief.advance(2).is_empty());
+        assert_eq!(belief.state().get::<i64>("enemy_x"), Some(2));
+        assert_eq!(belief.advance(1), vec!["enemy_x".to_string()]);
+    }
+
+    /// Test marking forgotten variables as unknown
+    /// Validates: MarkUnknown variables are listed as unknown until observed again
+    /// and the list can drive conformant planning
+    /// Failure: Unknown variables are lost or never cleared
+    #[test]
+    fn test_mark_unknown() {
+        let start = State::new().set("door_open", true).build();
+        let mut belief = BeliefState::new(start)
+            .ttl_with("door_open", 2, Forget::MarkUnknown)
+            .ttl("noise", 2);
+        belief.observe("noise", true);
+        belief.advance(2);
+
+        assert_eq!(belief.unknown(), ["door_open"]);
+        assert!(belief.is_unknown("door_open"));
+        assert!(!belief.is_unknown("noise"));
+
+        let actions = vec![
+            Action::new("open_door").sets("door_open", true).build(),
+            Action::new("enter")
+                .requires("door_open", true)
+                .sets("inside", true)
+                .build(),
+        ];
+        let goal = Goal::new("inside").requires("inside", true).build();
+        let plan = Planner::new()
+            .plan_conformant(belief.state(), &belief.unknown(), &goal, &actions)
+            .unwrap();
+        assert_eq!(plan.actions.len(), 2);
+
+        belief.observe("door_open", false);
+        assert!(belief.unknown().is_empty());
+    }
+
+    /// Test planning around a forgotten variable
+    /// Validates: An absence precondition becomes usable once a value is forgotten
+    /// Failure: Plans keep relying on the stale value
+    #[test]
+    fn test_plan_after_forgetting() {
+        let actions = vec![
+            Action::new("scout")
+                .requires_absent("enemy_x")
+                .sets("enemy_x", 7)
+                .build(),
+            Action::new("attack")
+                .requires("enemy_x", 7)
+                .sets("enemy_defeated", true)
+                .build(),
+        ];
+        let goal = Goal::new("win").requires("enemy_defeated", true).build();
+        let planner = Planner::new();
+
+        let mut belief = BeliefState::new(State::empty()).ttl("enemy_x", 10);
+        belief.observe("enemy_x", 3);
+        assert!(
+            planner
+                .plan(belief.state().clone(), &goal, &actions)
+                .is_err()
+        );
+
+        belief.advance(10);
+        let plan = planner
+            .plan(belief.state().clone(), &goal, &actions)
+            .unwrap();
+        let names: Vec<&str> = plan.actions.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["scout", "attack"]);
+    }
+
+    /// Test that noisy boolean readings collapse once confident enough
+    /// Validates: Fusion::Probability believes a value past its threshold and drops it when readings conflict
+    /// Failure: Would indicate a single weak reading decides the belief or contradictions are ignored
+    #[test]
+    fn test_probability_fusion() {
+        let mut belief = BeliefState::new(State::empty())
+            .fuse("enemy_seen", Fusion::Probability { threshold: 0.9 });
+
+        belief.observe_noisy("enemy_seen", true, 0.8);
+        assert_eq!(belief.state().get::<bool>("enemy_seen"), None);
+        belief.observe_noisy("enemy_seen", true, 0.8);
+        assert_eq!(belief.state().get::<bool>("enemy_seen"), Some(true));
+
+        belief.observe_noisy("enemy_seen", false, 0.9);
+        assert_eq!(belief.state().get::<bool>("enemy_seen"), None);
+        assert!((belief.confidence("enemy_seen").unwrap() - 0.64).abs() < 0.01);
+
+        belief.observe_noisy("enemy_seen", false, 0.99);
+        belief.observe_noisy("enemy_seen", false, 0.99);
+        assert_eq!(belief.state().get::<bool>("enemy_seen"), Some(false));
+    }
+
+    /// Test last-write-wins fusion with decaying confidence
+    /// Validates: Weaker readings lose to fresh confident ones until the confidence decays, then fade out
+    /// Failure: Would indicate noisy readings overwrite good ones or stale readings linger forever
+    #[test]
+    fn test_latest_fusion_decays() {
+        let mut belief = BeliefState::new(State::empty()).fuse(
+            "enemy_x",
+            Fusion::Latest {
+                half_life: 2.0,
+                floor: 0.2,
+            },
+        );
+
+        belief.observe_noisy("enemy_x", 5, 0.8);
+        belief.observe_noisy("enemy_x", 9, 0.5);
+        assert_eq!(belief.state().get::<i64>("enemy_x"), Some(5));
+
+        assert!(belief.advance(2).is_empty());
+        assert!((belief.confidence("enemy_x").unwrap() - 0.4).abs() < 1e-9);
+        belief.observe_noisy("enemy_x", 9, 0.5);
+        assert_eq!(belief.state().get::<i64>("enemy_x"), Some(9));
+
+        assert_eq!(belief.advance(4), vec!["enemy_x"]);
+        assert_eq!(belief.state().get::<i64>("enemy_x"), None);
+        assert_eq!(belief.confidence("enemy_x"), None);
+    }
+}
@@ -0,0 +1,99 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for search budget reports and adaptive per-agent budgets
+
+    fn gold_domain(target: i64) -> (State, Goal, Vec<Action>) {
+        let start = state! { gold: 0 };
+        let goal = Goal::new("rich").requires("gold", target).build();
+        let actions = vec![Action::new("work").adds("gold", 5).build()];
+        (start, goal, actions)
+    }
+
+    /// Test budget reports for found plans, exhausted budgets and impossible goals
+    /// Validates: Binding limits are named and further nodes are estimated only when a limit stopped the search
+    /// Failure: Would indicate budget tuning grows budgets for impossible goals or misses exhausted ones
+    #[test]
+    fn test_budget_report() {
+        let (start, goal, actions) = gold_domain(40);
+        let planner = Planner::with_config(PlannerConfig::new().max_nodes_expanded(100));
+        let result = planner.plan(&start, &goal, &actions);
+        let report = planner.budget_report(&start, &goal, &result);
+        assert!(!report.is_binding());
+        assert_eq!(report.additional_nodes, Some(0));
+        assert_eq!(report.node_budget, Some(100));
+        assert_eq!(report.nodes_expanded, result.unwrap().stats.nodes_expanded);
+        assert!(report.utilization().unwrap() < 0.5);
+
+        let tight = Planner::with_config(PlannerConfig::new().max_nodes_expanded(3));
+        let result = tight.plan(&start, &goal, &actions);
+        let report = tight.budget_report(&start, &goal, &result);
+        assert_eq!(report.binding, Some(SearchBudget::NodesExpanded(3)));
+        assert_eq!(report.utilization(), Some(1.0));
+        // Three expansions covered a fraction of the way; the rest needs more
+        let additional = report.additional_nodes.unwrap();
+        assert!(additional >= 3, "estimated {additional} further nodes");
+
+        let impossible = Goal::new("mine").requires("has_mine", true).build();
+        let result = planner.plan(&start, &impossible, &[]);
+        let report = planner.budget_report(&start, &impossible, &result);
+        assert_eq!(report.binding, None);
+        assert_eq!(report.additional_nodes, None);
+    }
+
+    /// Test adapting per-agent budgets
+    /// Validates: Failing agents get more nodes, trivial agents less, within the bounds
+    /// Failure: Would indicate hard agents starve or easy agents hoard search time
+    #[test]
+    fn test_auto_budget_adapts() {
+        let (start, goal, actions) = gold_domain(60);
+        let planner = Planner::new();
+        let mut budgets = AutoBudget::new(4).bounds(2, 64);
+
+        let first = budgets.plan(1u32, &planner, &start, &goal, &actions);
+        assert!(matches!(
+            first,
+            Err(PlannerError::BudgetExceeded {
+                budget: SearchBudget::NodesExpanded(4),
+                ..
+            })
+        ));
+        assert!(budgets.budget(&1) >= 8);
+        let plan = (0..4)
+            .find_map(|_| budgets.plan(1, &planner, &start, &goal, &actions).ok())
+            .unwrap();
+        assert_eq!(plan.actions.len(), 12);
+        let fitted = budgets.budget(&1);
+
+        // A trivial agent's budget shrinks towards twice what it uses
+        let (start, easy, actions) = gold_domain(5);
+        for _ in 0..10 {
+            budgets.plan(2, &planner, &start, &easy, &actions).unwrap();
+        }
+        assert!(budgets.budget(&2) < 4);
+        assert!(budgets.budget(&2) >= 2);
+        assert_eq!(budgets.budget(&1), fitted);
+
+        // Other limits and impossible goals leave budgets alone
+        let report = BudgetReport {
+            binding: Some(SearchBudget::PlanLength(3)),
+            nodes_expanded: 10,
+            node_budget: Some(fitted),
+            additional_nodes: Some(50),
+        };
+        assert_eq!(budgets.record(1, &report), fitted);
+        assert_eq!(budgets.record(1, &BudgetReport::default()), fitted);
+
+        // Growth is capped by the bounds
+        let exhausted = BudgetReport {
+            binding: Some(SearchBudget::NodesExpanded(fitted)),
+            nodes_expanded: fitted,
+            node_budget: Some(fitted),
+            additional_nodes: Some(1000),
+        };
+        assert_eq!(budgets.record(1, &exhausted), 64);
+        budgets.forget(&1);
+        assert_eq!(budgets.budget(&1), 4);
+    }
+}
@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for calibrating action costs from observed executions
+
+    /// Test weighted averages of observations
+    /// Validates: The first observation sets the averages and later ones move
+    /// them by the smoothing weight; invalid durations are ignored
+    /// Failure: Costs jump to the latest observation or never adapt
+    #[test]
+    fn test_weighted_averages() {
+        let mut calibrator = CostCalibrator::new(0.25);
+        calibrator.record("mine", 8.0, true);
+        calibrator.record("mine", 4.0, true);
+        calibrator.record("mine", f64::NAN, true);
+        calibrator.record("mine", -1.0, true);
+
+        let estimate = calibrator.estimate("mine").unwrap();
+        assert_eq!(estimate.duration, 7.0);
+        assert_eq!(estimate.success_rate, 1.0);
+        assert_eq!(estimate.samples, 2);
+        assert_eq!(calibrator.suggested_cost("mine"), Some(7.0));
+        assert_eq!(calibrator.suggested_cost("smelt"), None);
+    }
+
+    /// Test failures raising the suggested cost
+    /// Validates: The suggestion is the average duration divided by the success
+    /// rate, and none is made without successes or enough samples
+    /// Failure: Unreliable actions look as cheap as reliable ones
+    #[test]
+    fn test_failures_raise_cost() {
+        let mut calibrator = CostCalibrator::new(0.5).min_samples(2);
+        calibrator.ingest(&PlanReport::new().step("pick_lock", 2.0, false));
+        assert_eq!(calibrator.suggested_cost("pick_lock"), None);
+
+        calibrator.ingest(&PlanReport::new().step("pick_lock", 2.0, true));
+        assert_eq!(calibrator.estimate("pick_lock").unwrap().success_rate, 0.5);
+        assert_eq!(calibrator.suggested_cost("pick_lock"), Some(4.0));
+
+        calibrator.record("sing", 1.0, false);
+        calibrator.record("sing", 1.0, false);
+        assert_eq!(calibrator.suggested_cost("sing"), None);
+    }
+
+    /// Test suggesting and applying costs
+    /// Validates: Suggestions list only changed costs and applying them updates
+    /// the actions, changing the plans the planner finds
+    /// Failure: Calibrated costs do not reach the planner
+    #[test]
+    fn test_apply_suggestions() {
+        let mut actions = vec![
+            Action::new("walk")
+                .sets("at_market", true)
+                .cost(1.0)
+                .build(),
+            Action::new("ride")
+                .sets("at_market", true)
+                .cost(2.0)
+                .build(),
+            Action::new("trade").cost(3.0).build(),
+        ];
+        let mut calibrator = CostCalibrator::new(1.0);
+        calibrator.ingest(
+            &PlanReport::new()
+                .step("walk", 6.0, true)
+                .step("ride", 2.0, true)
+                .step("trade", 3.0, true),
+        );
+
+        let suggestions = calibrator.suggestions(&actions);
+        assert_eq!(
+            suggestions,
+            vec![CostSuggestion {
+                action: "walk".to_string(),
+                current: 1.0,
+                suggested: 6.0,
+            }]
+        );
+        assert_eq!(suggestions[0].to_string(), "walk: 1.00 -> 6.00");
+
+        assert_eq!(calibrator.apply(&mut actions), 1);
+        assert_eq!(calibrator.apply(&mut actions), 0);
+        let goal = Goal::new("shop").requires("at_market", true).build();
+        let plan = Planner::new()
+            .plan(State::empty(), &goal, &actions)
+            .unwrap();
+        assert_eq!(plan.actions[0].name, "ride");
+    }
+}
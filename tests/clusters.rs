@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for goal clusters that warm-start planning
+
+    fn domain() -> (Vec<Goal>, Vec<Action>) {
+        let goals = vec![
+            Goal::new("eat_meal").requires("fed", true).build(),
+            Goal::new("feast")
+                .requires("fed", true)
+                .requires("has_wine", true)
+                .build(),
+            Goal::new("rich").requires("gold", 50).build(),
+        ];
+        let actions = vec![
+            Action::new("eat")
+                .requires("has_food", true)
+                .sets("fed", true)
+                .sets("has_food", false)
+                .build(),
+            Action::new("buy_food")
+                .requires("gold", 5)
+                .subtracts("gold", 5)
+                .sets("has_food", true)
+                .build(),
+            Action::new("buy_wine")
+                .sets("has_wine", true)
+                .cost(3.0)
+                .build(),
+            Action::new("work").adds("gold", 10).build(),
+            Action::new("dance").sets("happy", true).build(),
+        ];
+        (goals, actions)
+    }
+
+    /// Test goal similarity
+    /// Validates: Similarity is the share of requirement variables two goals have in common
+    /// Failure: Goals are compared on something other than their variables
+    #[test]
+    fn test_goal_similarity() {
+        let (goals, _) = domain();
+        assert_eq!(goal_similarity(&goals[0], &goals[0]), 1.0);
+        assert_eq!(goal_similarity(&goals[0], &goals[1]), 0.5);
+        assert_eq!(goal_similarity(&goals[0], &goals[2]), 0.0);
+    }
+
+    /// Test building clusters
+    /// Validates: Similar goals share a cluster, relevant actions follow
+    /// preconditions transitively, and the cheapest cost per variable is recorded
+    /// Failure: Clusters group unrelated goals or miss actions a plan needs
+    #[test]
+    fn test_cluster_goals() {
+        let (goals, actions) = domain();
+        let clusters = Planner::new().cluster_goals(&goals, &actions, 0.5);
+
+        assert_eq!(clusters.len(), 2);
+        let food = &clusters.clusters()[0];
+        assert_eq!(food.goals, ["eat_meal", "feast"]);
+        assert_eq!(food.keys.iter().collect::<Vec<_>>(), ["fed", "has_wine"]);
+        // "work" is needed to afford food, "dance" never helps
+        assert_eq!(
+            food.actions.iter().collect::<Vec<_>>(),
+            ["buy_food", "buy_wine", "eat", "work"]
+        );
+        assert_eq!(food.cheapest["has_wine"], 3.0);
+        assert_eq!(clusters.cluster_for("rich").unwrap().goals, ["rich"]);
+        assert!(clusters.cluster_for("unknown").is_none());
+
+        let single = Planner::new().cluster_goals(&goals, &actions, 0.0);
+        assert_eq!(single.len(), 1);
+
+        let text = clusters.to_string();
+        assert!(text.contains("Cluster 1: eat_meal, feast"));
+        assert!(text.contains("Actions: buy_food, buy_wine, eat, work"));
+    }
+
+    /// Test planning with clusters
+    /// Validates: Clustered planning finds the same plan, rebuilt clusters behave
+    /// alike, and unreachable goals fail without searching, also within a horizon
+    /// Failure: Warm starts change plans or prune needed actions
+    #[test]
+    fn test_plan_with_clusters() {
+        let (goals, actions) = domain();
+        let clusters = Planner::new().cluster_goals(&goals, &actions, 0.5);
+        let rebuilt = GoalClusters::new(clusters.clusters().to_vec());
+        assert_eq!(rebuilt, clusters);
+
+        let state = State::new()
+            .set("fed", false)
+            .set("gold", 0)
+            .set("has_food", false)
+            .build();
+        let expected = Planner::new()
+            .plan(state.clone(), &goals[1], &actions)
+            .unwrap();
+        let planner = Planner::with_config(PlannerConfig::new().goal_clusters(rebuilt));
+        let plan = planner.plan(state.clone(), &goals[1], &actions).unwrap();
+        assert_eq!(plan, expected);
+
+        let impossible = Goal::new("rich").requires("fame", 10).build();
+        let clusters =
+            Planner::new().cluster_goals(std::slice::from_ref(&impossible), &actions, 0.5);
+        let planner = Planner::with_config(PlannerConfig::new().goal_clusters(clusters));
+        let mut observer = CountingObserver::default();
+        let result = planner.plan_observed(state.clone(), &impossible, &actions, &mut observer);
+        assert!(matches!(result, Err(PlannerError::NoPlanFound)));
+        assert_eq!(observer.expansions, 0);
+        // Receding-horizon planning warm-starts from the same clusters
+        let result = planner.plan_horizon(3, &state, &impossible, &actions);
+        assert!(matches!(result, Err(PlannerError::NoPlanFound)));
+    }
+}
@@ -0,0 +1,168 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for planning over compiled domains
+
+    fn crafting_actions() -> Vec<Action> {
+        vec![
+            Action::new("go_to_forest")
+                .sets("location", "forest")
+                .build(),
+            Action::new("go_to_town")
+                .sets("location", "town")
+                .cost(2.0)
+                .build(),
+            Action::new("chop_wood")
+                .requires("location", "forest")
+                .adds("wood", 2)
+                .build(),
+            Action::new("sell_wood")
+                .requires("location", "town")
+                .requires("wood", 3)
+                .subtracts("wood", 3)
+                .adds("gold", 10)
+                .build(),
+            Action::new("buy_axe")
+                .requires_any([("gold", 10), ("has_coupon", 1)])
+                .requires_absent("axe")
+                .sets("axe", "iron")
+                .build(),
+        ]
+    }
+
+    /// Test compiled planning against the regular planner
+    /// Validates: Plans found from a compiled domain cost the same as regular plans,
+    /// across strings, numbers, alternative and absence preconditions
+    /// Failure: The compiled form changes the meaning of actions
+    #[test]
+    fn test_matches_regular_planner() {
+        let actions = crafting_actions();
+        let planner = Planner::new();
+        let domain = planner.compile(&actions).unwrap();
+        let initial_state = State::new()
+            .set("location", "town")
+            .set("wood", 0)
+            .set("gold", 0)
+            .build();
+
+        let goals = [
+            Goal::new("rich").requires("gold", 20).build(),
+            Goal::new("armed").requires("axe", "iron").build(),
+            Goal::new("in_forest")
+                .requires("location", "forest")
+                .build(),
+        ];
+        for goal in &goals {
+            let expected = planner.plan(initial_state.clone(), goal, &actions).unwrap();
+            let plan = planner
+                .plan_compiled(&domain, &initial_state, goal)
+                .unwrap();
+            assert_eq!(plan.cost, expected.cost, "goal {}", goal.name);
+            let mut state = initial_state.clone();
+            for action in &plan.actions {
+                assert!(action.can_execute(&state));
+                state = action.apply_effect(&state);
+            }
+            assert!(goal.is_satisfied(&state));
+        }
+    }
+
+    /// Test variables and strings the domain has never seen
+    /// Validates: Goals and states may mention variables and values outside the
+    /// compiled actions, which only the initial state can satisfy
+    /// Failure: Unknown names are mixed up with compiled ones
+    #[test]
+    fn test_unknown_keys_and_values() {
+        let actions = crafting_actions();
+        let planner = Planner::new();
+        let domain = planner.compile(&actions).unwrap();
+        let initial_state = State::new()
+            .set("location", "town")
+            .set("weather", "sunny")
+            .build();
+
+        let sunny = Goal::new("sunny")
+            .requires("weather", "sunny")
+            .requires("location", "forest")
+            .build();
+        let plan = planner
+            .plan_compiled(&domain, &initial_state, &sunny)
+            .unwrap();
+        assert_eq!(plan.actions.len(), 1);
+
+        let rainy = Goal::new("rainy").requires("weather", "rainy").build();
+        let result = planner.plan_compiled(&domain, &initial_state, &rainy);
+        assert!(matches!(result, Err(PlannerError::NoPlanFound)));
+
+        let castle = Goal::new("castle").requires("location", "castle").build();
+        let result = planner.plan_compiled(&domain, initial_state, &castle);
+        assert!(matches!(result, Err(PlannerError::NoPlanFound)));
+    }
+
+    /// Test compiling with the planner's configuration
+    /// Validates: Excluded tags and mutex groups apply to compiled domains
+    /// Failure: Compiled plans ignore the configuration
+    #[test]
+    fn test_configuration_applies() {
+        let actions = vec![
+            Action::new("walk").sets("at_home", true).cost(3.0).build(),
+            Action::new("teleport")
+                .sets("at_home", true)
+                .tag("magic")
+                .build(),
+            Action::new("rest")
+                .requires("at_home", true)
+                .sets("resting", true)
+                .build(),
+        ];
+        let planner = Planner::with_config(PlannerConfig::new().exclude_tags(["magic"]));
+        let domain = planner.compile(&actions).unwrap();
+        assert_eq!(domain.actions().len(), 2);
+
+        let goal = Goal::new("rested").requires("resting", true).build();
+        let plan = planner
+            .plan_compiled(&domain, State::new().set("at_home", false).build(), &goal)
+            .unwrap();
+        assert_eq!(plan.cost, 4.0);
+
+        let planner = Planner::with_config(PlannerConfig::new().mutex_group(&["idle", "busy"]));
+        let actions = vec![Action::new("work").sets("busy", true).build()];
+        let domain = planner.compile(&actions).unwrap();
+        let goal = Goal::new("working")
+            .requires("busy", true)
+            .requires("idle", false)
+            .build();
+        let initial_state = State::new().set("idle", true).set("busy", false).build();
+        let plan = planner
+            .plan_compiled(&domain, initial_state, &goal)
+            .unwrap();
+        assert_eq!(plan.actions[0].effects.len(), 2);
+    }
+
+    /// Test actions the compiled form cannot represent
+    /// Validates: Expression preconditions and dynamic effects are reported
+    /// Failure: Such actions are silently compiled with a different meaning
+    #[test]
+    fn test_unsupported_actions() {
+        let action = Action::new("buy")
+            .requires_expr("gold >= price")
+            .unwrap()
+            .build();
+        let error = Planner::new().compile(&[action]).unwrap_err();
+        assert_eq!(
+            error,
+            CompileError::Unsupported {
+                action: "buy".to_string(),
+                feature: "expression preconditions",
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "Action 'buy' cannot be compiled: it uses expression preconditions"
+        );
+
+        let action = Action::new("wait").takes_time(5).build();
+        assert!(Planner::new().compile(&[action]).is_err());
+    }
+}
@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+    use std::sync::{Arc, RwLock};
+    use std::thread;
+
+    // Tests for sharing planning types between threads
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    /// Test thread-safety bounds of public types
+    /// Validates: Core and auxiliary types can be shared between threads
+    /// Failure: A type gained a non-thread-safe field such as an `Rc` or `Cell`
+    #[test]
+    fn test_types_are_send_sync() {
+        assert_send_sync::<State>();
+        assert_send_sync::<Goal>();
+        assert_send_sync::<Action>();
+        assert_send_sync::<Planner>();
+        assert_send_sync::<PlannerConfig>();
+        assert_send_sync::<Plan>();
+        assert_send_sync::<PlannerError>();
+        assert_send_sync::<PlanExecutor>();
+        assert_send_sync::<GoalClusters>();
+        assert_send_sync::<PlannerProfile>();
+        assert_send_sync::<BeliefState>();
+        assert_send_sync::<KeyOwnership>();
+        assert_send_sync::<DecisionStats>();
+        assert_send_sync::<CostCalibrator>();
+        assert_send_sync::<HeuristicReport>();
+    }
+
+    /// Test planning from several threads with shared actions
+    /// Validates: Threads sharing one planner and action set through `Arc` find
+    /// the same plans as a single thread, including actions with dynamic effects
+    /// Failure: Shared planning types race or need copying per thread
+    #[test]
+    fn test_shared_planning_across_threads() {
+        let actions: Arc<[Action]> = Arc::from(vec![
+            Action::new("work").adds("gold", 5).build(),
+            Action::new("invest")
+                .requires("gold", 5)
+                .effect_expr("gold = gold * 2")
+                .unwrap()
+                .build(),
+        ]);
+        let planner = Arc::new(Planner::new());
+        let state = Arc::new(RwLock::new(State::new().set("gold", 0).build()));
+        let expected = planner
+            .plan(
+                state.read().unwrap().clone(),
+                &Goal::new("rich").requires("gold", 20).build(),
+                &actions,
+            )
+            .unwrap();
+
+        let workers: Vec<_> = (0..8)
+            .map(|_| {
+                let (actions, planner, state) = (actions.clone(), planner.clone(), state.clone());
+                thread::spawn(move || {
+                    let goal = Goal::new("rich").requires("gold", 20).build();
+                    let start = state.read().unwrap().clone();
+                    planner.plan(start, &goal, &actions).unwrap()
+                })
+            })
+            .collect();
+        for worker in workers {
+            assert_eq!(worker.join().unwrap(), expected);
+        }
+    }
+}
@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for conformant planning
+
+    /// Test a plan that works regardless of an unknown door state
+    /// Validates: Conformant plans include sensing-free actions that cover every world
+    /// Failure: Planner relies on a value that is not known
+    #[test]
+    fn test_conformant_plan_covers_unknown_value() {
+        let planner = Planner::new();
+        let initial_state = State::new().set("inside", false).build();
+        let goal = Goal::new("enter").requires("inside", true).build();
+        let actions = vec![
+            Action::new("walk_in")
+                .requires("door_open", true)
+                .sets("inside", true)
+                .build(),
+            Action::new("open_door").sets("door_open", true).build(),
+        ];
+
+        let plan = planner
+            .plan_conformant(&initial_state, &["door_open"], &goal, &actions)
+            .unwrap();
+        let names: Vec<&str> = plan.actions.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["open_door", "walk_in"]);
+
+        for door_open in [false, true] {
+            let mut state = initial_state.clone();
+            state.set("door_open", door_open);
+            for action in &plan.actions {
+                assert!(action.can_execute(&state));
+                state = action.apply_effect(&state);
+            }
+            assert!(goal.is_satisfied(&state));
+        }
+    }
+
+    /// Test that no conformant plan exists when the outcome depends on the unknown value
+    /// Validates: NoConformantPlan is returned instead of a plan valid for only some worlds
+    /// Failure: Planner returns a plan that fails in some possible world
+    #[test]
+    fn test_no_conformant_plan() {
+        let planner = Planner::new();
+        let initial_state = State::new().set("inside", false).build();
+        let goal = Goal::new("enter").requires("inside", true).build();
+        let actions = vec![Action::new("walk_in")
+            .requires("door_open", true)
+            .sets("inside", true)
+            .build()];
+
+        // A regular plan exists when the door is known to be open
+        let mut known = initial_state.clone();
+        known.set("door_open", true);
+        assert!(planner.plan(known, &goal, &actions).is_ok());
+
+        let result = planner.plan_conformant(initial_state, &["door_open"], &goal, &actions);
+        assert_eq!(result, Err(PlannerError::NoConformantPlan));
+    }
+
+    /// Test the bound on unknown variables
+    /// Validates: Too many unknowns are rejected rather than exploding the belief state
+    /// Failure: Planner attempts an exponential search
+    #[test]
+    fn test_conformant_unknown_limit() {
+        let planner = Planner::new();
+        let keys: Vec<String> = (0..=goap::conformant::MAX_UNKNOWN_VARS)
+            .map(|i| format!("flag_{i}"))
+            .collect();
+        let unknown: Vec<&str> = keys.iter().map(String::as_str).collect();
+        let goal = Goal::new("done").requires("done", true).build();
+        let actions = vec![Action::new("finish").sets("done", true).build()];
+
+        let result = planner.plan_conformant(State::empty(), &unknown, &goal, &actions);
+        assert_eq!(result, Err(PlannerError::NoConformantPlan));
+        assert!(planner
+            .plan_conformant(State::empty(), &unknown[..2], &goal, &actions)
+            .is_ok());
+    }
+}
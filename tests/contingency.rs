@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    fn door_domain() -> (State, Goal, Vec<Action>) {
+        let initial_state = State::new()
+            .set("door_open", false)
+            .set("lockpick", true)
+            .set("crowbar", true)
+            .build();
+        let goal = Goal::new("enter").requires("inside", true).build();
+        let actions = vec![
+            Action::new("pick_lock")
+                .requires("lockpick", true)
+                .sets("door_open", true)
+                .fails_with("lockpick", false)
+                .build(),
+            Action::new("pry_door")
+                .requires("crowbar", true)
+                .sets("door_open", true)
+                .fails_with("crowbar", false)
+                .cost(3.0)
+                .build(),
+            Action::new("kick_door")
+                .sets("door_open", true)
+                .cost(10.0)
+                .build(),
+            Action::new("walk_in")
+                .requires("door_open", true)
+                .sets("inside", true)
+                .build(),
+        ];
+        (initial_state, goal, actions)
+    }
+
+    fn names(actions: &[Action]) -> Vec<&str> {
+        actions.iter().map(|action| action.name.as_str()).collect()
+    }
+
+    // Tests for contingency plans
+
+    /// Test branches attached to risky steps
+    /// Validates: Fallbacks are planned from the predicted failure state, nested up to the depth
+    /// Failure: Branches are missing, misplaced or planned from the wrong state
+    #[test]
+    fn test_plan_contingent_branches() {
+        let (initial_state, goal, actions) = door_domain();
+        let plan = Planner::new()
+            .plan_contingent(initial_state, &goal, &actions, 2)
+            .unwrap();
+
+        assert_eq!(names(&plan.plan.actions), ["pick_lock", "walk_in"]);
+        assert_eq!(plan.branches.len(), 1);
+        let branch = plan.branch_at(0).unwrap();
+        assert_eq!(branch.action, "pick_lock");
+        assert_eq!(branch.failure_state.get::<bool>("lockpick"), Some(false));
+        assert_eq!(branch.failure_state.get::<bool>("door_open"), Some(false));
+        assert!(plan.branch_at(1).is_none());
+
+        let fallback = branch.fallback.as_ref().unwrap();
+        assert_eq!(names(&fallback.plan.actions), ["pry_door", "walk_in"]);
+        let nested = fallback.branch_at(0).unwrap().fallback.as_ref().unwrap();
+        assert_eq!(names(&nested.plan.actions), ["kick_door", "walk_in"]);
+        assert!(nested.branches.is_empty());
+    }
+
+    /// Test limiting the branch depth
+    /// Validates: Depth 0 plans no branches and depth 1 leaves fallbacks without branches
+    /// Failure: The depth limit is ignored
+    #[test]
+    fn test_plan_contingent_depth() {
+        let (initial_state, goal, actions) = door_domain();
+        let planner = Planner::new();
+
+        let flat = planner
+            .plan_contingent(&initial_state, &goal, &actions, 0)
+            .unwrap();
+        assert!(flat.branches.is_empty());
+
+        let shallow = planner
+            .plan_contingent(initial_state, &goal, &actions, 1)
+            .unwrap();
+        let fallback = shallow.branches[0].fallback.as_ref().unwrap();
+        assert!(fallback.branches.is_empty());
+    }
+
+    /// Test executing a contingency plan
+    /// Validates: The executor follows the primary plan and switches to fallbacks on failure
+    /// Failure: Failures are not handled or steps are skipped
+    #[test]
+    fn test_contingency_executor() {
+        let (initial_state, goal, actions) = door_domain();
+        let plan = Planner::new()
+            .plan_contingent(initial_state, &goal, &actions, 1)
+            .unwrap();
+
+        let mut executor = plan.executor();
+        assert_eq!(executor.current().unwrap().name, "pick_lock");
+        assert!(executor.failed());
+        assert_eq!(names(executor.remaining()), ["pry_door", "walk_in"]);
+
+        // The fallback has no branches of its own at depth 1
+        assert!(!executor.failed());
+        assert_eq!(executor.current().unwrap().name, "pry_door");
+
+        executor.succeeded();
+        executor.succeeded();
+        assert!(executor.is_finished());
+        assert!(executor.current().is_none());
+    }
+
+    /// Test a failure the goal cannot recover from
+    /// Validates: The branch is kept without a fallback
+    /// Failure: Planning fails or invents a fallback
+    #[test]
+    fn test_plan_contingent_unrecoverable_failure() {
+        let initial_state = State::new().set("has_key", true).build();
+        let goal = Goal::new("open").requires("open", true).build();
+        let actions = vec![
+            Action::new("use_key")
+                .requires("has_key", true)
+                .sets("open", true)
+                .fails_with("has_key", false)
+                .build(),
+        ];
+
+        let plan = Planner::new()
+            .plan_contingent(initial_state, &goal, &actions, 1)
+            .unwrap();
+        assert_eq!(plan.branches.len(), 1);
+        assert!(plan.branches[0].fallback.is_none());
+        assert!(!plan.executor().failed());
+    }
+}
@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for reachable state sampling and action coverage
+
+    fn camp_actions() -> Vec<Action> {
+        vec![
+            Action::new("chop").sets("has_wood", true).build(),
+            Action::new("light_fire")
+                .requires("has_wood", true)
+                .sets("has_wood", false)
+                .sets("fire", true)
+                .build(),
+            Action::new("cook")
+                .requires("fire", true)
+                .sets("fed", true)
+                .build(),
+            Action::new("teleport")
+                .requires("has_scroll", true)
+                .sets("at_town", true)
+                .build(),
+        ]
+    }
+
+    /// Test sampled states are distinct, reachable and bounded
+    /// Validates: The sample starts at the initial state, has no duplicates, stays
+    /// within the requested count and only contains states reachable by walking
+    /// Failure: Corpora contain duplicates or unreachable states
+    #[test]
+    fn test_sample_reachable_states() {
+        let initial = State::new()
+            .set("has_wood", false)
+            .set("fire", false)
+            .build();
+        let actions = camp_actions();
+        let sample = sample_reachable_states(&initial, &actions, 50, 6);
+
+        assert_eq!(sample.states[0], initial);
+        assert!(sample.states.len() > 2 && sample.states.len() <= 50);
+        for (index, state) in sample.states.iter().enumerate() {
+            assert!(!sample.states[..index].contains(state));
+            assert!(state.get::<bool>("at_town").is_none());
+        }
+
+        let small = sample_reachable_states(&initial, &actions, 2, 6);
+        assert_eq!(small.states.len(), 2);
+        assert!(
+            sample_reachable_states(&initial, &actions, 0, 6)
+                .states
+                .is_empty()
+        );
+    }
+
+    /// Test coverage statistics
+    /// Validates: Every action is listed, actions that cannot run are reported as
+    /// never fired, and seeds make samples reproducible
+    /// Failure: Dead content goes unnoticed or samples vary between runs
+    #[test]
+    fn test_action_coverage() {
+        let initial = State::new()
+            .set("has_wood", false)
+            .set("fire", false)
+            .build();
+        let actions = camp_actions();
+        let sample = sample_reachable_states_seeded(&initial, &actions, 50, 6, 7);
+
+        assert_eq!(sample.coverage.fired.len(), 4);
+        assert_eq!(sample.coverage.never_fired(), vec!["teleport"]);
+        assert_eq!(sample.coverage.ratio(), 0.75);
+        assert_eq!(
+            sample.coverage.to_string(),
+            "3/4 actions fired; never fired: teleport"
+        );
+        assert_eq!(
+            sample,
+            sample_reachable_states_seeded(&initial, &actions, 50, 6, 7)
+        );
+    }
+}
@@ -0,0 +1,135 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for logging agent decisions
+
+    /// Test recording planning outcomes
+    /// Validates: plan_logged records found and failed plans with their details
+    /// and returns the planner's result unchanged
+    /// Failure: Telemetry misses planning failures or misreports plans
+    #[test]
+    fn test_plan_logged() {
+        let actions = vec![Action::new("walk_to_post").sets("at_post", true).build()];
+        let patrol = Goal::new("patrol").requires("at_post", true).build();
+        let planner = Planner::new();
+
+        let mut log: Vec<DecisionEvent> = Vec::new();
+        let plan = planner
+            .plan_logged("guard", State::empty(), &patrol, &actions, &mut log)
+            .unwrap();
+        assert_eq!(plan.actions.len(), 1);
+        let error = planner
+            .plan_logged("guard", State::empty(), &patrol, &[], &mut log)
+            .unwrap_err();
+        assert_eq!(error, PlannerError::NoPlanFound);
+
+        assert_eq!(
+            log,
+            vec![
+                DecisionEvent::PlanFound {
+                    agent: "guard".to_string(),
+                    goal: "patrol".to_string(),
+                    steps: 1,
+                    cost: 1.0,
+                    nodes_expanded: plan.stats.nodes_expanded,
+                },
+                DecisionEvent::PlanFailed {
+                    agent: "guard".to_string(),
+                    goal: "patrol".to_string(),
+                    error: "No plan found".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            log[1].to_string(),
+            r#"plan_failed agent="guard" goal="patrol" error="No plan found""#
+        );
+    }
+
+    /// Test aggregating decisions
+    /// Validates: Stats count every kind of decision per agent and goal or action
+    /// Failure: Counts are attributed to the wrong agent, goal or action
+    #[test]
+    fn test_decision_stats() {
+        let mut stats = DecisionStats::new();
+        let events = [
+            DecisionEvent::GoalSelected {
+                agent: "guard".to_string(),
+                goal: "patrol".to_string(),
+            },
+            DecisionEvent::PlanFailed {
+                agent: "guard".to_string(),
+                goal: "patrol".to_string(),
+                error: "No plan found".to_string(),
+            },
+            DecisionEvent::Replanned {
+                agent: "guard".to_string(),
+                goal: "patrol".to_string(),
+                reason: "door locked".to_string(),
+            },
+            DecisionEvent::ActionSucceeded {
+                agent: "guard".to_string(),
+                action: "open_door".to_string(),
+            },
+            DecisionEvent::ActionFailed {
+                agent: "guard".to_string(),
+                action: "open_door".to_string(),
+                reason: "locked".to_string(),
+            },
+            DecisionEvent::GoalSelected {
+                agent: "merchant".to_string(),
+                goal: "patrol".to_string(),
+            },
+        ];
+        for event in &events {
+            stats.record(event);
+        }
+
+        assert_eq!(
+            stats.goal("guard", "patrol"),
+            GoalDecisions {
+                selected: 1,
+                plans_found: 0,
+                plans_failed: 1,
+                replans: 1,
+            }
+        );
+        assert_eq!(stats.goal("merchant", "patrol").selected, 1);
+        assert_eq!(stats.goal("merchant", "patrol").failure_rate(), None);
+        assert_eq!(
+            stats.action("guard", "open_door"),
+            ActionDecisions {
+                succeeded: 1,
+                failed: 1,
+            }
+        );
+        assert_eq!(stats.goals().count(), 2);
+        assert_eq!(stats.actions().count(), 1);
+        assert_eq!(events[4].kind(), "action_failed");
+        assert_eq!(events[5].agent(), "merchant");
+    }
+
+    /// Test merging stats from several sessions
+    /// Validates: Merged counts are the sums of each session's counts
+    /// Failure: Aggregation across play sessions loses or double counts decisions
+    #[test]
+    fn test_merge_stats() {
+        let patrol = Goal::new("patrol").requires("at_post", true).build();
+        let actions = vec![Action::new("walk_to_post").sets("at_post", true).build()];
+        let planner = Planner::new();
+
+        let mut total = DecisionStats::new();
+        for session in 0..4 {
+            let mut stats = DecisionStats::new();
+            let available: &[Action] = if session == 0 { &[] } else { &actions };
+            let _ = planner.plan_logged("guard", State::empty(), &patrol, available, &mut stats);
+            total.merge(&stats);
+        }
+
+        let patrols = total.goal("guard", "patrol");
+        assert_eq!(patrols.plans_found, 3);
+        assert_eq!(patrols.plans_failed, 1);
+        assert_eq!(patrols.failure_rate(), Some(0.25));
+    }
+}
@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for dominance between states and dominance pruning
+
+    /// Test comparing states for dominance
+    /// Validates: Dominance needs the same variables, equal booleans and strings
+    /// and numbers at least as large
+    /// Failure: States are pruned in favour of states that cannot do as much
+    #[test]
+    fn test_dominates() {
+        let base = State::new()
+            .set("gold", 10)
+            .set("speed", 1.5)
+            .set("town", "north")
+            .build();
+        let richer = State::new()
+            .set("gold", 12)
+            .set("speed", 1.5)
+            .set("town", "north")
+            .build();
+        let elsewhere = State::new()
+            .set("gold", 12)
+            .set("speed", 1.5)
+            .set("town", "south")
+            .build();
+        let with_extra = State::new()
+            .set("gold", 12)
+            .set("speed", 1.5)
+            .set("town", "north")
+            .set("cursed", true)
+            .build();
+
+        assert!(dominates(&richer, &base));
+        assert!(dominates(&base, &base));
+        assert!(!dominates(&base, &richer));
+        assert!(!dominates(&elsewhere, &base));
+        assert!(!dominates(&with_extra, &base));
+        assert!(!dominates(&base, &with_extra));
+    }
+
+    /// Test pruning dominated states during search
+    /// Validates: Enabling dominance pruning prunes states, expands no more states
+    /// and still finds an optimal plan
+    /// Failure: Resource domains explore dominated states or lose optimal plans
+    #[test]
+    fn test_dominance_pruning() {
+        let actions = vec![
+            Action::new("pick_up_coin").adds("gold", 1).build(),
+            Action::new("find_purse").adds("gold", 5).build(),
+            Action::new("buy_sword")
+                .requires("gold", 10)
+                .subtracts("gold", 10)
+                .sets("has_sword", true)
+                .build(),
+        ];
+        let start = State::new().set("gold", 0).set("has_sword", false).build();
+        let goal = Goal::new("armed").requires("has_sword", true).build();
+
+        let plain = Planner::new().plan(start.clone(), &goal, &actions).unwrap();
+        let pruning = Planner::with_config(PlannerConfig::new().dominance_pruning(true))
+            .plan(start, &goal, &actions)
+            .unwrap();
+
+        assert_eq!(plain.stats.nodes_dominated, 0);
+        assert!(pruning.stats.nodes_dominated > 0);
+        assert!(pruning.stats.nodes_expanded <= plain.stats.nodes_expanded);
+        assert_eq!(pruning.cost, 3.0);
+        assert_eq!(pruning.cost, plain.cost);
+    }
+}
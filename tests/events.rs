@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+    use std::sync::mpsc;
+
+    // Tests for plan change events
+
+    /// Test events emitted while simulating a plan
+    /// Validates: Each changed key is reported once per step with old and new values
+    /// Failure: Game systems miss changes or receive unchanged keys
+    #[test]
+    fn test_simulate_with_events() {
+        let initial_state = State::new().set("wood", 0).set("has_axe", false).build();
+        let plan = Plan {
+            actions: vec![
+                Action::new("get_axe").sets("has_axe", true).build(),
+                Action::new("chop")
+                    .adds("wood", 2)
+                    .sets("has_axe", true)
+                    .build(),
+            ],
+            cost: 2.0,
+            ..Plan::default()
+        };
+
+        let mut events = Vec::new();
+        let final_state = plan.simulate_with_events(&initial_state, |event| events.push(event));
+
+        assert_eq!(
+            events,
+            vec![
+                PlanEvent::KeyChanged {
+                    key: "has_axe".to_string(),
+                    old: Some(StateVar::Bool(false)),
+                    new: Some(StateVar::Bool(true)),
+                    step: 0,
+                },
+                PlanEvent::KeyChanged {
+                    key: "wood".to_string(),
+                    old: Some(StateVar::I64(0)),
+                    new: Some(StateVar::I64(2)),
+                    step: 1,
+                },
+            ]
+        );
+        assert_eq!(final_state.get::<i64>("wood"), Some(2));
+    }
+
+    /// Test forwarding events through a channel
+    /// Validates: The callback can feed a channel consumed elsewhere
+    /// Failure: Events cannot leave the simulating thread
+    #[test]
+    fn test_simulate_with_events_channel() {
+        let initial_state = State::new().set("gold", 0).build();
+        let plan = Plan {
+            actions: vec![Action::new("work").adds("gold", 5).build(); 3],
+            cost: 3.0,
+            ..Plan::default()
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        plan.simulate_with_events(&initial_state, |event| sender.send(event).unwrap());
+        drop(sender);
+
+        let steps: Vec<usize> = receiver
+            .iter()
+            .map(|PlanEvent::KeyChanged { step, .. }| step)
+            .collect();
+        assert_eq!(steps, vec![0, 1, 2]);
+    }
+
+    /// Test diffing actual states
+    /// Validates: Added, removed and changed keys are reported sorted by key
+    /// Failure: key_changes misses additions or removals
+    #[test]
+    fn test_key_changes_between_states() {
+        let before = State::new().set("a", 1).set("b", true).build();
+        let after = State::new().set("a", 2).set("c", "new").build();
+
+        let keys: Vec<(String, bool, bool)> = key_changes(&before, &after, 4)
+            .into_iter()
+            .map(
+                |PlanEvent::KeyChanged {
+                     key,
+                     old,
+                     new,
+                     step,
+                 }| {
+                    assert_eq!(step, 4);
+                    (key, old.is_some(), new.is_some())
+                },
+            )
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                ("a".to_string(), true, true),
+                ("b".to_string(), true, false),
+                ("c".to_string(), false, true),
+            ]
+        );
+        assert!(key_changes(&before, &before, 0).is_empty());
+    }
+}
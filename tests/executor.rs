@@ -0,0 +1,263 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    fn shopping_plan() -> (State, Plan) {
+        let initial_state = State::new()
+            .set("gold", 0)
+            .set("shop_open", true)
+            .set("has_bread", false)
+            .build();
+        let goal = Goal::new("fed").requires("has_bread", true).build();
+        let actions = vec![
+            Action::new("work").adds("gold", 10).build(),
+            Action::new("buy_bread")
+                .requires("gold", 10)
+                .requires("shop_open", true)
+                .subtracts("gold", 10)
+                .sets("has_bread", true)
+                .build(),
+        ];
+        let plan = Planner::new()
+            .plan(initial_state.clone(), &goal, &actions)
+            .unwrap();
+        (initial_state, plan)
+    }
+
+    // Tests for executing plans with precondition checks
+
+    /// Test strict precondition checks
+    /// Validates: Every precondition is checked against the current state
+    /// Failure: Unreported changes let invalid actions through
+    #[test]
+    fn test_strict_checks_every_precondition() {
+        let (mut state, plan) = shopping_plan();
+        let mut executor = PlanExecutor::new(plan);
+
+        let work = executor.next_action(&state).unwrap().unwrap();
+        state = work.apply_effect(&state);
+        executor.succeeded();
+
+        state.set("shop_open", false);
+        match executor.next_action(&state) {
+            Err(ExecutionError::PreconditionFailed { step, action, keys }) => {
+                assert_eq!(step, 1);
+                assert_eq!(action, "buy_bread");
+                assert_eq!(keys, vec!["shop_open".to_string()]);
+            }
+            other => panic!("expected PreconditionFailed, got {other:?}"),
+        }
+    }
+
+    /// Test optimistic precondition checks
+    /// Validates: Only reported variables are checked
+    /// Failure: Optimistic mode checks unreported variables or misses reported ones
+    #[test]
+    fn test_optimistic_checks_reported_changes() {
+        let (mut state, plan) = shopping_plan();
+        let mut executor = PlanExecutor::new(plan).mode(PreconditionCheck::Optimistic);
+        executor.succeeded();
+
+        // Not reported: assumed to still hold the predicted value
+        state.set("shop_open", false);
+        assert!(executor.next_action(&state).unwrap().is_some());
+
+        executor.notify_changed("shop_open");
+        assert!(executor.next_action(&state).is_err());
+
+        // "gold" is still 0 because "work" never ran, but it was not reported
+        state.set("shop_open", true);
+        assert!(executor.next_action(&state).unwrap().is_some());
+    }
+
+    /// Test clearing changes with set effects
+    /// Validates: A variable set by an executed action is back to its predicted value
+    /// Failure: Optimistic mode keeps checking variables the plan has overwritten
+    #[test]
+    fn test_optimistic_set_effect_restores_prediction() {
+        let goal = Goal::new("ready").requires("ready", true).build();
+        let actions = vec![
+            Action::new("open_shop").sets("shop_open", true).build(),
+            Action::new("prepare")
+                .requires("shop_open", true)
+                .sets("ready", true)
+                .build(),
+        ];
+        let initial_state = State::new().set("shop_open", false).build();
+        let plan = Planner::new()
+            .plan(initial_state.clone(), &goal, &actions)
+            .unwrap();
+
+        let mut executor = PlanExecutor::new(plan).mode(PreconditionCheck::Optimistic);
+        let mut changed = initial_state.clone();
+        changed.set("ready", false);
+        executor.notify_state_changes(&initial_state, &changed);
+        executor.notify_changed("shop_open");
+        executor.succeeded();
+
+        // The stale state still says closed, but "open_shop" set the variable
+        assert!(executor.next_action(&initial_state).unwrap().is_some());
+    }
+
+    /// Test per-action overrides
+    /// Validates: Overrides take precedence over the executor's mode
+    /// Failure: Overrides are ignored
+    #[test]
+    fn test_override_mode() {
+        let (mut state, plan) = shopping_plan();
+        let mut executor = PlanExecutor::new(plan)
+            .mode(PreconditionCheck::Strict)
+            .override_mode("buy_bread", PreconditionCheck::Skip);
+        executor.succeeded();
+
+        state.set("shop_open", false);
+        assert_eq!(
+            executor.mode_for(&executor.plan().actions[1]),
+            PreconditionCheck::Skip
+        );
+        assert!(executor.next_action(&state).unwrap().is_some());
+
+        executor.succeeded();
+        assert!(executor.is_finished());
+        assert!(executor.next_action(&state).unwrap().is_none());
+    }
+
+    /// Test validating postconditions after execution
+    /// Validates: Executions breaking an action's postconditions are reported and
+    /// the executor still moves past the action
+    /// Failure: Authoring mistakes in effects go unnoticed at runtime
+    #[test]
+    fn test_completed_checks_postconditions() {
+        let store = Action::new("store_crate")
+            .adds("used_storage", 10)
+            .ensures_expr("used_storage <= storage_space")
+            .unwrap()
+            .build();
+        let plan = Plan {
+            cost: 2.0,
+            actions: vec![store.clone(), store],
+            ..Plan::default()
+        };
+        let mut executor = PlanExecutor::new(plan);
+
+        let state = State::new()
+            .set("used_storage", 90)
+            .set("storage_space", 100)
+            .build();
+        assert_eq!(executor.completed(&state), Ok(()));
+
+        let state = State::new()
+            .set("used_storage", 110)
+            .set("storage_space", 100)
+            .build();
+        let error = executor.completed(&state).unwrap_err();
+        assert_eq!(
+            error,
+            ExecutionError::PostconditionViolated {
+                step: 1,
+                action: "store_crate".to_string(),
+                conditions: vec!["used_storage <= storage_space".to_string()],
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "Postconditions of 'store_crate' at step 1 do not hold: used_storage <= storage_space"
+        );
+        assert!(executor.is_finished());
+    }
+
+    /// Test saving and restoring executor progress
+    /// Validates: A snapshot round-trips through its text form and resumes at the
+    /// saved step with the variables reported as changed
+    /// Failure: Loading a save forces a replan or loses optimistic-check state
+    #[test]
+    fn test_snapshot_restore() {
+        let (mut state, plan) = shopping_plan();
+        let actions = plan.actions.clone();
+        let mut executor = PlanExecutor::new(plan).mode(PreconditionCheck::Optimistic);
+        executor.succeeded();
+        executor.notify_changed("shop_open");
+
+        let snapshot = executor.snapshot();
+        assert_eq!(snapshot.step, 1);
+        assert_eq!(snapshot.changed, ["shop_open"]);
+        let text = snapshot.to_string();
+        assert_eq!(ExecutorSnapshot::parse(&text).unwrap(), snapshot);
+
+        let restored = PlanExecutor::restore(&snapshot, &actions)
+            .unwrap()
+            .mode(PreconditionCheck::Optimistic);
+        assert_eq!(restored.step(), 1);
+        assert_eq!(restored.plan(), executor.plan());
+        // The closed shop is still reported as changed after loading
+        state.set("shop_open", false);
+        assert!(restored.next_action(&state).is_err());
+    }
+
+    /// Test rejecting unusable snapshots
+    /// Validates: Malformed lines, unknown actions and out-of-range steps are reported
+    /// Failure: Corrupt saves resume with a wrong plan
+    #[test]
+    fn test_restore_errors() {
+        let (_, plan) = shopping_plan();
+        let actions = plan.actions.clone();
+
+        assert_eq!(
+            ExecutorSnapshot::parse("step 0\ncost x\n"),
+            Err(RestoreError::Malformed { line: 2 })
+        );
+        assert_eq!(
+            ExecutorSnapshot::parse("step 0\n\nwhatever\n"),
+            Err(RestoreError::Malformed { line: 3 })
+        );
+
+        let snapshot = ExecutorSnapshot::parse("step 0\naction fly_to_moon\n").unwrap();
+        assert_eq!(
+            PlanExecutor::restore(&snapshot, &actions).unwrap_err(),
+            RestoreError::UnknownAction("fly_to_moon".to_string())
+        );
+
+        let snapshot = ExecutorSnapshot {
+            actions: vec!["work".to_string()],
+            step: 2,
+            ..ExecutorSnapshot::default()
+        };
+        let error = PlanExecutor::restore(&snapshot, &actions).unwrap_err();
+        assert_eq!(error, RestoreError::StepOutOfRange { step: 2, len: 1 });
+        assert_eq!(
+            error.to_string(),
+            "Step 2 is beyond the end of a plan of 1 actions"
+        );
+    }
+
+    /// Test checking plan assumptions while executing
+    /// Validates: Only assumptions not yet changed by performed steps are checked
+    /// Failure: Expected changes made by the plan are reported as broken assumptions
+    #[test]
+    fn test_broken_assumptions() {
+        let goal = Goal::new("rich").requires("gold", 10).build();
+        let actions = vec![
+            Action::new("work")
+                .requires("has_job", true)
+                .adds("gold", 10)
+                .build(),
+        ];
+        let mut state = State::new().set("has_job", true).set("gold", 0).build();
+        let plan = Planner::new().plan(&state, &goal, &actions).unwrap();
+        let mut executor = PlanExecutor::new(plan);
+
+        state.set("has_job", false);
+        assert_eq!(executor.broken_assumptions(&state), ["has_job"]);
+        state.set("has_job", true);
+        assert!(executor.broken_assumptions(&state).is_empty());
+
+        state = executor
+            .next_action(&state)
+            .unwrap()
+            .unwrap()
+            .apply_effect(&state);
+        executor.succeeded();
+        assert_eq!(state.get::<i64>("gold"), Some(10));
+        assert!(executor.broken_assumptions(&state).is_empty());
+    }
+}
@@ -0,0 +1,87 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for explaining planning failures
+
+    /// Test explaining a goal no action can reach
+    /// Validates: The failure lists unreachable requirements, blocked actions and the closest state
+    /// Failure: Failures are reported without the reason the goal is out of reach
+    #[test]
+    fn test_explain_unreachable_goal() {
+        let actions = vec![
+            Action::new("chop").sets("has_wood", true).build(),
+            Action::new("mine")
+                .requires("has_pickaxe", true)
+                .sets("has_ore", true)
+                .build(),
+        ];
+        let goal = Goal::new("craft")
+            .requires("has_wood", true)
+            .requires("has_ore", true)
+            .requires("has_hammer", true)
+            .build();
+        let state = State::new()
+            .set("has_wood", false)
+            .set("has_ore", false)
+            .set("has_pickaxe", false)
+            .build();
+
+        let failure = Planner::new()
+            .plan_diagnosed(&state, &goal, &actions)
+            .unwrap_err();
+        assert_eq!(failure.error, PlannerError::NoPlanFound);
+        let unreachable: Vec<&str> = failure.unreachable.iter().map(|c| c.key.as_str()).collect();
+        assert_eq!(unreachable, ["has_hammer", "has_ore"]);
+        assert!(failure.unreachable[1].has_producer);
+        assert_eq!(failure.deadlocks.len(), 1);
+        assert_eq!(failure.deadlocks[0].action, "mine");
+        assert_eq!(failure.closest.actions.len(), 1);
+        assert_eq!(failure.closest_state.get::<bool>("has_wood"), Some(true));
+        assert_eq!(failure.unmet.len(), 2);
+
+        assert_eq!(
+            failure.to_string(),
+            "No plan found\n\
+             Unreachable goal requirements:\n  \
+             - has_hammer: requires true (no action produces this variable)\n  \
+             - has_ore: requires true (no action produces a satisfying value)\n\
+             Action 'mine' can never run:\n  \
+             - requires has_pickaxe: true (no action produces this variable)\n\
+             Closest state reached after 1 steps misses:\n  \
+             - has_hammer: requires true, found nothing\n  \
+             - has_ore: requires true, found false"
+        );
+    }
+
+    /// Test diagnosed planning for reachable goals and interrupted searches
+    /// Validates: Reachable goals plan normally and budget errors keep their partial progress
+    /// Failure: Diagnosis changes successful plans or drops the search error
+    #[test]
+    fn test_diagnosed_plan_and_budget() {
+        let actions = vec![Action::new("work").adds("gold", 1).build()];
+        let state = State::new().set("gold", 0).build();
+
+        let small = Goal::new("some").requires("gold", 2).build();
+        let plan = Planner::new()
+            .plan_diagnosed(&state, &small, &actions)
+            .unwrap();
+        assert_eq!(plan.actions.len(), 2);
+
+        let rich = Goal::new("rich")
+            .requires("gold", 100)
+            .requires_not("cursed", true)
+            .build();
+        let cursed = State::new().set("gold", 0).set("cursed", true).build();
+        let failure = Planner::with_config(PlannerConfig::new().max_nodes_expanded(5))
+            .plan_diagnosed(&cursed, &rich, &actions)
+            .unwrap_err();
+        assert!(matches!(failure.error, PlannerError::BudgetExceeded { .. }));
+        assert!(failure.unreachable.is_empty());
+        assert_eq!(failure.closest_state.get::<i64>("gold"), Some(5));
+        assert_eq!(
+            failure.unmet[1].to_string(),
+            "cursed: requires != true, found true"
+        );
+    }
+}
@@ -0,0 +1,274 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    fn market_state() -> State {
+        State::new()
+            .set("gold", 100)
+            .set("price", 15)
+            .set("quantity", 4)
+            .set("tax_rate", 0.5)
+            .set("is_open", true)
+            .build()
+    }
+
+    // Tests for expression parsing and evaluation
+
+    /// Test arithmetic operator precedence and parentheses
+    /// Validates: Multiplication binds tighter than addition and parentheses override it
+    /// Failure: Parser builds the wrong expression tree
+    #[test]
+    fn test_expr_precedence() {
+        let state = market_state();
+        assert_eq!(
+            Expr::parse("price * quantity + 5").unwrap().evaluate(&state),
+            Ok(65.0)
+        );
+        assert_eq!(
+            Expr::parse("price * (quantity + 5)")
+                .unwrap()
+                .evaluate(&state),
+            Ok(135.0)
+        );
+        assert_eq!(Expr::parse("-price + 20").unwrap().evaluate(&state), Ok(5.0));
+        assert_eq!(
+            Expr::parse("gold * tax_rate").unwrap().evaluate(&state),
+            Ok(50.0)
+        );
+    }
+
+    /// Test expression display round-trips through the parser
+    /// Validates: Display output keeps required parentheses and can be parsed again
+    /// Failure: Expressions lose meaning when printed
+    #[test]
+    fn test_expr_display_round_trip() {
+        for source in ["price * (quantity + 5)", "gold - (price - 3)", "a / b * c"] {
+            let expr = Expr::parse(source).unwrap();
+            assert_eq!(expr.to_string(), source);
+            assert_eq!(Expr::parse(&expr.to_string()).unwrap(), expr);
+        }
+    }
+
+    /// Test expression evaluation errors
+    /// Validates: Missing, non-numeric variables and division by zero are reported
+    /// Failure: Evaluation silently produces wrong numbers
+    #[test]
+    fn test_expr_evaluation_errors() {
+        let state = market_state();
+        assert_eq!(
+            Expr::parse("missing + 1").unwrap().evaluate(&state),
+            Err(ExprError::UnknownVariable("missing".to_string()))
+        );
+        assert_eq!(
+            Expr::parse("is_open * 2").unwrap().evaluate(&state),
+            Err(ExprError::NonNumericVariable("is_open".to_string()))
+        );
+        assert_eq!(
+            Expr::parse("gold / 0").unwrap().evaluate(&state),
+            Err(ExprError::DivisionByZero)
+        );
+    }
+
+    /// Test parse errors
+    /// Validates: Malformed input returns descriptive errors instead of panicking
+    /// Failure: Parser accepts invalid expressions or panics
+    #[test]
+    fn test_expr_parse_errors() {
+        assert_eq!(Expr::parse("gold +"), Err(ExprError::UnexpectedEnd));
+        assert_eq!(
+            Expr::parse("gold $ 2"),
+            Err(ExprError::UnexpectedChar {
+                position: 5,
+                found: '$'
+            })
+        );
+        assert!(Expr::parse("(gold + 1").is_err());
+        assert!(Comparison::parse("gold + 1").is_err());
+        assert!(Assignment::parse("5 = gold").is_err());
+    }
+
+    /// Test the nesting limit
+    /// Validates: Deeply nested parentheses, signs and operator chains are rejected
+    /// with an error instead of overflowing the stack
+    /// Failure: Untrusted expressions from data files can abort the process
+    #[test]
+    fn test_expr_nesting_limit() {
+        use goap::expr::MAX_NESTING;
+
+        let parens = format!("{}1{}", "(".repeat(100_000), ")".repeat(100_000));
+        assert_eq!(
+            Expr::parse(&parens),
+            Err(ExprError::TooDeeplyNested {
+                position: MAX_NESTING
+            })
+        );
+        let signs = format!("{}1", "-".repeat(100_000));
+        assert!(matches!(
+            Expr::parse(&signs),
+            Err(ExprError::TooDeeplyNested { .. })
+        ));
+        let chain = vec!["1"; 100_000].join(" + ");
+        assert!(matches!(
+            Assignment::parse(&format!("gold = {chain}")),
+            Err(ExprError::TooDeeplyNested { .. })
+        ));
+
+        let nested = format!("{}1{}", "(".repeat(MAX_NESTING), ")".repeat(MAX_NESTING));
+        assert_eq!(Expr::parse(&nested), Ok(Expr::Number(1.0)));
+    }
+
+    // Tests for comparisons and assignments
+
+    /// Test comparison evaluation
+    /// Validates: All comparison operators evaluate against the state
+    /// Failure: Comparison operators are mapped incorrectly
+    #[test]
+    fn test_comparison_operators() {
+        let state = market_state();
+        assert!(Comparison::parse("gold >= price * quantity").unwrap().holds(&state));
+        assert!(!Comparison::parse("gold < price * quantity").unwrap().holds(&state));
+        assert!(Comparison::parse("quantity == 4").unwrap().holds(&state));
+        assert!(Comparison::parse("quantity != 5").unwrap().holds(&state));
+        assert!(Comparison::parse("price > 10").unwrap().holds(&state));
+        assert!(Comparison::parse("price <= 15").unwrap().holds(&state));
+        // Comparisons that cannot be evaluated do not hold
+        assert!(!Comparison::parse("missing >= 0").unwrap().holds(&state));
+    }
+
+    /// Test assignment conversion to state operations
+    /// Validates: Assignments produce operations in the target variable's representation
+    /// Failure: Fixed-point values are not scaled or integers are not rounded
+    #[test]
+    fn test_assignment_operations() {
+        let state = market_state();
+        assert_eq!(
+            Assignment::parse("gold -= price * quantity")
+                .unwrap()
+                .to_operation(&state),
+            Ok(StateOperation::Subtract(60))
+        );
+        assert_eq!(
+            Assignment::parse("tax_rate += 0.25")
+                .unwrap()
+                .to_operation(&state),
+            Ok(StateOperation::Add(250))
+        );
+        assert_eq!(
+            Assignment::parse("total = price * quantity")
+                .unwrap()
+                .to_operation(&state),
+            Ok(StateOperation::Set(StateVar::I64(60)))
+        );
+    }
+
+    /// Test assignments whose value cannot be stored
+    /// Validates: Infinite and out-of-range results are errors rather than saturated
+    /// or zeroed values
+    /// Failure: Effects silently write i64::MAX or zero into the state
+    #[test]
+    fn test_assignment_invalid_values() {
+        let state = market_state();
+        let huge = format!("1{}", "0".repeat(400));
+        assert_eq!(
+            Assignment::parse(&format!("gold = {huge}"))
+                .unwrap()
+                .to_operation(&state),
+            Err(ExprError::InvalidValue {
+                key: "gold".to_string(),
+                error: ConversionError::NotFinite
+            })
+        );
+        assert!(matches!(
+            Assignment::parse(&format!("gold += {huge} - {huge}"))
+                .unwrap()
+                .to_operation(&state),
+            Err(ExprError::InvalidValue {
+                error: ConversionError::NotFinite,
+                ..
+            })
+        ));
+        assert!(matches!(
+            Assignment::parse("gold -= 100000000000000000000")
+                .unwrap()
+                .to_operation(&state),
+            Err(ExprError::InvalidValue {
+                error: ConversionError::OutOfRange { target: "I64", .. },
+                ..
+            })
+        ));
+        assert!(matches!(
+            Assignment::parse("tax_rate = 100000000000000000")
+                .unwrap()
+                .to_operation(&state),
+            Err(ExprError::InvalidValue {
+                error: ConversionError::OutOfRange { target: "F64", .. },
+                ..
+            })
+        ));
+    }
+
+    // Tests for expression preconditions and effects on actions
+
+    /// Test expression-based preconditions and effects on an action
+    /// Validates: requires_expr and effect_expr are honored by can_execute and apply_effect
+    /// Failure: Expressions are not integrated into action execution
+    #[test]
+    fn test_action_expression_preconditions_and_effects() {
+        let buy = Action::new("buy")
+            .requires_expr("gold >= price * quantity")
+            .unwrap()
+            .effect_expr("gold -= price * quantity")
+            .unwrap()
+            .effect_expr("inventory += quantity")
+            .unwrap()
+            .build();
+
+        let state = market_state();
+        assert!(buy.can_execute(&state));
+        let after = buy.apply_effect(&state);
+        assert_eq!(after.get::<i64>("gold"), Some(40));
+        assert_eq!(after.get::<i64>("inventory"), None); // Add to a missing key is a no-op
+
+        let mut poor = market_state();
+        poor.set("gold", 10);
+        assert!(!buy.can_execute(&poor));
+
+        let display = buy.to_string();
+        assert!(display.contains("gold >= price * quantity"));
+        assert!(display.contains("gold -= price * quantity"));
+    }
+
+    /// Test planning with expression-based actions
+    /// Validates: A single data-driven action replaces one action per price point
+    /// Failure: Planner doesn't respect expression preconditions or effects
+    #[test]
+    fn test_planner_with_expressions() {
+        let planner = Planner::new();
+        let initial_state = State::new()
+            .set("gold", 0)
+            .set("price", 30)
+            .set("potions", 0)
+            .build();
+        let goal = Goal::new("stock_up").requires("potions", 2).build();
+
+        let work = Action::new("work").cost(1.0).adds("gold", 25).build();
+        let buy = Action::new("buy_potion")
+            .cost(1.0)
+            .requires_expr("gold >= price")
+            .unwrap()
+            .effect_expr("gold -= price")
+            .unwrap()
+            .adds("potions", 1)
+            .build();
+
+        let plan = planner.plan(initial_state, &goal, &[work, buy]).unwrap();
+        let buys = plan
+            .actions
+            .iter()
+            .filter(|a| a.name == "buy_potion")
+            .count();
+        let works = plan.actions.iter().filter(|a| a.name == "work").count();
+        assert_eq!(buys, 2);
+        assert!(works >= 3); // 60 gold needed at 25 gold per shift
+    }
+}
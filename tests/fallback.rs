@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for fallback behaviours
+
+    fn hungry_agent() -> (Vec<Goal>, Vec<Action>) {
+        let goals = vec![
+            Goal::new("eat").requires("fed", true).priority(3).build(),
+            Goal::new("sleep")
+                .requires("rested", true)
+                .priority(1)
+                .build(),
+        ];
+        let actions = vec![
+            Action::new("eat_bread")
+                .requires("has_bread", true)
+                .sets("fed", true)
+                .build(),
+            Action::new("nap")
+                .requires("has_bed", true)
+                .sets("rested", true)
+                .build(),
+        ];
+        (goals, actions)
+    }
+
+    /// Test choosing the highest-priority achievable goal
+    /// Validates: Goals are tried by priority, unachievable or satisfied goals
+    /// are skipped, and fallback is not used when a real goal can be planned
+    /// Failure: Agents idle although they could pursue a goal
+    #[test]
+    fn test_prefers_real_goals() {
+        let (goals, actions) = hungry_agent();
+        let planner = Planner::new();
+        let fallback = Fallback::idle(1.0);
+
+        let state = State::new()
+            .set("has_bread", false)
+            .set("has_bed", true)
+            .build();
+        let choice = planner
+            .plan_or_fallback(state, &goals, &actions, &fallback)
+            .unwrap();
+        assert_eq!(choice.goal, "sleep");
+        assert!(!choice.fallback);
+
+        let state = State::new()
+            .set("has_bread", true)
+            .set("has_bed", true)
+            .build();
+        let choice = planner
+            .plan_or_fallback(state, &goals, &actions, &fallback)
+            .unwrap();
+        assert_eq!(choice.goal, "eat");
+        assert_eq!(choice.plan.actions[0].name, "eat_bread");
+    }
+
+    /// Test falling back when no goal can be planned
+    /// Validates: The first fallback behaviour is chosen, again after its effects
+    /// were applied, and its action has the configured cost
+    /// Failure: Agents get an error every tick instead of something to do
+    #[test]
+    fn test_falls_back() {
+        let (goals, actions) = hungry_agent();
+        let planner = Planner::new();
+        let fallback = Fallback::idle(0.25).wander(2.0);
+        assert_eq!(fallback.goals().len(), 2);
+        assert_eq!(fallback.actions()[1].name, "wander");
+
+        let state = State::new().set("has_bread", false).build();
+        let choice = planner
+            .plan_or_fallback(state.clone(), &goals, &actions, &fallback)
+            .unwrap();
+        assert!(choice.fallback);
+        assert_eq!(choice.goal, "idle");
+        assert_eq!(choice.plan.cost, 0.25);
+
+        let idled = choice.plan.actions[0].apply_effect(&state);
+        assert_eq!(idled.get::<bool>(IDLE_KEY), Some(true));
+        let choice = planner
+            .plan_or_fallback(idled, &goals, &actions, &fallback)
+            .unwrap();
+        assert_eq!(choice.plan.actions.len(), 1);
+    }
+
+    /// Test custom behaviours and empty fallbacks
+    /// Validates: Custom fallback goals may use the agent's actions, and without
+    /// fallbacks the first real goal's error is returned
+    /// Failure: Custom behaviours cannot be planned or errors are lost
+    #[test]
+    fn test_custom_and_empty_fallback() {
+        let (goals, mut actions) = hungry_agent();
+        actions.push(Action::new("walk_home").sets("at_home", true).build());
+        let fallback = Fallback::new().behavior(
+            Goal::new("go_home")
+                .requires("at_home", true)
+                .requires("sitting", true)
+                .build(),
+            Action::new("sit_down")
+                .requires("at_home", true)
+                .sets("sitting", true)
+                .build(),
+        );
+        let planner = Planner::new();
+        let state = State::empty();
+
+        let choice = planner
+            .plan_or_fallback(state.clone(), &goals, &actions, &fallback)
+            .unwrap();
+        assert_eq!(choice.goal, "go_home");
+        assert_eq!(choice.plan.actions.len(), 2);
+
+        let error = planner
+            .plan_or_fallback(state, &goals, &actions, &Fallback::new())
+            .unwrap_err();
+        assert_eq!(error, PlannerError::NoPlanFound);
+    }
+}
@@ -0,0 +1,147 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// Counts the allocations made on the current thread.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[cfg(test)]
+mod tests {
+    use super::ALLOCATIONS;
+    use goap::fixed::{
+        FixedAction, FixedCondition, FixedOp, FixedPlanError, FixedSearch, FixedState,
+    };
+    use goap::prelude::*;
+
+    // Tests for allocation-free planning over fixed-size states
+
+    const GOLD: usize = 0;
+    const HAS_PICK: usize = 1;
+    const ORE: usize = 2;
+    const HAS_SWORD: usize = 3;
+
+    static ACTIONS: [FixedAction; 4] = [
+        FixedAction::new("work").effects(&[FixedOp::Add(GOLD, 5)]),
+        FixedAction::new("buy_pick")
+            .requires(&[FixedCondition::AtLeast(GOLD, 10)])
+            .effects(&[FixedOp::Add(GOLD, -10), FixedOp::Set(HAS_PICK, 1)]),
+        FixedAction::new("mine")
+            .cost(2.0)
+            .requires(&[FixedCondition::Equals(HAS_PICK, 1)])
+            .effects(&[FixedOp::Add(ORE, 1)]),
+        FixedAction::new("forge")
+            .requires(&[FixedCondition::AtLeast(ORE, 2)])
+            .effects(&[FixedOp::Add(ORE, -2), FixedOp::Set(HAS_SWORD, 1)]),
+    ];
+
+    static GOAL: [FixedCondition; 1] = [FixedCondition::Equals(HAS_SWORD, 1)];
+
+    /// Test planning over fixed states
+    /// Validates: The search finds the same cheapest plan as the regular planner
+    /// Failure: Fixed states change the meaning of the domain
+    #[test]
+    fn test_matches_regular_planner() {
+        let mut search = FixedSearch::<4, 256>::new();
+        let plan = search
+            .plan(FixedState::new([0, 0, 0, 0]), &GOAL, &ACTIONS)
+            .unwrap();
+        let names: Vec<&str> = plan.steps.iter().map(|&i| ACTIONS[i].name).collect();
+        assert_eq!(names, ["work", "work", "buy_pick", "mine", "mine", "forge"]);
+        assert_eq!(plan.cost, 8.0);
+
+        let actions = vec![
+            Action::new("work").adds("gold", 5).build(),
+            Action::new("buy_pick")
+                .requires("gold", 10)
+                .subtracts("gold", 10)
+                .sets("has_pick", true)
+                .build(),
+            Action::new("mine")
+                .cost(2.0)
+                .requires("has_pick", true)
+                .adds("ore", 1)
+                .build(),
+            Action::new("forge")
+                .requires("ore", 2)
+                .subtracts("ore", 2)
+                .sets("has_sword", true)
+                .build(),
+        ];
+        let initial_state = State::new()
+            .set("gold", 0)
+            .set("has_pick", false)
+            .set("ore", 0)
+            .set("has_sword", false)
+            .build();
+        let goal = Goal::new("armed").requires("has_sword", true).build();
+        let expected = Planner::new().plan(initial_state, &goal, &actions).unwrap();
+        assert_eq!(plan.cost, expected.cost);
+    }
+
+    /// Test that planning does not allocate
+    /// Validates: A reused workspace plans without any heap allocation
+    /// Failure: The fixed search allocates per node or per call
+    #[test]
+    fn test_no_allocation() {
+        let mut search = Box::new(FixedSearch::<4, 256>::new());
+        let start = FixedState::new([0, 0, 0, 0]);
+
+        let before = ALLOCATIONS.with(|count| count.get());
+        for _ in 0..3 {
+            let plan = search.plan(start, &GOAL, &ACTIONS).unwrap();
+            assert_eq!(plan.steps.len(), 6);
+        }
+        let after = ALLOCATIONS.with(|count| count.get());
+        assert_eq!(after, before);
+    }
+
+    /// Test the limits of the workspace
+    /// Validates: Running out of capacity, unreachable goals and out-of-range
+    /// slots are reported
+    /// Failure: The search overruns its buffers or loops forever
+    #[test]
+    fn test_errors() {
+        let mut small = FixedSearch::<4, 4>::new();
+        let start = FixedState::new([0, 0, 0, 0]);
+        assert_eq!(
+            small.plan(start, &GOAL, &ACTIONS).unwrap_err(),
+            FixedPlanError::CapacityExceeded
+        );
+
+        let mut search = FixedSearch::<4, 64>::new();
+        let unreachable = [FixedCondition::Equals(HAS_SWORD, 2)];
+        static STUCK: [FixedAction; 1] =
+            [FixedAction::new("toggle").effects(&[FixedOp::Set(HAS_PICK, 1)])];
+        assert_eq!(
+            search.plan(start, &unreachable, &STUCK).unwrap_err(),
+            FixedPlanError::NoPlanFound
+        );
+
+        let out_of_range = [FixedCondition::Equals(7, 1)];
+        assert_eq!(
+            search.plan(start, &out_of_range, &ACTIONS).unwrap_err(),
+            FixedPlanError::InvalidSlot { slot: 7 }
+        );
+
+        // The workspace still works after errors
+        let plan = search.plan(start, &GOAL, &ACTIONS).unwrap();
+        assert_eq!(plan.cost, 8.0);
+    }
+}
@@ -347,4 +347,311 @@ mod tests {
         let empty_state = State::empty();
         assert!(!goal.is_satisfied(&empty_state));
     }
+
+
+    // Tests for excluded values
+
+    /// Test goals with requires_not
+    /// Validates: Excluded values fail the goal; other or missing values satisfy it
+    /// Failure: Exclusion requirements are ignored by is_satisfied
+    #[test]
+    fn test_goal_requires_not() {
+        let goal = Goal::new("safe")
+            .requires("alive", true)
+            .requires_not("weather", "storm")
+            .build();
+
+        let stormy = State::new()
+            .set("alive", true)
+            .set("weather", "storm")
+            .build();
+        let sunny = State::new()
+            .set("alive", true)
+            .set("weather", "sun")
+            .build();
+        let unknown = State::new().set("alive", true).build();
+
+        assert!(!goal.is_satisfied(&stormy));
+        assert!(goal.is_satisfied(&sunny));
+        assert!(goal.is_satisfied(&unknown));
+        assert!(goal.to_string().contains("weather: not storm"));
+    }
+
+    /// Test requires_not with booleans
+    /// Validates: Excluding true is satisfied by false or a missing variable
+    /// Failure: Boolean exclusions behave differently from string exclusions
+    #[test]
+    fn test_goal_requires_not_bool() {
+        let goal = Goal::new("calm").requires_not("alarmed", true).build();
+        assert!(!goal.is_satisfied(&State::new().set("alarmed", true).build()));
+        assert!(goal.is_satisfied(&State::new().set("alarmed", false).build()));
+        assert!(goal.is_satisfied(&State::empty()));
+    }
+
+
+    // Tests for relative requirements
+
+    /// Test resolving relative requirements against a state
+    /// Validates: Deltas are added to current values; missing variables start at zero
+    /// Failure: Relative goals resolve to the wrong absolute threshold
+    #[test]
+    fn test_goal_requires_delta_resolve() {
+        let goal = Goal::new("earn")
+            .requires_delta("gold", 100)
+            .requires_delta("reputation", 1.5)
+            .requires_delta("gems", 2)
+            .build();
+        let state = State::new().set("gold", 40).set("reputation", 2.0).build();
+
+        let resolved = goal.resolve(&state);
+        assert_eq!(resolved.desired_state.get::<i64>("gold"), Some(140));
+        assert_eq!(resolved.desired_state.get::<f64>("reputation"), Some(3.5));
+        assert_eq!(resolved.desired_state.get::<i64>("gems"), Some(2));
+        assert!(resolved.relative.is_empty());
+        assert!(goal.to_string().contains("more than initially"));
+    }
+
+    // Tests for validated goal building
+
+    /// Test rejecting contradicting goal requirements
+    /// Validates: Requiring two values, or requiring and excluding the same value, is an error
+    /// Failure: Unreachable goals are silently accepted
+    #[test]
+    fn test_goal_try_build_conflicts() {
+        let conflict = |key: &str| DefinitionError::ConflictingRequirement {
+            key: key.to_string(),
+        };
+        assert_eq!(
+            Goal::new("g")
+                .requires("gold", 10)
+                .requires("gold", 20)
+                .try_build()
+                .unwrap_err(),
+            conflict("gold")
+        );
+        assert_eq!(
+            Goal::new("g")
+                .requires_not("weather", "storm")
+                .requires("weather", "storm")
+                .try_build()
+                .unwrap_err(),
+            conflict("weather")
+        );
+        assert_eq!(
+            Goal::new("g")
+                .requires("gold", 10)
+                .requires_delta("gold", 5)
+                .try_build()
+                .unwrap_err(),
+            conflict("gold")
+        );
+        assert!(
+            Goal::new("g")
+                .requires("weather", "sun")
+                .requires_not("weather", "storm")
+                .try_build()
+                .is_ok()
+        );
+    }
+
+    /// Test strict validation of empty goals
+    /// Validates: Only strict builders reject goals without requirements
+    /// Failure: Trivially satisfied goals slip through, or non-strict builds break
+    #[test]
+    fn test_goal_try_build_strict() {
+        assert!(Goal::new("idle").try_build().is_ok());
+        assert_eq!(
+            Goal::new("idle").strict(true).try_build().unwrap_err(),
+            DefinitionError::NoRequirements
+        );
+        assert!(
+            Goal::new("rich")
+                .strict(true)
+                .requires_delta("gold", 5)
+                .try_build()
+                .is_ok()
+        );
+        assert_eq!(
+            Goal::new("fast")
+                .requires("speed", f64::INFINITY)
+                .try_build()
+                .unwrap_err(),
+            DefinitionError::OutOfRange {
+                key: "speed".to_string()
+            }
+        );
+    }
+
+    /// Test strict validation of goal values
+    /// Validates: Strict goal builders report floats that cannot be converted exactly
+    /// Failure: A NaN requirement silently becomes zero
+    #[test]
+    fn test_goal_try_build_strict_values() {
+        assert!(
+            Goal::new("g")
+                .requires("temp", f64::NAN)
+                .try_build()
+                .is_ok()
+        );
+        assert_eq!(
+            Goal::new("g")
+                .requires("temp", f64::NAN)
+                .strict(true)
+                .try_build()
+                .unwrap_err(),
+            DefinitionError::InvalidValue {
+                key: "temp".to_string(),
+                error: ConversionError::NotFinite
+            }
+        );
+    }
+
+    /// Test the distance from a state to a goal
+    /// Validates: Numeric shortfalls are counted in units and other unmet requirements as one
+    /// Failure: Distances do not reflect how far the goal is
+    #[test]
+    fn test_goal_distance() {
+        let goal = Goal::new("ready")
+            .requires("gold", 100)
+            .requires("speed", 2.5)
+            .requires("location", "town")
+            .requires_not("cursed", true)
+            .build();
+
+        let far = State::new()
+            .set("gold", 40)
+            .set("speed", 1.0)
+            .set("location", "forest")
+            .set("cursed", true)
+            .build();
+        assert_eq!(goal.distance(&far), 60.0 + 1.5 + 1.0 + 1.0);
+
+        // Overshooting a minimum is not distance
+        let done = State::new()
+            .set("gold", 500)
+            .set("speed", 3.0)
+            .set("location", "town")
+            .set("cursed", false)
+            .build();
+        assert!(goal.is_satisfied(&done));
+        assert_eq!(goal.distance(&done), 0.0);
+
+        // Missing numbers count from zero, other missing values as one
+        assert_eq!(goal.distance(&State::empty()), 100.0 + 2.5 + 1.0);
+    }
+
+    /// Test ranking goals by distance
+    /// Validates: Closer goals have smaller distances and zero requirements still need the variable
+    /// Failure: Distance is zero for unsatisfied goals
+    #[test]
+    fn test_goal_distance_ranking() {
+        let state = State::new().set("wood", 8).set("stone", 1).build();
+        let house = Goal::new("house").requires("wood", 10).build();
+        let wall = Goal::new("wall").requires("stone", 10).build();
+        assert!(house.distance(&state) < wall.distance(&state));
+
+        let zero = Goal::new("zero").requires("score", 0).build();
+        assert!(!zero.is_satisfied(&State::empty()));
+        assert_eq!(zero.distance(&State::empty()), 1.0);
+    }
+
+    /// Test the ordering penalty of a step
+    /// Validates: Only steps achieving the later requirement while the earlier
+    /// one is unmet are penalized, and negative penalties become zero
+    /// Failure: Ordering hints penalize the wrong steps
+    #[test]
+    fn test_ordering_penalty() {
+        let goal = Goal::new("ambush")
+            .requires("hidden", true)
+            .requires("in_position", true)
+            .prefers_order("hidden", "in_position", 5.0)
+            .prefers_order("in_position", "hidden", -2.0)
+            .build();
+        let start = State::new()
+            .set("hidden", false)
+            .set("in_position", false)
+            .build();
+        let moved = State::new()
+            .set("hidden", false)
+            .set("in_position", true)
+            .build();
+        let hid = State::new()
+            .set("hidden", true)
+            .set("in_position", false)
+            .build();
+
+        assert_eq!(goal.ordering_penalty(&start, &moved), 5.0);
+        assert_eq!(goal.ordering_penalty(&start, &hid), 0.0);
+        assert_eq!(goal.ordering_penalty(&moved, &moved), 0.0);
+        assert!(goal.requirement_met("unrelated", &start));
+        assert!(
+            goal.to_string()
+                .contains("prefer hidden before in_position (penalty 5.0)")
+        );
+    }
+
+    /// Test goal requirements with comparison conditions
+    /// Validates: requires_less_than, requires_in_range and requires_not_equal are checked by is_satisfied and distance
+    /// Failure: Conditions are ignored or missing variables meet them
+    #[test]
+    fn test_goal_conditions() {
+        let goal = Goal::new("comfortable")
+            .requires_less_than("noise_level", 3)
+            .requires_in_range("temperature", 18, 24)
+            .requires_not_equal("weather", "storm")
+            .build();
+
+        let comfortable = State::new()
+            .set("noise_level", 2)
+            .set("temperature", 21)
+            .set("weather", "rain")
+            .build();
+        assert!(goal.is_satisfied(&comfortable));
+        assert_eq!(goal.distance(&comfortable), 0.0);
+
+        let loud = State::new()
+            .set("noise_level", 3)
+            .set("temperature", 25)
+            .set("weather", "rain")
+            .build();
+        assert!(!goal.is_satisfied(&loud));
+        assert_eq!(goal.distance(&loud), 2.0);
+        assert!(!goal.requirement_met("noise_level", &loud));
+        assert!(goal.requirement_met("weather", &loud));
+
+        // Missing variables never meet a condition
+        assert!(!goal.is_satisfied(&State::empty()));
+        assert!(
+            Goal::new("quiet")
+                .requires_less_than("noise_level", 3)
+                .strict(true)
+                .try_build()
+                .is_ok()
+        );
+    }
+
+    /// Test planning towards a goal with an upper bound
+    /// Validates: The planner reaches a condition like "noise_level < 3" by the cheapest actions
+    /// Failure: The planner ignores the condition or stops at the bound
+    #[test]
+    fn test_plan_to_goal_condition() {
+        let actions = vec![
+            Action::new("close_door")
+                .subtracts("noise_level", 2)
+                .build(),
+            Action::new("soundproof")
+                .sets("noise_level", 0)
+                .cost(5.0)
+                .build(),
+        ];
+        let goal = Goal::new("quiet")
+            .requires_less_than("noise_level", 3)
+            .build();
+        let state = State::new().set("noise_level", 6).build();
+
+        let plan = Planner::new().plan(&state, &goal, &actions).unwrap();
+        let names: Vec<&str> = plan.actions.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["close_door", "close_door"]);
+        assert!(goal.to_string().contains("noise_level < 3"));
+    }
 }
@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod tests {
+    use goap::golden::{GoldenError, check_golden, golden_text, line_diff};
+    use goap::prelude::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn scenario() -> (State, Goal, Plan) {
+        let initial_state = State::new()
+            .set("location", "town")
+            .set("gold", 0)
+            .set("tired", false)
+            .build();
+        let goal = Goal::new("rich")
+            .requires("gold", 10)
+            .requires_not("tired", true)
+            .priority(3)
+            .build();
+        let actions = vec![Action::new("work").adds("gold", 5).cost(1.5).build()];
+        let plan = Planner::new()
+            .plan(initial_state.clone(), &goal, &actions)
+            .unwrap();
+        (initial_state, goal, plan)
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("goap_golden_{}_{name}", std::process::id()))
+    }
+
+    // Tests for golden plan files
+
+    /// Test the canonical rendering of a scenario
+    /// Validates: Keys are sorted, strings quoted and costs printed with fixed precision
+    /// Failure: Equal plans render differently between runs
+    #[test]
+    fn test_golden_text_is_canonical() {
+        let (initial_state, goal, plan) = scenario();
+        assert_eq!(
+            golden_text(&initial_state, &goal, &plan),
+            "initial state:\n\
+             \x20 gold = 0\n\
+             \x20 location = \"town\"\n\
+             \x20 tired = false\n\
+             goal: rich (priority 3)\n\
+             \x20 require gold = 10\n\
+             \x20 exclude tired = true\n\
+             plan: cost 3.000\n\
+             \x20 1. work (cost 1.500)\n\
+             \x20 2. work (cost 1.500)\n"
+        );
+    }
+
+    /// Test comparing against golden files
+    /// Validates: Matching files pass, missing files and changed plans are reported with a diff
+    /// Failure: Regressions in tuned plans go unnoticed
+    #[test]
+    fn test_check_golden() {
+        let (initial_state, goal, plan) = scenario();
+        let text = golden_text(&initial_state, &goal, &plan);
+        let path = temp_path("check.txt");
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(
+            check_golden(&path, &text),
+            Err(GoldenError::Missing(path.clone()))
+        );
+
+        fs::write(&path, text.replace('\n', "\r\n")).unwrap();
+        assert_eq!(check_golden(&path, &text), Ok(()));
+
+        let changed = text.replace("cost 3.000", "cost 4.000");
+        match check_golden(&path, &changed) {
+            Err(GoldenError::Mismatch { diff, .. }) => {
+                assert!(diff.contains("- plan: cost 3.000\n+ plan: cost 4.000\n"));
+            }
+            other => panic!("expected a mismatch, got {other:?}"),
+        }
+        fs::remove_file(&path).unwrap();
+    }
+
+    /// Test the line diff used in mismatch reports
+    /// Validates: Unchanged, removed and added lines are marked
+    /// Failure: Mismatch reports are hard to read
+    #[test]
+    fn test_line_diff() {
+        assert_eq!(
+            line_diff("a\nb\nc\n", "a\nx\nc\nd\n"),
+            "  a\n- b\n+ x\n  c\n+ d\n"
+        );
+    }
+}
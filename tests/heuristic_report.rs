@@ -0,0 +1,180 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for the per-variable heuristic report
+
+    /// Test breaking the heuristic down per requirement
+    /// Validates: Terms cover requirements and exclusions, are sorted by distance
+    /// and add up to the planner's estimate
+    /// Failure: The report disagrees with the heuristic the search uses
+    #[test]
+    fn test_heuristic_report_terms() {
+        let goal = Goal::new("rich")
+            .requires("gold", 2000)
+            .requires("has_shop", true)
+            .requires("title", "merchant")
+            .requires_not("wanted", true)
+            .build();
+        let state = State::new()
+            .set("gold", 20)
+            .set("has_shop", false)
+            .set("wanted", true)
+            .build();
+        let planner = Planner::new();
+
+        let report = planner.heuristic_report(&state, &goal).unwrap();
+        assert_eq!(report.total, planner.estimate(&state, &goal).unwrap());
+        let keys: Vec<&str> = report.terms.iter().map(|term| term.key.as_str()).collect();
+        assert_eq!(keys, ["gold", "has_shop", "title", "wanted"]);
+        assert_eq!(report.terms[0].distance, 1980.0);
+        assert_eq!(report.terms[2].current, None);
+        assert!(report.terms[3].excluded);
+        assert_eq!(
+            report.to_string(),
+            "Heuristic: 1983.0\n  \
+             gold: 1980.0 (20 -> 2000) [suspect: counted per unit]\n  \
+             has_shop: 1.0 (false -> true)\n  \
+             title: 1.0 (missing -> merchant)\n  \
+             wanted: 1.0 (true must not be true)"
+        );
+    }
+
+    /// Test flagging suspected overestimation
+    /// Validates: Numeric terms counted per unit, per thousandth or beyond an
+    /// already met minimum are flagged; single steps and booleans are not
+    /// Failure: Misleading terms go unnoticed while debugging slow searches
+    #[test]
+    fn test_heuristic_report_suspects() {
+        let goal = Goal::new("ready")
+            .requires("wood", 10)
+            .requires("arrows", 5)
+            .requires("stamina", 2.5)
+            .requires("level", 3)
+            .requires("armed", true)
+            .build();
+        let state = State::new()
+            .set("wood", 40)
+            .set("arrows", 1)
+            .set("stamina", 2.0)
+            .set("level", 2)
+            .set("armed", false)
+            .build();
+
+        let report = Planner::new().heuristic_report(&state, &goal).unwrap();
+        let suspect = |key: &str| {
+            let term = report.terms.iter().find(|term| term.key == key).unwrap();
+            term.suspect
+        };
+        assert_eq!(suspect("wood"), Some(Overestimate::BeyondMinimum));
+        assert_eq!(suspect("arrows"), Some(Overestimate::PerUnit));
+        assert_eq!(suspect("stamina"), Some(Overestimate::FixedPoint));
+        assert_eq!(suspect("level"), None);
+        assert_eq!(suspect("armed"), None);
+        assert_eq!(report.suspects().count(), 3);
+    }
+
+    /// Test type errors in the report
+    /// Validates: Incompatible types are reported like the heuristic reports them
+    /// Failure: The report hides type mismatches or panics
+    #[test]
+    fn test_heuristic_report_type_error() {
+        let goal = Goal::new("rich").requires("gold", 100).build();
+        let state = State::new().set("gold", "lots").build();
+        assert!(matches!(
+            Planner::new().heuristic_report(&state, &goal),
+            Err(PlannerError::IncompatibleStateTypes(_))
+        ));
+    }
+
+    /// Test heuristic tables over a state grid
+    /// Validates: Every grid state gets a row, exported as CSV and JSON with missing values left empty
+    /// Failure: Rows are missing, columns are misaligned, or the exports are malformed
+    #[test]
+    fn test_heuristic_table_grid() {
+        let goal = Goal::new("ready")
+            .requires("gold", 100)
+            .requires("armed", true)
+            .build();
+        let base = State::new().set("name", "a, \"b\"").build();
+        let grid = state_grid(
+            &base,
+            &[
+                ("gold", vec![StateVar::I64(0), StateVar::I64(100)]),
+                ("armed", vec![StateVar::Bool(false), StateVar::Bool(true)]),
+            ],
+        );
+        assert_eq!(grid.len(), 4);
+
+        let mut states = grid.clone();
+        states.push(State::new().set("gold", 50).build());
+        let table = Planner::new().heuristic_table(&states, &goal).unwrap();
+        assert_eq!(table.keys, vec!["armed", "gold", "name"]);
+        assert_eq!(table.rows.len(), 5);
+        assert_eq!(table.rows[3].heuristic, 0.0);
+        assert_eq!(table.rows[4].values[2], None);
+
+        let csv = table.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "step,cost,heuristic,armed,gold,name");
+        assert_eq!(lines[4], ",,0,true,100,\"a, \"\"b\"\"\"");
+        assert_eq!(lines[5], ",,51,,50,");
+
+        let json = table.to_json();
+        assert!(json.starts_with("[{\"step\":null,\"cost\":null,\"heuristic\":101,"));
+        assert!(json.contains("\"state\":{\"armed\":true,\"gold\":100,\"name\":\"a, \\\"b\\\"\"}"));
+        assert!(
+            json.ends_with(
+                "{\"step\":null,\"cost\":null,\"heuristic\":51,\"state\":{\"gold\":50}}]"
+            )
+        );
+
+        let empty = Planner::new().heuristic_table(&[], &goal).unwrap();
+        assert!(empty.rows.is_empty());
+    }
+
+    /// Test heuristic tables along a plan
+    /// Validates: Rows follow the plan's states with steps and cumulative costs, and plateaus are found
+    /// Failure: Steps or costs are off, or runs of equal heuristic values are missed
+    #[test]
+    fn test_heuristic_table_along_plan() {
+        let actions = vec![
+            Action::new("walk")
+                .cost(2.0)
+                .sets("at_forest", true)
+                .build(),
+            Action::new("rest").cost(1.0).sets("rested", true).build(),
+            Action::new("chop")
+                .requires("at_forest", true)
+                .requires("rested", true)
+                .adds("wood", 1)
+                .build(),
+        ];
+        let goal = Goal::new("wood").requires("wood", 1).build();
+        let initial = State::new()
+            .set("wood", 0)
+            .set("at_forest", false)
+            .set("rested", false)
+            .build();
+        let plan = Planner::new()
+            .plan(initial.clone(), &goal, &actions)
+            .unwrap();
+        assert_eq!(plan.actions.len(), 3);
+
+        let table = Planner::new()
+            .heuristic_table_along(&plan, &initial, &goal)
+            .unwrap();
+        assert_eq!(table.rows.len(), 4);
+        let steps: Vec<Option<usize>> = table.rows.iter().map(|row| row.step).collect();
+        assert_eq!(steps, vec![Some(0), Some(1), Some(2), Some(3)]);
+        assert_eq!(table.rows[3].cost, Some(plan.cost));
+        assert_eq!(table.rows[3].heuristic, 0.0);
+        // Walking and resting do not move the heuristic
+        assert_eq!(table.plateaus(), vec![0..3]);
+        assert!(
+            table
+                .to_csv()
+                .starts_with("step,cost,heuristic,at_forest,rested,wood\n0,0,1,")
+        );
+    }
+}
@@ -0,0 +1,207 @@
+#[cfg(test)]
+#[cfg(any(feature = "json", feature = "ron", feature = "toml"))]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for loading domains from data files
+
+    /// Test loading a JSON domain and planning with it
+    /// Validates: States, goals, actions, expressions and tags load with their values and types
+    /// Failure: Would indicate authored content plans differently from the same domain written in Rust
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_load_json_domain() {
+        let domain = Domain::from_json(
+            r#"{
+                "initial_state": { "gold": 5, "speed": 1.5, "location": "home" },
+                "goals": [
+                    { "name": "rich", "priority": 3, "requires": { "gold": 20 } },
+                    { "name": "away", "requires_not": { "location": "home" } }
+                ],
+                "actions": [
+                    { "name": "walk", "sets": { "location": "mine" }, "tags": ["movement"] },
+                    {
+                        "name": "mine",
+                        "cost": 2.0,
+                        "requires": { "location": "mine" },
+                        "requires_expr": ["speed > 1"],
+                        "adds": { "gold": 10 }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(domain.initial_state.get::<f64>("speed"), Some(1.5));
+        assert_eq!(domain.goal("rich").unwrap().priority, 3);
+        assert!(domain.action("walk").unwrap().has_tag("movement"));
+
+        let plan = Planner::new()
+            .plan(
+                &domain.initial_state,
+                domain.goal("rich").unwrap(),
+                &domain.actions,
+            )
+            .unwrap();
+        let names: Vec<&str> = plan.actions.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["walk", "mine", "mine"]);
+        assert!(
+            domain
+                .goal("away")
+                .unwrap()
+                .is_satisfied(&plan.actions[0].apply_effect(&domain.initial_state))
+        );
+    }
+
+    /// Test the defaults of omitted fields
+    /// Validates: Goals and actions without priority or cost load like their builder defaults
+    /// Failure: Would indicate goals from files rank differently from the same goals built in code
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_load_defaults_match_builders() {
+        let domain = Domain::from_json(
+            r#"{
+                "goals": [{ "name": "wood", "requires": { "has_wood": true } }],
+                "actions": [{ "name": "chop", "sets": { "has_wood": true } }]
+            }"#,
+        )
+        .unwrap();
+
+        let built = Goal::new("wood").requires("has_wood", true).build();
+        assert_eq!(domain.goal("wood").unwrap().priority, built.priority);
+        assert_eq!(domain.goal("wood").unwrap().priority, 1);
+        assert_eq!(domain.action("chop").unwrap().cost, 1.0);
+    }
+
+    /// Test that validation errors name the offending entry
+    /// Validates: Invalid values, duplicate names, bad expressions and unknown fields are reported
+    /// Failure: Would indicate designers have to bisect content files to find a mistake
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_load_errors_point_to_entry() {
+        let error = Domain::from_json(
+            r#"{ "actions": [
+                { "name": "rest" },
+                { "name": "mine", "cost": -1.0 }
+            ] }"#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "actions[1] 'mine': Invalid cost -1: must be finite and not negative"
+        );
+
+        let error = Domain::from_json(
+            r#"{ "actions": [{ "name": "trade", "adds": { "gold": "lots" } }] }"#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "actions[0] 'trade': Amount for 'gold' is not a number"
+        );
+
+        let error =
+            Domain::from_json(r#"{ "goals": [{ "name": "a", "requires": {} }, { "name": "a" }] }"#)
+                .unwrap_err();
+        assert!(matches!(
+            error,
+            LoadError::Goal {
+                index: 0,
+                error: EntryError::Definition(DefinitionError::NoRequirements),
+                ..
+            }
+        ));
+
+        let error =
+            Domain::from_json(r#"{ "actions": [{ "name": "x", "requries": {} }] }"#).unwrap_err();
+        assert!(error.to_string().contains("requries"));
+        assert!(error.to_string().contains("line 1"));
+    }
+
+    /// Test loading a RON domain
+    /// Validates: RON structs, maps and lists load into the same domain as JSON
+    /// Failure: Would indicate content behaves differently depending on the file format
+    #[cfg(feature = "ron")]
+    #[test]
+    fn test_load_ron_domain() {
+        let domain = Domain::from_ron(
+            r#"(
+                initial_state: { "has_axe": false },
+                goals: [(name: "wood", requires: { "has_wood": true })],
+                actions: [
+                    (name: "get_axe", sets: { "has_axe": true }),
+                    (name: "chop", requires: { "has_axe": true }, sets: { "has_wood": true }),
+                ],
+            )"#,
+        )
+        .unwrap();
+
+        let plan = Planner::new()
+            .plan(&domain.initial_state, &domain.goals[0], &domain.actions)
+            .unwrap();
+        assert_eq!(plan.actions.len(), 2);
+    }
+
+    /// Test loading a TOML domain file
+    /// Validates: load picks the format by extension and rejects unknown extensions
+    /// Failure: Would indicate domain files cannot be loaded by path
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_load_toml_file() {
+        let toml = r#"
+            [initial_state]
+            has_axe = false
+
+            [[goals]]
+            name = "wood"
+            requires = { has_wood = true }
+
+            [[actions]]
+            name = "get_axe"
+            sets = { has_axe = true }
+
+            [[actions]]
+            name = "chop"
+            requires = { has_axe = true }
+            sets = { has_wood = true }
+        "#;
+        let duplicate = r#"
+            [[actions]]
+            name = "chop"
+
+            [[actions]]
+            name = "chop"
+        "#;
+
+        let directory = std::env::temp_dir().join(format!("goap_loader_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("domain.toml"), toml).unwrap();
+        std::fs::write(directory.join("duplicate.toml"), duplicate).unwrap();
+        let domain = Domain::load(directory.join("domain.toml"));
+        let duplicate = Domain::load(directory.join("duplicate.toml"));
+        let unsupported = Domain::load(directory.join("domain.yaml"));
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        let domain = domain.unwrap();
+        assert_eq!(
+            domain.initial_state,
+            State::new().set("has_axe", false).build()
+        );
+        assert_eq!(
+            domain
+                .action("chop")
+                .unwrap()
+                .preconditions
+                .get::<bool>("has_axe"),
+            Some(true)
+        );
+        assert_eq!(
+            duplicate.unwrap_err().to_string(),
+            "actions[1] 'chop': Name is already used"
+        );
+        assert!(matches!(
+            unsupported,
+            Err(LoadError::UnsupportedFormat { .. })
+        ));
+    }
+}
@@ -0,0 +1,121 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    fn gold_domain() -> (Goal, Vec<Action>) {
+        let goal = Goal::new("rich").requires("gold", 20).build();
+        let actions = vec![
+            Action::new("work").adds("gold", 5).build(),
+            Action::new("gamble").adds("gold", 1).cost(3.0).build(),
+        ];
+        (goal, actions)
+    }
+
+    fn monte_carlo_planner(seed: u64) -> Planner {
+        Planner::with_config(
+            PlannerConfig::default().strategy(SearchStrategy::MonteCarlo(MonteCarloConfig {
+                seed,
+                ..MonteCarloConfig::default()
+            })),
+        )
+    }
+
+    /// Always takes the first applicable transition
+    struct FirstRollout {
+        calls: usize,
+    }
+
+    impl RolloutPolicy for FirstRollout {
+        fn choose(
+            &mut self,
+            _state: &State,
+            _transitions: &[Transition<'_>],
+            _random: u64,
+        ) -> usize {
+            self.calls += 1;
+            0
+        }
+    }
+
+    // Tests for Monte Carlo Tree Search
+
+    /// Test selecting Monte Carlo search through the planner config
+    /// Validates: The strategy finds a plan that reaches the goal
+    /// Failure: The strategy is ignored or returns an invalid plan
+    #[test]
+    fn test_monte_carlo_finds_valid_plan() {
+        let (goal, actions) = gold_domain();
+        let initial_state = State::new().set("gold", 0).build();
+        let plan = monte_carlo_planner(7)
+            .plan(initial_state.clone(), &goal, &actions)
+            .unwrap();
+
+        let mut state = initial_state;
+        for action in &plan.actions {
+            assert!(action.can_execute(&state));
+            state = action.apply_effect(&state);
+        }
+        assert!(goal.is_satisfied(&state));
+        let total: f64 = plan.actions.iter().map(|action| action.cost).sum();
+        assert!((plan.cost - total).abs() < 1e-9);
+    }
+
+    /// Test that equal seeds give equal plans
+    /// Validates: Monte Carlo search is reproducible
+    /// Failure: Randomness is not derived from the configured seed
+    #[test]
+    fn test_monte_carlo_is_deterministic_per_seed() {
+        let (goal, actions) = gold_domain();
+        let initial_state = State::new().set("gold", 0).build();
+        let first = monte_carlo_planner(42)
+            .plan(initial_state.clone(), &goal, &actions)
+            .unwrap();
+        let second = monte_carlo_planner(42)
+            .plan(initial_state, &goal, &actions)
+            .unwrap();
+
+        let names = |plan: &Plan| {
+            plan.actions
+                .iter()
+                .map(|a| a.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&first), names(&second));
+        assert_eq!(first.cost, second.cost);
+    }
+
+    /// Test supplying a custom rollout policy
+    /// Validates: The policy is consulted and guides the returned plan
+    /// Failure: The policy is never called or its choice is ignored
+    #[test]
+    fn test_monte_carlo_custom_rollout_policy() {
+        let (goal, actions) = gold_domain();
+        let initial_state = State::new().set("gold", 0).build();
+        let mut policy = FirstRollout { calls: 0 };
+        let plan = Planner::new()
+            .plan_with_rollout(initial_state, &goal, &actions, &mut policy)
+            .unwrap();
+
+        assert!(policy.calls > 0);
+        assert_eq!(plan.actions.len(), 4);
+        assert!(plan.actions.iter().all(|action| action.name == "work"));
+    }
+
+    /// Test a goal that cannot be reached within the depth limit
+    /// Validates: Monte Carlo search reports NoPlanFound
+    /// Failure: An incomplete plan is returned
+    #[test]
+    fn test_monte_carlo_no_plan_within_depth() {
+        let (goal, actions) = gold_domain();
+        let initial_state = State::new().set("gold", 0).build();
+        let planner = Planner::with_config(PlannerConfig::default().strategy(
+            SearchStrategy::MonteCarlo(MonteCarloConfig {
+                max_depth: 3,
+                ..MonteCarloConfig::default()
+            }),
+        ));
+
+        let result = planner.plan(initial_state, &goal, &actions);
+        assert!(matches!(result, Err(PlannerError::NoPlanFound)));
+    }
+}
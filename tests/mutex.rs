@@ -0,0 +1,221 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for mutually exclusive variable groups
+
+    /// Test group consistency checks
+    /// Validates: Exactly one true member is required once any member is present
+    /// Failure: Invalid flag combinations are accepted
+    #[test]
+    fn test_mutex_group_consistency() {
+        let group = MutexGroup::new(&["at_base", "at_a", "at_b"]);
+        let valid = State::new()
+            .set("at_base", false)
+            .set("at_a", true)
+            .set("at_b", false)
+            .build();
+        assert!(group.is_consistent(&valid));
+        assert_eq!(group.active(&valid), Some("at_a"));
+
+        let two = State::new().set("at_base", true).set("at_a", true).build();
+        assert!(!group.is_consistent(&two));
+        let none = State::new().set("at_base", false).build();
+        assert!(!group.is_consistent(&none));
+        assert!(group.is_consistent(&State::empty()));
+    }
+
+    /// Test actions are completed with the implied effects
+    /// Validates: Setting one member true clears the others; explicit effects are kept
+    /// Failure: Authors still have to clear every other flag by hand
+    #[test]
+    fn test_mutex_group_completes_actions() {
+        let group = MutexGroup::new(&["at_base", "at_a", "at_b"]);
+        let go_a = Action::new("go_a").sets("at_a", true).build();
+        let completed = group.complete_action(&go_a);
+        assert_eq!(completed.effects.len(), 3);
+
+        let state = State::new()
+            .set("at_base", true)
+            .set("at_a", false)
+            .set("at_b", false)
+            .build();
+        let after = completed.apply_effect(&state);
+        assert_eq!(group.active(&after), Some("at_a"));
+
+        let unrelated = Action::new("rest").sets("rested", true).build();
+        assert_eq!(group.complete_action(&unrelated).effects.len(), 1);
+    }
+
+    /// Test planning with a mutex group instead of hand-written flag clearing
+    /// Validates: Movement actions only set their destination and the plan stays consistent
+    /// Failure: Planner produces states with several locations active at once
+    #[test]
+    fn test_planner_with_mutex_group() {
+        let planner = Planner::with_config(
+            PlannerConfig::new().mutex_group(&["at_base", "at_point_a", "at_point_b"]),
+        );
+        let initial_state = State::new()
+            .set("at_base", true)
+            .set("at_point_a", false)
+            .set("at_point_b", false)
+            .set("reported_a", false)
+            .set("reported_b", false)
+            .build();
+        let goal = Goal::new("patrol")
+            .requires("reported_a", true)
+            .requires("reported_b", true)
+            .requires("at_base", true)
+            .build();
+        let actions = vec![
+            Action::new("go_base").cost(5.0).sets("at_base", true).build(),
+            Action::new("go_a").cost(5.0).sets("at_point_a", true).build(),
+            Action::new("go_b").cost(5.0).sets("at_point_b", true).build(),
+            Action::new("report_a")
+                .requires("at_point_a", true)
+                .sets("reported_a", true)
+                .build(),
+            Action::new("report_b")
+                .requires("at_point_b", true)
+                .sets("reported_b", true)
+                .build(),
+        ];
+
+        // Movement actions only name their destination; the group clears the other flags
+        let plan = planner.plan(initial_state.clone(), &goal, &actions).unwrap();
+        let group = &planner.config().mutex_groups[0];
+        let mut state = initial_state;
+        for action in &plan.actions {
+            assert!(action.can_execute(&state));
+            state = action.apply_effect(&state);
+            assert!(group.is_consistent(&state));
+        }
+        assert!(goal.is_satisfied(&state));
+        assert_eq!(plan.actions.last().unwrap().name, "go_base");
+    }
+
+    /// Test completing actions for execution outside the planner
+    /// Validates: complete_actions clears other members in effects and failure effects
+    /// Failure: Executed or simulated plans drift from the states the planner predicted
+    #[test]
+    fn test_config_completes_actions() {
+        let config = PlannerConfig::new()
+            .mutex_group(&["at_home", "at_shop"])
+            .mutex_group(&["standing", "crouching"]);
+        let actions = vec![
+            Action::new("sneak_to_shop")
+                .sets("at_shop", true)
+                .sets("crouching", true)
+                .fails_with("at_home", true)
+                .build(),
+        ];
+
+        let completed = config.complete_actions(&actions);
+        let state = State::new()
+            .set("at_home", true)
+            .set("at_shop", false)
+            .set("standing", true)
+            .set("crouching", false)
+            .build();
+        let after = completed[0].apply_effect(&state);
+        assert_eq!(after.get::<bool>("at_home"), Some(false));
+        assert_eq!(after.get::<bool>("standing"), Some(false));
+        assert_eq!(
+            completed[0].failure_effects.get("at_shop"),
+            Some(&StateOperation::Set(StateVar::Bool(false)))
+        );
+        assert_eq!(actions[0].effects.len(), 2);
+    }
+
+    // Tests for converting flags into a single variable
+
+    /// Test migrating a boolean-flag domain to a location variable
+    /// Validates: States, goals and actions convert and still produce a valid plan
+    /// Failure: Converted domain loses behaviour or cannot be planned
+    #[test]
+    fn test_flags_to_enum_converts_domain() {
+        let conversion = FlagsToEnum::new("location")
+            .flag("at_base", "base")
+            .flag("at_point_a", "point_a");
+
+        let initial_state = State::new()
+            .set("at_base", true)
+            .set("at_point_a", false)
+            .set("reported", false)
+            .build();
+        let goal = Goal::new("report")
+            .requires("reported", true)
+            .requires("at_base", true)
+            .build();
+        let actions = [
+            Action::new("go_a")
+                .sets("at_point_a", true)
+                .sets("at_base", false)
+                .build(),
+            Action::new("go_base")
+                .sets("at_base", true)
+                .sets("at_point_a", false)
+                .build(),
+            Action::new("report")
+                .requires("at_point_a", true)
+                .sets("reported", true)
+                .build(),
+        ];
+
+        let state = conversion.convert_state(&initial_state).unwrap();
+        assert_eq!(state.get::<String>("location"), Some("base".to_string()));
+        assert!(state.get::<bool>("at_base").is_none());
+
+        let goal = conversion.convert_goal(&goal).unwrap();
+        assert_eq!(
+            goal.desired_state.get::<String>("location"),
+            Some("base".to_string())
+        );
+
+        let actions: Vec<Action> = actions
+            .iter()
+            .map(|action| conversion.convert_action(action).unwrap())
+            .collect();
+        assert_eq!(actions[0].effects.len(), 1);
+        assert_eq!(
+            actions[2].preconditions.get::<String>("location"),
+            Some("point_a".to_string())
+        );
+
+        let plan = Planner::new().plan(state, &goal, &actions).unwrap();
+        let names: Vec<&str> = plan.actions.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["go_a", "report", "go_base"]);
+    }
+
+    /// Test conversions that cannot be expressed with a single variable
+    /// Validates: Negated flags, conflicting flags and bare clears are reported
+    /// Failure: Conversion silently changes the meaning of the domain
+    #[test]
+    fn test_flags_to_enum_errors() {
+        let group = MutexGroup::new(&["at_base", "at_a"]);
+        let conversion = FlagsToEnum::from_group(&group, "location");
+
+        let both = State::new().set("at_base", true).set("at_a", true).build();
+        assert_eq!(
+            conversion.convert_state(&both),
+            Err(FlagConversionError::InconsistentState)
+        );
+
+        let leave = Action::new("leave").requires("at_base", false).build();
+        assert_eq!(
+            conversion.convert_action(&leave).unwrap_err(),
+            FlagConversionError::NegatedFlag {
+                flag: "at_base".to_string()
+            }
+        );
+
+        let vanish = Action::new("vanish").sets("at_base", false).build();
+        assert!(matches!(
+            conversion.convert_action(&vanish),
+            Err(FlagConversionError::UnsupportedEffect { .. })
+        ));
+
+        let unrelated = State::new().set("gold", 3).build();
+        assert_eq!(conversion.convert_state(&unrelated).unwrap(), unrelated);
+    }
+}
@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    fn patrol_graph() -> LocationGraph {
+        LocationGraph::new("location")
+            .connect("base", "point_a", 5.0)
+            .connect("point_a", "point_b", 5.0)
+            .connect("point_b", "point_c", 5.0)
+            .connect_one_way("point_c", "base", 2.0)
+    }
+
+    // Tests for movement action generation
+
+    /// Test movement actions are generated for every direct route
+    /// Validates: Names, costs, preconditions and effects of generated actions
+    /// Failure: Routes are missing, duplicated or have wrong costs
+    #[test]
+    fn test_location_graph_movement_actions() {
+        let graph = patrol_graph();
+        assert_eq!(graph.locations().len(), 4);
+        assert_eq!(graph.travel_cost("point_c", "base"), Some(2.0));
+        assert_eq!(graph.travel_cost("base", "point_c"), None);
+
+        let actions = graph.movement_actions();
+        assert_eq!(actions.len(), 7);
+
+        let to_a = actions
+            .iter()
+            .find(|a| a.name == "move_base_to_point_a")
+            .unwrap();
+        assert_eq!(to_a.cost, 5.0);
+        let at_base = State::new().set("location", "base").build();
+        assert!(to_a.can_execute(&at_base));
+        let moved = to_a.apply_effect(&at_base);
+        assert_eq!(moved.get::<String>("location"), Some("point_a".to_string()));
+    }
+
+    /// Test planning with generated movement actions
+    /// Validates: The planner navigates the graph to reach a location-dependent goal
+    /// Failure: Generated actions cannot be combined into a valid plan
+    #[test]
+    fn test_planning_with_location_graph() {
+        let graph = patrol_graph();
+        let mut actions = graph.movement_actions();
+        actions.push(
+            Action::new("report_at_c")
+                .requires("location", "point_c")
+                .sets("reported", true)
+                .build(),
+        );
+
+        let planner = Planner::with_config(PlannerConfig::new().symbols(graph.symbols()));
+        let initial_state = State::new()
+            .set("location", "base")
+            .set("reported", false)
+            .build();
+        let goal = Goal::new("report_home")
+            .requires("reported", true)
+            .requires("location", "base")
+            .build();
+
+        let plan = planner.plan(initial_state.clone(), &goal, &actions).unwrap();
+        let mut state = initial_state;
+        for action in &plan.actions {
+            assert!(action.can_execute(&state));
+            state = action.apply_effect(&state);
+        }
+        assert!(goal.is_satisfied(&state));
+        assert_eq!(plan.actions.last().unwrap().name, "move_point_c_to_base");
+    }
+}
@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for ownership of state variables shared between agents
+
+    /// Test ownership queries
+    /// Validates: Owners and shared variables are recorded, later declarations
+    /// replace earlier ones, and undeclared variables may not be modified
+    /// Failure: Agents may modify variables they do not own
+    #[test]
+    fn test_may_modify() {
+        let ownership = KeyOwnership::new()
+            .owns("guard", ["guard_health", "door_open"])
+            .owns("player", ["player_health"])
+            .shared("door_open");
+
+        assert_eq!(ownership.owner("guard_health"), Some("guard"));
+        assert_eq!(ownership.owner("door_open"), None);
+        assert!(ownership.is_shared("door_open"));
+        assert!(ownership.may_modify("guard", "guard_health"));
+        assert!(ownership.may_modify("player", "door_open"));
+        assert!(!ownership.may_modify("guard", "player_health"));
+        assert!(!ownership.may_modify("guard", "weather"));
+    }
+
+    /// Test finding the variables an action may not modify
+    /// Validates: Static, dynamic and failure effects are all checked
+    /// Failure: Some effects escape the ownership check
+    #[test]
+    fn test_violations() {
+        let ownership = KeyOwnership::new().owns("guard", ["guard_alert"]);
+        let action = Action::new("shout")
+            .sets("guard_alert", true)
+            .effect_expr("player_health -= 1")
+            .unwrap()
+            .fails_with("noise", 3)
+            .build();
+
+        assert_eq!(
+            ownership.violations("guard", &action),
+            ["noise", "player_health"]
+        );
+        assert_eq!(
+            ownership.violations("player", &action),
+            ["guard_alert", "noise", "player_health"]
+        );
+    }
+
+    /// Test enforcing ownership while executing a plan
+    /// Validates: The executor rejects an action modifying another agent's
+    /// variable, even when precondition checks are skipped
+    /// Failure: Authoring bugs mutate other agents' variables unnoticed
+    #[test]
+    fn test_executor_rejects_foreign_keys() {
+        let goal = Goal::new("patrolled").requires("patrolled", true).build();
+        let actions = vec![
+            Action::new("patrol")
+                .sets("patrolled", true)
+                .subtracts("player_health", 5)
+                .build(),
+        ];
+        let state = State::new().set("player_health", 100).build();
+        let plan = Planner::new().plan(state.clone(), &goal, &actions).unwrap();
+        let ownership = KeyOwnership::new()
+            .owns("guard", ["patrolled"])
+            .owns("player", ["player_health"]);
+
+        let executor = PlanExecutor::new(plan.clone())
+            .mode(PreconditionCheck::Skip)
+            .ownership("guard", ownership.clone());
+        let error = executor.next_action(&state).unwrap_err();
+        assert_eq!(
+            error,
+            ExecutionError::OwnershipViolated {
+                step: 0,
+                action: "patrol".to_string(),
+                agent: "guard".to_string(),
+                keys: vec!["player_health".to_string()],
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "'patrol' at step 0 modifies variables not owned by 'guard': player_health"
+        );
+
+        let shared = ownership.shared("player_health");
+        let executor = PlanExecutor::new(plan).ownership("guard", shared);
+        assert!(executor.next_action(&state).unwrap().is_some());
+    }
+}
@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for running independent plan steps at the same time
+
+    fn workshop_plan() -> Plan {
+        Plan {
+            actions: vec![
+                Action::new("craft")
+                    .requires("has_wood", true)
+                    .sets("has_part", true)
+                    .exclusive("hands")
+                    .build(),
+                Action::new("charge_battery").sets("charged", true).build(),
+                Action::new("polish")
+                    .sets("polished", true)
+                    .exclusive("hands")
+                    .build(),
+                Action::new("build")
+                    .requires("has_part", true)
+                    .requires("charged", true)
+                    .sets("built", true)
+                    .build(),
+            ],
+            cost: 4.0,
+            ..Plan::default()
+        }
+    }
+
+    /// Test dependencies derived from what steps read and change
+    /// Validates: A step waits for earlier steps producing its inputs and nothing else
+    /// Failure: Dependent steps start early or independent ones wait needlessly
+    #[test]
+    fn test_parallel_dependencies() {
+        let executor = ParallelExecutor::new(workshop_plan());
+        assert!(executor.dependencies(0).is_empty());
+        assert!(executor.dependencies(1).is_empty());
+        assert!(executor.dependencies(2).is_empty());
+        assert_eq!(executor.dependencies(3), &[0, 1]);
+        assert!(executor.dependencies(9).is_empty());
+    }
+
+    /// Test slots, joins and exclusive resources
+    /// Validates: Independent steps run together up to the slot count, steps sharing
+    /// a resource never overlap and a dependent step starts after its dependencies
+    /// Failure: The executor overbooks the agent or breaks the plan's order
+    #[test]
+    fn test_parallel_execution() {
+        let state = State::new().set("has_wood", true).build();
+        let mut executor = ParallelExecutor::new(workshop_plan()).slots(3);
+
+        // "polish" shares the hands with "craft"
+        assert_eq!(executor.start_ready(&state).unwrap(), vec![0, 1]);
+        assert_eq!(executor.status(2), Some(StepStatus::Pending));
+        assert!(executor.ready().is_empty());
+        assert!(!executor.finished(3));
+
+        assert!(executor.finished(0));
+        assert_eq!(executor.start_ready(&state).unwrap(), vec![2]);
+
+        // "build" joins on "craft" and "charge_battery"
+        assert!(executor.finished(1));
+        let state = State::new()
+            .set("has_part", true)
+            .set("charged", true)
+            .build();
+        assert_eq!(executor.start_ready(&state).unwrap(), vec![3]);
+        assert_eq!(executor.running(), vec![2, 3]);
+        assert!(executor.finished(2) && executor.finished(3));
+        assert!(executor.is_finished());
+    }
+
+    /// Test a single slot and failed preconditions
+    /// Validates: One slot runs steps in order, and a ready step whose preconditions
+    /// fail is reported without starting anything
+    /// Failure: The executor hands out actions that cannot run
+    #[test]
+    fn test_parallel_single_slot_and_preconditions() {
+        let mut executor = ParallelExecutor::new(workshop_plan());
+        let error = executor.start_ready(&State::empty()).unwrap_err();
+        assert!(matches!(
+            error,
+            ExecutionError::PreconditionFailed { step: 0, .. }
+        ));
+        assert!(executor.running().is_empty());
+
+        let state = State::new().set("has_wood", true).build();
+        assert_eq!(executor.start_ready(&state).unwrap(), vec![0]);
+        assert!(executor.start_ready(&state).unwrap().is_empty());
+        executor.finished(0);
+        assert_eq!(executor.start_ready(&state).unwrap(), vec![1]);
+    }
+}
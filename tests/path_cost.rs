@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for action costs that depend on the plan so far
+
+    fn combat_actions() -> Vec<Action> {
+        vec![
+            Action::new("slash").adds("damage", 3).cost(1.0).build(),
+            Action::new("fireball").adds("damage", 3).cost(1.5).build(),
+        ]
+    }
+
+    /// Test the path context handed to cost functions
+    /// Validates: Depth, last action, trailing repeats and counts describe the path
+    /// Failure: Cost functions see the wrong history
+    #[test]
+    fn test_path_context() {
+        let actions = combat_actions();
+        let path = PathContext::new(vec![&actions[1], &actions[0], &actions[0]]);
+        assert_eq!(path.depth(), 3);
+        assert_eq!(path.last().unwrap().name, "slash");
+        assert_eq!(path.repeats("slash"), 2);
+        assert_eq!(path.repeats("fireball"), 0);
+        assert_eq!(path.count("fireball"), 1);
+        assert_eq!(PathContext::default().last().map(|a| &a.name), None);
+    }
+
+    /// Test escalating costs for repeated actions
+    /// Validates: The search prices steps with the path cost and reports the plan's
+    /// cost under it, while plans without a path cost are unchanged
+    /// Failure: The cost function is ignored or the plan cost uses the flat action costs
+    #[test]
+    fn test_path_cost_escalation() {
+        let actions = combat_actions();
+        let goal = Goal::new("kill").requires("damage", 9).build();
+        let state = State::new().set("damage", 0).build();
+
+        let flat = Planner::new().plan(state.clone(), &goal, &actions).unwrap();
+        assert!(flat.actions.iter().all(|action| action.name == "slash"));
+        assert_eq!(flat.cost, 3.0);
+
+        let cost = PathCost::new(|action: &Action, _: &State, path: &PathContext| {
+            action.cost * (1 + path.repeats(&action.name)) as f64
+        });
+        let plan = Planner::with_config(PlannerConfig::new().path_cost(cost))
+            .plan(state, &goal, &actions)
+            .unwrap();
+        let names: Vec<&str> = plan.actions.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["slash", "fireball", "slash"]);
+        assert_eq!(plan.cost, 3.5);
+    }
+
+    /// Test combo discounts based on the previous action
+    /// Validates: A step can be cheaper when it follows a specific action
+    /// Failure: The context does not reach the cost function during the search
+    #[test]
+    fn test_path_cost_combo_discount() {
+        let actions = vec![
+            Action::new("feint").sets("opened", true).cost(1.0).build(),
+            Action::new("finisher").sets("won", true).cost(5.0).build(),
+            Action::new("brawl").sets("won", true).cost(4.0).build(),
+        ];
+        let goal = Goal::new("win").requires("won", true).build();
+        let cost = PathCost::new(|action: &Action, _: &State, path: &PathContext| {
+            match (action.name.as_str(), path.last()) {
+                ("finisher", Some(previous)) if previous.name == "feint" => 1.0,
+                _ => action.cost,
+            }
+        });
+        let plan = Planner::with_config(PlannerConfig::new().path_cost(cost))
+            .plan(State::empty(), &goal, &actions)
+            .unwrap();
+        let names: Vec<&str> = plan.actions.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["feint", "finisher"]);
+        assert_eq!(plan.cost, 2.0);
+    }
+}
@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for planning pipelines
+
+    fn gold_domain() -> (Goal, Vec<Action>) {
+        let goal = Goal::new("rich").requires("gold", 20).build();
+        let actions = vec![
+            Action::new("work").adds("gold", 5).cost(2.0).build(),
+            Action::new("gamble").adds("gold", 10).cost(5.0).build(),
+        ];
+        (goal, actions)
+    }
+
+    /// Test the order in which stages are tried
+    /// Validates: Later stages only run when earlier ones find nothing, and the
+    /// result names the stage that produced it
+    /// Failure: The pipeline always pays for a full search or misreports its stage
+    #[test]
+    fn test_pipeline_stage_order() {
+        let (goal, actions) = gold_domain();
+        let mut pipeline = PlannerPipeline::new(Planner::new())
+            .cache()
+            .repair()
+            .search(None);
+        assert_eq!(pipeline.stages().len(), 3);
+
+        let start = State::new().set("gold", 0).build();
+        let full = Planner::new().plan(start.clone(), &goal, &actions).unwrap();
+        let found = pipeline.plan(&start, &goal, &actions).unwrap();
+        assert_eq!((found.stage, found.name), (2, "search"));
+        assert_eq!(found.plan, full);
+
+        let cached = pipeline.plan(&start, &goal, &actions).unwrap();
+        assert_eq!((cached.stage, cached.name), (0, "cache"));
+        assert_eq!(cached.plan, full);
+
+        // One step was executed: the rest of the plan remains
+        let later = full.actions[0].apply_effect(&start);
+        let repaired = pipeline.plan(&later, &goal, &actions).unwrap();
+        assert_eq!(repaired.name, "repair");
+        assert_eq!(repaired.plan.actions.len(), full.actions.len() - 1);
+        assert_eq!(repaired.plan.cost, full.cost - full.actions[0].cost);
+
+        pipeline.clear();
+        assert_eq!(
+            pipeline.plan(&later, &goal, &actions).unwrap().name,
+            "search"
+        );
+        pipeline.forget("rich");
+        assert_eq!(
+            pipeline.plan(&later, &goal, &actions).unwrap().name,
+            "search"
+        );
+    }
+
+    /// Test per-stage budgets
+    /// Validates: A greedy stage that runs out of budget falls through to the next
+    /// stage, and a pipeline whose stages all fail reports an error
+    /// Failure: Budgets are ignored or exhausted stages end the pipeline
+    #[test]
+    fn test_pipeline_budgets() {
+        let (goal, actions) = gold_domain();
+        let start = State::new().set("gold", 0).build();
+
+        let mut pipeline = PlannerPipeline::new(Planner::new())
+            .greedy(3.0, 1)
+            .search(Some(1000));
+        let found = pipeline.plan(&start, &goal, &actions).unwrap();
+        assert_eq!(found.name, "search");
+
+        let mut starved = PlannerPipeline::new(Planner::new()).greedy(3.0, 1).cache();
+        assert_eq!(
+            starved.plan(&start, &goal, &actions),
+            Err(PlannerError::NoPlanFound)
+        );
+        assert_eq!(
+            PlannerPipeline::new(Planner::new()).plan(&start, &goal, &actions),
+            Err(PlannerError::NoPlanFound)
+        );
+    }
+}
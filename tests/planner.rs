@@ -572,4 +572,1292 @@ mod tests {
         assert!(!plan.actions.is_empty());
         assert!(plan.cost > 0.0);
     }
+
+    /// Test planning with a state-dependent effect
+    /// Validates: The planner uses dynamic effects when generating successor states
+    /// Failure: Dynamic effects are not evaluated during search
+    #[test]
+    fn test_planner_dynamic_effects() {
+        let planner = Planner::new();
+
+        let initial_state = State::new().set("farm_level", 1).set("grain", 0).build();
+        let goal = Goal::new("stockpile").requires("grain", 50).build();
+
+        let upgrade_farm = Action::new("upgrade_farm")
+            .cost(1.0)
+            .adds("farm_level", 4)
+            .build();
+        let harvest = Action::new("harvest")
+            .cost(1.0)
+            .effect_fn("grain", |state| {
+                StateOperation::add_i64(state.get::<i64>("farm_level").unwrap_or(0) * 10)
+            })
+            .build();
+
+        let plan = planner
+            .plan(initial_state.clone(), &goal, &[upgrade_farm, harvest])
+            .unwrap();
+
+        // Replaying the plan must reach the goal using the state-dependent yields
+        let mut state = initial_state;
+        for action in &plan.actions {
+            assert!(action.can_execute(&state));
+            state = action.apply_effect(&state);
+        }
+        assert!(goal.is_satisfied(&state));
+        assert!(plan.actions.len() <= 5);
+    }
+
+    // Tests for planner configuration and statistics
+
+    /// Test search statistics are reported on the plan
+    /// Validates: Expanded/generated node counts and memory usage are populated
+    /// Failure: Plan statistics are not collected during search
+    #[test]
+    fn test_planner_reports_stats() {
+        let planner = Planner::new();
+        let initial_state = State::new().set("wood", 0).build();
+        let goal = Goal::new("gather").requires("wood", 3).build();
+        let chop = Action::new("chop").adds("wood", 1).build();
+
+        let plan = planner.plan(initial_state, &goal, &[chop]).unwrap();
+        assert_eq!(plan.actions.len(), 3);
+        assert_eq!(plan.stats.nodes_expanded, 3);
+        assert_eq!(plan.stats.nodes_generated, 3);
+        assert!(plan.stats.memory_bytes > 0);
+    }
+
+    /// Test memory cap stops a runaway search
+    /// Validates: Exceeding max_memory_bytes returns BudgetExceeded with the best partial plan
+    /// Failure: Planner ignores the memory cap or returns no partial progress
+    #[test]
+    fn test_planner_memory_limit() {
+        let planner = Planner::with_config(PlannerConfig::new().max_memory_bytes(16 * 1024));
+        let initial_state = State::new().set("gold", 0).set("noise", 0).build();
+        let goal = Goal::new("get_rich").requires("gold", 1_000_000).build();
+        let actions = vec![
+            Action::new("earn").adds("gold", 1).build(),
+            Action::new("fidget").adds("noise", 1).build(),
+        ];
+
+        match planner.plan(initial_state.clone(), &goal, &actions) {
+            Err(PlannerError::BudgetExceeded {
+                budget,
+                best_partial,
+            }) => {
+                assert_eq!(budget, SearchBudget::Memory(16 * 1024));
+                assert!(best_partial.stats.memory_bytes > 16 * 1024);
+                assert!(!best_partial.actions.is_empty());
+
+                // The partial plan should move towards the goal
+                let mut state = initial_state;
+                for action in &best_partial.actions {
+                    state = action.apply_effect(&state);
+                }
+                assert!(state.get::<i64>("gold").unwrap() > 0);
+            }
+            other => panic!("Expected BudgetExceeded error, got {other:?}"),
+        }
+    }
+
+    /// Test planner errors and plans can be compared with `Eq`
+    /// Validates: PlannerError and Plan implement Eq, and a NaN-cost plan equals itself
+    /// Failure: Code requiring `Eq` on planning errors no longer compiles
+    #[test]
+    fn test_planner_error_eq() {
+        fn assert_eq_impl<T: Eq>(value: &T) -> bool {
+            value == value
+        }
+
+        let partial = Plan {
+            cost: f64::NAN,
+            ..Plan::default()
+        };
+        let error = PlannerError::BudgetExceeded {
+            budget: SearchBudget::Memory(1024),
+            best_partial: Box::new(partial),
+        };
+        assert!(assert_eq_impl(&error));
+        assert_ne!(error, PlannerError::NoPlanFound);
+    }
+
+    /// Test observer hooks receive search events
+    /// Validates: plan_observed reports expansions, heuristics, goal tests and transitions
+    /// Failure: Observer callbacks are missing or miscounted
+    #[test]
+    fn test_planner_observer_hooks() {
+        struct Recorder {
+            counts: CountingObserver,
+            satisfied_tests: usize,
+            successors: Vec<usize>,
+        }
+
+        impl PlannerObserver for Recorder {
+            fn on_expand_begin(&mut self, state: &State) {
+                self.counts.on_expand_begin(state);
+            }
+            fn on_expand_end(&mut self, _state: &State, successors: usize) {
+                self.successors.push(successors);
+            }
+            fn on_heuristic(&mut self, state: &State, value: f64) {
+                self.counts.on_heuristic(state, value);
+            }
+            fn on_goal_test(&mut self, state: &State, satisfied: bool) {
+                self.counts.on_goal_test(state, satisfied);
+                if satisfied {
+                    self.satisfied_tests += 1;
+                }
+            }
+        }
+
+        let planner = Planner::new();
+        let initial_state = State::new().set("wood", 0).build();
+        let goal = Goal::new("gather").requires("wood", 2).build();
+        let chop = Action::new("chop").adds("wood", 1).build();
+
+        let mut recorder = Recorder {
+            counts: CountingObserver::default(),
+            satisfied_tests: 0,
+            successors: Vec::new(),
+        };
+        let plan = planner
+            .plan_observed(initial_state, &goal, &[chop], &mut recorder)
+            .unwrap();
+
+        assert_eq!(plan.actions.len(), 2);
+        assert_eq!(recorder.counts.expansions, plan.stats.nodes_expanded);
+        assert_eq!(recorder.counts.heuristic_evaluations, 3); // start + two successors
+        assert_eq!(recorder.counts.goal_tests, 3);
+        assert_eq!(recorder.satisfied_tests, 1);
+        assert_eq!(recorder.successors, vec![1, 1]);
+    }
+
+    /// Test the counting observer tallies transitions
+    /// Validates: CountingObserver counts match the plan statistics
+    /// Failure: Transition events are not reported
+    #[test]
+    fn test_counting_observer_matches_stats() {
+        let planner = Planner::new();
+        let initial_state = State::new().set("gold", 0).build();
+        let goal = Goal::new("earn").requires("gold", 10).build();
+        let actions = vec![
+            Action::new("work").adds("gold", 3).build(),
+            Action::new("steal").adds("gold", 5).cost(4.0).build(),
+        ];
+
+        let mut counter = CountingObserver::default();
+        let plan = planner
+            .plan_observed(initial_state, &goal, &actions, &mut counter)
+            .unwrap();
+        assert_eq!(counter.transitions, plan.stats.nodes_generated);
+        assert_eq!(counter.expansions, plan.stats.nodes_expanded);
+    }
+
+
+    // Tests for strict type checking
+
+    /// Test strict mode reports a precondition type conflict
+    /// Validates: An action requiring I64 where the state holds Bool yields TypeConflict
+    /// Failure: Type conflicts are silently treated as unsatisfiable preconditions
+    #[test]
+    fn test_strict_types_precondition_conflict() {
+        let planner = Planner::with_config(PlannerConfig::new().strict_types(true));
+        let initial_state = State::new().set("gold", true).build();
+        let goal = Goal::new("rich").requires("rich", true).build();
+        let actions = vec![Action::new("buy")
+            .requires("gold", 10)
+            .sets("rich", true)
+            .build()];
+
+        let result = planner.plan(initial_state, &goal, &actions);
+        assert_eq!(
+            result,
+            Err(PlannerError::TypeConflict {
+                key: "gold".to_string(),
+                found: "I64",
+                expected: "Bool",
+                action: Some("buy".to_string()),
+            })
+        );
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("gold"));
+        assert!(message.contains("buy"));
+    }
+
+    /// Test strict mode reports goal and numeric effect conflicts
+    /// Validates: Goal types and Add effects on non-numeric variables are checked
+    /// Failure: Conflicts in the goal or arithmetic effects go unnoticed
+    #[test]
+    fn test_strict_types_goal_and_effect_conflicts() {
+        let planner = Planner::with_config(PlannerConfig::new().strict_types(true));
+
+        let initial_state = State::new().set("location", "home").build();
+        let goal = Goal::new("travel").requires("location", 3).build();
+        let result = planner.plan(initial_state, &goal, &[]);
+        assert!(matches!(
+            result,
+            Err(PlannerError::TypeConflict { action: None, found: "I64", expected: "String", .. })
+        ));
+
+        let initial_state = State::new().set("ready", false).build();
+        let goal = Goal::new("ready").requires("ready", true).build();
+        let actions = vec![Action::new("count").adds("ready", 1).build()];
+        let result = planner.plan(initial_state, &goal, &actions);
+        assert!(matches!(
+            result,
+            Err(PlannerError::TypeConflict { found: "numeric", expected: "Bool", .. })
+        ));
+    }
+
+    /// Test strict mode is off by default and passes consistent problems
+    /// Validates: Non-strict planning keeps the old behaviour; consistent types plan normally
+    /// Failure: Strict checks run by default or reject valid problems
+    #[test]
+    fn test_strict_types_default_and_consistent() {
+        let initial_state = State::new().set("gold", true).build();
+        let goal = Goal::new("rich").requires("rich", true).build();
+        let actions = vec![Action::new("buy")
+            .requires("gold", 10)
+            .sets("rich", true)
+            .build()];
+        assert_eq!(
+            Planner::new().plan(initial_state, &goal, &actions),
+            Err(PlannerError::NoPlanFound)
+        );
+
+        let planner = Planner::with_config(PlannerConfig::new().strict_types(true));
+        let initial_state = State::new().set("gold", 0).set("rich", false).build();
+        let actions = vec![
+            Action::new("mine").adds("gold", 10).build(),
+            Action::new("buy").requires("gold", 10).sets("rich", true).build(),
+        ];
+        let plan = planner.plan(initial_state, &goal, &actions).unwrap();
+        assert_eq!(plan.actions.len(), 2);
+    }
+
+
+    // Tests for planning from multiple initial states
+
+    /// Test plan_from_any picks the candidate state with the cheapest plan
+    /// Validates: The returned index identifies the start state the plan begins from
+    /// Failure: Wrong start state is chosen or the plan is invalid from it
+    #[test]
+    fn test_plan_from_any_chooses_cheapest_start() {
+        let planner = Planner::new();
+        let candidates = vec![
+            State::new().set("at_home", true).set("has_key", false).build(),
+            State::new().set("at_home", false).set("has_key", true).build(),
+        ];
+        let goal = Goal::new("inside").requires("inside", true).build();
+        let actions = vec![
+            Action::new("find_key")
+                .requires("at_home", true)
+                .sets("has_key", true)
+                .cost(5.0)
+                .build(),
+            Action::new("unlock")
+                .requires("has_key", true)
+                .sets("inside", true)
+                .build(),
+        ];
+
+        let (start, plan) = planner.plan_from_any(&candidates, &goal, &actions).unwrap();
+        assert_eq!(start, 1);
+        assert_eq!(plan.actions.len(), 1);
+        assert_eq!(plan.actions[0].name, "unlock");
+
+        let mut state = candidates[start].clone();
+        for action in &plan.actions {
+            assert!(action.can_execute(&state));
+            state = action.apply_effect(&state);
+        }
+        assert!(goal.is_satisfied(&state));
+    }
+
+    /// Test plan_from_any with no usable candidates
+    /// Validates: Empty candidate lists and unreachable goals return NoPlanFound
+    /// Failure: Planner panics or invents a plan
+    #[test]
+    fn test_plan_from_any_no_plan() {
+        let planner = Planner::new();
+        let goal = Goal::new("fly").requires("flying", true).build();
+        assert_eq!(
+            planner.plan_from_any(&[], &goal, &[]),
+            Err(PlannerError::NoPlanFound)
+        );
+
+        let candidates = vec![State::new().set("flying", false).build()];
+        assert_eq!(
+            planner.plan_from_any(&candidates, &goal, &[]),
+            Err(PlannerError::NoPlanFound)
+        );
+    }
+
+
+    /// Test planning to avoid a state
+    /// Validates: The planner finds actions that move away from an excluded value
+    /// Failure: Exclusion requirements are not reachable through planning
+    #[test]
+    fn test_planner_requires_not() {
+        let planner = Planner::new();
+        let initial_state = State::new().set("location", "storm_zone").build();
+        let goal = Goal::new("shelter")
+            .requires_not("location", "storm_zone")
+            .build();
+        let actions = vec![
+            Action::new("wait").build(),
+            Action::new("flee")
+                .requires("location", "storm_zone")
+                .sets("location", "valley")
+                .cost(3.0)
+                .build(),
+        ];
+
+        let plan = planner.plan(initial_state, &goal, &actions).unwrap();
+        let names: Vec<&str> = plan.actions.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["flee"]);
+    }
+
+
+    /// Test planning towards a relative goal
+    /// Validates: requires_delta is resolved against the initial state at plan time
+    /// Failure: Relative goals are ignored or resolved against the wrong baseline
+    #[test]
+    fn test_planner_requires_delta() {
+        let planner = Planner::new();
+        let goal = Goal::new("earn_more").requires_delta("gold", 10).build();
+        let actions = vec![Action::new("work").adds("gold", 5).build()];
+
+        for start in [0, 37] {
+            let initial_state = State::new().set("gold", start).build();
+            let plan = planner.plan(initial_state, &goal, &actions).unwrap();
+            assert_eq!(plan.actions.len(), 2);
+        }
+    }
+
+
+    // Tests for receding-horizon planning
+
+    /// Test horizon planning returns a partial prefix for distant goals
+    /// Validates: Plans are cut at the horizon and flagged as incomplete
+    /// Failure: Horizon is ignored or the prefix does not approach the goal
+    #[test]
+    fn test_plan_horizon_partial() {
+        let planner = Planner::new();
+        let initial_state = State::new().set("distance", 0).build();
+        let goal = Goal::new("arrive").requires("distance", 10).build();
+        let actions = vec![Action::new("step").adds("distance", 1).build()];
+
+        let result = planner
+            .plan_horizon(3, initial_state.clone(), &goal, &actions)
+            .unwrap();
+        assert!(!result.complete);
+        assert_eq!(result.plan.actions.len(), 3);
+
+        // Replanning from the reached state keeps making progress
+        let mut state = initial_state;
+        let mut rounds = 0;
+        loop {
+            let result = planner
+                .plan_horizon(3, state.clone(), &goal, &actions)
+                .unwrap();
+            for action in &result.plan.actions {
+                state = action.apply_effect(&state);
+            }
+            rounds += 1;
+            if result.complete {
+                break;
+            }
+        }
+        assert!(goal.is_satisfied(&state));
+        assert_eq!(rounds, 4);
+    }
+
+    /// Test horizon planning finds complete plans within the horizon
+    /// Validates: Goals reachable within the horizon produce complete plans
+    /// Failure: Complete plans are reported as partial
+    #[test]
+    fn test_plan_horizon_complete() {
+        let planner = Planner::new();
+        let initial_state = State::new().set("has_wood", false).build();
+        let goal = Goal::new("wood").requires("has_wood", true).build();
+        let actions = vec![Action::new("chop").sets("has_wood", true).build()];
+
+        let result = planner
+            .plan_horizon(5, initial_state, &goal, &actions)
+            .unwrap();
+        assert!(result.complete);
+        assert_eq!(result.plan.actions.len(), 1);
+    }
+
+
+    // Tests for annealed heuristic weights
+
+    /// Test annealing improves on the greedy plan when budget allows
+    /// Validates: Later low-weight searches find the cheaper plan
+    /// Failure: Only the first greedy plan is returned
+    #[test]
+    fn test_annealed_weight_improves_plan() {
+        let initial_state = State::new().set("gold", 0).build();
+        let goal = Goal::new("rich").requires("gold", 10).build();
+        let actions = vec![
+            Action::new("jackpot").sets("gold", 10).cost(10.0).build(),
+            Action::new("work").adds("gold", 5).build(),
+        ];
+
+        let generous = Planner::with_config(PlannerConfig::new().annealed_weight(5.0, 1000));
+        let plan = generous
+            .plan(initial_state.clone(), &goal, &actions)
+            .unwrap();
+        assert_eq!(plan.cost, 2.0);
+
+        // With a budget of a single expansion only the greedy plan is found
+        let tight = Planner::with_config(PlannerConfig::new().annealed_weight(5.0, 1));
+        let plan = tight.plan(initial_state, &goal, &actions).unwrap();
+        assert_eq!(plan.cost, 10.0);
+        assert_eq!(plan.stats.nodes_expanded, 1);
+    }
+
+    /// Test the weight schedule
+    /// Validates: Weights decrease linearly from the initial weight to 1.0
+    /// Failure: Schedule never reaches 1.0 or exceeds the initial weight
+    #[test]
+    fn test_weight_schedule() {
+        let schedule = WeightSchedule {
+            initial_weight: 3.0,
+            node_budget: 100,
+        };
+        assert_eq!(schedule.weight_at(0), 3.0);
+        assert_eq!(schedule.weight_at(50), 2.0);
+        assert_eq!(schedule.weight_at(100), 1.0);
+        assert_eq!(schedule.weight_at(500), 1.0);
+    }
+
+
+    // Tests for the public search building blocks
+
+    /// Test successor generation
+    /// Validates: expand yields executable actions with their resulting states
+    /// Failure: Custom searches see different transitions than the planner
+    #[test]
+    fn test_planner_expand_transitions() {
+        let planner = Planner::new();
+        let state = State::new().set("has_axe", false).set("wood", 0).build();
+        let actions = vec![
+            Action::new("chop")
+                .requires("has_axe", true)
+                .adds("wood", 1)
+                .build(),
+            Action::new("get_axe").sets("has_axe", true).cost(2.0).build(),
+        ];
+
+        let transitions: Vec<Transition> = planner.expand(&state, &actions).collect();
+        assert_eq!(transitions.len(), 1);
+        assert_eq!(transitions[0].action.name, "get_axe");
+        assert_eq!(transitions[0].action_index, 1);
+        assert_eq!(transitions[0].cost, 2.0);
+        assert_eq!(transitions[0].state.get::<bool>("has_axe"), Some(true));
+    }
+
+    /// Test a custom greedy search built on expand and estimate
+    /// Validates: The public heuristic guides a hand-written search to the goal
+    /// Failure: Public building blocks are insufficient for custom algorithms
+    #[test]
+    fn test_custom_search_with_estimate() {
+        let planner = Planner::new();
+        let goal = Goal::new("wood").requires("wood", 3).build();
+        let actions = vec![
+            Action::new("chop").adds("wood", 1).build(),
+            Action::new("burn").subtracts("wood", 1).build(),
+        ];
+        assert_eq!(
+            planner
+                .estimate(&State::new().set("wood", 1).build(), &goal)
+                .unwrap(),
+            2.0
+        );
+
+        let mut state = State::new().set("wood", 0).build();
+        let mut steps = 0;
+        while !goal.is_satisfied(&state) && steps < 10 {
+            let next = planner
+                .expand(&state, &actions)
+                .min_by(|a, b| {
+                    let ha = planner.estimate(&a.state, &goal).unwrap();
+                    let hb = planner.estimate(&b.state, &goal).unwrap();
+                    ha.total_cmp(&hb)
+                })
+                .unwrap();
+            state = next.state;
+            steps += 1;
+        }
+        assert_eq!(steps, 3);
+    }
+
+    // Tests for beam search
+
+    /// Test planning with a narrow beam
+    /// Validates: Beam search reaches the goal with a valid, correctly costed plan
+    /// Failure: The beam strategy is ignored or produces invalid plans
+    #[test]
+    fn test_beam_search_finds_plan() {
+        let goal = Goal::new("planks").requires("planks", 2).build();
+        let actions = vec![
+            Action::new("get_axe").sets("has_axe", true).build(),
+            Action::new("chop")
+                .requires("has_axe", true)
+                .adds("wood", 1)
+                .build(),
+            Action::new("saw")
+                .requires("wood", 1)
+                .subtracts("wood", 1)
+                .adds("planks", 1)
+                .build(),
+            Action::new("rest").sets("rested", true).cost(0.5).build(),
+        ];
+        let initial_state = State::new()
+            .set("has_axe", false)
+            .set("wood", 0)
+            .set("planks", 0)
+            .set("rested", false)
+            .build();
+        let planner =
+            Planner::with_config(PlannerConfig::new().strategy(SearchStrategy::Beam { width: 2 }));
+
+        let plan = planner
+            .plan(initial_state.clone(), &goal, &actions)
+            .unwrap();
+        let mut state = initial_state;
+        for action in &plan.actions {
+            assert!(action.can_execute(&state));
+            state = action.apply_effect(&state);
+        }
+        assert!(goal.is_satisfied(&state));
+        let total: f64 = plan.actions.iter().map(|action| action.cost).sum();
+        assert_eq!(plan.cost, total);
+        assert!(plan.stats.nodes_expanded > 0);
+    }
+
+    /// Test that a wide beam behaves like breadth-first search
+    /// Validates: With enough width, the shortest plan is found
+    /// Failure: Beam layers are not explored in depth order
+    #[test]
+    fn test_beam_search_wide_beam_matches_astar() {
+        let goal = Goal::new("rich").requires("gold", 10).build();
+        let actions = vec![
+            Action::new("work").adds("gold", 5).build(),
+            Action::new("beg").adds("gold", 1).build(),
+        ];
+        let initial_state = State::new().set("gold", 0).build();
+        let beam = Planner::with_config(
+            PlannerConfig::new().strategy(SearchStrategy::Beam { width: 100 }),
+        )
+        .plan(initial_state.clone(), &goal, &actions)
+        .unwrap();
+        let astar = Planner::new().plan(initial_state, &goal, &actions).unwrap();
+
+        assert_eq!(beam, astar);
+    }
+
+    /// Test an unreachable goal with beam search
+    /// Validates: Beam search terminates with NoPlanFound when the beam empties
+    /// Failure: The search loops forever on revisited states
+    #[test]
+    fn test_beam_search_no_plan() {
+        let goal = Goal::new("flag").requires("flag", true).build();
+        let actions = vec![
+            Action::new("toggle_on").sets("other", true).build(),
+            Action::new("toggle_off").sets("other", false).build(),
+        ];
+        let initial_state = State::new().set("flag", false).set("other", false).build();
+        let planner =
+            Planner::with_config(PlannerConfig::new().strategy(SearchStrategy::Beam { width: 0 }));
+
+        let result = planner.plan(initial_state, &goal, &actions);
+        assert!(matches!(result, Err(PlannerError::NoPlanFound)));
+    }
+
+    // Tests for plan enumeration
+
+    /// Test listing every way to reach a goal
+    /// Validates: All loop-free plans within the depth are returned, cheapest first
+    /// Failure: Plans are missing, duplicated or unsorted
+    #[test]
+    fn test_enumerate_plans() {
+        let goal = Goal::new("fed").requires("fed", true).build();
+        let actions = vec![
+            Action::new("cook")
+                .requires("has_food", true)
+                .sets("fed", true)
+                .cost(1.0)
+                .build(),
+            Action::new("buy_food")
+                .sets("has_food", true)
+                .cost(2.0)
+                .build(),
+            Action::new("eat_out").sets("fed", true).cost(5.0).build(),
+        ];
+        let initial_state = State::new()
+            .set("fed", false)
+            .set("has_food", false)
+            .build();
+
+        let plans = Planner::new()
+            .enumerate_plans(initial_state, &goal, &actions, 3)
+            .unwrap();
+        let names: Vec<Vec<&str>> = plans
+            .iter()
+            .map(|plan| plan.actions.iter().map(|a| a.name.as_str()).collect())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                vec!["buy_food", "cook"],
+                vec!["eat_out"],
+                vec!["buy_food", "eat_out"],
+            ]
+        );
+        assert_eq!(plans[0].cost, 3.0);
+    }
+
+    /// Test the depth limit of plan enumeration
+    /// Validates: Plans longer than max_depth are excluded, and a satisfied goal gives one empty plan
+    /// Failure: The depth limit is off by one or the trivial plan is missing
+    #[test]
+    fn test_enumerate_plans_depth_limit() {
+        let goal = Goal::new("rich").requires("gold", 2).build();
+        let actions = vec![Action::new("work").adds("gold", 1).build()];
+        let planner = Planner::new();
+
+        let poor = State::new().set("gold", 0).build();
+        assert!(
+            planner
+                .enumerate_plans(&poor, &goal, &actions, 1)
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            planner
+                .enumerate_plans(poor, &goal, &actions, 2)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let rich = State::new().set("gold", 2).build();
+        let plans = planner.enumerate_plans(rich, &goal, &actions, 2).unwrap();
+        assert_eq!(plans.len(), 1);
+        assert!(plans[0].actions.is_empty());
+    }
+
+    // Tests for time budgets
+
+    /// A clock that advances one millisecond every time it is read
+    #[derive(Debug, Default)]
+    struct TickClock(std::sync::atomic::AtomicU64);
+
+    impl Clock for TickClock {
+        fn now(&self) -> std::time::Duration {
+            let ticks = self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            std::time::Duration::from_millis(ticks)
+        }
+    }
+
+    /// Test stopping a search that exceeds its time budget
+    /// Validates: An injected clock drives the budget and the best partial plan is returned
+    /// Failure: Time budgets depend on Instant or are ignored
+    #[test]
+    fn test_max_duration_with_injected_clock() {
+        let goal = Goal::new("rich").requires("gold", 1000).build();
+        let actions = vec![
+            Action::new("work").adds("gold", 1).build(),
+            Action::new("rest").subtracts("gold", 1).build(),
+        ];
+        let initial_state = State::new().set("gold", 0).build();
+        let clock = std::sync::Arc::new(TickClock::default());
+        let planner = Planner::with_config(
+            PlannerConfig::new()
+                .max_duration(std::time::Duration::from_millis(10))
+                .clock(clock.clone()),
+        );
+
+        match planner.plan(initial_state.clone(), &goal, &actions) {
+            Err(PlannerError::BudgetExceeded {
+                budget,
+                best_partial,
+            }) => {
+                assert_eq!(
+                    budget,
+                    SearchBudget::Duration(std::time::Duration::from_millis(10))
+                );
+                assert!(!best_partial.actions.is_empty());
+                assert!(best_partial.actions.iter().all(|a| a.name == "work"));
+            }
+            other => panic!("expected BudgetExceeded, got {other:?}"),
+        }
+
+        let generous = Planner::with_config(
+            PlannerConfig::new()
+                .max_duration(std::time::Duration::from_secs(10))
+                .clock(clock),
+        );
+        assert_eq!(
+            generous
+                .plan(initial_state, &goal, &actions)
+                .unwrap()
+                .actions
+                .len(),
+            1000
+        );
+    }
+
+    /// Test the default std-backed clock
+    /// Validates: A generous time budget does not interfere with planning
+    /// Failure: The default clock stops searches early
+    #[test]
+    fn test_max_duration_with_std_clock() {
+        let goal = Goal::new("rich").requires("gold", 10).build();
+        let actions = vec![Action::new("work").adds("gold", 5).build()];
+        let planner = Planner::with_config(
+            PlannerConfig::new().max_duration(std::time::Duration::from_secs(60)),
+        );
+        let plan = planner
+            .plan(State::new().set("gold", 0).build(), &goal, &actions)
+            .unwrap();
+        assert_eq!(plan.actions.len(), 2);
+    }
+
+    /// Test the node, plan length and duration budgets
+    /// Validates: Each limit stops the search with BudgetExceeded and partial diagnostics
+    /// Failure: Searches over large action sets hang or fail without explanation
+    #[test]
+    fn test_search_budgets() {
+        let goal = Goal::new("rich").requires("gold", 1000).build();
+        let actions = vec![
+            Action::new("work").adds("gold", 1).build(),
+            Action::new("rest").subtracts("gold", 1).build(),
+        ];
+        let initial_state = State::new().set("gold", 0).build();
+
+        let nodes = Planner::with_config(PlannerConfig::new().max_nodes_expanded(50));
+        match nodes.plan(&initial_state, &goal, &actions) {
+            Err(PlannerError::BudgetExceeded {
+                budget,
+                best_partial,
+            }) => {
+                assert_eq!(budget, SearchBudget::NodesExpanded(50));
+                assert_eq!(best_partial.stats.nodes_expanded, 50);
+                assert!(best_partial.actions.iter().all(|a| a.name == "work"));
+            }
+            other => panic!("expected BudgetExceeded, got {other:?}"),
+        }
+
+        let length = Planner::with_config(PlannerConfig::new().max_plan_length(5));
+        let error = length.plan(&initial_state, &goal, &actions).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Search budget of plan length of 5 exceeded after 9 node expansions; \
+             best partial plan has 5 steps"
+        );
+        let small = Goal::new("some").requires("gold", 3).build();
+        assert_eq!(
+            length
+                .plan(&initial_state, &small, &actions)
+                .unwrap()
+                .actions
+                .len(),
+            3
+        );
+
+        let duration = Planner::with_config(
+            PlannerConfig::new()
+                .max_duration(std::time::Duration::from_millis(10))
+                .clock(std::sync::Arc::new(TickClock::default())),
+        );
+        assert!(matches!(
+            duration.plan(&initial_state, &goal, &actions),
+            Err(PlannerError::BudgetExceeded {
+                budget: SearchBudget::Duration(_),
+                ..
+            })
+        ));
+    }
+
+    /// Test anytime planning under a node budget
+    /// Validates: An interrupted search returns the partial plan closest to the goal
+    /// Failure: Running out of budget fails instead of returning a plan to act on
+    #[test]
+    fn test_plan_anytime() {
+        let goal = Goal::new("rich").requires("gold", 1000).build();
+        let actions = vec![
+            Action::new("work").adds("gold", 1).build(),
+            Action::new("rest").subtracts("gold", 1).build(),
+        ];
+        let initial_state = State::new().set("gold", 0).build();
+        let planner = Planner::with_config(PlannerConfig::new().max_nodes_expanded(20));
+
+        let partial = planner
+            .plan_anytime(&initial_state, &goal, &actions)
+            .unwrap();
+        assert!(!partial.complete);
+        assert!(!partial.plan.actions.is_empty());
+        assert!(partial.plan.actions.iter().all(|a| a.name == "work"));
+
+        let small = Goal::new("some").requires("gold", 3).build();
+        let complete = planner
+            .plan_anytime(&initial_state, &small, &actions)
+            .unwrap();
+        assert!(complete.complete);
+        assert_eq!(complete.plan.actions.len(), 3);
+    }
+
+    /// Test anytime planning for an unreachable goal
+    /// Validates: Exhausting the search space still reports that no plan exists
+    /// Failure: Unreachable goals are reported as partial plans
+    #[test]
+    fn test_plan_anytime_unreachable() {
+        let goal = Goal::new("fly").requires("flying", true).build();
+        let actions = vec![Action::new("walk").sets("walking", true).build()];
+        let planner = Planner::with_config(PlannerConfig::new().max_nodes_expanded(100));
+
+        assert!(matches!(
+            planner.plan_anytime(State::new().build(), &goal, &actions),
+            Err(PlannerError::NoPlanFound)
+        ));
+    }
+
+    /// Test planning for several goals with different priorities
+    /// Validates: Higher-priority goals are completed first when plans cost the same
+    /// Failure: Priorities do not influence the order of equally cheap actions
+    #[test]
+    fn test_plan_goals_orders_by_priority() {
+        let initial_state = State::new()
+            .set("fed", false)
+            .set("safe", false)
+            .set("rested", false)
+            .build();
+        let actions = vec![
+            Action::new("forage").sets("fed", true).build(),
+            Action::new("take_cover").sets("safe", true).build(),
+            Action::new("sleep").sets("rested", true).build(),
+        ];
+        let goals = [
+            Goal::new("eat").requires("fed", true).priority(1).build(),
+            Goal::new("rest")
+                .requires("rested", true)
+                .priority(3)
+                .build(),
+            Goal::new("hide").requires("safe", true).priority(9).build(),
+        ];
+
+        let result = Planner::new()
+            .plan_goals(initial_state, &goals, &actions)
+            .unwrap();
+        let names: Vec<&str> = result
+            .plan
+            .actions
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect();
+        assert_eq!(names, ["take_cover", "sleep", "forage"]);
+        assert_eq!(result.plan.cost, 3.0);
+
+        let order: Vec<(&str, usize)> = result
+            .completions
+            .iter()
+            .map(|c| (c.goal.as_str(), c.step))
+            .collect();
+        assert_eq!(order, [("hide", 1), ("rest", 2), ("eat", 3)]);
+    }
+
+    /// Test that priorities never make a multi-goal plan more expensive
+    /// Validates: Cost takes precedence over priority ordering
+    /// Failure: A costlier plan is chosen to satisfy a high-priority goal first
+    #[test]
+    fn test_plan_goals_prefers_cheaper_plan() {
+        let initial_state = State::new().set("gold", 0).set("armed", false).build();
+        let actions = vec![
+            Action::new("work").adds("gold", 10).build(),
+            Action::new("buy_sword")
+                .requires("gold", 10)
+                .subtracts("gold", 10)
+                .sets("armed", true)
+                .build(),
+        ];
+        let goals = [
+            Goal::new("rich").requires("gold", 10).priority(1).build(),
+            Goal::new("armed")
+                .requires("armed", true)
+                .priority(5)
+                .build(),
+        ];
+
+        let result = Planner::new()
+            .plan_goals(initial_state, &goals, &actions)
+            .unwrap();
+        let names: Vec<&str> = result
+            .plan
+            .actions
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect();
+        assert_eq!(names, ["work", "buy_sword", "work"]);
+        // "rich" is lost by buying the sword and only holds again after the final step
+        assert_eq!(result.completions[0].goal, "armed");
+        assert_eq!(result.completions[0].step, 2);
+        assert_eq!(result.completions[1].goal, "rich");
+        assert_eq!(result.completions[1].step, 3);
+    }
+
+    /// Test merging goals
+    /// Validates: Numeric minimums combine and contradictions are rejected
+    /// Failure: Merged goals accept states violating one of the goals
+    #[test]
+    fn test_goal_merge() {
+        let goals = [
+            Goal::new("a")
+                .requires("gold", 5)
+                .requires("armed", true)
+                .build(),
+            Goal::new("b").requires("gold", 8).priority(4).build(),
+        ];
+        let merged = Goal::merge(&goals).unwrap();
+        assert_eq!(merged.name, "a+b");
+        assert_eq!(merged.priority, 4);
+        assert!(!merged.is_satisfied(&State::new().set("gold", 6).set("armed", true).build()));
+        assert!(merged.is_satisfied(&State::new().set("gold", 8).set("armed", true).build()));
+
+        let contradicting = [
+            Goal::new("home").requires("location", "home").build(),
+            Goal::new("away").requires("location", "away").build(),
+        ];
+        assert!(matches!(
+            Goal::merge(&contradicting),
+            Err(DefinitionError::ConflictingRequirement { .. })
+        ));
+        assert!(matches!(
+            Planner::new().plan_goals(State::empty(), &contradicting, &[]),
+            Err(PlannerError::NoPlanFound)
+        ));
+    }
+
+    /// Test querying plan steps by tag
+    /// Validates: Only tagged steps are returned, in plan order
+    /// Failure: Untagged steps are returned or order is lost
+    #[test]
+    fn test_plan_actions_with_tag() {
+        let actions = vec![
+            Action::new("walk_to_forest")
+                .sets("at_forest", true)
+                .tag("movement")
+                .build(),
+            Action::new("chop")
+                .requires("at_forest", true)
+                .sets("has_wood", true)
+                .build(),
+            Action::new("walk_home")
+                .requires("has_wood", true)
+                .sets("at_home", true)
+                .tag("movement")
+                .build(),
+        ];
+        let goal = Goal::new("home_with_wood")
+            .requires("has_wood", true)
+            .requires("at_home", true)
+            .build();
+        let plan = Planner::new()
+            .plan(State::empty(), &goal, &actions)
+            .unwrap();
+
+        let movement: Vec<&str> = plan
+            .actions_with_tag("movement")
+            .map(|a| a.name.as_str())
+            .collect();
+        assert_eq!(movement, ["walk_to_forest", "walk_home"]);
+        assert_eq!(plan.actions_with_tag("combat").count(), 0);
+    }
+
+    /// Test excluding actions by tag
+    /// Validates: Excluded actions are never planned, even when cheaper
+    /// Failure: Tag exclusion is ignored or removes untagged actions
+    #[test]
+    fn test_exclude_tags() {
+        let actions = vec![
+            Action::new("smash_door")
+                .sets("door_open", true)
+                .tag("loud")
+                .build(),
+            Action::new("pick_lock")
+                .sets("door_open", true)
+                .cost(4.0)
+                .tag("quiet")
+                .build(),
+        ];
+        let goal = Goal::new("enter").requires("door_open", true).build();
+
+        let plan = Planner::new()
+            .plan(State::empty(), &goal, &actions)
+            .unwrap();
+        assert_eq!(plan.actions[0].name, "smash_door");
+
+        let sneaking = Planner::with_config(PlannerConfig::new().exclude_tags(["loud"]));
+        let plan = sneaking.plan(State::empty(), &goal, &actions).unwrap();
+        assert_eq!(plan.actions[0].name, "pick_lock");
+
+        let stuck = Planner::with_config(PlannerConfig::new().exclude_tags(["loud", "quiet"]));
+        assert!(matches!(
+            stuck.plan(State::empty(), &goal, &actions),
+            Err(PlannerError::NoPlanFound)
+        ));
+    }
+
+    /// Test reporting tracked currencies separately from the plan cost
+    /// Validates: Plans report the net change of tracked variables across search strategies
+    /// Failure: Currency totals missing, wrong sign, or differing between strategies
+    #[test]
+    fn test_track_currency() {
+        let actions = vec![
+            Action::new("buy_sword")
+                .requires("gold", 100)
+                .subtracts("gold", 100)
+                .adds("turns", 1)
+                .sets("has_sword", true)
+                .cost(1.0)
+                .build(),
+            Action::new("hire_smith")
+                .requires("has_sword", true)
+                .subtracts("gold", 140)
+                .adds("turns", 2)
+                .sets("sword_sharp", true)
+                .cost(3.0)
+                .build(),
+        ];
+        let goal = Goal::new("armed").requires("sword_sharp", true).build();
+        let state = State::new().set("gold", 300).set("turns", 0).build();
+
+        let config = PlannerConfig::new()
+            .track_currency("gold")
+            .track_currency("turns");
+        let plan = Planner::with_config(config.clone())
+            .plan(state.clone(), &goal, &actions)
+            .unwrap();
+        assert_eq!(plan.cost, 4.0);
+        assert_eq!(plan.currency_change("gold"), Some(-240.0));
+        assert_eq!(plan.currency_change("turns"), Some(3.0));
+        assert_eq!(plan.currency_change("mana"), None);
+        assert!(plan.to_string().contains("Currencies: gold -240, turns +3"));
+
+        let beam = Planner::with_config(config.clone().strategy(SearchStrategy::Beam { width: 4 }));
+        let beam_plan = beam.plan(state.clone(), &goal, &actions).unwrap();
+        assert_eq!(beam_plan.currencies, plan.currencies);
+
+        let domain = Planner::new().compile(&actions).unwrap();
+        let compiled = Planner::with_config(config)
+            .plan_compiled(&domain, &state, &goal)
+            .unwrap();
+        assert_eq!(compiled.currencies, plan.currencies);
+
+        let untracked = Planner::new().plan(state, &goal, &actions).unwrap();
+        assert!(untracked.currencies.is_empty());
+    }
+
+    /// Test postconditions pruning transitions
+    /// Validates: The planner never applies an action whose effects break its
+    /// postconditions, and plans around it
+    /// Failure: Plans overfill storage despite the declared invariant
+    #[test]
+    fn test_postconditions_prune_transitions() {
+        let actions = vec![
+            Action::new("store_crate")
+                .adds("used_storage", 10)
+                .adds("crates", 1)
+                .ensures_expr("used_storage <= storage_space")
+                .unwrap()
+                .build(),
+            Action::new("expand_storage")
+                .adds("storage_space", 20)
+                .cost(2.0)
+                .build(),
+        ];
+        let goal = Goal::new("stock_up").requires("crates", 3).build();
+        let state = State::new()
+            .set("crates", 0)
+            .set("used_storage", 80)
+            .set("storage_space", 100)
+            .build();
+
+        let plan = Planner::new().plan(state.clone(), &goal, &actions).unwrap();
+        assert_eq!(plan.actions.len(), 4);
+        let mut current = state;
+        for action in &plan.actions {
+            current = action.apply_effect(&current);
+            assert!(action.violated_postconditions(&current).is_empty());
+        }
+        assert!(goal.is_satisfied(&current));
+        assert!(
+            actions[0]
+                .to_string()
+                .contains("Postconditions:\n    - used_storage <= storage_space")
+        );
+        assert!(matches!(
+            Planner::new().compile(&actions),
+            Err(CompileError::Unsupported {
+                feature: "postconditions",
+                ..
+            })
+        ));
+    }
+
+    /// Test soft ordering preferences between goal requirements
+    /// Validates: A plan achieving requirements out of the preferred order is
+    /// avoided when the penalty outweighs its savings and chosen otherwise
+    /// Failure: Ordering hints are ignored or treated as hard constraints
+    #[test]
+    fn test_ordering_hints() {
+        let actions = vec![
+            Action::new("hide").sets("hidden", true).cost(2.0).build(),
+            Action::new("move_to_position")
+                .sets("in_position", true)
+                .cost(2.0)
+                .build(),
+            Action::new("hide_in_position")
+                .requires("in_position", true)
+                .sets("hidden", true)
+                .cost(1.0)
+                .build(),
+        ];
+        let start = State::new()
+            .set("hidden", false)
+            .set("in_position", false)
+            .build();
+        let ambush = |penalty: f64| {
+            Goal::new("ambush")
+                .requires("hidden", true)
+                .requires("in_position", true)
+                .prefers_order("hidden", "in_position", penalty)
+                .build()
+        };
+        let names =
+            |plan: &Plan| -> Vec<String> { plan.actions.iter().map(|a| a.name.clone()).collect() };
+        let planner = Planner::new();
+
+        let plan = planner.plan(start.clone(), &ambush(5.0), &actions).unwrap();
+        assert_eq!(names(&plan), vec!["hide", "move_to_position"]);
+        assert_eq!(plan.cost, 4.0);
+
+        let plan = planner
+            .plan(start.clone(), &ambush(0.25), &actions)
+            .unwrap();
+        assert_eq!(names(&plan), vec!["move_to_position", "hide_in_position"]);
+
+        let beam =
+            Planner::with_config(PlannerConfig::new().strategy(SearchStrategy::Beam { width: 4 }));
+        let plan = beam.plan(start, &ambush(5.0), &actions).unwrap();
+        assert_eq!(names(&plan), vec!["hide", "move_to_position"]);
+    }
+
+    /// Test planning from a borrowed initial state
+    /// Validates: Planner::plan and the other entry points accept &State and match the plan found from an owned state
+    /// Failure: Borrowed states are rejected or planned differently
+    #[test]
+    fn test_plan_from_borrowed_state() {
+        let actions = vec![
+            Action::new("chop").sets("has_wood", true).build(),
+            Action::new("build")
+                .requires("has_wood", true)
+                .sets("has_hut", true)
+                .build(),
+        ];
+        let goal = Goal::new("shelter").requires("has_hut", true).build();
+        let state = State::new().set("has_wood", false).build();
+        let planner = Planner::new();
+
+        let borrowed = planner.plan(&state, &goal, &actions).unwrap();
+        let again = planner.plan(&state, &goal, &actions).unwrap();
+        let owned = planner.plan(state.clone(), &goal, &actions).unwrap();
+        assert_eq!(borrowed, owned);
+        assert_eq!(again, owned);
+
+        // The other entry points borrow the state too
+        let goals = planner
+            .plan_goals(&state, std::slice::from_ref(&goal), &actions)
+            .unwrap();
+        assert_eq!(goals.plan, owned);
+        let plans = planner.enumerate_plans(&state, &goal, &actions, 2).unwrap();
+        assert_eq!(plans, std::slice::from_ref(&owned));
+        let contingent = planner.plan_contingent(&state, &goal, &actions, 1).unwrap();
+        assert_eq!(contingent.plan, owned);
+        assert_eq!(state.get::<bool>("has_wood"), Some(false));
+    }
+
+    /// Test compact single-line plan formatting
+    /// Validates: display_compact lists the action names of a plan on one line
+    /// Failure: Compact output spans lines or misreports the cost
+    #[test]
+    fn test_plan_display_compact() {
+        let actions = vec![
+            Action::new("chop").sets("has_wood", true).build(),
+            Action::new("build")
+                .requires("has_wood", true)
+                .sets("has_hut", true)
+                .cost(2.0)
+                .build(),
+        ];
+        let goal = Goal::new("shelter").requires("has_hut", true).build();
+        let plan = Planner::new()
+            .plan(State::empty(), &goal, &actions)
+            .unwrap();
+
+        let compact = plan.display_compact().to_string();
+        assert_eq!(compact, "Plan (total cost: 3.0): chop -> build");
+        assert_eq!(format!("{plan:#}"), compact);
+        assert_eq!(
+            Plan::default().display_compact().to_string(),
+            "Plan (total cost: 0.0): no steps"
+        );
+    }
+
+    /// Test the assumptions recorded with a plan
+    /// Validates: Only variables read before the plan sets them are recorded, with their values and lifetime
+    /// Failure: Plans miss variables they rely on or record ones they overwrite
+    #[test]
+    fn test_plan_assumptions() {
+        let goal = Goal::new("stocked").requires("wood", 5).build();
+        let actions = vec![
+            Action::new("walk").sets("at_forest", true).build(),
+            Action::new("chop")
+                .requires("has_axe", true)
+                .requires("at_forest", true)
+                .adds("wood", 5)
+                .build(),
+        ];
+        let initial_state = State::new()
+            .set("has_axe", true)
+            .set("at_forest", false)
+            .set("wood", 0)
+            .set("weather", "sunny")
+            .build();
+
+        let plan = Planner::new()
+            .plan(&initial_state, &goal, &actions)
+            .unwrap();
+        let keys: Vec<&str> = plan.assumptions.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["has_axe", "wood"]);
+        assert_eq!(
+            plan.assumptions["has_axe"],
+            Assumption {
+                value: Some(StateVar::Bool(true)),
+                until: 2,
+            }
+        );
+        assert_eq!(plan.assumptions["wood"].until, 1);
+
+        let mut state = initial_state.clone();
+        state.set("weather", "rain");
+        state.set("at_forest", true);
+        assert!(plan.assumptions_hold(0, &state));
+        state.set("wood", 2);
+        assert_eq!(plan.broken_assumptions(0, &state), ["wood"]);
+        assert!(plan.assumptions_hold(2, &state));
+    }
 }
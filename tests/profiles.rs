@@ -0,0 +1,115 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for planner profiles selected per goal
+
+    /// Test looking up profiles by name
+    /// Validates: Presets are built in, configured profiles replace them, and
+    /// unknown names are reported when planning
+    /// Failure: Goals silently plan with the wrong settings
+    #[test]
+    fn test_goal_profile_lookup() {
+        let urgent = Goal::new("flee").requires("safe", true).profile("urgent");
+        let optimal = Goal::new("build").requires("safe", true).profile("optimal");
+        let unknown = Goal::new("idle").requires("safe", true).profile("lazy");
+
+        let planner = Planner::new();
+        assert_eq!(
+            planner.goal_profile(&urgent.build()).unwrap(),
+            PlannerProfile::preset("urgent")
+        );
+        assert_eq!(
+            planner.goal_profile(&optimal.build()).unwrap(),
+            Some(PlannerProfile::new())
+        );
+        let plain = Goal::new("rest").requires("safe", true).build();
+        assert_eq!(planner.goal_profile(&plain).unwrap(), None);
+
+        let unknown = unknown.build();
+        let actions = [Action::new("run").sets("safe", true).build()];
+        let error = planner
+            .plan(State::empty(), &unknown, &actions)
+            .unwrap_err();
+        assert_eq!(error, PlannerError::UnknownProfile("lazy".to_string()));
+        assert_eq!(error.to_string(), "Unknown planner profile 'lazy'");
+
+        let beam = PlannerProfile::new().strategy(SearchStrategy::Beam { width: 2 });
+        let planner = Planner::with_config(
+            PlannerConfig::new()
+                .profile("urgent", beam.clone())
+                .profile("lazy", PlannerProfile::new()),
+        );
+        let flee = Goal::new("flee").requires("safe", true).profile("urgent");
+        assert_eq!(planner.goal_profile(&flee.build()).unwrap(), Some(beam));
+        assert!(planner.plan(State::empty(), &unknown, &actions).is_ok());
+    }
+
+    /// Test planning with a profile's excluded tags
+    /// Validates: A profile's tags are excluded in addition to the configuration's,
+    /// only for goals selecting that profile
+    /// Failure: Profiles leak into other goals or replace the configured tags
+    #[test]
+    fn test_profile_excluded_tags() {
+        let actions = vec![
+            Action::new("smash_door")
+                .sets("door_open", true)
+                .tag("loud")
+                .build(),
+            Action::new("pick_lock")
+                .sets("door_open", true)
+                .cost(4.0)
+                .tag("slow")
+                .build(),
+            Action::new("find_key")
+                .sets("door_open", true)
+                .cost(9.0)
+                .build(),
+        ];
+        let planner = Planner::with_config(
+            PlannerConfig::new()
+                .exclude_tags(["slow"])
+                .profile("stealth", PlannerProfile::new().exclude_tags(["loud"])),
+        );
+
+        let enter = Goal::new("enter").requires("door_open", true);
+        let plan = planner
+            .plan(State::empty(), &enter.build(), &actions)
+            .unwrap();
+        assert_eq!(plan.actions[0].name, "smash_door");
+
+        let sneak = Goal::new("sneak")
+            .requires("door_open", true)
+            .profile("stealth")
+            .build();
+        let plan = planner.plan(State::empty(), &sneak, &actions).unwrap();
+        assert_eq!(plan.actions[0].name, "find_key");
+    }
+
+    /// Test a profile replacing the configured budgets
+    /// Validates: Goals selecting a profile search with its budgets instead of
+    /// the configuration's
+    /// Failure: Strategic goals stay bound by the reactive budget
+    #[test]
+    fn test_profile_replaces_budgets() {
+        let planner = Planner::with_config(PlannerConfig::new().max_memory_bytes(1024));
+        let state = State::new().set("gold", 0).build();
+        let actions = vec![Action::new("earn").adds("gold", 1).build()];
+
+        let quick = Goal::new("quick").requires("gold", 20).build();
+        assert!(matches!(
+            planner.plan(state.clone(), &quick, &actions),
+            Err(PlannerError::BudgetExceeded {
+                budget: SearchBudget::Memory(1024),
+                ..
+            })
+        ));
+
+        let saving = Goal::new("saving")
+            .requires("gold", 20)
+            .profile("optimal")
+            .build();
+        let plan = planner.plan(state, &saving, &actions).unwrap();
+        assert_eq!(plan.actions.len(), 20);
+    }
+}
@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    fn crafting_actions() -> Vec<Action> {
+        vec![
+            Action::new("chop")
+                .requires("has_axe", true)
+                .adds("wood", 2)
+                .build(),
+            Action::new("craft_table")
+                .requires("wood", 4)
+                .subtracts("wood", 4)
+                .sets("has_table", true)
+                .build(),
+        ]
+    }
+
+    // Tests for goal regression
+
+    /// Test regressing requirements through a plan suffix
+    /// Validates: Numeric effects shift bounds and Set effects discharge requirements
+    /// Failure: Weakest precondition is too strong or too weak
+    #[test]
+    fn test_regress_plan_suffix() {
+        let actions = crafting_actions();
+        let goal = Goal::new("table").requires("has_table", true).build();
+
+        let before_craft = regress(&goal.desired_state, &actions[1..]).unwrap();
+        assert_eq!(before_craft.get::<i64>("wood"), Some(4));
+        assert!(before_craft.get::<bool>("has_table").is_none());
+
+        let before_chop = regress(&goal.desired_state, &actions).unwrap();
+        assert_eq!(before_chop.get::<i64>("wood"), Some(2));
+        assert_eq!(before_chop.get::<bool>("has_axe"), Some(true));
+
+        // Any state satisfying the regression reaches the goal
+        let mut state = State::new()
+            .set("has_axe", true)
+            .set("wood", 3)
+            .set("has_table", false)
+            .build();
+        assert!(state.satisfies(&before_chop));
+        for action in &actions {
+            assert!(action.can_execute(&state));
+            state = action.apply_effect(&state);
+        }
+        assert!(goal.is_satisfied(&state));
+    }
+
+    /// Test regression detects unachievable suffixes
+    /// Validates: Conflicting Set effects and preconditions return None
+    /// Failure: Regression reports a precondition for an impossible suffix
+    #[test]
+    fn test_regress_conflicts() {
+        let goal = Goal::new("awake").requires("awake", true).build();
+        let sleep = Action::new("sleep").sets("awake", false).build();
+        assert!(regress(&goal.desired_state, &[sleep]).is_none());
+
+        let requirements = State::new().set("location", "town").build();
+        let wait = Action::new("wait").requires("location", "forest").build();
+        assert!(regress(&requirements, &[wait]).is_none());
+
+        let empty = regress(&requirements, &[]).unwrap();
+        assert_eq!(empty, requirements);
+    }
+
+
+    // Tests for plan resumption
+
+    /// Test resuming a plan after some steps were completed externally
+    /// Validates: can_resume_at accepts states that let the remaining steps reach the goal
+    /// Failure: Executor is forced to replan when it could skip completed work
+    #[test]
+    fn test_plan_can_resume_at() {
+        let planner = Planner::new();
+        let initial_state = State::new()
+            .set("has_axe", true)
+            .set("wood", 0)
+            .set("has_table", false)
+            .build();
+        let goal = Goal::new("table").requires("has_table", true).build();
+        let plan = planner
+            .plan(initial_state.clone(), &goal, &crafting_actions())
+            .unwrap();
+        let last = plan.actions.len() - 1;
+        assert_eq!(plan.actions[last].name, "craft_table");
+
+        assert!(plan.can_resume_at(0, &initial_state, &goal));
+        assert!(!plan.can_resume_at(last, &initial_state, &goal));
+
+        // Someone dropped off enough wood: only crafting remains
+        let mut stocked = initial_state.clone();
+        stocked.set("wood", 5);
+        assert!(plan.can_resume_at(last, &stocked, &goal));
+    }
+
+    /// Test resumption at and past the end of a plan
+    /// Validates: Resuming at the end requires the goal to already hold; past the end fails
+    /// Failure: Out-of-range steps panic or report success
+    #[test]
+    fn test_plan_can_resume_at_end() {
+        let planner = Planner::new();
+        let initial_state = State::new()
+            .set("has_axe", true)
+            .set("wood", 4)
+            .set("has_table", false)
+            .build();
+        let goal = Goal::new("table").requires("has_table", true).build();
+        let plan = planner
+            .plan(initial_state.clone(), &goal, &crafting_actions())
+            .unwrap();
+        let end = plan.actions.len();
+
+        assert!(!plan.can_resume_at(end, &initial_state, &goal));
+        let done = State::new().set("has_table", true).build();
+        assert!(plan.can_resume_at(end, &done, &goal));
+        assert!(!plan.can_resume_at(end + 1, &done, &goal));
+    }
+}
@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for renaming state variables of generic action libraries
+
+    /// Test choosing the new name of a key
+    /// Validates: Exact renames win over prefixes, the longest prefix wins and
+    /// other keys are kept
+    /// Failure: Keys are renamed by the wrong rule or shared keys are renamed
+    #[test]
+    fn test_key_precedence() {
+        let map = KeyMap::new()
+            .prefix("self.", "tank_")
+            .prefix("self.weapon.", "cannon_")
+            .rename("self.health", "hull");
+
+        assert_eq!(map.key("self.health"), "hull");
+        assert_eq!(map.key("self.ammo"), "tank_ammo");
+        assert_eq!(map.key("self.weapon.heat"), "cannon_heat");
+        assert_eq!(map.key("enemy_visible"), "enemy_visible");
+        assert!(KeyMap::new().is_empty());
+
+        let state = State::new().set("self.ammo", 3).set("day", true).build();
+        let renamed = map.state(&state);
+        assert_eq!(renamed.get::<i64>("tank_ammo"), Some(3));
+        assert_eq!(renamed.get::<bool>("day"), Some(true));
+        assert_eq!(renamed.get::<i64>("self.ammo"), None);
+
+        let goal = Goal::new("survive")
+            .requires("self.health", 50)
+            .requires_not("self.weapon.jammed", true)
+            .priority(4)
+            .build();
+        let renamed = map.goal(&goal);
+        assert_eq!(renamed.name, "survive");
+        assert_eq!(renamed.priority, 4);
+        assert_eq!(renamed.desired_state.get::<i64>("hull"), Some(50));
+        assert_eq!(renamed.excluded.get::<bool>("cannon_jammed"), Some(true));
+    }
+
+    /// Test renaming every part of an action
+    /// Validates: Preconditions, expression preconditions, effects and dynamic
+    /// effects use the new keys, and dynamic effects still read generic keys
+    /// Failure: Part of an action keeps the generic keys and never applies
+    #[test]
+    fn test_action_remap() {
+        let loot = Action::new("loot")
+            .requires("self.alive", true)
+            .requires_expr("self.bag < 10")
+            .unwrap()
+            .effect_expr("self.gold += self.level * 5")
+            .unwrap()
+            .sets("self.looted", true)
+            .build();
+        let map = KeyMap::new().prefix("self.", "rogue_");
+        let action = map.action(&loot);
+
+        assert_eq!(action.name, "loot");
+        assert!(action.preconditions.contains_key("rogue_alive"));
+        assert!(action.effects.contains_key("rogue_looted"));
+        assert!(action.dynamic_effects.contains_key("rogue_gold"));
+
+        let state = State::new()
+            .set("rogue_alive", true)
+            .set("rogue_bag", 2)
+            .set("rogue_level", 3)
+            .set("rogue_gold", 1)
+            .build();
+        assert!(action.can_execute(&state));
+        let after = action.apply_effect(&state);
+        assert_eq!(after.get::<i64>("rogue_gold"), Some(16));
+        assert_eq!(after.get::<bool>("rogue_looted"), Some(true));
+        assert!(!loot.can_execute(&state));
+    }
+
+    /// Test instantiating one library for several agents
+    /// Validates: Each agent plans with its own keys from the same library
+    /// Failure: Instantiated libraries interfere or cannot be planned with
+    #[test]
+    fn test_instantiate_library() {
+        let library = vec![
+            Action::new("reload")
+                .requires("self.has_clip", true)
+                .sets("self.loaded", true)
+                .build(),
+            Action::new("pick_up_clip")
+                .sets("self.has_clip", true)
+                .build(),
+        ];
+        let goal = Goal::new("armed").requires("self.loaded", true).build();
+        let planner = Planner::new();
+
+        for agent in ["tank", "scout"] {
+            let map = KeyMap::new().prefix("self.", &format!("{agent}_"));
+            let state = State::new()
+                .set(&format!("{agent}_has_clip"), agent == "tank")
+                .build();
+            let plan = planner
+                .plan(state, &map.goal(&goal), &map.actions(&library))
+                .unwrap();
+            let expected = if agent == "tank" { 1 } else { 2 };
+            assert_eq!(plan.actions.len(), expected);
+        }
+        assert!(library[0].preconditions.contains_key("self.has_clip"));
+    }
+}
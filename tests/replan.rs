@@ -0,0 +1,184 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for repairing previous plans with Planner::replan
+
+    fn errand_actions() -> Vec<Action> {
+        vec![
+            Action::new("goto_town")
+                .requires("at_home", true)
+                .sets("at_home", false)
+                .sets("at_town", true)
+                .build(),
+            Action::new("buy_food")
+                .requires("at_town", true)
+                .requires("has_coin", true)
+                .sets("has_coin", false)
+                .sets("has_food", true)
+                .build(),
+            Action::new("go_home")
+                .requires("at_town", true)
+                .sets("at_town", false)
+                .sets("at_home", true)
+                .build(),
+            Action::new("cook")
+                .requires("at_home", true)
+                .requires("has_food", true)
+                .sets("fed", true)
+                .build(),
+            Action::new("work")
+                .cost(5.0)
+                .requires("at_town", true)
+                .sets("has_coin", true)
+                .build(),
+        ]
+    }
+
+    fn names(plan: &Plan) -> Vec<&str> {
+        plan.actions.iter().map(|a| a.name.as_str()).collect()
+    }
+
+    fn errand() -> (Goal, Plan) {
+        let goal = Goal::new("eat").requires("fed", true).build();
+        let start = state! { at_home: true, at_town: false, has_coin: true, has_food: false };
+        let plan = Planner::new()
+            .plan(&start, &goal, &errand_actions())
+            .unwrap();
+        (goal, plan)
+    }
+
+    /// Test reusing the rest of a plan that still works
+    /// Validates: Steps already done are skipped without searching
+    /// Failure: Would indicate agents redo finished steps or search needlessly
+    #[test]
+    fn test_reuse_valid_suffix() {
+        let (goal, plan) = errand();
+        assert_eq!(names(&plan), ["goto_town", "buy_food", "go_home", "cook"]);
+
+        let in_town = state! { at_home: false, at_town: true, has_coin: true, has_food: false };
+        let repaired = Planner::new()
+            .replan(&plan, &in_town, &goal, &errand_actions())
+            .unwrap();
+        assert_eq!(repaired.repair, PlanRepair::Reused { skipped: 1 });
+        assert_eq!(names(&repaired.plan), ["buy_food", "go_home", "cook"]);
+        assert_eq!(repaired.plan.cost, 3.0);
+        assert_eq!(repaired.plan.stats.nodes_expanded, 0);
+    }
+
+    /// Test patching a broken step with a bridge
+    /// Validates: Only the missing coin is planned for and the rest of the plan is kept
+    /// Failure: Would indicate small world changes throw away the whole plan
+    #[test]
+    fn test_patch_broken_step() {
+        let (goal, plan) = errand();
+        // The agent reached town but was robbed
+        let robbed = state! { at_home: false, at_town: true, has_coin: false, has_food: false };
+
+        let repaired = Planner::new()
+            .replan(&plan, &robbed, &goal, &errand_actions())
+            .unwrap();
+        assert_eq!(
+            repaired.repair,
+            PlanRepair::Patched {
+                skipped: 1,
+                at: 1,
+                inserted: 1
+            }
+        );
+        assert_eq!(
+            names(&repaired.plan),
+            ["work", "buy_food", "go_home", "cook"]
+        );
+        assert_eq!(repaired.plan.cost, 8.0);
+        assert!(repaired.plan.assumptions.contains_key("at_town"));
+    }
+
+    /// Test falling back to a full search
+    /// Validates: Plans that cannot be patched are replaced by a new search, and failures are reported
+    /// Failure: Would indicate replanning returns invalid plans or hides that no plan exists
+    #[test]
+    fn test_replan_from_scratch() {
+        let goal = Goal::new("eat").requires("fed", true).build();
+        let actions = vec![
+            Action::new("forage").sets("fed", true).cost(4.0).build(),
+            Action::new("open_tin")
+                .requires("has_tin", true)
+                .requires_absent("spoiled")
+                .sets("fed", true)
+                .build(),
+        ];
+        let previous = Planner::new()
+            .plan(state! { has_tin: true }, &goal, &actions)
+            .unwrap();
+        assert_eq!(names(&previous), ["open_tin"]);
+
+        // The absence precondition cannot be regressed, so no bridge is tried
+        let spoiled = state! { has_tin: true, spoiled: true };
+        let repaired = Planner::new()
+            .replan(&previous, &spoiled, &goal, &actions)
+            .unwrap();
+        assert_eq!(repaired.repair, PlanRepair::Replanned);
+        assert_eq!(names(&repaired.plan), ["forage"]);
+
+        let repaired = Planner::new()
+            .replan(&Plan::default(), &spoiled, &goal, &actions)
+            .unwrap();
+        assert_eq!(repaired.repair, PlanRepair::Replanned);
+        assert_eq!(
+            Planner::new().replan(&previous, &spoiled, &goal, &actions[1..]),
+            Err(PlannerError::NoPlanFound)
+        );
+    }
+
+    /// Test that kept steps are checked as the search would expand them
+    /// Validates: A step the planner's configuration excludes is not reused, even
+    /// though its preconditions still hold
+    /// Failure: Would indicate repaired plans contain actions `plan` would never pick
+    #[test]
+    fn test_replan_checks_steps_like_search() {
+        let goal = Goal::new("arrive").requires("arrived", true).build();
+        let actions = vec![
+            Action::new("teleport")
+                .sets("arrived", true)
+                .tag("magic")
+                .build(),
+            Action::new("walk").sets("arrived", true).cost(3.0).build(),
+        ];
+        let start = state! { arrived: false };
+        let previous = Planner::new().plan(&start, &goal, &actions).unwrap();
+        assert_eq!(names(&previous), ["teleport"]);
+
+        let planner = Planner::with_config(PlannerConfig::new().exclude_tags(["magic"]));
+        let repaired = planner.replan(&previous, &start, &goal, &actions).unwrap();
+        assert_eq!(repaired.repair, PlanRepair::Replanned);
+        assert_eq!(names(&repaired.plan), ["walk"]);
+    }
+
+    /// Test the node budget of bridge searches
+    /// Validates: Bridges are searched within half of the configured node limit,
+    /// and are not tried at all when that leaves no expansions
+    /// Failure: Would indicate repairs can search more than the full plan would
+    #[test]
+    fn test_replan_bridge_budget() {
+        let (goal, plan) = errand();
+        let robbed = state! { at_home: false, at_town: true, has_coin: false, has_food: false };
+
+        let planner = Planner::with_config(PlannerConfig::new().max_nodes_expanded(20));
+        let repaired = planner
+            .replan(&plan, &robbed, &goal, &errand_actions())
+            .unwrap();
+        assert!(matches!(repaired.repair, PlanRepair::Patched { .. }));
+        assert!(repaired.plan.stats.nodes_expanded <= 10);
+
+        // No budget is left for bridges, and the full search hits the node limit
+        let planner = Planner::with_config(PlannerConfig::new().max_nodes_expanded(1));
+        assert!(matches!(
+            planner.replan(&plan, &robbed, &goal, &errand_actions()),
+            Err(PlannerError::BudgetExceeded {
+                budget: SearchBudget::NodesExpanded(1),
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for plan robustness under perturbation
+
+    /// Test that a fragile plan scores lower than a sturdy alternative
+    /// Validates: Jittered numbers block steps and the report counts the blocking step
+    /// Failure: Would indicate robustness hides how often the cheapest plan breaks
+    #[test]
+    fn test_robustness_compares_plans() {
+        let goal = Goal::new("armed").requires("has_sword", true).build();
+        let initial = State::new()
+            .set("gold", 10)
+            .set("at_shop", false)
+            .set("has_sword", false)
+            .build();
+        let walk = Action::new("walk").sets("at_shop", true).build();
+        let buy = Action::new("buy_sword")
+            .requires("at_shop", true)
+            .requires("gold", 10)
+            .sets("has_sword", true)
+            .build();
+        let forge = Action::new("forge_sword")
+            .cost(5.0)
+            .sets("has_sword", true)
+            .build();
+        let cheap = Plan {
+            actions: vec![walk, buy],
+            ..Plan::default()
+        };
+        let sturdy = Plan {
+            actions: vec![forge],
+            ..Plan::default()
+        };
+        let perturbations = Perturbations::new(200).seed(7).jitter("gold", -2, 2);
+
+        let report = cheap.robustness(&initial, &goal, &perturbations);
+        assert_eq!(report.runs, 200);
+        assert!(report.ratio() > 0.4 && report.ratio() < 0.8);
+        assert_eq!(report.weakest_step(), Some(1));
+        assert_eq!(report.successes + report.blocked[&1], 200);
+        assert_eq!(report, cheap.robustness(&initial, &goal, &perturbations));
+
+        let sturdy = sturdy.robustness(&initial, &goal, &perturbations);
+        assert_eq!(sturdy.to_string(), "200/200 runs succeeded (100%)");
+    }
+
+    /// Test failing actions and removed variables
+    /// Validates: Failed actions apply their failure effects and later steps see the result
+    /// Failure: Would indicate perturbed action outcomes are ignored
+    #[test]
+    fn test_robustness_with_action_failures() {
+        let goal = Goal::new("escape").requires("outside", true).build();
+        let initial = State::new()
+            .set("door_open", false)
+            .set("outside", false)
+            .set("has_key", true)
+            .build();
+        let plan = Plan {
+            actions: vec![
+                Action::new("unlock")
+                    .requires("has_key", true)
+                    .sets("door_open", true)
+                    .fails_with("has_key", false)
+                    .build(),
+                Action::new("leave")
+                    .requires("door_open", true)
+                    .sets("outside", true)
+                    .build(),
+            ],
+            ..Plan::default()
+        };
+
+        let never = plan.robustness(&initial, &goal, &Perturbations::new(50));
+        assert_eq!(never.ratio(), 1.0);
+
+        let always = plan.robustness(
+            &initial,
+            &goal,
+            &Perturbations::new(50).action_fails("unlock", 1.0),
+        );
+        assert_eq!(always.successes, 0);
+        assert_eq!(
+            always.to_string(),
+            "0/50 runs succeeded (0%); 50 blocked at step 1"
+        );
+
+        let no_key = plan.robustness(
+            &initial,
+            &goal,
+            &Perturbations::new(50)
+                .remove("has_key", 1.0)
+                .one_of("outside", [true]),
+        );
+        assert_eq!(no_key.blocked.get(&0), Some(&50));
+    }
+}
@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for sampling varied, near-optimal plans
+
+    fn routes() -> Vec<Action> {
+        vec![
+            Action::new("road").sets("at_town", true).cost(4.0).build(),
+            Action::new("forest_trail")
+                .sets("at_town", true)
+                .cost(4.5)
+                .build(),
+            Action::new("mountain_pass")
+                .sets("at_town", true)
+                .cost(9.0)
+                .build(),
+        ]
+    }
+
+    fn sampled(settings: SamplingConfig) -> Plan {
+        let goal = Goal::new("travel").requires("at_town", true).build();
+        Planner::with_config(PlannerConfig::new().sampling(settings))
+            .plan(State::empty(), &goal, &routes())
+            .unwrap()
+    }
+
+    /// Test the variety and quality bound of sampled plans
+    /// Validates: Different seeds pick different near-optimal plans, none costing
+    /// more than the tolerance allows
+    /// Failure: Sampling never varies or picks plans that are far too expensive
+    #[test]
+    fn test_sampling_varies_within_tolerance() {
+        let mut names = std::collections::BTreeSet::new();
+        for seed in 0..32 {
+            let plan = sampled(SamplingConfig {
+                temperature: 1.0,
+                tolerance: 0.25,
+                seed,
+            });
+            assert!(plan.cost <= 4.0 * 1.25);
+            names.insert(plan.actions[0].name.clone());
+        }
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        assert_eq!(names, vec!["forest_trail", "road"]);
+    }
+
+    /// Test reproducibility and zero temperature
+    /// Validates: Equal seeds give equal plans and zero temperature always picks
+    /// the optimal plan
+    /// Failure: Sampled plans cannot be reproduced when debugging
+    #[test]
+    fn test_sampling_deterministic() {
+        let settings = SamplingConfig {
+            temperature: 5.0,
+            tolerance: 1.0,
+            seed: 7,
+        };
+        assert_eq!(sampled(settings), sampled(settings));
+
+        for seed in 0..8 {
+            let plan = sampled(SamplingConfig {
+                temperature: 0.0,
+                tolerance: 1.0,
+                seed,
+            });
+            assert_eq!(plan.actions[0].name, "road");
+        }
+    }
+
+    /// Test sampling multi-step plans
+    /// Validates: Sampled plans reach the goal through several steps and report
+    /// the cost of their actions
+    /// Failure: Sampling stops early or loops between states
+    #[test]
+    fn test_sampling_multi_step() {
+        let actions = vec![
+            Action::new("get_axe").sets("has_axe", true).build(),
+            Action::new("chop")
+                .requires("has_axe", true)
+                .adds("wood", 1)
+                .build(),
+            Action::new("gather_sticks")
+                .adds("wood", 1)
+                .cost(1.5)
+                .build(),
+        ];
+        let start = State::new().set("has_axe", false).set("wood", 0).build();
+        let goal = Goal::new("fire").requires("wood", 3).build();
+
+        for seed in 0..8 {
+            let config = PlannerConfig::new().sampling(SamplingConfig {
+                seed,
+                ..Default::default()
+            });
+            let plan = Planner::with_config(config)
+                .plan(start.clone(), &goal, &actions)
+                .unwrap();
+            let end = plan
+                .actions
+                .iter()
+                .fold(start.clone(), |state, action| action.apply_effect(&state));
+            assert!(goal.is_satisfied(&end));
+            assert!(plan.cost <= 4.0 * 1.2 + 1e-9);
+            assert_eq!(
+                plan.cost,
+                plan.actions.iter().map(|action| action.cost).sum::<f64>()
+            );
+        }
+    }
+}
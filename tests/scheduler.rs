@@ -0,0 +1,151 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::Duration;
+
+    fn gold_request(target: i64, priority: u16) -> (Goal, Vec<Action>, State) {
+        let goal = Goal::new("rich")
+            .requires("gold", target)
+            .priority(priority)
+            .build();
+        let actions = vec![Action::new("work").adds("gold", 1).build()];
+        (goal, actions, State::new().set("gold", 0).build())
+    }
+
+    /// A clock that advances by one millisecond every time it is read.
+    /// A frame reads it once at the start and once before each request.
+    #[derive(Debug, Default)]
+    struct TickClock(AtomicU64);
+
+    impl Clock for TickClock {
+        fn now(&self) -> Duration {
+            Duration::from_millis(self.0.fetch_add(1, Ordering::Relaxed))
+        }
+    }
+
+    // Tests for scheduling plan requests across frames
+
+    /// Test deferring requests to later frames
+    /// Validates: Each frame spends at most its node budget and unfinished requests resume
+    /// Failure: The budget is exceeded or search progress is lost between frames
+    #[test]
+    fn test_requests_span_frames() {
+        let mut scheduler = PlanningScheduler::new(Planner::new(), 5);
+        let (goal, actions, state) = gold_request(12, 1);
+        let id = scheduler.submit(goal, actions, state).unwrap();
+
+        let mut frames = 0;
+        let plan = loop {
+            frames += 1;
+            let mut finished = scheduler.run_frame();
+            if let Some((finished_id, result)) = finished.pop() {
+                assert_eq!(finished_id, id);
+                break result.unwrap();
+            }
+            assert!(frames < 10, "request never finished");
+        };
+        assert_eq!(plan.actions.len(), 12);
+        assert!(frames >= 3);
+        assert_eq!(scheduler.pending(), 0);
+        assert_eq!(scheduler.frame(), frames);
+    }
+
+    /// Test serving requests by goal priority
+    /// Validates: Higher priorities are served first and later requests wait
+    /// Failure: A low-priority request consumes the budget first
+    #[test]
+    fn test_goal_priority_policy() {
+        let mut scheduler = PlanningScheduler::new(Planner::new(), 6);
+        let (goal, actions, state) = gold_request(20, 1);
+        let low = scheduler.submit(goal, actions, state).unwrap();
+        let (goal, actions, state) = gold_request(3, 8);
+        let high = scheduler.submit(goal, actions, state).unwrap();
+
+        let finished = scheduler.run_frame();
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].0, high);
+        assert!(scheduler.is_pending(low));
+    }
+
+    /// Test serving requests by staleness
+    /// Validates: Older requests are served before newer, higher-priority ones
+    /// Failure: Staleness is ignored
+    #[test]
+    fn test_staleness_policy() {
+        let mut scheduler =
+            PlanningScheduler::new(Planner::new(), 3).policy(SchedulingPolicy::Staleness);
+        let (goal, actions, state) = gold_request(4, 1);
+        let old = scheduler.submit(goal, actions, state).unwrap();
+        assert!(scheduler.run_frame().is_empty());
+
+        let (goal, actions, state) = gold_request(1, 9);
+        let new = scheduler.submit(goal, actions, state).unwrap();
+        let finished = scheduler.run_frame();
+        assert_eq!(finished[0].0, old);
+        assert!(scheduler.is_pending(new) || finished.len() == 2);
+    }
+
+    /// Test limiting a request's share of the frame
+    /// Validates: A slice leaves budget for the other requests
+    /// Failure: One request starves the others
+    #[test]
+    fn test_slice_shares_budget() {
+        let mut scheduler = PlanningScheduler::new(Planner::new(), 10).slice(4);
+        let (goal, actions, state) = gold_request(50, 9);
+        let expensive = scheduler.submit(goal, actions, state).unwrap();
+        let (goal, actions, state) = gold_request(2, 1);
+        let cheap = scheduler.submit(goal, actions, state).unwrap();
+
+        let finished = scheduler.run_frame();
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].0, cheap);
+        assert!(scheduler.is_pending(expensive));
+    }
+
+    /// Test the per-frame time budget
+    /// Validates: No further requests are served once the frame's time is spent
+    /// Failure: The time budget is ignored
+    #[test]
+    fn test_time_budget_defers_requests() {
+        let mut scheduler = PlanningScheduler::new(Planner::new(), 100)
+            .time_budget(Duration::from_millis(3))
+            .clock(Arc::new(TickClock::default()));
+        let mut ids = Vec::new();
+        for _ in 0..4 {
+            let (goal, actions, state) = gold_request(1, 1);
+            ids.push(scheduler.submit(goal, actions, state).unwrap());
+        }
+
+        let finished = scheduler.run_frame();
+        assert_eq!(finished.len(), 2);
+        assert_eq!(scheduler.pending(), 2);
+    }
+
+    /// Test cancelling and updating requests
+    /// Validates: Cancelled requests are dropped and state updates apply to queued ones
+    /// Failure: Cancelled requests are still served or updates are lost
+    #[test]
+    fn test_cancel_and_update_state() {
+        let mut scheduler = PlanningScheduler::new(Planner::new(), 2);
+        let (goal, actions, state) = gold_request(5, 1);
+        let cancelled = scheduler
+            .submit(goal.clone(), actions.clone(), &state)
+            .unwrap();
+        let updated = scheduler.submit(goal, actions, state).unwrap();
+
+        assert!(scheduler.cancel(cancelled));
+        assert!(!scheduler.cancel(cancelled));
+        assert!(
+            scheduler
+                .update_state(updated, State::new().set("gold", 4).build())
+                .unwrap()
+        );
+        assert!(!scheduler.update_state(cancelled, State::empty()).unwrap());
+
+        let finished = scheduler.run_frame();
+        assert_eq!(finished[0].0, updated);
+        assert_eq!(finished[0].1.as_ref().unwrap().actions.len(), 1);
+    }
+}
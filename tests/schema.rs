@@ -0,0 +1,208 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    fn rpg_schema() -> StateSchema {
+        StateSchema::new()
+            .key(
+                "health",
+                KeyInfo::new()
+                    .description("Hit points left")
+                    .unit("HP")
+                    .maximum(100)
+                    .category("vitals"),
+            )
+            .key("stamina", KeyInfo::new().maximum(50).category("vitals"))
+            .key("gold", KeyInfo::new().unit("gp").category("inventory"))
+    }
+
+    // Tests for state variable metadata
+
+    /// Test formatting values with metadata
+    /// Validates: Units and maximums are printed and descriptions are available
+    /// Failure: Metadata is lost or printed in the wrong format
+    #[test]
+    fn test_format_value() {
+        let schema = rpg_schema();
+        assert_eq!(
+            schema.format_value("health", &StateVar::I64(75)),
+            "75/100 HP"
+        );
+        assert_eq!(schema.format_value("stamina", &StateVar::I64(5)), "5/50");
+        assert_eq!(
+            schema.format_value("unknown", &StateVar::Bool(true)),
+            "true"
+        );
+        assert_eq!(schema.description("health"), Some("Hit points left"));
+        assert_eq!(schema.description("gold"), None);
+    }
+
+    /// Test displaying a state grouped by category
+    /// Validates: Uncategorised keys come first and categories are sorted, as are keys within them
+    /// Failure: Output order depends on HashMap iteration order
+    #[test]
+    fn test_display_state_grouped() {
+        let state = State::new()
+            .set("stamina", 20)
+            .set("health", 75)
+            .set("gold", 20)
+            .set("alive", true)
+            .build();
+        assert_eq!(
+            rpg_schema().display_state(&state).to_string(),
+            "alive: true\ninventory:\n  gold: 20 gp\nvitals:\n  health: 75/100 HP\n  stamina: 20/50\n"
+        );
+    }
+
+    /// Test displaying a plan with its predicted changes
+    /// Validates: Each step lists the variables it changes with units
+    /// Failure: Changes are missing or unformatted
+    #[test]
+    fn test_display_plan() {
+        let initial_state = State::new().set("health", 40).set("gold", 10).build();
+        let goal = Goal::new("healthy").requires("health", 90).build();
+        let actions = vec![
+            Action::new("buy_potion")
+                .requires("gold", 10)
+                .subtracts("gold", 10)
+                .adds("health", 50)
+                .build(),
+        ];
+        let plan = Planner::new()
+            .plan(initial_state.clone(), &goal, &actions)
+            .unwrap();
+        assert_eq!(
+            rpg_schema().display_plan(&plan, &initial_state).to_string(),
+            "Plan (total cost: 1.0):\nStep 1: buy_potion\n  - gold: 0 gp\n  - health: 90/100 HP\n"
+        );
+    }
+
+    /// Test that plain State display is ordered
+    /// Validates: Variables are listed sorted by key
+    /// Failure: Debug output order is arbitrary
+    #[test]
+    fn test_state_display_sorted() {
+        let state = State::new().set("b", 2).set("c", 3).set("a", 1).build();
+        assert_eq!(state.to_string(), "State:\n  - a: 1\n  - b: 2\n  - c: 3\n");
+    }
+
+    /// Test finding decreased monotonic variables
+    /// Validates: Lower numbers and booleans turning false are reported only for
+    /// variables declared monotonic
+    /// Failure: Monotonic variables are allowed to decrease or others are flagged
+    #[test]
+    fn test_monotonic_decreased() {
+        let schema = StateSchema::new()
+            .key("xp", KeyInfo::new().monotonic())
+            .key("met_king", KeyInfo::new().monotonic())
+            .key("gold", KeyInfo::new().unit("gp"));
+        let before = State::new()
+            .set("xp", 10)
+            .set("met_king", true)
+            .set("gold", 5)
+            .build();
+
+        assert!(schema.is_monotonic("xp"));
+        assert!(!schema.is_monotonic("gold"));
+        let poorer = State::new()
+            .set("xp", 12)
+            .set("met_king", true)
+            .set("gold", 0)
+            .build();
+        assert_eq!(schema.decreased(&before, &poorer), None);
+        let forgot = State::new()
+            .set("xp", 10)
+            .set("met_king", false)
+            .set("gold", 5)
+            .build();
+        assert_eq!(schema.decreased(&before, &forgot), Some("met_king"));
+        let drained = State::new()
+            .set("xp", 3)
+            .set("met_king", true)
+            .set("gold", 5)
+            .build();
+        assert_eq!(schema.decreased(&before, &drained), Some("xp"));
+    }
+
+    /// Test planning with monotonic declarations
+    /// Validates: Actions decreasing a monotonic variable are never applied and
+    /// states where a monotonic flag the goal needs false became true are dead ends
+    /// Failure: Plans rely on undoing irreversible changes
+    #[test]
+    fn test_monotonic_planning() {
+        let actions = vec![
+            Action::new("smash_case")
+                .sets("has_gem", true)
+                .sets("alarm", true)
+                .build(),
+            Action::new("pick_lock")
+                .sets("has_gem", true)
+                .cost(3.0)
+                .build(),
+            Action::new("reset_alarm").sets("alarm", false).build(),
+        ];
+        let start = State::new()
+            .set("has_gem", false)
+            .set("alarm", false)
+            .build();
+        let goal = Goal::new("steal")
+            .requires("has_gem", true)
+            .requires("alarm", false)
+            .build();
+        let schema = StateSchema::new().key("alarm", KeyInfo::new().monotonic());
+
+        let plan = Planner::new().plan(start.clone(), &goal, &actions).unwrap();
+        assert_eq!(plan.cost, 2.0);
+
+        let config = PlannerConfig::new().schema(schema.clone());
+        let plan = Planner::with_config(config.clone())
+            .plan(start.clone(), &goal, &actions)
+            .unwrap();
+        assert_eq!(plan.actions.len(), 1);
+        assert_eq!(plan.actions[0].name, "pick_lock");
+        assert_eq!(plan.stats.dead_ends, 0);
+
+        let plan = Planner::with_config(config.dead_end_pruning(true))
+            .plan(start, &goal, &actions)
+            .unwrap();
+        assert_eq!(plan.actions[0].name, "pick_lock");
+        assert!(plan.stats.dead_ends > 0);
+
+        assert!(analyze_dead_ends(&actions, &goal).rules.is_empty());
+        let analysis = analyze_dead_ends_with_schema(&actions, &goal, &schema);
+        assert_eq!(analysis.rules[0].key, "alarm");
+    }
+
+    #[derive(Clone, Copy)]
+    enum Weather {
+        Rain,
+    }
+
+    impl std::fmt::Display for Weather {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Weather::Rain => write!(f, "rain"),
+            }
+        }
+    }
+
+    impl EnumStateVar for Weather {}
+
+    /// Test enum declarations in the schema
+    /// Validates: `enum_key` records the key's enum type next to its metadata
+    /// Failure: Tools cannot tell which enum a variable holds
+    #[test]
+    fn test_schema_enum_key() {
+        const WEATHER: EnumKey<Weather> = EnumKey::new("weather");
+        let schema = StateSchema::new()
+            .enum_key(WEATHER, KeyInfo::new().description("Current weather"))
+            .key("gold", KeyInfo::new());
+
+        assert!(schema.enum_type("weather").unwrap().ends_with("Weather"));
+        assert_eq!(schema.description("weather"), Some("Current weather"));
+        assert_eq!(schema.enum_type("gold"), None);
+
+        let state = State::new().set_enum(WEATHER, Weather::Rain).build();
+        assert_eq!(schema.display_state(&state).to_string(), "weather: rain\n");
+    }
+}
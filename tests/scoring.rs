@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    fn domain() -> (State, Goal, Vec<Action>) {
+        let initial_state = State::new()
+            .set("wood", 0)
+            .set("has_axe", false)
+            .set("alive", true)
+            .build();
+        let goal = Goal::new("stockpile")
+            .requires("wood", 2)
+            .requires("has_axe", true)
+            .build();
+        let actions = vec![
+            Action::new("get_axe").sets("has_axe", true).cost(2.0).build(),
+            Action::new("chop")
+                .requires("has_axe", true)
+                .adds("wood", 1)
+                .build(),
+            Action::new("juggle_axes")
+                .requires("has_axe", true)
+                .sets("alive", false)
+                .build(),
+        ];
+        (initial_state, goal, actions)
+    }
+
+    fn scripted(actions: &[Action], names: &[&str]) -> Plan {
+        let steps: Vec<Action> = names
+            .iter()
+            .map(|name| actions.iter().find(|a| a.name == *name).unwrap().clone())
+            .collect();
+        Plan {
+            cost: steps.iter().map(|a| a.cost).sum(),
+            actions: steps,
+            ..Plan::default()
+        }
+    }
+
+    // Tests for plan scoring
+
+    /// Test scoring a valid scripted plan
+    /// Validates: Validity, cost, coverage and goal satisfaction of a correct plan
+    /// Failure: Correct external plans are scored as broken
+    #[test]
+    fn test_score_valid_plan() {
+        let (initial_state, goal, actions) = domain();
+        let plan = scripted(&actions, &["get_axe", "chop", "chop"]);
+
+        let score = score_plan(&plan, &initial_state, &goal, &ActionCosts);
+        assert!(score.valid);
+        assert_eq!(score.first_invalid_step, None);
+        assert_eq!(score.total_cost, 4.0);
+        assert!(score.goal_satisfied);
+        assert_eq!(score.goal_coverage, 1.0);
+        assert!(score.invariant_violations.is_empty());
+        assert_eq!(score.final_state.get::<i64>("wood"), Some(2));
+    }
+
+    /// Test scoring an invalid and incomplete plan
+    /// Validates: The first failing step is reported and coverage reflects partial progress
+    /// Failure: Invalid plans are reported as valid or coverage is wrong
+    #[test]
+    fn test_score_invalid_plan() {
+        let (initial_state, goal, actions) = domain();
+        let plan = scripted(&actions, &["chop", "get_axe"]);
+
+        let score = score_plan(&plan, &initial_state, &goal, &ActionCosts);
+        assert!(!score.valid);
+        assert_eq!(score.first_invalid_step, Some(0));
+        assert_eq!(score.total_cost, 0.0);
+        assert!(!score.goal_satisfied);
+        assert_eq!(score.goal_coverage, 0.0);
+
+        let partial = scripted(&actions, &["get_axe", "chop"]);
+        let score = score_plan(&partial, &initial_state, &goal, &ActionCosts);
+        assert!(score.valid);
+        assert_eq!(score.goal_coverage, 0.5);
+    }
+
+    /// Test invariant violations and custom cost models
+    /// Validates: Violations are reported per step and custom costs are used
+    /// Failure: Unsafe plans pass scoring or costs ignore the model
+    #[test]
+    fn test_score_invariants_and_cost_model() {
+        struct Doubled(Vec<Invariant>);
+        impl CostModel for Doubled {
+            fn action_cost(&self, action: &Action, _state: &State) -> f64 {
+                action.cost * 2.0
+            }
+            fn invariants(&self) -> &[Invariant] {
+                &self.0
+            }
+        }
+
+        let (initial_state, goal, actions) = domain();
+        let plan = scripted(&actions, &["get_axe", "juggle_axes", "chop", "chop"]);
+        let model = Doubled(vec![Invariant::new(
+            "stay_alive",
+            State::new().set("alive", true).build(),
+        )]);
+
+        let score = score_plan(&plan, &initial_state, &goal, &model);
+        assert!(score.valid);
+        assert!(score.goal_satisfied);
+        assert_eq!(score.total_cost, 10.0);
+        let steps: Vec<usize> = score.invariant_violations.iter().map(|v| v.step).collect();
+        assert_eq!(steps, vec![2, 3, 4]);
+        assert_eq!(score.invariant_violations[0].invariant, "stay_alive");
+    }
+}
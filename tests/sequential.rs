@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for combining the plans of sequential goals
+
+    fn travel_actions(mine_from_home_only: bool) -> Vec<Action> {
+        let goto_mine = Action::new("goto_mine").sets("at_home", false);
+        let goto_mine = if mine_from_home_only {
+            goto_mine.requires("at_home", true)
+        } else {
+            goto_mine
+        };
+        vec![
+            Action::new("goto_town").sets("at_home", false).build(),
+            Action::new("buy_sword").sets("has_sword", true).build(),
+            goto_mine.build(),
+            Action::new("dig_ore").sets("has_ore", true).build(),
+            Action::new("return_home").sets("at_home", true).build(),
+        ]
+    }
+
+    fn plan_of(actions: &[Action], names: &[&str]) -> Plan {
+        let actions: Vec<Action> = names
+            .iter()
+            .map(|name| actions.iter().find(|a| a.name == *name).unwrap().clone())
+            .collect();
+        Plan {
+            cost: actions.len() as f64,
+            actions,
+            ..Plan::default()
+        }
+    }
+
+    fn names(plan: &Plan) -> Vec<&str> {
+        plan.actions.iter().map(|a| a.name.as_str()).collect()
+    }
+
+    /// Test that a trailing step shared with the next plan runs once
+    /// Validates: The earlier goal's marker moves to the end, where its requirements hold again
+    /// Failure: Would indicate agents walk home twice or goals are marked before they hold
+    #[test]
+    fn test_shared_suffix_runs_once() {
+        let initial_state = state! { at_home: true };
+        let actions = travel_actions(false);
+        let sword = Goal::new("sword")
+            .requires("has_sword", true)
+            .requires("at_home", true)
+            .build();
+        let ore = Goal::new("ore")
+            .requires("has_ore", true)
+            .requires("at_home", true)
+            .build();
+        let sword_plan = plan_of(&actions, &["goto_town", "buy_sword", "return_home"]);
+        let ore_plan = plan_of(&actions, &["goto_mine", "dig_ore", "return_home"]);
+
+        let merged =
+            merge_sequential_plans(&initial_state, &[(&sword, &sword_plan), (&ore, &ore_plan)])
+                .unwrap();
+        assert_eq!(
+            names(&merged.plan),
+            [
+                "goto_town",
+                "buy_sword",
+                "goto_mine",
+                "dig_ore",
+                "return_home"
+            ]
+        );
+        assert_eq!(merged.plan.cost, 5.0);
+        assert_eq!(merged.removed_steps, 1);
+        let steps: Vec<usize> = merged.markers.iter().map(|m| m.step).collect();
+        assert_eq!(steps, [5, 5]);
+
+        // The mine can only be reached from home, so the first trip must end there
+        let actions = travel_actions(true);
+        let sword_plan = plan_of(&actions, &["goto_town", "buy_sword", "return_home"]);
+        let ore_plan = plan_of(&actions, &["goto_mine", "dig_ore", "return_home"]);
+        let merged =
+            merge_sequential_plans(&initial_state, &[(&sword, &sword_plan), (&ore, &ore_plan)])
+                .unwrap();
+        assert_eq!(merged.plan.actions.len(), 6);
+        assert_eq!(merged.removed_steps, 0);
+        assert_eq!(merged.markers[0].step, 3);
+        assert_eq!(merged.markers[1].step, 6);
+    }
+
+    /// Test goals that already hold and plans that no longer work
+    /// Validates: Plans for satisfied goals are dropped and broken sequences return None
+    /// Failure: Would indicate redundant work or invalid combined plans handed to executors
+    #[test]
+    fn test_satisfied_and_broken_plans() {
+        let initial_state = state! { at_home: true };
+        let actions = travel_actions(true);
+        let sword = Goal::new("sword").requires("has_sword", true).build();
+        let armed = Goal::new("armed").requires("has_sword", true).build();
+        let ore = Goal::new("ore").requires("has_ore", true).build();
+        let sword_plan = plan_of(&actions, &["goto_town", "buy_sword"]);
+
+        let merged = merge_sequential_plans(
+            &initial_state,
+            &[(&sword, &sword_plan), (&armed, &sword_plan)],
+        )
+        .unwrap();
+        assert_eq!(names(&merged.plan), ["goto_town", "buy_sword"]);
+        assert_eq!(merged.removed_steps, 2);
+        assert_eq!(
+            merged.markers,
+            [
+                GoalMarker {
+                    goal: "sword".to_string(),
+                    step: 2
+                },
+                GoalMarker {
+                    goal: "armed".to_string(),
+                    step: 2
+                },
+            ]
+        );
+
+        // After the town trip the agent is no longer home, so the mine is out of reach
+        let ore_plan = plan_of(&actions, &["goto_mine", "dig_ore"]);
+        assert!(
+            merge_sequential_plans(&initial_state, &[(&sword, &sword_plan), (&ore, &ore_plan)])
+                .is_none()
+        );
+        assert_eq!(
+            merge_sequential_plans(&initial_state, &[]),
+            Some(MergedPlan::default())
+        );
+    }
+}
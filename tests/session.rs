@@ -0,0 +1,161 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+    use std::collections::HashMap;
+
+    fn gold_domain() -> (Goal, Vec<Action>) {
+        let goal = Goal::new("rich").requires("gold", 20).build();
+        let actions = vec![
+            Action::new("work").adds("gold", 5).build(),
+            Action::new("gamble").adds("gold", 1).cost(3.0).build(),
+        ];
+        (goal, actions)
+    }
+
+    fn run_to_plan(session: &mut PlanningSession, budget: usize) -> (Plan, usize) {
+        let mut ticks = 0;
+        loop {
+            ticks += 1;
+            match session.resume(budget) {
+                SessionStatus::Found(plan) => return (plan, ticks),
+                SessionStatus::Searching => continue,
+                SessionStatus::Failed(error) => panic!("unexpected failure: {error}"),
+            }
+        }
+    }
+
+    // Tests for planning sessions
+
+    /// Test spreading a search over several ticks
+    /// Validates: Small budgets make progress across resume calls and match a full plan
+    /// Failure: Resuming restarts the search or loses progress
+    #[test]
+    fn test_session_resume_across_ticks() {
+        let (goal, actions) = gold_domain();
+        let initial_state = State::new().set("gold", 0).build();
+        let full = Planner::new()
+            .plan(initial_state.clone(), &goal, &actions)
+            .unwrap();
+
+        let mut session =
+            PlanningSession::new(Planner::new(), goal, actions, initial_state).unwrap();
+        let (plan, ticks) = run_to_plan(&mut session, 1);
+        assert!(ticks > 1);
+        assert_eq!(plan, full);
+
+        // Resuming again returns the same plan without searching
+        assert_eq!(session.resume(0), SessionStatus::Found(plan));
+    }
+
+    /// Test state updates along the found plan keep the remaining steps
+    /// Validates: Progress along the plan trims it instead of replanning
+    /// Failure: Executing a step throws the plan away
+    #[test]
+    fn test_session_progress_along_plan() {
+        let (goal, actions) = gold_domain();
+        let initial_state = State::new().set("gold", 0).build();
+        let mut session =
+            PlanningSession::new(Planner::new(), goal, actions, initial_state).unwrap();
+        let (plan, _) = run_to_plan(&mut session, 100);
+        assert_eq!(plan.actions.len(), 4);
+
+        let changes = HashMap::from([("gold".to_string(), StateOperation::add_i64(5))]);
+        session.apply_changes(&changes).unwrap();
+        assert_eq!(session.generation(), 1);
+        match session.resume(0) {
+            SessionStatus::Found(remaining) => {
+                assert_eq!(remaining.actions.len(), 3);
+                assert_eq!(remaining.cost, 3.0);
+            }
+            other => panic!("expected the remaining plan, got {other:?}"),
+        }
+    }
+
+    /// Test state updates off the plan replan from the new state
+    /// Validates: Explored states are re-rooted and unknown states restart the search
+    /// Failure: Plans from a stale state are returned after the world changed
+    #[test]
+    fn test_session_replans_after_unexpected_change() {
+        let (goal, actions) = gold_domain();
+        let initial_state = State::new().set("gold", 0).build();
+        let mut session =
+            PlanningSession::new(Planner::new(), goal.clone(), actions, initial_state).unwrap();
+        run_to_plan(&mut session, 100);
+
+        for gold in [1, 7, 100] {
+            session
+                .set_state(State::new().set("gold", gold).build())
+                .unwrap();
+            let (plan, _) = run_to_plan(&mut session, 100);
+            let mut state = session.state().clone();
+            for action in &plan.actions {
+                state = action.apply_effect(&state);
+            }
+            assert!(goal.is_satisfied(&state), "plan from gold={gold} misses goal");
+        }
+        assert_eq!(session.generation(), 3);
+    }
+
+    /// Test inspecting the open set of a paused search
+    /// Validates: The frontier lists queued states best first with consistent scores
+    /// Failure: Research tooling sees stale, duplicate or misordered states
+    #[test]
+    fn test_session_frontier() {
+        let (goal, actions) = gold_domain();
+        let initial_state = State::new().set("gold", 0).build();
+        let mut session =
+            PlanningSession::new(Planner::new(), goal, actions, initial_state.clone()).unwrap();
+
+        let frontier = session.frontier(5);
+        assert_eq!(frontier.len(), 1);
+        assert_eq!(frontier[0].state, initial_state);
+        assert_eq!((frontier[0].g, frontier[0].depth), (0.0, 0));
+
+        assert_eq!(session.resume(1), SessionStatus::Searching);
+        let frontier = session.frontier(5);
+        assert_eq!(frontier.len(), 2);
+        assert_eq!(frontier[0].state.get::<i64>("gold"), Some(5));
+        assert!(frontier[0].f <= frontier[1].f);
+        for node in &frontier {
+            assert_eq!(node.f, node.g + node.h);
+            assert_eq!(node.depth, 1);
+        }
+        assert_eq!(session.frontier(1).len(), 1);
+    }
+
+    /// Test steering a paused search with seeds and weights
+    /// Validates: Seeded paths join the frontier, invalid seeds are reported, and the
+    /// heuristic weight rescores queued states without breaking the search
+    /// Failure: Injected states are lost or produce plans that do not start at the session's state
+    #[test]
+    fn test_session_seed_and_weight() {
+        let (goal, actions) = gold_domain();
+        let initial_state = State::new().set("gold", 0).build();
+        let full = Planner::new()
+            .plan(initial_state.clone(), &goal, &actions)
+            .unwrap();
+        let mut session =
+            PlanningSession::new(Planner::new(), goal, actions, initial_state).unwrap();
+
+        session.seed(&["work", "work", "work"]).unwrap();
+        let frontier = session.frontier(10);
+        let seeded = frontier
+            .iter()
+            .find(|node| node.state.get::<i64>("gold") == Some(15))
+            .unwrap();
+        assert_eq!((seeded.g, seeded.depth), (3.0, 3));
+        assert_eq!(
+            session.seed(&["work", "fly"]),
+            Err(PlannerError::InvalidSeed("fly".to_string()))
+        );
+
+        session.set_weight(2.0);
+        assert_eq!(session.weight(), 2.0);
+        for node in session.frontier(10) {
+            assert_eq!(node.f, node.g + 2.0 * node.h);
+        }
+        let (plan, _) = run_to_plan(&mut session, 100);
+        assert_eq!(plan.cost, full.cost);
+        assert_eq!(plan.actions.len(), full.actions.len());
+    }
+}
@@ -0,0 +1,102 @@
+#[cfg(test)]
+#[cfg(feature = "signature")]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for stable plan hashes and signatures
+
+    fn chop_plan() -> Plan {
+        let chop = Action::new("chop")
+            .requires("has_axe", true)
+            .sets("has_wood", true)
+            .build();
+        Plan {
+            cost: 1.0,
+            actions: vec![chop],
+            ..Plan::default()
+        }
+    }
+
+    /// Test that digests match reference values computed independently
+    /// Validates: The canonical encoding, SHA-256 and HMAC-SHA256 are stable and standard
+    /// Failure: Would indicate servers and clients on other builds disagree about the same plan
+    #[test]
+    fn test_reference_digests() {
+        assert_eq!(
+            Plan::default().content_hash().to_string(),
+            "b6441ae399286ae7d7b6f8a56d3df1c0a0c4b1c4eb13a0da60b1409718b899de"
+        );
+        let plan = chop_plan();
+        assert_eq!(
+            plan.content_hash().to_string(),
+            "16e69d24e8f0a54e22deeb49b0e518b047dd4b71023ea796e42ccd0233f93080"
+        );
+        assert_eq!(
+            plan.sign(b"key").to_string(),
+            "81afc408ac4b655886d4691f6799fea991d10d546d1d66e68e3f0c448a7887da"
+        );
+        // Keys longer than a block are hashed first
+        assert_eq!(
+            plan.sign(&[b'k'; 100]).to_string(),
+            "6a97328fb43335c2ceccf385d95afa2f2465127a1b08482099bb72cf530bc856"
+        );
+    }
+
+    /// Test which changes affect the digest
+    /// Validates: Content changes alter the digest; search statistics and map order do not
+    /// Failure: Would indicate tampered plans pass validation or identical plans are rejected
+    #[test]
+    fn test_digest_coverage() {
+        let plan = chop_plan();
+        let hash = plan.content_hash();
+
+        let mut searched = plan.clone();
+        searched.stats.nodes_expanded = 40;
+        assert_eq!(searched.content_hash(), hash);
+
+        // Effects inserted in a different order digest the same
+        let forward = Action::new("trade").sets("a", 1).sets("b", 2).build();
+        let backward = Action::new("trade").sets("b", 2).sets("a", 1).build();
+        let digest = |action: Action| {
+            Plan {
+                actions: vec![action],
+                ..Plan::default()
+            }
+            .content_hash()
+        };
+        assert_eq!(digest(forward.clone()), digest(backward));
+
+        let mut cheaper = plan.clone();
+        cheaper.actions[0].cost = 0.5;
+        let mut stronger = plan.clone();
+        stronger.actions[0].add_effect_set("has_wood", false);
+        let renamed = Plan {
+            actions: vec![forward],
+            ..plan.clone()
+        };
+        for changed in [cheaper, stronger, renamed] {
+            assert_ne!(changed.content_hash(), hash);
+        }
+    }
+
+    /// Test signing and verifying plans
+    /// Validates: Signatures verify under the same key only, and digests round-trip through hex
+    /// Failure: Would indicate forged or tampered plans are accepted by the server
+    #[test]
+    fn test_sign_and_verify() {
+        let plan = chop_plan();
+        let signature = plan.sign(b"secret");
+        assert!(plan.verify_signature(b"secret", &signature));
+        assert!(!plan.verify_signature(b"other", &signature));
+        assert!(!plan.verify_signature(b"secret", &plan.content_hash()));
+
+        let text = signature.to_string();
+        assert_eq!(PlanDigest::from_hex(&text), Some(signature));
+        assert_eq!(PlanDigest::from_hex(&text.to_uppercase()), Some(signature));
+        assert_eq!(PlanDigest::from_hex(&text[1..]), None);
+        assert_eq!(PlanDigest::from_hex(&"zz".repeat(32)), None);
+        // Sign prefixes accepted by integer parsing are not hex digits
+        assert_eq!(PlanDigest::from_hex(&"+f".repeat(32)), None);
+        assert_eq!(signature.as_bytes().len(), 32);
+    }
+}
@@ -0,0 +1,99 @@
+#[cfg(test)]
+#[cfg(feature = "small-state")]
+mod tests {
+    use goap::prelude::*;
+    use std::collections::HashMap;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn hash_of<T: Hash>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Test basic set/get/remove on the inline storage
+    /// Validates: Typed access works and keys stay sorted regardless of insertion order
+    /// Failure: Sorted insertion or lookup is broken
+    #[test]
+    fn test_small_state_set_get_remove() {
+        let mut state = State::empty();
+        state.set("zeta", 3);
+        state.set("alpha", true);
+        state.set("mid", "town");
+        state.set("alpha", false);
+
+        assert_eq!(state.len(), 3);
+        assert_eq!(state.get::<bool>("alpha"), Some(false));
+        assert_eq!(state.get::<i64>("zeta"), Some(3));
+        assert_eq!(state.get::<String>("mid"), Some("town".to_string()));
+        assert_eq!(state.get::<i64>("missing"), None);
+
+        let keys: Vec<&str> = state.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["alpha", "mid", "zeta"]);
+
+        assert_eq!(state.remove("mid"), Some(StateVar::from("town")));
+        assert!(!state.contains_key("mid"));
+        assert_eq!(state.len(), 2);
+        assert_eq!(state.remove("mid"), None);
+    }
+
+    /// Test states larger than the inline capacity
+    /// Validates: Variables past the inline capacity move to the heap and stay sorted
+    /// Failure: Spilling loses or reorders entries
+    #[test]
+    fn test_small_state_spills_to_heap() {
+        let mut state = State::empty();
+        for i in (0..40).rev() {
+            state.set(&format!("key_{i:02}"), i);
+        }
+        assert_eq!(state.len(), 40);
+        for i in 0..40 {
+            assert_eq!(state.get::<i64>(&format!("key_{i:02}")), Some(i));
+        }
+        let keys: Vec<&String> = state.keys().collect();
+        let mut sorted = keys.clone();
+        sorted.sort();
+        assert_eq!(keys, sorted);
+
+        for i in 0..30 {
+            state.remove(&format!("key_{i:02}"));
+        }
+        assert_eq!(state.len(), 10);
+        assert_eq!(state.get::<i64>("key_35"), Some(35));
+    }
+
+    /// Test that states share semantics with the map-backed storage
+    /// Validates: apply, clone, equality and hashing ignore insertion order
+    /// Failure: The inline storage diverges from the map-backed State
+    #[test]
+    fn test_small_state_semantics() {
+        let state = State::new()
+            .set("gold", 100)
+            .set("speed", 1.5)
+            .set("location", "town")
+            .set("has_axe", true)
+            .build();
+        let mut reordered = State::empty();
+        reordered.set("has_axe", true);
+        reordered.set("location", "town");
+        reordered.set("speed", 1.5);
+        reordered.set("gold", 100);
+        assert_eq!(reordered, state);
+        assert_eq!(hash_of(&reordered), hash_of(&state));
+
+        let mut changes = HashMap::new();
+        changes.insert("gold".to_string(), StateOperation::subtract_i64(30));
+        changes.insert("missing".to_string(), StateOperation::set_i64(1));
+        let mut applied = state.clone();
+        applied.apply(&changes);
+        assert_eq!(applied.get::<i64>("gold"), Some(70));
+        assert_eq!(applied.get::<i64>("missing"), Some(1));
+        assert_eq!(state.get::<i64>("gold"), Some(100));
+        assert_ne!(applied, state);
+
+        let owned: Vec<(String, StateVar)> = applied.into_iter().collect();
+        let keys: Vec<&str> = owned.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, ["gold", "has_axe", "location", "missing", "speed"]);
+    }
+}
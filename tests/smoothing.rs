@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+    use goap::smoothing::merge_actions;
+
+    // Tests for plan smoothing
+
+    /// Test consecutive movement steps collapse into one composite step
+    /// Validates: Smoothed plans keep cost and behaviour while merging tagged runs
+    /// Failure: Executors still receive every intermediate hop
+    #[test]
+    fn test_smoothed_plan_merges_movement() {
+        let graph = LocationGraph::new("location")
+            .connect("a", "b", 1.0)
+            .connect("b", "c", 1.0)
+            .connect("c", "d", 1.0);
+        let mut actions = graph.movement_actions();
+        actions.push(
+            Action::new("deliver")
+                .requires("location", "d")
+                .sets("delivered", true)
+                .build(),
+        );
+
+        let initial_state = State::new()
+            .set("location", "a")
+            .set("delivered", false)
+            .build();
+        let goal = Goal::new("deliver").requires("delivered", true).build();
+        let plan = Planner::new()
+            .plan(initial_state.clone(), &goal, &actions)
+            .unwrap();
+        assert_eq!(plan.actions.len(), 4);
+
+        let smoothed = plan.smoothed(MOVEMENT_TAG);
+        let names: Vec<&str> = smoothed.actions.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["move_a_to_b..move_c_to_d", "deliver"]);
+        assert_eq!(smoothed.cost, plan.cost);
+        assert_eq!(smoothed.actions[0].cost, 3.0);
+        assert!(smoothed.actions[0].has_tag(MOVEMENT_TAG));
+
+        let mut state = initial_state;
+        for action in &smoothed.actions {
+            assert!(action.can_execute(&state));
+            state = action.apply_effect(&state);
+        }
+        assert!(goal.is_satisfied(&state));
+    }
+
+    /// Test merging combines preconditions and numeric effects exactly
+    /// Validates: Weakest preconditions and composed Add/Subtract/Set effects
+    /// Failure: Composite action behaves differently from the sequence
+    #[test]
+    fn test_merge_actions_composes_effects() {
+        let first = Action::new("walk")
+            .requires("stamina", 2)
+            .subtracts("stamina", 2)
+            .sets("location", "bridge")
+            .build();
+        let second = Action::new("climb")
+            .requires("stamina", 3)
+            .subtracts("stamina", 3)
+            .adds("height", 5)
+            .build();
+
+        let merged = merge_actions(&[first.clone(), second.clone()]).unwrap();
+        assert_eq!(merged.name, "walk..climb");
+        assert_eq!(merged.preconditions.get::<i64>("stamina"), Some(5));
+
+        let start = State::new().set("stamina", 6).set("height", 0).build();
+        let sequential = second.apply_effect(&first.apply_effect(&start));
+        assert_eq!(merged.apply_effect(&start), sequential);
+
+        let low = State::new().set("stamina", 4).set("height", 0).build();
+        assert!(!merged.can_execute(&low));
+        assert!(merge_actions(&[]).is_none());
+    }
+
+    /// Test untagged steps and single tagged steps are left alone
+    /// Validates: Smoothing only replaces runs of two or more tagged actions
+    /// Failure: Smoothing renames or drops unrelated steps
+    #[test]
+    fn test_smoothed_leaves_single_steps() {
+        let plan = Plan {
+            actions: vec![
+                Action::new("move").tag("movement").build(),
+                Action::new("work").build(),
+                Action::new("move").tag("movement").build(),
+            ],
+            cost: 3.0,
+            ..Plan::default()
+        };
+        assert_eq!(plan.smoothed(MOVEMENT_TAG), plan);
+    }
+}
@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+    use std::sync::{Arc, RwLock};
+    use std::thread;
+
+    fn gold_domain() -> (Goal, Vec<Action>) {
+        let goal = Goal::new("rich").requires("gold", 10).build();
+        let actions = vec![Action::new("work").adds("gold", 5).build()];
+        (goal, actions)
+    }
+
+    // Tests for planning from shared state
+
+    /// Test planning from an unchanged shared state
+    /// Validates: The plan matches planning from a plain copy and is not stale
+    /// Failure: Snapshotting changes the plan or reports spurious changes
+    #[test]
+    fn test_plan_shared_fresh() {
+        let (goal, actions) = gold_domain();
+        let state = State::new().set("gold", 0).build();
+        let shared = Arc::new(RwLock::new(state.clone()));
+
+        let result = Planner::new()
+            .plan_shared(&shared, &goal, &actions)
+            .unwrap();
+        assert!(!result.is_stale());
+        assert_eq!(result.snapshot, state);
+        assert_eq!(
+            result.plan,
+            Planner::new().plan(state, &goal, &actions).unwrap()
+        );
+    }
+
+    /// Test detecting changes made while planning
+    /// Validates: Keys written to the shared state during the search are reported
+    /// Failure: Callers trust plans made from outdated state
+    #[test]
+    fn test_plan_shared_reports_stale_keys() {
+        let goal = Goal::new("rich").requires("gold", 10).build();
+        let shared = Arc::new(RwLock::new(
+            State::new().set("gold", 0).set("alarm", false).build(),
+        ));
+        // Another system raises the alarm while the planner is searching
+        let world = Arc::clone(&shared);
+        let actions = vec![
+            Action::new("work")
+                .effect_fn("gold", move |_| {
+                    world.write().unwrap().set("alarm", true);
+                    StateOperation::add_i64(5)
+                })
+                .build(),
+        ];
+
+        let result = Planner::new()
+            .plan_shared(&shared, &goal, &actions)
+            .unwrap();
+        assert!(result.is_stale());
+        assert_eq!(result.changed_keys, vec!["alarm".to_string()]);
+        assert_eq!(result.snapshot.get::<bool>("alarm"), Some(false));
+        assert_eq!(result.plan.actions.len(), 2);
+    }
+
+    /// Test a shared state whose lock was poisoned by a panicking writer
+    /// Validates: Planning still reads the state instead of panicking
+    /// Failure: One crashed system takes planning down with it
+    #[test]
+    fn test_plan_shared_poisoned_lock() {
+        let (goal, actions) = gold_domain();
+        let shared = Arc::new(RwLock::new(State::new().set("gold", 0).build()));
+        let writer = Arc::clone(&shared);
+        let _ = thread::spawn(move || {
+            let _guard = writer.write().unwrap();
+            panic!("writer crashed");
+        })
+        .join();
+        assert!(shared.is_poisoned());
+
+        let result = Planner::new()
+            .plan_shared(&shared, &goal, &actions)
+            .unwrap();
+        assert_eq!(result.plan.actions.len(), 2);
+    }
+}
@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+    use std::sync::Arc;
+
+    // Tests for external planning backends behind the Solver trait
+
+    /// A backend proposing a fixed list of actions, or failing when it has none.
+    #[derive(Debug)]
+    struct Scripted(Vec<Action>);
+
+    impl Solver for Scripted {
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        fn solve(&self, _: &State, _: &Goal, _: &[Action]) -> Result<Plan, PlannerError> {
+            if self.0.is_empty() {
+                return Err(PlannerError::SolverFailed {
+                    solver: self.name().to_string(),
+                    reason: "service unavailable".to_string(),
+                });
+            }
+            Ok(Plan {
+                cost: self.0.iter().map(|action| action.cost).sum(),
+                actions: self.0.clone(),
+                ..Plan::default()
+            })
+        }
+    }
+
+    fn wood_domain() -> (Vec<Action>, Goal) {
+        let actions = vec![
+            Action::new("buy_axe").sets("has_axe", true).build(),
+            Action::new("chop")
+                .requires("has_axe", true)
+                .sets("has_wood", true)
+                .build(),
+        ];
+        let goal = Goal::new("wood").requires("has_wood", true).build();
+        (actions, goal)
+    }
+
+    /// Test that the built-in planner is a solver and external plans are replayed
+    /// Validates: Planner::solve matches plan, and solve_checked rejects plans that fail
+    /// Failure: Would indicate broken plans from external backends reach executors
+    #[test]
+    fn test_checked_solving() {
+        let (actions, goal) = wood_domain();
+        let state = state! { has_axe: false };
+        let planner = Planner::new();
+        assert_eq!(
+            planner.solve_checked(&state, &goal, &actions),
+            planner.plan(&state, &goal, &actions)
+        );
+
+        let skips_axe = Scripted(vec![actions[1].clone()]);
+        assert_eq!(
+            skips_axe.solve_checked(&state, &goal, &actions),
+            Err(PlannerError::SolverFailed {
+                solver: "scripted".to_string(),
+                reason: "step 1 'chop' cannot be performed".to_string(),
+            })
+        );
+        let stops_early = Scripted(vec![actions[0].clone()]);
+        let error = stops_early
+            .solve_checked(&state, &goal, &actions)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Solver 'scripted' failed: the plan does not achieve goal 'wood'"
+        );
+        let works = Scripted(actions.clone());
+        assert_eq!(
+            works.solve_checked(&state, &goal, &actions).unwrap().cost,
+            2.0
+        );
+    }
+
+    /// Test solvers as pipeline stages
+    /// Validates: Failing solvers fall through to later stages and working ones report their stage
+    /// Failure: Would indicate external backends cannot be combined with the built-in search
+    #[test]
+    fn test_solver_pipeline_stage() {
+        let (actions, goal) = wood_domain();
+        let state = state! { has_axe: false };
+
+        let mut pipeline = PlannerPipeline::new(Planner::new())
+            .solver(Arc::new(Scripted(Vec::new())))
+            .search(None);
+        assert_eq!(pipeline.stages()[0], PipelineStage::Solver { index: 0 });
+        let found = pipeline.plan(&state, &goal, &actions).unwrap();
+        assert_eq!((found.stage, found.name), (1, "search"));
+
+        let mut pipeline = PlannerPipeline::new(Planner::new())
+            .solver(Arc::new(Scripted(Vec::new())))
+            .solver(Arc::new(Scripted(actions.clone())));
+        let found = pipeline.plan(&state, &goal, &actions).unwrap();
+        assert_eq!((found.stage, found.name), (1, "solver"));
+        assert_eq!(found.plan.actions.len(), 2);
+
+        let mut failing =
+            PlannerPipeline::new(Planner::new()).solver(Arc::new(Scripted(Vec::new())));
+        assert!(matches!(
+            failing.plan(&state, &goal, &actions),
+            Err(PlannerError::SolverFailed { .. })
+        ));
+    }
+}
@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+    use std::cell::RefCell;
+
+    // Tests for planning from external state sources
+
+    /// Test that planning from a source reads only the domain's variables
+    /// Validates: plan_from_source looks up exactly the keys the goal and actions mention
+    /// Failure: Would indicate agents copy unrelated components every tick
+    #[test]
+    fn test_plan_reads_domain_keys_only() {
+        let reads = RefCell::new(Vec::new());
+        let source = |key: &str| {
+            reads.borrow_mut().push(key.to_string());
+            match key {
+                "has_axe" => Some(StateVar::Bool(true)),
+                "mood" => Some("calm".into_state_var()),
+                _ => None,
+            }
+        };
+        let goal = Goal::new("wood").requires("has_wood", true).build();
+        let actions = vec![
+            Action::new("chop")
+                .requires("has_axe", true)
+                .sets("has_wood", true)
+                .build(),
+        ];
+
+        let plan = Planner::new()
+            .plan_from_source(&source, &goal, &actions)
+            .unwrap();
+        assert_eq!(plan.actions[0].name, "chop");
+        assert_eq!(*reads.borrow(), ["has_axe", "has_wood"]);
+    }
+
+    /// Test reading entities of a world through key readers
+    /// Validates: Each entity source reads its own values and skips variables it lacks
+    /// Failure: Would indicate readers mix up entities or invent missing variables
+    #[test]
+    fn test_key_readers() {
+        struct World {
+            health: Vec<i64>,
+        }
+        let readers = KeyReaders::new().key("health", |world: &World, entity: usize| {
+            world
+                .health
+                .get(entity)
+                .map(|health| StateVar::I64(*health))
+        });
+        let world = World {
+            health: vec![40, 90],
+        };
+
+        let keys = ["health", "mana"];
+        let first = State::from_source(&readers.source(&world, 0), keys);
+        let second = State::from_source(&readers.source(&world, 1), keys);
+        assert_eq!(first, State::new().set("health", 40).build());
+        assert_eq!(second.get::<i64>("health"), Some(90));
+        assert_eq!(
+            State::from_source(&readers.source(&world, 5), keys),
+            State::empty()
+        );
+        assert_eq!(readers.keys(), ["health"]);
+    }
+
+    /// Test reading Bevy components as planning variables
+    /// Validates: KeyReaders::component reads the entity's component and skips entities without it
+    /// Failure: Would indicate the Bevy adapter reads the wrong entity or component
+    #[cfg(feature = "bevy")]
+    #[test]
+    fn test_bevy_components() {
+        use bevy_ecs::prelude::{Component, World};
+
+        #[derive(Component)]
+        struct Gold(i64);
+
+        let mut world = World::new();
+        let rich = world.spawn(Gold(120)).id();
+        let poor = world.spawn_empty().id();
+        let readers = KeyReaders::new().component("gold", |gold: &Gold| gold.0);
+
+        let goal = Goal::new("rich").requires("gold", 100).build();
+        let actions = vec![Action::new("mine").adds("gold", 50).build()];
+        let planner = Planner::new();
+        let plan = planner
+            .plan_from_source(&readers.source(&world, rich), &goal, &actions)
+            .unwrap();
+        assert!(plan.actions.is_empty());
+        assert_eq!(readers.source(&world, poor).read("gold"), None);
+    }
+}
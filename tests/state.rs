@@ -52,11 +52,11 @@ mod tests {
     #[test]
     fn test_state_var_string_distance() {
         assert_eq!(
-            StateVar::String("A".to_string()).distance(&StateVar::String("A".to_string())).unwrap(),
+            StateVar::String("A".into()).distance(&StateVar::String("A".into())).unwrap(),
             0
         );
         assert_eq!(
-            StateVar::String("A".to_string()).distance(&StateVar::String("B".to_string())).unwrap(),
+            StateVar::String("A".into()).distance(&StateVar::String("B".into())).unwrap(),
             1
         );
     }
@@ -146,11 +146,11 @@ mod tests {
 
         // Test String conversion
         let string_var: StateVar = "test".to_string().into();
-        assert_eq!(string_var, StateVar::String("test".to_string()));
+        assert_eq!(string_var, StateVar::String("test".into()));
 
         // Test str conversion
         let str_var: StateVar = "test".into();
-        assert_eq!(str_var, StateVar::String("test".to_string()));
+        assert_eq!(str_var, StateVar::String("test".into()));
 
         // Test smaller integer conversions
         let i32_var: StateVar = 42i32.into();
@@ -176,7 +176,7 @@ mod tests {
         state.set("bool_var", StateVar::Bool(true));
         state.set("int_var", StateVar::I64(42));
         state.set("float_var", StateVar::F64(1500)); // 1.5
-        state.set("string_var", StateVar::String("test".to_string()));
+        state.set("string_var", StateVar::String("test".into()));
 
         assert_eq!(state.get::<bool>("bool_var"), Some(true));
         assert_eq!(state.get::<i64>("int_var"), Some(42));
@@ -253,15 +253,15 @@ mod tests {
     #[test]
     fn test_state_satisfies_string() {
         let mut state = State::empty();
-        state.set("string_var", StateVar::String("test".to_string()));
+        state.set("string_var", StateVar::String("test".into()));
 
         let mut conditions = State::empty();
         // Test exact match
-        conditions.set("string_var", StateVar::String("test".to_string()));
+        conditions.set("string_var", StateVar::String("test".into()));
         assert!(state.satisfies(&conditions));
 
         // Test mismatch
-        conditions.set("string_var", StateVar::String("other".to_string()));
+        conditions.set("string_var", StateVar::String("other".into()));
         assert!(!state.satisfies(&conditions));
     }
 
@@ -301,7 +301,7 @@ mod tests {
         let mut state = State::empty();
         state.set("bool_var", StateVar::Bool(false));
         state.set("int_var", StateVar::I64(10));
-        state.set("string_var", StateVar::String("old".to_string()));
+        state.set("string_var", StateVar::String("old".into()));
 
         let mut changes = HashMap::new();
         changes.insert(
@@ -314,7 +314,7 @@ mod tests {
         );
         changes.insert(
             "string_var".to_string(),
-            StateOperation::Set(StateVar::String("new".to_string())),
+            StateOperation::Set(StateVar::String("new".into())),
         );
 
         state.apply(&changes);
@@ -412,7 +412,7 @@ mod tests {
 
         let mut state2 = State::empty();
         state2.set("var2", StateVar::I64(20)); // Should override
-        state2.set("var3", StateVar::String("test".to_string()));
+        state2.set("var3", StateVar::String("test".into()));
 
         state1.merge(&state2);
 
@@ -512,7 +512,7 @@ mod tests {
     #[test]
     fn test_state_get_enum() {
         let mut state = State::empty();
-        state.set("string_var", StateVar::String("test".to_string()));
+        state.set("string_var", StateVar::String("test".into()));
         state.set("bool_var", StateVar::Bool(true));
 
         // Test successful retrieval
@@ -683,4 +683,290 @@ mod tests {
         state.apply(&changes);
         assert_eq!(state.get::<f64>("value"), Some(3.042));
     }
+
+    /// Test string values are shared between cloned and derived states
+    /// Validates: Cloning a state or applying effects doesn't deep-copy untouched strings
+    /// Failure: String values are duplicated on every successor state
+    #[test]
+    fn test_string_values_are_shared_across_successors() {
+        let long_text = "a long description that would be expensive to copy ".repeat(8);
+        let state = State::new()
+            .set("description", long_text.as_str())
+            .set("gold", 10)
+            .build();
+
+        let action = Action::new("earn").adds("gold", 5).build();
+        let successor = action.apply_effect(&state);
+
+        match (state.var("description"), successor.var("description")) {
+            (Some(StateVar::String(original)), Some(StateVar::String(shared))) => {
+                assert!(std::sync::Arc::ptr_eq(original, shared));
+            }
+            _ => panic!("Expected string values in both states"),
+        }
+        assert_eq!(successor.get::<String>("description"), Some(long_text));
+        assert_eq!(successor.get::<i64>("gold"), Some(15));
+    }
+
+    // Tests for checked numeric conversions
+
+    /// Test fallible conversion of floats to fixed-point values
+    /// Validates: Finite in-range values convert, NaN, infinities and huge values are errors
+    /// Failure: Authoring mistakes are silently saturated or zeroed
+    #[test]
+    fn test_state_var_try_from_f64() {
+        assert_eq!(StateVar::try_from_f64(1.5), Ok(StateVar::F64(1500)));
+        assert_eq!(StateVar::try_from_f64(-0.0004), Ok(StateVar::F64(0)));
+        assert_eq!(
+            StateVar::try_from_f64(f64::NAN),
+            Err(ConversionError::NotFinite)
+        );
+        assert_eq!(
+            StateVar::try_from_f64(f64::NEG_INFINITY),
+            Err(ConversionError::NotFinite)
+        );
+        assert!(matches!(
+            StateVar::try_from_f64(1e17),
+            Err(ConversionError::OutOfRange { target: "F64", .. })
+        ));
+        assert!(StateVar::try_from_f64(9e15).is_ok());
+    }
+
+    /// Test fallible extraction of numeric values
+    /// Validates: try_into_* return values or typed errors with context
+    /// Failure: Precision loss or type mismatches are reported as a bare None
+    #[test]
+    fn test_state_var_try_into() {
+        assert_eq!(StateVar::I64(42).try_into_i32(), Ok(42));
+        assert_eq!(
+            StateVar::I64(i64::from(i32::MAX) + 1).try_into_i32(),
+            Err(ConversionError::OutOfRange {
+                value: "2147483648".to_string(),
+                target: "i32"
+            })
+        );
+        assert_eq!(StateVar::I64(-7).try_into_i64(), Ok(-7));
+        assert_eq!(StateVar::from_f64(2.25).try_into_f64(), Ok(2.25));
+        assert_eq!(
+            StateVar::Bool(true).try_into_f64(),
+            Err(ConversionError::WrongType {
+                expected: "F64",
+                found: "Bool"
+            })
+        );
+        assert_eq!(
+            StateVar::from_f64(1.0).try_into_i32(),
+            Err(ConversionError::WrongType {
+                expected: "I64",
+                found: "F64"
+            })
+        );
+    }
+
+    /// Test reading and removing variables through the accessors
+    /// Validates: Lookups, counts, iteration and removal work without touching the map
+    /// Failure: Code reading states depends on the map type chosen by the ordered-state feature
+    #[test]
+    fn test_state_accessors() {
+        let mut state = State::new().set("gold", 5).set("has_axe", true).build();
+        assert_eq!(state.len(), 2);
+        assert!(state.contains_key("gold"));
+        assert_eq!(state.var("gold"), Some(&StateVar::I64(5)));
+        assert_eq!(state.var("wood"), None);
+
+        let mut keys: Vec<&String> = state.keys().collect();
+        keys.sort();
+        assert_eq!(keys, ["gold", "has_axe"]);
+        assert_eq!(state.iter().len(), 2);
+        assert_eq!((&state).into_iter().count(), 2);
+
+        assert_eq!(state.remove("gold"), Some(StateVar::I64(5)));
+        assert_eq!(state.remove("gold"), None);
+        let rest: Vec<(String, StateVar)> = state.clone().into_iter().collect();
+        assert_eq!(rest, [("has_axe".to_string(), StateVar::Bool(true))]);
+        state.remove("has_axe");
+        assert!(state.is_empty());
+    }
+
+    /// Test converting between states and maps of variables
+    /// Validates: A HashMap of variables builds a state and is recovered from it
+    /// Failure: Code that filled or read the formerly public `vars` map has no
+    /// replacement
+    #[test]
+    fn test_state_hash_map_conversion() {
+        let mut vars = HashMap::new();
+        vars.insert("gold".to_string(), StateVar::I64(5));
+        vars.insert("has_axe".to_string(), StateVar::Bool(true));
+
+        let state = State::from(vars.clone());
+        assert_eq!(state, State::new().set("gold", 5).set("has_axe", true).build());
+        let back: HashMap<String, StateVar> = state.into();
+        assert_eq!(back, vars);
+    }
+
+    /// Test iteration order of the ordered state map
+    /// Validates: With the ordered-state feature, variables iterate in key order
+    /// Failure: The BTreeMap backing is not used
+    #[cfg(feature = "ordered-state")]
+    #[test]
+    fn test_ordered_state_iteration() {
+        let state = State::new().set("c", 3).set("a", 1).set("b", 2).build();
+        let keys: Vec<&str> = state.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["a", "b", "c"]);
+    }
+
+    /// Test collecting a state from an iterator
+    /// Validates: Pairs of any key and value types become state variables
+    /// Failure: Iterator pipelines cannot build states directly
+    #[test]
+    fn test_state_from_iterator() {
+        let components = vec![("health", 80), ("mana", 35)];
+        let state: State = components.into_iter().collect();
+        assert_eq!(state.get::<i64>("health"), Some(80));
+        assert_eq!(state.get::<i64>("mana"), Some(35));
+
+        let raw: State = vec![("alive".to_string(), StateVar::Bool(true))]
+            .into_iter()
+            .collect();
+        assert_eq!(raw.get::<bool>("alive"), Some(true));
+    }
+
+    /// Test extending and iterating a state
+    /// Validates: Extend overwrites existing values and both iterators visit every variable
+    /// Failure: Values are lost or iteration skips variables
+    #[test]
+    fn test_state_extend_and_iterate() {
+        let mut state = State::new().set("gold", 5).build();
+        state.extend([("gold", 10), ("wood", 3)]);
+        assert_eq!(state.get::<i64>("gold"), Some(10));
+
+        let mut borrowed: Vec<(String, StateVar)> = (&state)
+            .into_iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        borrowed.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            borrowed,
+            vec![
+                ("gold".to_string(), StateVar::I64(10)),
+                ("wood".to_string(), StateVar::I64(3)),
+            ]
+        );
+
+        let roundtrip: State = state.clone().into_iter().collect();
+        assert_eq!(roundtrip, state);
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Location {
+        Town,
+        Mine,
+    }
+
+    impl std::fmt::Display for Location {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Location::Town => write!(f, "town"),
+                Location::Mine => write!(f, "mine"),
+            }
+        }
+    }
+
+    impl EnumStateVar for Location {}
+
+    const LOCATION: EnumKey<Location> = EnumKey::new("location");
+
+    /// Test typed enum keys in states, goals and actions
+    /// Validates: `_enum` methods store the enum's text under the key's name
+    /// Failure: Typed requirements never match values set through the same key
+    #[test]
+    fn test_enum_keys() {
+        assert_eq!(LOCATION.name(), "location");
+        let mut state = State::new().set_enum(LOCATION, Location::Town).build();
+        assert_eq!(state.get::<String>("location").as_deref(), Some("town"));
+
+        let goal = Goal::new("mine_ore")
+            .requires_enum(LOCATION, Location::Mine)
+            .build();
+        let travel = Action::new("travel_to_mine")
+            .requires_enum(LOCATION, Location::Town)
+            .sets_enum(LOCATION, Location::Mine)
+            .build();
+        let plan = Planner::new()
+            .plan(state.clone(), &goal, std::slice::from_ref(&travel))
+            .unwrap();
+        assert_eq!(plan.actions[0].name, "travel_to_mine");
+
+        state.set_enum(LOCATION, Location::Mine);
+        assert!(goal.is_satisfied(&state));
+        assert!(!travel.can_execute(&state));
+    }
+
+    /// Test compact single-line state formatting
+    /// Validates: display_compact and the alternate flag list sorted keys on one line
+    /// Failure: Compact output spans lines or depends on insertion order
+    #[test]
+    fn test_state_display_compact() {
+        let state = State::new()
+            .set("has_wood", true)
+            .set("gold", 120)
+            .set("name", "bob")
+            .build();
+
+        let compact = state.display_compact().to_string();
+        assert_eq!(compact, "{gold: 120, has_wood: true, name: bob}");
+        assert_eq!(format!("{state:#}"), compact);
+        assert!(!compact.contains('\n'));
+        assert!(state.to_string().contains('\n'));
+        assert_eq!(State::empty().display_compact().to_string(), "{}");
+    }
+
+    /// Test building states from key-value pairs
+    /// Validates: from_pairs matches the builder, and later pairs overwrite earlier ones
+    /// Failure: Pairs are dropped, converted wrongly, or the first duplicate wins
+    #[test]
+    fn test_state_from_pairs() {
+        let state = State::from_pairs([
+            ("gold", 100.into()),
+            ("has_axe", true.into()),
+            ("name", "bob".into()),
+            ("gold", 120.into()),
+        ]);
+        let expected = State::new()
+            .set("gold", 120)
+            .set("has_axe", true)
+            .set("name", "bob")
+            .build();
+        assert_eq!(state, expected);
+
+        let owned = State::from_pairs(vec![(String::from("speed"), StateVar::from(1.5))]);
+        assert_eq!(owned.get::<f64>("speed"), Some(1.5));
+        assert_eq!(
+            State::from_pairs(Vec::<(&str, StateVar)>::new()),
+            State::empty()
+        );
+    }
+
+    /// Test the state! macro
+    /// Validates: Identifier and string keys with any convertible value build the expected state
+    /// Failure: Keys are mangled, values are converted wrongly, or trailing commas fail to parse
+    #[test]
+    fn test_state_macro() {
+        let gold = 40;
+        let state = state! {
+            gold: gold + 60,
+            has_axe: true,
+            "home.location": "village",
+            speed: 1.5,
+        };
+        let expected = State::new()
+            .set("gold", 100)
+            .set("has_axe", true)
+            .set("home.location", "village")
+            .set("speed", 1.5)
+            .build();
+        assert_eq!(state, expected);
+        assert_eq!(state! { gold: 1, gold: 2 }.get::<i64>("gold"), Some(2));
+        assert_eq!(state! {}, State::empty());
+    }
 }
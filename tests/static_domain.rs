@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+    use goap::static_domain::{StaticAction, StaticDomain, StaticGoal, StaticOp, StaticValue};
+
+    const MOVE_TO_TREE: StaticAction =
+        StaticAction::new("move_to_tree").effects(&[("at_tree", StaticOp::Set(StaticValue::Bool(true)))]);
+
+    static LUMBER_DOMAIN: StaticDomain = StaticDomain::new(
+        &[
+            MOVE_TO_TREE,
+            StaticAction::new("chop_tree")
+                .cost(2.0)
+                .requires(&[
+                    ("has_axe", StaticValue::Bool(true)),
+                    ("at_tree", StaticValue::Bool(true)),
+                ])
+                .effects(&[
+                    ("wood", StaticOp::AddInt(2)),
+                    ("stamina", StaticOp::SubtractFloat(1.5)),
+                ]),
+        ],
+        &[StaticGoal::new("gather_wood")
+            .priority(5)
+            .requires(&[("wood", StaticValue::Int(2))])],
+    );
+
+    /// Test conversion of a const action definition
+    /// Validates: Static preconditions and effects convert to the equivalent runtime action
+    /// Failure: Values or operations are lost during conversion
+    #[test]
+    fn test_static_action_conversion() {
+        let action = Action::from(&LUMBER_DOMAIN.actions[1]);
+        assert_eq!(action.name, "chop_tree");
+        assert_eq!(action.cost, 2.0);
+        assert_eq!(action.preconditions.get::<bool>("has_axe"), Some(true));
+        assert_eq!(action.effects.get("wood"), Some(&StateOperation::Add(2)));
+        assert_eq!(
+            action.effects.get("stamina"),
+            Some(&StateOperation::Subtract(1500))
+        );
+
+        let default_cost = MOVE_TO_TREE.to_action();
+        assert_eq!(default_cost.cost, 1.0);
+        assert!(default_cost.preconditions.is_empty());
+    }
+
+    /// Test goal lookup in a static domain
+    /// Validates: Goals are found by name with their priority and requirements
+    /// Failure: Goal lookup or conversion is broken
+    #[test]
+    fn test_static_goal_lookup() {
+        let goal = LUMBER_DOMAIN.goal("gather_wood").unwrap();
+        assert_eq!(goal.priority, 5);
+        assert_eq!(goal.desired_state.get::<i64>("wood"), Some(2));
+        assert!(LUMBER_DOMAIN.goal("missing").is_none());
+    }
+
+    /// Test planning with a domain declared at compile time
+    /// Validates: A static domain plans identically to a builder-defined domain
+    /// Failure: Static definitions are not usable by the planner
+    #[test]
+    fn test_static_domain_planning() {
+        let initial_state = State::new()
+            .set("has_axe", true)
+            .set("at_tree", false)
+            .set("wood", 0)
+            .set("stamina", 10.0)
+            .build();
+        let goal = LUMBER_DOMAIN.goal("gather_wood").unwrap();
+
+        let plan = Planner::new()
+            .plan(initial_state, &goal, &LUMBER_DOMAIN.actions())
+            .unwrap();
+
+        let names: Vec<&str> = plan.actions.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["move_to_tree", "chop_tree"]);
+        assert_eq!(plan.cost, 3.0);
+    }
+}
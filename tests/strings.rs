@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for string comparison rules
+
+    /// Test canonical forms of each comparison rule
+    /// Validates: Values equal under a rule share a canonical form
+    /// Failure: Designer content with mixed spelling is treated as different values
+    #[test]
+    fn test_string_comparison_equals() {
+        assert!(StringComparison::CaseInsensitive.equals("Town", "tOWN"));
+        assert!(!StringComparison::CaseInsensitive.equals("Town", " town"));
+        assert!(StringComparison::Trimmed.equals("  town\t", "town"));
+        assert!(!StringComparison::Trimmed.equals("Town", "town"));
+        assert!(StringComparison::NumericId.equals("007", " 7 "));
+        assert!(!StringComparison::NumericId.equals("007", "8"));
+        assert!(StringComparison::NumericId.equals("gate", "gate"));
+        assert!(!StringComparison::Exact.equals("Town", "town"));
+    }
+
+    /// Test planning with a case-insensitive variable
+    /// Validates: Preconditions, goals and heuristics use the registered rule
+    /// Failure: The planner treats "Town" and "town" as different worlds
+    #[test]
+    fn test_planning_with_case_insensitive_strings() {
+        let actions = vec![
+            Action::new("travel_to_forest")
+                .requires("location", "TOWN")
+                .sets("location", "Forest")
+                .build(),
+            Action::new("gather_herbs")
+                .requires("location", "forest")
+                .sets("has_herbs", true)
+                .build(),
+        ];
+        let goal = Goal::new("herbs")
+            .requires("has_herbs", true)
+            .requires("location", "FOREST")
+            .build();
+        let initial_state = State::new()
+            .set("location", "Town")
+            .set("has_herbs", false)
+            .build();
+
+        assert!(
+            Planner::new()
+                .plan(initial_state.clone(), &goal, &actions)
+                .is_err()
+        );
+
+        let planner = Planner::with_config(
+            PlannerConfig::new().string_comparison("location", StringComparison::CaseInsensitive),
+        );
+        let plan = planner.plan(initial_state, &goal, &actions).unwrap();
+        let names: Vec<&str> = plan.actions.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["travel_to_forest", "gather_herbs"]);
+    }
+
+    /// Test numeric id comparison against computed values
+    /// Validates: Values produced by dynamic effects are compared with the rule too
+    /// Failure: Ids computed at plan time never match authored ids
+    #[test]
+    fn test_planning_with_numeric_ids_and_dynamic_effects() {
+        let actions = vec![
+            Action::new("open_door")
+                .effect_fn("door", |_| StateOperation::Set(StateVar::from("0042")))
+                .build(),
+        ];
+        let goal = Goal::new("door").requires("door", "42").build();
+        let initial_state = State::new().set("door", "0").build();
+        let planner = Planner::with_config(
+            PlannerConfig::new().string_comparison("door", StringComparison::NumericId),
+        );
+
+        let plan = planner.plan(initial_state, &goal, &actions).unwrap();
+        assert_eq!(plan.actions.len(), 1);
+    }
+}
@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    fn world() -> SymbolTable {
+        SymbolTable::new()
+            .define("location", &["town", "forest", "cave", "island"])
+            .connect("location", "town", "forest")
+            .connect("location", "forest", "cave")
+    }
+
+    // Tests for symbol registration
+
+    /// Test allowed values and graph distances
+    /// Validates: define/connect register values and BFS distances are computed
+    /// Failure: Values are rejected or distances are wrong
+    #[test]
+    fn test_symbol_table_values_and_distances() {
+        let symbols = world();
+        assert_eq!(symbols.values("location").unwrap().len(), 4);
+        assert!(symbols.is_allowed("location", "cave"));
+        assert!(!symbols.is_allowed("location", "castle"));
+        assert!(symbols.is_allowed("weather", "rain")); // unregistered variable
+
+        assert_eq!(symbols.distance("location", "town", "town"), Some(0));
+        assert_eq!(symbols.distance("location", "town", "cave"), Some(2));
+        assert_eq!(symbols.distance("location", "cave", "town"), Some(2));
+        assert_eq!(symbols.distance("location", "town", "island"), None);
+        assert_eq!(symbols.distance("weather", "rain", "sun"), None);
+    }
+
+    /// Test validation of states, goals and actions
+    /// Validates: Unregistered strings are reported with their key and value
+    /// Failure: Typos in string values slip through to planning
+    #[test]
+    fn test_symbol_validation() {
+        let symbols = world();
+        let state = State::new().set("location", "twon").set("gold", 5).build();
+        assert_eq!(
+            symbols.validate_state(&state),
+            Err(SymbolError {
+                key: "location".to_string(),
+                value: "twon".to_string(),
+            })
+        );
+
+        let goal = Goal::new("explore").requires("location", "cave").build();
+        assert!(symbols.validate_goal(&goal).is_ok());
+
+        let action = Action::new("sail").sets("location", "atlantis").build();
+        assert!(symbols.validate_action(&action).is_err());
+    }
+
+    /// Test the planner validates problems against its symbol table
+    /// Validates: Unknown symbols produce PlannerError::UnknownSymbol before searching
+    /// Failure: Planner searches with invalid string values
+    #[test]
+    fn test_planner_rejects_unknown_symbols() {
+        let planner = Planner::with_config(PlannerConfig::new().symbols(world()));
+        let initial_state = State::new().set("location", "town").build();
+        let goal = Goal::new("explore").requires("location", "castle").build();
+
+        let result = planner.plan(initial_state, &goal, &[]);
+        assert!(matches!(result, Err(PlannerError::UnknownSymbol(ref e)) if e.value == "castle"));
+    }
+
+    /// Test planning with graph distances between locations
+    /// Validates: Plans over registered locations are found and follow the graph
+    /// Failure: Symbol-aware heuristic breaks planning
+    #[test]
+    fn test_planner_uses_symbol_distances() {
+        let planner = Planner::with_config(PlannerConfig::new().symbols(world()));
+        let initial_state = State::new().set("location", "town").build();
+        let goal = Goal::new("explore").requires("location", "cave").build();
+        let actions = vec![
+            Action::new("town_to_forest")
+                .requires("location", "town")
+                .sets("location", "forest")
+                .build(),
+            Action::new("forest_to_cave")
+                .requires("location", "forest")
+                .sets("location", "cave")
+                .build(),
+            Action::new("forest_to_town")
+                .requires("location", "forest")
+                .sets("location", "town")
+                .build(),
+        ];
+
+        let plan = planner.plan(initial_state, &goal, &actions).unwrap();
+        let names: Vec<&str> = plan.actions.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["town_to_forest", "forest_to_cave"]);
+    }
+}
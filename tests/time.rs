@@ -0,0 +1,150 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for the built-in time variable
+
+    /// Test advancing time on a state
+    /// Validates: Missing time counts as tick 0 and time never runs backwards
+    /// Failure: Time starts elsewhere or can decrease
+    #[test]
+    fn test_advance_time() {
+        let mut state = State::empty();
+        assert_eq!(state.time(), 0);
+        state.advance_time(5);
+        state.advance_time(-3);
+        assert_eq!(state.time(), 5);
+        assert_eq!(state.get::<i64>(TIME_KEY), Some(5));
+    }
+
+    /// Test waiting for a point in time
+    /// Validates: The planner waits until a time precondition holds
+    /// Failure: Time preconditions are ignored or time never advances
+    #[test]
+    fn test_plan_waits_for_time() {
+        let actions = vec![
+            Action::new("buy_bread")
+                .requires_time(100)
+                .sets("has_bread", true)
+                .build(),
+            wait_action(40),
+        ];
+        let goal = Goal::new("fed").requires("has_bread", true).build();
+        let initial_state = State::new().set(TIME_KEY, 30).build();
+
+        let plan = Planner::new()
+            .plan(initial_state.clone(), &goal, &actions)
+            .unwrap();
+        let names: Vec<&str> = plan.actions.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["wait", "wait", "buy_bread"]);
+        assert_eq!(
+            plan.simulate_with_events(&initial_state, |_| {}).time(),
+            110
+        );
+    }
+
+    /// Test actions that take time
+    /// Validates: Durations advance time, also from states without the time variable
+    /// Failure: Durations are lost when the time variable is missing
+    #[test]
+    fn test_takes_time() {
+        let cook = Action::new("cook").takes_time(15).build();
+        assert_eq!(cook.apply_effect(&State::empty()).time(), 15);
+        let later = State::new().set(TIME_KEY, 50).build();
+        assert_eq!(cook.apply_effect(&later).time(), 65);
+    }
+
+    /// Test starting and waiting for timers
+    /// Validates: A timer holds its deadline and blocks actions until it runs out
+    /// Failure: Timers run out early or never
+    #[test]
+    fn test_timers() {
+        let actions = vec![
+            Action::new("plant")
+                .sets("planted", true)
+                .starts_timer("crop_ready_at", 30)
+                .build(),
+            Action::new("harvest")
+                .requires("planted", true)
+                .requires_timer("crop_ready_at")
+                .sets("has_crop", true)
+                .build(),
+            wait_action(10),
+        ];
+        let goal = Goal::new("harvested").requires("has_crop", true).build();
+        let initial_state = State::new().set("planted", false).build();
+
+        let plan = Planner::new()
+            .plan(initial_state.clone(), &goal, &actions)
+            .unwrap();
+        let names: Vec<&str> = plan.actions.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["plant", "wait", "wait", "wait", "harvest"]);
+
+        let planted = actions[0].apply_effect(&initial_state);
+        assert_eq!(planted.get::<i64>("crop_ready_at"), Some(30));
+        assert!(!actions[1].can_execute(&planted));
+        assert!(!actions[1].can_execute(&initial_state));
+    }
+
+    /// Test waiting for a passive process
+    /// Validates: The planner combines wait lengths to let a battery charge exactly enough
+    /// Failure: Processes do not progress while waiting or waits are not combined
+    #[test]
+    fn test_wait_for_process() {
+        let mut actions = vec![
+            Action::new("drive")
+                .requires_gt("battery", 79)
+                .sets("arrived", true)
+                .build(),
+        ];
+        actions.extend(
+            Wait::new()
+                .durations([1, 5])
+                .cost_per_tick(2.0)
+                .process_until("battery", 10, 100)
+                .actions(),
+        );
+        let goal = Goal::new("arrive").requires("arrived", true).build();
+        let initial_state = State::new().set("battery", 20).build();
+
+        let plan = Planner::new()
+            .plan(initial_state.clone(), &goal, &actions)
+            .unwrap();
+        let mut names: Vec<&str> = plan.actions.iter().map(|a| a.name.as_str()).collect();
+        names[..2].sort_unstable();
+        assert_eq!(names, ["wait_1", "wait_5", "drive"]);
+        assert_eq!(plan.cost, 13.0);
+
+        let charged = plan.simulate_with_events(&initial_state, |_| {});
+        assert_eq!(charged.get::<i64>("battery"), Some(80));
+        assert_eq!(charged.time(), 6);
+    }
+
+    /// Test the limits and types of processes
+    /// Validates: Processes stop at their limit, start missing variables at zero and skip other types
+    /// Failure: Processes overshoot their limit or change variables of another type
+    #[test]
+    fn test_process_limits() {
+        let wait = Wait::new()
+            .ticks(10)
+            .process_until("battery", 10, 100)
+            .process_until("food", -0.5, 1.0)
+            .process("crops", 2)
+            .process("weather", 1)
+            .build();
+        assert_eq!(wait.name, "wait");
+        assert_eq!(wait.cost, 10.0);
+
+        let state = State::new()
+            .set("battery", 95)
+            .set("food", 4.0)
+            .set("weather", "rain")
+            .build();
+        let after = wait.apply_effect(&state);
+        assert_eq!(after.get::<i64>("battery"), Some(100));
+        assert_eq!(after.get::<f64>("food"), Some(1.0));
+        assert_eq!(after.get::<i64>("crops"), Some(20));
+        assert_eq!(after.get::<String>("weather"), Some("rain".to_string()));
+        assert_eq!(after.time(), 10);
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for typed state wrappers
+
+    typed_state! {
+        #[derive(Clone, Debug, Default, PartialEq)]
+        struct Worker {
+            /// Gold carried
+            gold / set_gold: i64 => KeyInfo::new().unit("gp").category("inventory"),
+            energy / set_energy: f64,
+            has_job / set_has_job: bool,
+            location / set_location: String,
+        }
+    }
+
+    /// Test typed getters and setters
+    /// Validates: Setters write the variable named after the getter and getters read it back
+    /// Failure: Values are stored under the wrong key or converted incorrectly
+    #[test]
+    fn test_typed_accessors() {
+        let mut worker = Worker::default();
+        assert_eq!(worker.gold(), 0);
+        assert!(!worker.has_job());
+        assert_eq!(worker.location(), "");
+
+        worker.set_gold(120);
+        worker.set_energy(0.5);
+        worker.set_has_job(true);
+        worker.set_location("mine".to_string());
+        assert_eq!(worker.gold(), 120);
+        assert_eq!(worker.energy(), 0.5);
+        assert!(worker.has_job());
+        assert_eq!(worker.location(), "mine");
+
+        let state: State = worker.clone().into();
+        assert_eq!(state.get::<i64>("gold"), Some(120));
+        assert_eq!(state.get::<String>("location"), Some("mine".to_string()));
+        assert_eq!(Worker::from(state), worker);
+
+        // A variable of another type reads as the default
+        let mismatched = Worker::from_state(State::new().set("gold", "lots").build());
+        assert_eq!(mismatched.gold(), 0);
+    }
+
+    /// Test the schema of a typed wrapper
+    /// Validates: schema() registers every field with its metadata
+    /// Failure: Fields are missing from the schema or lose their metadata
+    #[test]
+    fn test_typed_schema() {
+        let schema = Worker::schema();
+        let gold = schema.info("gold").unwrap();
+        assert_eq!(gold.unit.as_deref(), Some("gp"));
+        assert_eq!(gold.category.as_deref(), Some("inventory"));
+        assert_eq!(schema.info("has_job"), Some(&KeyInfo::new()));
+        assert!(schema.info("location").is_some());
+        assert!(schema.info("missing").is_none());
+    }
+}
@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+
+    // Tests for planning the undo of a plan
+
+    /// Test undoing with derived inverses
+    /// Validates: Derived inverses reverse numeric changes relatively and restore
+    /// set values, in reverse order, returning the world to the initial state
+    /// Failure: Undo plans leave the world changed or undo steps out of order
+    #[test]
+    fn test_derived_undo() {
+        let actions = vec![
+            Action::new("open_crate")
+                .sets("crate_open", true)
+                .cost(2.0)
+                .build(),
+            Action::new("take_grenades")
+                .requires("crate_open", true)
+                .adds("grenades", 3)
+                .sets("mood", "ready")
+                .build(),
+        ];
+        let start = State::new()
+            .set("crate_open", false)
+            .set("grenades", 1)
+            .set("mood", "calm")
+            .build();
+        let goal = Goal::new("stocked").requires("grenades", 4).build();
+        let planner = Planner::new();
+        let plan = planner.plan(start.clone(), &goal, &actions).unwrap();
+
+        let undo = planner.plan_undo(&start, &plan, &actions).unwrap();
+        let names: Vec<&str> = undo.actions.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["undo_take_grenades", "undo_open_crate"]);
+        assert_eq!(undo.cost, 3.0);
+        assert_eq!(
+            undo.actions[0].effects.get("grenades"),
+            Some(&StateOperation::Subtract(3))
+        );
+
+        let end = plan
+            .actions
+            .iter()
+            .chain(&undo.actions)
+            .fold(start.clone(), |state, action| action.apply_effect(&state));
+        assert_eq!(end, start);
+    }
+
+    /// Test undoing with declared inverses
+    /// Validates: A declared inverse is used when its preconditions hold and a
+    /// derived inverse replaces it when they do not
+    /// Failure: Declared inverses are ignored or used where they cannot execute
+    #[test]
+    fn test_declared_inverse() {
+        let actions = vec![
+            Action::new("equip")
+                .sets("equipped", true)
+                .inverse("unequip")
+                .build(),
+            Action::new("unequip")
+                .requires("equipped", true)
+                .requires("in_armory", true)
+                .sets("equipped", false)
+                .build(),
+        ];
+        let start = State::new()
+            .set("equipped", false)
+            .set("in_armory", true)
+            .build();
+        let goal = Goal::new("armed").requires("equipped", true).build();
+        let planner = Planner::new();
+        let plan = planner.plan(start.clone(), &goal, &actions).unwrap();
+
+        let undo = planner.plan_undo(&start, &plan, &actions).unwrap();
+        assert_eq!(undo.actions[0].name, "unequip");
+
+        let field = State::new()
+            .set("equipped", false)
+            .set("in_armory", false)
+            .build();
+        let undo = planner.plan_undo(&field, &plan, &actions).unwrap();
+        assert_eq!(undo.actions[0].name, "undo_equip");
+        assert!(actions[0].to_string().contains("Undone by: unequip"));
+    }
+
+    /// Test a declared inverse missing from the actions
+    /// Validates: plan_undo reports the unknown inverse by name
+    /// Failure: Misspelled inverses are silently replaced by derived ones
+    #[test]
+    fn test_unknown_inverse() {
+        let actions = vec![
+            Action::new("light_torch")
+                .sets("lit", true)
+                .inverse("douse")
+                .build(),
+        ];
+        let start = State::new().set("lit", false).build();
+        let goal = Goal::new("light").requires("lit", true).build();
+        let planner = Planner::new();
+        let plan = planner.plan(start.clone(), &goal, &actions).unwrap();
+
+        let error = planner.plan_undo(&start, &plan, &actions).unwrap_err();
+        assert_eq!(error, PlannerError::UnknownInverse("douse".to_string()));
+        assert_eq!(error.to_string(), "Unknown inverse action 'douse'");
+    }
+}
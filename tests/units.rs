@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod tests {
+    use goap::prelude::*;
+    use std::time::Duration;
+
+    // Tests for units of numeric variables
+
+    const COOLDOWN: QuantityKey<Duration> = QuantityKey::scaled("cooldown", 1000.0);
+    const MORALE: QuantityKey<Percent> = QuantityKey::new("morale");
+
+    /// Test storing quantities through their keys
+    /// Validates: Scaled keys store integers, fixed-point keys store floats, and both round-trip
+    /// Failure: Builders convert the same quantity differently or lose precision
+    #[test]
+    fn test_quantity_storage() {
+        let state = State::new()
+            .set_quantity(COOLDOWN, Duration::from_millis(2500))
+            .set_quantity(MORALE, Percent(40.0))
+            .build();
+        assert_eq!(state.get::<i64>("cooldown"), Some(2500));
+        assert_eq!(state.get::<f64>("morale"), Some(40.0));
+        assert_eq!(
+            state.get_quantity(COOLDOWN),
+            Some(Duration::from_millis(2500))
+        );
+
+        let rest = Action::new("rest")
+            .subtracts_quantity(COOLDOWN, Duration::from_secs(1))
+            .adds_quantity(MORALE, Percent(12.5))
+            .build();
+        let after = rest.apply_effect(&state);
+        assert_eq!(
+            after.get_quantity(COOLDOWN),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(after.get_quantity(MORALE), Some(Percent(52.5)));
+
+        let goal = Goal::new("inspired")
+            .requires_quantity(MORALE, Percent(75.0))
+            .build();
+        let plan = Planner::new().plan(&state, &goal, &[rest]).unwrap();
+        assert_eq!(plan.actions.len(), 3);
+
+        let mut unscaled = State::empty();
+        unscaled.set("cooldown", 2.5);
+        assert_eq!(unscaled.get_quantity(COOLDOWN), None);
+    }
+
+    /// Test finding unit mistakes with a schema
+    /// Validates: Mixed units in expressions and values of the wrong representation are reported
+    /// Failure: Seconds can be compared with gold or floats stored in integer quantity variables
+    #[test]
+    fn test_check_units() {
+        let schema = StateSchema::new()
+            .quantity_key(COOLDOWN, KeyInfo::new().maximum(10_000))
+            .quantity_key(MORALE, KeyInfo::new())
+            .key("gold", KeyInfo::new().unit("gp"));
+        assert_eq!(
+            schema.format_value("cooldown", &StateVar::I64(1500)),
+            "1.5/10 s"
+        );
+        assert_eq!(
+            schema.format_value("morale", &StateVar::from_f64(50.0)),
+            "50 %"
+        );
+
+        let actions = vec![
+            Action::new("bribe")
+                .requires_expr("gold >= cooldown + 5")
+                .unwrap()
+                .requires_expr("gold * 2 >= morale")
+                .unwrap()
+                .build(),
+            Action::new("wait").sets("cooldown", 1.5).build(),
+        ];
+        let goals = vec![Goal::new("calm").requires("morale", 80).build()];
+
+        let errors: Vec<String> = schema
+            .check_units(&actions, &goals)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                "Action 'bribe': 'gold >= cooldown + 5' mixes units gp and s",
+                "Action 'wait': cooldown is stored as I64, found F64",
+                "Goal 'calm': morale is stored as F64, found I64",
+            ]
+        );
+    }
+}